@@ -10,6 +10,8 @@ fn main() {
         let mut config = AppleSwiftConfig::new("waterkit-audio", "MediaHelper")
             .swift_source("src/sys/apple/MediaHelper.swift")
             .swift_source("src/sys/apple/AudioPlayerHelper.swift")
+            .swift_source("src/sys/apple/AudioInputHelper.swift")
+            .swift_source("src/sys/apple/AudioRouteHelper.swift")
             .framework("Foundation")
             .framework("MediaPlayer")
             .framework("AVFoundation");
@@ -0,0 +1,120 @@
+//! Conversion of captured audio to the format requested from the recorder.
+//!
+//! Devices only offer a few native configurations, so the recorder captures
+//! in the closest one and remixes and resamples on the capture thread.
+
+/// Convert a sample in `-1.0..=1.0` to 16-bit PCM.
+#[allow(clippy::cast_possible_truncation)] // clamped to the i16 range
+pub(crate) fn f32_to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16
+}
+
+/// Convert a 16-bit PCM sample to `-1.0..=1.0`.
+pub(crate) fn i16_to_f32(sample: i16) -> f32 {
+    f32::from(sample) / f32::from(i16::MAX)
+}
+
+/// Converts interleaved audio between channel counts and sample rates.
+///
+/// Resampling interpolates linearly between frames, carrying the last frame
+/// and the fractional position over to the next callback so buffer
+/// boundaries don't click.
+#[derive(Debug)]
+pub(crate) struct Converter {
+    in_channels: usize,
+    out_channels: usize,
+    /// Input frames advanced per output frame.
+    step: f64,
+    /// Position of the next output frame, in frames from the start of
+    /// `previous` (or of the next input if there is none yet).
+    position: f64,
+    /// Last remixed frame of the previous input.
+    previous: Vec<f32>,
+    /// Remixed frames of the current input.
+    remixed: Vec<f32>,
+}
+
+impl Converter {
+    pub(crate) fn new(in_rate: u32, in_channels: u16, out_rate: u32, out_channels: u16) -> Self {
+        Self {
+            in_channels: usize::from(in_channels.max(1)),
+            out_channels: usize::from(out_channels.max(1)),
+            step: f64::from(in_rate) / f64::from(out_rate.max(1)),
+            position: 0.0,
+            previous: Vec::new(),
+            remixed: Vec::new(),
+        }
+    }
+
+    /// Whether input already matches the output format.
+    #[allow(clippy::float_cmp)] // exact for equal rates
+    pub(crate) fn is_passthrough(&self) -> bool {
+        self.in_channels == self.out_channels && self.step == 1.0
+    }
+
+    /// Convert `input`, appending the result to `output`.
+    pub(crate) fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        self.remixed.clear();
+        self.remixed.extend_from_slice(&self.previous);
+        for frame in input.chunks_exact(self.in_channels) {
+            self.remix(frame);
+        }
+
+        let channels = self.out_channels;
+        let frames = self.remixed.len() / channels;
+        if frames == 0 {
+            return;
+        }
+        #[allow(clippy::float_cmp)]
+        if self.step == 1.0 {
+            // Same rate: only the channels changed
+            let start = self.previous.len();
+            output.extend_from_slice(&self.remixed[start..]);
+        } else {
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                clippy::cast_precision_loss
+            )]
+            while (self.position as usize) + 1 < frames {
+                let index = self.position as usize;
+                let t = (self.position - index as f64) as f32;
+                let current = &self.remixed[index * channels..(index + 1) * channels];
+                let next = &self.remixed[(index + 1) * channels..(index + 2) * channels];
+                output.extend(
+                    current
+                        .iter()
+                        .zip(next)
+                        .map(|(&a, &b)| (b - a).mul_add(t, a)),
+                );
+                self.position += self.step;
+            }
+            #[allow(clippy::cast_precision_loss)]
+            {
+                self.position -= (frames - 1) as f64;
+            }
+        }
+
+        self.previous.clear();
+        self.previous
+            .extend_from_slice(&self.remixed[(frames - 1) * channels..frames * channels]);
+    }
+
+    /// Remix one input frame to the output channel count.
+    ///
+    /// Mono output averages all channels and mono input is copied to every
+    /// channel; otherwise channels are mapped in order, dropping extras and
+    /// repeating when there are too few.
+    fn remix(&mut self, frame: &[f32]) {
+        if self.out_channels == self.in_channels {
+            self.remixed.extend_from_slice(frame);
+        } else if self.out_channels == 1 {
+            #[allow(clippy::cast_precision_loss)] // channel counts are tiny
+            self.remixed
+                .push(frame.iter().sum::<f32>() / frame.len() as f32);
+        } else {
+            self.remixed
+                .extend((0..self.out_channels).map(|channel| frame[channel % self.in_channels]));
+        }
+    }
+}
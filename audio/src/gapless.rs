@@ -0,0 +1,223 @@
+//! Encoder delay and padding handling for gapless playback.
+//!
+//! Lossy encoders prepend silent priming samples and pad the final frame,
+//! which is audible as a gap between consecutive tracks of an album.
+//! Rodio's Symphonia MP3 demuxer already trims using the LAME/Xing header;
+//! other formats (AAC in MP4) only carry the iTunes `iTunSMPB` tag, which is
+//! applied here.
+
+use lofty::file::{FileType, TaggedFile, TaggedFileExt};
+use lofty::tag::ItemKey;
+use rodio::source::SeekError;
+use rodio::{Sample, Source};
+use std::time::Duration;
+
+/// Priming and padding frames (samples per channel) to drop from a track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct EncoderDelay {
+    delay: u64,
+    /// Frames of real audio after the delay, if known.
+    valid_frames: Option<u64>,
+}
+
+impl EncoderDelay {
+    /// Read the encoder delay from the tags of `file`, if it must be trimmed here.
+    pub(crate) fn from_tagged_file(file: &TaggedFile) -> Option<Self> {
+        // Trimmed by the decoder from the LAME header
+        if file.file_type() == FileType::Mpeg {
+            return None;
+        }
+
+        file.tags()
+            .iter()
+            .flat_map(lofty::tag::Tag::items)
+            .filter(|item| is_itunsmpb(item.key()))
+            .find_map(|item| item.value().text().and_then(Self::parse_itunsmpb))
+    }
+
+    /// Parse an `iTunSMPB` value such as
+    /// `" 00000000 00000840 000001C0 0000000000A1B2C3 ..."`:
+    /// the second, third and fourth fields are the delay, padding and
+    /// original frame count in hex.
+    fn parse_itunsmpb(value: &str) -> Option<Self> {
+        let fields: Vec<u64> = value
+            .split_whitespace()
+            .map(|field| u64::from_str_radix(field, 16).ok())
+            .collect::<Option<_>>()?;
+        if fields.len() < 4 || fields[0] != 0 {
+            return None;
+        }
+
+        let delay = fields[1];
+        let valid_frames = (fields[3] > 0).then_some(fields[3]);
+        (delay > 0 || valid_frames.is_some()).then_some(Self {
+            delay,
+            valid_frames,
+        })
+    }
+
+    /// Duration of the real audio at `sample_rate`, if known.
+    pub(crate) fn duration(self, sample_rate: u32) -> Option<Duration> {
+        self.valid_frames
+            .map(|frames| frames_to_duration(frames, sample_rate))
+    }
+}
+
+/// Whether `key` names an `iTunSMPB` tag, such as the MP4 freeform atom
+/// `----:com.apple.iTunes:iTunSMPB`.
+fn is_itunsmpb(key: &ItemKey) -> bool {
+    matches!(key, ItemKey::Unknown(key) if key.rsplit(':').next() == Some("iTunSMPB"))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn frames_to_duration(frames: u64, sample_rate: u32) -> Duration {
+    Duration::from_secs_f64(frames as f64 / f64::from(sample_rate.max(1)))
+}
+
+/// A source with the encoder delay and padding removed.
+pub(crate) struct Trimmed<S> {
+    inner: S,
+    delay: EncoderDelay,
+    /// Interleaved samples left before the padding starts.
+    remaining: Option<u64>,
+}
+
+impl<S> Trimmed<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    pub(crate) fn new(mut inner: S, delay: EncoderDelay) -> Self {
+        let channels = u64::from(inner.channels());
+        for _ in 0..delay.delay * channels {
+            if inner.next().is_none() {
+                break;
+            }
+        }
+        Self {
+            inner,
+            delay,
+            remaining: delay.valid_frames.map(|frames| frames * channels),
+        }
+    }
+}
+
+impl<S> Iterator for Trimmed<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(remaining) = self.remaining.as_mut() {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+        }
+        self.inner.next()
+    }
+}
+
+impl<S> Source for Trimmed<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        let inner = self.inner.current_frame_len();
+        match (inner, self.remaining) {
+            (Some(len), Some(remaining)) => {
+                Some(len.min(usize::try_from(remaining).unwrap_or(usize::MAX)))
+            }
+            (None, Some(remaining)) => usize::try_from(remaining).ok(),
+            (len, None) => len,
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.delay
+            .duration(self.inner.sample_rate())
+            .or_else(|| self.inner.total_duration())
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let sample_rate = self.inner.sample_rate();
+        let delay = frames_to_duration(self.delay.delay, sample_rate);
+        self.inner.try_seek(pos + delay)?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let skipped = (pos.as_secs_f64() * f64::from(sample_rate)) as u64;
+        let channels = u64::from(self.inner.channels());
+        self.remaining = self
+            .delay
+            .valid_frames
+            .map(|frames| frames.saturating_sub(skipped) * channels);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    /// The tag of an AAC track encoded by iTunes.
+    const ITUNSMPB: &str = " 00000000 00000840 000001CA 00000000003F31F6 00000000 00000000 \
+                            00000000 00000000 00000000 00000000 00000000 00000000";
+
+    #[test]
+    fn parses_itunsmpb() {
+        assert_eq!(
+            EncoderDelay::parse_itunsmpb(ITUNSMPB),
+            Some(EncoderDelay {
+                delay: 2112,
+                valid_frames: Some(4_141_558),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_itunsmpb() {
+        assert_eq!(EncoderDelay::parse_itunsmpb(""), None);
+        assert_eq!(EncoderDelay::parse_itunsmpb(" 00000000 00000840"), None);
+        assert_eq!(EncoderDelay::parse_itunsmpb(" 00000000 0000084G 0 0"), None);
+        // Nothing to trim
+        assert_eq!(EncoderDelay::parse_itunsmpb(" 00000000 0 0 0"), None);
+    }
+
+    #[test]
+    fn matches_itunsmpb_keys_only() {
+        assert!(is_itunsmpb(&ItemKey::Unknown(
+            "----:com.apple.iTunes:iTunSMPB".into()
+        )));
+        assert!(is_itunsmpb(&ItemKey::Unknown("iTunSMPB".into())));
+        assert!(!is_itunsmpb(&ItemKey::Unknown(
+            "----:com.apple.iTunes:iTunNORM".into()
+        )));
+        assert!(!is_itunsmpb(&ItemKey::Comment));
+    }
+
+    #[test]
+    fn trims_delay_and_padding() {
+        // Stereo frames 0..8, of which 2 are priming and 3 are real audio
+        let samples: Vec<f32> = (0..16u8).map(f32::from).collect();
+        let source = SamplesBuffer::new(2, 44100, samples);
+        let delay = EncoderDelay {
+            delay: 2,
+            valid_frames: Some(3),
+        };
+
+        let trimmed: Vec<f32> = Trimmed::new(source, delay).collect();
+        assert_eq!(trimmed, [4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+    }
+}
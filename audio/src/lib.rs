@@ -8,14 +8,28 @@
 
 #![warn(missing_docs)]
 
+mod convert;
+mod gapless;
+mod output;
 mod player;
 mod recorder;
+mod route;
 mod shutdown;
 mod sys;
+mod vad;
+mod wav;
 
-pub use player::{AudioDevice, AudioPlayer, PlayerError, rodio};
+pub use output::DeviceEvent;
+pub use player::{AudioDevice, AudioHint, AudioPlayer, AudioPlayerBuilder, PlayerError, rodio};
+pub use recorder::{
+    AudioBuffer, AudioFormat, AudioRecorder, AudioRecorderBuilder, RecordError, RecorderEvent,
+    SampleFormat,
+};
+pub use route::{RouteEvent, watch_routes};
 pub use shutdown::{ShutdownHandle, ShutdownReceiver};
-pub use recorder::{AudioBuffer, AudioFormat, AudioRecorder, AudioRecorderBuilder, RecordError};
+#[cfg(target_os = "android")]
+pub use sys::android::start_route_monitor_with_context;
+pub use wav::WavSampleFormat;
 
 use std::time::Duration;
 
@@ -30,6 +44,8 @@ pub struct MediaMetadata {
     pub album: Option<String>,
     /// URL to artwork image.
     pub artwork_url: Option<String>,
+    /// Encoded artwork image (PNG or JPEG). Takes precedence over `artwork_url`.
+    pub artwork_bytes: Option<Vec<u8>>,
     /// Total duration of the media.
     pub duration: Option<Duration>,
 }
@@ -70,6 +86,16 @@ impl MediaMetadata {
         self
     }
 
+    /// Set the artwork from encoded image bytes (PNG or JPEG).
+    ///
+    /// Used instead of the artwork URL when both are set. Large images are
+    /// downscaled before being handed to the system media controls.
+    #[must_use]
+    pub fn artwork_bytes(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.artwork_bytes = Some(bytes.into());
+        self
+    }
+
     /// Set the duration.
     #[must_use]
     pub const fn duration(mut self, duration: Duration) -> Self {
@@ -155,6 +181,57 @@ pub enum MediaCommand {
     SeekForward(Duration),
     /// Seek backward by an amount.
     SeekBackward(Duration),
+    /// Set the volume, from 0.0 (muted) to 1.0 (full).
+    SetVolume(f64),
+    /// Set the playback rate, within the range [`AudioPlayer::set_rate`] accepts.
+    SetRate(f64),
+    /// Another app or the system took over audio, such as for a phone call
+    /// or a voice assistant.
+    ///
+    /// Delivered for `AVAudioSession` interruptions on iOS and transient
+    /// audio focus loss on Android.
+    InterruptionBegan,
+    /// An interruption ended.
+    InterruptionEnded {
+        /// Whether the system suggests resuming playback.
+        should_resume: bool,
+    },
+    /// Audio focus changed (Android only).
+    ///
+    /// Reported alongside the interruption commands, so apps can duck their
+    /// own volume on [`AudioFocusChange::LossTransientCanDuck`].
+    AudioFocusChanged(AudioFocusChange),
+}
+
+/// Kind of audio focus to request with [`MediaSession::request_audio_focus_with`].
+///
+/// Only Android distinguishes these; other platforms treat all of them as
+/// [`AudioFocusType::Gain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AudioFocusType {
+    /// Long-running playback such as music; other apps stop.
+    #[default]
+    Gain,
+    /// Short playback; other apps pause until focus is abandoned.
+    Transient,
+    /// Short sounds like navigation prompts; other apps keep playing quieter.
+    TransientMayDuck,
+    /// Short playback that must not be mixed with anything, such as dictation.
+    TransientExclusive,
+}
+
+/// Change in audio focus reported by [`MediaCommand::AudioFocusChanged`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioFocusChange {
+    /// Focus was regained.
+    Gain,
+    /// Another app took focus for good; playback should stop.
+    Loss,
+    /// Another app took focus briefly; playback should pause.
+    LossTransient,
+    /// Another app took focus briefly but allows lowering the volume instead
+    /// of pausing.
+    LossTransientCanDuck,
 }
 
 /// Errors that can occur with media control.
@@ -229,6 +306,26 @@ impl MediaSession {
         self.inner.request_audio_focus()
     }
 
+    /// Request a specific kind of audio focus.
+    ///
+    /// Use [`AudioFocusType::TransientMayDuck`] for short sounds that should
+    /// play over other apps instead of stopping them. Platforms other than
+    /// Android treat every type like [`MediaSession::request_audio_focus`].
+    ///
+    /// # Errors
+    /// Returns [`MediaError::AudioFocusDenied`] if focus is refused.
+    pub fn request_audio_focus_with(&self, focus_type: AudioFocusType) -> Result<(), MediaError> {
+        #[cfg(target_os = "android")]
+        {
+            self.inner.request_audio_focus_with(focus_type)
+        }
+        #[cfg(not(target_os = "android"))]
+        {
+            let _ = focus_type;
+            self.inner.request_audio_focus()
+        }
+    }
+
     /// Abandon audio focus.
     ///
     /// Call this when stopping playback to allow other apps to play audio.
@@ -0,0 +1,169 @@
+//! Moving playback between output devices.
+//!
+//! Rodio ties a sink to the mixer of one output stream. To switch devices
+//! without reloading the queue, sinks are created idle and their output is
+//! played through a [`Relay`] on the current stream. Moving to a new stream
+//! retires the old relay and starts a new one on the same queue, so playback
+//! continues from the same sample.
+
+use crate::{AudioDevice, PlayerError};
+use rodio::queue::SourcesQueueOutput;
+use rodio::{OutputStream, OutputStreamHandle, Source};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// A change of the output device an [`AudioPlayer`](crate::AudioPlayer)
+/// plays to, reported by [`AudioPlayer::device_events`](crate::AudioPlayer::device_events).
+#[derive(Debug, Clone)]
+pub enum DeviceEvent {
+    /// Playback moved to the new default output device.
+    Switched {
+        /// Name of the new device.
+        device: String,
+    },
+    /// The default output device changed, but playback could not move to it
+    /// and stays on the previous device.
+    SwitchFailed {
+        /// Name of the new default device.
+        device: String,
+        /// Why the device could not be opened.
+        error: PlayerError,
+    },
+    /// The output device chosen with
+    /// [`set_output_device`](crate::AudioPlayer::set_output_device) went
+    /// away, and playback moved to the default output device.
+    Disconnected {
+        /// Name of the device that went away.
+        device: String,
+    },
+}
+
+/// Open an output stream on `device`, or on the default device for `None`.
+///
+/// On iOS the stream always plays to the session's route, so the device is
+/// selected by overriding the route instead.
+pub(crate) fn open_stream(
+    device: Option<&AudioDevice>,
+) -> Result<(OutputStream, OutputStreamHandle), PlayerError> {
+    #[cfg(target_os = "ios")]
+    crate::sys::select_output(device.map(AudioDevice::id))?;
+
+    #[cfg(not(target_os = "ios"))]
+    if let Some(device) = device {
+        return OutputStream::try_from_device(&find_device(device.id())?)
+            .map_err(|e| PlayerError::OutputInitFailed(e.to_string()));
+    }
+
+    OutputStream::try_default().map_err(|e| PlayerError::OutputInitFailed(e.to_string()))
+}
+
+/// Find the cpal output device with the given id.
+#[cfg(not(target_os = "ios"))]
+fn find_device(id: &str) -> Result<rodio::cpal::Device, PlayerError> {
+    use rodio::cpal::traits::{DeviceTrait, HostTrait};
+
+    rodio::cpal::default_host()
+        .output_devices()
+        .map_err(|e| PlayerError::Unknown(format!("failed to list devices: {e}")))?
+        .find(|d| d.name().is_ok_and(|name| name == id))
+        .ok_or_else(|| PlayerError::DeviceNotFound(id.to_string()))
+}
+
+/// Name of the default output device, or an empty string if there is none.
+pub(crate) fn default_device_name() -> String {
+    use rodio::cpal::traits::{DeviceTrait, HostTrait};
+
+    rodio::cpal::default_host()
+        .default_output_device()
+        .and_then(|device| device.name().ok())
+        .unwrap_or_default()
+}
+
+/// Whether `device` can still be played to.
+///
+/// Failed enumerations count as available, so a glitch doesn't move playback.
+pub(crate) fn is_available(device: &AudioDevice) -> bool {
+    #[cfg(target_os = "ios")]
+    {
+        crate::sys::is_output_routed(device.id())
+    }
+
+    #[cfg(not(target_os = "ios"))]
+    {
+        !matches!(
+            find_device(device.id()),
+            Err(PlayerError::DeviceNotFound(_))
+        )
+    }
+}
+
+/// The output of an idle sink, played on whichever device is current.
+pub(crate) struct SinkOutput {
+    queue: Mutex<SourcesQueueOutput<f32>>,
+    // Bumped on every move, retiring the relay on the previous device
+    generation: AtomicU64,
+}
+
+impl SinkOutput {
+    pub(crate) const fn new(queue: SourcesQueueOutput<f32>) -> Self {
+        Self {
+            queue: Mutex::new(queue),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    fn queue(&self) -> MutexGuard<'_, SourcesQueueOutput<f32>> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Retire the current relay and return one to play on the next device.
+    pub(crate) fn relay(self: &Arc<Self>) -> Relay {
+        Relay {
+            output: Arc::clone(self),
+            generation: self.generation.fetch_add(1, Ordering::AcqRel) + 1,
+        }
+    }
+}
+
+/// Plays a [`SinkOutput`] on one device until it moves to another.
+pub(crate) struct Relay {
+    output: Arc<SinkOutput>,
+    generation: u64,
+}
+
+impl Relay {
+    fn retired(&self) -> bool {
+        self.output.generation.load(Ordering::Acquire) != self.generation
+    }
+}
+
+impl Iterator for Relay {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        // Ending removes the relay from the old device's mixer
+        if self.retired() {
+            return None;
+        }
+        self.output.queue().next()
+    }
+}
+
+impl Source for Relay {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.output.queue().current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.output.queue().channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.output.queue().sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
@@ -3,30 +3,69 @@
 //! Uses `rodio` for audio playback on all platforms, with platform-specific
 //! media center integrations (`MPNowPlayingInfoCenter`, SMTC, MPRIS, `MediaSession`).
 
+use crate::gapless::{EncoderDelay, Trimmed};
+use crate::output::{self, DeviceEvent, SinkOutput};
+use crate::route::{self, RouteEvent};
 use crate::shutdown::ShutdownHandle;
-use crate::{MediaCommand, MediaError, MediaMetadata, PlaybackState};
+use crate::sys::MediaCenterIntegration;
+use crate::{MediaCommand, MediaError, MediaMetadata, PlaybackState, PlaybackStatus};
 use futures::Stream;
+use lofty::picture::PictureType;
 use lofty::prelude::*;
+use rodio::source::EmptyCallback;
 use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
 use std::cell::Cell;
 use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
-use std::sync::Arc;
+use std::io::{BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // Re-export rodio for advanced users
 pub use rodio;
 
-/// Audio output device.
-#[derive(Debug, Clone)]
+/// How often the background worker checks that the chosen output device is
+/// still present.
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Slowest playback rate accepted by [`AudioPlayer::set_rate`].
+pub(crate) const MIN_RATE: f32 = 0.5;
+/// Fastest playback rate accepted by [`AudioPlayer::set_rate`].
+pub(crate) const MAX_RATE: f32 = 3.0;
+
+/// Clamp a rate requested by system controls to the supported range.
+#[cfg(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "linux"
+))]
+pub(crate) fn clamp_rate(rate: f64) -> f64 {
+    rate.clamp(f64::from(MIN_RATE), f64::from(MAX_RATE))
+}
+
+/// Audio input or output device.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AudioDevice {
+    id: String,
     name: String,
-    // Device handle is not Clone, so we store the name and recreate when needed
+    // Device handle is not Clone, so we store the identifiers and recreate when needed
 }
 
 impl AudioDevice {
+    pub(crate) const fn new(id: String, name: String) -> Self {
+        Self { id, name }
+    }
+
+    /// Get the platform identifier used to select this device.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
     /// Get the device name.
     #[must_use]
     pub fn name(&self) -> &str {
@@ -58,6 +97,9 @@ pub enum PlayerError {
     /// No audio device available.
     #[error("no audio device available")]
     NoDevice,
+    /// The requested output device was not found.
+    #[error("device not found: {0}")]
+    DeviceNotFound(String),
     /// An unknown error occurred.
     #[error("unknown error: {0}")]
     Unknown(String),
@@ -69,8 +111,729 @@ impl From<MediaError> for PlayerError {
     }
 }
 
+/// Container format of in-memory audio.
+///
+/// Passed to [`AudioPlayer::open_bytes`] to skip format detection, which can
+/// fail for formats without a reliable header such as raw MP3 streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioHint {
+    /// WAV (RIFF) audio.
+    Wav,
+    /// MP3 audio.
+    Mp3,
+    /// FLAC audio.
+    Flac,
+    /// Ogg Vorbis audio.
+    Vorbis,
+}
+
+impl AudioHint {
+    const fn file_type(self) -> lofty::file::FileType {
+        match self {
+            Self::Wav => lofty::file::FileType::Wav,
+            Self::Mp3 => lofty::file::FileType::Mpeg,
+            Self::Flac => lofty::file::FileType::Flac,
+            Self::Vorbis => lofty::file::FileType::Vorbis,
+        }
+    }
+}
+
+/// Where a track's audio data comes from.
+#[derive(Debug, Clone)]
+enum TrackSource {
+    File(PathBuf),
+    Memory(Arc<[u8]>, Option<AudioHint>),
+}
+
+impl TrackSource {
+    /// Create a fresh decoder positioned at the start of the track.
+    fn decode(&self) -> Result<Box<dyn Source<Item = i16> + Send>, PlayerError> {
+        match self {
+            Self::File(path) => {
+                let file = File::open(path)
+                    .map_err(|e| PlayerError::LoadFailed(format!("{}: {e}", path.display())))?;
+                let decoder = Decoder::new(BufReader::new(file))
+                    .map_err(|e| PlayerError::UnsupportedFormat(e.to_string()))?;
+                Ok(Box::new(decoder))
+            }
+            Self::Memory(bytes, hint) => {
+                let data = Cursor::new(Arc::clone(bytes));
+                let decoder = match hint {
+                    None => Decoder::new(data),
+                    Some(AudioHint::Wav) => Decoder::new_wav(data),
+                    Some(AudioHint::Mp3) => Decoder::new_mp3(data),
+                    Some(AudioHint::Flac) => Decoder::new_flac(data),
+                    Some(AudioHint::Vorbis) => Decoder::new_vorbis(data),
+                }
+                .map_err(|e| PlayerError::UnsupportedFormat(e.to_string()))?;
+                Ok(Box::new(decoder))
+            }
+        }
+    }
+}
+
+/// A queue entry.
+#[derive(Debug, Clone)]
+struct Track {
+    source: TrackSource,
+    metadata: MediaMetadata,
+    delay: Option<EncoderDelay>,
+}
+
+impl Track {
+    /// Create a fresh source for the track, without encoder delay and padding.
+    fn decode(&self) -> Result<Box<dyn Source<Item = i16> + Send>, PlayerError> {
+        let source = self.source.decode()?;
+        Ok(match self.delay {
+            Some(delay) => Box::new(Trimmed::new(source, delay)),
+            None => source,
+        })
+    }
+
+    /// Load a local file, extracting metadata (title, artist, album) with `lofty`.
+    fn from_path(path: &Path) -> Result<Self, PlayerError> {
+        let tagged_file = lofty::read_from_path(path).ok();
+        let mut track = Self {
+            source: TrackSource::File(path.to_path_buf()),
+            metadata: MediaMetadata::default(),
+            delay: tagged_file
+                .as_ref()
+                .and_then(EncoderDelay::from_tagged_file),
+        };
+
+        // Get duration from decoder
+        track.metadata.duration = track.decode()?.total_duration();
+        let metadata = &mut track.metadata;
+
+        // Try extracting tags with lofty
+        if let Some(tagged_file) = &tagged_file {
+            metadata.read_tags(tagged_file);
+        }
+
+        // Fallback to filename if title is missing
+        if metadata.title.is_none() {
+            metadata.title = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+        }
+
+        Ok(track)
+    }
+
+    /// Load a track held in memory, extracting metadata with `lofty`.
+    fn from_bytes(bytes: Arc<[u8]>, hint: Option<AudioHint>) -> Result<Self, PlayerError> {
+        let probe = lofty::probe::Probe::new(Cursor::new(&bytes[..]));
+        let probe = match hint {
+            Some(hint) => Some(probe.set_file_type(hint.file_type())),
+            None => probe.guess_file_type().ok(),
+        };
+        let tagged_file = probe.and_then(|probe| probe.read().ok());
+        let mut track = Self {
+            source: TrackSource::Memory(bytes, hint),
+            metadata: MediaMetadata::default(),
+            delay: tagged_file
+                .as_ref()
+                .and_then(EncoderDelay::from_tagged_file),
+        };
+
+        // Get duration if available
+        track.metadata.duration = track.decode()?.total_duration();
+        if let Some(tagged_file) = &tagged_file {
+            track.metadata.read_tags(tagged_file);
+        }
+
+        Ok(track)
+    }
+
+    /// Read a track from `reader` into memory.
+    fn from_reader(mut reader: impl Read) -> Result<Self, PlayerError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| PlayerError::LoadFailed(e.to_string()))?;
+        Self::from_bytes(bytes.into(), None)
+    }
+
+    /// Fetch a track from a URL into memory.
+    #[allow(clippy::future_not_send)]
+    async fn from_url(url: &str) -> Result<Self, PlayerError> {
+        // Fetch audio data
+        let response = zenwave::get(url)
+            .await
+            .map_err(|e| PlayerError::LoadFailed(format!("HTTP request failed: {e}")))?;
+
+        let bytes =
+            response.into_body().into_bytes().await.map_err(|e| {
+                PlayerError::LoadFailed(format!("Failed to read response body: {e}"))
+            })?;
+
+        let mut track = Self::from_bytes(Arc::from(&bytes[..]), None)?;
+
+        // Use URL as fallback title
+        if track.metadata.title.is_none() {
+            track.metadata.title = Some(
+                url.rsplit('/')
+                    .next()
+                    .unwrap_or("Stream")
+                    .split('?')
+                    .next()
+                    .unwrap_or("Stream")
+                    .to_string(),
+            );
+        }
+
+        Ok(track)
+    }
+}
+
+impl MediaMetadata {
+    /// Fill in title, artist, album and cover art from the file's primary tag.
+    fn read_tags(&mut self, tagged_file: &lofty::file::TaggedFile) {
+        let Some(tag) = tagged_file.primary_tag() else {
+            return;
+        };
+        self.title = tag.title().map(String::from);
+        self.artist = tag.artist().map(String::from);
+        self.album = tag.album().map(String::from);
+        self.artwork_bytes = tag
+            .get_picture_type(PictureType::CoverFront)
+            .or_else(|| tag.pictures().first())
+            .map(|picture| picture.data().to_vec());
+    }
+}
+
+/// Tracks of an [`AudioPlayer`], in playback order.
+#[derive(Debug, Default)]
+struct Queue {
+    tracks: Vec<Track>,
+    current: usize,
+    // Bumped whenever the sink is reloaded or stopped, so end-of-track
+    // callbacks from a previous load don't advance the queue.
+    generation: u64,
+    // Index of the track already appended to the sink after the current one.
+    preloaded: Option<usize>,
+}
+
+/// A consumer of [`AudioPlayer::progress_stream`].
+struct ProgressSubscriber {
+    sender: async_channel::Sender<PlaybackState>,
+    interval: Duration,
+    // When the last state was sent, and its status
+    last: (Instant, PlaybackStatus),
+}
+
+/// An outgoing track fading out under the current one.
+#[derive(Debug, Clone, Copy)]
+struct Fade {
+    /// Index in [`PlayerShared::sinks`] of the outgoing track.
+    outgoing: usize,
+    /// Position of the current track at which the fade completes.
+    length: Duration,
+}
+
+/// Player state shared with the background and end-of-track threads.
+struct PlayerShared {
+    // Two sinks, so the next track can fade in over the current one
+    sinks: [Sink; 2],
+    // Outputs of the sinks, played to the current device
+    outputs: [Arc<SinkOutput>; 2],
+    // Index of the sink playing the current track
+    active: AtomicUsize,
+    // Volume set by the app, as f32 bits; crossfades ramp towards it
+    volume: AtomicU32,
+    media_center: Arc<MediaCenterIntegration>,
+    queue: Mutex<Queue>,
+    gapless: AtomicBool,
+    // Zero when crossfading is off
+    crossfade: Mutex<Duration>,
+    fade: Mutex<Option<Fade>>,
+    progress: Mutex<Vec<ProgressSubscriber>>,
+    // Route changes, while pausing on route change is enabled
+    routes: Mutex<Option<async_channel::Receiver<RouteEvent>>>,
+    // Route changes, while following the default output device
+    default_device: Mutex<Option<async_channel::Receiver<RouteEvent>>>,
+    // Device chosen by the app, or `None` for the default device
+    output_device: Mutex<Option<AudioDevice>>,
+    device_events: Mutex<Vec<async_channel::Sender<DeviceEvent>>>,
+    // Set while playback is paused by an interruption, so it can resume after
+    interrupted: AtomicBool,
+    resume_after_interruption: AtomicBool,
+}
+
+impl PlayerShared {
+    /// The sink playing the current track.
+    fn sink(&self) -> &Sink {
+        &self.sinks[self.active.load(Ordering::Relaxed)]
+    }
+
+    fn volume(&self) -> f32 {
+        f32::from_bits(self.volume.load(Ordering::Relaxed))
+    }
+
+    fn queue(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn fade(&self) -> MutexGuard<'_, Option<Fade>> {
+        self.fade.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn crossfade(&self) -> Duration {
+        *self
+            .crossfade
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn progress(&self) -> MutexGuard<'_, Vec<ProgressSubscriber>> {
+        self.progress.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn current_metadata(&self) -> MediaMetadata {
+        let queue = self.queue();
+        queue
+            .tracks
+            .get(queue.current)
+            .map(|track| track.metadata.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replace the sink contents with the track at `index`, keeping the
+    /// play/pause state.
+    fn load(self: &Arc<Self>, index: usize) -> Result<(), PlayerError> {
+        self.finish_fade();
+        let mut queue = self.queue();
+        let track = queue
+            .tracks
+            .get(index)
+            .ok_or_else(|| PlayerError::PlaybackFailed(format!("no track at index {index}")))?;
+        let source = track.decode()?;
+        queue.current = index;
+        queue.generation += 1;
+        queue.preloaded = None;
+        let generation = queue.generation;
+        drop(queue);
+
+        let paused = self.sink().is_paused();
+        self.sink().clear();
+        self.append(self.sink(), source, generation, index);
+
+        if !paused {
+            self.sink().play();
+        }
+        self.preload_next();
+        self.update_now_playing();
+        Ok(())
+    }
+
+    /// Append a decoded track to `sink`, followed by its end-of-track callback.
+    fn append(
+        self: &Arc<Self>,
+        sink: &Sink,
+        source: Box<dyn Source<Item = i16> + Send>,
+        generation: u64,
+        index: usize,
+    ) {
+        sink.append(source);
+
+        let shared = Arc::downgrade(self);
+        sink.append(EmptyCallback::<f32>::new(Box::new(move || {
+            // Leave the audio thread before decoding the next track
+            let shared = Weak::clone(&shared);
+            std::thread::spawn(move || {
+                if let Some(shared) = shared.upgrade() {
+                    shared.track_finished(generation, index);
+                }
+            });
+        })));
+    }
+
+    /// With gapless playback, decode the track after the current one and
+    /// queue it in the sink so it starts without a gap.
+    fn preload_next(self: &Arc<Self>) {
+        if !self.gapless.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let mut queue = self.queue();
+        if queue.preloaded.is_some() {
+            return;
+        }
+        // Skip tracks that can no longer be loaded
+        let generation = queue.generation;
+        let Some((index, source)) = (queue.current + 1..queue.tracks.len())
+            .find_map(|index| queue.tracks[index].decode().ok().map(|s| (index, s)))
+        else {
+            return;
+        };
+        queue.preloaded = Some(index);
+        drop(queue);
+
+        self.append(self.sink(), source, generation, index);
+    }
+
+    /// Start fading in the next track when the current one is about to end,
+    /// and ramp the volumes of a fade in progress. Called by the background
+    /// worker.
+    fn tick_crossfade(self: &Arc<Self>) {
+        if self.ramp_fade() {
+            return;
+        }
+        let crossfade = self.crossfade();
+        if crossfade.is_zero() || self.sink().is_paused() || self.sink().empty() {
+            return;
+        }
+        let duration = {
+            let queue = self.queue();
+            queue
+                .tracks
+                .get(queue.current)
+                .and_then(|track| track.metadata.duration)
+        };
+        let Some(duration) = duration else {
+            return;
+        };
+
+        // Tracks shorter than two fades only fade over their second half
+        let length = crossfade.min(duration / 2);
+        let remaining = duration.saturating_sub(self.sink().get_pos());
+        if !remaining.is_zero() && remaining <= length {
+            self.start_fade(remaining);
+        }
+    }
+
+    /// Start the next track on the idle sink and make it current, fading in
+    /// over `length` while the outgoing track plays out.
+    fn start_fade(self: &Arc<Self>, length: Duration) {
+        let mut queue = self.queue();
+        // Skip tracks that can no longer be loaded
+        let Some((index, source)) = (queue.current + 1..queue.tracks.len())
+            .find_map(|index| queue.tracks[index].decode().ok().map(|s| (index, s)))
+        else {
+            return;
+        };
+        // The outgoing track's end-of-track callback must not advance the queue
+        queue.current = index;
+        queue.generation += 1;
+        queue.preloaded = None;
+        let generation = queue.generation;
+        drop(queue);
+
+        let outgoing = self.active.load(Ordering::Relaxed);
+        let incoming = &self.sinks[1 - outgoing];
+        incoming.clear();
+        incoming.set_volume(0.0);
+        self.append(incoming, source, generation, index);
+        incoming.play();
+
+        *self.fade() = Some(Fade { outgoing, length });
+        self.active.store(1 - outgoing, Ordering::Relaxed);
+        self.update_now_playing();
+    }
+
+    /// Set the volumes of both tracks in a crossfade from the current
+    /// track's position, finishing the fade once it is complete.
+    ///
+    /// Returns whether a fade is still in progress.
+    fn ramp_fade(&self) -> bool {
+        let Some(fade) = *self.fade() else {
+            return false;
+        };
+        let outgoing = &self.sinks[fade.outgoing];
+        let progress = if fade.length.is_zero() {
+            1.0
+        } else {
+            self.sink().get_pos().as_secs_f32() / fade.length.as_secs_f32()
+        };
+        if progress >= 1.0 || outgoing.empty() {
+            self.finish_fade();
+            return false;
+        }
+
+        // Linear ramps keep the combined level roughly constant
+        let volume = self.volume();
+        outgoing.set_volume(volume * (1.0 - progress));
+        self.sink().set_volume(volume * progress);
+        true
+    }
+
+    /// Complete a crossfade in progress right away: the outgoing track is
+    /// stopped and the current one plays at full volume.
+    fn finish_fade(&self) {
+        let Some(fade) = self.fade().take() else {
+            return;
+        };
+        self.sinks[fade.outgoing].clear();
+        self.sink().set_volume(self.volume());
+    }
+
+    /// Resume playback, including the outgoing track of a crossfade.
+    fn play(&self) {
+        self.sink().play();
+        if let Some(fade) = *self.fade() {
+            self.sinks[fade.outgoing].play();
+        }
+    }
+
+    /// Pause playback, including the outgoing track of a crossfade.
+    fn pause(&self) {
+        self.sink().pause();
+        if let Some(fade) = *self.fade() {
+            self.sinks[fade.outgoing].pause();
+        }
+    }
+
+    /// Reload the current track at its position, dropping a preloaded track
+    /// that is no longer next.
+    fn reload_current(self: &Arc<Self>) {
+        let (current, preloaded) = {
+            let queue = self.queue();
+            (queue.current, queue.preloaded)
+        };
+        if preloaded.is_none() || self.sink().empty() {
+            return;
+        }
+
+        let position = self.sink().get_pos();
+        if self.load(current).is_ok() {
+            let _ = self.sink().try_seek(position);
+        }
+    }
+
+    /// Advance to the next track once the track at `index` has ended.
+    fn track_finished(self: &Arc<Self>, generation: u64, index: usize) {
+        let (next, len) = {
+            let mut queue = self.queue();
+            if queue.generation != generation || queue.current != index {
+                return;
+            }
+
+            // The preloaded track is already playing; only move the queue along
+            if let Some(preloaded) = queue.preloaded.take() {
+                queue.current = preloaded;
+                drop(queue);
+                self.preload_next();
+                self.update_now_playing();
+                return;
+            }
+            (queue.current + 1, queue.tracks.len())
+        };
+
+        // Skip tracks that can no longer be loaded
+        if !(next..len).any(|index| self.load(index).is_ok()) {
+            self.update_now_playing();
+        }
+    }
+
+    fn next(self: &Arc<Self>) -> Result<bool, PlayerError> {
+        // Mid-fade, the next track is already current; skip the rest of the fade
+        if self.fade().is_some() {
+            self.finish_fade();
+            self.update_now_playing();
+            return Ok(true);
+        }
+        let next = self.queue().current + 1;
+        if next >= self.queue().tracks.len() {
+            return Ok(false);
+        }
+        self.load(next)?;
+        Ok(true)
+    }
+
+    fn previous(self: &Arc<Self>) -> Result<bool, PlayerError> {
+        let current = self.queue().current;
+        if current == 0 {
+            // Restart the first track instead
+            let _ = self.sink().try_seek(Duration::ZERO);
+            self.update_now_playing();
+            return Ok(false);
+        }
+        self.load(current - 1)?;
+        Ok(true)
+    }
+
+    fn playback_state(&self) -> PlaybackState {
+        if !self.sink().is_paused() && !self.sink().empty() {
+            PlaybackState {
+                rate: f64::from(self.sink().speed()),
+                ..PlaybackState::playing(self.sink().get_pos())
+            }
+        } else if self.sink().empty() {
+            PlaybackState::stopped()
+        } else {
+            PlaybackState::paused(self.sink().get_pos())
+        }
+    }
+
+    fn update_now_playing(&self) {
+        let state = self.playback_state();
+        self.media_center.update(&self.current_metadata(), &state);
+        self.send_progress(&state, true);
+    }
+
+    /// Send periodic progress while playing, and catch transitions that
+    /// didn't go through [`update_now_playing`](Self::update_now_playing),
+    /// such as playback running out. Called by the background worker.
+    fn tick_progress(&self) {
+        if self.progress().is_empty() {
+            return;
+        }
+        self.send_progress(&self.playback_state(), false);
+    }
+
+    /// Pause when the output device goes away, as the platform players do.
+    /// Called by the background worker.
+    fn poll_routes(&self) {
+        let unavailable = {
+            let routes = self.routes.lock().unwrap_or_else(PoisonError::into_inner);
+            let Some(routes) = routes.as_ref() else {
+                return;
+            };
+            let mut unavailable = false;
+            while let Ok(event) = routes.try_recv() {
+                unavailable |= matches!(event, RouteEvent::OldDeviceUnavailable { .. });
+            }
+            unavailable
+        };
+        if unavailable && !self.sink().is_paused() {
+            self.pause();
+            self.update_now_playing();
+        }
+    }
+
+    /// Play the sinks on the stream of `handle`, moving them off the
+    /// previous stream.
+    fn play_to(&self, handle: &OutputStreamHandle) -> Result<(), PlayerError> {
+        for output in &self.outputs {
+            handle
+                .play_raw(output.relay())
+                .map_err(|e| PlayerError::OutputInitFailed(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn output_device(&self) -> MutexGuard<'_, Option<AudioDevice>> {
+        self.output_device
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The new default output device, if it changed while following it.
+    /// Changes are ignored while the app has chosen a device.
+    /// Called by the background worker.
+    fn poll_default_device(&self) -> Option<String> {
+        let default_device = self
+            .default_device
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut changed = None;
+        while let Ok(event) = default_device.as_ref()?.try_recv() {
+            if let RouteEvent::DefaultDeviceChanged { device } = event {
+                changed = Some(device);
+            }
+        }
+        changed.filter(|_| self.output_device().is_none())
+    }
+
+    /// Move playback to `device`, or to the default device for `None`.
+    ///
+    /// Returns the new stream, which must be kept alive in place of the old
+    /// one. Called by the background worker, which owns the streams.
+    fn move_to(&self, device: Option<AudioDevice>) -> Result<OutputStream, PlayerError> {
+        let (stream, handle) = output::open_stream(device.as_ref())?;
+        self.play_to(&handle)?;
+        *self.output_device() = device;
+        Ok(stream)
+    }
+
+    /// Move playback to the default output device, which is now `device`.
+    /// Called by the background worker.
+    fn switch_to_default(&self, device: String) -> Option<OutputStream> {
+        let (stream, event) = match self.move_to(None) {
+            Ok(stream) => (Some(stream), DeviceEvent::Switched { device }),
+            Err(error) => (None, DeviceEvent::SwitchFailed { device, error }),
+        };
+        self.send_device_event(&event);
+        stream
+    }
+
+    /// Fall back to the default output device if the chosen one went away.
+    /// Called by the background worker.
+    fn check_output_device(&self) -> Option<OutputStream> {
+        let chosen = self.output_device().clone();
+        let lost = chosen.filter(|device| !output::is_available(device))?;
+        self.send_device_event(&DeviceEvent::Disconnected {
+            device: lost.name().to_string(),
+        });
+        match self.move_to(None) {
+            Ok(stream) => Some(stream),
+            Err(error) => {
+                // Stop watching the lost device; playback is silent until
+                // the app picks another one
+                *self.output_device() = None;
+                self.send_device_event(&DeviceEvent::SwitchFailed {
+                    device: output::default_device_name(),
+                    error,
+                });
+                None
+            }
+        }
+    }
+
+    fn send_device_event(&self, event: &DeviceEvent) {
+        self.device_events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|sender| sender.try_send(event.clone()).is_ok());
+    }
+
+    /// Pause for an interruption, and resume after it if the system allows.
+    /// Called by the background worker.
+    fn handle_interruption(&self, cmd: &MediaCommand) {
+        match cmd {
+            MediaCommand::InterruptionBegan => {
+                if !self.sink().is_paused() && !self.sink().empty() {
+                    self.pause();
+                    self.interrupted.store(true, Ordering::Relaxed);
+                    self.update_now_playing();
+                }
+            }
+            MediaCommand::InterruptionEnded { should_resume } => {
+                if self.interrupted.swap(false, Ordering::Relaxed)
+                    && *should_resume
+                    && self.resume_after_interruption.load(Ordering::Relaxed)
+                {
+                    self.play();
+                    self.update_now_playing();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Send `state` to progress subscribers that are due, dropping closed ones.
+    ///
+    /// A `transition` is sent to every subscriber right away.
+    fn send_progress(&self, state: &PlaybackState, transition: bool) {
+        let now = Instant::now();
+        self.progress().retain_mut(|subscriber| {
+            let (sent_at, status) = subscriber.last;
+            let due = transition
+                || status != state.status
+                || (state.status == PlaybackStatus::Playing
+                    && now.duration_since(sent_at) >= subscriber.interval);
+            if !due {
+                return !subscriber.sender.is_closed();
+            }
+            subscriber.last = (now, state.status);
+            subscriber.sender.try_send(state.clone()).is_ok()
+        });
+    }
+}
+
 /// Cross-platform audio player with media center integration.
 ///
+/// The player keeps a queue of tracks. It advances automatically when a
+/// track ends, and the "Now Playing" information follows the current track.
+///
 /// # Example
 ///
 /// ```no_run
@@ -78,6 +841,7 @@ impl From<MediaError> for PlayerError {
 ///
 /// // Metadata is automatically extracted from the file
 /// let mut player = AudioPlayer::open("song.mp3").unwrap();
+/// player.enqueue("next-song.mp3").unwrap();
 /// player.play();
 ///
 /// // Override metadata if needed
@@ -90,11 +854,7 @@ pub struct AudioPlayer {
     // (it lives in the background thread)
     #[allow(dead_code)]
     stream_handle: OutputStreamHandle,
-    sink: Arc<Sink>,
-
-    // State
-    metadata: MediaMetadata,
-    media_center: Arc<crate::sys::MediaCenterIntegration>,
+    shared: Arc<PlayerShared>,
 
     // Deferred metadata updates: builder methods set this flag,
     // first action (play/pause/seek) flushes to media center
@@ -104,12 +864,102 @@ pub struct AudioPlayer {
     shutdown_handle: ShutdownHandle,
     background_thread: Option<JoinHandle<()>>,
     command_receiver: async_channel::Receiver<MediaCommand>,
+    // Set once the app takes the command stream; until then the background
+    // worker handles Next/Previous with the queue itself.
+    commands_taken: Arc<AtomicBool>,
+    // Output device changes, carried out by the background worker
+    device_requests: std::sync::mpsc::Sender<DeviceRequest>,
+}
+
+/// A request to move playback to a device (or the default for `None`), and
+/// where to send the result.
+type DeviceRequest = (
+    Option<AudioDevice>,
+    std::sync::mpsc::Sender<Result<(), PlayerError>>,
+);
+
+/// Builder for an [`AudioPlayer`] with options that apply before playback
+/// starts.
+///
+/// # Example
+///
+/// ```no_run
+/// use waterkit_audio::AudioPlayer;
+///
+/// let devices = AudioPlayer::list_devices().unwrap();
+/// let player = AudioPlayer::builder()
+///     .device(&devices[0])
+///     .open("song.mp3")
+///     .unwrap();
+/// player.play();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AudioPlayerBuilder {
+    device: Option<AudioDevice>,
+}
+
+impl AudioPlayerBuilder {
+    /// Create a new player builder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Play to a specific output device (optional, uses default if not set).
+    ///
+    /// The device must come from [`AudioPlayer::list_devices`]. If it goes
+    /// away during playback, the player moves to the default device as
+    /// described in [`AudioPlayer::set_output_device`].
+    #[must_use]
+    pub fn device(mut self, device: &AudioDevice) -> Self {
+        self.device = Some(device.clone());
+        self
+    }
+
+    /// Open audio from a file path, as [`AudioPlayer::open`] does.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or the audio output fails.
+    pub fn open(self, path: impl AsRef<Path>) -> Result<AudioPlayer, PlayerError> {
+        AudioPlayer::start(Track::from_path(path.as_ref())?, self.device)
+    }
+
+    /// Open audio held in memory, as [`AudioPlayer::open_bytes`] does.
+    ///
+    /// # Errors
+    /// Returns an error if the audio format is unsupported or the audio output fails.
+    pub fn open_bytes(
+        self,
+        data: Vec<u8>,
+        hint: Option<AudioHint>,
+    ) -> Result<AudioPlayer, PlayerError> {
+        AudioPlayer::start(Track::from_bytes(data.into(), hint)?, self.device)
+    }
+
+    /// Open audio from a reader, as [`AudioPlayer::open_reader`] does.
+    ///
+    /// # Errors
+    /// Returns an error if reading fails, the audio format is unsupported or
+    /// the audio output fails.
+    pub fn open_reader(self, reader: impl Read) -> Result<AudioPlayer, PlayerError> {
+        AudioPlayer::start(Track::from_reader(reader)?, self.device)
+    }
+
+    /// Open audio from a URL, as [`AudioPlayer::open_url`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL cannot be fetched or the audio format is unsupported.
+    #[allow(clippy::future_not_send)]
+    pub async fn open_url(self, url: &str) -> Result<AudioPlayer, PlayerError> {
+        AudioPlayer::start(Track::from_url(url).await?, self.device)
+    }
 }
 
 impl std::fmt::Debug for AudioPlayer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AudioPlayer")
-            .field("metadata", &self.metadata)
+            .field("metadata", &self.shared.current_metadata())
             .finish_non_exhaustive()
     }
 }
@@ -126,52 +976,149 @@ impl AudioPlayer {
     /// # Errors
     /// Returns an error if the file cannot be opened or the audio output fails.
     pub fn open(path: impl AsRef<Path>) -> Result<Self, PlayerError> {
-        let path = path.as_ref();
+        AudioPlayerBuilder::new().open(path)
+    }
+
+    /// Open audio held in memory, such as an asset embedded in the binary.
+    ///
+    /// Metadata is extracted the same way as in [`open`](Self::open). Pass a
+    /// `hint` to skip format detection.
+    ///
+    /// # Errors
+    /// Returns an error if the audio format is unsupported or the audio output fails.
+    pub fn open_bytes(data: Vec<u8>, hint: Option<AudioHint>) -> Result<Self, PlayerError> {
+        AudioPlayerBuilder::new().open_bytes(data, hint)
+    }
+
+    /// Open audio from a reader.
+    ///
+    /// The reader is read to the end up front so the track can be seeked and
+    /// replayed; metadata is extracted the same way as in [`open`](Self::open).
+    ///
+    /// # Errors
+    /// Returns an error if reading fails, the audio format is unsupported or
+    /// the audio output fails.
+    pub fn open_reader(reader: impl Read) -> Result<Self, PlayerError> {
+        AudioPlayerBuilder::new().open_reader(reader)
+    }
+
+    /// Open audio from a URL (async).
+    ///
+    /// Fetches audio data from the URL and creates a player.
+    /// Note: Metadata extraction from URL streams is limited compared to local files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL cannot be fetched or the audio format is unsupported.
+    #[allow(clippy::future_not_send)]
+    pub async fn open_url(url: &str) -> Result<Self, PlayerError> {
+        AudioPlayerBuilder::new().open_url(url).await
+    }
+
+    /// Create a player builder, to choose options such as the output device
+    /// before opening audio.
+    #[must_use]
+    pub fn builder() -> AudioPlayerBuilder {
+        AudioPlayerBuilder::new()
+    }
 
-        // 1. Initialize audio output in background thread (to keep OutputStream !Send contained)
+    /// Start the audio output on `device` and the background worker, then
+    /// load `track`.
+    fn start(track: Track, device: Option<AudioDevice>) -> Result<Self, PlayerError> {
+        // Initialize audio output in background thread (to keep OutputStream !Send contained)
         let (handle_tx, handle_rx) = std::sync::mpsc::channel();
+        let (shared_tx, shared_rx) = std::sync::mpsc::channel::<Weak<PlayerShared>>();
         let (shutdown_handle, shutdown_rx) = ShutdownHandle::new();
+        let commands_taken = Arc::new(AtomicBool::new(false));
 
         let media_center = Arc::new(
-            crate::sys::MediaCenterIntegration::new()
+            MediaCenterIntegration::new()
                 .map_err(|e| PlayerError::Unknown(format!("media center init failed: {e}")))?,
         );
 
         let (cmd_tx, cmd_rx) = async_channel::unbounded();
+        let (device_tx, device_rx) = std::sync::mpsc::channel::<DeviceRequest>();
 
         let background_thread = {
             let mc = Arc::clone(&media_center);
-            let tx = cmd_tx;
+            let commands_taken = Arc::clone(&commands_taken);
+            let device = device.clone();
 
             std::thread::spawn(move || {
                 // Create stream on this thread
-                let (_stream, stream_handle) = match OutputStream::try_default() {
+                let (mut stream, stream_handle) = match output::open_stream(device.as_ref()) {
                     Ok(s) => s,
                     Err(e) => {
-                        let _ = handle_tx.send(Err(PlayerError::OutputInitFailed(e.to_string())));
+                        let _ = handle_tx.send(Err(e));
                         return;
                     }
                 };
+                let mut device_checked = Instant::now();
 
                 // Send handle back
                 if handle_tx.send(Ok(stream_handle)).is_err() {
                     return;
                 }
+                let Ok(shared) = shared_rx.recv() else {
+                    return;
+                };
 
                 // Run loop until shutdown is signaled
-                if let Ok(local_mc) = crate::sys::MediaCenterIntegration::new() {
+                if let Ok(local_mc) = MediaCenterIntegration::new() {
                     while !shutdown_rx.is_shutdown() {
                         // Run platform loop step
                         local_mc.run_loop(Duration::from_millis(50));
 
+                        if let Some(shared) = shared.upgrade() {
+                            shared.tick_crossfade();
+                            shared.tick_progress();
+                            shared.poll_routes();
+                            if let Some(switched) = shared
+                                .poll_default_device()
+                                .and_then(|device| shared.switch_to_default(device))
+                            {
+                                stream = switched;
+                            }
+                            while let Ok((device, reply)) = device_rx.try_recv() {
+                                let moved = shared.move_to(device).map(|moved| stream = moved);
+                                let _ = reply.send(moved);
+                            }
+                            if device_checked.elapsed() >= DEVICE_CHECK_INTERVAL {
+                                device_checked = Instant::now();
+                                if let Some(fallback) = shared.check_output_device() {
+                                    stream = fallback;
+                                }
+                            }
+                        }
+
                         // Check for commands
-                        if let Some(cmd) = mc.poll_command().or_else(|| local_mc.poll_command()) {
-                            let _ = tx.send_blocking(cmd);
+                        let Some(cmd) = mc.poll_command().or_else(|| local_mc.poll_command())
+                        else {
+                            continue;
+                        };
+                        if let Some(shared) = shared.upgrade() {
+                            shared.handle_interruption(&cmd);
+                        }
+                        match cmd {
+                            MediaCommand::Next | MediaCommand::Previous
+                                if !commands_taken.load(Ordering::Relaxed) =>
+                            {
+                                if let Some(shared) = shared.upgrade() {
+                                    let _ = if cmd == MediaCommand::Next {
+                                        shared.next()
+                                    } else {
+                                        shared.previous()
+                                    };
+                                }
+                            }
+                            cmd => {
+                                let _ = cmd_tx.send_blocking(cmd);
+                            }
                         }
                     }
                 }
 
-                // _stream dropped here
+                drop(stream);
             })
         };
 
@@ -180,162 +1127,51 @@ impl AudioPlayer {
             .recv()
             .map_err(|_| PlayerError::OutputInitFailed("audio thread failed to start".into()))??;
 
-        let sink = Sink::try_new(&stream_handle)
-            .map_err(|e| PlayerError::OutputInitFailed(e.to_string()))?;
-
-        // 2. Load audio file
-        let file = File::open(path)
-            .map_err(|e| PlayerError::LoadFailed(format!("{}: {e}", path.display())))?;
-        let reader = BufReader::new(file);
-
-        let source =
-            Decoder::new(reader).map_err(|e| PlayerError::UnsupportedFormat(e.to_string()))?;
-
-        // 3. Extract metadata
-        let mut metadata = MediaMetadata::default();
-
-        // Get duration from decoder
-        if let Some(d) = source.total_duration() {
-            metadata.duration = Some(d);
-        }
-
-        // Try extracting tags with lofty
-        if let Ok(tagged_file) = lofty::read_from_path(path)
-            && let Some(tag) = tagged_file.primary_tag()
-        {
-            metadata.title = tag.title().map(String::from);
-            metadata.artist = tag.artist().map(String::from);
-            metadata.album = tag.album().map(String::from);
-        }
-
-        // Fallback to filename if title is missing
-        if metadata.title.is_none() {
-            metadata.title = path.file_stem().map(|s| s.to_string_lossy().into_owned());
-        }
-
-        // 4. Setup playback
-        sink.append(source);
-        sink.pause(); // Start paused
-
-        // Initial update
-        media_center.update(&metadata, &PlaybackState::paused(Duration::ZERO));
-
-        Ok(Self {
-            stream_handle,
-            sink: Arc::new(sink),
-            metadata,
-            media_center,
-            metadata_dirty: Cell::new(false),
-            shutdown_handle,
-            background_thread: Some(background_thread),
-            command_receiver: cmd_rx,
-        })
-    }
-
-    /// Open audio from a URL (async).
-    ///
-    /// Fetches audio data from the URL and creates a player.
-    /// Note: Metadata extraction from URL streams is limited compared to local files.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the URL cannot be fetched or the audio format is unsupported.
-    #[allow(clippy::future_not_send)]
-    pub async fn open_url(url: &str) -> Result<Self, PlayerError> {
-        // Fetch audio data
-        let response = zenwave::get(url)
-            .await
-            .map_err(|e| PlayerError::LoadFailed(format!("HTTP request failed: {e}")))?;
-
-        let bytes =
-            response.into_body().into_bytes().await.map_err(|e| {
-                PlayerError::LoadFailed(format!("Failed to read response body: {e}"))
-            })?;
-
-        // Create a cursor for in-memory decoding
-        let cursor = std::io::Cursor::new(bytes);
-
-        // Initialize audio output and media center in background thread
-        let (stream_handle_tx, stream_handle_rx) = std::sync::mpsc::channel();
-        let (shutdown_handle, shutdown_rx) = ShutdownHandle::new();
-
-        let media_center = Arc::new(
-            crate::sys::MediaCenterIntegration::new()
-                .map_err(|e| PlayerError::Unknown(format!("media center init failed: {e}")))?,
-        );
-
-        let (cmd_tx, cmd_rx) = async_channel::unbounded();
-
-        let background_thread = {
-            let mc = Arc::clone(&media_center);
-
-            std::thread::spawn(move || {
-                let (_stream, stream_handle) = match OutputStream::try_default() {
-                    Ok(pair) => pair,
-                    Err(e) => {
-                        let _ = stream_handle_tx.send(Err(e.to_string()));
-                        return;
-                    }
-                };
-                let _ = stream_handle_tx.send(Ok(stream_handle));
-
-                // Run loop until shutdown is signaled (fixes thread leak)
-                if let Ok(local_mc) = crate::sys::MediaCenterIntegration::new() {
-                    while !shutdown_rx.is_shutdown() {
-                        local_mc.run_loop(Duration::from_millis(50));
-                        if let Some(cmd) = mc.poll_command().or_else(|| local_mc.poll_command()) {
-                            let _ = cmd_tx.send_blocking(cmd);
-                        }
-                    }
-                }
-                // _stream dropped here, thread exits cleanly
-            })
+        // Idle sinks, so their output can move to another device
+        let new_sink = || {
+            let (sink, queue) = Sink::new_idle();
+            sink.pause(); // Start paused
+            (sink, Arc::new(SinkOutput::new(queue)))
         };
-
-        let stream_handle = stream_handle_rx
-            .recv()
-            .map_err(|_| PlayerError::OutputInitFailed("Background thread died".into()))?
-            .map_err(PlayerError::OutputInitFailed)?;
-
-        let sink = Sink::try_new(&stream_handle)
-            .map_err(|e| PlayerError::OutputInitFailed(e.to_string()))?;
-
-        // Decode audio
-        let source =
-            Decoder::new(cursor).map_err(|e| PlayerError::UnsupportedFormat(e.to_string()))?;
-
-        // Get duration if available
-        let mut metadata = MediaMetadata::default();
-        if let Some(d) = source.total_duration() {
-            metadata.duration = Some(d);
-        }
-
-        // Use URL as fallback title
-        metadata.title = Some(
-            url.rsplit('/')
-                .next()
-                .unwrap_or("Stream")
-                .split('?')
-                .next()
-                .unwrap_or("Stream")
-                .to_string(),
-        );
-
-        // Setup playback
-        sink.append(source);
-        sink.pause(); // Start paused
-
-        media_center.update(&metadata, &PlaybackState::paused(Duration::ZERO));
+        let (first, first_output) = new_sink();
+        let (second, second_output) = new_sink();
+
+        let shared = Arc::new(PlayerShared {
+            sinks: [first, second],
+            outputs: [first_output, second_output],
+            active: AtomicUsize::new(0),
+            volume: AtomicU32::new(1.0f32.to_bits()),
+            media_center,
+            queue: Mutex::new(Queue {
+                tracks: vec![track],
+                ..Queue::default()
+            }),
+            gapless: AtomicBool::new(true),
+            crossfade: Mutex::new(Duration::ZERO),
+            fade: Mutex::new(None),
+            progress: Mutex::new(Vec::new()),
+            routes: Mutex::new(None),
+            default_device: Mutex::new(default_device_watch(true)),
+            output_device: Mutex::new(device),
+            device_events: Mutex::new(Vec::new()),
+            interrupted: AtomicBool::new(false),
+            resume_after_interruption: AtomicBool::new(true),
+        });
+        shared.play_to(&stream_handle)?;
+        let _ = shared_tx.send(Arc::downgrade(&shared));
+
+        // Setup playback and initial update
+        shared.load(0)?;
 
         Ok(Self {
             stream_handle,
-            sink: Arc::new(sink),
-            metadata,
-            media_center,
+            shared,
             metadata_dirty: Cell::new(false),
             shutdown_handle,
             background_thread: Some(background_thread),
             command_receiver: cmd_rx,
+            commands_taken,
+            device_requests: device_tx,
         })
     }
 
@@ -344,36 +1180,51 @@ impl AudioPlayer {
 
     /// Set the title.
     #[must_use]
-    pub fn title(mut self, title: impl Into<String>) -> Self {
-        self.metadata.title = Some(title.into());
-        self.metadata_dirty.set(true);
+    pub fn title(self, title: impl Into<String>) -> Self {
+        self.edit_metadata(|m| m.title = Some(title.into()));
         self
     }
 
     /// Set the artist.
     #[must_use]
-    pub fn artist(mut self, artist: impl Into<String>) -> Self {
-        self.metadata.artist = Some(artist.into());
-        self.metadata_dirty.set(true);
+    pub fn artist(self, artist: impl Into<String>) -> Self {
+        self.edit_metadata(|m| m.artist = Some(artist.into()));
         self
     }
 
     /// Set the album.
     #[must_use]
-    pub fn album(mut self, album: impl Into<String>) -> Self {
-        self.metadata.album = Some(album.into());
-        self.metadata_dirty.set(true);
+    pub fn album(self, album: impl Into<String>) -> Self {
+        self.edit_metadata(|m| m.album = Some(album.into()));
         self
     }
 
     /// Set the artwork URL.
     #[must_use]
-    pub fn artwork_url(mut self, url: impl Into<String>) -> Self {
-        self.metadata.artwork_url = Some(url.into());
-        self.metadata_dirty.set(true);
+    pub fn artwork_url(self, url: impl Into<String>) -> Self {
+        self.edit_metadata(|m| m.artwork_url = Some(url.into()));
+        self
+    }
+
+    /// Set the artwork from encoded image bytes (PNG or JPEG).
+    ///
+    /// Replaces artwork embedded in the file and takes precedence over the artwork URL.
+    #[must_use]
+    pub fn artwork_bytes(self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.edit_metadata(|m| m.artwork_bytes = Some(bytes.into()));
         self
     }
 
+    /// Apply a metadata override to the current track.
+    fn edit_metadata(&self, edit: impl FnOnce(&mut MediaMetadata)) {
+        let mut queue = self.shared.queue();
+        let current = queue.current;
+        if let Some(track) = queue.tracks.get_mut(current) {
+            edit(&mut track.metadata);
+        }
+        self.metadata_dirty.set(true);
+    }
+
     // --- Playback Control ---
 
     /// Flush pending metadata updates to the media center.
@@ -389,14 +1240,17 @@ impl AudioPlayer {
     /// Start playback.
     pub fn play(&self) {
         self.flush_metadata();
-        self.sink.play();
+        self.shared.interrupted.store(false, Ordering::Relaxed);
+        self.shared.play();
         self.update_now_playing();
     }
 
     /// Pause playback.
     pub fn pause(&self) {
         self.flush_metadata();
-        self.sink.pause();
+        // Stay paused when the interruption ends
+        self.shared.interrupted.store(false, Ordering::Relaxed);
+        self.shared.pause();
         self.update_now_playing();
     }
 
@@ -413,21 +1267,282 @@ impl AudioPlayer {
     /// Stop playback.
     pub fn stop(&self) {
         self.flush_metadata();
-        self.sink.stop();
-        self.media_center.clear();
+        // Don't advance the queue when the stopped track ends
+        {
+            let mut queue = self.shared.queue();
+            queue.generation += 1;
+            queue.preloaded = None;
+        }
+        self.shared.finish_fade();
+        self.shared.sink().stop();
+        self.shared.media_center.clear();
         self.update_now_playing();
     }
 
     /// Seek to a specific position.
     pub fn seek(&self, position: Duration) {
         self.flush_metadata();
-        let _ = self.sink.try_seek(position);
+        self.shared.finish_fade();
+        let _ = self.shared.sink().try_seek(position);
         self.update_now_playing();
     }
 
     /// Set volume (0.0 to 1.0).
     pub fn set_volume(&self, volume: f32) {
-        self.sink.set_volume(volume.clamp(0.0, 1.0));
+        let volume = volume.clamp(0.0, 1.0);
+        self.shared
+            .volume
+            .store(volume.to_bits(), Ordering::Relaxed);
+        // A crossfade in progress ramps towards the new volume instead
+        if !self.shared.ramp_fade() {
+            self.shared.sink().set_volume(volume);
+        }
+    }
+
+    /// Set the playback rate, clamped to 0.5–3.0 (1.0 is normal speed).
+    ///
+    /// The rate is reported to the system media controls so their progress
+    /// bar advances at the right speed. Audio is resampled, so the pitch
+    /// changes along with the speed.
+    pub fn set_rate(&self, rate: f32) {
+        self.flush_metadata();
+        for sink in &self.shared.sinks {
+            sink.set_speed(rate.clamp(MIN_RATE, MAX_RATE));
+        }
+        self.update_now_playing();
+    }
+
+    /// Get the playback rate (1.0 is normal speed).
+    #[must_use]
+    pub fn rate(&self) -> f32 {
+        self.shared.sink().speed()
+    }
+
+    // --- Queue ---
+
+    /// Append a local file to the end of the queue.
+    ///
+    /// Metadata is extracted the same way as in [`open`](Self::open).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or decoded.
+    pub fn enqueue(&self, path: impl AsRef<Path>) -> Result<(), PlayerError> {
+        let track = Track::from_path(path.as_ref())?;
+        self.shared.queue().tracks.push(track);
+        self.shared.preload_next();
+        Ok(())
+    }
+
+    /// Append audio held in memory to the end of the queue.
+    ///
+    /// # Errors
+    /// Returns an error if the audio format is unsupported.
+    pub fn enqueue_bytes(&self, data: Vec<u8>, hint: Option<AudioHint>) -> Result<(), PlayerError> {
+        let track = Track::from_bytes(data.into(), hint)?;
+        self.shared.queue().tracks.push(track);
+        self.shared.preload_next();
+        Ok(())
+    }
+
+    /// Fetch audio from a URL and append it to the end of the queue.
+    ///
+    /// # Errors
+    /// Returns an error if the URL cannot be fetched or the audio format is unsupported.
+    #[allow(clippy::future_not_send)]
+    pub async fn enqueue_url(&self, url: &str) -> Result<(), PlayerError> {
+        let track = Track::from_url(url).await?;
+        self.shared.queue().tracks.push(track);
+        self.shared.preload_next();
+        Ok(())
+    }
+
+    /// Skip to the next track in the queue.
+    ///
+    /// Returns `false` if the current track is the last one.
+    ///
+    /// # Errors
+    /// Returns an error if the next track cannot be loaded.
+    pub fn next(&self) -> Result<bool, PlayerError> {
+        self.flush_metadata();
+        self.shared.next()
+    }
+
+    /// Go back to the previous track in the queue.
+    ///
+    /// On the first track this restarts it and returns `false`.
+    ///
+    /// # Errors
+    /// Returns an error if the previous track cannot be loaded.
+    pub fn previous(&self) -> Result<bool, PlayerError> {
+        self.flush_metadata();
+        self.shared.previous()
+    }
+
+    /// Get the metadata of every track in the queue, in playback order.
+    ///
+    /// This includes the current track, see [`current_index`](Self::current_index).
+    #[must_use]
+    pub fn queue(&self) -> Vec<MediaMetadata> {
+        self.shared
+            .queue()
+            .tracks
+            .iter()
+            .map(|track| track.metadata.clone())
+            .collect()
+    }
+
+    /// Get the position of the current track in [`queue`](Self::queue).
+    #[must_use]
+    pub fn current_index(&self) -> usize {
+        self.shared.queue().current
+    }
+
+    /// Remove every track from the queue except the current one.
+    pub fn clear_queue(&self) {
+        {
+            let mut queue = self.shared.queue();
+            let current = queue.current;
+            queue.tracks.truncate(current + 1);
+            queue.tracks.drain(..current);
+            queue.current = 0;
+        }
+        // Drop the next track from the sink if it was preloaded
+        self.shared.reload_current();
+    }
+
+    /// Enable or disable gapless transitions between queued tracks.
+    ///
+    /// When enabled (the default), the next track is decoded ahead of time
+    /// and starts right as the current one ends. When disabled, it is only
+    /// opened after the current track has finished, leaving a short gap.
+    ///
+    /// Gapless playback and [crossfading](Self::set_crossfade) are mutually
+    /// exclusive: enabling gapless playback turns crossfading off.
+    pub fn set_gapless(&self, gapless: bool) {
+        self.shared.gapless.store(gapless, Ordering::Relaxed);
+        if gapless {
+            *self
+                .shared
+                .crossfade
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Duration::ZERO;
+            self.shared.finish_fade();
+            self.shared.preload_next();
+        } else {
+            self.shared.reload_current();
+        }
+    }
+
+    /// Crossfade between queued tracks over `duration`, or turn crossfading
+    /// off with [`Duration::ZERO`] (the default).
+    ///
+    /// The next track starts `duration` before the current one ends, and
+    /// their volumes are ramped linearly in opposite directions. A track
+    /// shorter than twice the crossfade only fades over its second half,
+    /// and a track of unknown duration ends without a fade. Skipping to the
+    /// next track during a fade completes it immediately.
+    ///
+    /// Crossfading and [gapless playback](Self::set_gapless) are mutually
+    /// exclusive: a non-zero crossfade turns gapless playback off.
+    pub fn set_crossfade(&self, duration: Duration) {
+        *self
+            .shared
+            .crossfade
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = duration;
+        if duration.is_zero() {
+            self.shared.finish_fade();
+        } else {
+            self.set_gapless(false);
+        }
+    }
+
+    /// Pause playback when the output device goes away, such as when
+    /// headphones are unplugged. Off by default.
+    ///
+    /// See [`watch_routes`](crate::watch_routes) for how route changes are
+    /// detected on each platform.
+    pub fn set_pause_on_route_change(&self, pause: bool) {
+        *self
+            .shared
+            .routes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = pause.then(route::subscribe);
+    }
+
+    /// Move playback to the new default output device when it changes, such
+    /// as when a Bluetooth headset connects. On by default.
+    ///
+    /// Changes are detected by polling about once a second, as for
+    /// [`watch_routes`](crate::watch_routes). Playback continues on the new
+    /// device from the same position; each switch is reported through
+    /// [`device_events`](Self::device_events). Has no effect on iOS and
+    /// Android, where the system moves playback itself.
+    pub fn follow_default_device(&self, follow: bool) {
+        *self
+            .shared
+            .default_device
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = default_device_watch(follow);
+    }
+
+    /// Move playback to `device`, continuing from the same position.
+    ///
+    /// The device must come from [`list_devices`](Self::list_devices). While
+    /// a device is chosen, changes of the default device are not followed.
+    /// If the device goes away, playback moves to the default device and
+    /// [`DeviceEvent::Disconnected`] is reported through
+    /// [`device_events`](Self::device_events).
+    ///
+    /// On iOS only the built-in speaker can be chosen over the system's
+    /// route, through an `AVAudioSession` output override.
+    ///
+    /// # Errors
+    /// Returns an error if the device is not found or cannot be opened, in
+    /// which case playback stays on the current device.
+    pub fn set_output_device(&self, device: &AudioDevice) -> Result<(), PlayerError> {
+        self.request_device(Some(device.clone()))
+    }
+
+    /// Move playback back to the default output device, following it again
+    /// if [`follow_default_device`](Self::follow_default_device) is on.
+    ///
+    /// # Errors
+    /// Returns an error if the default device cannot be opened.
+    pub fn reset_output_device(&self) -> Result<(), PlayerError> {
+        self.request_device(None)
+    }
+
+    /// The output device chosen with [`set_output_device`](Self::set_output_device)
+    /// or [`AudioPlayerBuilder::device`], or `None` when playing to the
+    /// default device.
+    #[must_use]
+    pub fn output_device(&self) -> Option<AudioDevice> {
+        self.shared.output_device().clone()
+    }
+
+    /// Have the background worker, which owns the output stream, move
+    /// playback, and wait for the result.
+    fn request_device(&self, device: Option<AudioDevice>) -> Result<(), PlayerError> {
+        let (reply_tx, reply_rx) = std::sync::mpsc::channel();
+        self.device_requests
+            .send((device, reply_tx))
+            .map_err(|_| PlayerError::PlaybackFailed("audio thread stopped".into()))?;
+        reply_rx
+            .recv()
+            .map_err(|_| PlayerError::PlaybackFailed("audio thread stopped".into()))?
+    }
+
+    /// Resume playback when an interruption ends, if the system reports
+    /// that it should. On by default.
+    ///
+    /// Playback is always paused when an interruption such as a phone call
+    /// begins; both events are also delivered through [`commands`](Self::commands).
+    /// Playback paused or resumed by the app during the interruption is left as is.
+    pub fn set_resume_after_interruption(&self, resume: bool) {
+        self.shared
+            .resume_after_interruption
+            .store(resume, Ordering::Relaxed);
     }
 
     // --- State Queries ---
@@ -435,49 +1550,100 @@ impl AudioPlayer {
     /// Check if audio is currently playing.
     #[must_use]
     pub fn is_playing(&self) -> bool {
-        !self.sink.is_paused() && !self.sink.empty()
+        !self.shared.sink().is_paused() && !self.shared.sink().empty()
     }
 
     /// Check if audio is paused.
     #[must_use]
     pub fn is_paused(&self) -> bool {
-        self.sink.is_paused()
+        self.shared.sink().is_paused()
     }
 
     /// Check if the playlist is empty (playback finished).
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.sink.empty()
+        self.shared.sink().empty()
     }
 
     /// Get current playback position.
     pub fn position(&self) -> Duration {
-        self.sink.get_pos()
+        self.shared.sink().get_pos()
     }
 
-    /// Get total duration.
+    /// Get total duration of the current track.
     #[must_use]
-    pub const fn duration(&self) -> Option<Duration> {
-        self.metadata.duration
+    pub fn duration(&self) -> Option<Duration> {
+        self.shared.current_metadata().duration
     }
 
-    /// Get the current metadata.
-    pub fn metadata(&self) -> &MediaMetadata {
-        &self.metadata
+    /// Get the metadata of the current track.
+    #[must_use]
+    pub fn metadata(&self) -> MediaMetadata {
+        self.shared.current_metadata()
     }
 
     // --- Events ---
 
     /// Get a stream of media commands (Play, Pause, Next, etc.).
     ///
+    /// Until this is called, Next and Previous from system controls move
+    /// through the queue automatically. Afterwards they are delivered here
+    /// instead; pass them to [`handle`](Self::handle) to keep that behavior.
+    ///
     /// This is runtime-agnostic and can be used with any async executor.
     pub fn commands(&self) -> impl Stream<Item = MediaCommand> + '_ {
+        self.commands_taken.store(true, Ordering::Relaxed);
         self.command_receiver.clone()
     }
 
+    /// Get a stream of playback progress.
+    ///
+    /// The current state is sent right away. While playing, the status,
+    /// position and rate are then sent every `interval`; any transition
+    /// (play, pause, seek, track change, playback ending) is sent immediately.
+    /// Nothing is sent while paused or stopped.
+    ///
+    /// The background worker drives the updates, so intervals are rounded
+    /// up to its 50ms tick.
+    pub fn progress_stream(&self, interval: Duration) -> impl Stream<Item = PlaybackState> + use<> {
+        let (sender, receiver) = async_channel::unbounded();
+        let state = self.shared.playback_state();
+        let last = (Instant::now(), state.status);
+        let _ = sender.try_send(state);
+        self.shared.progress().push(ProgressSubscriber {
+            sender,
+            interval,
+            last,
+        });
+        receiver
+    }
+
+    /// Get a stream of audio route changes, such as headphones being
+    /// plugged in or unplugged.
+    ///
+    /// Same as [`watch_routes`](crate::watch_routes).
+    #[allow(clippy::unused_self)]
+    pub fn route_events(&self) -> impl Stream<Item = RouteEvent> + use<> {
+        route::subscribe()
+    }
+
+    /// Get a stream of switches to a new default output device, made while
+    /// [following it](Self::follow_default_device), and of fallbacks from a
+    /// [chosen device](Self::set_output_device) that went away.
+    pub fn device_events(&self) -> impl Stream<Item = DeviceEvent> + use<> {
+        let (sender, receiver) = async_channel::unbounded();
+        self.shared
+            .device_events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(sender);
+        receiver
+    }
+
     /// Handle a standard media command.
     ///
-    /// Automatically performs the action (Play, Pause, Seek) for standard commands.
+    /// Automatically performs the action (Play, Pause, Seek, Next, Previous, volume and rate
+    /// changes) for standard commands.
     /// You should call this when processing the command stream if you want default behavior.
     pub fn handle(&self, cmd: &MediaCommand) {
         match cmd {
@@ -492,25 +1658,38 @@ impl AudioPlayer {
             MediaCommand::SeekBackward(delta) => {
                 self.seek(self.position().saturating_sub(*delta));
             }
-            _ => {} // Next/Prev handled by app
+            MediaCommand::Next => {
+                let _ = self.next();
+            }
+            MediaCommand::Previous => {
+                let _ = self.previous();
+            }
+            #[allow(clippy::cast_possible_truncation)] // both are clamped to small ranges
+            MediaCommand::SetVolume(volume) => self.set_volume(*volume as f32),
+            #[allow(clippy::cast_possible_truncation)]
+            MediaCommand::SetRate(rate) => self.set_rate(*rate as f32),
+            _ => {}
         }
     }
 
     // --- Internal ---
 
     fn update_now_playing(&self) {
-        let state = if self.is_playing() {
-            PlaybackState::playing(self.sink.get_pos())
-        } else if self.sink.empty() {
-            PlaybackState::stopped()
-        } else {
-            PlaybackState::paused(self.sink.get_pos())
-        };
+        self.shared.update_now_playing();
+    }
 
-        self.media_center.update(&self.metadata, &state);
+    /// List available audio output devices.
+    ///
+    /// iOS only exposes the session's current route through cpal, so the
+    /// route's outputs and the built-in speaker are listed instead.
+    #[cfg(target_os = "ios")]
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn list_devices() -> Result<Vec<AudioDevice>, PlayerError> {
+        Ok(crate::sys::list_outputs())
     }
 
     /// List available audio output devices.
+    #[cfg(not(target_os = "ios"))]
     pub fn list_devices() -> Result<Vec<AudioDevice>, PlayerError> {
         use rodio::cpal::traits::{DeviceTrait, HostTrait};
 
@@ -518,24 +1697,41 @@ impl AudioPlayer {
         let devices: Vec<AudioDevice> = host
             .output_devices()
             .map_err(|e| PlayerError::Unknown(format!("failed to list devices: {e}")))?
-            .filter_map(|d| d.name().ok().map(|name| AudioDevice { name }))
+            .filter_map(|d| {
+                d.name()
+                    .ok()
+                    .map(|name| AudioDevice::new(name.clone(), name))
+            })
             .collect();
 
         Ok(devices)
     }
 }
 
+/// Route changes to follow the default output device with, if `follow` is set.
+///
+/// Mobile platforms move playback to the new route themselves.
+fn default_device_watch(follow: bool) -> Option<async_channel::Receiver<RouteEvent>> {
+    if cfg!(any(target_os = "ios", target_os = "android")) {
+        return None;
+    }
+    follow.then(route::subscribe)
+}
+
 impl Drop for AudioPlayer {
     fn drop(&mut self) {
         // ShutdownHandle is dropped automatically, signaling background thread to exit.
         // We explicitly drop it first to ensure the signal is sent before we try to join.
-        drop(std::mem::replace(&mut self.shutdown_handle, ShutdownHandle::default()));
+        drop(std::mem::replace(
+            &mut self.shutdown_handle,
+            ShutdownHandle::default(),
+        ));
 
         // Wait for background thread to exit cleanly
         if let Some(handle) = self.background_thread.take() {
             let _ = handle.join();
         }
 
-        self.media_center.clear();
+        self.shared.media_center.clear();
     }
 }
@@ -2,7 +2,24 @@
 //!
 //! Uses `cpal` for desktop platforms and native APIs for mobile.
 
+use crate::AudioDevice;
+use crate::convert::{f32_to_i16, i16_to_f32};
+use crate::vad::VadSettings;
+use crate::wav::{WavFileWriter, WavSampleFormat};
+use std::borrow::Cow;
 use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Encoding of recorded samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SampleFormat {
+    /// 32-bit float in `-1.0..=1.0`.
+    #[default]
+    F32,
+    /// 16-bit signed integer PCM.
+    I16,
+}
 
 /// Audio sample format configuration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -11,6 +28,8 @@ pub struct AudioFormat {
     pub sample_rate: u32,
     /// Number of channels (1 = mono, 2 = stereo).
     pub channels: u16,
+    /// Encoding of the samples.
+    pub sample_format: SampleFormat,
 }
 
 impl Default for AudioFormat {
@@ -18,30 +37,23 @@ impl Default for AudioFormat {
         Self {
             sample_rate: 44100,
             channels: 1,
+            sample_format: SampleFormat::F32,
         }
     }
 }
 
-/// Information about an audio input device.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct InputDevice {
-    /// Unique identifier for the device.
-    pub id: String,
-    /// Human-readable name.
-    pub name: String,
-}
-
-impl fmt::Display for InputDevice {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.name)
-    }
+/// Samples in the encoding of their [`SampleFormat`].
+#[derive(Clone)]
+enum Samples {
+    F32(Vec<f32>),
+    I16(Vec<i16>),
 }
 
 /// A buffer of recorded audio samples.
 #[derive(Clone)]
 pub struct AudioBuffer {
-    /// Audio samples as f32 (-1.0 to 1.0).
-    samples: Vec<f32>,
+    /// Interleaved audio samples.
+    samples: Samples,
     /// Format of the audio data.
     format: AudioFormat,
 }
@@ -49,7 +61,7 @@ pub struct AudioBuffer {
 impl fmt::Debug for AudioBuffer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("AudioBuffer")
-            .field("samples_len", &self.samples.len())
+            .field("samples_len", &self.len())
             .field("format", &self.format)
             .finish()
     }
@@ -57,15 +69,38 @@ impl fmt::Debug for AudioBuffer {
 
 impl AudioBuffer {
     /// Create a new audio buffer.
+    ///
+    /// The samples are stored in `format.sample_format`, converting from
+    /// f32 if it is [`SampleFormat::I16`].
     #[must_use]
-    pub const fn new(samples: Vec<f32>, format: AudioFormat) -> Self {
+    pub fn new(samples: Vec<f32>, format: AudioFormat) -> Self {
+        let samples = match format.sample_format {
+            SampleFormat::F32 => Samples::F32(samples),
+            SampleFormat::I16 => Samples::I16(samples.into_iter().map(f32_to_i16).collect()),
+        };
         Self { samples, format }
     }
 
-    /// Get the audio samples.
+    /// Get the audio samples as f32 (-1.0 to 1.0).
+    ///
+    /// Borrowed for [`SampleFormat::F32`] buffers, converted otherwise.
     #[must_use]
-    pub fn samples(&self) -> &[f32] {
-        &self.samples
+    pub fn samples(&self) -> Cow<'_, [f32]> {
+        match &self.samples {
+            Samples::F32(samples) => Cow::Borrowed(samples),
+            Samples::I16(samples) => Cow::Owned(samples.iter().copied().map(i16_to_f32).collect()),
+        }
+    }
+
+    /// Get the audio samples as 16-bit PCM.
+    ///
+    /// Borrowed for [`SampleFormat::I16`] buffers, converted otherwise.
+    #[must_use]
+    pub fn samples_i16(&self) -> Cow<'_, [i16]> {
+        match &self.samples {
+            Samples::I16(samples) => Cow::Borrowed(samples),
+            Samples::F32(samples) => Cow::Owned(samples.iter().copied().map(f32_to_i16).collect()),
+        }
     }
 
     /// Get the audio format.
@@ -77,24 +112,35 @@ impl AudioBuffer {
     /// Get the number of samples.
     #[must_use]
     pub const fn len(&self) -> usize {
-        self.samples.len()
+        match &self.samples {
+            Samples::F32(samples) => samples.len(),
+            Samples::I16(samples) => samples.len(),
+        }
     }
 
     /// Check if the buffer is empty.
     #[must_use]
     pub const fn is_empty(&self) -> bool {
-        self.samples.is_empty()
+        self.len() == 0
     }
 
     /// Get duration in seconds.
     #[must_use]
     #[allow(clippy::cast_precision_loss)]
     pub fn duration_secs(&self) -> f64 {
-        self.samples.len() as f64
-            / (f64::from(self.format.sample_rate) * f64::from(self.format.channels))
+        self.len() as f64 / (f64::from(self.format.sample_rate) * f64::from(self.format.channels))
     }
 }
 
+/// Voice activity reported by [`AudioRecorder::events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecorderEvent {
+    /// The user started speaking.
+    SpeechStarted,
+    /// The user stopped speaking for longer than the hangover.
+    SpeechEnded,
+}
+
 /// Errors that can occur during audio recording.
 #[derive(Debug, Clone)]
 pub enum RecordError {
@@ -104,6 +150,10 @@ pub enum RecordError {
     EnumerationFailed(String),
     /// Device not found.
     DeviceNotFound(String),
+    /// The requested format can't be recorded.
+    UnsupportedFormat(String),
+    /// The selected device was disconnected while recording.
+    DeviceDisconnected(String),
     /// Failed to open device.
     OpenFailed(String),
     /// Failed to start recording.
@@ -114,6 +164,8 @@ pub enum RecordError {
     PermissionDenied,
     /// Recording is not active.
     NotRecording,
+    /// Failed to write the output file.
+    WriteFailed(String),
     /// An unknown error occurred.
     Unknown(String),
 }
@@ -124,11 +176,14 @@ impl fmt::Display for RecordError {
             Self::NotSupported => write!(f, "recording not supported on this platform"),
             Self::EnumerationFailed(msg) => write!(f, "failed to enumerate devices: {msg}"),
             Self::DeviceNotFound(id) => write!(f, "device not found: {id}"),
+            Self::UnsupportedFormat(msg) => write!(f, "unsupported format: {msg}"),
+            Self::DeviceDisconnected(id) => write!(f, "device disconnected: {id}"),
             Self::OpenFailed(msg) => write!(f, "failed to open device: {msg}"),
             Self::StartFailed(msg) => write!(f, "failed to start recording: {msg}"),
             Self::ReadFailed(msg) => write!(f, "failed to read audio: {msg}"),
             Self::PermissionDenied => write!(f, "microphone permission denied"),
             Self::NotRecording => write!(f, "not currently recording"),
+            Self::WriteFailed(msg) => write!(f, "failed to write output file: {msg}"),
             Self::Unknown(msg) => write!(f, "unknown error: {msg}"),
         }
     }
@@ -142,6 +197,11 @@ pub struct AudioRecorderBuilder {
     device_id: Option<String>,
     sample_rate: Option<u32>,
     channels: Option<u16>,
+    sample_format: Option<SampleFormat>,
+    frames_per_buffer: Option<u32>,
+    output: Option<(PathBuf, WavSampleFormat)>,
+    vad: Option<VadSettings>,
+    skip_silence: bool,
 }
 
 impl AudioRecorderBuilder {
@@ -152,13 +212,20 @@ impl AudioRecorderBuilder {
     }
 
     /// Set a specific input device (optional, uses default if not set).
+    ///
+    /// The device must come from [`AudioRecorder::list_input_devices`]. If it
+    /// disappears while recording, reads fail with [`RecordError::DeviceDisconnected`]
+    /// instead of falling back to the default microphone.
     #[must_use]
-    pub fn device(mut self, device: &InputDevice) -> Self {
-        self.device_id = Some(device.id.clone());
+    pub fn device(mut self, device: &AudioDevice) -> Self {
+        self.device_id = Some(device.id().to_string());
         self
     }
 
     /// Set the sample rate in Hz.
+    ///
+    /// The device records at its closest native rate, and the audio is
+    /// resampled if that differs.
     #[must_use]
     pub const fn sample_rate(mut self, rate: u32) -> Self {
         self.sample_rate = Some(rate);
@@ -166,23 +233,118 @@ impl AudioRecorderBuilder {
     }
 
     /// Set the number of channels.
+    ///
+    /// Extra device channels are mixed down (averaged for mono), and a mono
+    /// device is copied to every channel.
     #[must_use]
     pub const fn channels(mut self, channels: u16) -> Self {
         self.channels = Some(channels);
         self
     }
 
+    /// Set the encoding of delivered samples (f32 by default).
+    #[must_use]
+    pub const fn sample_format(mut self, format: SampleFormat) -> Self {
+        self.sample_format = Some(format);
+        self
+    }
+
+    /// Deliver audio in buffers of exactly `frames` frames.
+    ///
+    /// By default each buffer holds whatever the device delivered in one
+    /// callback, which varies by platform. Fixed-size chunks suit consumers
+    /// such as speech recognition that expect e.g. 20 ms frames: at 16 kHz,
+    /// that is `frames_per_buffer(320)`. The last buffer before
+    /// [`AudioRecorder::stop`] may be shorter.
+    #[must_use]
+    pub const fn frames_per_buffer(mut self, frames: u32) -> Self {
+        self.frames_per_buffer = Some(frames);
+        self
+    }
+
+    /// Detect when the user is speaking.
+    ///
+    /// Audio counts as speech in 10 ms windows whose energy reaches
+    /// `threshold_db` dBFS (around -40 suits a quiet room) and whose
+    /// zero-crossing rate isn't that of broadband noise. Speech ends once
+    /// none has been heard for `hangover`, so short pauses don't end it.
+    /// Transitions are reported by [`AudioRecorder::events`].
+    #[must_use]
+    pub const fn enable_vad(mut self, threshold_db: f32, hangover: Duration) -> Self {
+        self.vad = Some(VadSettings {
+            threshold_db,
+            hangover,
+        });
+        self
+    }
+
+    /// Don't deliver audio while no speech is detected.
+    ///
+    /// Silence is dropped from [`AudioRecorder::read`], streams and the
+    /// output file alike. Uses the [`enable_vad`](Self::enable_vad) settings,
+    /// or -40 dBFS with a 300 ms hangover if it wasn't called. With
+    /// [`frames_per_buffer`](Self::frames_per_buffer), the last buffer of
+    /// each utterance may be shorter.
+    #[must_use]
+    pub const fn skip_silence(mut self, skip: bool) -> Self {
+        self.skip_silence = skip;
+        self
+    }
+
+    /// Also write the recording to a WAV file at `path`.
+    ///
+    /// The file uses the configured sample rate and channel count with the given
+    /// sample encoding. Its header is finalized when [`AudioRecorder::stop`] is called.
+    /// If writing fails, including when the recording would exceed the 4 GiB
+    /// RIFF limit, capture stops: reads return [`RecordError::WriteFailed`],
+    /// and so does `stop()`, after finalizing what was written.
+    #[must_use]
+    pub fn output_path(mut self, path: impl Into<PathBuf>, sample_format: WavSampleFormat) -> Self {
+        self.output = Some((path.into(), sample_format));
+        self
+    }
+
     /// Build the audio recorder.
     ///
     /// # Errors
     ///
-    /// Returns an error if the device cannot be opened.
+    /// Returns [`RecordError::UnsupportedFormat`] for a zero sample rate or
+    /// channel count, or if the device has no input configuration to convert
+    /// from. Returns other errors if the device cannot be opened or the output
+    /// file cannot be created.
     pub fn build(self) -> Result<AudioRecorder, RecordError> {
         let format = AudioFormat {
             sample_rate: self.sample_rate.unwrap_or(44100),
             channels: self.channels.unwrap_or(1),
+            sample_format: self.sample_format.unwrap_or_default(),
         };
-        AudioRecorder::new_internal(self.device_id, format)
+        if format.sample_rate == 0 || format.channels == 0 {
+            return Err(RecordError::UnsupportedFormat(format!(
+                "{} Hz with {} channels",
+                format.sample_rate, format.channels
+            )));
+        }
+        let mut recorder =
+            AudioRecorder::new_internal(self.device_id, format, self.frames_per_buffer)?;
+        let vad = if self.skip_silence {
+            Some(self.vad.unwrap_or_default())
+        } else {
+            self.vad
+        };
+        if let Some(vad) = vad {
+            recorder.inner.set_vad(vad, self.skip_silence);
+        }
+        if let Some((path, sample_format)) = self.output {
+            let wav = WavFileWriter::create(
+                &path,
+                format,
+                sample_format,
+                recorder.inner.failure_reporter(),
+            )?;
+            recorder.inner.add_tap(wav.sender());
+            recorder.wav = Some(wav);
+        }
+        Ok(recorder)
     }
 }
 
@@ -212,6 +374,7 @@ impl AudioRecorderBuilder {
 pub struct AudioRecorder {
     inner: crate::sys::AudioRecorderInner,
     format: AudioFormat,
+    wav: Option<WavFileWriter>,
 }
 
 impl fmt::Debug for AudioRecorder {
@@ -223,6 +386,9 @@ impl fmt::Debug for AudioRecorder {
 }
 
 impl AudioRecorder {
+    /// Number of buffers a [`stream`](Self::stream) holds before dropping the oldest.
+    pub const STREAM_CAPACITY: usize = 32;
+
     /// Create a new audio recorder builder.
     #[must_use]
     #[allow(clippy::new_ret_no_self)]
@@ -235,14 +401,19 @@ impl AudioRecorder {
     /// # Errors
     ///
     /// Returns an error if device enumeration fails.
-    pub fn list_devices() -> Result<Vec<InputDevice>, RecordError> {
+    pub fn list_input_devices() -> Result<Vec<AudioDevice>, RecordError> {
         crate::sys::AudioRecorderInner::list_devices()
     }
 
-    fn new_internal(device_id: Option<String>, format: AudioFormat) -> Result<Self, RecordError> {
+    fn new_internal(
+        device_id: Option<String>,
+        format: AudioFormat,
+        frames_per_buffer: Option<u32>,
+    ) -> Result<Self, RecordError> {
         Ok(Self {
-            inner: crate::sys::AudioRecorderInner::new(device_id, format)?,
+            inner: crate::sys::AudioRecorderInner::new(device_id, format, frames_per_buffer)?,
             format,
+            wav: None,
         })
     }
 
@@ -254,12 +425,15 @@ impl AudioRecorder {
         self.inner.start().await
     }
 
+    /// Stop recording and finalize the output file, if one was configured.
+    ///
     /// # Errors
     ///
-    /// Returns an error if recording cannot be stopped.
+    /// Returns an error if recording cannot be stopped or the output file cannot be finalized.
     #[allow(clippy::future_not_send)]
     pub async fn stop(&mut self) -> Result<(), RecordError> {
-        self.inner.stop().await
+        self.inner.stop().await?;
+        self.wav.take().map_or(Ok(()), WavFileWriter::finish)
     }
 
     /// # Errors
@@ -290,9 +464,30 @@ impl AudioRecorder {
         self.inner.read_blocking()
     }
 
-    /// Get an async stream of audio buffers.
-    pub fn stream(&self) -> impl futures::Stream<Item = AudioBuffer> {
-        self.inner.receiver()
+    /// Get an async stream of audio buffers as they are captured.
+    ///
+    /// Each stream receives its own copy of every buffer, independently of
+    /// [`read`](Self::read) and other streams. Buffers are sized by
+    /// [`AudioRecorderBuilder::frames_per_buffer`]. The stream can be created
+    /// before or during recording, and ends when [`stop`](Self::stop) is called.
+    ///
+    /// At most [`STREAM_CAPACITY`](Self::STREAM_CAPACITY) buffers are queued. If the consumer falls
+    /// further behind, the oldest buffers are dropped so the capture thread is
+    /// never blocked and the stream stays close to real time.
+    pub fn stream(&mut self) -> impl futures::Stream<Item = AudioBuffer> + Send + use<> {
+        self.inner.subscribe(Self::STREAM_CAPACITY)
+    }
+
+    /// Get an async stream of voice activity events.
+    ///
+    /// Requires [`AudioRecorderBuilder::enable_vad`] or
+    /// [`AudioRecorderBuilder::skip_silence`]; otherwise the stream yields
+    /// nothing. Speech still in progress ends with
+    /// [`RecorderEvent::SpeechEnded`] when [`stop`](Self::stop) is called,
+    /// after which the stream ends. Like [`stream`](Self::stream), at most
+    /// [`STREAM_CAPACITY`](Self::STREAM_CAPACITY) events are queued.
+    pub fn events(&mut self) -> impl futures::Stream<Item = RecorderEvent> + Send + use<> {
+        self.inner.subscribe_events(Self::STREAM_CAPACITY)
     }
 
     /// Check if currently recording.
@@ -0,0 +1,88 @@
+//! Audio route change notifications.
+//!
+//! Platform monitors report changes through [`dispatch`], which fans them
+//! out to every [`watch_routes`] stream.
+
+use crate::sys;
+use futures::Stream;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A change of the audio output route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteEvent {
+    /// The output device in use went away, e.g. headphones were unplugged.
+    OldDeviceUnavailable {
+        /// Name of the device that went away.
+        device: String,
+    },
+    /// A new output device became available, e.g. headphones were plugged in.
+    NewDeviceAvailable {
+        /// Name of the new device.
+        device: String,
+    },
+    /// The audio session category changed (iOS only).
+    CategoryChange,
+    /// The system default output device changed (desktop only).
+    ///
+    /// Reported on macOS and Windows, where the default device can be
+    /// switched in the system settings. On Linux, cpal plays to the sound
+    /// server's default sink, which follows changes on its own.
+    DefaultDeviceChanged {
+        /// Name of the new default device.
+        device: String,
+    },
+}
+
+static SUBSCRIBERS: Mutex<Vec<async_channel::Sender<RouteEvent>>> = Mutex::new(Vec::new());
+
+fn subscribers() -> MutexGuard<'static, Vec<async_channel::Sender<RouteEvent>>> {
+    SUBSCRIBERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Watch for audio output route changes.
+///
+/// Uses `AVAudioSession.routeChangeNotification` on iOS and an
+/// `AudioDeviceCallback` on Android. On desktop the output devices are
+/// polled through cpal about once a second: `OldDeviceUnavailable` is
+/// reported when the default output device disappears, and
+/// `DefaultDeviceChanged` when another device becomes the default.
+///
+/// On Android, events are only reported after `start_route_monitor_with_context`
+/// has been called.
+pub fn watch_routes() -> impl Stream<Item = RouteEvent> {
+    subscribe()
+}
+
+/// Register a subscriber and make sure the platform monitor is running.
+pub(crate) fn subscribe() -> async_channel::Receiver<RouteEvent> {
+    let (sender, receiver) = async_channel::unbounded();
+    subscribers().push(sender);
+    sys::start_route_monitor();
+    receiver
+}
+
+/// Whether any stream is still listening, dropping closed ones.
+pub(crate) fn has_subscribers() -> bool {
+    let mut subscribers = subscribers();
+    subscribers.retain(|sender| !sender.is_closed());
+    !subscribers.is_empty()
+}
+
+/// Send `event` to every subscriber.
+pub(crate) fn dispatch(event: &RouteEvent) {
+    subscribers().retain(|sender| sender.try_send(event.clone()).is_ok());
+}
+
+/// Dispatch an event reported by native code.
+///
+/// Reason codes: 0 old device unavailable, 1 new device available,
+/// 2 category change.
+#[cfg(any(target_os = "ios", target_os = "macos", target_os = "android"))]
+pub(crate) fn dispatch_native(reason: u8, device: String) {
+    let event = match reason {
+        0 => RouteEvent::OldDeviceUnavailable { device },
+        1 => RouteEvent::NewDeviceAvailable { device },
+        _ => RouteEvent::CategoryChange,
+    };
+    dispatch(&event);
+}
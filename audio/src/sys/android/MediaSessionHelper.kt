@@ -2,17 +2,100 @@ package waterkit.media
 
 import android.content.Context
 import android.media.AudioAttributes
+import android.media.AudioDeviceCallback
+import android.media.AudioDeviceInfo
 import android.media.AudioFocusRequest
 import android.media.AudioManager
 import android.media.MediaMetadata
 import android.media.session.MediaSession
 import android.media.session.PlaybackState
 import android.os.Build
+import android.os.Handler
+import android.os.Looper
+import android.graphics.Bitmap
 import android.graphics.BitmapFactory
 import java.net.URL
 import kotlin.concurrent.thread
 
 object MediaSessionHelper {
+    private const val MAX_ARTWORK_SIZE = 1024
+
+    // Reasons passed to onRouteChange
+    private const val ROUTE_OLD_DEVICE_UNAVAILABLE = 0
+    private const val ROUTE_NEW_DEVICE_AVAILABLE = 1
+
+    private var deviceCallback: AudioDeviceCallback? = null
+
+    /** Receives output device changes; [device] is the product name. */
+    @JvmStatic
+    external fun onRouteChange(reason: Int, device: String)
+
+    /**
+     * Report output devices being connected and disconnected.
+     * Stays registered for the lifetime of the process.
+     */
+    @JvmStatic
+    fun startRouteMonitor(ctx: Context) {
+        if (deviceCallback != null) {
+            return
+        }
+        val manager = ctx.getSystemService(Context.AUDIO_SERVICE) as AudioManager
+
+        // The callback first reports every connected device as added; skip those
+        val known = manager.getDevices(AudioManager.GET_DEVICES_OUTPUTS).map { it.id }.toMutableSet()
+        val callback = object : AudioDeviceCallback() {
+            override fun onAudioDevicesAdded(addedDevices: Array<AudioDeviceInfo>) {
+                addedDevices.filter { it.isSink && known.add(it.id) }.forEach {
+                    onRouteChange(ROUTE_NEW_DEVICE_AVAILABLE, it.productName?.toString() ?: "")
+                }
+            }
+
+            override fun onAudioDevicesRemoved(removedDevices: Array<AudioDeviceInfo>) {
+                removedDevices.filter { it.isSink && known.remove(it.id) }.forEach {
+                    onRouteChange(ROUTE_OLD_DEVICE_UNAVAILABLE, it.productName?.toString() ?: "")
+                }
+            }
+        }
+        manager.registerAudioDeviceCallback(callback, Handler(Looper.getMainLooper()))
+        deviceCallback = callback
+    }
+
+    /**
+     * Receives audio focus changes as interruptions.
+     * [shouldResume] is only meaningful when the interruption ends.
+     */
+    @JvmStatic
+    external fun onInterruption(began: Boolean, shouldResume: Boolean)
+
+    /** Receives every raw `AudioManager.AUDIOFOCUS_*` change. */
+    @JvmStatic
+    external fun onAudioFocusChange(change: Int)
+
+    /** Receives playback speed changes requested by media controllers. */
+    @JvmStatic
+    external fun onSetRate(rate: Float)
+
+    // Focus only comes back on its own after a transient loss
+    private var focusLostTransiently = false
+
+    private val focusChangeListener = AudioManager.OnAudioFocusChangeListener { change ->
+        onAudioFocusChange(change)
+        when (change) {
+            AudioManager.AUDIOFOCUS_LOSS_TRANSIENT -> {
+                focusLostTransiently = true
+                onInterruption(true, false)
+            }
+            AudioManager.AUDIOFOCUS_LOSS -> {
+                focusLostTransiently = false
+                onInterruption(true, false)
+            }
+            AudioManager.AUDIOFOCUS_GAIN -> {
+                onInterruption(false, focusLostTransiently)
+                focusLostTransiently = false
+            }
+        }
+    }
+
     private var mediaSession: MediaSession? = null
     private var audioManager: AudioManager? = null
     private var audioFocusRequest: AudioFocusRequest? = null
@@ -48,13 +131,24 @@ object MediaSessionHelper {
                 override fun onSeekTo(pos: Long) {
                     // Callback to Rust would go here
                 }
+
+                override fun onSetPlaybackSpeed(speed: Float) {
+                    onSetRate(speed)
+                }
             })
             isActive = true
         }
     }
     
     @JvmStatic
-    fun setMetadata(title: String, artist: String, album: String, artworkUrl: String, durationMs: Long) {
+    fun setMetadata(
+        title: String,
+        artist: String,
+        album: String,
+        artworkUrl: String,
+        artwork: ByteArray?,
+        durationMs: Long
+    ) {
         val builder = MediaMetadata.Builder()
         
         if (title.isNotEmpty()) {
@@ -70,8 +164,12 @@ object MediaSessionHelper {
             builder.putLong(MediaMetadata.METADATA_KEY_DURATION, durationMs)
         }
         
-        // Load artwork from URL in background
-        if (artworkUrl.isNotEmpty()) {
+        // Artwork bytes win over the URL
+        val artworkBitmap = artwork?.let { decodeArtwork(it) }
+        if (artworkBitmap != null) {
+            builder.putBitmap(MediaMetadata.METADATA_KEY_ALBUM_ART, artworkBitmap)
+        } else if (artworkUrl.isNotEmpty()) {
+            // Load artwork from URL in background
             thread {
                 try {
                     val url = URL(artworkUrl)
@@ -90,6 +188,25 @@ object MediaSessionHelper {
         
         mediaSession?.setMetadata(builder.build())
     }
+
+    /**
+     * Decode PNG/JPEG bytes, subsampling large images so the bitmap stays
+     * well under the binder transaction limit.
+     */
+    private fun decodeArtwork(bytes: ByteArray): Bitmap? {
+        val bounds = BitmapFactory.Options().apply { inJustDecodeBounds = true }
+        BitmapFactory.decodeByteArray(bytes, 0, bytes.size, bounds)
+        if (bounds.outWidth <= 0 || bounds.outHeight <= 0) {
+            return null
+        }
+
+        var sampleSize = 1
+        while (maxOf(bounds.outWidth, bounds.outHeight) / (sampleSize * 2) >= MAX_ARTWORK_SIZE) {
+            sampleSize *= 2
+        }
+        val options = BitmapFactory.Options().apply { inSampleSize = sampleSize }
+        return BitmapFactory.decodeByteArray(bytes, 0, bytes.size, options)
+    }
     
     @JvmStatic
     fun setPlaybackState(status: Int, positionMs: Long, speed: Float) {
@@ -100,13 +217,16 @@ object MediaSessionHelper {
             else -> PlaybackState.STATE_NONE
         }
         
-        val actions = PlaybackState.ACTION_PLAY or
+        var actions = PlaybackState.ACTION_PLAY or
                 PlaybackState.ACTION_PAUSE or
                 PlaybackState.ACTION_PLAY_PAUSE or
                 PlaybackState.ACTION_STOP or
                 PlaybackState.ACTION_SKIP_TO_NEXT or
                 PlaybackState.ACTION_SKIP_TO_PREVIOUS or
                 PlaybackState.ACTION_SEEK_TO
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.Q) {
+            actions = actions or PlaybackState.ACTION_SET_PLAYBACK_SPEED
+        }
         
         val playbackState = PlaybackState.Builder()
             .setState(state, if (positionMs >= 0) positionMs else PlaybackState.PLAYBACK_POSITION_UNKNOWN, speed)
@@ -116,27 +236,29 @@ object MediaSessionHelper {
         mediaSession?.setPlaybackState(playbackState)
     }
     
+    /** [focusGain] is one of the `AudioManager.AUDIOFOCUS_GAIN*` constants. */
     @JvmStatic
-    fun requestAudioFocus(): Boolean {
+    fun requestAudioFocus(focusGain: Int): Boolean {
         val am = audioManager ?: return false
         
         return if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
-            val focusRequest = AudioFocusRequest.Builder(AudioManager.AUDIOFOCUS_GAIN)
+            val focusRequest = AudioFocusRequest.Builder(focusGain)
                 .setAudioAttributes(
                     AudioAttributes.Builder()
                         .setUsage(AudioAttributes.USAGE_MEDIA)
                         .setContentType(AudioAttributes.CONTENT_TYPE_MUSIC)
                         .build()
                 )
+                .setOnAudioFocusChangeListener(focusChangeListener, Handler(Looper.getMainLooper()))
                 .build()
             audioFocusRequest = focusRequest
             am.requestAudioFocus(focusRequest) == AudioManager.AUDIOFOCUS_REQUEST_GRANTED
         } else {
             @Suppress("DEPRECATION")
             am.requestAudioFocus(
-                null,
+                focusChangeListener,
                 AudioManager.STREAM_MUSIC,
-                AudioManager.AUDIOFOCUS_GAIN
+                focusGain
             ) == AudioManager.AUDIOFOCUS_REQUEST_GRANTED
         }
     }
@@ -149,7 +271,7 @@ object MediaSessionHelper {
             audioFocusRequest?.let { am.abandonAudioFocusRequest(it) }
         } else {
             @Suppress("DEPRECATION")
-            am.abandonAudioFocus(null)
+            am.abandonAudioFocus(focusChangeListener)
         }
     }
     
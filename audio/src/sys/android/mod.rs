@@ -1,9 +1,14 @@
 //! Android media control implementation using JNI and MediaSession.
 
-use crate::{MediaCommand, MediaCommandHandler, MediaError, MediaMetadata, PlaybackState, PlaybackStatus};
+use crate::{
+    AudioFocusChange, AudioFocusType, MediaCommand, MediaCommandHandler, MediaError, MediaMetadata,
+    PlaybackState, PlaybackStatus,
+};
 use jni::JNIEnv;
-use jni::objects::{GlobalRef, JObject, JValue};
-use std::sync::OnceLock;
+use jni::objects::{GlobalRef, JObject, JString, JValue};
+use jni::sys::{jboolean, jfloat, jint};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
 
 /// Embedded DEX bytecode containing MediaSessionHelper class.
 /// Generated at build time by kotlinc + D8.
@@ -79,9 +84,138 @@ pub fn init_with_context(env: &mut JNIEnv, context: &JObject) -> Result<(), Medi
         .map_err(|e| MediaError::InitializationFailed(format!("new_global_ref: {e}")))?;
 
     let _ = CLASS_LOADER.set(global_ref);
+
+    // The helper is loaded from our own DexClassLoader, so the runtime can't
+    // resolve its native methods by symbol name; register them explicitly.
+    register_natives(env)?;
+
     Ok(())
 }
 
+fn register_natives(env: &mut JNIEnv) -> Result<(), MediaError> {
+    let class = get_helper_class(env)?;
+    let native_methods = [
+        jni::NativeMethod {
+            name: "onRouteChange".into(),
+            sig: "(ILjava/lang/String;)V".into(),
+            fn_ptr: Java_waterkit_media_MediaSessionHelper_onRouteChange as *mut _,
+        },
+        jni::NativeMethod {
+            name: "onInterruption".into(),
+            sig: "(ZZ)V".into(),
+            fn_ptr: Java_waterkit_media_MediaSessionHelper_onInterruption as *mut _,
+        },
+        jni::NativeMethod {
+            name: "onAudioFocusChange".into(),
+            sig: "(I)V".into(),
+            fn_ptr: Java_waterkit_media_MediaSessionHelper_onAudioFocusChange as *mut _,
+        },
+        jni::NativeMethod {
+            name: "onSetRate".into(),
+            sig: "(F)V".into(),
+            fn_ptr: Java_waterkit_media_MediaSessionHelper_onSetRate as *mut _,
+        },
+    ];
+
+    env.register_native_methods(class, &native_methods)
+        .map_err(|e| MediaError::InitializationFailed(format!("register_native_methods: {e}")))
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_media_MediaSessionHelper_onRouteChange(
+    mut env: JNIEnv,
+    _class: JClass,
+    reason: jint,
+    device: JString,
+) {
+    let device: String = env.get_string(&device).map(Into::into).unwrap_or_default();
+    crate::route::dispatch_native(u8::try_from(reason).unwrap_or(u8::MAX), device);
+}
+
+/// Commands waiting for [`MediaCenterInner::poll_command`].
+static COMMAND_QUEUE: Mutex<VecDeque<MediaCommand>> = Mutex::new(VecDeque::new());
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_media_MediaSessionHelper_onInterruption(
+    _env: JNIEnv,
+    _class: JClass,
+    began: jboolean,
+    should_resume: jboolean,
+) {
+    let command = if began != 0 {
+        MediaCommand::InterruptionBegan
+    } else {
+        MediaCommand::InterruptionEnded {
+            should_resume: should_resume != 0,
+        }
+    };
+    if let Ok(mut queue) = COMMAND_QUEUE.lock() {
+        queue.push_back(command);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_media_MediaSessionHelper_onAudioFocusChange(
+    _env: JNIEnv,
+    _class: JClass,
+    change: jint,
+) {
+    // AudioManager.AUDIOFOCUS_* values
+    let change = match change {
+        1 => AudioFocusChange::Gain,
+        -1 => AudioFocusChange::Loss,
+        -2 => AudioFocusChange::LossTransient,
+        -3 => AudioFocusChange::LossTransientCanDuck,
+        _ => return,
+    };
+    if let Ok(mut queue) = COMMAND_QUEUE.lock() {
+        queue.push_back(MediaCommand::AudioFocusChanged(change));
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_media_MediaSessionHelper_onSetRate(
+    _env: JNIEnv,
+    _class: JClass,
+    rate: jfloat,
+) {
+    if rate.is_nan() || rate <= 0.0 {
+        return;
+    }
+    let rate = crate::player::clamp_rate(f64::from(rate));
+    if let Ok(mut queue) = COMMAND_QUEUE.lock() {
+        queue.push_back(MediaCommand::SetRate(rate));
+    }
+}
+
+/// Start reporting output device changes to [`watch_routes`](crate::watch_routes).
+///
+/// Registers an `AudioDeviceCallback`, which stays registered for the
+/// lifetime of the process. Calling this again has no effect.
+///
+/// # Errors
+/// Returns [`MediaError::InitializationFailed`] if the helper can't be loaded
+/// or the callback can't be registered.
+pub fn start_route_monitor_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<(), MediaError> {
+    init_with_context(env, context)?;
+    let helper_class = get_helper_class(env)?;
+    env.call_static_method::<&JClass, _, _>(
+        &helper_class,
+        "startRouteMonitor",
+        "(Landroid/content/Context;)V",
+        &[JValue::Object(context)],
+    )
+    .map_err(|e| MediaError::InitializationFailed(format!("startRouteMonitor: {e}")))?;
+    Ok(())
+}
+
+/// Route changes are only reported once [`start_route_monitor_with_context`]
+/// has been called with a Context.
+pub(crate) fn start_route_monitor() {}
+
 use jni::objects::JClass;
 
 /// Get the MediaSessionHelper class.
@@ -146,6 +280,13 @@ pub fn set_metadata_with_context(
     let artwork_url = env
         .new_string(metadata.artwork_url.as_deref().unwrap_or(""))
         .map_err(|e| MediaError::UpdateFailed(format!("new_string artwork_url: {e}")))?;
+    let artwork_bytes = match metadata.artwork_bytes {
+        Some(ref bytes) => JObject::from(
+            env.byte_array_from_slice(bytes)
+                .map_err(|e| MediaError::UpdateFailed(format!("byte_array artwork: {e}")))?,
+        ),
+        None => JObject::null(),
+    };
 
     let duration_ms = metadata
         .duration
@@ -155,12 +296,13 @@ pub fn set_metadata_with_context(
     env.call_static_method::<&JClass, _, _>(
         &helper_class,
         "setMetadata",
-        "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;J)V",
+        "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;[BJ)V",
         &[
             JValue::Object(&title),
             JValue::Object(&artist),
             JValue::Object(&album),
             JValue::Object(&artwork_url),
+            JValue::Object(&artwork_bytes),
             JValue::Long(duration_ms),
         ],
     )
@@ -201,10 +343,30 @@ pub fn set_playback_state_with_context(
 
 /// Request audio focus.
 pub fn request_audio_focus_with_context(env: &mut JNIEnv) -> Result<(), MediaError> {
+    request_audio_focus_of_type_with_context(env, AudioFocusType::Gain)
+}
+
+/// Request a specific kind of audio focus.
+pub fn request_audio_focus_of_type_with_context(
+    env: &mut JNIEnv,
+    focus_type: AudioFocusType,
+) -> Result<(), MediaError> {
     let helper_class = get_helper_class(env)?;
 
+    // AudioManager.AUDIOFOCUS_GAIN* values
+    let focus_gain = match focus_type {
+        AudioFocusType::Gain => 1,
+        AudioFocusType::Transient => 2,
+        AudioFocusType::TransientMayDuck => 3,
+        AudioFocusType::TransientExclusive => 4,
+    };
     let result = env
-        .call_static_method::<&JClass, _, _>(&helper_class, "requestAudioFocus", "()Z", &[])
+        .call_static_method::<&JClass, _, _>(
+            &helper_class,
+            "requestAudioFocus",
+            "(I)Z",
+            &[JValue::Int(focus_gain)],
+        )
         .map_err(|e| MediaError::Unknown(format!("requestAudioFocus: {e}")))?
         .z()
         .map_err(|e| MediaError::Unknown(format!("requestAudioFocus result: {e}")))?;
@@ -277,6 +439,12 @@ impl MediaCenterInner {
         ))
     }
 
+    pub fn request_audio_focus_with(&self, _focus_type: AudioFocusType) -> Result<(), MediaError> {
+        Err(MediaError::InitializationFailed(
+            "Android: use request_audio_focus_of_type_with_context()".into(),
+        ))
+    }
+
     pub fn abandon_audio_focus(&self) -> Result<(), MediaError> {
         Err(MediaError::InitializationFailed(
             "Android: use abandon_audio_focus_with_context()".into(),
@@ -295,8 +463,9 @@ impl MediaCenterInner {
 
     pub fn run_loop(&self, _duration: std::time::Duration) {}
 
+    /// Audio focus changes reported since the last poll.
     pub fn poll_command(&self) -> Option<MediaCommand> {
-        None
+        COMMAND_QUEUE.lock().ok()?.pop_front()
     }
 }
 
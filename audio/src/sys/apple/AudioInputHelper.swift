@@ -0,0 +1,81 @@
+import Foundation
+import AVFoundation
+
+// MARK: - Audio Input Routing
+//
+// On iOS, cpal only exposes the session's current input, so device
+// selection goes through AVAudioSession's preferred input instead.
+// On macOS, cpal enumerates Core Audio devices directly and these are unused.
+
+#if os(iOS)
+private func availableInputs() -> [AVAudioSessionPortDescription] {
+    let session = AVAudioSession.sharedInstance()
+    do {
+        try session.setCategory(.playAndRecord, mode: .default, options: [.allowBluetooth])
+    } catch {
+        print("waterkit-audio: Failed to set record category: \(error)")
+    }
+    return session.availableInputs ?? []
+}
+#endif
+
+// MARK: - FFI Functions
+
+func audio_input_count() -> Int32 {
+    #if os(iOS)
+    return Int32(availableInputs().count)
+    #else
+    return 0
+    #endif
+}
+
+func audio_input_id(index: Int32) -> RustString {
+    #if os(iOS)
+    let inputs = availableInputs()
+    guard index >= 0, Int(index) < inputs.count else { return "".intoRustString() }
+    return inputs[Int(index)].uid.intoRustString()
+    #else
+    return "".intoRustString()
+    #endif
+}
+
+func audio_input_name(index: Int32) -> RustString {
+    #if os(iOS)
+    let inputs = availableInputs()
+    guard index >= 0, Int(index) < inputs.count else { return "".intoRustString() }
+    return inputs[Int(index)].portName.intoRustString()
+    #else
+    return "".intoRustString()
+    #endif
+}
+
+/// Route recording through the input with the given port UID.
+func audio_input_select(id: RustString) -> Bool {
+    #if os(iOS)
+    let uid = id.toString()
+    guard let port = availableInputs().first(where: { $0.uid == uid }) else {
+        return false
+    }
+    do {
+        let session = AVAudioSession.sharedInstance()
+        try session.setPreferredInput(port)
+        try session.setActive(true)
+        return true
+    } catch {
+        print("waterkit-audio: Failed to select input \(uid): \(error)")
+        return false
+    }
+    #else
+    return false
+    #endif
+}
+
+/// Check whether the input with the given port UID is part of the current route.
+func audio_input_is_routed(id: RustString) -> Bool {
+    #if os(iOS)
+    let uid = id.toString()
+    return AVAudioSession.sharedInstance().currentRoute.inputs.contains { $0.uid == uid }
+    #else
+    return false
+    #endif
+}
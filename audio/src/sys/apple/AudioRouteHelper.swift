@@ -0,0 +1,140 @@
+import Foundation
+import AVFoundation
+
+// MARK: - Audio Route Changes
+//
+// Forwards AVAudioSession route changes to Rust on iOS.
+// macOS has no AVAudioSession; output devices are polled through cpal instead.
+
+// Reasons passed to rust_on_route_change
+private let routeOldDeviceUnavailable: UInt8 = 0
+private let routeNewDeviceAvailable: UInt8 = 1
+private let routeCategoryChange: UInt8 = 2
+
+#if os(iOS)
+private var routeObserver: NSObjectProtocol?
+
+private func outputName(_ route: AVAudioSessionRouteDescription?) -> String {
+    route?.outputs.first?.portName ?? ""
+}
+#endif
+
+func audio_route_start() {
+    #if os(iOS)
+    guard routeObserver == nil else { return }
+    routeObserver = NotificationCenter.default.addObserver(
+        forName: AVAudioSession.routeChangeNotification,
+        object: nil,
+        queue: nil
+    ) { notification in
+        guard let rawReason = notification.userInfo?[AVAudioSessionRouteChangeReasonKey] as? UInt,
+              let reason = AVAudioSession.RouteChangeReason(rawValue: rawReason) else {
+            return
+        }
+
+        switch reason {
+        case .oldDeviceUnavailable:
+            let previous = notification.userInfo?[AVAudioSessionRouteChangePreviousRouteKey] as? AVAudioSessionRouteDescription
+            rust_on_route_change(routeOldDeviceUnavailable, outputName(previous))
+        case .newDeviceAvailable:
+            rust_on_route_change(routeNewDeviceAvailable, outputName(AVAudioSession.sharedInstance().currentRoute))
+        case .categoryChange:
+            rust_on_route_change(routeCategoryChange, "")
+        default:
+            break
+        }
+    }
+    #endif
+}
+
+// MARK: - Audio Output Selection
+//
+// iOS doesn't let apps pick an arbitrary output: the route can only be
+// overridden to the built-in speaker. The outputs listed are the current
+// route's plus the speaker, which is always listed under `speakerID`.
+
+#if os(iOS)
+private let speakerID = "BuiltInSpeaker"
+
+private func outputID(_ port: AVAudioSessionPortDescription) -> String {
+    port.portType == .builtInSpeaker ? speakerID : port.uid
+}
+
+private func availableOutputs() -> [(id: String, name: String)] {
+    var outputs = AVAudioSession.sharedInstance().currentRoute.outputs.map {
+        (id: outputID($0), name: $0.portName)
+    }
+    if !outputs.contains(where: { $0.id == speakerID }) {
+        outputs.append((id: speakerID, name: "Speaker"))
+    }
+    return outputs
+}
+
+private func isRouted(_ id: String) -> Bool {
+    AVAudioSession.sharedInstance().currentRoute.outputs.contains { outputID($0) == id }
+}
+#endif
+
+func audio_output_count() -> Int32 {
+    #if os(iOS)
+    return Int32(availableOutputs().count)
+    #else
+    return 0
+    #endif
+}
+
+func audio_output_id(index: Int32) -> RustString {
+    #if os(iOS)
+    let outputs = availableOutputs()
+    guard index >= 0, Int(index) < outputs.count else { return "".intoRustString() }
+    return outputs[Int(index)].id.intoRustString()
+    #else
+    return "".intoRustString()
+    #endif
+}
+
+func audio_output_name(index: Int32) -> RustString {
+    #if os(iOS)
+    let outputs = availableOutputs()
+    guard index >= 0, Int(index) < outputs.count else { return "".intoRustString() }
+    return outputs[Int(index)].name.intoRustString()
+    #else
+    return "".intoRustString()
+    #endif
+}
+
+/// Route playback to the output with the given id, or to the system's
+/// route for an empty id.
+func audio_output_select(id: RustString) -> Bool {
+    #if os(iOS)
+    let id = id.toString()
+    let session = AVAudioSession.sharedInstance()
+    do {
+        if id == speakerID {
+            // Overrides only apply to the play-and-record category
+            if session.category != .playAndRecord {
+                try session.setCategory(.playAndRecord, mode: .default, options: [.allowBluetoothA2DP])
+            }
+            try session.overrideOutputAudioPort(.speaker)
+        } else {
+            try session.overrideOutputAudioPort(.none)
+        }
+        try session.setActive(true)
+    } catch {
+        print("waterkit-audio: Failed to select output \(id): \(error)")
+        return false
+    }
+    return id.isEmpty || isRouted(id)
+    #else
+    return false
+    #endif
+}
+
+/// Whether the output with the given id is part of the current route.
+func audio_output_is_routed(id: RustString) -> Bool {
+    #if os(iOS)
+    return isRouted(id.toString())
+    #else
+    return false
+    #endif
+}
@@ -126,6 +126,23 @@ func media_session_set_metadata(metadata: MediaMetadataFFI) -> MediaResultFFI {
     return .Success
 }
 
+func media_session_set_artwork(data: RustVec<UInt8>) -> MediaResultFFI {
+    let bytes = Data(bytes: data.as_ptr(), count: Int(data.len()))
+    guard let image = PlatformImage(data: bytes) else {
+        return .UpdateFailed
+    }
+    let scaled = downscaleArtwork(image, maxDimension: 1024)
+
+    var info = MPNowPlayingInfoCenter.default().nowPlayingInfo ?? [:]
+    #if os(iOS)
+    info[MPMediaItemPropertyArtwork] = MPMediaItemArtwork(boundsSize: scaled.size) { _ in scaled }
+    #else
+    info[MPMediaItemPropertyArtwork] = MPMediaItemArtwork(boundsSize: NSSize(width: scaled.size.width, height: scaled.size.height)) { _ in scaled }
+    #endif
+    MPNowPlayingInfoCenter.default().nowPlayingInfo = info
+    return .Success
+}
+
 func media_session_set_playback_state(state: PlaybackStateFFI) -> MediaResultFFI {
     var nowPlayingInfo = MPNowPlayingInfoCenter.default().nowPlayingInfo ?? [:]
     
@@ -222,6 +239,44 @@ func media_session_register_command_handler() {
         }
         return .success
     }
+
+    commandCenter.changePlaybackRateCommand.isEnabled = true
+    commandCenter.changePlaybackRateCommand.supportedPlaybackRates = [0.5, 0.75, 1.0, 1.25, 1.5, 2.0]
+    commandCenter.changePlaybackRateCommand.addTarget { event in
+        if let rateEvent = event as? MPChangePlaybackRateCommandEvent {
+            rust_on_set_rate(Double(rateEvent.playbackRate))
+        }
+        return .success
+    }
+
+    #if os(iOS)
+    // Phone calls, alarms and Siri interrupt the audio session
+    NotificationCenter.default.addObserver(
+        forName: AVAudioSession.interruptionNotification,
+        object: nil,
+        queue: nil
+    ) { notification in
+        guard let rawType = notification.userInfo?[AVAudioSessionInterruptionTypeKey] as? UInt,
+              let type = AVAudioSession.InterruptionType(rawValue: rawType) else {
+            return
+        }
+
+        switch type {
+        case .began:
+            rust_on_interruption_began()
+        case .ended:
+            let rawOptions = notification.userInfo?[AVAudioSessionInterruptionOptionKey] as? UInt ?? 0
+            let shouldResume = AVAudioSession.InterruptionOptions(rawValue: rawOptions).contains(.shouldResume)
+            if shouldResume {
+                // The session is deactivated by the interruption
+                try? AVAudioSession.sharedInstance().setActive(true)
+            }
+            rust_on_interruption_ended(shouldResume)
+        @unknown default:
+            break
+        }
+    }
+    #endif
 }
 
 func media_session_request_audio_focus() -> MediaResultFFI {
@@ -276,6 +331,26 @@ import AppKit
 typealias PlatformImage = NSImage
 #endif
 
+/// Shrink `image` so its longest side is at most `maxDimension` points.
+private func downscaleArtwork(_ image: PlatformImage, maxDimension: CGFloat) -> PlatformImage {
+    let longest = max(image.size.width, image.size.height)
+    guard longest > maxDimension else { return image }
+    let scale = maxDimension / longest
+    let size = CGSize(width: (image.size.width * scale).rounded(), height: (image.size.height * scale).rounded())
+
+    #if os(iOS)
+    return UIGraphicsImageRenderer(size: size).image { _ in
+        image.draw(in: CGRect(origin: .zero, size: size))
+    }
+    #else
+    let scaled = NSImage(size: size)
+    scaled.lockFocus()
+    image.draw(in: NSRect(origin: .zero, size: size), from: .zero, operation: .copy, fraction: 1.0)
+    scaled.unlockFocus()
+    return scaled
+    #endif
+}
+
 private func loadArtwork(from url: URL, completion: @escaping (PlatformImage?) -> Void) {
     URLSession.shared.dataTask(with: url) { data, _, _ in
         guard let data = data else {
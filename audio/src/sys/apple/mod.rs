@@ -1,5 +1,7 @@
 //! Apple platform (iOS/macOS) media control implementation using swift-bridge.
 
+#[cfg(target_os = "ios")]
+use crate::{AudioDevice, PlayerError, RecordError};
 use crate::{MediaError, MediaMetadata, PlaybackState, PlaybackStatus};
 use std::sync::RwLock;
 
@@ -46,6 +48,7 @@ mod ffi {
         // Media session functions
         fn media_session_init() -> MediaResultFFI;
         fn media_session_set_metadata(metadata: MediaMetadataFFI) -> MediaResultFFI;
+        fn media_session_set_artwork(data: Vec<u8>) -> MediaResultFFI;
         fn media_session_set_playback_state(state: PlaybackStateFFI) -> MediaResultFFI;
         fn media_session_request_audio_focus() -> MediaResultFFI;
         fn media_session_abandon_audio_focus() -> MediaResultFFI;
@@ -63,6 +66,23 @@ mod ffi {
         fn audio_player_seek(position_secs: f64) -> PlayerResultFFI;
         fn audio_player_set_volume(volume: f32) -> PlayerResultFFI;
         fn audio_player_get_state() -> PlayerStateFFI;
+
+        // Audio input routing (iOS)
+        fn audio_input_count() -> i32;
+        fn audio_input_id(index: i32) -> String;
+        fn audio_input_name(index: i32) -> String;
+        fn audio_input_select(id: String) -> bool;
+        fn audio_input_is_routed(id: String) -> bool;
+
+        // Audio output routing (iOS)
+        fn audio_output_count() -> i32;
+        fn audio_output_id(index: i32) -> String;
+        fn audio_output_name(index: i32) -> String;
+        fn audio_output_select(id: String) -> bool;
+        fn audio_output_is_routed(id: String) -> bool;
+
+        // Route changes (iOS)
+        fn audio_route_start();
     }
 
     extern "Rust" {
@@ -75,6 +95,11 @@ mod ffi {
         fn rust_on_seek_to(position_secs: f64);
         fn rust_on_seek_forward(secs: f64);
         fn rust_on_seek_backward(secs: f64);
+        fn rust_on_set_rate(rate: f64);
+        fn rust_on_interruption_began();
+        fn rust_on_interruption_ended(should_resume: bool);
+        // reason 0: old device unavailable, 1: new device available, 2: category change
+        fn rust_on_route_change(reason: u8, device: String);
     }
 }
 
@@ -129,6 +154,58 @@ fn rust_on_seek_backward(secs: f64) {
     ));
 }
 
+fn rust_on_set_rate(rate: f64) {
+    if rate.is_nan() || rate <= 0.0 {
+        return;
+    }
+    dispatch_command(crate::MediaCommand::SetRate(crate::player::clamp_rate(
+        rate,
+    )));
+}
+
+fn rust_on_interruption_began() {
+    dispatch_command(crate::MediaCommand::InterruptionBegan);
+}
+
+fn rust_on_interruption_ended(should_resume: bool) {
+    dispatch_command(crate::MediaCommand::InterruptionEnded { should_resume });
+}
+
+fn rust_on_route_change(reason: u8, device: String) {
+    crate::route::dispatch_native(reason, device);
+}
+
+/// Start forwarding `AVAudioSession` route changes.
+#[cfg(target_os = "ios")]
+pub(crate) fn start_route_monitor() {
+    static STARTED: std::sync::Once = std::sync::Once::new();
+    STARTED.call_once(ffi::audio_route_start);
+}
+
+/// List the inputs available to the shared `AVAudioSession`.
+#[cfg(target_os = "ios")]
+pub fn list_inputs() -> Vec<AudioDevice> {
+    (0..ffi::audio_input_count())
+        .map(|i| AudioDevice::new(ffi::audio_input_id(i), ffi::audio_input_name(i)))
+        .collect()
+}
+
+/// Set the session's preferred input so recording is routed through it.
+#[cfg(target_os = "ios")]
+pub fn select_input(id: &str) -> Result<(), RecordError> {
+    if ffi::audio_input_select(id.to_string()) {
+        Ok(())
+    } else {
+        Err(RecordError::DeviceNotFound(id.to_string()))
+    }
+}
+
+/// Check whether the given input is still part of the current audio route.
+#[cfg(target_os = "ios")]
+pub fn is_input_routed(id: &str) -> bool {
+    ffi::audio_input_is_routed(id.to_string())
+}
+
 fn convert_result(result: ffi::MediaResultFFI) -> Result<(), MediaError> {
     match result {
         ffi::MediaResultFFI::Success => Ok(()),
@@ -145,6 +222,30 @@ fn convert_result(result: ffi::MediaResultFFI) -> Result<(), MediaError> {
 #[derive(Debug)]
 pub struct MediaSessionInner;
 
+/// Publish `metadata` to the Now Playing center.
+///
+/// Artwork bytes win over the artwork URL, so the URL isn't loaded when both are set.
+fn set_metadata(metadata: &MediaMetadata) -> Result<(), MediaError> {
+    let artwork_url = if metadata.artwork_bytes.is_some() {
+        String::new()
+    } else {
+        metadata.artwork_url.clone().unwrap_or_default()
+    };
+    let ffi_metadata = ffi::MediaMetadataFFI {
+        title: metadata.title.clone().unwrap_or_default(),
+        artist: metadata.artist.clone().unwrap_or_default(),
+        album: metadata.album.clone().unwrap_or_default(),
+        artwork_url,
+        duration_secs: metadata.duration.map_or(-1.0, |d| d.as_secs_f64()),
+    };
+    convert_result(ffi::media_session_set_metadata(ffi_metadata))?;
+
+    if let Some(ref bytes) = metadata.artwork_bytes {
+        convert_result(ffi::media_session_set_artwork(bytes.clone()))?;
+    }
+    Ok(())
+}
+
 impl MediaSessionInner {
     pub fn new() -> Result<Self, MediaError> {
         convert_result(ffi::media_session_init())?;
@@ -153,14 +254,7 @@ impl MediaSessionInner {
 
     #[allow(clippy::unused_self)]
     pub fn set_metadata(&self, metadata: &MediaMetadata) -> Result<(), MediaError> {
-        let ffi_metadata = ffi::MediaMetadataFFI {
-            title: metadata.title.clone().unwrap_or_default(),
-            artist: metadata.artist.clone().unwrap_or_default(),
-            album: metadata.album.clone().unwrap_or_default(),
-            artwork_url: metadata.artwork_url.clone().unwrap_or_default(),
-            duration_secs: metadata.duration.map_or(-1.0, |d| d.as_secs_f64()),
-        };
-        convert_result(ffi::media_session_set_metadata(ffi_metadata))
+        set_metadata(metadata)
     }
 
     #[allow(clippy::unused_self)]
@@ -213,14 +307,7 @@ impl MediaCenterInner {
 
     #[allow(clippy::unused_self)]
     pub fn update(&self, metadata: &MediaMetadata, state: &PlaybackState) {
-        let ffi_metadata = ffi::MediaMetadataFFI {
-            title: metadata.title.clone().unwrap_or_default(),
-            artist: metadata.artist.clone().unwrap_or_default(),
-            album: metadata.album.clone().unwrap_or_default(),
-            artwork_url: metadata.artwork_url.clone().unwrap_or_default(),
-            duration_secs: metadata.duration.map_or(-1.0, |d| d.as_secs_f64()),
-        };
-        let _ = ffi::media_session_set_metadata(ffi_metadata);
+        let _ = set_metadata(metadata);
 
         let ffi_state = ffi::PlaybackStateFFI {
             status: match state.status {
@@ -257,3 +344,28 @@ impl MediaCenterInner {
         })
     }
 }
+
+/// List the outputs the shared `AVAudioSession` can play to.
+#[cfg(target_os = "ios")]
+pub fn list_outputs() -> Vec<AudioDevice> {
+    (0..ffi::audio_output_count())
+        .map(|i| AudioDevice::new(ffi::audio_output_id(i), ffi::audio_output_name(i)))
+        .collect()
+}
+
+/// Route playback to the given output, or back to the system's route for `None`.
+#[cfg(target_os = "ios")]
+pub fn select_output(id: Option<&str>) -> Result<(), PlayerError> {
+    let id = id.unwrap_or_default();
+    if ffi::audio_output_select(id.to_string()) {
+        Ok(())
+    } else {
+        Err(PlayerError::DeviceNotFound(id.to_string()))
+    }
+}
+
+/// Check whether the given output is still part of the current audio route.
+#[cfg(target_os = "ios")]
+pub fn is_output_routed(id: &str) -> bool {
+    ffi::audio_output_is_routed(id.to_string())
+}
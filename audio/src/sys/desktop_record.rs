@@ -1,29 +1,90 @@
 //! Desktop audio recording using cpal.
 //!
-//! Works on macOS, Windows, and Linux.
+//! Works on macOS, Windows, and Linux. Also used on iOS, where inputs are
+//! selected through `AVAudioSession`, and on Android, where cpal records
+//! through Oboe.
 
-use crate::recorder::{AudioBuffer, AudioFormat, InputDevice, RecordError};
+use crate::AudioDevice;
+use crate::convert::Converter;
+use crate::recorder::{AudioBuffer, AudioFormat, RecordError, RecorderEvent};
+use crate::vad::{VadSettings, VoiceDetector};
+use cpal::Sample;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::sync::{
-    Arc,
+    Arc, Mutex,
     atomic::{AtomicBool, Ordering},
 };
 
 /// Desktop audio recorder using cpal.
 pub struct AudioRecorderInner {
     device: cpal::Device,
+    // Explicitly selected device, if any. Its loss is reported rather than
+    // letting the platform reroute to the default input.
+    selected_id: Option<String>,
     format: AudioFormat,
+    // Native configuration the device records in, converted to `format`
+    config: cpal::StreamConfig,
+    native_format: cpal::SampleFormat,
     stream: Option<cpal::Stream>,
     // Channel for streaming audio data
     sender: Option<async_channel::Sender<AudioBuffer>>,
     receiver: async_channel::Receiver<AudioBuffer>,
+    // Additional consumers that receive a copy of every buffer (e.g. file output)
+    taps: Vec<async_channel::Sender<AudioBuffer>>,
+    // Samples per emitted buffer, or one buffer per device callback if unset
+    chunk_len: Option<usize>,
+    // Bounded senders for `stream()`, handed to the capture callback
+    subscribe: async_channel::Sender<async_channel::Sender<AudioBuffer>>,
+    subscriptions: async_channel::Receiver<async_channel::Sender<AudioBuffer>>,
+    // Voice activity detection, and whether silence is dropped
+    vad: Option<VadSettings>,
+    skip_silence: bool,
+    // Bounded senders for `events()`, handed to the capture callback
+    subscribe_events: async_channel::Sender<async_channel::Sender<RecorderEvent>>,
+    event_subscriptions: async_channel::Receiver<async_channel::Sender<RecorderEvent>>,
     recording: Arc<AtomicBool>,
+    // Why recording failed, if it did; reads return it from then on
+    failure: Arc<Mutex<Option<RecordError>>>,
+}
+
+/// Fails a recording from the capture callback or the WAV writer.
+#[derive(Clone)]
+struct Failure {
+    error: Arc<Mutex<Option<RecordError>>>,
+    recording: Arc<AtomicBool>,
+    sender: Option<async_channel::Sender<AudioBuffer>>,
+}
+
+impl Failure {
+    /// Stop capture and make reads return `error`.
+    fn report(&self, error: RecordError) {
+        // Later failures follow from the first
+        if let Ok(mut slot) = self.error.lock() {
+            slot.get_or_insert(error);
+        }
+        self.recording.store(false, Ordering::Relaxed);
+        // Wake pending readers so they observe the failure
+        if let Some(sender) = &self.sender {
+            sender.close();
+        }
+    }
 }
 
 impl AudioRecorderInner {
     /// List available input devices.
+    ///
+    /// iOS only exposes the session's current input through cpal, so the
+    /// `AVAudioSession` inputs are listed instead.
+    #[cfg(target_os = "ios")]
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn list_devices() -> Result<Vec<AudioDevice>, RecordError> {
+        Ok(super::apple::list_inputs())
+    }
+
+    /// List available input devices.
+    #[cfg(not(target_os = "ios"))]
     #[allow(deprecated)]
-    pub fn list_devices() -> Result<Vec<InputDevice>, RecordError> {
+    pub fn list_devices() -> Result<Vec<AudioDevice>, RecordError> {
         let host = cpal::default_host();
         let devices = host
             .input_devices()
@@ -32,21 +93,36 @@ impl AudioRecorderInner {
         let mut result = Vec::new();
         for device in devices {
             if let Ok(name) = device.name() {
-                result.push(InputDevice {
-                    id: name.clone(),
-                    name,
-                });
+                result.push(AudioDevice::new(name.clone(), name));
             }
         }
         Ok(result)
     }
 
-    /// Create a new audio recorder.
-    #[allow(deprecated)]
-    pub fn new(device_id: Option<String>, format: AudioFormat) -> Result<Self, RecordError> {
-        let host = cpal::default_host();
+    /// Resolve the cpal device for the requested input.
+    ///
+    /// On iOS the input is selected as the session's preferred input, which
+    /// cpal then records from as the default device.
+    #[cfg(target_os = "ios")]
+    fn open_device(
+        host: &cpal::Host,
+        device_id: Option<&str>,
+    ) -> Result<cpal::Device, RecordError> {
+        if let Some(id) = device_id {
+            super::apple::select_input(id)?;
+        }
+        host.default_input_device()
+            .ok_or_else(|| RecordError::DeviceNotFound("no default device".into()))
+    }
 
-        let device = if let Some(id) = device_id {
+    /// Resolve the cpal device for the requested input.
+    #[cfg(not(target_os = "ios"))]
+    #[allow(deprecated)]
+    fn open_device(
+        host: &cpal::Host,
+        device_id: Option<&str>,
+    ) -> Result<cpal::Device, RecordError> {
+        if let Some(id) = device_id {
             let devices = host
                 .input_devices()
                 .map_err(|e| RecordError::EnumerationFailed(e.to_string()))?;
@@ -54,37 +130,177 @@ impl AudioRecorderInner {
             devices
                 .into_iter()
                 .find(|d| d.name().map(|n| n == id).unwrap_or(false))
-                .ok_or(RecordError::DeviceNotFound(id))?
+                .ok_or_else(|| RecordError::DeviceNotFound(id.to_string()))
         } else {
             host.default_input_device()
-                .ok_or_else(|| RecordError::DeviceNotFound("no default device".into()))?
-        };
+                .ok_or_else(|| RecordError::DeviceNotFound("no default device".into()))
+        }
+    }
+
+    /// Pick the device's input configuration closest to `format`.
+    ///
+    /// The nearest sample rate wins, then a channel count of at least the
+    /// requested one (the fewest such), then float over integer samples.
+    fn negotiate(
+        device: &cpal::Device,
+        format: AudioFormat,
+    ) -> Result<cpal::SupportedStreamConfig, RecordError> {
+        let configs = device
+            .supported_input_configs()
+            .map_err(|e| RecordError::OpenFailed(e.to_string()))?;
+
+        configs
+            .filter(|range| range.channels() > 0 && is_convertible(range.sample_format()))
+            .map(|range| {
+                let rate = format
+                    .sample_rate
+                    .clamp(range.min_sample_rate().0, range.max_sample_rate().0);
+                let score = (
+                    rate.abs_diff(format.sample_rate),
+                    range.channels() < format.channels,
+                    range.channels().abs_diff(format.channels),
+                    range.sample_format() != cpal::SampleFormat::F32,
+                );
+                (score, range.with_sample_rate(cpal::SampleRate(rate)))
+            })
+            .min_by_key(|(score, _)| *score)
+            .map(|(_, config)| config)
+            .ok_or_else(|| {
+                RecordError::UnsupportedFormat("device has no usable input configuration".into())
+            })
+    }
+
+    /// Create a new audio recorder.
+    ///
+    /// The device's closest native configuration is chosen here, so formats
+    /// it can't be converted from fail before recording starts. With
+    /// `frames_per_buffer` set, captured audio is regrouped into buffers
+    /// of exactly that many frames.
+    pub fn new(
+        device_id: Option<String>,
+        format: AudioFormat,
+        frames_per_buffer: Option<u32>,
+    ) -> Result<Self, RecordError> {
+        let host = cpal::default_host();
+        let device = Self::open_device(&host, device_id.as_deref())?;
+        let native = Self::negotiate(&device, format)?;
 
         // Create unbound channel for audio data
         let (sender, receiver) = async_channel::unbounded();
+        let (subscribe, subscriptions) = async_channel::unbounded();
+        let (subscribe_events, event_subscriptions) = async_channel::unbounded();
 
         Ok(Self {
             device,
+            selected_id: device_id,
             format,
+            config: native.config(),
+            native_format: native.sample_format(),
             stream: None,
             sender: Some(sender),
             receiver,
+            taps: Vec::new(),
+            chunk_len: frames_per_buffer
+                .map(|frames| frames.max(1) as usize * usize::from(format.channels.max(1))),
+            subscribe,
+            subscriptions,
+            vad: None,
+            skip_silence: false,
+            subscribe_events,
+            event_subscriptions,
             recording: Arc::new(AtomicBool::new(false)),
+            failure: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// Name of the recorded input in errors.
+    fn device_id(&self) -> String {
+        self.selected_id
+            .clone()
+            .unwrap_or_else(|| "default input".into())
+    }
+
+    fn failure_handle(&self) -> Failure {
+        Failure {
+            error: Arc::clone(&self.failure),
+            recording: Arc::clone(&self.recording),
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// A callback that fails the recording: capture stops, and pending and
+    /// later reads return the error it is called with.
+    pub fn failure_reporter(&self) -> impl Fn(RecordError) + Send + 'static {
+        let failure = self.failure_handle();
+        move |error| failure.report(error)
+    }
+
+    /// Fail if recording failed or the selected input is no longer available.
+    fn check_failure(&self) -> Result<(), RecordError> {
+        #[cfg(target_os = "ios")]
+        if let Some(id) = &self.selected_id
+            && !super::apple::is_input_routed(id)
+        {
+            self.failure_handle()
+                .report(RecordError::DeviceDisconnected(id.clone()));
+        }
+
+        match self.failure.lock() {
+            Ok(error) => error.clone().map_or(Ok(()), Err),
+            Err(e) => Err(RecordError::Unknown(e.to_string())),
+        }
+    }
+
+    /// Send a copy of every recorded buffer to `sender`.
+    ///
+    /// Must be called before [`Self::start`].
+    pub fn add_tap(&mut self, sender: async_channel::Sender<AudioBuffer>) {
+        self.taps.push(sender);
+    }
+
+    /// Subscribe a bounded stream to recorded buffers.
+    ///
+    /// The stream receives buffers until [`Self::stop`]; if it falls more than
+    /// `capacity` buffers behind, the oldest are dropped.
+    pub fn subscribe(&self, capacity: usize) -> async_channel::Receiver<AudioBuffer> {
+        let (sender, receiver) = async_channel::bounded(capacity);
+        // Picked up by the capture callback on its next invocation
+        let _ = self.subscribe.try_send(sender);
+        receiver
+    }
+
+    /// Detect voice activity, dropping silence if `skip_silence` is set.
+    ///
+    /// Must be called before [`Self::start`].
+    pub const fn set_vad(&mut self, settings: VadSettings, skip_silence: bool) {
+        self.vad = Some(settings);
+        self.skip_silence = skip_silence;
+    }
+
+    /// Subscribe a bounded stream to voice activity events.
+    ///
+    /// Like [`Self::subscribe`], the oldest events are dropped past `capacity`.
+    pub fn subscribe_events(&self, capacity: usize) -> async_channel::Receiver<RecorderEvent> {
+        let (sender, receiver) = async_channel::bounded(capacity);
+        // Picked up by the capture callback on its next invocation
+        let _ = self.subscribe_events.try_send(sender);
+        receiver
+    }
+
+    /// Map a closed channel to the failure that caused it, if any.
+    fn recv_error(&self, err: &async_channel::RecvError) -> RecordError {
+        self.check_failure()
+            .err()
+            .unwrap_or_else(|| RecordError::ReadFailed(err.to_string()))
+    }
+
     /// Start recording.
     #[allow(clippy::future_not_send, clippy::unused_async)]
     pub async fn start(&mut self) -> Result<(), RecordError> {
         if self.stream.is_some() {
             return Ok(()); // Already recording
         }
-
-        let config = cpal::StreamConfig {
-            channels: self.format.channels,
-            sample_rate: cpal::SampleRate(self.format.sample_rate),
-            buffer_size: cpal::BufferSize::Default,
-        };
+        self.check_failure()?;
 
         let recording = Arc::clone(&self.recording);
 
@@ -97,22 +313,60 @@ impl AudioRecorderInner {
             ));
         };
 
-        let format = self.format;
+        let failure = self.failure_handle();
+        let device_id = self.device_id();
+        let mut dispatch = Dispatch {
+            format: self.format,
+            chunk_len: self.chunk_len,
+            converter: Converter::new(
+                self.config.sample_rate.0,
+                self.config.channels,
+                self.format.sample_rate,
+                self.format.channels,
+            ),
+            converted: Vec::new(),
+            pending: Vec::new(),
+            sender,
+            taps: self.taps.clone(),
+            streams: Vec::new(),
+            subscriptions: self.subscriptions.clone(),
+            vad: self.vad.map(|settings| Vad {
+                detector: VoiceDetector::new(settings, self.format.channels),
+                window_len: VoiceDetector::window_len(
+                    self.format.sample_rate,
+                    self.format.channels,
+                ),
+                window: Vec::new(),
+                skip_silence: self.skip_silence,
+                events: Vec::new(),
+                subscriptions: self.event_subscriptions.clone(),
+            }),
+        };
 
         let stream = self
             .device
-            .build_input_stream(
-                &config,
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    if recording.load(Ordering::Relaxed) {
-                        let samples = data.to_vec();
-                        let buffer = AudioBuffer::new(samples, format);
-                        // Ignore errors if receiver is dropped
-                        let _ = sender.try_send(buffer);
+            .build_input_stream_raw(
+                &self.config,
+                self.native_format,
+                move |data: &cpal::Data, _: &cpal::InputCallbackInfo| {
+                    if !recording.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    if let Some(samples) = data.as_slice::<f32>() {
+                        dispatch.push(samples);
+                    } else if let Some(samples) = to_f32(data) {
+                        dispatch.push(&samples);
                     }
                 },
-                |err| {
-                    eprintln!("Audio input error: {err}");
+                move |err| {
+                    failure.report(match err {
+                        cpal::StreamError::DeviceNotAvailable => {
+                            RecordError::DeviceDisconnected(device_id.clone())
+                        }
+                        cpal::StreamError::BackendSpecific { err } => {
+                            RecordError::ReadFailed(err.description)
+                        }
+                    });
                 },
                 None,
             )
@@ -133,9 +387,14 @@ impl AudioRecorderInner {
     pub async fn stop(&mut self) -> Result<(), RecordError> {
         self.recording.store(false, Ordering::Relaxed);
 
+        // Dropping the stream drops its callback, which flushes any partial
+        // chunk and closes the subscribed streams
         if let Some(stream) = self.stream.take() {
             drop(stream);
         }
+        // Streams subscribed while idle end as well
+        while self.subscriptions.try_recv().is_ok() {}
+        while self.event_subscriptions.try_recv().is_ok() {}
 
         Ok(())
     }
@@ -143,14 +402,13 @@ impl AudioRecorderInner {
     /// Read audio buffer (async).
     #[allow(clippy::future_not_send)]
     pub async fn read(&self) -> Result<AudioBuffer, RecordError> {
+        // A failure also ends recording, so it is checked first
+        self.check_failure()?;
         if !self.recording.load(Ordering::Relaxed) {
             return Err(RecordError::NotRecording);
         }
 
-        self.receiver
-            .recv()
-            .await
-            .map_err(|e| RecordError::ReadFailed(e.to_string()))
+        self.receiver.recv().await.map_err(|e| self.recv_error(&e))
     }
 
     /// Try to read without waiting.
@@ -164,13 +422,15 @@ impl AudioRecorderInner {
     /// This is more reliable than using `pollster::block_on(read())` as it doesn't
     /// depend on async runtime waker semantics.
     pub fn read_blocking(&self) -> Result<AudioBuffer, RecordError> {
+        // A failure also ends recording, so it is checked first
+        self.check_failure()?;
         if !self.recording.load(Ordering::Relaxed) {
             return Err(RecordError::NotRecording);
         }
 
         self.receiver
             .recv_blocking()
-            .map_err(|e| RecordError::ReadFailed(e.to_string()))
+            .map_err(|e| self.recv_error(&e))
     }
 
     /// Check if recording.
@@ -183,8 +443,190 @@ impl AudioRecorderInner {
         let receiver = self.receiver.clone();
         (self, receiver)
     }
+}
+
+/// Whether [`to_f32`] can read samples of `format`.
+const fn is_convertible(format: cpal::SampleFormat) -> bool {
+    matches!(
+        format,
+        cpal::SampleFormat::I8
+            | cpal::SampleFormat::I16
+            | cpal::SampleFormat::I32
+            | cpal::SampleFormat::U8
+            | cpal::SampleFormat::U16
+            | cpal::SampleFormat::U32
+            | cpal::SampleFormat::F32
+            | cpal::SampleFormat::F64
+    )
+}
+
+/// Convert captured samples of any supported native format to f32.
+fn to_f32(data: &cpal::Data) -> Option<Vec<f32>> {
+    fn convert<T>(data: &cpal::Data) -> Option<Vec<f32>>
+    where
+        T: cpal::SizedSample,
+        f32: cpal::FromSample<T>,
+    {
+        data.as_slice::<T>()
+            .map(|samples| samples.iter().map(|&s| s.to_sample::<f32>()).collect())
+    }
+
+    match data.sample_format() {
+        cpal::SampleFormat::I8 => convert::<i8>(data),
+        cpal::SampleFormat::I16 => convert::<i16>(data),
+        cpal::SampleFormat::I32 => convert::<i32>(data),
+        cpal::SampleFormat::U8 => convert::<u8>(data),
+        cpal::SampleFormat::U16 => convert::<u16>(data),
+        cpal::SampleFormat::U32 => convert::<u32>(data),
+        cpal::SampleFormat::F32 => convert::<f32>(data),
+        cpal::SampleFormat::F64 => convert::<f64>(data),
+        _ => None,
+    }
+}
+
+/// Delivers captured audio from the input callback to every consumer.
+///
+/// Owned by the callback, so it never waits on a lock; consumers only ever
+/// see non-blocking sends.
+struct Dispatch {
+    format: AudioFormat,
+    chunk_len: Option<usize>,
+    /// Converts from the device's native configuration to `format`.
+    converter: Converter,
+    /// Scratch space for converted samples.
+    converted: Vec<f32>,
+    /// Samples waiting to fill the next chunk.
+    pending: Vec<f32>,
+    sender: async_channel::Sender<AudioBuffer>,
+    taps: Vec<async_channel::Sender<AudioBuffer>>,
+    streams: Vec<async_channel::Sender<AudioBuffer>>,
+    subscriptions: async_channel::Receiver<async_channel::Sender<AudioBuffer>>,
+    vad: Option<Vad>,
+}
+
+/// Voice activity detection state of a [`Dispatch`].
+struct Vad {
+    detector: VoiceDetector,
+    /// Samples per analysis window.
+    window_len: usize,
+    /// Samples waiting to fill the next analysis window.
+    window: Vec<f32>,
+    skip_silence: bool,
+    events: Vec<async_channel::Sender<RecorderEvent>>,
+    subscriptions: async_channel::Receiver<async_channel::Sender<RecorderEvent>>,
+}
+
+impl Vad {
+    fn send(&mut self, event: RecorderEvent) {
+        // A lagging stream loses its oldest event rather than stalling capture
+        self.events
+            .retain(|stream| stream.force_send(event).is_ok());
+    }
+}
+
+impl Dispatch {
+    fn push(&mut self, data: &[f32]) {
+        while let Ok(stream) = self.subscriptions.try_recv() {
+            self.streams.push(stream);
+        }
+
+        if self.converter.is_passthrough() {
+            self.push_converted(data);
+        } else {
+            let mut converted = std::mem::take(&mut self.converted);
+            converted.clear();
+            self.converter.process(data, &mut converted);
+            self.push_converted(&converted);
+            self.converted = converted;
+        }
+    }
+
+    /// Run converted samples through voice activity detection, if enabled.
+    fn push_converted(&mut self, mut data: &[f32]) {
+        let Some(mut vad) = self.vad.take() else {
+            self.push_chunks(data);
+            return;
+        };
+        while let Ok(stream) = vad.subscriptions.try_recv() {
+            vad.events.push(stream);
+        }
+
+        // Audio is delivered a window late, once it is known to be speech
+        while !data.is_empty() {
+            let take = (vad.window_len - vad.window.len()).min(data.len());
+            let (head, rest) = data.split_at(take);
+            vad.window.extend_from_slice(head);
+            data = rest;
+            if vad.window.len() < vad.window_len {
+                break;
+            }
+            let event = vad.detector.process(&vad.window);
+            if !vad.skip_silence || vad.detector.is_speaking() {
+                self.push_chunks(&vad.window);
+            }
+            vad.window.clear();
+            if let Some(event) = event {
+                vad.send(event);
+                // Deliver the end of an utterance without waiting for the next
+                if event == RecorderEvent::SpeechEnded && vad.skip_silence {
+                    self.flush();
+                }
+            }
+        }
+        self.vad = Some(vad);
+    }
 
-    pub fn receiver(&self) -> async_channel::Receiver<AudioBuffer> {
-        self.receiver.clone()
+    fn push_chunks(&mut self, mut data: &[f32]) {
+        let Some(chunk_len) = self.chunk_len else {
+            self.emit(data.to_vec());
+            return;
+        };
+        while !data.is_empty() {
+            let take = (chunk_len - self.pending.len()).min(data.len());
+            let (head, rest) = data.split_at(take);
+            self.pending.extend_from_slice(head);
+            data = rest;
+            if self.pending.len() == chunk_len {
+                let samples = std::mem::replace(&mut self.pending, Vec::with_capacity(chunk_len));
+                self.emit(samples);
+            }
+        }
+    }
+
+    fn emit(&mut self, samples: Vec<f32>) {
+        let buffer = AudioBuffer::new(samples, self.format);
+        // Ignore errors if receiver is dropped
+        for tap in &self.taps {
+            let _ = tap.try_send(buffer.clone());
+        }
+        // A lagging stream loses its oldest buffer rather than stalling capture
+        self.streams
+            .retain(|stream| stream.force_send(buffer.clone()).is_ok());
+        let _ = self.sender.try_send(buffer);
+    }
+
+    /// Emit a partial chunk, if any.
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            let samples = std::mem::take(&mut self.pending);
+            self.emit(samples);
+        }
+    }
+}
+
+impl Drop for Dispatch {
+    fn drop(&mut self) {
+        if let Some(mut vad) = self.vad.take() {
+            // A partial window is delivered unless it may be silence
+            if !vad.skip_silence || vad.detector.is_speaking() {
+                let window = std::mem::take(&mut vad.window);
+                self.push_chunks(&window);
+            }
+            if let Some(event) = vad.detector.finish() {
+                vad.send(event);
+            }
+        }
+        // The last buffer of a recording may be shorter than a chunk
+        self.flush();
     }
 }
@@ -0,0 +1,95 @@
+//! Output route monitoring on desktop (and macOS) using cpal.
+//!
+//! Neither cpal nor rodio report device changes, so the output devices are
+//! polled while anyone is watching and compared with the previous poll.
+
+use crate::route::{self, RouteEvent};
+use cpal::traits::{DeviceTrait, HostTrait};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Output devices seen in one poll.
+struct Outputs {
+    default: Option<String>,
+    devices: Vec<String>,
+}
+
+impl Outputs {
+    /// Enumerate the output devices, or `None` if enumeration failed.
+    #[allow(deprecated)]
+    fn poll() -> Option<Self> {
+        let host = cpal::default_host();
+        let devices = host
+            .output_devices()
+            .ok()?
+            .filter_map(|device| device.name().ok())
+            .collect();
+        let default = host
+            .default_output_device()
+            .and_then(|device| device.name().ok());
+        Some(Self { default, devices })
+    }
+
+    /// Events describing the change from `previous` to `self`.
+    fn changes_since(&self, previous: &Self) -> Vec<RouteEvent> {
+        let mut events = Vec::new();
+        if let Some(old) = &previous.default
+            && !self.devices.contains(old)
+        {
+            events.push(RouteEvent::OldDeviceUnavailable {
+                device: old.clone(),
+            });
+        }
+        for device in &self.devices {
+            if !previous.devices.contains(device) {
+                events.push(RouteEvent::NewDeviceAvailable {
+                    device: device.clone(),
+                });
+            }
+        }
+        if let Some(default) = &self.default
+            && self.default != previous.default
+        {
+            events.push(RouteEvent::DefaultDeviceChanged {
+                device: default.clone(),
+            });
+        }
+        events
+    }
+}
+
+/// Start polling output devices, unless already running.
+///
+/// The polling thread exits once the last route stream is dropped.
+pub(crate) fn start_route_monitor() {
+    if RUNNING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    std::thread::spawn(|| {
+        let mut previous = Outputs::poll();
+        while route::has_subscribers() {
+            std::thread::sleep(POLL_INTERVAL);
+            // Skip failed polls rather than reporting every device as gone
+            let Some(current) = Outputs::poll() else {
+                continue;
+            };
+            if let Some(previous) = &previous {
+                for event in current.changes_since(previous) {
+                    route::dispatch(&event);
+                }
+            }
+            previous = Some(current);
+        }
+
+        RUNNING.store(false, Ordering::Release);
+        // A stream may have subscribed after the last check
+        if route::has_subscribers() {
+            start_route_monitor();
+        }
+    });
+}
@@ -5,8 +5,11 @@ use crate::{
 };
 use futures::StreamExt;
 use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use zbus::names::BusName;
 use zbus::zvariant::{ObjectPath, Value};
 use zbus::{Connection, ConnectionBuilder, interface};
 
@@ -19,9 +22,70 @@ static CURRENT_METADATA: RwLock<HashMap<String, Value<'static>>> = RwLock::new(H
 /// Current playback status
 static CURRENT_STATUS: RwLock<PlaybackStatus> = RwLock::new(PlaybackStatus::Stopped);
 
-/// Current position in microseconds
+/// Position in microseconds at the last playback state update
 static CURRENT_POSITION: RwLock<i64> = RwLock::new(0);
 
+/// When `CURRENT_POSITION` was set, if playing since then
+static PLAYING_SINCE: RwLock<Option<Instant>> = RwLock::new(None);
+
+/// Current playback rate
+static CURRENT_RATE: RwLock<f64> = RwLock::new(1.0);
+
+/// Volume last requested through MPRIS
+static CURRENT_VOLUME: RwLock<f64> = RwLock::new(1.0);
+
+/// Object path of the MPRIS interfaces
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+
+/// Position jumps larger than this (in microseconds) are reported as seeks
+const SEEK_TOLERANCE_US: i64 = 500_000;
+
+/// Current position in microseconds, extrapolated from the last update while playing.
+fn current_position() -> i64 {
+    let position = CURRENT_POSITION.read().map(|p| *p).unwrap_or(0);
+    let Some(since) = PLAYING_SINCE.read().ok().and_then(|s| *s) else {
+        return position;
+    };
+    let rate = CURRENT_RATE.read().map(|r| *r).unwrap_or(1.0);
+    position + (since.elapsed().as_micros() as f64 * rate) as i64
+}
+
+/// Artwork file written for the current track
+static CURRENT_ARTWORK: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Write artwork bytes to the cache directory and return a `file://` URL for `mpris:artUrl`.
+///
+/// The file name is derived from the content so clients reload the image when it changes.
+/// The previous artwork file is removed.
+fn cache_artwork(bytes: &[u8]) -> Option<String> {
+    let dir = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?
+        .join("waterkit");
+    std::fs::create_dir_all(&dir).ok()?;
+
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    let extension = if bytes.starts_with(b"\x89PNG") {
+        "png"
+    } else {
+        "jpg"
+    };
+    let path = dir.join(format!("artwork-{:016x}.{extension}", hasher.finish()));
+    if !path.exists() {
+        std::fs::write(&path, bytes).ok()?;
+    }
+
+    if let Ok(mut current) = CURRENT_ARTWORK.write()
+        && let Some(previous) = current.replace(path.clone())
+        && previous != path
+    {
+        let _ = std::fs::remove_file(previous);
+    }
+
+    Some(format!("file://{}", path.display()))
+}
+
 /// MPRIS MediaPlayer2 interface implementation
 struct MediaPlayer2;
 
@@ -94,22 +158,53 @@ impl MprisPlayer {
 
     #[zbus(property)]
     fn position(&self) -> i64 {
-        CURRENT_POSITION.read().map(|p| *p).unwrap_or(0)
+        current_position()
     }
 
     #[zbus(property)]
     fn rate(&self) -> f64 {
-        1.0
+        CURRENT_RATE.read().map(|r| *r).unwrap_or(1.0)
+    }
+
+    /// The player reports the new rate back through `set_playback_state`.
+    #[zbus(property)]
+    fn set_rate(&mut self, rate: f64) {
+        if rate.is_nan() {
+            return;
+        }
+        // MPRIS defines a rate of 0.0 as pausing
+        if rate <= 0.0 {
+            dispatch_command(MediaCommand::Pause);
+        } else {
+            dispatch_command(MediaCommand::SetRate(crate::player::clamp_rate(rate)));
+        }
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        CURRENT_VOLUME.read().map(|v| *v).unwrap_or(1.0)
+    }
+
+    #[zbus(property)]
+    fn set_volume(&mut self, volume: f64) {
+        if volume.is_nan() {
+            return;
+        }
+        let volume = volume.clamp(0.0, 1.0);
+        if let Ok(mut guard) = CURRENT_VOLUME.write() {
+            *guard = volume;
+        }
+        dispatch_command(MediaCommand::SetVolume(volume));
     }
 
     #[zbus(property)]
     fn minimum_rate(&self) -> f64 {
-        1.0
+        f64::from(crate::player::MIN_RATE)
     }
 
     #[zbus(property)]
     fn maximum_rate(&self) -> f64 {
-        1.0
+        f64::from(crate::player::MAX_RATE)
     }
 
     #[zbus(property)]
@@ -245,8 +340,14 @@ impl MediaSessionInner {
             mpris_metadata.insert("xesam:album".to_string(), Value::new(album.clone()));
         }
 
-        if let Some(ref url) = metadata.artwork_url {
-            mpris_metadata.insert("mpris:artUrl".to_string(), Value::new(url.clone()));
+        // Artwork bytes win over the URL
+        let art_url = metadata
+            .artwork_bytes
+            .as_deref()
+            .and_then(cache_artwork)
+            .or_else(|| metadata.artwork_url.clone());
+        if let Some(url) = art_url {
+            mpris_metadata.insert("mpris:artUrl".to_string(), Value::new(url));
         }
 
         if let Some(duration) = metadata.duration {
@@ -264,16 +365,49 @@ impl MediaSessionInner {
     }
 
     pub fn set_playback_state(&self, state: &PlaybackState) -> Result<(), MediaError> {
-        if let Ok(mut guard) = CURRENT_STATUS.write() {
-            *guard = state.status;
+        let expected = current_position();
+        let previous_status = CURRENT_STATUS
+            .write()
+            .map(|mut guard| std::mem::replace(&mut *guard, state.status));
+        let previous_rate = CURRENT_RATE.read().map(|r| *r).unwrap_or(1.0);
+
+        // Without a new position, keep the extrapolated one
+        let position = state
+            .position
+            .map_or(expected, |pos| pos.as_micros() as i64);
+        if let Ok(mut guard) = CURRENT_POSITION.write() {
+            *guard = position;
+        }
+        if let Ok(mut guard) = PLAYING_SINCE.write() {
+            *guard = (state.status == PlaybackStatus::Playing).then(Instant::now);
         }
 
-        if let Some(pos) = state.position {
-            if let Ok(mut guard) = CURRENT_POSITION.write() {
-                *guard = pos.as_micros() as i64;
+        // MPRIS keeps the rate while paused and uses PlaybackStatus instead
+        if state.status == PlaybackStatus::Playing {
+            if let Ok(mut guard) = CURRENT_RATE.write() {
+                *guard = state.rate;
             }
         }
 
+        // Clients extrapolate Position themselves, so only jumps are signalled
+        let seeked = (position - expected).abs() > SEEK_TOLERANCE_US;
+        let status_changed = previous_status.is_ok_and(|status| status != state.status)
+            || (state.status == PlaybackStatus::Playing
+                && (previous_rate - state.rate).abs() > f64::EPSILON);
+
+        if (seeked || status_changed)
+            && let Some(connection) = self.connection.read().ok().and_then(|c| c.clone())
+        {
+            smol::block_on(async {
+                if seeked {
+                    let _ = emit_seeked(&connection, position).await;
+                }
+                if status_changed {
+                    let _ = emit_status_changed(&connection).await;
+                }
+            });
+        }
+
         Ok(())
     }
 
@@ -311,10 +445,41 @@ impl MediaSessionInner {
 async fn start_dbus_service() -> Result<Connection, zbus::Error> {
     let connection = ConnectionBuilder::session()?
         .name("org.mpris.MediaPlayer2.waterkit")?
-        .serve_at("/org/mpris/MediaPlayer2", MediaPlayer2)?
-        .serve_at("/org/mpris/MediaPlayer2", MprisPlayer)?
+        .serve_at(MPRIS_PATH, MediaPlayer2)?
+        .serve_at(MPRIS_PATH, MprisPlayer)?
         .build()
         .await?;
 
     Ok(connection)
 }
+
+/// Emit `org.mpris.MediaPlayer2.Player.Seeked` with the new position in microseconds.
+async fn emit_seeked(connection: &Connection, position: i64) -> Result<(), zbus::Error> {
+    connection
+        .emit_signal(
+            None::<BusName<'_>>,
+            MPRIS_PATH,
+            "org.mpris.MediaPlayer2.Player",
+            "Seeked",
+            &(position,),
+        )
+        .await
+}
+
+/// Emit `PropertiesChanged` for the playback status and rate.
+async fn emit_status_changed(connection: &Connection) -> Result<(), zbus::Error> {
+    let player = MprisPlayer;
+    let changed = HashMap::from([
+        ("PlaybackStatus", Value::new(player.playback_status())),
+        ("Rate", Value::new(player.rate())),
+    ]);
+    connection
+        .emit_signal(
+            None::<BusName<'_>>,
+            MPRIS_PATH,
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+            &("org.mpris.MediaPlayer2.Player", changed, Vec::<&str>::new()),
+        )
+        .await
+}
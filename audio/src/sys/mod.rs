@@ -11,11 +11,27 @@ use std::time::Duration;
 mod desktop_record;
 pub use desktop_record::AudioRecorderInner;
 
+// Route monitoring - polls cpal on desktop, native notifications on mobile
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+mod desktop_route;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub(crate) use desktop_route::start_route_monitor;
+
+#[cfg(target_os = "ios")]
+pub(crate) use apple::start_route_monitor;
+
+// Output selection - cpal devices elsewhere, AVAudioSession routes on iOS
+#[cfg(target_os = "ios")]
+pub(crate) use apple::{is_output_routed, list_outputs, select_output};
+
+#[cfg(target_os = "android")]
+pub(crate) use android::start_route_monitor;
+
 #[cfg(any(target_os = "ios", target_os = "macos"))]
 mod apple;
 
 #[cfg(target_os = "android")]
-mod android;
+pub(crate) mod android;
 
 #[cfg(target_os = "windows")]
 mod windows;
@@ -1,199 +1,418 @@
 //! Windows media control implementation using SystemMediaTransportControls.
+//!
+//! Apps with a `CoreWindow` use the controls of their view. Desktop and
+//! console apps have none, so the controls are obtained for a hidden window
+//! through `ISystemMediaTransportControlsInterop`; the window lives on its
+//! own thread, which pumps its messages.
 
 use crate::{
     MediaCommand, MediaCommandHandler, MediaError, MediaMetadata, PlaybackState, PlaybackStatus,
 };
-use std::sync::RwLock;
-use windows::Foundation::TypedEventHandler;
-use windows::Media::Playback::{MediaPlaybackType, MediaPlayer};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once, OnceLock, PoisonError, RwLock};
+use std::time::Duration;
+use windows::Foundation::{TimeSpan, TypedEventHandler, Uri};
 use windows::Media::{
-    MediaPlaybackAutoRepeatMode, MediaPlaybackStatus, MediaPlaybackType as MPType,
-    SystemMediaTransportControls, SystemMediaTransportControlsButton,
-    SystemMediaTransportControlsButtonPressedEventArgs,
+    MediaPlaybackStatus, MediaPlaybackType, PlaybackPositionChangeRequestedEventArgs,
+    PlaybackRateChangeRequestedEventArgs, SystemMediaTransportControls,
+    SystemMediaTransportControlsButton, SystemMediaTransportControlsButtonPressedEventArgs,
+    SystemMediaTransportControlsTimelineProperties,
 };
+use windows::Security::Cryptography::CryptographicBuffer;
+use windows::Storage::Streams::{InMemoryRandomAccessStream, RandomAccessStreamReference};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::WinRT::ISystemMediaTransportControlsInterop;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, MSG, RegisterClassW,
+    TranslateMessage, WINDOW_EX_STYLE, WNDCLASSW, WS_OVERLAPPEDWINDOW,
+};
+use windows::core::{HSTRING, w};
 
 /// Global command handler
 static COMMAND_HANDLER: RwLock<Option<Box<dyn MediaCommandHandler>>> = RwLock::new(None);
 
+/// Commands waiting for [`MediaCenterInner::poll_command`].
+static COMMAND_QUEUE: Mutex<VecDeque<MediaCommand>> = Mutex::new(VecDeque::new());
+
+/// Number of live [`MediaCenterInner`]s; commands are only queued while one
+/// can poll them.
+static MEDIA_CENTERS: AtomicUsize = AtomicUsize::new(0);
+
+fn dispatch_command(cmd: MediaCommand) {
+    if MEDIA_CENTERS.load(Ordering::Acquire) > 0 {
+        COMMAND_QUEUE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back(cmd.clone());
+    }
+    if let Ok(guard) = COMMAND_HANDLER.read()
+        && let Some(handler) = guard.as_ref()
+    {
+        handler.on_command(cmd);
+    }
+}
+
+fn to_time_span(duration: Duration) -> TimeSpan {
+    // TimeSpan counts 100 ns ticks
+    TimeSpan {
+        Duration: i64::try_from(duration.as_nanos() / 100).unwrap_or(i64::MAX),
+    }
+}
+
+fn from_time_span(span: TimeSpan) -> Duration {
+    Duration::from_nanos(u64::try_from(span.Duration).unwrap_or(0) * 100)
+}
+
+unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+/// The hidden window the controls of desktop apps are attached to, created
+/// on first use.
+fn hidden_window() -> Result<HWND, MediaError> {
+    // Address of the window handle, which is only used as an identifier
+    static WINDOW: OnceLock<Result<usize, String>> = OnceLock::new();
+
+    let window = WINDOW.get_or_init(|| {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let created = unsafe { create_hidden_window() };
+            let running = created.is_ok();
+            let _ = tx.send(
+                created
+                    .map(|hwnd| hwnd.0.expose_provenance())
+                    .map_err(|e| e.message().to_string()),
+            );
+            if !running {
+                return;
+            }
+            let mut msg = MSG::default();
+            while unsafe { GetMessageW(&raw mut msg, None, 0, 0) }.as_bool() {
+                unsafe {
+                    let _ = TranslateMessage(&raw const msg);
+                    DispatchMessageW(&raw const msg);
+                }
+            }
+        });
+        rx.recv()
+            .unwrap_or_else(|_| Err("window thread exited".into()))
+    });
+
+    window
+        .clone()
+        .map(|addr| HWND(std::ptr::with_exposed_provenance_mut(addr)))
+        .map_err(MediaError::InitializationFailed)
+}
+
+/// Create a window that is never shown, to own the media controls.
+unsafe fn create_hidden_window() -> windows::core::Result<HWND> {
+    let instance = unsafe { GetModuleHandleW(None) }?;
+    let class_name = w!("WaterkitMediaControls");
+    let class = WNDCLASSW {
+        lpfnWndProc: Some(window_proc),
+        hInstance: instance.into(),
+        lpszClassName: class_name,
+        ..WNDCLASSW::default()
+    };
+    unsafe {
+        RegisterClassW(&raw const class);
+        CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            w!("Media Controls"),
+            WS_OVERLAPPEDWINDOW,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )
+    }
+}
+
+/// Get the app's media controls, enabling them and forwarding their events
+/// on first use.
+fn system_controls() -> Result<SystemMediaTransportControls, MediaError> {
+    static REGISTER: Once = Once::new();
+
+    let controls = match SystemMediaTransportControls::GetForCurrentView() {
+        Ok(controls) => controls,
+        // No CoreWindow: a desktop or console app
+        Err(_) => {
+            let window = hidden_window()?;
+            let interop = windows::core::factory::<
+                SystemMediaTransportControls,
+                ISystemMediaTransportControlsInterop,
+            >()
+            .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?;
+            unsafe { interop.GetForWindow::<SystemMediaTransportControls>(window) }
+                .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?
+        }
+    };
+
+    let mut result = Ok(());
+    REGISTER.call_once(|| result = enable(&controls));
+    result.map(|()| controls)
+}
+
+/// Enable the buttons and forward button presses and seek and rate requests
+/// as commands.
+fn enable(controls: &SystemMediaTransportControls) -> Result<(), MediaError> {
+    let failed =
+        |e: windows::core::Error| MediaError::InitializationFailed(e.message().to_string());
+
+    controls.SetIsEnabled(true).map_err(failed)?;
+    controls.SetIsPlayEnabled(true).map_err(failed)?;
+    controls.SetIsPauseEnabled(true).map_err(failed)?;
+    controls.SetIsStopEnabled(true).map_err(failed)?;
+    controls.SetIsNextEnabled(true).map_err(failed)?;
+    controls.SetIsPreviousEnabled(true).map_err(failed)?;
+
+    controls
+        .ButtonPressed(&TypedEventHandler::new(|_, args| {
+            let button = args
+                .ok()
+                .and_then(SystemMediaTransportControlsButtonPressedEventArgs::Button);
+            let cmd = match button {
+                Ok(SystemMediaTransportControlsButton::Play) => MediaCommand::Play,
+                Ok(SystemMediaTransportControlsButton::Pause) => MediaCommand::Pause,
+                Ok(SystemMediaTransportControlsButton::Stop) => MediaCommand::Stop,
+                Ok(SystemMediaTransportControlsButton::Next) => MediaCommand::Next,
+                Ok(SystemMediaTransportControlsButton::Previous) => MediaCommand::Previous,
+                _ => return Ok(()),
+            };
+            dispatch_command(cmd);
+            Ok(())
+        }))
+        .map_err(failed)?;
+
+    controls
+        .PlaybackPositionChangeRequested(&TypedEventHandler::new(|_, args| {
+            if let Ok(position) = args
+                .ok()
+                .and_then(PlaybackPositionChangeRequestedEventArgs::RequestedPlaybackPosition)
+            {
+                dispatch_command(MediaCommand::Seek(from_time_span(position)));
+            }
+            Ok(())
+        }))
+        .map_err(failed)?;
+
+    controls
+        .PlaybackRateChangeRequested(&TypedEventHandler::new(|_, args| {
+            if let Ok(rate) = args
+                .ok()
+                .and_then(PlaybackRateChangeRequestedEventArgs::RequestedPlaybackRate)
+                && rate > 0.0
+            {
+                dispatch_command(MediaCommand::SetRate(crate::player::clamp_rate(rate)));
+            }
+            Ok(())
+        }))
+        .map_err(failed)?;
+
+    Ok(())
+}
+
+/// Create a thumbnail from encoded image bytes.
+fn thumbnail_from_bytes(bytes: &[u8]) -> windows::core::Result<RandomAccessStreamReference> {
+    let stream = InMemoryRandomAccessStream::new()?;
+    let buffer = CryptographicBuffer::CreateFromByteArray(bytes)?;
+    stream.WriteAsync(&buffer)?.get()?;
+    stream.Seek(0)?;
+    RandomAccessStreamReference::CreateFromStream(&stream)
+}
+
 #[derive(Debug)]
 pub struct MediaSessionInner {
-    media_player: MediaPlayer,
     controls: SystemMediaTransportControls,
+    // Duration of the current media, which bounds the timeline
+    duration: Mutex<Option<Duration>>,
 }
 
 impl MediaSessionInner {
     pub fn new() -> Result<Self, MediaError> {
-        let media_player = MediaPlayer::new()
-            .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?;
-
-        let controls = media_player
-            .SystemMediaTransportControls()
-            .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?;
-
-        // Enable controls
-        controls
-            .SetIsEnabled(true)
-            .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?;
-        controls
-            .SetIsPlayEnabled(true)
-            .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?;
-        controls
-            .SetIsPauseEnabled(true)
-            .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?;
-        controls
-            .SetIsStopEnabled(true)
-            .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?;
-        controls
-            .SetIsNextEnabled(true)
-            .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?;
-        controls
-            .SetIsPreviousEnabled(true)
-            .map_err(|e| MediaError::InitializationFailed(e.message().to_string()))?;
-
         Ok(Self {
-            media_player,
-            controls,
+            controls: system_controls()?,
+            duration: Mutex::new(None),
         })
     }
 
     pub fn set_metadata(&self, metadata: &MediaMetadata) -> Result<(), MediaError> {
-        let updater = self
-            .controls
-            .DisplayUpdater()
-            .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
-
-        updater
-            .SetType(MPType::Music)
-            .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
+        let failed = |e: windows::core::Error| MediaError::UpdateFailed(e.message().to_string());
 
-        let music_props = updater
-            .MusicProperties()
-            .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
+        let updater = self.controls.DisplayUpdater().map_err(failed)?;
+        updater.ClearAll().map_err(failed)?;
+        updater.SetType(MediaPlaybackType::Music).map_err(failed)?;
 
+        let music_props = updater.MusicProperties().map_err(failed)?;
         if let Some(ref title) = metadata.title {
             music_props
-                .SetTitle(&windows::core::HSTRING::from(title.as_str()))
-                .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
+                .SetTitle(&HSTRING::from(title.as_str()))
+                .map_err(failed)?;
         }
-
         if let Some(ref artist) = metadata.artist {
             music_props
-                .SetArtist(&windows::core::HSTRING::from(artist.as_str()))
-                .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
+                .SetArtist(&HSTRING::from(artist.as_str()))
+                .map_err(failed)?;
         }
-
         if let Some(ref album) = metadata.album {
             music_props
-                .SetAlbumTitle(&windows::core::HSTRING::from(album.as_str()))
-                .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
+                .SetAlbumTitle(&HSTRING::from(album.as_str()))
+                .map_err(failed)?;
         }
 
-        // Artwork from URL
-        if let Some(ref url) = metadata.artwork_url {
-            if let Ok(uri) =
-                windows::Foundation::Uri::CreateUri(&windows::core::HSTRING::from(url.as_str()))
-            {
-                if let Ok(stream) =
-                    windows::Storage::Streams::RandomAccessStreamReference::CreateFromUri(&uri)
-                {
-                    let _ = updater.SetThumbnail(&stream);
-                }
-            }
+        // Artwork bytes win over the URL; a missing thumbnail isn't an error
+        let thumbnail = match (&metadata.artwork_bytes, &metadata.artwork_url) {
+            (Some(bytes), _) => thumbnail_from_bytes(bytes).ok(),
+            (None, Some(url)) => Uri::CreateUri(&HSTRING::from(url.as_str()))
+                .and_then(|uri| RandomAccessStreamReference::CreateFromUri(&uri))
+                .ok(),
+            (None, None) => None,
+        };
+        if let Some(thumbnail) = thumbnail {
+            let _ = updater.SetThumbnail(&thumbnail);
         }
 
-        updater
-            .Update()
-            .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
+        updater.Update().map_err(failed)?;
 
+        *self.duration.lock().unwrap_or_else(PoisonError::into_inner) = metadata.duration;
         Ok(())
     }
 
     pub fn set_playback_state(&self, state: &PlaybackState) -> Result<(), MediaError> {
+        let failed = |e: windows::core::Error| MediaError::UpdateFailed(e.message().to_string());
+
         let status = match state.status {
             PlaybackStatus::Playing => MediaPlaybackStatus::Playing,
             PlaybackStatus::Paused => MediaPlaybackStatus::Paused,
             PlaybackStatus::Stopped => MediaPlaybackStatus::Stopped,
         };
+        self.controls.SetPlaybackStatus(status).map_err(failed)?;
 
-        self.controls
-            .SetPlaybackStatus(status)
-            .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
+        if state.status == PlaybackStatus::Playing {
+            self.controls.SetPlaybackRate(state.rate).map_err(failed)?;
+        }
+
+        // The timeline needs both ends, so it is only shown with a duration
+        let duration = *self.duration.lock().unwrap_or_else(PoisonError::into_inner);
+        if let (Some(position), Some(duration)) = (state.position, duration) {
+            let timeline = SystemMediaTransportControlsTimelineProperties::new().map_err(failed)?;
+            let end = to_time_span(duration);
+            timeline.SetStartTime(TimeSpan::default()).map_err(failed)?;
+            timeline
+                .SetMinSeekTime(TimeSpan::default())
+                .map_err(failed)?;
+            timeline.SetEndTime(end).map_err(failed)?;
+            timeline.SetMaxSeekTime(end).map_err(failed)?;
+            timeline
+                .SetPosition(to_time_span(position.min(duration)))
+                .map_err(failed)?;
+            self.controls
+                .UpdateTimelineProperties(&timeline)
+                .map_err(failed)?;
+        }
 
         Ok(())
     }
 
+    #[allow(clippy::unused_self)]
     pub fn set_command_handler(
         &self,
         handler: Box<dyn MediaCommandHandler>,
     ) -> Result<(), MediaError> {
-        {
-            let mut guard = COMMAND_HANDLER
-                .write()
-                .map_err(|e| MediaError::Unknown(format!("Lock poisoned: {e}")))?;
-            *guard = Some(handler);
-        }
-
-        let handler = TypedEventHandler::new(
-            |_sender: &Option<SystemMediaTransportControls>,
-             args: &Option<SystemMediaTransportControlsButtonPressedEventArgs>| {
-                if let Some(args) = args {
-                    if let Ok(button) = args.Button() {
-                        let cmd = match button {
-                            SystemMediaTransportControlsButton::Play => Some(MediaCommand::Play),
-                            SystemMediaTransportControlsButton::Pause => Some(MediaCommand::Pause),
-                            SystemMediaTransportControlsButton::Stop => Some(MediaCommand::Stop),
-                            SystemMediaTransportControlsButton::Next => Some(MediaCommand::Next),
-                            SystemMediaTransportControlsButton::Previous => {
-                                Some(MediaCommand::Previous)
-                            }
-                            _ => None,
-                        };
-
-                        if let Some(cmd) = cmd {
-                            if let Ok(guard) = COMMAND_HANDLER.read() {
-                                if let Some(handler) = guard.as_ref() {
-                                    handler.on_command(cmd);
-                                }
-                            }
-                        }
-                    }
-                }
-                Ok(())
-            },
-        );
-
-        self.controls
-            .ButtonPressed(&handler)
-            .map_err(|e| MediaError::Unknown(e.message().to_string()))?;
-
+        let mut guard = COMMAND_HANDLER
+            .write()
+            .map_err(|e| MediaError::Unknown(format!("Lock poisoned: {e}")))?;
+        *guard = Some(handler);
         Ok(())
     }
 
+    #[allow(clippy::unused_self)]
     pub fn request_audio_focus(&self) -> Result<(), MediaError> {
         // Windows doesn't have an explicit audio focus API like Android
         // The SMTC handles this automatically
         Ok(())
     }
 
+    #[allow(clippy::unused_self)]
     pub fn abandon_audio_focus(&self) -> Result<(), MediaError> {
         // Windows doesn't have an explicit audio focus API
         Ok(())
     }
 
     pub fn clear(&self) -> Result<(), MediaError> {
-        let updater = self
-            .controls
-            .DisplayUpdater()
-            .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
-
-        updater
-            .ClearAll()
-            .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
+        let failed = |e: windows::core::Error| MediaError::UpdateFailed(e.message().to_string());
 
+        self.controls
+            .DisplayUpdater()
+            .and_then(|updater| updater.ClearAll())
+            .map_err(failed)?;
         self.controls
             .SetPlaybackStatus(MediaPlaybackStatus::Closed)
-            .map_err(|e| MediaError::UpdateFailed(e.message().to_string()))?;
+            .map_err(failed)?;
+        *self.duration.lock().unwrap_or_else(PoisonError::into_inner) = None;
 
         Ok(())
     }
 }
+
+/// Media center integration for Windows.
+/// Uses `SystemMediaTransportControls`, queueing commands for polling.
+#[derive(Debug)]
+pub struct MediaCenterInner {
+    session: MediaSessionInner,
+}
+
+impl MediaCenterInner {
+    pub fn new() -> Result<Self, MediaError> {
+        let session = MediaSessionInner::new()?;
+        MEDIA_CENTERS.fetch_add(1, Ordering::AcqRel);
+        Ok(Self { session })
+    }
+
+    pub fn update(&self, metadata: &MediaMetadata, state: &PlaybackState) {
+        let _ = self.session.set_metadata(metadata);
+        let _ = self.session.set_playback_state(state);
+    }
+
+    pub fn clear(&self) {
+        let _ = self.session.clear();
+    }
+
+    // Events arrive on WinRT threads, and the hidden window pumps its own
+    // messages, so there is nothing to run here
+    #[allow(clippy::unused_self)]
+    pub fn run_loop(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+
+    #[allow(clippy::unused_self)]
+    pub fn poll_command(&self) -> Option<MediaCommand> {
+        COMMAND_QUEUE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+    }
+}
+
+impl Drop for MediaCenterInner {
+    fn drop(&mut self) {
+        if MEDIA_CENTERS.fetch_sub(1, Ordering::AcqRel) == 1 {
+            COMMAND_QUEUE
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clear();
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Voice activity detection for the recorder.
+//!
+//! Audio is judged in short windows by its energy and zero-crossing rate.
+//! Speech starts on the first loud window and ends once no speech has been
+//! heard for the hangover, so pauses between words don't split an utterance.
+
+use crate::recorder::RecorderEvent;
+use std::time::Duration;
+
+/// Length of an analysis window in milliseconds.
+const WINDOW_MS: u64 = 10;
+
+/// Zero crossings per sample above which a window is treated as noise.
+///
+/// Voiced speech crosses zero far less often; broadband hiss crosses on
+/// about every other sample.
+const MAX_ZERO_CROSSING_RATE: f32 = 0.4;
+
+/// Voice activity detector settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct VadSettings {
+    /// Energy in dBFS at which a window counts as speech.
+    pub(crate) threshold_db: f32,
+    /// How long speech lasts after the last loud window.
+    pub(crate) hangover: Duration,
+}
+
+impl Default for VadSettings {
+    fn default() -> Self {
+        Self {
+            threshold_db: -40.0,
+            hangover: Duration::from_millis(300),
+        }
+    }
+}
+
+/// Tracks whether interleaved audio currently contains speech.
+#[derive(Debug)]
+pub(crate) struct VoiceDetector {
+    channels: usize,
+    /// Mean square energy at the threshold.
+    threshold: f32,
+    /// Silent windows tolerated before speech ends.
+    hangover_windows: u32,
+    /// Silent windows since the last loud one.
+    silent_windows: u32,
+    speaking: bool,
+}
+
+impl VoiceDetector {
+    pub(crate) fn new(settings: VadSettings, channels: u16) -> Self {
+        let hangover_windows = u32::try_from(
+            settings
+                .hangover
+                .as_millis()
+                .div_ceil(u128::from(WINDOW_MS)),
+        )
+        .unwrap_or(u32::MAX);
+        Self {
+            channels: usize::from(channels.max(1)),
+            threshold: 10f32.powf(settings.threshold_db / 10.0),
+            hangover_windows,
+            silent_windows: 0,
+            speaking: false,
+        }
+    }
+
+    /// Number of interleaved samples in one analysis window.
+    pub(crate) fn window_len(sample_rate: u32, channels: u16) -> usize {
+        let frames = (u64::from(sample_rate) * WINDOW_MS / 1000).max(1);
+        usize::try_from(frames).unwrap_or(usize::MAX) * usize::from(channels.max(1))
+    }
+
+    /// Whether the audio analyzed so far ends in speech.
+    pub(crate) const fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+
+    /// Analyze one window, returning the event it triggers, if any.
+    pub(crate) fn process(&mut self, window: &[f32]) -> Option<RecorderEvent> {
+        if self.is_speech(window) {
+            self.silent_windows = 0;
+            if !self.speaking {
+                self.speaking = true;
+                return Some(RecorderEvent::SpeechStarted);
+            }
+        } else if self.speaking {
+            self.silent_windows += 1;
+            if self.silent_windows > self.hangover_windows {
+                self.speaking = false;
+                return Some(RecorderEvent::SpeechEnded);
+            }
+        }
+        None
+    }
+
+    /// End detection, closing any speech in progress.
+    pub(crate) fn finish(&mut self) -> Option<RecorderEvent> {
+        std::mem::take(&mut self.speaking).then_some(RecorderEvent::SpeechEnded)
+    }
+
+    /// Judge a window on its mono mix.
+    #[allow(clippy::cast_precision_loss)] // window and channel counts are small
+    fn is_speech(&self, window: &[f32]) -> bool {
+        let mut energy = 0.0;
+        let mut crossings = 0u32;
+        let mut previous = 0.0f32;
+        let mut frames = 0u32;
+        for frame in window.chunks_exact(self.channels) {
+            let sample = frame.iter().sum::<f32>() / self.channels as f32;
+            energy += sample * sample;
+            if frames > 0 && (sample >= 0.0) != (previous >= 0.0) {
+                crossings += 1;
+            }
+            previous = sample;
+            frames += 1;
+        }
+        if frames == 0 {
+            return false;
+        }
+        let frames = frames as f32;
+        energy / frames >= self.threshold && crossings as f32 / frames <= MAX_ZERO_CROSSING_RATE
+    }
+}
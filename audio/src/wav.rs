@@ -0,0 +1,249 @@
+//! WAV file output for [`AudioRecorder`](crate::AudioRecorder).
+//!
+//! Samples are written on a dedicated thread so the capture callback never
+//! blocks on disk I/O. The RIFF header is patched roughly once per second of
+//! audio, so a crash mid-recording still leaves a parseable file.
+
+use crate::recorder::{AudioBuffer, AudioFormat, RecordError};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::thread::JoinHandle;
+
+/// Largest data chunk a RIFF/WAV file can describe, leaving room for the header.
+const MAX_DATA_BYTES: u64 = u32::MAX as u64 - 128;
+
+/// Sample encoding of a recorded WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WavSampleFormat {
+    /// 16-bit signed integer PCM.
+    #[default]
+    I16,
+    /// 32-bit IEEE float.
+    F32,
+}
+
+impl WavSampleFormat {
+    const fn bytes_per_sample(self) -> u64 {
+        match self {
+            Self::I16 => 2,
+            Self::F32 => 4,
+        }
+    }
+
+    const fn spec(self, format: AudioFormat) -> hound::WavSpec {
+        let (bits_per_sample, sample_format) = match self {
+            Self::I16 => (16, hound::SampleFormat::Int),
+            Self::F32 => (32, hound::SampleFormat::Float),
+        };
+        hound::WavSpec {
+            channels: format.channels,
+            sample_rate: format.sample_rate,
+            bits_per_sample,
+            sample_format,
+        }
+    }
+}
+
+/// Background writer that persists recorded buffers to a WAV file.
+#[derive(Debug)]
+pub(crate) struct WavFileWriter {
+    sender: async_channel::Sender<AudioBuffer>,
+    thread: Option<JoinHandle<Result<(), RecordError>>>,
+}
+
+impl WavFileWriter {
+    /// Create the file and start the writer thread.
+    ///
+    /// `on_error` is called from the writer thread if writing fails, such as
+    /// when the file reaches the 4 GiB RIFF limit; nothing is written after.
+    pub(crate) fn create(
+        path: &Path,
+        format: AudioFormat,
+        sample_format: WavSampleFormat,
+        on_error: impl Fn(RecordError) + Send + 'static,
+    ) -> Result<Self, RecordError> {
+        let writer = hound::WavWriter::create(path, sample_format.spec(format))
+            .map_err(|e| RecordError::WriteFailed(format!("{}: {e}", path.display())))?;
+
+        let (sender, receiver) = async_channel::unbounded();
+        let thread = std::thread::spawn(move || {
+            write_loop(writer, &receiver, format, sample_format, MAX_DATA_BYTES)
+                .inspect_err(|error| on_error(error.clone()))
+        });
+
+        Ok(Self {
+            sender,
+            thread: Some(thread),
+        })
+    }
+
+    /// Sender to tap recorded buffers into the file.
+    pub(crate) fn sender(&self) -> async_channel::Sender<AudioBuffer> {
+        self.sender.clone()
+    }
+
+    /// Write all pending buffers and finalize the header.
+    pub(crate) fn finish(mut self) -> Result<(), RecordError> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<(), RecordError> {
+        self.sender.close();
+        self.thread.take().map_or(Ok(()), |thread| {
+            thread
+                .join()
+                .map_err(|_| RecordError::WriteFailed("WAV writer thread panicked".into()))?
+        })
+    }
+}
+
+impl Drop for WavFileWriter {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+fn write_loop(
+    mut writer: hound::WavWriter<BufWriter<File>>,
+    receiver: &async_channel::Receiver<AudioBuffer>,
+    format: AudioFormat,
+    sample_format: WavSampleFormat,
+    max_data_bytes: u64,
+) -> Result<(), RecordError> {
+    let write_err = |e: hound::Error| RecordError::WriteFailed(e.to_string());
+    let flush_interval = u64::from(format.sample_rate) * u64::from(format.channels);
+    let mut data_bytes = 0u64;
+    let mut since_flush = 0u64;
+
+    while let Ok(buffer) = receiver.recv_blocking() {
+        let len = buffer.len() as u64;
+        if data_bytes + len * sample_format.bytes_per_sample() > max_data_bytes {
+            writer.finalize().map_err(write_err)?;
+            return Err(RecordError::WriteFailed(
+                "WAV file would exceed the 4 GiB RIFF limit".into(),
+            ));
+        }
+
+        match sample_format {
+            WavSampleFormat::I16 => {
+                for &sample in buffer.samples_i16().iter() {
+                    writer.write_sample(sample).map_err(write_err)?;
+                }
+            }
+            WavSampleFormat::F32 => {
+                for &sample in buffer.samples().iter() {
+                    writer.write_sample(sample).map_err(write_err)?;
+                }
+            }
+        }
+        data_bytes += len * sample_format.bytes_per_sample();
+
+        // Patch the header periodically so an interrupted recording stays readable
+        since_flush += len;
+        if since_flush >= flush_interval {
+            writer.flush().map_err(write_err)?;
+            since_flush = 0;
+        }
+    }
+
+    writer.finalize().map_err(write_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::SampleFormat;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    const FORMAT: AudioFormat = AudioFormat {
+        sample_rate: 8000,
+        channels: 2,
+        sample_format: SampleFormat::F32,
+    };
+
+    /// A path in the temporary directory, removed when dropped.
+    struct TempPath(PathBuf);
+
+    impl TempPath {
+        fn new(name: &str) -> Self {
+            Self(
+                std::env::temp_dir()
+                    .join(format!("waterkit-wav-{}-{name}.wav", std::process::id())),
+            )
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn buffer(frames: usize) -> AudioBuffer {
+        #[allow(clippy::cast_precision_loss)]
+        let samples = (0..frames * 2).map(|i| i as f32 / 65536.0).collect();
+        AudioBuffer::new(samples, FORMAT)
+    }
+
+    /// Number of samples the header of the WAV file at `path` describes.
+    fn header_len(path: &Path) -> Option<u32> {
+        hound::WavReader::open(path).ok().map(|reader| reader.len())
+    }
+
+    #[test]
+    fn finish_finalizes_the_header() {
+        let path = TempPath::new("finish");
+        let writer = WavFileWriter::create(&path.0, FORMAT, WavSampleFormat::F32, |_| {})
+            .expect("create the file");
+        let sender = writer.sender();
+        sender.try_send(buffer(100)).expect("send a buffer");
+        sender.try_send(buffer(50)).expect("send a buffer");
+        writer.finish().expect("finish the file");
+
+        let mut reader = hound::WavReader::open(&path.0).expect("open the file");
+        assert_eq!(reader.spec(), WavSampleFormat::F32.spec(FORMAT));
+        let samples: Vec<f32> = reader.samples().map(Result::unwrap).collect();
+        let expected: Vec<f32> = [buffer(100), buffer(50)]
+            .iter()
+            .flat_map(|buffer| buffer.samples().into_owned())
+            .collect();
+        assert_eq!(samples, expected);
+    }
+
+    #[test]
+    fn header_is_patched_while_recording() {
+        let path = TempPath::new("patch");
+        let writer = WavFileWriter::create(&path.0, FORMAT, WavSampleFormat::I16, |_| {})
+            .expect("create the file");
+        // A second of audio is the flush interval
+        writer
+            .sender()
+            .try_send(buffer(8000))
+            .expect("send a buffer");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while header_len(&path.0) != Some(16000) {
+            assert!(Instant::now() < deadline, "header was never patched");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        writer.finish().expect("finish the file");
+    }
+
+    #[test]
+    fn size_limit_finalizes_the_file() {
+        let path = TempPath::new("limit");
+        let writer = hound::WavWriter::create(&path.0, WavSampleFormat::I16.spec(FORMAT))
+            .expect("create the file");
+        let (sender, receiver) = async_channel::unbounded();
+        sender.try_send(buffer(100)).expect("send a buffer");
+        sender.try_send(buffer(100)).expect("send a buffer");
+        sender.close();
+
+        // Room for the first buffer only: 100 stereo frames of 2 bytes
+        let result = write_loop(writer, &receiver, FORMAT, WavSampleFormat::I16, 500);
+        assert!(matches!(result, Err(RecordError::WriteFailed(_))));
+        assert_eq!(header_len(&path.0), Some(200));
+    }
+}
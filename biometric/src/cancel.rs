@@ -0,0 +1,83 @@
+//! Cancelling an authentication prompt that is still showing.
+
+use crate::BiometricError;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Dismisses the prompt shown by [`authenticate_cancelable`](crate::authenticate_cancelable).
+///
+/// Clones cancel the same prompt.
+#[derive(Clone, Default)]
+pub struct AuthCancelToken {
+    state: Arc<Mutex<CancelState>>,
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: bool,
+    // Platform action that dismisses the prompt, set once it is shown
+    dismiss: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl fmt::Debug for AuthCancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthCancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl AuthCancelToken {
+    fn state(&self) -> MutexGuard<'_, CancelState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Dismiss the prompt. The authentication then fails with
+    /// [`BiometricError::Cancelled`].
+    ///
+    /// Does nothing once the authentication has finished.
+    pub fn cancel(&self) {
+        let dismiss = {
+            let mut state = self.state();
+            state.cancelled = true;
+            state.dismiss.take()
+        };
+        if let Some(dismiss) = dismiss {
+            dismiss();
+        }
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.state().cancelled
+    }
+
+    /// Register how to dismiss the prompt once it is shown, running it right
+    /// away if the token was cancelled in the meantime.
+    #[cfg(any(
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "android",
+        target_os = "windows"
+    ))]
+    pub(crate) fn on_cancel(&self, dismiss: impl FnOnce() + Send + 'static) {
+        let mut state = self.state();
+        if state.cancelled {
+            drop(state);
+            dismiss();
+        } else {
+            state.dismiss = Some(Box::new(dismiss));
+        }
+    }
+
+    /// Report any failure after cancellation as [`BiometricError::Cancelled`],
+    /// since platforms describe a dismissed prompt in their own terms.
+    pub(crate) fn finish(&self, result: Result<(), BiometricError>) -> Result<(), BiometricError> {
+        self.state().dismiss = None;
+        match result {
+            Err(_) if self.is_cancelled() => Err(BiometricError::Cancelled),
+            result => result,
+        }
+    }
+}
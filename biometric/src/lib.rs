@@ -3,9 +3,11 @@
 
 #![warn(missing_docs)]
 
+mod cancel;
 /// Platform-specific implementations.
 mod sys;
 
+pub use cancel::AuthCancelToken;
 use thiserror::Error;
 
 /// The type of biometric authentication available.
@@ -38,6 +40,21 @@ pub enum BiometricError {
     PlatformError(String),
 }
 
+/// Options for [`authenticate_with_options`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthOptions {
+    /// Let the user enter their device passcode, PIN or pattern instead of
+    /// using biometrics, such as after repeated failed fingerprint attempts.
+    ///
+    /// Windows Hello always offers the PIN, so this has no effect there.
+    pub allow_device_passcode: bool,
+    /// Title of the cancel button. Uses the system default if `None`.
+    ///
+    /// Ignored on Windows, and on Android when the passcode is allowed since
+    /// the system provides that button.
+    pub cancel_title: Option<String>,
+}
+
 /// Checks if biometric authentication is available on the current device.
 pub async fn is_available() -> bool {
     sys::is_available().await
@@ -51,7 +68,49 @@ pub async fn is_available() -> bool {
 /// - The user cancels the authentication.
 /// - Authentication fails.
 pub async fn authenticate(reason: &str) -> Result<(), BiometricError> {
-    sys::authenticate(reason).await
+    authenticate_with_options(reason, &AuthOptions::default()).await
+}
+
+/// Request authentication with a reason, configured by `options`.
+///
+/// With [`AuthOptions::allow_device_passcode`] set, the device passcode is
+/// accepted as well, so authentication can succeed on devices without
+/// enrolled biometrics.
+///
+/// # Errors
+/// Returns a [`BiometricError`] if:
+/// - Authentication is not available.
+/// - The user cancels the authentication.
+/// - Authentication fails.
+pub async fn authenticate_with_options(
+    reason: &str,
+    options: &AuthOptions,
+) -> Result<(), BiometricError> {
+    let cancel = AuthCancelToken::default();
+    let result = sys::authenticate(reason, options, &cancel).await;
+    cancel.finish(result)
+}
+
+/// Request biometric authentication that can be cancelled while the prompt
+/// is showing, such as when the app times out its lock screen.
+///
+/// Calling [`AuthCancelToken::cancel`] dismisses the prompt and makes the
+/// returned future resolve with [`BiometricError::Cancelled`]. Cancelling
+/// before the future is first polled keeps the prompt from showing at all.
+pub fn authenticate_cancelable(
+    reason: &str,
+) -> (
+    impl Future<Output = Result<(), BiometricError>>,
+    AuthCancelToken,
+) {
+    let cancel = AuthCancelToken::default();
+    let token = cancel.clone();
+    let reason = reason.to_owned();
+    let future = async move {
+        let result = sys::authenticate(&reason, &AuthOptions::default(), &cancel).await;
+        cancel.finish(result)
+    };
+    (future, token)
 }
 
 /// Get the available biometric type.
@@ -60,3 +119,19 @@ pub async fn authenticate(reason: &str) -> Result<(), BiometricError> {
 pub async fn get_biometric_type() -> Option<BiometricType> {
     sys::get_biometric_type().await
 }
+
+/// Checks if the user has enrolled at least one biometric.
+///
+/// Unlike [`is_available`], this returns `false` when the hardware is present
+/// but no fingerprint or face has been registered.
+pub async fn is_enrolled() -> bool {
+    sys::is_enrolled().await
+}
+
+/// Get the biometric types the user has enrolled.
+///
+/// A device may have several enrolled types, such as both fingerprint and face.
+/// Returns an empty list if nothing is enrolled.
+pub async fn get_enrolled_types() -> Vec<BiometricType> {
+    sys::get_enrolled_types().await
+}
@@ -2,7 +2,9 @@ package waterkit.biometric
 
 import android.content.Context
 import android.content.pm.PackageManager
+import android.hardware.biometrics.BiometricManager
 import android.hardware.biometrics.BiometricPrompt
+import android.hardware.fingerprint.FingerprintManager
 import android.os.Build
 import android.os.CancellationSignal
 import android.os.Handler
@@ -11,6 +13,9 @@ import java.util.concurrent.Executor
 
 class BiometricHelper {
     companion object {
+        /** Signals of prompts still showing, keyed by request ID. */
+        private val signals = HashMap<Long, CancellationSignal>()
+
         @JvmStatic
         fun isAvailable(context: Context): Boolean {
             if (Build.VERSION.SDK_INT < Build.VERSION_CODES.P) {
@@ -39,37 +44,114 @@ class BiometricHelper {
         }
 
         @JvmStatic
-        fun authenticate(context: Context, reason: String, callbackPtr: Long) {
+        fun isEnrolled(context: Context): Boolean {
+            if (Build.VERSION.SDK_INT < Build.VERSION_CODES.Q) {
+                return getEnrolledTypes(context) != 0
+            }
+            val manager = context.getSystemService(BiometricManager::class.java) ?: return false
+            val result = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.R) {
+                manager.canAuthenticate(BiometricManager.Authenticators.BIOMETRIC_STRONG)
+            } else {
+                @Suppress("DEPRECATION")
+                manager.canAuthenticate()
+            }
+            // BIOMETRIC_ERROR_NONE_ENROLLED means hardware is present but nothing is registered
+            return result == BiometricManager.BIOMETRIC_SUCCESS
+        }
+
+        @JvmStatic
+        fun getEnrolledTypes(context: Context): Int {
+            // Bitmask: 1: Fingerprint, 2: Face, 4: Iris
+            if (Build.VERSION.SDK_INT < Build.VERSION_CODES.P) {
+                return 0
+            }
+            val pm = context.packageManager
+            var types = 0
+
+            if (pm.hasSystemFeature(PackageManager.FEATURE_FINGERPRINT)) {
+                @Suppress("DEPRECATION")
+                val fingerprint = context.getSystemService(FingerprintManager::class.java)
+                @Suppress("DEPRECATION")
+                if (fingerprint?.hasEnrolledFingerprints() == true) types = types or 1
+            }
+
+            // Android has no public per-modality query for face or iris. If some
+            // biometric can authenticate while no fingerprint is enrolled,
+            // attribute it to the remaining hardware.
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.R) {
+                val manager = context.getSystemService(BiometricManager::class.java)
+                val weak = manager?.canAuthenticate(BiometricManager.Authenticators.BIOMETRIC_WEAK)
+                if (weak == BiometricManager.BIOMETRIC_SUCCESS && types == 0) {
+                    if (pm.hasSystemFeature(PackageManager.FEATURE_FACE)) types = types or 2
+                    else if (pm.hasSystemFeature(PackageManager.FEATURE_IRIS)) types = types or 4
+                }
+            }
+            return types
+        }
+
+        @JvmStatic
+        fun authenticate(
+            context: Context,
+            reason: String,
+            allowDeviceCredential: Boolean,
+            cancelTitle: String?,
+            requestId: Long,
+            callbackPtr: Long
+        ) {
             if (Build.VERSION.SDK_INT < Build.VERSION_CODES.P) {
                 onResult(callbackPtr, false, "Android version not supported (requires API 28+)")
                 return
             }
 
+            // Registered before posting, so a cancel that arrives first isn't lost
+            val signal = CancellationSignal()
+            synchronized(signals) { signals[requestId] = signal }
+            fun finish(success: Boolean, error: String?) {
+                synchronized(signals) { signals.remove(requestId) }
+                onResult(callbackPtr, success, error)
+            }
+
             val mainHandler = Handler(Looper.getMainLooper())
             mainHandler.post {
+                if (signal.isCanceled) {
+                    finish(false, "Cancelled")
+                    return@post
+                }
                 try {
                     val executor = Executor { command -> mainHandler.post(command) }
                     
-                    val prompt = BiometricPrompt.Builder(context)
+                    val builder = BiometricPrompt.Builder(context)
                         .setTitle("Authentication Required")
                         .setDescription(reason)
-                        .setNegativeButton("Cancel", executor) { _, _ ->
-                            onResult(callbackPtr, false, "Cancelled by user")
+                    // The system shows its own "Use PIN" button in place of the
+                    // negative button, and rejects prompts that set both
+                    if (allowDeviceCredential && Build.VERSION.SDK_INT >= Build.VERSION_CODES.R) {
+                        builder.setAllowedAuthenticators(
+                            BiometricManager.Authenticators.BIOMETRIC_STRONG or
+                                BiometricManager.Authenticators.DEVICE_CREDENTIAL
+                        )
+                    } else if (allowDeviceCredential && Build.VERSION.SDK_INT >= Build.VERSION_CODES.Q) {
+                        @Suppress("DEPRECATION")
+                        builder.setDeviceCredentialAllowed(true)
+                    } else {
+                        builder.setNegativeButton(cancelTitle ?: "Cancel", executor) { _, _ ->
+                            finish(false, "Cancelled by user")
                         }
-                        .build()
+                    }
+                    val prompt = builder.build()
 
                     prompt.authenticate(
-                        CancellationSignal(),
+                        signal,
                         executor,
                         object : BiometricPrompt.AuthenticationCallback() {
                             override fun onAuthenticationSucceeded(result: BiometricPrompt.AuthenticationResult) {
                                 super.onAuthenticationSucceeded(result)
-                                onResult(callbackPtr, true, null)
+                                finish(true, null)
                             }
 
                             override fun onAuthenticationError(errorCode: Int, errString: CharSequence) {
                                 super.onAuthenticationError(errorCode, errString)
-                                onResult(callbackPtr, false, errString.toString())
+                                finish(false, errString.toString())
                             }
 
                             override fun onAuthenticationFailed() {
@@ -82,11 +164,18 @@ class BiometricHelper {
                         }
                     )
                 } catch (e: Exception) {
-                    onResult(callbackPtr, false, e.message ?: "Unknown error")
+                    finish(false, e.message ?: "Unknown error")
                 }
             }
         }
 
+        /** Dismiss the prompt of [requestId], which then reports an error. */
+        @JvmStatic
+        fun cancel(requestId: Long) {
+            val signal = synchronized(signals) { signals.remove(requestId) }
+            signal?.cancel()
+        }
+
         // Native method to call back to Rust
         @JvmStatic
         external fun onResult(callbackPtr: Long, success: Boolean, error: String?)
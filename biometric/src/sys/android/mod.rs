@@ -1,8 +1,9 @@
-use crate::{BiometricError, BiometricType};
+use crate::{AuthCancelToken, AuthOptions, BiometricError, BiometricType};
 use jni::JNIEnv;
 use jni::objects::{GlobalRef, JClass, JObject, JString, JValue};
 use jni::sys::{jboolean, jlong};
 use std::sync::OnceLock;
+use std::sync::atomic::{AtomicI64, Ordering};
 
 /// Embedded DEX bytecode.
 static DEX_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/classes.dex"));
@@ -10,6 +11,9 @@ static DEX_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/classes.dex"
 /// Cached class loader.
 static CLASS_LOADER: OnceLock<GlobalRef> = OnceLock::new();
 
+/// Identifies a prompt to Kotlin, so it can be cancelled.
+static NEXT_REQUEST_ID: AtomicI64 = AtomicI64::new(0);
+
 /// Map to store callbacks: pointer -> Sender
 /// Note: We cast the raw pointer of the Sender to pass to Java, and cast it back.
 /// Using a map might be safer but passing pointer is standard FFI.
@@ -58,8 +62,9 @@ pub fn init(env: &mut JNIEnv, context: &JObject) -> Result<(), BiometricError> {
             .map_err(|e| BiometricError::PlatformError(format!("metadata DEX failed: {e}")))?
             .permissions();
         perms.set_mode(0o444); // Read-only
-        std::fs::set_permissions(&dex_path, perms)
-            .map_err(|e| BiometricError::PlatformError(format!("set_permissions DEX failed: {e}")))?;
+        std::fs::set_permissions(&dex_path, perms).map_err(|e| {
+            BiometricError::PlatformError(format!("set_permissions DEX failed: {e}"))
+        })?;
     }
 
     let dex_path_jstring = env
@@ -180,7 +185,20 @@ pub async fn get_biometric_type() -> Option<BiometricType> {
     None
 }
 
-pub async fn authenticate(_reason: &str) -> Result<(), BiometricError> {
+pub async fn is_enrolled() -> bool {
+    // Stub: requires context, use `is_enrolled_with_context`.
+    false
+}
+
+pub async fn get_enrolled_types() -> Vec<BiometricType> {
+    Vec::new()
+}
+
+pub async fn authenticate(
+    _reason: &str,
+    _options: &AuthOptions,
+    _cancel: &AuthCancelToken,
+) -> Result<(), BiometricError> {
     Err(BiometricError::PlatformError(
         "Android requires authenticate_with_context".into(),
     ))
@@ -191,6 +209,56 @@ pub fn authenticate_with_context(
     env: &mut JNIEnv,
     context: &JObject,
     reason: &str,
+) -> Result<tokio::sync::oneshot::Receiver<Result<(), BiometricError>>, BiometricError> {
+    authenticate_with_options_with_context(env, context, reason, &AuthOptions::default())
+}
+
+/// Like [`authenticate_with_context`], also returning a token that dismisses
+/// the prompt. The future then resolves with [`BiometricError::Cancelled`].
+pub fn authenticate_cancelable_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    reason: &str,
+) -> Result<
+    (
+        impl Future<Output = Result<(), BiometricError>> + use<>,
+        AuthCancelToken,
+    ),
+    BiometricError,
+> {
+    let cancel = AuthCancelToken::default();
+    let receiver = start_authentication(env, context, reason, &AuthOptions::default(), &cancel)?;
+    let token = cancel.clone();
+    let future = async move {
+        let result = receiver
+            .await
+            .unwrap_or_else(|_| Err(BiometricError::PlatformError("Channel closed".to_string())));
+        cancel.finish(result)
+    };
+    Ok((future, token))
+}
+
+/// Authenticate with `BiometricPrompt`, accepting the device PIN, pattern or
+/// password too if `options` allows it (API 29+).
+pub fn authenticate_with_options_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    reason: &str,
+    options: &AuthOptions,
+) -> Result<tokio::sync::oneshot::Receiver<Result<(), BiometricError>>, BiometricError> {
+    start_authentication(env, context, reason, options, &AuthCancelToken::default())
+}
+
+/// Show the prompt, registering how to dismiss it with `cancel`.
+///
+/// Failures after cancellation are reported by Kotlin as errors, so callers
+/// map them with the token.
+fn start_authentication(
+    env: &mut JNIEnv,
+    context: &JObject,
+    reason: &str,
+    options: &AuthOptions,
+    cancel: &AuthCancelToken,
 ) -> Result<tokio::sync::oneshot::Receiver<Result<(), BiometricError>>, BiometricError> {
     init(env, context)?;
 
@@ -201,15 +269,30 @@ pub fn authenticate_with_context(
     let reason_jstr = env
         .new_string(reason)
         .map_err(|e| BiometricError::PlatformError(format!("new_string: {e}")))?;
+    let cancel_title_jstr = match &options.cancel_title {
+        Some(title) => env
+            .new_string(title)
+            .map_err(|e| BiometricError::PlatformError(format!("new_string: {e}")))?
+            .into(),
+        None => JObject::null(),
+    };
+
+    let request_id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    let vm = env
+        .get_java_vm()
+        .map_err(|e| BiometricError::PlatformError(format!("get_java_vm: {e}")))?;
 
     let class = get_helper_class(env)?;
     env.call_static_method(
         class,
         "authenticate",
-        "(Landroid/content/Context;Ljava/lang/String;J)V",
+        "(Landroid/content/Context;Ljava/lang/String;ZLjava/lang/String;JJ)V",
         &[
             JValue::Object(context),
             JValue::Object(&reason_jstr),
+            JValue::Bool(options.allow_device_passcode.into()),
+            JValue::Object(&cancel_title_jstr),
+            JValue::Long(request_id),
             JValue::Long(sender_ptr),
         ],
     )
@@ -219,5 +302,62 @@ pub fn authenticate_with_context(
         BiometricError::PlatformError(format!("authenticate call: {e}"))
     })?;
 
+    // Cancelling the prompt's CancellationSignal dismisses it
+    cancel.on_cancel(move || {
+        let Ok(mut env) = vm.attach_current_thread() else {
+            return;
+        };
+        if let Ok(class) = get_helper_class(&mut env) {
+            let _ = env.call_static_method(class, "cancel", "(J)V", &[JValue::Long(request_id)]);
+        }
+    });
+
     Ok(rx)
 }
+
+pub fn is_enrolled_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<bool, BiometricError> {
+    init(env, context)?;
+
+    let class = get_helper_class(env)?;
+    env.call_static_method(
+        class,
+        "isEnrolled",
+        "(Landroid/content/Context;)Z",
+        &[JValue::Object(context)],
+    )
+    .map_err(|e| BiometricError::PlatformError(format!("isEnrolled call: {e}")))?
+    .z()
+    .map_err(|e| BiometricError::PlatformError(format!("isEnrolled res: {e}")))
+}
+
+pub fn get_enrolled_types_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<Vec<BiometricType>, BiometricError> {
+    init(env, context)?;
+
+    let class = get_helper_class(env)?;
+    let mask = env
+        .call_static_method(
+            class,
+            "getEnrolledTypes",
+            "(Landroid/content/Context;)I",
+            &[JValue::Object(context)],
+        )
+        .map_err(|e| BiometricError::PlatformError(format!("getEnrolledTypes call: {e}")))?
+        .i()
+        .map_err(|e| BiometricError::PlatformError(format!("getEnrolledTypes res: {e}")))?;
+
+    Ok([
+        (1, BiometricType::Fingerprint),
+        (2, BiometricType::Face),
+        (4, BiometricType::Iris),
+    ]
+    .into_iter()
+    .filter(|(bit, _)| mask & bit != 0)
+    .map(|(_, kind)| kind)
+    .collect())
+}
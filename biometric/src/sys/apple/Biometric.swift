@@ -24,11 +24,57 @@ public func biometric_get_type() -> UInt8 {
     return 0
 }
 
-public func biometric_authenticate(reason: RustStr, callback: BiometricCallback) {
+public func biometric_is_enrolled() -> Bool {
+    let context = LAContext()
+    var error: NSError?
+    if context.canEvaluatePolicy(.deviceOwnerAuthenticationWithBiometrics, error: &error) {
+        return true
+    }
+    guard let error = error, error.domain == LAError.errorDomain else {
+        return false
+    }
+    switch LAError.Code(rawValue: error.code) {
+    case .biometryNotEnrolled, .biometryNotAvailable:
+        return false
+    case .biometryLockout:
+        // Locked out after too many failures, but still enrolled
+        return true
+    default:
+        return false
+    }
+}
+
+/// Contexts of prompts still showing, so they can be invalidated.
+private var contexts: [UInt64: LAContext] = [:]
+private let contextsLock = NSLock()
+
+public func biometric_authenticate(
+    id: UInt64,
+    reason: RustStr,
+    allow_passcode: Bool,
+    cancel_title: RustStr,
+    callback: BiometricCallback
+) {
     let context = LAContext()
     let reasonStr = reason.toString()
+    let cancelTitle = cancel_title.toString()
+    if !cancelTitle.isEmpty {
+        context.localizedCancelTitle = cancelTitle
+    }
+
+    // deviceOwnerAuthentication falls back to the passcode when biometry fails
+    let policy: LAPolicy = allow_passcode
+        ? .deviceOwnerAuthentication
+        : .deviceOwnerAuthenticationWithBiometrics
 
-    context.evaluatePolicy(.deviceOwnerAuthenticationWithBiometrics, localizedReason: reasonStr) { success, authenticationError in
+    contextsLock.lock()
+    contexts[id] = context
+    contextsLock.unlock()
+
+    context.evaluatePolicy(policy, localizedReason: reasonStr) { success, authenticationError in
+        contextsLock.lock()
+        contexts.removeValue(forKey: id)
+        contextsLock.unlock()
         if success {
             callback.on_success()
         } else {
@@ -37,3 +83,11 @@ public func biometric_authenticate(reason: RustStr, callback: BiometricCallback)
         }
     }
 }
+
+/// Dismiss the prompt, which then fails with `LAError.appCancel`.
+public func biometric_cancel(id: UInt64) {
+    contextsLock.lock()
+    let context = contexts.removeValue(forKey: id)
+    contextsLock.unlock()
+    context?.invalidate()
+}
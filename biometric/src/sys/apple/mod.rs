@@ -1,6 +1,7 @@
 //! Apple platform (iOS/macOS) biometric implementation using swift-bridge.
 
-use crate::{BiometricError, BiometricType};
+use crate::{AuthCancelToken, AuthOptions, BiometricError, BiometricType};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 #[swift_bridge::bridge]
 mod ffi {
@@ -17,11 +18,26 @@ mod ffi {
         #[swift_bridge(rust_name = "biometric_get_type")]
         fn biometric_get_type() -> u8; // 0: None, 1: TouchID, 2: FaceID, 3: OpticID
 
+        #[swift_bridge(rust_name = "biometric_is_enrolled")]
+        fn biometric_is_enrolled() -> bool;
+
         #[swift_bridge(rust_name = "biometric_authenticate")]
-        fn biometric_authenticate(reason: &str, callback: BiometricCallback);
+        fn biometric_authenticate(
+            id: u64,
+            reason: &str,
+            allow_passcode: bool,
+            cancel_title: &str,
+            callback: BiometricCallback,
+        );
+
+        #[swift_bridge(rust_name = "biometric_cancel")]
+        fn biometric_cancel(id: u64);
     }
 }
 
+/// Identifies a prompt to Swift, so it can be cancelled.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
 /// A callback structure for biometric authentication results.
 pub struct BiometricCallback {
     sender: tokio::sync::oneshot::Sender<Result<(), BiometricError>>,
@@ -54,20 +70,56 @@ pub async fn get_biometric_type() -> Option<BiometricType> {
     }
 }
 
+/// Check if biometrics are enrolled on Apple platforms.
+#[allow(clippy::unused_async)]
+pub async fn is_enrolled() -> bool {
+    ffi::biometric_is_enrolled()
+}
+
+/// Get the enrolled biometric types on Apple platforms.
+///
+/// Apple devices have a single biometry sensor, so this holds at most one type.
+pub async fn get_enrolled_types() -> Vec<BiometricType> {
+    if !is_enrolled().await {
+        return Vec::new();
+    }
+    get_biometric_type().await.into_iter().collect()
+}
+
 /// Perform biometric authentication on Apple platforms.
 ///
+/// With `allow_device_passcode`, evaluates `deviceOwnerAuthentication` so the
+/// user can fall back to their passcode.
+///
 /// # Errors
-/// Returns `BiometricError::NotAvailable` if biometrics are not ready,
-/// or `BiometricError::PlatformError` if the channel fails.
-pub async fn authenticate(reason: &str) -> Result<(), BiometricError> {
-    if !is_available().await {
+/// Returns `BiometricError::NotAvailable` if biometrics are not ready and no
+/// passcode fallback is allowed, or `BiometricError::PlatformError` if the
+/// channel fails.
+pub async fn authenticate(
+    reason: &str,
+    options: &AuthOptions,
+    cancel: &AuthCancelToken,
+) -> Result<(), BiometricError> {
+    if !options.allow_device_passcode && !is_available().await {
         return Err(BiometricError::NotAvailable);
     }
+    if cancel.is_cancelled() {
+        return Err(BiometricError::Cancelled);
+    }
 
     let (tx, rx) = tokio::sync::oneshot::channel();
     let callback = BiometricCallback { sender: tx };
 
-    ffi::biometric_authenticate(reason, callback);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    ffi::biometric_authenticate(
+        id,
+        reason,
+        options.allow_device_passcode,
+        options.cancel_title.as_deref().unwrap_or_default(),
+        callback,
+    );
+    // Invalidating the LAContext dismisses its prompt
+    cancel.on_cancel(move || ffi::biometric_cancel(id));
 
     rx.await
         .unwrap_or_else(|_| Err(BiometricError::PlatformError("Channel closed".to_string())))
@@ -20,19 +20,36 @@ pub use windows::*;
     target_os = "windows"
 )))]
 pub mod stub {
-    use crate::{BiometricError, BiometricType};
+    use crate::{AuthCancelToken, AuthOptions, BiometricError, BiometricType};
 
+    #[allow(clippy::unused_async)]
     pub async fn is_available() -> bool {
         false
     }
 
-    pub async fn authenticate(_reason: &str) -> Result<(), BiometricError> {
+    #[allow(clippy::unused_async)]
+    pub async fn authenticate(
+        _reason: &str,
+        _options: &AuthOptions,
+        _cancel: &AuthCancelToken,
+    ) -> Result<(), BiometricError> {
         Err(BiometricError::NotAvailable)
     }
 
+    #[allow(clippy::unused_async)]
     pub async fn get_biometric_type() -> Option<BiometricType> {
         None
     }
+
+    #[allow(clippy::unused_async)]
+    pub async fn is_enrolled() -> bool {
+        false
+    }
+
+    #[allow(clippy::unused_async)]
+    pub async fn get_enrolled_types() -> Vec<BiometricType> {
+        Vec::new()
+    }
 }
 #[cfg(not(any(
     target_os = "ios",
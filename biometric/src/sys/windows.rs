@@ -1,8 +1,17 @@
-use crate::{BiometricError, BiometricType};
-use windows::Foundation::IAsyncOperation;
+//! Windows Hello through `UserConsentVerifier`.
+//!
+//! `UserConsentVerifier` doesn't say which sensor it will use, so the
+//! biometric type comes from the sensors the Windows Biometric Framework
+//! reports.
+
+use crate::{AuthCancelToken, AuthOptions, BiometricError, BiometricType};
 use windows::Security::Credentials::UI::{
     UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
 };
+use windows::Win32::Devices::BiometricFramework::{
+    WINBIO_TYPE_FACIAL_FEATURES, WINBIO_TYPE_FINGERPRINT, WINBIO_TYPE_IRIS, WINBIO_UNIT_SCHEMA,
+    WinBioEnumBiometricUnits, WinBioFree,
+};
 
 pub async fn is_available() -> bool {
     let availability = match UserConsentVerifier::CheckAvailabilityAsync() {
@@ -16,43 +25,103 @@ pub async fn is_available() -> bool {
     availability == UserConsentVerifierAvailability::Available
 }
 
+/// Biometric sensors attached to the system, face first as Windows Hello
+/// prefers it when several are set up.
+fn sensor_types() -> Vec<BiometricType> {
+    [
+        (WINBIO_TYPE_FACIAL_FEATURES, BiometricType::Face),
+        (WINBIO_TYPE_FINGERPRINT, BiometricType::Fingerprint),
+        (WINBIO_TYPE_IRIS, BiometricType::Iris),
+    ]
+    .into_iter()
+    .filter(|&(factor, _)| {
+        let mut units: *mut WINBIO_UNIT_SCHEMA = std::ptr::null_mut();
+        let mut count = 0;
+        // SAFETY: both out-pointers are valid; the array is freed below
+        let found = unsafe { WinBioEnumBiometricUnits(factor, &raw mut units, &raw mut count) }
+            .is_ok()
+            && count > 0;
+        if !units.is_null() {
+            // SAFETY: `units` was allocated by WinBioEnumBiometricUnits
+            let _ = unsafe { WinBioFree(units.cast()) };
+        }
+        found
+    })
+    .map(|(_, kind)| kind)
+    .collect()
+}
+
 pub async fn get_biometric_type() -> Option<BiometricType> {
     if is_available().await {
-        // Windows Hello encompasses Face, Fingerprint, PIN.
-        // UserConsentVerifier doesn't easily distinguish precisely between Face/Fingerprint without more complex APIs.
-        // Usually it's treated as "Windows Hello" / "Biometrics".
-        // We'll return Fingerprint as a generic placeholder or Unknown if we want to be strict.
-        // But for now, let's say Unknown because we don't know if it's Face or Finger.
-        // Or we could try to guess, but it's not exposed in this API.
-        Some(BiometricType::Unknown)
+        // Windows Hello set up with only a PIN has no sensor to report
+        Some(
+            sensor_types()
+                .first()
+                .copied()
+                .unwrap_or(BiometricType::Unknown),
+        )
     } else {
         None
     }
 }
 
-pub async fn authenticate(reason: &str) -> Result<(), BiometricError> {
+pub async fn is_enrolled() -> bool {
+    // `CheckAvailabilityAsync` reports `NotConfiguredForUser` when Windows Hello
+    // is supported but not set up, so availability implies enrollment.
+    is_available().await
+}
+
+// The framework only lists enrollments to elevated processes, so every
+// sensor counts once Windows Hello is set up
+pub async fn get_enrolled_types() -> Vec<BiometricType> {
+    if !is_available().await {
+        return Vec::new();
+    }
+    let types = sensor_types();
+    if types.is_empty() {
+        vec![BiometricType::Unknown]
+    } else {
+        types
+    }
+}
+
+// Windows Hello always offers the PIN and has a fixed cancel button
+pub async fn authenticate(
+    reason: &str,
+    _options: &AuthOptions,
+    cancel: &AuthCancelToken,
+) -> Result<(), BiometricError> {
     if !is_available().await {
         return Err(BiometricError::NotAvailable);
     }
+    if cancel.is_cancelled() {
+        return Err(BiometricError::Cancelled);
+    }
 
-    // Convert reason to HSTRING which is handled automatically by windows-rs for &str usually?
-    // Actually RequestVerificationAsync takes HSTRING.
-    let result =
+    let operation =
         UserConsentVerifier::RequestVerificationAsync(&windows::core::HSTRING::from(reason))
-            .map_err(|e| BiometricError::PlatformError(e.to_string()))?
-            .await
             .map_err(|e| BiometricError::PlatformError(e.to_string()))?;
+    // Cancelling the operation closes the Windows Hello dialog
+    let pending = operation.clone();
+    cancel.on_cancel(move || {
+        let _ = pending.Cancel();
+    });
+    let result = operation
+        .await
+        .map_err(|e| BiometricError::PlatformError(e.to_string()))?;
 
     match result {
         UserConsentVerificationResult::Verified => Ok(()),
         UserConsentVerificationResult::Canceled => Err(BiometricError::Cancelled),
+        UserConsentVerificationResult::DeviceNotPresent
+        | UserConsentVerificationResult::NotConfiguredForUser
+        | UserConsentVerificationResult::DisabledByPolicy => Err(BiometricError::NotAvailable),
         UserConsentVerificationResult::DeviceBusy => {
             Err(BiometricError::Failed("Device busy".into()))
         }
         UserConsentVerificationResult::RetriesExhausted => {
             Err(BiometricError::Failed("Retries exhausted".into()))
         }
-        UserConsentVerificationResult::DisabledByPolicy => Err(BiometricError::NotAvailable), // Or failed
         _ => Err(BiometricError::Failed("Verification failed".into())),
     }
 }
@@ -1,6 +1,6 @@
 //! Android camera implementation using Camera2 API via JNI.
 
-use crate::{CameraError, CameraFrame, CameraInfo, FrameFormat, Resolution};
+use crate::{CameraError, CameraFrame, CameraInfo, FrameFormat, PhotoFormat, Resolution};
 use jni::JNIEnv;
 use jni::objects::{GlobalRef, JObject, JString, JValue, JClass};
 use std::sync::{Arc, Mutex, OnceLock};
@@ -364,7 +364,10 @@ impl CameraInner {
         false
     }
 
-    pub fn take_photo(&mut self) -> Result<CameraFrame, CameraError> {
+    pub fn take_photo(&mut self, format: PhotoFormat) -> Result<CameraFrame, CameraError> {
+        if format == PhotoFormat::Heic {
+            return Err(CameraError::NotSupported);
+        }
         self.get_frame() // Just take next frame for now
     }
 
@@ -450,12 +450,24 @@ class PhotoCaptureDelegate: NSObject, AVCapturePhotoCaptureDelegate {
     }
 }
 
-func camera_take_photo() -> CameraResultFFI {
+func camera_take_photo(heic: Bool) -> CameraResultFFI {
     guard let output = photoOutput else {
         return .NotSupported
     }
     
-    let settings = AVCapturePhotoSettings()
+    let settings: AVCapturePhotoSettings
+    if heic {
+        // HEVC photo capture needs a hardware encoder
+        guard output.availablePhotoCodecTypes.contains(.hevc) else {
+            return .NotSupported
+        }
+        settings = AVCapturePhotoSettings(format: [AVVideoCodecKey: AVVideoCodecType.hevc])
+    } else if output.availablePhotoCodecTypes.contains(.jpeg) {
+        // The default settings pick HEVC where available
+        settings = AVCapturePhotoSettings(format: [AVVideoCodecKey: AVVideoCodecType.jpeg])
+    } else {
+        settings = AVCapturePhotoSettings()
+    }
     #if os(iOS)
     settings.isHighResolutionPhotoEnabled = true
     #endif
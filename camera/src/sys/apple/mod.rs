@@ -2,7 +2,7 @@
 //!
 //! Uses Metal texture interop for zero-copy frame rendering with wgpu.
 
-use crate::{CameraError, CameraFrame, CameraInfo, FrameFormat, Resolution};
+use crate::{CameraError, CameraFrame, CameraInfo, FrameFormat, PhotoFormat, Resolution};
 use std::sync::{Arc, Mutex};
 
 #[swift_bridge::bridge]
@@ -48,7 +48,7 @@ mod ffi {
         fn camera_set_hdr(enabled: bool) -> CameraResultFFI;
         fn camera_get_hdr() -> bool;
 
-        fn camera_take_photo() -> CameraResultFFI;
+        fn camera_take_photo(heic: bool) -> CameraResultFFI;
         fn camera_get_photo_len() -> i32;
         fn camera_start_recording(path: String) -> CameraResultFFI;
         fn camera_stop_recording() -> CameraResultFFI;
@@ -320,8 +320,9 @@ impl CameraInner {
     ///
     /// # Errors
     /// Returns a `CameraError` if the photo cannot be taken.
-    pub fn take_photo(&self) -> Result<CameraFrame, CameraError> {
-        convert_result(ffi::camera_take_photo(), "take_photo")?;
+    pub fn take_photo(&self, format: PhotoFormat) -> Result<CameraFrame, CameraError> {
+        let heic = format == PhotoFormat::Heic;
+        convert_result(ffi::camera_take_photo(heic), "take_photo")?;
 
         let len = ffi::camera_get_photo_len();
         if len <= 0 {
@@ -335,14 +336,18 @@ impl CameraInner {
             camera_copy_photo_data(data.as_mut_ptr(), len as u64);
         }
 
-        // Return with current resolution (though JPEG might differ)
+        // Return with current resolution (though the photo might differ)
         let res = self.resolution();
 
         Ok(CameraFrame::new(
             data,
             res.width,
             res.height,
-            FrameFormat::Jpeg,
+            if heic {
+                FrameFormat::Heic
+            } else {
+                FrameFormat::Jpeg
+            },
             None,
         ))
     }
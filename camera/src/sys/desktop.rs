@@ -1,6 +1,6 @@
 //! Desktop camera implementation using nokhwa.
 
-use crate::{CameraError, CameraFrame, CameraInfo, FrameFormat, Resolution};
+use crate::{CameraError, CameraFrame, CameraInfo, FrameFormat, PhotoFormat, Resolution};
 use nokhwa::Camera as NokhwaCamera;
 use nokhwa::pixel_format::RgbFormat;
 use nokhwa::utils::{CameraIndex, RequestedFormat, RequestedFormatType};
@@ -127,7 +127,10 @@ impl CameraInner {
         false
     }
 
-    pub fn take_photo(&mut self) -> Result<CameraFrame, CameraError> {
+    pub fn take_photo(&mut self, format: PhotoFormat) -> Result<CameraFrame, CameraError> {
+        if format == PhotoFormat::Heic {
+            return Err(CameraError::NotSupported);
+        }
         // Desktop fallback: just get the next frame
         self.get_frame()
     }
@@ -30,7 +30,7 @@ pub use desktop::CameraInner;
     target_os = "linux"
 )))]
 mod fallback {
-    use crate::{CameraError, CameraFrame, CameraInfo, Resolution};
+    use crate::{CameraError, CameraFrame, CameraInfo, PhotoFormat, Resolution};
 
     #[derive(Debug)]
     pub struct CameraInner;
@@ -76,7 +76,7 @@ mod fallback {
             false
         }
 
-        pub fn take_photo(&self) -> Result<CameraFrame, CameraError> {
+        pub fn take_photo(&self, _format: PhotoFormat) -> Result<CameraFrame, CameraError> {
             Err(CameraError::NotSupported)
         }
 
@@ -0,0 +1,68 @@
+//! Typed clipboard change events.
+
+use crate::sys;
+use futures::{FutureExt, Stream, StreamExt, stream};
+use std::time::{Duration, SystemTime};
+
+/// How long to wait after a change for the writer to finish. Applications
+/// often write several formats one after another, each reported as a change.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The kind of content on the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ClipboardContentType {
+    /// Text, including HTML with a plain-text fallback.
+    Text,
+    /// An image.
+    Image,
+    /// File references, such as files copied in a file manager.
+    Files,
+    /// Something else, or nothing.
+    Other,
+}
+
+/// A change to the clipboard, from [`watch_clipboard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardEvent {
+    /// What the clipboard holds after the change.
+    pub content_type: ClipboardContentType,
+    /// When the change was noticed.
+    pub timestamp: SystemTime,
+}
+
+/// The kind of content currently on the clipboard.
+fn content_type() -> ClipboardContentType {
+    // File managers add the names as text and often an icon, and images
+    // come with a textual description, so check from most specific
+    if sys::get_files().is_ok_and(|files| !files.is_empty()) {
+        ClipboardContentType::Files
+    } else if sys::has_image() {
+        ClipboardContentType::Image
+    } else if sys::has_text() {
+        ClipboardContentType::Text
+    } else {
+        ClipboardContentType::Other
+    }
+}
+
+/// Watch for changes to the clipboard, with what it holds afterwards.
+///
+/// Builds on [`watch`](crate::watch), so changes are noticed the same way.
+/// Changes within 100ms of each other are reported as one event.
+pub fn watch_clipboard() -> impl Stream<Item = ClipboardEvent> {
+    stream::unfold(Box::pin(sys::watch()), |mut changes| async move {
+        changes.next().await?;
+        let timestamp = SystemTime::now();
+
+        futures_timer::Delay::new(DEBOUNCE).await;
+        // Swallow the changes that came in meanwhile
+        while changes.next().now_or_never() == Some(Some(())) {}
+
+        let event = ClipboardEvent {
+            content_type: content_type(),
+            timestamp,
+        };
+        Some((event, changes))
+    })
+}
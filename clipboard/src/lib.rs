@@ -5,9 +5,16 @@
 
 #![warn(missing_docs)]
 
+mod event;
 mod sys;
 
-pub use sys::{get_image, get_text, set_image, set_text};
+use std::path::PathBuf;
+
+pub use event::{ClipboardContentType, ClipboardEvent, watch_clipboard};
+pub use sys::{
+    clear, get_html, get_image, get_rtf, get_text, has_image, has_text, set_html, set_image,
+    set_rtf, set_text, watch,
+};
 
 /// Image data containing width, height, and raw RGBA bytes.
 #[derive(Debug, Clone)]
@@ -19,3 +26,43 @@ pub struct ImageData {
     /// Raw RGBA bytes of the image.
     pub bytes: std::borrow::Cow<'static, [u8]>,
 }
+
+/// Errors that can occur when accessing the clipboard.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ClipboardError {
+    /// The operation is not supported on this platform.
+    #[error("operation not supported on this platform")]
+    NotSupported,
+    /// An unknown error occurred.
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+/// Get the files on the clipboard, such as files copied in a file manager.
+///
+/// Paths are canonicalized; files that don't exist are returned as they
+/// were on the clipboard. Returns an empty list if the clipboard holds no
+/// files. Android only reads `file:` URIs, since `content:` URIs have no
+/// path.
+///
+/// # Errors
+/// Returns `ClipboardError::NotSupported` on Android without a `Context`
+/// (use `get_files_with_context`), or `ClipboardError::Unknown` if the
+/// clipboard can't be read.
+pub fn get_files() -> Result<Vec<PathBuf>, ClipboardError> {
+    Ok(sys::get_files()?
+        .into_iter()
+        .map(|path| path.canonicalize().unwrap_or(path))
+        .collect())
+}
+
+/// Put files on the clipboard, replacing its contents, so they can be
+/// pasted in a file manager.
+///
+/// # Errors
+/// Returns `ClipboardError::NotSupported` on iOS and Android, whose
+/// sandboxes keep other apps from opening the files. Returns
+/// `ClipboardError::Unknown` if the clipboard can't be written.
+pub fn set_files(paths: &[PathBuf]) -> Result<(), ClipboardError> {
+    sys::set_files(paths)
+}
@@ -4,10 +4,25 @@ import android.content.ClipData
 import android.content.ClipboardManager
 import android.content.Context
 import android.net.Uri
+import android.os.Build
 import java.io.ByteArrayOutputStream
+import java.util.concurrent.atomic.AtomicInteger
 
 class ClipboardHelper {
+    /** Counts the primary clip changes since the last [takeChanges]. */
+    private class Watch : ClipboardManager.OnPrimaryClipChangedListener {
+        val changes = AtomicInteger(0)
+
+        override fun onPrimaryClipChanged() {
+            changes.incrementAndGet()
+        }
+    }
+
     companion object {
+        /** Active watches, keyed by the ID returned from [startWatch]. */
+        private val watches = HashMap<Int, Watch>()
+        private val watchIds = AtomicInteger(1)
+
         @JvmStatic
         fun getText(context: Context): String? {
             val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
@@ -24,7 +39,43 @@ class ClipboardHelper {
             val clip = ClipData.newPlainText("text", text)
             clipboard?.setPrimaryClip(clip)
         }
+
+        @JvmStatic
+        fun getHtml(context: Context): String? {
+            val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
+            val clip = clipboard?.primaryClip
+            if (clip != null && clip.itemCount > 0) {
+                return clip.getItemAt(0).htmlText
+            }
+            return null
+        }
+
+        @JvmStatic
+        fun setHtml(context: Context, html: String, plain: String) {
+            val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
+            val clip = ClipData.newHtmlText("html", plain, html)
+            clipboard?.setPrimaryClip(clip)
+        }
         
+        @JvmStatic
+        fun clear(context: Context) {
+            val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
+                ?: return
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.P) {
+                clipboard.clearPrimaryClip()
+            } else {
+                clipboard.setPrimaryClip(ClipData.newPlainText("", ""))
+            }
+        }
+
+        // Checks the clip's MIME types without reading its items.
+        @JvmStatic
+        fun hasText(context: Context): Boolean {
+            val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
+            val description = clipboard?.primaryClipDescription ?: return false
+            return description.hasMimeType("text/*")
+        }
+
         @JvmStatic
         fun hasImage(context: Context): Boolean {
              val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
@@ -35,6 +86,18 @@ class ClipboardHelper {
              return false
         }
 
+        /** Paths of the `file:` URIs in the primary clip. */
+        @JvmStatic
+        fun getFiles(context: Context): Array<String> {
+            val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
+            val clip = clipboard?.primaryClip ?: return emptyArray()
+            return (0 until clip.itemCount)
+                .mapNotNull { clip.getItemAt(it).uri }
+                .filter { it.scheme == "file" }
+                .mapNotNull { it.path }
+                .toTypedArray()
+        }
+
         @JvmStatic
         fun getImage(context: Context): ByteArray? {
             val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
@@ -60,6 +123,39 @@ class ClipboardHelper {
             return null
         }
 
+        /**
+         * Register a primary clip listener. Returns the watch ID, or 0 if
+         * there is no clipboard service.
+         *
+         * Android 10+ only reports changes while the app is in the
+         * foreground or is the default input method.
+         */
+        @JvmStatic
+        fun startWatch(context: Context): Int {
+            val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
+                ?: return 0
+            val id = watchIds.getAndIncrement()
+            val watch = Watch()
+            synchronized(watches) { watches[id] = watch }
+            clipboard.addPrimaryClipChangedListener(watch)
+            return id
+        }
+
+        /** Take the number of changes watch [id] saw since the last call. */
+        @JvmStatic
+        fun takeChanges(id: Int): Int {
+            val watch = synchronized(watches) { watches[id] } ?: return 0
+            return watch.changes.getAndSet(0)
+        }
+
+        /** Unregister the listener of watch [id]. */
+        @JvmStatic
+        fun stopWatch(context: Context, id: Int) {
+            val watch = synchronized(watches) { watches.remove(id) } ?: return
+            val clipboard = context.getSystemService(Context.CLIPBOARD_SERVICE) as? ClipboardManager
+            clipboard?.removePrimaryClipChangedListener(watch)
+        }
+
         // setImage is complex without FileProvider, skipping for now or implementing later.
     }
 }
@@ -1,12 +1,18 @@
-use crate::ImageData;
-use jni::JNIEnv;
-use jni::objects::{GlobalRef, JByteArray, JObject, JString, JValue};
+use crate::{ClipboardError, ImageData};
+use futures::{Stream, stream};
+use jni::objects::{GlobalRef, JByteArray, JObject, JObjectArray, JString, JValue};
+use jni::{JNIEnv, JavaVM};
 use std::borrow::Cow;
+use std::path::PathBuf;
 use std::sync::OnceLock;
+use std::time::Duration;
 
 static DEX_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/classes.dex"));
 static CLASS_LOADER: OnceLock<GlobalRef> = OnceLock::new();
 
+/// How often a watch checks its listener for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
 pub fn init_with_context(env: &mut JNIEnv, context: &JObject) -> Result<(), String> {
     if CLASS_LOADER.get().is_some() {
         return Ok(());
@@ -140,6 +146,65 @@ pub fn set_text_with_context(
     Ok(())
 }
 
+pub fn get_html_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<Option<String>, String> {
+    init_with_context(env, context)?;
+    let helper_class = get_helper_class(env)?;
+
+    let result = env
+        .call_static_method(
+            helper_class,
+            "getHtml",
+            "(Landroid/content/Context;)Ljava/lang/String;",
+            &[JValue::Object(context)],
+        )
+        .map_err(|e| format!("JNI error getHtml: {e}"))?;
+
+    let obj = result.l().map_err(|e| format!("JNI error result: {e}"))?;
+    if obj.is_null() {
+        Ok(None)
+    } else {
+        let jstring = unsafe { JString::from_raw(obj.into_raw()) };
+        let html = env
+            .get_string(&jstring)
+            .map_err(|e| format!("JNI error get_string: {e}"))?;
+        Ok(Some(html.into()))
+    }
+}
+
+pub fn set_html_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    html: &str,
+    plain_fallback: &str,
+) -> Result<(), String> {
+    init_with_context(env, context)?;
+    let helper_class = get_helper_class(env)?;
+
+    let jhtml = env
+        .new_string(html)
+        .map_err(|e| format!("JNI error new_string: {e}"))?;
+    let jplain = env
+        .new_string(plain_fallback)
+        .map_err(|e| format!("JNI error new_string: {e}"))?;
+
+    env.call_static_method(
+        helper_class,
+        "setHtml",
+        "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;)V",
+        &[
+            JValue::Object(context),
+            JValue::Object(&jhtml),
+            JValue::Object(&jplain),
+        ],
+    )
+    .map_err(|e| format!("JNI error setHtml: {e}"))?;
+
+    Ok(())
+}
+
 pub fn get_image_with_context(
     env: &mut JNIEnv,
     context: &JObject,
@@ -188,6 +253,171 @@ pub fn set_image_with_context(
     Err("set_image not implemented on Android".into())
 }
 
+/// Get the paths of the `file:` URIs on the clipboard. `content:` URIs,
+/// which most apps share, have no path and are left out.
+pub fn get_files_with_context(env: &mut JNIEnv, context: &JObject) -> Result<Vec<PathBuf>, String> {
+    init_with_context(env, context)?;
+    let helper_class = get_helper_class(env)?;
+
+    let result = env
+        .call_static_method(
+            helper_class,
+            "getFiles",
+            "(Landroid/content/Context;)[Ljava/lang/String;",
+            &[JValue::Object(context)],
+        )
+        .map_err(|e| format!("JNI error getFiles: {e}"))?;
+
+    let obj = result.l().map_err(|e| format!("JNI error result: {e}"))?;
+    let array = JObjectArray::from(obj);
+    let len = env
+        .get_array_length(&array)
+        .map_err(|e| format!("JNI error get_array_length: {e}"))?;
+    let mut files = Vec::new();
+    for index in 0..len {
+        let element = env
+            .get_object_array_element(&array, index)
+            .map_err(|e| format!("JNI error get_object_array_element: {e}"))?;
+        let path: String = env
+            .get_string(&JString::from(element))
+            .map_err(|e| format!("JNI error get_string: {e}"))?
+            .into();
+        files.push(PathBuf::from(path));
+    }
+    Ok(files)
+}
+
+pub fn clear_with_context(env: &mut JNIEnv, context: &JObject) -> Result<(), String> {
+    init_with_context(env, context)?;
+    let helper_class = get_helper_class(env)?;
+
+    env.call_static_method(
+        helper_class,
+        "clear",
+        "(Landroid/content/Context;)V",
+        &[JValue::Object(context)],
+    )
+    .map_err(|e| format!("JNI error clear: {e}"))?;
+
+    Ok(())
+}
+
+pub fn has_text_with_context(env: &mut JNIEnv, context: &JObject) -> Result<bool, String> {
+    init_with_context(env, context)?;
+    let helper_class = get_helper_class(env)?;
+
+    env.call_static_method(
+        helper_class,
+        "hasText",
+        "(Landroid/content/Context;)Z",
+        &[JValue::Object(context)],
+    )
+    .and_then(|v| v.z())
+    .map_err(|e| format!("JNI error hasText: {e}"))
+}
+
+pub fn has_image_with_context(env: &mut JNIEnv, context: &JObject) -> Result<bool, String> {
+    init_with_context(env, context)?;
+    let helper_class = get_helper_class(env)?;
+
+    env.call_static_method(
+        helper_class,
+        "hasImage",
+        "(Landroid/content/Context;)Z",
+        &[JValue::Object(context)],
+    )
+    .and_then(|v| v.z())
+    .map_err(|e| format!("JNI error hasImage: {e}"))
+}
+
+/// A primary clip listener registered by the helper.
+///
+/// Dropping it unregisters the listener.
+struct ClipboardWatch {
+    vm: JavaVM,
+    context: GlobalRef,
+    id: i32,
+}
+
+impl ClipboardWatch {
+    /// Take the number of changes since the last call.
+    fn take_changes(&self) -> Result<i32, String> {
+        let mut env = self
+            .vm
+            .attach_current_thread()
+            .map_err(|e| format!("JNI error attach_current_thread: {e}"))?;
+        let helper_class = get_helper_class(&mut env)?;
+
+        env.call_static_method(helper_class, "takeChanges", "(I)I", &[JValue::Int(self.id)])
+            .and_then(|v| v.i())
+            .map_err(|e| format!("JNI error takeChanges: {e}"))
+    }
+}
+
+impl Drop for ClipboardWatch {
+    fn drop(&mut self) {
+        let Ok(mut env) = self.vm.attach_current_thread() else {
+            return;
+        };
+        let Ok(helper_class) = get_helper_class(&mut env) else {
+            return;
+        };
+        let _ = env.call_static_method(
+            helper_class,
+            "stopWatch",
+            "(Landroid/content/Context;I)V",
+            &[JValue::Object(self.context.as_obj()), JValue::Int(self.id)],
+        );
+    }
+}
+
+/// Watch for changes to the clipboard.
+///
+/// Yields when the primary clip changes, using an
+/// `OnPrimaryClipChangedListener`. Android 10+ only reports changes while
+/// the app is in the foreground or is the default input method.
+pub fn watch_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<impl Stream<Item = ()> + use<>, String> {
+    init_with_context(env, context)?;
+    let vm = env
+        .get_java_vm()
+        .map_err(|e| format!("JNI error get_java_vm: {e}"))?;
+    let global_context = env
+        .new_global_ref(context)
+        .map_err(|e| format!("JNI error new_global_ref: {e}"))?;
+    let helper_class = get_helper_class(env)?;
+
+    let id = env
+        .call_static_method(
+            helper_class,
+            "startWatch",
+            "(Landroid/content/Context;)I",
+            &[JValue::Object(context)],
+        )
+        .and_then(|v| v.i())
+        .map_err(|e| format!("JNI error startWatch: {e}"))?;
+    if id == 0 {
+        return Err("Clipboard service not available".into());
+    }
+
+    let watch = ClipboardWatch {
+        vm,
+        context: global_context,
+        id,
+    };
+
+    Ok(stream::unfold(watch, |watch| async move {
+        loop {
+            futures_timer::Delay::new(POLL_INTERVAL).await;
+            if watch.take_changes().ok()? > 0 {
+                return Some(((), watch));
+            }
+        }
+    }))
+}
+
 // Public API stubs
 pub fn get_text() -> Option<String> {
     eprintln!("Android: use get_text_with_context");
@@ -198,6 +428,35 @@ pub fn set_text(_text: String) {
     eprintln!("Android: use set_text_with_context");
 }
 
+pub fn get_html() -> Result<Option<String>, ClipboardError> {
+    eprintln!("Android: use get_html_with_context");
+    Err(ClipboardError::NotSupported)
+}
+
+pub fn set_html(_html: &str, _plain_fallback: &str) -> Result<(), ClipboardError> {
+    eprintln!("Android: use set_html_with_context");
+    Err(ClipboardError::NotSupported)
+}
+
+// ClipData has no RTF flavor
+pub const fn get_rtf() -> Result<Option<String>, ClipboardError> {
+    Err(ClipboardError::NotSupported)
+}
+
+pub const fn set_rtf(_rtf: &str) -> Result<(), ClipboardError> {
+    Err(ClipboardError::NotSupported)
+}
+
+pub fn get_files() -> Result<Vec<PathBuf>, ClipboardError> {
+    eprintln!("Android: use get_files_with_context");
+    Err(ClipboardError::NotSupported)
+}
+
+// Other apps can't open files in this app's sandbox
+pub const fn set_files(_paths: &[PathBuf]) -> Result<(), ClipboardError> {
+    Err(ClipboardError::NotSupported)
+}
+
 pub fn get_image() -> Option<ImageData> {
     eprintln!("Android: use get_image_with_context");
     None
@@ -206,3 +465,22 @@ pub fn get_image() -> Option<ImageData> {
 pub fn set_image(_image: ImageData) {
     eprintln!("Android: use set_image_with_context");
 }
+
+pub fn clear() {
+    eprintln!("Android: use clear_with_context");
+}
+
+pub fn has_text() -> bool {
+    eprintln!("Android: use has_text_with_context");
+    false
+}
+
+pub fn has_image() -> bool {
+    eprintln!("Android: use has_image_with_context");
+    false
+}
+
+pub fn watch() -> impl Stream<Item = ()> {
+    eprintln!("Android: use watch_with_context");
+    stream::empty()
+}
@@ -36,6 +36,59 @@ public func clipboard_set_text(text: RustString) {
     #endif
 }
 
+public func clipboard_get_html() -> Optional<String> {
+    #if os(iOS)
+    guard let data = UIPasteboard.general.data(forPasteboardType: "public.html") else {
+        return nil
+    }
+    return String(data: data, encoding: .utf8)
+    #elseif os(macOS)
+    return NSPasteboard.general.string(forType: .html)
+    #endif
+}
+
+// Both flavors go into one item, so apps that only read text get the fallback.
+// UIPasteboard reports no failure; NSPasteboard does per flavor.
+public func clipboard_set_html(html: RustString, plain: RustString) -> Bool {
+    let swiftHtml = html.toString()
+    let swiftPlain = plain.toString()
+    #if os(iOS)
+    UIPasteboard.general.setItems([[
+        "public.html": swiftHtml,
+        "public.utf8-plain-text": swiftPlain,
+    ]])
+    return true
+    #elseif os(macOS)
+    let pb = NSPasteboard.general
+    pb.clearContents()
+    pb.declareTypes([.html, .string], owner: nil)
+    return pb.setString(swiftHtml, forType: .html)
+        && pb.setString(swiftPlain, forType: .string)
+    #endif
+}
+
+public func clipboard_get_rtf() -> Optional<String> {
+    #if os(iOS)
+    let data = UIPasteboard.general.data(forPasteboardType: "public.rtf")
+    #elseif os(macOS)
+    let data = NSPasteboard.general.data(forType: .rtf)
+    #endif
+    guard let data else { return nil }
+    return String(data: data, encoding: .utf8)
+}
+
+public func clipboard_set_rtf(rtf: RustString) -> Bool {
+    let data = Data(rtf.toString().utf8)
+    #if os(iOS)
+    UIPasteboard.general.setData(data, forPasteboardType: "public.rtf")
+    return true
+    #elseif os(macOS)
+    let pb = NSPasteboard.general
+    pb.clearContents()
+    return pb.setData(data, forType: .rtf)
+    #endif
+}
+
 public func clipboard_get_image() -> SwiftImageData {
     #if os(iOS)
     guard let image = UIPasteboard.general.image else {
@@ -129,3 +182,81 @@ public func clipboard_set_image(image: SwiftImageData) {
     pb.writeObjects([nsImage])
     #endif
 }
+
+public func clipboard_get_files(files: FileListRefMut) {
+    #if os(iOS)
+    // Item providers load asynchronously; wait for every file URL, keeping
+    // them in pasteboard order
+    let providers = UIPasteboard.general.itemProviders
+        .filter { $0.hasItemConformingToTypeIdentifier("public.file-url") }
+    let group = DispatchGroup()
+    let lock = NSLock()
+    var loaded = [URL?](repeating: nil, count: providers.count)
+    for (index, provider) in providers.enumerated() {
+        group.enter()
+        provider.loadItem(forTypeIdentifier: "public.file-url", options: nil) { item, _ in
+            let url = (item as? URL) ?? (item as? Data).flatMap { URL(dataRepresentation: $0, relativeTo: nil) }
+            lock.lock()
+            loaded[index] = url
+            lock.unlock()
+            group.leave()
+        }
+    }
+    group.wait()
+    let urls = loaded.compactMap { $0 }.filter(\.isFileURL)
+    #elseif os(macOS)
+    let urls = NSPasteboard.general.readObjects(
+        forClasses: [NSURL.self],
+        options: [.urlReadingFileURLsOnly: true]
+    ) as? [URL] ?? []
+    #endif
+    for url in urls {
+        files.push(url.path)
+    }
+}
+
+public func clipboard_set_files(files: FileListRef) -> Bool {
+    #if os(iOS)
+    return false
+    #elseif os(macOS)
+    let urls = (0..<files.len()).map { index in
+        URL(fileURLWithPath: files.get(index).toString()) as NSURL
+    }
+    let pb = NSPasteboard.general
+    pb.clearContents()
+    return pb.writeObjects(urls)
+    #endif
+}
+
+public func clipboard_clear() {
+    #if os(iOS)
+    UIPasteboard.general.items = []
+    #elseif os(macOS)
+    NSPasteboard.general.clearContents()
+    #endif
+}
+
+// The presence checks only look at the declared types, without reading the data.
+public func clipboard_has_text() -> Bool {
+    #if os(iOS)
+    return UIPasteboard.general.hasStrings
+    #elseif os(macOS)
+    return NSPasteboard.general.canReadObject(forClasses: [NSString.self], options: nil)
+    #endif
+}
+
+public func clipboard_has_image() -> Bool {
+    #if os(iOS)
+    return UIPasteboard.general.hasImages
+    #elseif os(macOS)
+    return NSPasteboard.general.canReadObject(forClasses: [NSImage.self], options: nil)
+    #endif
+}
+
+public func clipboard_change_count() -> Int {
+    #if os(iOS)
+    return UIPasteboard.general.changeCount
+    #elseif os(macOS)
+    return NSPasteboard.general.changeCount
+    #endif
+}
@@ -1,7 +1,13 @@
 //! Apple platform (iOS/macOS) clipboard implementation using swift-bridge.
 
-use crate::ImageData;
+use crate::{ClipboardError, ImageData};
+use futures::{Stream, stream};
 use std::borrow::Cow;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How often [`watch`] polls the pasteboard's change count.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 #[swift_bridge::bridge]
 mod ffi {
@@ -13,11 +19,47 @@ mod ffi {
         is_valid: bool,
     }
 
+    extern "Rust" {
+        type FileList;
+        fn push(self: &mut FileList, path: String);
+        fn len(self: &FileList) -> usize;
+        fn get(self: &FileList, index: usize) -> String;
+    }
+
     extern "Swift" {
         fn clipboard_get_text() -> Option<String>;
         fn clipboard_set_text(text: String);
+        fn clipboard_get_html() -> Option<String>;
+        fn clipboard_set_html(html: String, plain: String) -> bool;
+        fn clipboard_get_rtf() -> Option<String>;
+        fn clipboard_set_rtf(rtf: String) -> bool;
         fn clipboard_get_image() -> SwiftImageData;
         fn clipboard_set_image(image: SwiftImageData);
+        fn clipboard_get_files(files: &mut FileList);
+        // Returns false on iOS, whose sandbox keeps other apps from the files
+        fn clipboard_set_files(files: &FileList) -> bool;
+        fn clipboard_clear();
+        fn clipboard_has_text() -> bool;
+        fn clipboard_has_image() -> bool;
+        fn clipboard_change_count() -> isize;
+    }
+}
+
+/// File paths passed between Rust and Swift.
+#[derive(Default)]
+pub struct FileList(Vec<String>);
+
+impl FileList {
+    fn push(&mut self, path: String) {
+        self.0.push(path);
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn get(&self, index: usize) -> String {
+        self.0[index].clone()
     }
 }
 
@@ -32,6 +74,51 @@ pub fn set_text(text: String) {
     ffi::clipboard_set_text(text);
 }
 
+/// Get HTML from the Apple system clipboard, or `None` if it holds none.
+///
+/// # Errors
+/// Never fails; the pasteboard is always readable.
+pub fn get_html() -> Result<Option<String>, ClipboardError> {
+    Ok(ffi::clipboard_get_html())
+}
+
+/// Set HTML to the Apple system clipboard as `public.html`, with a
+/// plain-text fallback for applications that don't read HTML.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the pasteboard rejects the data.
+pub fn set_html(html: &str, plain_fallback: &str) -> Result<(), ClipboardError> {
+    if ffi::clipboard_set_html(html.to_string(), plain_fallback.to_string()) {
+        Ok(())
+    } else {
+        Err(ClipboardError::Unknown(
+            "the pasteboard rejected the HTML".into(),
+        ))
+    }
+}
+
+/// Get RTF from the Apple system clipboard, or `None` if it holds none.
+///
+/// # Errors
+/// Never fails; the pasteboard is always readable.
+pub fn get_rtf() -> Result<Option<String>, ClipboardError> {
+    Ok(ffi::clipboard_get_rtf())
+}
+
+/// Set RTF to the Apple system clipboard as `public.rtf`.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the pasteboard rejects the data.
+pub fn set_rtf(rtf: &str) -> Result<(), ClipboardError> {
+    if ffi::clipboard_set_rtf(rtf.to_string()) {
+        Ok(())
+    } else {
+        Err(ClipboardError::Unknown(
+            "the pasteboard rejected the RTF".into(),
+        ))
+    }
+}
+
 /// Get image from the Apple system clipboard.
 #[must_use]
 pub fn get_image() -> Option<ImageData> {
@@ -56,3 +143,67 @@ pub fn set_image(image: ImageData) {
     };
     ffi::clipboard_set_image(swift_image);
 }
+
+/// Get the file URLs on the Apple system clipboard as paths.
+///
+/// # Errors
+/// Never fails; a pasteboard without files yields an empty list.
+pub fn get_files() -> Result<Vec<PathBuf>, ClipboardError> {
+    let mut files = FileList::default();
+    ffi::clipboard_get_files(&mut files);
+    Ok(files.0.into_iter().map(PathBuf::from).collect())
+}
+
+/// Put files on the macOS pasteboard as file URLs.
+///
+/// # Errors
+/// Returns `ClipboardError::NotSupported` on iOS.
+pub fn set_files(paths: &[PathBuf]) -> Result<(), ClipboardError> {
+    let files = FileList(
+        paths
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect(),
+    );
+    if ffi::clipboard_set_files(&files) {
+        Ok(())
+    } else {
+        Err(ClipboardError::NotSupported)
+    }
+}
+
+/// Remove everything from the Apple system clipboard.
+pub fn clear() {
+    ffi::clipboard_clear();
+}
+
+/// Check if the Apple system clipboard holds text.
+#[must_use]
+pub fn has_text() -> bool {
+    ffi::clipboard_has_text()
+}
+
+/// Check if the Apple system clipboard holds an image.
+#[must_use]
+pub fn has_image() -> bool {
+    ffi::clipboard_has_image()
+}
+
+/// Watch for changes to the Apple system clipboard.
+///
+/// Neither pasteboard notifies other apps of changes, so this polls its
+/// `changeCount` every 500ms and yields when it moves. Reading the count
+/// doesn't access the contents, so it shows no paste prompt on iOS. iOS
+/// suspends apps in the background, so changes made meanwhile are noticed
+/// once, when the app returns to the foreground.
+pub fn watch() -> impl Stream<Item = ()> {
+    stream::unfold(ffi::clipboard_change_count(), |last| async move {
+        loop {
+            futures_timer::Delay::new(POLL_INTERVAL).await;
+            let count = ffi::clipboard_change_count();
+            if count != last {
+                return Some(((), count));
+            }
+        }
+    })
+}
@@ -1,8 +1,9 @@
-use crate::ImageData;
+use crate::{ClipboardError, ImageData};
 use arboard::Clipboard;
 use std::borrow::Cow;
 
 /// Get text from the clipboard.
+#[must_use]
 pub fn get_text() -> Option<String> {
     Clipboard::new().ok()?.get_text().ok()
 }
@@ -14,7 +15,34 @@ pub fn set_text(text: String) {
     }
 }
 
+/// Get HTML from the clipboard, or `None` if it holds none.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the clipboard can't be read.
+pub fn get_html() -> Result<Option<String>, ClipboardError> {
+    let mut clipboard = Clipboard::new().map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+    match clipboard.get().html() {
+        Ok(html) => Ok(Some(html)),
+        Err(arboard::Error::ContentNotAvailable) => Ok(None),
+        Err(e) => Err(ClipboardError::Unknown(e.to_string())),
+    }
+}
+
+/// Set HTML to the clipboard, with a plain-text fallback for applications
+/// that don't read HTML.
+///
+/// On Windows the HTML is written as `CF_HTML`, on Linux as `text/html`.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the clipboard can't be written.
+pub fn set_html(html: &str, plain_fallback: &str) -> Result<(), ClipboardError> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_html(html, Some(plain_fallback)))
+        .map_err(|e| ClipboardError::Unknown(e.to_string()))
+}
+
 /// Get image from the clipboard.
+#[must_use]
 pub fn get_image() -> Option<ImageData> {
     let mut clipboard = Clipboard::new().ok()?;
     let image = clipboard.get_image().ok()?;
@@ -35,3 +63,25 @@ pub fn set_image(image: ImageData) {
         });
     }
 }
+
+/// Remove everything from the clipboard.
+pub fn clear() {
+    if let Ok(mut clipboard) = Clipboard::new() {
+        let _ = clipboard.clear();
+    }
+}
+
+/// Check if the clipboard holds text.
+#[must_use]
+pub fn has_text() -> bool {
+    get_text().is_some_and(|text| !text.is_empty())
+}
+
+/// Check if the clipboard holds an image.
+///
+/// `arboard` has no way to query the available formats, so this reads the
+/// image.
+#[must_use]
+pub fn has_image() -> bool {
+    Clipboard::new().is_ok_and(|mut clipboard| clipboard.get_image().is_ok())
+}
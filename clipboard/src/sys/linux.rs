@@ -0,0 +1,113 @@
+//! Linux clipboard features beyond `arboard`'s shared API.
+//!
+//! Neither X11 nor Wayland offers `arboard` a change event, so the text on
+//! the clipboard is polled. Files are read from and written to the
+//! `text/uri-list` target, and RTF, which `arboard` can't offer, goes
+//! through `x11-clipboard` as `text/richtext`.
+
+use crate::ClipboardError;
+use arboard::Clipboard;
+use futures::{Stream, stream};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+use x11_clipboard::Clipboard as X11Clipboard;
+
+/// How often the clipboard is polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The MIME type RTF is offered under.
+const RTF_TARGET: &str = "text/richtext";
+
+/// How long the owner of the clipboard has to hand over its RTF.
+const RTF_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Watch for changes to the clipboard.
+///
+/// Polls the clipboard text every 500ms and yields when it changes, so
+/// copying an image or the same text again is not noticed.
+pub fn watch() -> impl Stream<Item = ()> {
+    stream::unfold(super::get_text(), |last| async move {
+        loop {
+            futures_timer::Delay::new(POLL_INTERVAL).await;
+            let text = super::get_text();
+            if text != last {
+                return Some(((), text));
+            }
+        }
+    })
+}
+
+/// The X11 clipboard used for RTF, which `arboard` has no target for.
+///
+/// It's kept for the life of the process: its thread answers other
+/// applications' requests for the RTF until another owner takes over.
+fn rtf_clipboard() -> Result<&'static Mutex<X11Clipboard>, ClipboardError> {
+    static CLIPBOARD: OnceLock<Mutex<X11Clipboard>> = OnceLock::new();
+
+    if let Some(clipboard) = CLIPBOARD.get() {
+        return Ok(clipboard);
+    }
+    let clipboard = X11Clipboard::new().map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+    Ok(CLIPBOARD.get_or_init(|| Mutex::new(clipboard)))
+}
+
+/// Get RTF from the clipboard's `text/richtext` target, or `None` if it
+/// holds none.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the X11 clipboard can't be read.
+pub fn get_rtf() -> Result<Option<String>, ClipboardError> {
+    let clipboard = rtf_clipboard()?
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let atoms = &clipboard.getter.atoms;
+    let target = clipboard
+        .getter
+        .get_atom(RTF_TARGET)
+        .map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+    // An owner without the target refuses the conversion, leaving no data
+    let rtf = clipboard
+        .load(atoms.clipboard, target, atoms.property, RTF_TIMEOUT)
+        .map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+    drop(clipboard);
+    Ok((!rtf.is_empty()).then(|| String::from_utf8_lossy(&rtf).into_owned()))
+}
+
+/// Set RTF to the clipboard's `text/richtext` target, replacing its
+/// contents.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the X11 clipboard can't be written.
+pub fn set_rtf(rtf: &str) -> Result<(), ClipboardError> {
+    let clipboard = rtf_clipboard()?
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let target = clipboard
+        .setter
+        .get_atom(RTF_TARGET)
+        .map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+    clipboard
+        .store(clipboard.setter.atoms.clipboard, target, rtf)
+        .map_err(|e| ClipboardError::Unknown(e.to_string()))
+}
+
+/// Get the files on the clipboard, from its `text/uri-list`.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the clipboard can't be opened.
+pub fn get_files() -> Result<Vec<PathBuf>, ClipboardError> {
+    let mut clipboard = Clipboard::new().map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+    // Anything but a URI list means there are no files
+    Ok(clipboard.get().file_list().unwrap_or_default())
+}
+
+/// Put files on the clipboard as a `text/uri-list`, replacing its contents.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the clipboard can't be written.
+pub fn set_files(paths: &[PathBuf]) -> Result<(), ClipboardError> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set().file_list(paths))
+        .map_err(|e| ClipboardError::Unknown(e.to_string()))
+}
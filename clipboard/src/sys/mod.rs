@@ -6,6 +6,18 @@ pub mod desktop;
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 pub use desktop::*;
 
+#[cfg(target_os = "windows")]
+/// Windows clipboard change listener, RTF and files.
+mod windows;
+#[cfg(target_os = "windows")]
+pub use windows::{get_files, get_rtf, set_files, set_rtf, watch};
+
+#[cfg(target_os = "linux")]
+/// Linux clipboard change polling.
+mod linux;
+#[cfg(target_os = "linux")]
+pub use linux::{get_files, get_rtf, set_files, set_rtf, watch};
+
 #[cfg(target_os = "android")]
 /// Android platform backend.
 pub mod android;
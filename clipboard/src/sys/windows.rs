@@ -0,0 +1,294 @@
+//! Windows clipboard features beyond `arboard`: change notifications, RTF
+//! and files.
+//!
+//! A message-only window registered with `AddClipboardFormatListener`
+//! receives `WM_CLIPBOARDUPDATE`; the window lives on its own thread, which
+//! pumps its messages, and fans each update out to the open streams.
+
+use crate::ClipboardError;
+use futures::Stream;
+use std::ffi::OsString;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use windows::Win32::Foundation::{GlobalFree, HANDLE, HGLOBAL, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::DataExchange::{
+    AddClipboardFormatListener, CloseClipboard, EmptyClipboard, GetClipboardData, OpenClipboard,
+    RegisterClipboardFormatW, SetClipboardData,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Memory::{
+    GMEM_MOVEABLE, GMEM_ZEROINIT, GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock,
+};
+use windows::Win32::System::Ole::CF_HDROP;
+use windows::Win32::UI::Shell::{DROPFILES, DragQueryFileW, HDROP};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, HWND_MESSAGE, MSG,
+    RegisterClassW, TranslateMessage, WINDOW_EX_STYLE, WINDOW_STYLE, WM_CLIPBOARDUPDATE, WNDCLASSW,
+};
+use windows::core::w;
+
+/// Senders of the open [`watch`] streams.
+static WATCHERS: Mutex<Vec<async_channel::Sender<()>>> = Mutex::new(Vec::new());
+
+unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_CLIPBOARDUPDATE {
+        WATCHERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            // Drop the senders of streams that were dropped
+            .retain(|sender| !matches!(sender.try_send(()), Err(e) if e.is_closed()));
+        return LRESULT(0);
+    }
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+/// Start the listener window on first use. Returns whether it is running.
+fn start_listener() -> bool {
+    static RUNNING: OnceLock<bool> = OnceLock::new();
+
+    *RUNNING.get_or_init(|| {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let running = unsafe { create_listener_window() }.is_ok();
+            let _ = tx.send(running);
+            if !running {
+                return;
+            }
+            let mut msg = MSG::default();
+            while unsafe { GetMessageW(&raw mut msg, None, 0, 0) }.as_bool() {
+                unsafe {
+                    let _ = TranslateMessage(&raw const msg);
+                    DispatchMessageW(&raw const msg);
+                }
+            }
+        });
+        rx.recv().unwrap_or(false)
+    })
+}
+
+/// Create a message-only window and subscribe it to clipboard updates.
+unsafe fn create_listener_window() -> windows::core::Result<HWND> {
+    let instance = unsafe { GetModuleHandleW(None) }?;
+    let class_name = w!("WaterkitClipboardListener");
+    let class = WNDCLASSW {
+        lpfnWndProc: Some(window_proc),
+        hInstance: instance.into(),
+        lpszClassName: class_name,
+        ..WNDCLASSW::default()
+    };
+    unsafe {
+        RegisterClassW(&raw const class);
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            w!("Clipboard Listener"),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            Some(HWND_MESSAGE),
+            None,
+            Some(instance.into()),
+            None,
+        )?;
+        AddClipboardFormatListener(hwnd)?;
+        Ok(hwnd)
+    }
+}
+
+/// Watch for changes to the clipboard.
+///
+/// Yields once each time any application writes to the clipboard, using
+/// `AddClipboardFormatListener`. The stream ends immediately if the
+/// listener can't be started.
+pub fn watch() -> impl Stream<Item = ()> {
+    let (sender, receiver) = async_channel::unbounded();
+    if start_listener() {
+        WATCHERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(sender);
+    }
+    receiver
+}
+
+/// The clipboard format of RTF, registered by name by every RTF writer.
+fn rtf_format() -> u32 {
+    unsafe { RegisterClipboardFormatW(w!("Rich Text Format")) }
+}
+
+/// Get RTF from the clipboard, or `None` if it holds none.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the clipboard can't be read.
+pub fn get_rtf() -> Result<Option<String>, ClipboardError> {
+    let format = rtf_format();
+    unsafe {
+        OpenClipboard(None).map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+        // Anything but RTF means there is none
+        let rtf = GetClipboardData(format)
+            .ok()
+            .map(|handle| read_global(HGLOBAL(handle.0)))
+            .transpose();
+        let _ = CloseClipboard();
+        rtf
+    }
+}
+
+/// Copy a NUL-terminated string out of clipboard memory.
+unsafe fn read_global(memory: HGLOBAL) -> Result<String, ClipboardError> {
+    unsafe {
+        let data = GlobalLock(memory).cast::<u8>();
+        if data.is_null() {
+            return Err(ClipboardError::Unknown("failed to lock memory".into()));
+        }
+        let bytes = std::slice::from_raw_parts(data, GlobalSize(memory));
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        let text = String::from_utf8_lossy(&bytes[..end]).into_owned();
+        let _ = GlobalUnlock(memory);
+        Ok(text)
+    }
+}
+
+/// Set RTF to the clipboard, replacing its contents.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the clipboard can't be written.
+pub fn set_rtf(rtf: &str) -> Result<(), ClipboardError> {
+    let format = rtf_format();
+    unsafe {
+        let memory = write_global(rtf)?;
+        if let Err(e) = OpenClipboard(None) {
+            let _ = GlobalFree(Some(memory));
+            return Err(ClipboardError::Unknown(e.to_string()));
+        }
+        // The clipboard owns the memory once it was set; free it otherwise
+        let result = EmptyClipboard()
+            .and_then(|()| SetClipboardData(format, Some(HANDLE(memory.0))))
+            .map(|_| ())
+            .map_err(|e| {
+                let _ = GlobalFree(Some(memory));
+                ClipboardError::Unknown(e.to_string())
+            });
+        let _ = CloseClipboard();
+        result
+    }
+}
+
+/// Copy `text` into movable memory as a NUL-terminated string.
+unsafe fn write_global(text: &str) -> Result<HGLOBAL, ClipboardError> {
+    unsafe {
+        let memory = GlobalAlloc(GMEM_MOVEABLE, text.len() + 1)
+            .map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+        let data = GlobalLock(memory).cast::<u8>();
+        if data.is_null() {
+            let _ = GlobalFree(Some(memory));
+            return Err(ClipboardError::Unknown("failed to lock memory".into()));
+        }
+        std::ptr::copy_nonoverlapping(text.as_ptr(), data, text.len());
+        *data.add(text.len()) = 0;
+        let _ = GlobalUnlock(memory);
+        Ok(memory)
+    }
+}
+
+/// Get the files on the clipboard, from its `CF_HDROP`.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the clipboard can't be opened.
+pub fn get_files() -> Result<Vec<PathBuf>, ClipboardError> {
+    unsafe {
+        OpenClipboard(None).map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+        // Anything but a file drop means there are no files
+        let files = GetClipboardData(u32::from(CF_HDROP.0))
+            .map_or_else(|_| Vec::new(), |handle| read_drop(HDROP(handle.0)));
+        let _ = CloseClipboard();
+        Ok(files)
+    }
+}
+
+/// Read the paths out of a file drop.
+unsafe fn read_drop(drop: HDROP) -> Vec<PathBuf> {
+    unsafe {
+        let count = DragQueryFileW(drop, u32::MAX, None);
+        (0..count)
+            .map(|index| {
+                // The length leaves out the terminating NUL
+                let len = DragQueryFileW(drop, index, None) as usize;
+                let mut path = vec![0u16; len + 1];
+                let copied = DragQueryFileW(drop, index, Some(&mut path)) as usize;
+                PathBuf::from(OsString::from_wide(&path[..copied]))
+            })
+            .collect()
+    }
+}
+
+/// Put files on the clipboard as a `CF_HDROP`, replacing its contents.
+///
+/// Relative paths are made absolute, since Explorer resolves them against
+/// its own working directory.
+///
+/// # Errors
+/// Returns `ClipboardError::Unknown` if the clipboard can't be written.
+pub fn set_files(paths: &[PathBuf]) -> Result<(), ClipboardError> {
+    let mut list = Vec::new();
+    for path in paths {
+        let path = std::path::absolute(path).map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+        list.extend(path.as_os_str().encode_wide());
+        list.push(0);
+    }
+    // The list ends with an empty path
+    list.push(0);
+
+    unsafe {
+        let memory = write_drop(&list)?;
+        if let Err(e) = OpenClipboard(None) {
+            let _ = GlobalFree(Some(memory));
+            return Err(ClipboardError::Unknown(e.to_string()));
+        }
+        // The clipboard owns the memory once it was set; free it otherwise
+        let result = EmptyClipboard()
+            .and_then(|()| SetClipboardData(u32::from(CF_HDROP.0), Some(HANDLE(memory.0))))
+            .map(|_| ())
+            .map_err(|e| {
+                let _ = GlobalFree(Some(memory));
+                ClipboardError::Unknown(e.to_string())
+            });
+        let _ = CloseClipboard();
+        result
+    }
+}
+
+/// Copy a NUL-separated list of wide paths into movable memory, behind the
+/// `DROPFILES` header that makes it a file drop.
+unsafe fn write_drop(list: &[u16]) -> Result<HGLOBAL, ClipboardError> {
+    // The header is 20 bytes, so the cast can't truncate
+    #[allow(clippy::cast_possible_truncation)]
+    const HEADER: u32 = size_of::<DROPFILES>() as u32;
+    let offset = HEADER as usize;
+
+    unsafe {
+        let memory = GlobalAlloc(GMEM_MOVEABLE | GMEM_ZEROINIT, offset + size_of_val(list))
+            .map_err(|e| ClipboardError::Unknown(e.to_string()))?;
+        let data = GlobalLock(memory).cast::<u8>();
+        if data.is_null() {
+            let _ = GlobalFree(Some(memory));
+            return Err(ClipboardError::Unknown("failed to lock memory".into()));
+        }
+        data.cast::<DROPFILES>().write(DROPFILES {
+            pFiles: HEADER,
+            fWide: true.into(),
+            ..DROPFILES::default()
+        });
+        std::ptr::copy_nonoverlapping(list.as_ptr(), data.add(offset).cast::<u16>(), list.len());
+        let _ = GlobalUnlock(memory);
+        Ok(memory)
+    }
+}
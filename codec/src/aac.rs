@@ -0,0 +1,334 @@
+//! AAC audio encoding and decoding.
+//!
+//! Encoded audio is exchanged as an ADTS stream: every AAC frame carries a
+//! 7-byte header with its sample rate and channel count, so streams can be
+//! decoded without side information and written straight to `.aac` files.
+//! Frames are compressed with `AudioToolbox` on Apple platforms, `MediaCodec`
+//! on Android and the Fraunhofer FDK AAC library elsewhere.
+
+use crate::{CodecError, sys};
+use std::fmt;
+
+/// PCM frames (samples per channel) in one AAC frame.
+pub const FRAME_LEN: usize = 1024;
+
+/// Sample rates by their MPEG-4 sampling frequency index.
+const SAMPLE_RATES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// MPEG-4 audio object type of AAC-LC.
+const AAC_LC: u8 = 2;
+
+/// Length of an ADTS header without CRC.
+const ADTS_HEADER_LEN: usize = 7;
+
+/// Sample rate and channel count of an AAC stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioConfig {
+    pub sample_rate: u32,
+    pub channels: u8,
+}
+
+impl AudioConfig {
+    fn sample_rate_index(self) -> Option<u8> {
+        SAMPLE_RATES
+            .iter()
+            .position(|&rate| rate == self.sample_rate)
+            .and_then(|index| u8::try_from(index).ok())
+    }
+
+    /// The two-byte `AudioSpecificConfig` of an AAC-LC stream.
+    #[cfg(target_os = "android")]
+    pub fn audio_specific_config(self) -> [u8; 2] {
+        let index = self.sample_rate_index().unwrap_or(0);
+        [
+            (AAC_LC << 3) | (index >> 1),
+            ((index & 1) << 7) | (self.channels << 3),
+        ]
+    }
+
+    /// Prefix an AAC-LC frame with its ADTS header.
+    fn adts_frame(self, payload: &[u8]) -> Vec<u8> {
+        let index = self.sample_rate_index().unwrap_or(0);
+        let len = ADTS_HEADER_LEN + payload.len();
+        let mut frame = Vec::with_capacity(len);
+        // MPEG-4 without CRC; the buffer fullness of 0x7FF marks VBR
+        #[allow(clippy::cast_possible_truncation)] // 13-bit frame length
+        frame.extend_from_slice(&[
+            0xFF,
+            0xF1,
+            ((AAC_LC - 1) << 6) | (index << 2) | (self.channels >> 2),
+            ((self.channels & 3) << 6) | (len >> 11) as u8,
+            (len >> 3) as u8,
+            ((len & 7) << 5) as u8 | 0x1F,
+            0xFC,
+        ]);
+        frame.extend_from_slice(payload);
+        frame
+    }
+}
+
+/// The next ADTS frame at the start of a byte stream.
+enum Adts {
+    /// A complete frame and its header length, in bytes.
+    Frame {
+        config: AudioConfig,
+        header_len: usize,
+        len: usize,
+    },
+    /// More data is needed.
+    Incomplete,
+    /// Not a frame header; skip this many bytes to resynchronize.
+    Skip(usize),
+}
+
+fn parse_adts(data: &[u8]) -> Result<Adts, CodecError> {
+    let Some(header) = data.get(..ADTS_HEADER_LEN) else {
+        return Ok(Adts::Incomplete);
+    };
+    if header[0] != 0xFF || header[1] & 0xF6 != 0xF0 {
+        // Skip to the next possible syncword
+        let next = data[1..]
+            .iter()
+            .position(|&b| b == 0xFF)
+            .map_or(data.len(), |i| i + 1);
+        return Ok(Adts::Skip(next));
+    }
+
+    let object_type = (header[2] >> 6) + 1;
+    if object_type != AAC_LC {
+        return Err(CodecError::Unsupported(format!(
+            "AAC object type {object_type}; only AAC-LC is supported"
+        )));
+    }
+    let rate = SAMPLE_RATES.get(usize::from((header[2] >> 2) & 0xF));
+    let channels = ((header[2] & 1) << 2) | (header[3] >> 6);
+    let (Some(&sample_rate), 1..=6) = (rate, channels) else {
+        return Err(CodecError::Unsupported(format!(
+            "ADTS sample rate index {} with channel configuration {channels}",
+            (header[2] >> 2) & 0xF
+        )));
+    };
+
+    let header_len = if header[1] & 1 == 0 {
+        9
+    } else {
+        ADTS_HEADER_LEN
+    };
+    let len = (usize::from(header[3] & 3) << 11)
+        | (usize::from(header[4]) << 3)
+        | usize::from(header[5] >> 5);
+    if len <= header_len {
+        return Ok(Adts::Skip(1));
+    }
+    if data.len() < len {
+        return Ok(Adts::Incomplete);
+    }
+    Ok(Adts::Frame {
+        config: AudioConfig {
+            sample_rate,
+            channels,
+        },
+        header_len,
+        len,
+    })
+}
+
+/// Encodes PCM audio to AAC-LC.
+///
+/// # Example
+///
+/// ```no_run
+/// use waterkit_codec::AacEncoder;
+///
+/// # fn run(pcm: &[f32]) -> Result<(), waterkit_codec::CodecError> {
+/// let mut encoder = AacEncoder::new(48000, 2, 128_000)?;
+/// let adts = encoder.encode_pcm(pcm)?;
+/// std::fs::write("audio.aac", adts).unwrap();
+/// # Ok(())
+/// # }
+/// ```
+pub struct AacEncoder {
+    inner: sys::AacEncoderInner,
+    config: AudioConfig,
+    /// Samples waiting to fill the next AAC frame.
+    pending: Vec<f32>,
+}
+
+impl fmt::Debug for AacEncoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AacEncoder")
+            .field("sample_rate", &self.config.sample_rate)
+            .field("channels", &self.config.channels)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AacEncoder {
+    /// Create an encoder for mono or stereo audio.
+    ///
+    /// `bit_rate` is in bits per second; 64 kbps per channel is transparent
+    /// for most content.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::Unsupported` for a sample rate AAC can't carry,
+    /// more than two channels or a zero bit rate, and
+    /// `CodecError::InitializationFailed` if the platform encoder rejects the
+    /// configuration.
+    pub fn new(sample_rate: u32, channels: u8, bit_rate: u32) -> Result<Self, CodecError> {
+        let config = AudioConfig {
+            sample_rate,
+            channels,
+        };
+        if config.sample_rate_index().is_none() || !(1..=2).contains(&channels) || bit_rate == 0 {
+            return Err(CodecError::Unsupported(format!(
+                "AAC at {sample_rate} Hz with {channels} channels and {bit_rate} bps"
+            )));
+        }
+        Ok(Self {
+            inner: sys::AacEncoderInner::new(config, bit_rate)?,
+            config,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Encode interleaved samples in `-1.0..=1.0`, returning ADTS frames.
+    ///
+    /// AAC compresses 1024 frames at a time, and the encoder delays its
+    /// output by a few frames, so a call may return nothing. Samples short of
+    /// a whole AAC frame are kept for the next call.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::EncodingFailed` if the platform encoder fails.
+    pub fn encode_pcm(&mut self, samples: &[f32]) -> Result<Vec<u8>, CodecError> {
+        let frame_len = FRAME_LEN * usize::from(self.config.channels);
+        self.pending.extend_from_slice(samples);
+
+        let mut out = Vec::new();
+        let mut start = 0;
+        while self.pending.len() - start >= frame_len {
+            for packet in self
+                .inner
+                .encode_frame(&self.pending[start..start + frame_len])?
+            {
+                out.extend(self.config.adts_frame(&packet));
+            }
+            start += frame_len;
+        }
+        self.pending.drain(..start);
+        Ok(out)
+    }
+
+    /// The sample rate in Hz.
+    #[must_use]
+    pub const fn sample_rate(&self) -> u32 {
+        self.config.sample_rate
+    }
+
+    /// The number of channels.
+    #[must_use]
+    pub const fn channels(&self) -> u8 {
+        self.config.channels
+    }
+}
+
+/// Decodes an AAC-LC ADTS stream to PCM audio.
+///
+/// The sample rate and channel count are read from the stream, and may
+/// change between frames.
+#[derive(Default)]
+pub struct AacDecoder {
+    inner: Option<(AudioConfig, sys::AacDecoderInner)>,
+    /// Bytes of a frame that hasn't been received completely.
+    pending: Vec<u8>,
+}
+
+impl fmt::Debug for AacDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AacDecoder")
+            .field("sample_rate", &self.sample_rate())
+            .field("channels", &self.channels())
+            .finish_non_exhaustive()
+    }
+}
+
+impl AacDecoder {
+    /// Create a decoder.
+    ///
+    /// The platform decoder is opened once the first frame header is known.
+    ///
+    /// # Errors
+    ///
+    /// Never fails at the moment; decoder errors surface from
+    /// [`decode`](Self::decode).
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn new() -> Result<Self, CodecError> {
+        Ok(Self::default())
+    }
+
+    /// Decode ADTS data, returning interleaved samples in `-1.0..=1.0`.
+    ///
+    /// `data` needn't be aligned to frames: a partial frame at the end is
+    /// kept for the next call, and bytes before the first frame header are
+    /// skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::Unsupported` for AAC profiles other than LC,
+    /// and `CodecError::DecodingFailed` if a frame is corrupt.
+    pub fn decode(&mut self, data: &[u8]) -> Result<Vec<f32>, CodecError> {
+        self.pending.extend_from_slice(data);
+
+        let mut out = Vec::new();
+        let mut start = 0;
+        let result = loop {
+            let (config, header_len, len) = match parse_adts(&self.pending[start..]) {
+                Ok(Adts::Frame {
+                    config,
+                    header_len,
+                    len,
+                }) => (config, header_len, len),
+                Ok(Adts::Skip(len)) => {
+                    start += len;
+                    continue;
+                }
+                Ok(Adts::Incomplete) => break Ok(()),
+                Err(e) => {
+                    // Drop the offending header so the stream can recover
+                    start += 1;
+                    break Err(e);
+                }
+            };
+            let frame = &self.pending[start..start + len];
+            start += len;
+
+            let inner = match &mut self.inner {
+                Some((current, inner)) if *current == config => inner,
+                inner => match sys::AacDecoderInner::new(config) {
+                    Ok(decoder) => &mut inner.insert((config, decoder)).1,
+                    Err(e) => break Err(e),
+                },
+            };
+            if let Err(e) = inner.decode_frame(frame, header_len, &mut out) {
+                break Err(e);
+            }
+        };
+        self.pending.drain(..start);
+        result.map(|()| out)
+    }
+
+    /// The sample rate of the stream, once a frame has been decoded.
+    #[must_use]
+    pub fn sample_rate(&self) -> Option<u32> {
+        self.inner.as_ref().map(|(config, _)| config.sample_rate)
+    }
+
+    /// The number of channels of the stream, once a frame has been decoded.
+    #[must_use]
+    pub fn channels(&self) -> Option<u8> {
+        self.inner.as_ref().map(|(config, _)| config.channels)
+    }
+}
@@ -1,6 +1,6 @@
 //! AV1 software encoding (rav1e) and decoding (dav1d).
 
-use crate::{CodecError, Frame, PixelFormat, VideoDecoder, VideoEncoder};
+use crate::{CodecError, Frame, PixelFormat, VideoDecoder, VideoEncoder, yuv};
 use rav1e::prelude::*;
 use std::fmt;
 use std::sync::Arc;
@@ -49,44 +49,6 @@ impl Av1Encoder {
 
         Ok(Self { ctx, width, height })
     }
-
-    /// Convert RGBA to I420 (YUV420 planar).
-    fn rgba_to_i420(rgba: &[u8], width: usize, height: usize) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
-        let y_size = width * height;
-        let uv_size = (width / 2) * (height / 2);
-
-        let mut y_plane = vec![0u8; y_size];
-        let mut u_plane = vec![0u8; uv_size];
-        let mut v_plane = vec![0u8; uv_size];
-
-        for row_idx in 0..height {
-            for col_idx in 0..width {
-                let px_idx = (row_idx * width + col_idx) * 4;
-                let r_val = i32::from(rgba[px_idx]);
-                let g_val = i32::from(rgba[px_idx + 1]);
-                let b_val = i32::from(rgba[px_idx + 2]);
-
-                // BT.601 RGB to YUV conversion
-                let y_val = ((66 * r_val + 129 * g_val + 25 * b_val + 128) >> 8) + 16;
-                y_plane[row_idx * width + col_idx] = u8::try_from(y_val.clamp(0, 255)).unwrap_or(0);
-
-                // Subsample U and V (every 2x2 block)
-                if row_idx % 2 == 0 && col_idx % 2 == 0 {
-                    let u_val = ((-38 * r_val - 74 * g_val + 112 * b_val + 128) >> 8) + 128;
-                    let v_val = ((112 * r_val - 94 * g_val - 18 * b_val + 128) >> 8) + 128;
-
-                    let uv_row = row_idx / 2;
-                    let uv_col = col_idx / 2;
-                    let uv_idx = uv_row * (width / 2) + uv_col;
-
-                    u_plane[uv_idx] = u8::try_from(u_val.clamp(0, 255)).unwrap_or(0);
-                    v_plane[uv_idx] = u8::try_from(v_val.clamp(0, 255)).unwrap_or(0);
-                }
-            }
-        }
-
-        (y_plane, u_plane, v_plane)
-    }
 }
 
 impl VideoEncoder for Av1Encoder {
@@ -115,52 +77,7 @@ impl VideoEncoder for Av1Encoder {
         let mut f = self.ctx.new_frame();
 
         // Convert input to I420 and copy to frame planes
-        let (y_plane, u_plane, v_plane) = match frame.format {
-            PixelFormat::Rgba | PixelFormat::Bgra => {
-                let rgba_data = if frame.format == PixelFormat::Bgra {
-                    // Convert BGRA to RGBA
-                    let mut rgba = frame.data.as_ref().clone();
-                    for chunk in rgba.chunks_exact_mut(4) {
-                        chunk.swap(0, 2);
-                    }
-                    rgba
-                } else {
-                    frame.data.as_ref().clone()
-                };
-                Self::rgba_to_i420(&rgba_data, self.width, self.height)
-            }
-            PixelFormat::I420 => {
-                // Already I420 - split planes
-                let y_size = self.width * self.height;
-                let uv_size = (self.width / 2) * (self.height / 2);
-                let data = frame.data.as_ref();
-
-                (
-                    data[0..y_size].to_vec(),
-                    data[y_size..y_size + uv_size].to_vec(),
-                    data[y_size + uv_size..].to_vec(),
-                )
-            }
-            PixelFormat::Nv12 => {
-                // NV12: Y plane followed by interleaved UV
-                let y_size = self.width * self.height;
-                let uv_size = (self.width / 2) * (self.height / 2);
-                let data = frame.data.as_ref();
-
-                let y_plane = data[0..y_size].to_vec();
-                let mut u_plane = vec![0u8; uv_size];
-                let mut v_plane = vec![0u8; uv_size];
-
-                // De-interleave UV
-                let uv_data = &data[y_size..];
-                for i in 0..uv_size {
-                    u_plane[i] = uv_data[i * 2];
-                    v_plane[i] = uv_data[i * 2 + 1];
-                }
-
-                (y_plane, u_plane, v_plane)
-            }
-        };
+        let (y_plane, u_plane, v_plane) = yuv::i420_planes(frame);
 
         // Copy Y plane - limit to actual height (rav1e may have internal padding)
         for (row_idx, row) in f.planes[0].rows_iter_mut().take(self.height).enumerate() {
@@ -208,6 +125,22 @@ impl VideoEncoder for Av1Encoder {
 
         Ok(output)
     }
+
+    fn flush(&mut self) -> Result<Vec<Vec<u8>>, CodecError> {
+        self.ctx.flush();
+
+        // rav1e holds frames back for look-ahead until the end of the stream
+        let mut packets = Vec::new();
+        loop {
+            match self.ctx.receive_packet() {
+                Ok(pkt) => packets.push(pkt.data),
+                Err(EncoderStatus::Encoded) => {}
+                Err(EncoderStatus::LimitReached | EncoderStatus::NeedMoreData) => break,
+                Err(e) => return Err(CodecError::EncodingFailed(e.to_string())),
+            }
+        }
+        Ok(packets)
+    }
 }
 
 /// AV1 software decoder using dav1d.
@@ -231,7 +164,9 @@ impl Av1Decoder {
     ///
     /// Returns `CodecError::InitializationFailed` if `dav1d` initialization fails.
     pub fn new() -> Result<Self, CodecError> {
-        let settings = dav1d::Settings::new();
+        let mut settings = dav1d::Settings::new();
+        // Output each frame as soon as its data is in, since there's no flush
+        settings.set_max_frame_delay(1);
         let dec = dav1d::Decoder::with_settings(&settings)
             .map_err(|e| CodecError::InitializationFailed(format!("dav1d init failed: {e:?}")))?;
 
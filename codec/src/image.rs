@@ -0,0 +1,263 @@
+//! Still image encoding and decoding (JPEG, PNG and HEIC).
+//!
+//! Images are compressed through the platform's image codecs: `ImageIO` on
+//! Apple platforms and `BitmapFactory`/`Bitmap.compress` on Android. Other
+//! platforms use the pure-Rust `image` crate, which has no HEIC support.
+
+use crate::{CodecError, Frame, PixelFormat, sys};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// A compressed still image format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    /// JPEG, lossy and without transparency.
+    Jpeg,
+    /// PNG, lossless with transparency.
+    Png,
+    /// HEIC (HEVC-compressed HEIF), about half the size of JPEG at the same
+    /// quality.
+    Heic,
+}
+
+impl ImageFormat {
+    /// Detect the format of encoded image data from its signature.
+    #[must_use]
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if data.get(4..8) == Some(b"ftyp")
+            && matches!(
+                data.get(8..12),
+                Some(b"heic" | b"heix" | b"heim" | b"heis" | b"mif1" | b"msf1")
+            )
+        {
+            Some(Self::Heic)
+        } else {
+            None
+        }
+    }
+}
+
+/// Encodes frames into a compressed image format.
+pub trait ImageEncode: Send + Sync {
+    /// The format produced by [`encode`](Self::encode).
+    fn format(&self) -> ImageFormat;
+
+    /// Encode a frame into an image file.
+    ///
+    /// Frames of any [`PixelFormat`] are accepted; YUV and BGRA frames are
+    /// converted to RGBA first. JPEG drops the alpha channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::EncodingFailed` if the frame data is too short
+    /// for its size or the platform encoder fails.
+    fn encode(&self, frame: &Frame) -> Result<Vec<u8>, CodecError>;
+}
+
+/// Constructors for the [`ImageEncode`] implementations.
+///
+/// # Example
+///
+/// ```no_run
+/// use waterkit_codec::{ImageDecoder, ImageEncode, ImageEncoder};
+///
+/// # fn run(jpeg_bytes: &[u8]) -> Result<(), waterkit_codec::CodecError> {
+/// let frame = ImageDecoder::decode(jpeg_bytes)?;
+/// let png = ImageEncoder::png().encode(&frame)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ImageEncoder;
+
+impl ImageEncoder {
+    /// A JPEG encoder. `quality` ranges from 1 (smallest) to 100 (best) and
+    /// is clamped to that range.
+    #[must_use]
+    pub fn jpeg(quality: u8) -> impl ImageEncode {
+        JpegEncoder {
+            quality: quality.clamp(1, 100),
+        }
+    }
+
+    /// A PNG encoder.
+    #[must_use]
+    pub fn png() -> impl ImageEncode {
+        PngEncoder
+    }
+
+    /// A HEIC encoder. `quality` ranges from 0.0 (smallest) to 1.0 (best)
+    /// and is clamped to that range.
+    ///
+    /// Only available on Apple platforms; elsewhere, and on Apple hardware
+    /// without an HEVC encoder, encoding fails with
+    /// `CodecError::Unsupported`.
+    #[must_use]
+    pub fn heic(quality: f32) -> impl ImageEncode {
+        HeicEncoder {
+            quality: quality.clamp(0.0, 1.0),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct JpegEncoder {
+    quality: u8,
+}
+
+impl ImageEncode for JpegEncoder {
+    fn format(&self) -> ImageFormat {
+        ImageFormat::Jpeg
+    }
+
+    fn encode(&self, frame: &Frame) -> Result<Vec<u8>, CodecError> {
+        sys::encode_image(
+            &to_rgba(frame)?,
+            frame.width,
+            frame.height,
+            ImageFormat::Jpeg,
+            self.quality,
+        )
+    }
+}
+
+#[derive(Debug)]
+struct PngEncoder;
+
+impl ImageEncode for PngEncoder {
+    fn format(&self) -> ImageFormat {
+        ImageFormat::Png
+    }
+
+    fn encode(&self, frame: &Frame) -> Result<Vec<u8>, CodecError> {
+        sys::encode_image(
+            &to_rgba(frame)?,
+            frame.width,
+            frame.height,
+            ImageFormat::Png,
+            100,
+        )
+    }
+}
+
+#[derive(Debug)]
+struct HeicEncoder {
+    quality: f32,
+}
+
+impl ImageEncode for HeicEncoder {
+    fn format(&self) -> ImageFormat {
+        ImageFormat::Heic
+    }
+
+    fn encode(&self, frame: &Frame) -> Result<Vec<u8>, CodecError> {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // 0..=100
+        let quality = (self.quality * 100.0).round() as u8;
+        sys::encode_image(
+            &to_rgba(frame)?,
+            frame.width,
+            frame.height,
+            ImageFormat::Heic,
+            quality,
+        )
+    }
+}
+
+/// Decodes JPEG, PNG and HEIC images into frames.
+#[derive(Debug)]
+pub struct ImageDecoder;
+
+impl ImageDecoder {
+    /// Decode a JPEG, PNG or HEIC image, detecting the format from its
+    /// signature.
+    ///
+    /// HEIC is decoded on Apple platforms and Android 9+ only.
+    ///
+    /// The frame is [`PixelFormat::Rgba`] with straight (not premultiplied)
+    /// alpha and a zero timestamp.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::Unsupported` if the format isn't recognized or
+    /// can't be decoded on this platform, and `CodecError::DecodingFailed` if
+    /// the image is corrupt.
+    pub fn decode(data: &[u8]) -> Result<Frame, CodecError> {
+        let format = ImageFormat::detect(data)
+            .ok_or_else(|| CodecError::Unsupported("unrecognized image format".into()))?;
+        let (rgba, width, height) = sys::decode_image(data, format)?;
+        Ok(Frame {
+            data: Arc::new(rgba),
+            width,
+            height,
+            format: PixelFormat::Rgba,
+            timestamp_ns: 0,
+        })
+    }
+}
+
+/// Convert a frame to tightly packed RGBA.
+fn to_rgba(frame: &Frame) -> Result<Cow<'_, [u8]>, CodecError> {
+    let width = frame.width as usize;
+    let height = frame.height as usize;
+    let pixels = width * height;
+    let chroma = width.div_ceil(2) * height.div_ceil(2);
+    let needed = match frame.format {
+        PixelFormat::Rgba | PixelFormat::Bgra => pixels * 4,
+        PixelFormat::Nv12 | PixelFormat::I420 => pixels + chroma * 2,
+    };
+    if pixels == 0 || frame.data.len() < needed {
+        return Err(CodecError::EncodingFailed(format!(
+            "{}x{} {:?} frame needs {needed} bytes, got {}",
+            frame.width,
+            frame.height,
+            frame.format,
+            frame.data.len()
+        )));
+    }
+
+    let data = frame.data.as_slice();
+    let rgba = match frame.format {
+        PixelFormat::Rgba => return Ok(Cow::Borrowed(&data[..needed])),
+        PixelFormat::Bgra => data[..needed]
+            .chunks_exact(4)
+            .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+            .collect(),
+        PixelFormat::Nv12 | PixelFormat::I420 => {
+            let (luma, chroma_planes) = data.split_at(pixels);
+            let chroma_width = width.div_ceil(2);
+            let mut rgba = Vec::with_capacity(pixels * 4);
+            for y in 0..height {
+                for x in 0..width {
+                    let c = (y / 2) * chroma_width + x / 2;
+                    let (u, v) = if frame.format == PixelFormat::Nv12 {
+                        (chroma_planes[c * 2], chroma_planes[c * 2 + 1])
+                    } else {
+                        (chroma_planes[c], chroma_planes[chroma + c])
+                    };
+                    rgba.extend(yuv_to_rgba(luma[y * width + x], u, v));
+                }
+            }
+            rgba
+        }
+    };
+    Ok(Cow::Owned(rgba))
+}
+
+/// Convert a BT.601 limited-range YUV sample to opaque RGBA.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // clamped to 0..=255
+fn yuv_to_rgba(luma: u8, cb: u8, cr: u8) -> [u8; 4] {
+    let luma = (i32::from(luma) - 16) * 298;
+    let cb = i32::from(cb) - 128;
+    let cr = i32::from(cr) - 128;
+    let channel = |value: i32| ((value + 128) >> 8).clamp(0, 255) as u8;
+    [
+        channel(luma + 409 * cr),
+        channel(luma - 100 * cb - 208 * cr),
+        channel(luma + 516 * cb),
+        255,
+    ]
+}
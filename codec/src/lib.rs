@@ -5,9 +5,18 @@
 //! - **Apple**: `VideoToolbox`
 //! - **Android**: `MediaCodec`
 //! - **Windows**: Media Foundation
-//! - **Linux**: (TODO: GStreamer/VA-API)
+//! - **Linux**: V4L2 and (with the `vaapi` feature) VA-API capability queries
 //!
-//! It also provides software fallback for modern codecs like AV1 via `rav1e` and `dav1d`.
+//! It also provides software fallback for modern codecs like AV1 via `rav1e` and `dav1d`,
+//! and for VP9 encoding on Apple platforms via `libvpx`.
+//!
+//! Still images are encoded to and decoded from JPEG, PNG and (on Apple
+//! platforms) HEIC with [`ImageEncoder`] and [`ImageDecoder`].
+//!
+//! [`CodecRegistry`] lists the encoders and decoders the device offers.
+//!
+//! Audio is encoded to and decoded from AAC with [`AacEncoder`] and
+//! [`AacDecoder`].
 
 #![warn(missing_docs)]
 
@@ -22,7 +31,18 @@ pub mod sys;
 
 #[cfg(feature = "av1")]
 pub mod av1;
+#[cfg(all(feature = "vp9", target_vendor = "apple"))]
+pub mod vp9;
+#[cfg(any(feature = "av1", all(feature = "vp9", target_vendor = "apple")))]
+mod yuv;
+
+mod aac;
+mod image;
 
+pub use aac::{AacDecoder, AacEncoder};
+pub use image::{ImageDecoder, ImageEncode, ImageEncoder, ImageFormat};
+
+use std::collections::HashMap;
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -61,6 +81,265 @@ pub enum CodecType {
     Av1,
 }
 
+impl CodecType {
+    /// All codec types known to this crate.
+    pub const ALL: [Self; 5] = [Self::H264, Self::H265, Self::Vp8, Self::Vp9, Self::Av1];
+}
+
+/// A frame size in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Resolution {
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+}
+
+/// What the current device supports for a single codec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CodecSupport {
+    /// Whether a hardware encoder is available.
+    pub hardware_encode: bool,
+    /// Whether a hardware decoder is available.
+    pub hardware_decode: bool,
+    /// Whether both a software encoder and a software decoder are available
+    /// (e.g. AV1 via the `av1` feature).
+    pub software: bool,
+    /// Largest frame size the hardware codec accepts, if the platform reports it.
+    pub max_resolution: Option<Resolution>,
+}
+
+impl CodecSupport {
+    /// Whether frames of this codec can be encoded by any available implementation.
+    #[must_use]
+    pub const fn can_encode(&self) -> bool {
+        self.hardware_encode || self.software
+    }
+
+    /// Whether frames of this codec can be decoded by any available implementation.
+    #[must_use]
+    pub const fn can_decode(&self) -> bool {
+        self.hardware_decode || self.software
+    }
+}
+
+/// Codec support of the current device, keyed by [`CodecType`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    codecs: HashMap<CodecType, CodecSupport>,
+}
+
+impl Capabilities {
+    /// Get the support information for a codec.
+    #[must_use]
+    pub fn get(&self, codec: CodecType) -> CodecSupport {
+        self.codecs.get(&codec).copied().unwrap_or_default()
+    }
+
+    /// Iterate over the support information for every codec.
+    pub fn iter(&self) -> impl Iterator<Item = (CodecType, CodecSupport)> + '_ {
+        self.codecs
+            .iter()
+            .map(|(codec, support)| (*codec, *support))
+    }
+}
+
+/// Query which codecs the current device can encode and decode.
+///
+/// This summarizes [`CodecRegistry`] per codec: hardware support and the
+/// largest hardware frame size come from its hardware entries, and a codec
+/// counts as supported in software when it has both a software encoder and a
+/// software decoder. The platform is queried once, without creating an
+/// encoder or decoder session.
+#[must_use]
+pub fn capabilities() -> Capabilities {
+    let (encoders, decoders) = CodecRegistry::scan();
+    let has_software =
+        |infos: &[CodecInfo], codec| infos.iter().any(|i| !i.is_hardware && i.codec == codec);
+    let mut codecs: HashMap<CodecType, CodecSupport> = CodecType::ALL
+        .into_iter()
+        .map(|codec| {
+            let support = CodecSupport {
+                software: has_software(&encoders, codec) && has_software(&decoders, codec),
+                ..CodecSupport::default()
+            };
+            (codec, support)
+        })
+        .collect();
+
+    let hardware = encoders
+        .iter()
+        .map(|info| (true, info))
+        .chain(decoders.iter().map(|info| (false, info)))
+        .filter(|(_, info)| info.is_hardware);
+    for (is_encoder, info) in hardware {
+        let support = codecs.entry(info.codec).or_default();
+        if is_encoder {
+            support.hardware_encode = true;
+        } else {
+            support.hardware_decode = true;
+        }
+        if let Some(max) = info.max_resolution {
+            support.max_resolution = match support.max_resolution {
+                Some(current) if current.width * current.height >= max.width * max.height => {
+                    Some(current)
+                }
+                _ => Some(max),
+            };
+        }
+    }
+    Capabilities { codecs }
+}
+
+/// An encoder or decoder available on the current device.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CodecInfo {
+    /// The codec it encodes or decodes.
+    pub codec: CodecType,
+    /// Whether it runs on dedicated hardware.
+    pub is_hardware: bool,
+    /// Largest frame size it accepts, if the platform reports it.
+    pub max_resolution: Option<Resolution>,
+    /// Highest bitrate it supports in bits per second, if the platform reports it.
+    pub max_bitrate_bps: Option<u32>,
+    /// Pixel formats an encoder accepts or a decoder produces.
+    pub supported_pixel_formats: Vec<PixelFormat>,
+}
+
+/// Lists the encoders and decoders available on the current device.
+///
+/// Where [`capabilities`] answers "can this codec be used at all", the
+/// registry reports every implementation separately, so hardware paths can be
+/// chosen up front instead of by trial and error.
+#[derive(Debug)]
+pub struct CodecRegistry;
+
+impl CodecRegistry {
+    /// List the available encoders, hardware ones first.
+    ///
+    /// Queries `VideoToolbox` on Apple platforms, `MediaCodecList` on
+    /// Android, Media Foundation on Windows, and on Linux V4L2 memory-to-memory
+    /// devices plus VA-API drivers with the `vaapi` feature. The software AV1
+    /// encoder is included with the `av1` feature,
+    /// and the software VP9 encoder on Apple platforms with the `vp9` feature.
+    #[must_use]
+    pub fn supported_encoders() -> Vec<CodecInfo> {
+        Self::scan().0
+    }
+
+    /// List the available decoders, hardware ones first.
+    ///
+    /// Queried from the same sources as
+    /// [`supported_encoders`](Self::supported_encoders). The software AV1
+    /// decoder is included with the `av1` feature.
+    #[must_use]
+    pub fn supported_decoders() -> Vec<CodecInfo> {
+        Self::scan().1
+    }
+
+    /// Query the platform once for its encoders and decoders, hardware ones first.
+    fn scan() -> (Vec<CodecInfo>, Vec<CodecInfo>) {
+        let (mut encoders, mut decoders) = sys::codec_infos();
+        #[cfg(feature = "av1")]
+        {
+            encoders.push(CodecInfo {
+                codec: CodecType::Av1,
+                is_hardware: false,
+                max_resolution: None,
+                max_bitrate_bps: None,
+                supported_pixel_formats: vec![
+                    PixelFormat::Rgba,
+                    PixelFormat::Bgra,
+                    PixelFormat::Nv12,
+                    PixelFormat::I420,
+                ],
+            });
+            decoders.push(CodecInfo {
+                codec: CodecType::Av1,
+                is_hardware: false,
+                max_resolution: None,
+                max_bitrate_bps: None,
+                supported_pixel_formats: vec![PixelFormat::I420],
+            });
+        }
+        #[cfg(all(feature = "vp9", target_vendor = "apple"))]
+        encoders.push(CodecInfo {
+            codec: CodecType::Vp9,
+            is_hardware: false,
+            max_resolution: None,
+            max_bitrate_bps: None,
+            supported_pixel_formats: vec![
+                PixelFormat::Rgba,
+                PixelFormat::Bgra,
+                PixelFormat::Nv12,
+                PixelFormat::I420,
+            ],
+        });
+        encoders.sort_by_key(|info| !info.is_hardware);
+        decoders.sort_by_key(|info| !info.is_hardware);
+        (encoders, decoders)
+    }
+}
+
+/// How an encoder spends bits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateControl {
+    /// Constant bitrate at [`EncoderConfig::target_bitrate_bps`], for live
+    /// streaming over links of fixed capacity.
+    Cbr,
+    /// Variable bitrate averaging [`EncoderConfig::target_bitrate_bps`].
+    Vbr {
+        /// Quality from 0.0 (smallest) to 1.0 (best).
+        quality: f32,
+    },
+    /// Constant quality without a bitrate target.
+    Crf {
+        /// Rate factor on the x264 scale, from 0 (best) to 51 (smallest);
+        /// 23 is a typical default.
+        value: u32,
+    },
+}
+
+/// Bitrate and keyframe settings for a video encoder.
+///
+/// Zero means "leave it to the encoder" for the bitrates and keyframe
+/// interval. The default is VBR at quality 0.75 with no bitrate target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EncoderConfig {
+    /// Average bitrate in bits per second.
+    pub target_bitrate_bps: u32,
+    /// Hard cap on the bitrate in bits per second, measured over one second.
+    pub max_bitrate_bps: u32,
+    /// How the bitrate is controlled.
+    pub rate_control: RateControl,
+    /// Maximum number of frames between keyframes.
+    pub keyframe_interval: u32,
+}
+
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self {
+            target_bitrate_bps: 0,
+            max_bitrate_bps: 0,
+            rate_control: RateControl::Vbr { quality: 0.75 },
+            keyframe_interval: 0,
+        }
+    }
+}
+
+/// Running statistics of a video encoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EncoderStats {
+    /// Frames the encoder produced output for.
+    pub encoded_frames: u64,
+    /// Frames the encoder dropped, e.g. to stay within the bitrate.
+    pub dropped_frames: u64,
+    /// Bitrate of the output so far, in bits per second.
+    pub actual_bitrate_bps: u32,
+    /// Index of the most recent keyframe among the submitted frames.
+    pub last_keyframe_idx: u64,
+}
+
 /// Generic Video Encoder trait.
 pub trait VideoEncoder: Send + Sync {
     /// Encode a frame.
@@ -69,6 +348,26 @@ pub trait VideoEncoder: Send + Sync {
     ///
     /// Returns `CodecError::EncodingFailed` if encoding fails.
     fn encode(&mut self, frame: &Frame) -> Result<Vec<u8>, CodecError>;
+
+    /// Drain the output of frames still buffered in the encoder.
+    ///
+    /// Call once after the last frame, before closing the container, or the
+    /// end of the stream may be missing. Each returned packet is one encoded
+    /// frame. Encoders that don't buffer return nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::EncodingFailed` if draining fails.
+    fn flush(&mut self) -> Result<Vec<Vec<u8>>, CodecError> {
+        Ok(Vec::new())
+    }
+
+    /// Statistics of the frames encoded so far.
+    ///
+    /// Encoders that don't track statistics report all zeros.
+    fn stats(&self) -> EncoderStats {
+        EncoderStats::default()
+    }
 }
 
 /// Generic Video Decoder trait.
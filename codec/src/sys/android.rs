@@ -1,13 +1,185 @@
 //! Android MediaCodec implementation.
 #![allow(unused_imports)]
 
-use crate::{CodecError, CodecType, Frame, PixelFormat, VideoDecoder, VideoEncoder};
+use crate::{
+    CodecError, CodecInfo, CodecType, Frame, PixelFormat, Resolution, VideoDecoder, VideoEncoder,
+};
+use jni::JNIEnv;
+use jni::objects::{JIntArray, JObject, JObjectArray, JString, JValue};
 use ndk::media::media_codec::{
     MediaCodec, MediaCodecDirection, MediaCodecInfo, MediaCodecResult, MediaFormat,
 };
 use std::collections::VecDeque;
 use std::time::Duration;
 
+/// `MediaCodecList.REGULAR_CODECS`
+const REGULAR_CODECS: i32 = 0;
+
+/// `CodecCapabilities.COLOR_FormatYUV420Planar`
+const COLOR_FORMAT_YUV420_PLANAR: i32 = 19;
+/// `CodecCapabilities.COLOR_FormatYUV420SemiPlanar`
+const COLOR_FORMAT_YUV420_SEMI_PLANAR: i32 = 21;
+/// `CodecCapabilities.COLOR_Format32bitBGRA8888`
+const COLOR_FORMAT_32BIT_BGRA8888: i32 = 15;
+/// `CodecCapabilities.COLOR_Format32bitABGR8888`, RGBA in memory order
+const COLOR_FORMAT_32BIT_ABGR8888: i32 = 0x7F00_A000;
+/// `CodecCapabilities.COLOR_FormatYUV420Flexible`
+const COLOR_FORMAT_YUV420_FLEXIBLE: i32 = 0x7F42_0888;
+
+const fn mime_type(codec: CodecType) -> &'static str {
+    match codec {
+        CodecType::H264 => "video/avc",
+        CodecType::H265 => "video/hevc",
+        CodecType::Vp8 => "video/x-vnd.on2.vp8",
+        CodecType::Vp9 => "video/x-vnd.on2.vp9",
+        CodecType::Av1 => "video/av01",
+    }
+}
+
+fn supports_type(env: &mut JNIEnv, info: &JObject, mime: &str) -> jni::errors::Result<bool> {
+    let types: JObjectArray = env
+        .call_method(info, "getSupportedTypes", "()[Ljava/lang/String;", &[])?
+        .l()?
+        .into();
+    for i in 0..env.get_array_length(&types)? {
+        let ty = JString::from(env.get_object_array_element(&types, i)?);
+        let ty: String = env.get_string(&ty)?.into();
+        if ty.eq_ignore_ascii_case(mime) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn capabilities_for_type<'local>(
+    env: &mut JNIEnv<'local>,
+    info: &JObject,
+    mime: &str,
+) -> jni::errors::Result<JObject<'local>> {
+    let mime = env.new_string(mime)?;
+    env.call_method(
+        info,
+        "getCapabilitiesForType",
+        "(Ljava/lang/String;)Landroid/media/MediaCodecInfo$CodecCapabilities;",
+        &[JValue::Object(&mime)],
+    )?
+    .l()
+}
+
+fn video_capabilities<'local>(
+    env: &mut JNIEnv<'local>,
+    caps: &JObject,
+) -> jni::errors::Result<JObject<'local>> {
+    env.call_method(
+        caps,
+        "getVideoCapabilities",
+        "()Landroid/media/MediaCodecInfo$VideoCapabilities;",
+        &[],
+    )?
+    .l()
+}
+
+/// Map the codec's `colorFormats` to the pixel formats this crate knows.
+fn pixel_formats(env: &mut JNIEnv, caps: &JObject) -> jni::errors::Result<Vec<PixelFormat>> {
+    let array = JIntArray::from(env.get_field(caps, "colorFormats", "[I")?.l()?);
+    let mut colors = vec![0; usize::try_from(env.get_array_length(&array)?).unwrap_or(0)];
+    env.get_int_array_region(&array, 0, &mut colors)?;
+
+    let mut formats = Vec::new();
+    for color in colors {
+        let matched: &[PixelFormat] = match color {
+            COLOR_FORMAT_YUV420_PLANAR => &[PixelFormat::I420],
+            COLOR_FORMAT_YUV420_SEMI_PLANAR => &[PixelFormat::Nv12],
+            // Flexible YUV can be laid out either way
+            COLOR_FORMAT_YUV420_FLEXIBLE => &[PixelFormat::Nv12, PixelFormat::I420],
+            COLOR_FORMAT_32BIT_BGRA8888 => &[PixelFormat::Bgra],
+            COLOR_FORMAT_32BIT_ABGR8888 => &[PixelFormat::Rgba],
+            _ => &[],
+        };
+        for &format in matched {
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+    }
+    Ok(formats)
+}
+
+fn range_upper(env: &mut JNIEnv, video: &JObject, method: &str) -> jni::errors::Result<u32> {
+    let range = env
+        .call_method(video, method, "()Landroid/util/Range;", &[])?
+        .l()?;
+    let upper = env
+        .call_method(&range, "getUpper", "()Ljava/lang/Comparable;", &[])?
+        .l()?;
+    let value = env.call_method(&upper, "intValue", "()I", &[])?.i()?;
+    Ok(value.cast_unsigned())
+}
+
+/// List the encoders and decoders `MediaCodecList` offers for the codecs this crate knows.
+pub fn codec_infos() -> (Vec<CodecInfo>, Vec<CodecInfo>) {
+    let vm = unsafe { jni::JavaVM::from_raw(ndk_context::android_context().vm().cast()) };
+    let Ok(vm) = vm else {
+        return (Vec::new(), Vec::new());
+    };
+    let Ok(mut env) = vm.attach_current_thread() else {
+        return (Vec::new(), Vec::new());
+    };
+    list_codecs(&mut env).unwrap_or_default()
+}
+
+fn list_codecs(env: &mut JNIEnv) -> jni::errors::Result<(Vec<CodecInfo>, Vec<CodecInfo>)> {
+    let list = env.new_object(
+        "android/media/MediaCodecList",
+        "(I)V",
+        &[JValue::Int(REGULAR_CODECS)],
+    )?;
+    let infos: JObjectArray = env
+        .call_method(
+            &list,
+            "getCodecInfos",
+            "()[Landroid/media/MediaCodecInfo;",
+            &[],
+        )?
+        .l()?
+        .into();
+
+    let mut encoders = Vec::new();
+    let mut decoders = Vec::new();
+    for i in 0..env.get_array_length(&infos)? {
+        let info = env.get_object_array_element(&infos, i)?;
+        let is_encoder = env.call_method(&info, "isEncoder", "()Z", &[])?.z()?;
+        let is_hardware = env
+            .call_method(&info, "isHardwareAccelerated", "()Z", &[])?
+            .z()?;
+
+        for codec in CodecType::ALL {
+            let mime = mime_type(codec);
+            if !supports_type(env, &info, mime)? {
+                continue;
+            }
+            let caps = capabilities_for_type(env, &info, mime)?;
+            let video = video_capabilities(env, &caps)?;
+            let codec_info = CodecInfo {
+                codec,
+                is_hardware,
+                max_resolution: Some(Resolution {
+                    width: range_upper(env, &video, "getSupportedWidths")?,
+                    height: range_upper(env, &video, "getSupportedHeights")?,
+                }),
+                max_bitrate_bps: Some(range_upper(env, &video, "getBitrateRange")?),
+                supported_pixel_formats: pixel_formats(env, &caps)?,
+            };
+            if is_encoder {
+                encoders.push(codec_info);
+            } else {
+                decoders.push(codec_info);
+            }
+        }
+    }
+    Ok((encoders, decoders))
+}
+
 pub struct AndroidEncoder;
 
 impl AndroidEncoder {
@@ -45,7 +217,9 @@ impl AndroidDecoder {
         // This suggests `lib.rs` conditionally exports differently?
         // Or I was viewing `stub.rs` or `android.rs` which was just a stub.
         // Let's implement the FULL signature.
-        Err(CodecError::InitializationFailed("Use new_with_config".into()))
+        Err(CodecError::InitializationFailed(
+            "Use new_with_config".into(),
+        ))
     }
 
     pub fn new_with_config(
@@ -54,23 +228,17 @@ impl AndroidDecoder {
         width: u32,
         height: u32,
     ) -> Result<Self, CodecError> {
-         let mime = match codec {
-            CodecType::H264 => "video/avc",
-            CodecType::H265 => "video/hevc",
-            CodecType::VP8 => "video/x-vnd.on2.vp8",
-            CodecType::VP9 => "video/x-vnd.on2.vp9",
-            CodecType::AV1 => "video/av01",
-            _ => return Err(CodecError::Unsupported(format!("{codec:?}"))),
-        };
+        let mime = mime_type(codec);
 
-        let media_codec = MediaCodec::from_decoder_type(mime)
-            .ok_or(CodecError::InitializationFailed("Failed to create codec".into()))?;
+        let media_codec = MediaCodec::from_decoder_type(mime).ok_or(
+            CodecError::InitializationFailed("Failed to create codec".into()),
+        )?;
 
         let format = MediaFormat::new();
         format.set_str("mime", mime);
         format.set_i32("width", width as i32);
         format.set_i32("height", height as i32);
-        
+
         // Android requires csd-0 / csd-1 for AVC/HEVC if not in stream.
         // If config is provided (avcC/hvcC), we should try to parse and set it.
         // For simplicity, we assume generic configuration or that the first frame contains necessary headers (if converted).
@@ -81,13 +249,15 @@ impl AndroidDecoder {
         // We will rely on that or the stream content.
         // Ideally we pass `config` as `csd-0`.
         if let Some(c) = config {
-             format.set_buffer("csd-0", c);
+            format.set_buffer("csd-0", c);
         }
 
-        media_codec.configure(&format, None, MediaCodecDirection::Decoder)
+        media_codec
+            .configure(&format, None, MediaCodecDirection::Decoder)
             .map_err(|e| CodecError::InitializationFailed(format!("Configure failed: {e}")))?;
 
-        media_codec.start()
+        media_codec
+            .start()
             .map_err(|e| CodecError::InitializationFailed(format!("Start failed: {e}")))?;
 
         Ok(Self {
@@ -105,16 +275,19 @@ impl VideoDecoder for AndroidDecoder {
         // 1. Dequeue input buffer
         match self.codec.dequeue_input_buffer(Duration::from_millis(10)) {
             Ok(idx) => {
-                let mut buffer = self.codec.get_input_buffer(idx)
+                let mut buffer = self
+                    .codec
+                    .get_input_buffer(idx)
                     .ok_or(CodecError::DecodingFailed("Input buffer null".into()))?;
-                 
+
                 // Copy data
                 // Note: If data is larger than buffer, we have a problem.
                 let len = data.len().min(buffer.len());
                 buffer[..len].copy_from_slice(&data[..len]);
 
                 // Queue
-                self.codec.queue_input_buffer(idx, 0, len, 0, 0) // timestamp ? flags ?
+                self.codec
+                    .queue_input_buffer(idx, 0, len, 0, 0) // timestamp ? flags ?
                     .map_err(|e| CodecError::DecodingFailed(format!("Queue input failed: {e}")))?;
             }
             Err(_e) => {
@@ -129,13 +302,18 @@ impl VideoDecoder for AndroidDecoder {
         // 2. Dequeue output buffer
         loop {
             let mut info = ndk::media::media_codec::MediaCodecBufferInfo::default();
-            match self.codec.dequeue_output_buffer(&mut info, Duration::from_millis(0)) {
+            match self
+                .codec
+                .dequeue_output_buffer(&mut info, Duration::from_millis(0))
+            {
                 Ok(idx) => {
                     if idx >= 0 {
                         // Got valid buffer
-                        let buffer = self.codec.get_output_buffer(idx as usize)
+                        let buffer = self
+                            .codec
+                            .get_output_buffer(idx as usize)
                             .ok_or(CodecError::DecodingFailed("Output buffer null".into()))?;
-                        
+
                         // Convert buffer (NV12/YUV) to RGBA
                         if let Some(fmt) = self.output_format.as_ref() {
                             // Default to width/height if not in format (though usually they are)
@@ -154,7 +332,7 @@ impl VideoDecoder for AndroidDecoder {
                             // We need access to Y, U, V planes.
                             // Buffer is flat.
                             // layout depends on color format.
-                            
+
                             // Naive NV12 to RGBA
                             // NV12: Y plane (stride * slice_height), then UV plane interlaced (stride * slice_height / 2)
                             // Length check
@@ -162,33 +340,37 @@ impl VideoDecoder for AndroidDecoder {
                                 let y_plane = &buffer[0..stride * h];
                                 let uv_plane_offset = stride * slice_height;
                                 let uv_plane = &buffer[uv_plane_offset..];
-                                
+
                                 for y in 0..h {
                                     for x in 0..w {
                                         let y_idx = y * stride + x;
                                         let uv_idx = (y / 2) * stride + (x / 2) * 2;
-                                        
+
                                         let y_val = y_plane[y_idx] as i32;
                                         let u_val = uv_plane[uv_idx] as i32; // V first? NV12 is UV usually, NV21 is VU. Android default is usually NV12/NV21.
                                         // Let's assume NV12 (UV)
                                         let v_val = uv_plane[uv_idx + 1] as i32;
-                                        
+
                                         // YUV to RGB (integers)
                                         let c = y_val - 16;
                                         let d = u_val - 128; // U
                                         let e = v_val - 128; // V
-                                        
-                                        let r = ((298 * c + 409 * e + 128) >> 8).clamp(0, 255) as u8;
-                                        let g = ((298 * c - 100 * d - 208 * e + 128) >> 8).clamp(0, 255) as u8;
-                                        let b = ((298 * c + 516 * d + 128) >> 8).clamp(0, 255) as u8;
-                                        
+
+                                        let r =
+                                            ((298 * c + 409 * e + 128) >> 8).clamp(0, 255) as u8;
+                                        let g = ((298 * c - 100 * d - 208 * e + 128) >> 8)
+                                            .clamp(0, 255)
+                                            as u8;
+                                        let b =
+                                            ((298 * c + 516 * d + 128) >> 8).clamp(0, 255) as u8;
+
                                         rgba.push(r);
                                         rgba.push(g);
                                         rgba.push(b);
                                         rgba.push(255);
                                     }
                                 }
-                                
+
                                 frames.push(Frame {
                                     data: std::sync::Arc::new(rgba), // Arc<Vec<u8>>? Check Frame definition
                                     width: w as u32,
@@ -200,11 +382,15 @@ impl VideoDecoder for AndroidDecoder {
                         }
 
                         // Release
-                        self.codec.release_output_buffer(idx as usize, false)
-                             .map_err(|e| CodecError::DecodingFailed(format!("Release output failed: {e}")))?;
-                        
+                        self.codec
+                            .release_output_buffer(idx as usize, false)
+                            .map_err(|e| {
+                                CodecError::DecodingFailed(format!("Release output failed: {e}"))
+                            })?;
+
                         // frames.push(...);
-                    } else if idx == ndk::media::media_codec::MediaCodec::INFO_OUTPUT_FORMAT_CHANGED {
+                    } else if idx == ndk::media::media_codec::MediaCodec::INFO_OUTPUT_FORMAT_CHANGED
+                    {
                         self.output_format = Some(self.codec.output_format().unwrap());
                     } else if idx == ndk::media::media_codec::MediaCodec::INFO_TRY_AGAIN_LATER {
                         break;
@@ -213,7 +399,7 @@ impl VideoDecoder for AndroidDecoder {
                 Err(_) => break,
             }
         }
-        
+
         Ok(frames)
     }
 }
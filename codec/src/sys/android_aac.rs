@@ -0,0 +1,204 @@
+//! AAC encoding and decoding with `MediaCodec`.
+//!
+//! The codecs exchange raw AAC frames and 16-bit PCM. Output trails input
+//! by a few buffers, so each call returns whatever the codec has finished.
+
+use crate::CodecError;
+use crate::aac::{AudioConfig, FRAME_LEN};
+use ndk::media::media_codec::{
+    DequeuedInputBufferResult, DequeuedOutputBufferInfoResult, MediaCodec, MediaCodecDirection,
+    MediaFormat,
+};
+use std::time::Duration;
+
+const MIME_AAC: &str = "audio/mp4a-latm";
+/// `MediaCodecInfo.CodecProfileLevel.AACObjectLC`
+const AAC_OBJECT_LC: i32 = 2;
+/// `MediaCodec.BUFFER_FLAG_CODEC_CONFIG`
+const BUFFER_FLAG_CODEC_CONFIG: u32 = 2;
+/// How long to wait for a free input buffer.
+const INPUT_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// Configure and start a codec.
+fn start(
+    codec: Option<MediaCodec>,
+    format: &MediaFormat,
+    direction: MediaCodecDirection,
+) -> Result<MediaCodec, CodecError> {
+    let codec = codec.ok_or_else(|| {
+        CodecError::InitializationFailed(format!("no codec available for {MIME_AAC}"))
+    })?;
+    codec
+        .configure(format, None, direction)
+        .map_err(|e| CodecError::InitializationFailed(format!("configure failed: {e}")))?;
+    codec
+        .start()
+        .map_err(|e| CodecError::InitializationFailed(format!("start failed: {e}")))?;
+    Ok(codec)
+}
+
+/// Queue `data` as one input buffer.
+fn queue(codec: &MediaCodec, data: &[u8], time_us: u64) -> Result<(), String> {
+    let DequeuedInputBufferResult::Buffer(mut buffer) = codec
+        .dequeue_input_buffer(INPUT_TIMEOUT)
+        .map_err(|e| e.to_string())?
+    else {
+        return Err("no input buffer available".into());
+    };
+    let dest = buffer.buffer_mut();
+    if dest.len() < data.len() {
+        return Err(format!(
+            "{} bytes don't fit an input buffer of {}",
+            data.len(),
+            dest.len()
+        ));
+    }
+    for (dest, &byte) in dest.iter_mut().zip(data) {
+        dest.write(byte);
+    }
+    codec
+        .queue_input_buffer(buffer, 0, data.len(), time_us, 0)
+        .map_err(|e| e.to_string())
+}
+
+/// Pass every finished output buffer to `output`, skipping codec config.
+fn drain(codec: &MediaCodec, mut output: impl FnMut(&[u8])) -> Result<(), String> {
+    loop {
+        match codec
+            .dequeue_output_buffer(Duration::ZERO)
+            .map_err(|e| e.to_string())?
+        {
+            DequeuedOutputBufferInfoResult::Buffer(buffer) => {
+                if buffer.info().flags() & BUFFER_FLAG_CODEC_CONFIG == 0 {
+                    output(buffer.buffer());
+                }
+                codec
+                    .release_output_buffer(buffer, false)
+                    .map_err(|e| e.to_string())?;
+            }
+            DequeuedOutputBufferInfoResult::TryAgainLater => return Ok(()),
+            // Format and buffer changes need no handling for PCM and AAC
+            _ => {}
+        }
+    }
+}
+
+/// Presentation time of the AAC frame at `index`, in microseconds.
+fn frame_time_us(index: u64, sample_rate: u32) -> u64 {
+    index * FRAME_LEN as u64 * 1_000_000 / u64::from(sample_rate)
+}
+
+pub struct AacEncoderInner {
+    codec: MediaCodec,
+    sample_rate: u32,
+    frames: u64,
+}
+
+// The codec is only used through `&mut self`
+unsafe impl Send for AacEncoderInner {}
+
+impl AacEncoderInner {
+    pub fn new(config: AudioConfig, bit_rate: u32) -> Result<Self, CodecError> {
+        let format = MediaFormat::new();
+        format.set_str("mime", MIME_AAC);
+        format.set_i32("sample-rate", config.sample_rate.cast_signed());
+        format.set_i32("channel-count", i32::from(config.channels));
+        format.set_i32("bitrate", bit_rate.cast_signed());
+        format.set_i32("aac-profile", AAC_OBJECT_LC);
+        let codec = start(
+            MediaCodec::from_encoder_type(MIME_AAC),
+            &format,
+            MediaCodecDirection::Encoder,
+        )?;
+        Ok(Self {
+            codec,
+            sample_rate: config.sample_rate,
+            frames: 0,
+        })
+    }
+
+    pub fn encode_frame(&mut self, samples: &[f32]) -> Result<Vec<Vec<u8>>, CodecError> {
+        #[allow(clippy::cast_possible_truncation)] // clamped to the i16 range
+        let pcm: Vec<u8> = samples
+            .iter()
+            .flat_map(|&sample| {
+                ((sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16).to_le_bytes()
+            })
+            .collect();
+        queue(
+            &self.codec,
+            &pcm,
+            frame_time_us(self.frames, self.sample_rate),
+        )
+        .map_err(CodecError::EncodingFailed)?;
+        self.frames += 1;
+
+        let mut packets = Vec::new();
+        drain(&self.codec, |packet| packets.push(packet.to_vec()))
+            .map_err(CodecError::EncodingFailed)?;
+        Ok(packets)
+    }
+}
+
+impl Drop for AacEncoderInner {
+    fn drop(&mut self) {
+        let _ = self.codec.stop();
+    }
+}
+
+pub struct AacDecoderInner {
+    codec: MediaCodec,
+    sample_rate: u32,
+    frames: u64,
+}
+
+// The codec is only used through `&mut self`
+unsafe impl Send for AacDecoderInner {}
+
+impl AacDecoderInner {
+    pub fn new(config: AudioConfig) -> Result<Self, CodecError> {
+        let format = MediaFormat::new();
+        format.set_str("mime", MIME_AAC);
+        format.set_i32("sample-rate", config.sample_rate.cast_signed());
+        format.set_i32("channel-count", i32::from(config.channels));
+        format.set_buffer("csd-0", &config.audio_specific_config());
+        let codec = start(
+            MediaCodec::from_decoder_type(MIME_AAC),
+            &format,
+            MediaCodecDirection::Decoder,
+        )?;
+        Ok(Self {
+            codec,
+            sample_rate: config.sample_rate,
+            frames: 0,
+        })
+    }
+
+    pub fn decode_frame(
+        &mut self,
+        frame: &[u8],
+        header_len: usize,
+        out: &mut Vec<f32>,
+    ) -> Result<(), CodecError> {
+        queue(
+            &self.codec,
+            &frame[header_len..],
+            frame_time_us(self.frames, self.sample_rate),
+        )
+        .map_err(CodecError::DecodingFailed)?;
+        self.frames += 1;
+
+        drain(&self.codec, |pcm| {
+            out.extend(pcm.chunks_exact(2).map(|bytes| {
+                f32::from(i16::from_le_bytes([bytes[0], bytes[1]])) / f32::from(i16::MAX)
+            }));
+        })
+        .map_err(CodecError::DecodingFailed)
+    }
+}
+
+impl Drop for AacDecoderInner {
+    fn drop(&mut self) {
+        let _ = self.codec.stop();
+    }
+}
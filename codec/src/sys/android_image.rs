@@ -0,0 +1,196 @@
+//! Image encoding and decoding with Android's `Bitmap` APIs.
+//!
+//! Bitmaps are kept unpremultiplied (API 19+), so pixels are copied in and
+//! out with straight alpha, matching the other platforms.
+
+use crate::CodecError;
+use crate::image::ImageFormat;
+use jni::JNIEnv;
+use jni::objects::{JByteArray, JObject, JValue};
+
+const BITMAP_CONFIG: &str = "android/graphics/Bitmap$Config";
+const COMPRESS_FORMAT: &str = "android/graphics/Bitmap$CompressFormat";
+
+/// Run `f` with the JNI environment of the current thread.
+fn with_env<T>(
+    f: impl FnOnce(&mut JNIEnv) -> jni::errors::Result<T>,
+    to_error: fn(String) -> CodecError,
+) -> Result<T, CodecError> {
+    let vm = unsafe { jni::JavaVM::from_raw(ndk_context::android_context().vm().cast()) }
+        .map_err(|e| CodecError::InitializationFailed(e.to_string()))?;
+    let mut env = vm
+        .attach_current_thread()
+        .map_err(|e| CodecError::InitializationFailed(e.to_string()))?;
+    let result = f(&mut env);
+    // Leave no pending exception behind for the next JNI call
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+    result.map_err(|e| to_error(e.to_string()))
+}
+
+fn argb_8888<'local>(env: &mut JNIEnv<'local>) -> jni::errors::Result<JObject<'local>> {
+    env.get_static_field(
+        BITMAP_CONFIG,
+        "ARGB_8888",
+        "Landroid/graphics/Bitmap$Config;",
+    )?
+    .l()
+}
+
+pub fn encode_image(
+    rgba: &[u8],
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+    quality: u8,
+) -> Result<Vec<u8>, CodecError> {
+    let name = match format {
+        ImageFormat::Jpeg => "JPEG",
+        ImageFormat::Png => "PNG",
+        // Bitmap.compress has no HEIF format; only the androidx HeifWriter does
+        ImageFormat::Heic => {
+            return Err(CodecError::Unsupported(
+                "HEIC encoding is not available on Android".into(),
+            ));
+        }
+    };
+    let width =
+        i32::try_from(width).map_err(|_| CodecError::EncodingFailed("image too large".into()))?;
+    let height =
+        i32::try_from(height).map_err(|_| CodecError::EncodingFailed("image too large".into()))?;
+
+    let encoded = with_env(
+        |env| {
+            let config = argb_8888(env)?;
+            let bitmap = env
+                .call_static_method(
+                    "android/graphics/Bitmap",
+                    "createBitmap",
+                    "(IILandroid/graphics/Bitmap$Config;)Landroid/graphics/Bitmap;",
+                    &[
+                        JValue::Int(width),
+                        JValue::Int(height),
+                        JValue::Object(&config),
+                    ],
+                )?
+                .l()?;
+            env.call_method(&bitmap, "setPremultiplied", "(Z)V", &[JValue::Bool(0)])?;
+
+            // ARGB_8888 bitmaps store their pixels as R, G, B, A bytes
+            let pixels = env.byte_array_from_slice(rgba)?;
+            let buffer = env
+                .call_static_method(
+                    "java/nio/ByteBuffer",
+                    "wrap",
+                    "([B)Ljava/nio/ByteBuffer;",
+                    &[JValue::Object(&pixels)],
+                )?
+                .l()?;
+            env.call_method(
+                &bitmap,
+                "copyPixelsFromBuffer",
+                "(Ljava/nio/Buffer;)V",
+                &[JValue::Object(&buffer)],
+            )?;
+
+            let compress_format = env
+                .get_static_field(
+                    COMPRESS_FORMAT,
+                    name,
+                    "Landroid/graphics/Bitmap$CompressFormat;",
+                )?
+                .l()?;
+            let stream = env.new_object("java/io/ByteArrayOutputStream", "()V", &[])?;
+            let compressed = env
+                .call_method(
+                    &bitmap,
+                    "compress",
+                    "(Landroid/graphics/Bitmap$CompressFormat;ILjava/io/OutputStream;)Z",
+                    &[
+                        JValue::Object(&compress_format),
+                        JValue::Int(i32::from(quality)),
+                        JValue::Object(&stream),
+                    ],
+                )?
+                .z()?;
+            env.call_method(&bitmap, "recycle", "()V", &[])?;
+            if !compressed {
+                return Ok(None);
+            }
+
+            let bytes: JByteArray = env
+                .call_method(&stream, "toByteArray", "()[B", &[])?
+                .l()?
+                .into();
+            env.convert_byte_array(&bytes).map(Some)
+        },
+        CodecError::EncodingFailed,
+    )?;
+    encoded.ok_or_else(|| CodecError::EncodingFailed("Bitmap.compress failed".into()))
+}
+
+pub fn decode_image(data: &[u8], _format: ImageFormat) -> Result<(Vec<u8>, u32, u32), CodecError> {
+    let length = i32::try_from(data.len())
+        .map_err(|_| CodecError::DecodingFailed("image too large".into()))?;
+
+    let decoded = with_env(
+        |env| {
+            let options = env.new_object("android/graphics/BitmapFactory$Options", "()V", &[])?;
+            let config = argb_8888(env)?;
+            env.set_field(
+                &options,
+                "inPreferredConfig",
+                "Landroid/graphics/Bitmap$Config;",
+                JValue::Object(&config),
+            )?;
+            env.set_field(&options, "inPremultiplied", "Z", JValue::Bool(0))?;
+
+            let bytes = env.byte_array_from_slice(data)?;
+            let bitmap = env
+                .call_static_method(
+                    "android/graphics/BitmapFactory",
+                    "decodeByteArray",
+                    "([BIILandroid/graphics/BitmapFactory$Options;)Landroid/graphics/Bitmap;",
+                    &[
+                        JValue::Object(&bytes),
+                        JValue::Int(0),
+                        JValue::Int(length),
+                        JValue::Object(&options),
+                    ],
+                )?
+                .l()?;
+            // BitmapFactory returns null for data it can't decode
+            if bitmap.is_null() {
+                return Ok(None);
+            }
+
+            let width = env.call_method(&bitmap, "getWidth", "()I", &[])?.i()?;
+            let height = env.call_method(&bitmap, "getHeight", "()I", &[])?.i()?;
+            let size = env.call_method(&bitmap, "getByteCount", "()I", &[])?.i()?;
+            let pixels = env.new_byte_array(size)?;
+            let buffer = env
+                .call_static_method(
+                    "java/nio/ByteBuffer",
+                    "wrap",
+                    "([B)Ljava/nio/ByteBuffer;",
+                    &[JValue::Object(&pixels)],
+                )?
+                .l()?;
+            env.call_method(
+                &bitmap,
+                "copyPixelsToBuffer",
+                "(Ljava/nio/Buffer;)V",
+                &[JValue::Object(&buffer)],
+            )?;
+            env.call_method(&bitmap, "recycle", "()V", &[])?;
+
+            let rgba = env.convert_byte_array(&pixels)?;
+            Ok(Some((rgba, width.cast_unsigned(), height.cast_unsigned())))
+        },
+        CodecError::DecodingFailed,
+    )?;
+    decoded.ok_or_else(|| {
+        CodecError::DecodingFailed("BitmapFactory could not decode the image".into())
+    })
+}
@@ -3,10 +3,14 @@
 
 use objc2::rc::Retained;
 use objc2_core_media::{
-    CMSampleBuffer, CMSampleTimingInfo, CMTime, kCMVideoCodecType_H264, kCMVideoCodecType_HEVC,
+    CMSampleBuffer, CMSampleTimingInfo, CMTime, kCMVideoCodecType_AV1, kCMVideoCodecType_H264,
+    kCMVideoCodecType_HEVC, kCMVideoCodecType_VP9,
 };
 
-use crate::{CodecError, CodecType, Frame, PixelFormat, VideoEncoder};
+use crate::{
+    CodecError, CodecInfo, CodecType, EncoderConfig, EncoderStats, Frame, PixelFormat, RateControl,
+    VideoEncoder,
+};
 use objc2_core_foundation::CFRetained;
 use objc2_core_video::{
     CVPixelBuffer, CVPixelBufferCreate, CVPixelBufferGetBaseAddress, CVPixelBufferGetBytesPerRow,
@@ -145,6 +149,52 @@ unsafe extern "C" {
         valuePtr: *const c_void,
     ) -> *const c_void;
 
+    static kVTVideoEncoderList_CodecType: *const c_void;
+    static kVTVideoEncoderList_IsHardwareAccelerated: *const c_void;
+    static kVTVideoEncoderList_EncoderID: *const c_void;
+    static kVTVideoEncoderSpecification_EncoderID: *const c_void;
+    static kVTPropertySupportedValueMaximumKey: *const c_void;
+
+    fn VTIsHardwareDecodeSupported(codec_type: u32) -> u8;
+    fn VTCopyVideoEncoderList(options: *const c_void, list_out: *mut *const c_void) -> i32;
+    fn VTCopySupportedPropertyDictionaryForEncoder(
+        width: i32,
+        height: i32,
+        codec_type: u32,
+        encoder_specification: *const c_void,
+        encoder_id_out: *mut *const c_void,
+        supported_properties_out: *mut *const c_void,
+    ) -> i32;
+
+    fn CFArrayGetCount(theArray: *const c_void) -> isize;
+    fn CFArrayGetValueAtIndex(theArray: *const c_void, idx: isize) -> *const c_void;
+    fn CFNumberGetValue(number: *const c_void, theType: i64, valuePtr: *mut c_void) -> u8;
+    fn CFBooleanGetValue(boolean: *const c_void) -> u8;
+
+    static kCFTypeArrayCallBacks: c_void;
+    static kCMSampleAttachmentKey_NotSync: *const c_void;
+    static kVTCompressionPropertyKey_AverageBitRate: *const c_void;
+    static kVTCompressionPropertyKey_ConstantBitRate: *const c_void;
+    static kVTCompressionPropertyKey_DataRateLimits: *const c_void;
+    static kVTCompressionPropertyKey_Quality: *const c_void;
+    static kVTCompressionPropertyKey_MaxKeyFrameInterval: *const c_void;
+
+    fn VTSessionSetProperty(
+        session: *const c_void,
+        key: *const c_void,
+        value: *const c_void,
+    ) -> i32;
+    fn CFArrayCreate(
+        allocator: *const c_void,
+        values: *const *const c_void,
+        numValues: isize,
+        callBacks: *const c_void,
+    ) -> *const c_void;
+    fn CMSampleBufferGetSampleAttachmentsArray(
+        sbuf: *mut CMSampleBuffer,
+        createIfNecessary: u8,
+    ) -> *const c_void;
+
     fn CMBlockBufferReplaceDataBytes(
         sourceBytes: *const c_void,
         destinationBuffer: *const c_void,
@@ -153,6 +203,162 @@ unsafe extern "C" {
     ) -> i32;
 }
 
+/// `kCFNumberSInt32Type`
+const CF_NUMBER_SINT32_TYPE: i64 = 3;
+/// `kCFNumberSInt64Type`
+const CF_NUMBER_SINT64_TYPE: i64 = 4;
+/// `kCFNumberFloat32Type`
+const CF_NUMBER_FLOAT32_TYPE: i64 = 5;
+
+/// Frame rate the encoder timestamps frames at.
+const FRAME_RATE: i32 = 30;
+
+const fn video_codec_type(codec: CodecType) -> Option<u32> {
+    match codec {
+        CodecType::H264 => Some(kCMVideoCodecType_H264),
+        CodecType::H265 => Some(kCMVideoCodecType_HEVC),
+        CodecType::Vp9 => Some(kCMVideoCodecType_VP9),
+        CodecType::Av1 => Some(kCMVideoCodecType_AV1),
+        CodecType::Vp8 => None,
+    }
+}
+
+/// Pixel formats [`AppleEncoder`] accepts.
+const ENCODER_PIXEL_FORMATS: [PixelFormat; 2] = [PixelFormat::Rgba, PixelFormat::Bgra];
+
+/// Pixel formats [`AppleDecoder`] produces.
+const DECODER_PIXEL_FORMATS: [PixelFormat; 1] = [PixelFormat::Bgra];
+
+/// Read a `CFNumber` as an `i64`.
+unsafe fn cf_i64(number: *const c_void) -> Option<i64> {
+    let mut value: i64 = 0;
+    let ok = !number.is_null()
+        && unsafe {
+            CFNumberGetValue(
+                number,
+                CF_NUMBER_SINT64_TYPE,
+                (&raw mut value).cast::<c_void>(),
+            ) != 0
+        };
+    ok.then_some(value)
+}
+
+/// The upper bound an encoder reports for its average bitrate.
+unsafe fn max_encoder_bitrate(encoder_id: *const c_void, codec_type: u32) -> Option<u32> {
+    if encoder_id.is_null() {
+        return None;
+    }
+
+    unsafe {
+        let keys = [kVTVideoEncoderSpecification_EncoderID];
+        let values = [encoder_id];
+        let specification = CFDictionaryCreate(
+            kCFAllocatorDefault,
+            keys.as_ptr(),
+            values.as_ptr(),
+            1,
+            &raw const kCFTypeDictionaryKeyCallBacks,
+            &raw const kCFTypeDictionaryValueCallBacks,
+        );
+        if specification.is_null() {
+            return None;
+        }
+
+        // The size only picks the configuration the encoder reports on
+        let mut properties: *const c_void = ptr::null();
+        let status = VTCopySupportedPropertyDictionaryForEncoder(
+            1920,
+            1080,
+            codec_type,
+            specification,
+            ptr::null_mut(),
+            &raw mut properties,
+        );
+        CFRelease(specification);
+        if status != 0 || properties.is_null() {
+            return None;
+        }
+
+        let bitrate = CFDictionaryGetValue(properties, kVTCompressionPropertyKey_AverageBitRate);
+        let max = if bitrate.is_null() {
+            None
+        } else {
+            cf_i64(CFDictionaryGetValue(
+                bitrate,
+                kVTPropertySupportedValueMaximumKey,
+            ))
+        };
+        CFRelease(properties);
+        max.and_then(|bps| u32::try_from(bps).ok())
+    }
+}
+
+/// List the encoders and decoders `VideoToolbox` offers for the codecs this crate knows.
+pub fn codec_infos() -> (Vec<CodecInfo>, Vec<CodecInfo>) {
+    (encoder_infos(), decoder_infos())
+}
+
+/// List the encoders `VideoToolbox` offers for the codecs this crate knows.
+fn encoder_infos() -> Vec<CodecInfo> {
+    unsafe {
+        let mut list: *const c_void = ptr::null();
+        if VTCopyVideoEncoderList(ptr::null(), &raw mut list) != 0 || list.is_null() {
+            return Vec::new();
+        }
+
+        let infos = (0..CFArrayGetCount(list))
+            .filter_map(|i| {
+                let entry = CFArrayGetValueAtIndex(list, i);
+                let codec_type = cf_i64(CFDictionaryGetValue(entry, kVTVideoEncoderList_CodecType))
+                    .and_then(|ty| u32::try_from(ty).ok())?;
+                let codec = CodecType::ALL
+                    .into_iter()
+                    .find(|&codec| video_codec_type(codec) == Some(codec_type))?;
+
+                let hardware =
+                    CFDictionaryGetValue(entry, kVTVideoEncoderList_IsHardwareAccelerated);
+                let encoder_id = CFDictionaryGetValue(entry, kVTVideoEncoderList_EncoderID);
+                Some(CodecInfo {
+                    codec,
+                    is_hardware: !hardware.is_null() && CFBooleanGetValue(hardware) != 0,
+                    // VideoToolbox does not report size limits
+                    max_resolution: None,
+                    max_bitrate_bps: max_encoder_bitrate(encoder_id, codec_type),
+                    supported_pixel_formats: ENCODER_PIXEL_FORMATS.to_vec(),
+                })
+            })
+            .collect();
+
+        CFRelease(list);
+        infos
+    }
+}
+
+/// List the decoders `VideoToolbox` offers for the codecs this crate knows.
+///
+/// `VideoToolbox` has no decoder list; hardware decoders are probed with
+/// `VTIsHardwareDecodeSupported`, and the H.264 and HEVC software decoders
+/// ship with every OS version this crate supports.
+fn decoder_infos() -> Vec<CodecInfo> {
+    let info = |codec, is_hardware| CodecInfo {
+        codec,
+        is_hardware,
+        max_resolution: None,
+        max_bitrate_bps: None,
+        supported_pixel_formats: DECODER_PIXEL_FORMATS.to_vec(),
+    };
+
+    let hardware = CodecType::ALL.into_iter().filter(|&codec| {
+        video_codec_type(codec)
+            .is_some_and(|codec_type| unsafe { VTIsHardwareDecodeSupported(codec_type) != 0 })
+    });
+    let software = [CodecType::H264, CodecType::H265];
+    hardware
+        .map(|codec| info(codec, true))
+        .chain(software.into_iter().map(|codec| info(codec, false)))
+        .collect()
+}
+
 /// Apple `VideoToolbox` hardware encoder.
 pub struct AppleEncoder {
     session: Retained<VTCompressionSession>,
@@ -175,6 +381,162 @@ impl fmt::Debug for AppleEncoder {
 struct EncoderContext {
     encoded_data: Mutex<Vec<u8>>,
     codec_config: Mutex<Option<Vec<u8>>>,
+    stats: Mutex<StatsCounter>,
+}
+
+/// Output totals collected by the encode callback.
+#[derive(Default)]
+struct StatsCounter {
+    encoded_frames: u64,
+    dropped_frames: u64,
+    encoded_bytes: u64,
+    last_keyframe_idx: u64,
+}
+
+impl StatsCounter {
+    fn stats(&self) -> EncoderStats {
+        // Frames are timestamped at FRAME_RATE, so the output spans
+        // encoded_frames / FRAME_RATE seconds
+        let bitrate = (self.encoded_bytes * 8 * u64::from(FRAME_RATE.unsigned_abs()))
+            .checked_div(self.encoded_frames)
+            .unwrap_or(0);
+        EncoderStats {
+            encoded_frames: self.encoded_frames,
+            dropped_frames: self.dropped_frames,
+            actual_bitrate_bps: u32::try_from(bitrate).unwrap_or(u32::MAX),
+            last_keyframe_idx: self.last_keyframe_idx,
+        }
+    }
+}
+
+/// Whether an encoded sample is a sync (key) frame.
+unsafe fn is_keyframe(sample_buffer: *mut CMSampleBuffer) -> bool {
+    unsafe {
+        let attachments = CMSampleBufferGetSampleAttachmentsArray(sample_buffer, 0);
+        if attachments.is_null() || CFArrayGetCount(attachments) == 0 {
+            return true;
+        }
+        let not_sync = CFDictionaryGetValue(
+            CFArrayGetValueAtIndex(attachments, 0),
+            kCMSampleAttachmentKey_NotSync,
+        );
+        not_sync.is_null() || CFBooleanGetValue(not_sync) == 0
+    }
+}
+
+/// Create a `CFNumber`, released by the caller.
+unsafe fn cf_number<T>(number_type: i64, value: T) -> *const c_void {
+    unsafe { CFNumberCreate(ptr::null(), number_type, (&raw const value).cast()) }
+}
+
+/// Set a session property, releasing `value`.
+unsafe fn set_property(session: *const c_void, key: *const c_void, value: *const c_void) -> i32 {
+    unsafe {
+        let status = VTSessionSetProperty(session, key, value);
+        CFRelease(value);
+        status
+    }
+}
+
+/// Apply bitrate, quality and keyframe settings to a compression session.
+fn apply_config(session: *const c_void, config: &EncoderConfig) -> Result<(), CodecError> {
+    let check = |status: i32, property: &str| {
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(CodecError::InitializationFailed(format!(
+                "setting {property} failed: {status}"
+            )))
+        }
+    };
+    let average_bitrate = |bps: u32| unsafe {
+        set_property(
+            session,
+            kVTCompressionPropertyKey_AverageBitRate,
+            cf_number(CF_NUMBER_SINT64_TYPE, i64::from(bps)),
+        )
+    };
+
+    unsafe {
+        // A cap of [bytes, seconds] pairs; CBR falls back to capping at the target
+        let cap = match config.rate_control {
+            RateControl::Cbr => config.target_bitrate_bps,
+            _ => config.max_bitrate_bps,
+        };
+
+        match config.rate_control {
+            RateControl::Cbr => {
+                let constant = set_property(
+                    session,
+                    kVTCompressionPropertyKey_ConstantBitRate,
+                    cf_number(CF_NUMBER_SINT64_TYPE, i64::from(config.target_bitrate_bps)),
+                );
+                // Only newer HEVC and H.264 encoders support true CBR
+                if constant != 0 && config.target_bitrate_bps > 0 {
+                    check(average_bitrate(config.target_bitrate_bps), "AverageBitRate")?;
+                }
+            }
+            RateControl::Vbr { quality } => {
+                if config.target_bitrate_bps > 0 {
+                    check(average_bitrate(config.target_bitrate_bps), "AverageBitRate")?;
+                }
+                check(
+                    set_property(
+                        session,
+                        kVTCompressionPropertyKey_Quality,
+                        cf_number(CF_NUMBER_FLOAT32_TYPE, quality.clamp(0.0, 1.0)),
+                    ),
+                    "Quality",
+                )?;
+            }
+            RateControl::Crf { value } => {
+                // VideoToolbox has no rate factor; map the 0..=51 scale onto quality
+                #[allow(clippy::cast_precision_loss)]
+                let quality = 1.0 - value.min(51) as f32 / 51.0;
+                check(
+                    set_property(
+                        session,
+                        kVTCompressionPropertyKey_Quality,
+                        cf_number(CF_NUMBER_FLOAT32_TYPE, quality),
+                    ),
+                    "Quality",
+                )?;
+            }
+        }
+
+        if cap > 0 {
+            let bytes = cf_number(CF_NUMBER_SINT64_TYPE, i64::from(cap / 8));
+            let seconds = cf_number(CF_NUMBER_FLOAT32_TYPE, 1.0f32);
+            let values = [bytes, seconds];
+            let limits = CFArrayCreate(
+                ptr::null(),
+                values.as_ptr(),
+                2,
+                (&raw const kCFTypeArrayCallBacks).cast(),
+            );
+            CFRelease(bytes);
+            CFRelease(seconds);
+            check(
+                set_property(session, kVTCompressionPropertyKey_DataRateLimits, limits),
+                "DataRateLimits",
+            )?;
+        }
+
+        if config.keyframe_interval > 0 {
+            check(
+                set_property(
+                    session,
+                    kVTCompressionPropertyKey_MaxKeyFrameInterval,
+                    cf_number(
+                        CF_NUMBER_SINT32_TYPE,
+                        i32::try_from(config.keyframe_interval).unwrap_or(i32::MAX),
+                    ),
+                ),
+                "MaxKeyFrameInterval",
+            )?;
+        }
+    }
+    Ok(())
 }
 
 #[allow(clippy::non_send_fields_in_send_ty)]
@@ -187,22 +549,37 @@ unsafe impl Sync for AppleEncoder {}
 #[allow(clippy::collapsible_if)]
 unsafe extern "C-unwind" fn encode_callback(
     output_callback_ref_con: *mut c_void,
-    _source_frame_ref_con: *mut c_void,
+    source_frame_ref_con: *mut c_void,
     status: i32,
-    _info_flags: VTEncodeInfoFlags,
+    info_flags: VTEncodeInfoFlags,
     sample_buffer: *mut CMSampleBuffer,
 ) {
+    // Cast context
+    let context = unsafe { &*(output_callback_ref_con as *const EncoderContext) };
+
     if status != 0 {
         eprintln!("VTCompressionSession callback error: {status}");
-        return;
     }
-
-    if sample_buffer.is_null() {
+    if status != 0
+        || sample_buffer.is_null()
+        || info_flags.0 & VTEncodeInfoFlags::FrameDropped.0 != 0
+    {
+        if let Ok(mut stats) = context.stats.lock() {
+            stats.dropped_frames += 1;
+        }
         return;
     }
 
-    // Cast context
-    let context = unsafe { &*(output_callback_ref_con as *const EncoderContext) };
+    if let Ok(mut stats) = context.stats.lock() {
+        stats.encoded_frames += 1;
+        // The frame index travels as the source frame refcon
+        if unsafe { is_keyframe(sample_buffer) } {
+            stats.last_keyframe_idx = source_frame_ref_con.addr() as u64;
+        }
+        if let Some(data_buffer) = unsafe { (*sample_buffer).data_buffer() } {
+            stats.encoded_bytes += data_buffer.data_length() as u64;
+        }
+    }
 
     // Extract encoded data
     unsafe {
@@ -524,34 +901,40 @@ unsafe extern "C-unwind" fn encode_callback(
 }
 
 impl AppleEncoder {
-    /// Create a new Apple hardware encoder.
-    ///
-    /// # Errors
+    /// Create an encoder for frames of the given size.
     ///
-    /// Returns `CodecError::InitializationFailed` if `VideoToolbox` session creation fails.
-    pub fn new(codec: CodecType) -> Result<Self, CodecError> {
-        Self::with_size(codec, 1920, 1080)
-    }
-
-    /// Create encoder with specific dimensions.
+    /// Pass `EncoderConfig::default()` to leave bitrate and keyframes to
+    /// `VideoToolbox`.
     ///
     /// # Errors
     ///
-    /// Returns `CodecError::InitializationFailed` if `VideoToolbox` session creation fails.
+    /// Returns `CodecError::InitializationFailed` if `VideoToolbox` session
+    /// creation fails or the encoder rejects a setting in `config`.
     ///
     /// # Panics
     ///
     /// Panics if the internal session pointer cannot be wrapped in `NonNull`.
-    pub fn with_size(codec: CodecType, width: u32, height: u32) -> Result<Self, CodecError> {
+    pub fn with_config(
+        codec: CodecType,
+        width: u32,
+        height: u32,
+        config: EncoderConfig,
+    ) -> Result<Self, CodecError> {
         let codec_type = match codec {
             CodecType::H264 => kCMVideoCodecType_H264,
             CodecType::H265 => kCMVideoCodecType_HEVC,
+            CodecType::Vp9 => {
+                return Err(CodecError::Unsupported(
+                    "VP9 encoding needs the vp9 feature's Vp9Encoder".into(),
+                ));
+            }
             _ => return Err(CodecError::Unsupported(format!("{codec:?}"))),
         };
 
         let context = Arc::new(EncoderContext {
             encoded_data: Mutex::new(Vec::new()),
             codec_config: Mutex::new(None),
+            stats: Mutex::new(StatsCounter::default()),
         });
         let context_ptr = Arc::as_ptr(&context) as *mut c_void;
 
@@ -580,6 +963,7 @@ impl AppleEncoder {
 
         let session = unsafe { Retained::retain(session_ptr) }
             .ok_or_else(|| CodecError::InitializationFailed("Failed to retain session".into()))?;
+        apply_config(Retained::as_ptr(&session).cast(), &config)?;
 
         Ok(Self {
             session,
@@ -640,9 +1024,10 @@ impl AppleEncoder {
         unsafe {
             use objc2_core_media::CMTimeFlags;
 
+            let frame_index = self.frame_count;
             let presentation_time = CMTime {
                 value: self.frame_count,
-                timescale: 30,
+                timescale: FRAME_RATE,
                 flags: CMTimeFlags(1),
                 epoch: 0,
             };
@@ -650,7 +1035,7 @@ impl AppleEncoder {
 
             let duration = CMTime {
                 value: 1,
-                timescale: 30,
+                timescale: FRAME_RATE,
                 flags: CMTimeFlags(1),
                 epoch: 0,
             };
@@ -661,7 +1046,7 @@ impl AppleEncoder {
                 presentation_time,
                 duration,
                 None,
-                ptr::null_mut(),
+                ptr::without_provenance_mut(usize::try_from(frame_index).unwrap_or(0)),
                 &raw mut info_flags,
             );
 
@@ -709,6 +1094,48 @@ impl AppleEncoder {
 }
 
 impl VideoEncoder for AppleEncoder {
+    fn stats(&self) -> EncoderStats {
+        self.context
+            .stats
+            .lock()
+            .map(|stats| stats.stats())
+            .unwrap_or_default()
+    }
+
+    fn flush(&mut self) -> Result<Vec<Vec<u8>>, CodecError> {
+        // Output of the last encode() is still in the buffer
+        if let Ok(mut lock) = self.context.encoded_data.lock() {
+            lock.clear();
+        }
+
+        // An invalid time completes every pending frame
+        let status = unsafe {
+            self.session.complete_frames(CMTime {
+                value: 0,
+                timescale: 0,
+                flags: objc2_core_media::CMTimeFlags(0),
+                epoch: 0,
+            })
+        };
+        if status != 0 {
+            return Err(CodecError::EncodingFailed(format!(
+                "complete_frames failed: {status}"
+            )));
+        }
+
+        let data = self
+            .context
+            .encoded_data
+            .lock()
+            .map(|mut lock| std::mem::take(&mut *lock))
+            .map_err(|_| CodecError::Unknown("Lock error".into()))?;
+        Ok(if data.is_empty() {
+            Vec::new()
+        } else {
+            vec![data]
+        })
+    }
+
     #[allow(clippy::too_many_lines)]
     fn encode(&mut self, frame: &Frame) -> Result<Vec<u8>, CodecError> {
         // Validate dimensions
@@ -796,16 +1223,17 @@ impl VideoEncoder for AppleEncoder {
             use objc2_core_media::CMTimeFlags;
 
             // Create presentation time
+            let frame_index = self.frame_count;
             let presentation_time = CMTime {
                 value: self.frame_count,
-                timescale: 30,
+                timescale: FRAME_RATE,
                 flags: CMTimeFlags(1),
                 epoch: 0,
             };
             self.frame_count += 1;
             let duration = CMTime {
                 value: 1,
-                timescale: 30,
+                timescale: FRAME_RATE,
                 flags: CMTimeFlags(1),
                 epoch: 0,
             };
@@ -816,8 +1244,8 @@ impl VideoEncoder for AppleEncoder {
                 pixel_buffer_ref,
                 presentation_time,
                 duration,
-                None,            // frameProperties
-                ptr::null_mut(), // sourceFrameRefCon
+                None, // frameProperties
+                ptr::without_provenance_mut(usize::try_from(frame_index).unwrap_or(0)), // sourceFrameRefCon
                 &raw mut info_flags,
             );
 
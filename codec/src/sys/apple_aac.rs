@@ -0,0 +1,426 @@
+//! AAC encoding and decoding with `AudioToolbox` audio converters.
+//!
+//! Converters pull their input through a callback. Each call hands over a
+//! single AAC frame (or 1024 PCM frames); once it is consumed the callback
+//! reports `NO_MORE_INPUT`, which returns control with whatever output the
+//! converter could produce so far.
+
+use crate::CodecError;
+use crate::aac::{AudioConfig, FRAME_LEN};
+use std::ffi::c_void;
+use std::ptr;
+
+/// `kAudioFormatLinearPCM`
+const FORMAT_LINEAR_PCM: u32 = u32::from_be_bytes(*b"lpcm");
+/// `kAudioFormatMPEG4AAC`
+const FORMAT_MPEG4_AAC: u32 = u32::from_be_bytes(*b"aac ");
+/// `kAudioFormatFlagIsFloat | kAudioFormatFlagIsPacked`
+const FORMAT_FLAGS_FLOAT_PACKED: u32 = 1 | 8;
+/// `kMPEG4Object_AAC_LC`
+const MPEG4_OBJECT_AAC_LC: u32 = 2;
+/// `kAudioEncoderComponentType`
+const ENCODER_COMPONENT_TYPE: u32 = u32::from_be_bytes(*b"aenc");
+/// `kAppleSoftwareAudioCodecManufacturer`
+const APPLE_SOFTWARE_CODEC: u32 = u32::from_be_bytes(*b"appl");
+/// `kAudioConverterEncodeBitRate`
+const PROPERTY_ENCODE_BIT_RATE: u32 = u32::from_be_bytes(*b"brat");
+/// `kAudioConverterPropertyMaximumOutputPacketSize`
+const PROPERTY_MAXIMUM_OUTPUT_PACKET_SIZE: u32 = u32::from_be_bytes(*b"xops");
+
+/// Returned by the input callbacks once their data has been consumed.
+const NO_MORE_INPUT: i32 = i32::from_be_bytes(*b"!dat");
+
+/// Size of an f32 sample in bytes.
+const SAMPLE_SIZE: u32 = 4;
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(non_snake_case)]
+struct AudioStreamBasicDescription {
+    mSampleRate: f64,
+    mFormatID: u32,
+    mFormatFlags: u32,
+    mBytesPerPacket: u32,
+    mFramesPerPacket: u32,
+    mBytesPerFrame: u32,
+    mChannelsPerFrame: u32,
+    mBitsPerChannel: u32,
+    mReserved: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(non_snake_case)]
+struct AudioStreamPacketDescription {
+    mStartOffset: i64,
+    mVariableFramesInPacket: u32,
+    mDataByteSize: u32,
+}
+
+#[repr(C)]
+#[allow(non_snake_case)]
+struct AudioBuffer {
+    mNumberChannels: u32,
+    mDataByteSize: u32,
+    mData: *mut c_void,
+}
+
+#[repr(C)]
+#[allow(non_snake_case)]
+struct AudioBufferList {
+    mNumberBuffers: u32,
+    mBuffers: [AudioBuffer; 1],
+}
+
+#[repr(C)]
+#[allow(non_snake_case)]
+struct AudioClassDescription {
+    mType: u32,
+    mSubType: u32,
+    mManufacturer: u32,
+}
+
+type AudioConverterRef = *mut c_void;
+
+type InputDataProc = unsafe extern "C" fn(
+    converter: AudioConverterRef,
+    io_number_data_packets: *mut u32,
+    io_data: *mut AudioBufferList,
+    out_packet_descriptions: *mut *mut AudioStreamPacketDescription,
+    user_data: *mut c_void,
+) -> i32;
+
+#[link(name = "AudioToolbox", kind = "framework")]
+unsafe extern "C" {
+    fn AudioConverterNew(
+        source: *const AudioStreamBasicDescription,
+        destination: *const AudioStreamBasicDescription,
+        converter: *mut AudioConverterRef,
+    ) -> i32;
+    fn AudioConverterNewSpecificCodec(
+        source: *const AudioStreamBasicDescription,
+        destination: *const AudioStreamBasicDescription,
+        number_class_descriptions: u32,
+        class_descriptions: *const AudioClassDescription,
+        converter: *mut AudioConverterRef,
+    ) -> i32;
+    fn AudioConverterSetProperty(
+        converter: AudioConverterRef,
+        property: u32,
+        size: u32,
+        data: *const c_void,
+    ) -> i32;
+    fn AudioConverterGetProperty(
+        converter: AudioConverterRef,
+        property: u32,
+        size: *mut u32,
+        data: *mut c_void,
+    ) -> i32;
+    fn AudioConverterFillComplexBuffer(
+        converter: AudioConverterRef,
+        input_proc: InputDataProc,
+        user_data: *mut c_void,
+        io_output_data_packet_size: *mut u32,
+        out_output_data: *mut AudioBufferList,
+        out_packet_description: *mut AudioStreamPacketDescription,
+    ) -> i32;
+    fn AudioConverterDispose(converter: AudioConverterRef) -> i32;
+}
+
+/// Interleaved float PCM in `config`.
+fn pcm_description(config: AudioConfig) -> AudioStreamBasicDescription {
+    let channels = u32::from(config.channels);
+    AudioStreamBasicDescription {
+        mSampleRate: f64::from(config.sample_rate),
+        mFormatID: FORMAT_LINEAR_PCM,
+        mFormatFlags: FORMAT_FLAGS_FLOAT_PACKED,
+        mBytesPerPacket: SAMPLE_SIZE * channels,
+        mFramesPerPacket: 1,
+        mBytesPerFrame: SAMPLE_SIZE * channels,
+        mChannelsPerFrame: channels,
+        mBitsPerChannel: SAMPLE_SIZE * 8,
+        mReserved: 0,
+    }
+}
+
+/// AAC-LC in `config`.
+fn aac_description(config: AudioConfig) -> AudioStreamBasicDescription {
+    AudioStreamBasicDescription {
+        mSampleRate: f64::from(config.sample_rate),
+        mFormatID: FORMAT_MPEG4_AAC,
+        mFormatFlags: MPEG4_OBJECT_AAC_LC,
+        mFramesPerPacket: 1024,
+        mChannelsPerFrame: u32::from(config.channels),
+        ..AudioStreamBasicDescription::default()
+    }
+}
+
+/// Data handed to a converter by an input callback.
+struct Input {
+    data: Vec<u8>,
+    /// Packets in `data`, or zero once the converter has taken it.
+    packets: u32,
+    channels: u32,
+    /// Describes `data` as a single AAC packet when decoding.
+    description: Option<AudioStreamPacketDescription>,
+}
+
+impl Input {
+    fn new(channels: u8) -> Self {
+        Self {
+            data: Vec::new(),
+            packets: 0,
+            channels: u32::from(channels),
+            description: None,
+        }
+    }
+}
+
+unsafe extern "C" fn input_proc(
+    _converter: AudioConverterRef,
+    io_number_data_packets: *mut u32,
+    io_data: *mut AudioBufferList,
+    out_packet_descriptions: *mut *mut AudioStreamPacketDescription,
+    user_data: *mut c_void,
+) -> i32 {
+    unsafe {
+        let input = &mut *user_data.cast::<Input>();
+        if input.packets == 0 {
+            *io_number_data_packets = 0;
+            return NO_MORE_INPUT;
+        }
+
+        // The data stays alive until the callback is invoked again
+        let buffer = &mut (*io_data).mBuffers[0];
+        buffer.mNumberChannels = input.channels;
+        buffer.mDataByteSize = u32::try_from(input.data.len()).unwrap_or(u32::MAX);
+        buffer.mData = input.data.as_mut_ptr().cast();
+        *io_number_data_packets = input.packets;
+        if !out_packet_descriptions.is_null()
+            && let Some(description) = &mut input.description
+        {
+            *out_packet_descriptions = description;
+        }
+        input.packets = 0;
+        0
+    }
+}
+
+/// Run `converter` until it needs more input, passing `output` each fill of
+/// an `output_size` byte buffer with up to `output_packets` packets.
+fn drain(
+    converter: AudioConverterRef,
+    input: &mut Input,
+    output_size: u32,
+    output_packets: u32,
+    mut output: impl FnMut(&[u8]),
+) -> Result<(), i32> {
+    let mut buffer = vec![0u8; output_size as usize];
+    loop {
+        let mut list = AudioBufferList {
+            mNumberBuffers: 1,
+            mBuffers: [AudioBuffer {
+                mNumberChannels: input.channels,
+                mDataByteSize: output_size,
+                mData: buffer.as_mut_ptr().cast(),
+            }],
+        };
+        let mut packets = output_packets;
+        let mut description = AudioStreamPacketDescription::default();
+        let status = unsafe {
+            AudioConverterFillComplexBuffer(
+                converter,
+                input_proc,
+                ptr::from_mut(input).cast(),
+                &raw mut packets,
+                &raw mut list,
+                &raw mut description,
+            )
+        };
+        if status != 0 && status != NO_MORE_INPUT {
+            return Err(status);
+        }
+        if packets == 0 {
+            return Ok(());
+        }
+        let len = (list.mBuffers[0].mDataByteSize as usize).min(buffer.len());
+        output(&buffer[..len]);
+    }
+}
+
+pub struct AacEncoderInner {
+    converter: AudioConverterRef,
+    input: Input,
+    max_packet_size: u32,
+}
+
+// The converter is only used through `&mut self`
+unsafe impl Send for AacEncoderInner {}
+
+impl AacEncoderInner {
+    pub fn new(config: AudioConfig, bit_rate: u32) -> Result<Self, CodecError> {
+        let source = pcm_description(config);
+        let destination = aac_description(config);
+        let codec = AudioClassDescription {
+            mType: ENCODER_COMPONENT_TYPE,
+            mSubType: FORMAT_MPEG4_AAC,
+            mManufacturer: APPLE_SOFTWARE_CODEC,
+        };
+
+        let mut converter = ptr::null_mut();
+        let status = unsafe {
+            AudioConverterNewSpecificCodec(
+                &raw const source,
+                &raw const destination,
+                1,
+                &raw const codec,
+                &raw mut converter,
+            )
+        };
+        if status != 0 || converter.is_null() {
+            return Err(CodecError::InitializationFailed(format!(
+                "AudioConverterNewSpecificCodec failed: {status}"
+            )));
+        }
+        // Disposed on drop from here on
+        let mut encoder = Self {
+            converter,
+            input: Input::new(config.channels),
+            max_packet_size: 0,
+        };
+
+        let status = unsafe {
+            AudioConverterSetProperty(
+                converter,
+                PROPERTY_ENCODE_BIT_RATE,
+                4,
+                (&raw const bit_rate).cast(),
+            )
+        };
+        if status != 0 {
+            return Err(CodecError::InitializationFailed(format!(
+                "unsupported bit rate {bit_rate}: {status}"
+            )));
+        }
+
+        let mut size = 4;
+        let status = unsafe {
+            AudioConverterGetProperty(
+                converter,
+                PROPERTY_MAXIMUM_OUTPUT_PACKET_SIZE,
+                &raw mut size,
+                (&raw mut encoder.max_packet_size).cast(),
+            )
+        };
+        if status != 0 || encoder.max_packet_size == 0 {
+            // The largest AAC frame is 6144 bits per channel
+            encoder.max_packet_size = 768 * u32::from(config.channels);
+        }
+        Ok(encoder)
+    }
+
+    pub fn encode_frame(&mut self, samples: &[f32]) -> Result<Vec<Vec<u8>>, CodecError> {
+        self.input.data.clear();
+        self.input
+            .data
+            .extend(samples.iter().flat_map(|sample| sample.to_ne_bytes()));
+        self.input.packets = u32::try_from(FRAME_LEN).unwrap_or(u32::MAX);
+
+        let mut packets = Vec::new();
+        drain(
+            self.converter,
+            &mut self.input,
+            self.max_packet_size,
+            1,
+            |packet| packets.push(packet.to_vec()),
+        )
+        .map_err(|status| {
+            CodecError::EncodingFailed(format!("AudioConverterFillComplexBuffer failed: {status}"))
+        })?;
+        Ok(packets)
+    }
+}
+
+impl Drop for AacEncoderInner {
+    fn drop(&mut self) {
+        unsafe {
+            AudioConverterDispose(self.converter);
+        }
+    }
+}
+
+pub struct AacDecoderInner {
+    converter: AudioConverterRef,
+    input: Input,
+}
+
+// The converter is only used through `&mut self`
+unsafe impl Send for AacDecoderInner {}
+
+impl AacDecoderInner {
+    pub fn new(config: AudioConfig) -> Result<Self, CodecError> {
+        let source = aac_description(config);
+        let destination = pcm_description(config);
+
+        let mut converter = ptr::null_mut();
+        let status = unsafe {
+            AudioConverterNew(
+                &raw const source,
+                &raw const destination,
+                &raw mut converter,
+            )
+        };
+        if status != 0 || converter.is_null() {
+            return Err(CodecError::InitializationFailed(format!(
+                "AudioConverterNew failed: {status}"
+            )));
+        }
+        Ok(Self {
+            converter,
+            input: Input::new(config.channels),
+        })
+    }
+
+    pub fn decode_frame(
+        &mut self,
+        frame: &[u8],
+        header_len: usize,
+        out: &mut Vec<f32>,
+    ) -> Result<(), CodecError> {
+        let payload = &frame[header_len..];
+        self.input.data.clear();
+        self.input.data.extend_from_slice(payload);
+        self.input.packets = 1;
+        self.input.description = Some(AudioStreamPacketDescription {
+            mStartOffset: 0,
+            mVariableFramesInPacket: 0,
+            mDataByteSize: u32::try_from(payload.len()).unwrap_or(u32::MAX),
+        });
+
+        let frames = u32::try_from(FRAME_LEN).unwrap_or(u32::MAX);
+        let output_size = frames * SAMPLE_SIZE * self.input.channels;
+        drain(
+            self.converter,
+            &mut self.input,
+            output_size,
+            frames,
+            |pcm| {
+                out.extend(
+                    pcm.chunks_exact(SAMPLE_SIZE as usize)
+                        .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+                );
+            },
+        )
+        .map_err(|status| {
+            CodecError::DecodingFailed(format!("AudioConverterFillComplexBuffer failed: {status}"))
+        })
+    }
+}
+
+impl Drop for AacDecoderInner {
+    fn drop(&mut self) {
+        unsafe {
+            AudioConverterDispose(self.converter);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! Image encoding and decoding with `ImageIO`.
+//!
+//! `ImageIO` uses the hardware JPEG codec where the device has one. Core
+//! Graphics only draws into premultiplied bitmaps, so decoded pixels are
+//! unpremultiplied with vImage to match the other platforms.
+
+use crate::CodecError;
+use crate::image::ImageFormat;
+use std::ffi::c_void;
+use std::ptr;
+
+/// `kCFStringEncodingUTF8`
+const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+/// `kCFNumberFloat64Type`
+const CF_NUMBER_FLOAT64_TYPE: isize = 6;
+/// `kCGImageAlphaPremultipliedLast | kCGBitmapByteOrder32Big`: RGBA bytes.
+const BITMAP_RGBA_PREMULTIPLIED: u32 = 1 | (4 << 12);
+/// `kCGImageAlphaLast | kCGBitmapByteOrder32Big`: RGBA bytes, straight alpha.
+const BITMAP_RGBA: u32 = 3 | (4 << 12);
+/// `kCGRenderingIntentDefault`
+const RENDERING_INTENT_DEFAULT: i32 = 0;
+/// `kvImageNoFlags`
+const VIMAGE_NO_FLAGS: u32 = 0;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CGPoint {
+    x: f64,
+    y: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CGSize {
+    width: f64,
+    height: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CGRect {
+    origin: CGPoint,
+    size: CGSize,
+}
+
+#[repr(C)]
+struct VImageBuffer {
+    data: *mut c_void,
+    height: usize,
+    width: usize,
+    row_bytes: usize,
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+#[link(name = "CoreGraphics", kind = "framework")]
+#[link(name = "ImageIO", kind = "framework")]
+#[link(name = "Accelerate", kind = "framework")]
+unsafe extern "C" {
+    static kCFTypeDictionaryKeyCallBacks: c_void;
+    static kCFTypeDictionaryValueCallBacks: c_void;
+    static kCGImageDestinationLossyCompressionQuality: *const c_void;
+
+    fn CFRelease(cf: *const c_void);
+    fn CFDataCreate(allocator: *const c_void, bytes: *const u8, length: isize) -> *const c_void;
+    fn CFDataCreateMutable(allocator: *const c_void, capacity: isize) -> *mut c_void;
+    fn CFDataGetBytePtr(data: *const c_void) -> *const u8;
+    fn CFDataGetLength(data: *const c_void) -> isize;
+    fn CFStringCreateWithCString(
+        allocator: *const c_void,
+        c_str: *const i8,
+        encoding: u32,
+    ) -> *const c_void;
+    fn CFNumberCreate(
+        allocator: *const c_void,
+        number_type: isize,
+        value: *const c_void,
+    ) -> *const c_void;
+    fn CFDictionaryCreate(
+        allocator: *const c_void,
+        keys: *const *const c_void,
+        values: *const *const c_void,
+        count: isize,
+        key_callbacks: *const c_void,
+        value_callbacks: *const c_void,
+    ) -> *const c_void;
+
+    fn CGColorSpaceCreateDeviceRGB() -> *const c_void;
+    fn CGColorSpaceRelease(space: *const c_void);
+    fn CGDataProviderCreateWithCFData(data: *const c_void) -> *const c_void;
+    fn CGDataProviderRelease(provider: *const c_void);
+    fn CGImageCreate(
+        width: usize,
+        height: usize,
+        bits_per_component: usize,
+        bits_per_pixel: usize,
+        bytes_per_row: usize,
+        space: *const c_void,
+        bitmap_info: u32,
+        provider: *const c_void,
+        decode: *const f64,
+        should_interpolate: bool,
+        intent: i32,
+    ) -> *const c_void;
+    fn CGImageGetWidth(image: *const c_void) -> usize;
+    fn CGImageGetHeight(image: *const c_void) -> usize;
+    fn CGImageRelease(image: *const c_void);
+    fn CGBitmapContextCreate(
+        data: *mut c_void,
+        width: usize,
+        height: usize,
+        bits_per_component: usize,
+        bytes_per_row: usize,
+        space: *const c_void,
+        bitmap_info: u32,
+    ) -> *const c_void;
+    fn CGContextDrawImage(context: *const c_void, rect: CGRect, image: *const c_void);
+    fn CGContextRelease(context: *const c_void);
+
+    fn CGImageSourceCreateWithData(data: *const c_void, options: *const c_void) -> *const c_void;
+    fn CGImageSourceCreateImageAtIndex(
+        source: *const c_void,
+        index: usize,
+        options: *const c_void,
+    ) -> *const c_void;
+    fn CGImageDestinationCreateWithData(
+        data: *mut c_void,
+        uti: *const c_void,
+        count: usize,
+        options: *const c_void,
+    ) -> *const c_void;
+    fn CGImageDestinationAddImage(
+        destination: *const c_void,
+        image: *const c_void,
+        properties: *const c_void,
+    );
+    fn CGImageDestinationFinalize(destination: *const c_void) -> bool;
+
+    fn vImageUnpremultiplyData_RGBA8888(
+        src: *const VImageBuffer,
+        dest: *const VImageBuffer,
+        flags: u32,
+    ) -> isize;
+}
+
+/// Release a Core Foundation object when dropped.
+struct Owned(*const c_void);
+
+impl Owned {
+    /// Take ownership of `ptr`, or fail with `error` if it is null.
+    fn new(ptr: *const c_void, error: impl FnOnce() -> CodecError) -> Result<Self, CodecError> {
+        if ptr.is_null() {
+            Err(error())
+        } else {
+            Ok(Self(ptr))
+        }
+    }
+}
+
+impl Drop for Owned {
+    fn drop(&mut self) {
+        unsafe { CFRelease(self.0) };
+    }
+}
+
+pub fn encode_image(
+    rgba: &[u8],
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+    quality: u8,
+) -> Result<Vec<u8>, CodecError> {
+    let failed = |step: &str| CodecError::EncodingFailed(format!("{step} failed"));
+    let (width, height) = (width as usize, height as usize);
+    let uti: &[u8] = match format {
+        ImageFormat::Jpeg => b"public.jpeg\0",
+        ImageFormat::Png => b"public.png\0",
+        ImageFormat::Heic => b"public.heic\0",
+    };
+
+    unsafe {
+        let pixels = Owned::new(
+            CFDataCreate(ptr::null(), rgba.as_ptr(), rgba.len().cast_signed()),
+            || failed("CFDataCreate"),
+        )?;
+        let provider = CGDataProviderCreateWithCFData(pixels.0);
+        let space = CGColorSpaceCreateDeviceRGB();
+        let image = CGImageCreate(
+            width,
+            height,
+            8,
+            32,
+            width * 4,
+            space,
+            BITMAP_RGBA,
+            provider,
+            ptr::null(),
+            false,
+            RENDERING_INTENT_DEFAULT,
+        );
+        CGColorSpaceRelease(space);
+        CGDataProviderRelease(provider);
+        if image.is_null() {
+            return Err(failed("CGImageCreate"));
+        }
+
+        let output = CFDataCreateMutable(ptr::null(), 0);
+        let uti =
+            CFStringCreateWithCString(ptr::null(), uti.as_ptr().cast(), CF_STRING_ENCODING_UTF8);
+        let destination = CGImageDestinationCreateWithData(output, uti, 1, ptr::null());
+        CFRelease(uti);
+        let output = Owned::new(output.cast_const(), || failed("CFDataCreateMutable"));
+        // ImageIO has no HEIC destination without a hardware HEVC encoder
+        let destination = Owned::new(destination, || match format {
+            ImageFormat::Heic => CodecError::Unsupported("no HEIC encoder on this device".into()),
+            _ => failed("CGImageDestinationCreateWithData"),
+        });
+        let (output, destination) = match (output, destination) {
+            (Ok(output), Ok(destination)) => (output, destination),
+            (Err(e), _) | (_, Err(e)) => {
+                CGImageRelease(image);
+                return Err(e);
+            }
+        };
+
+        // PNG is lossless and ignores the quality
+        let quality = f64::from(quality) / 100.0;
+        let quality = CFNumberCreate(
+            ptr::null(),
+            CF_NUMBER_FLOAT64_TYPE,
+            (&raw const quality).cast(),
+        );
+        let keys = [kCGImageDestinationLossyCompressionQuality];
+        let values = [quality];
+        let properties = CFDictionaryCreate(
+            ptr::null(),
+            keys.as_ptr(),
+            values.as_ptr(),
+            1,
+            (&raw const kCFTypeDictionaryKeyCallBacks).cast(),
+            (&raw const kCFTypeDictionaryValueCallBacks).cast(),
+        );
+        CFRelease(quality);
+
+        CGImageDestinationAddImage(destination.0, image, properties);
+        CFRelease(properties);
+        CGImageRelease(image);
+        if !CGImageDestinationFinalize(destination.0) {
+            return Err(failed("CGImageDestinationFinalize"));
+        }
+
+        let length = CFDataGetLength(output.0).cast_unsigned();
+        Ok(std::slice::from_raw_parts(CFDataGetBytePtr(output.0), length).to_vec())
+    }
+}
+
+pub fn decode_image(data: &[u8], _format: ImageFormat) -> Result<(Vec<u8>, u32, u32), CodecError> {
+    let failed = |step: &str| CodecError::DecodingFailed(format!("{step} failed"));
+
+    unsafe {
+        let input = Owned::new(
+            CFDataCreate(ptr::null(), data.as_ptr(), data.len().cast_signed()),
+            || failed("CFDataCreate"),
+        )?;
+        let source = Owned::new(CGImageSourceCreateWithData(input.0, ptr::null()), || {
+            failed("CGImageSourceCreateWithData")
+        })?;
+        let image = CGImageSourceCreateImageAtIndex(source.0, 0, ptr::null());
+        if image.is_null() {
+            return Err(failed("CGImageSourceCreateImageAtIndex"));
+        }
+
+        let width = CGImageGetWidth(image);
+        let height = CGImageGetHeight(image);
+        let (Ok(frame_width), Ok(frame_height)) = (u32::try_from(width), u32::try_from(height))
+        else {
+            CGImageRelease(image);
+            return Err(CodecError::DecodingFailed("image too large".into()));
+        };
+
+        // Draw into an RGBA bitmap, whatever the source's color model
+        let mut rgba = vec![0u8; width * height * 4];
+        let space = CGColorSpaceCreateDeviceRGB();
+        let context = CGBitmapContextCreate(
+            rgba.as_mut_ptr().cast(),
+            width,
+            height,
+            8,
+            width * 4,
+            space,
+            BITMAP_RGBA_PREMULTIPLIED,
+        );
+        CGColorSpaceRelease(space);
+        if context.is_null() {
+            CGImageRelease(image);
+            return Err(failed("CGBitmapContextCreate"));
+        }
+        #[allow(clippy::cast_precision_loss)] // image sizes are far below 2^52
+        let rect = CGRect {
+            origin: CGPoint { x: 0.0, y: 0.0 },
+            size: CGSize {
+                width: width as f64,
+                height: height as f64,
+            },
+        };
+        CGContextDrawImage(context, rect, image);
+        CGContextRelease(context);
+        CGImageRelease(image);
+
+        let buffer = VImageBuffer {
+            data: rgba.as_mut_ptr().cast(),
+            height,
+            width,
+            row_bytes: width * 4,
+        };
+        if vImageUnpremultiplyData_RGBA8888(&raw const buffer, &raw const buffer, VIMAGE_NO_FLAGS)
+            != 0
+        {
+            return Err(failed("vImageUnpremultiplyData_RGBA8888"));
+        }
+
+        Ok((rgba, frame_width, frame_height))
+    }
+}
@@ -0,0 +1,107 @@
+//! AAC encoding and decoding with the Fraunhofer FDK AAC library.
+
+use crate::CodecError;
+use crate::aac::{AudioConfig, FRAME_LEN};
+use fdk_aac::dec::{Decoder, DecoderError, Transport as DecoderTransport};
+use fdk_aac::enc::{AudioObjectType, BitRate, ChannelMode, Encoder, EncoderParams, Transport};
+
+/// Largest decoded frame: 2048 samples (with SBR) for up to 8 channels.
+const MAX_DECODED_SAMPLES: usize = 2048 * 8;
+
+pub struct AacEncoderInner {
+    encoder: Encoder,
+    /// Scratch space for one encoded frame.
+    output: Vec<u8>,
+}
+
+impl AacEncoderInner {
+    pub fn new(config: AudioConfig, bit_rate: u32) -> Result<Self, CodecError> {
+        let encoder = Encoder::new(EncoderParams {
+            bit_rate: BitRate::Cbr(bit_rate),
+            sample_rate: config.sample_rate,
+            // Raw frames; the ADTS header is added by the caller
+            transport: Transport::Raw,
+            channels: if config.channels == 1 {
+                ChannelMode::Mono
+            } else {
+                ChannelMode::Stereo
+            },
+            audio_object_type: AudioObjectType::Mpeg4LowComplexity,
+        })
+        .map_err(|e| CodecError::InitializationFailed(format!("{e:?}")))?;
+        Ok(Self {
+            encoder,
+            // The largest AAC frame is 6144 bits per channel
+            output: vec![0; 768 * usize::from(config.channels)],
+        })
+    }
+
+    pub fn encode_frame(&mut self, samples: &[f32]) -> Result<Vec<Vec<u8>>, CodecError> {
+        debug_assert_eq!(samples.len() % FRAME_LEN, 0);
+        #[allow(clippy::cast_possible_truncation)] // clamped to the i16 range
+        let pcm: Vec<i16> = samples
+            .iter()
+            .map(|&sample| (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16)
+            .collect();
+        let info = self
+            .encoder
+            .encode(&pcm, &mut self.output)
+            .map_err(|e| CodecError::EncodingFailed(format!("{e:?}")))?;
+        // Nothing comes out while the encoder fills its look-ahead
+        Ok(if info.output_size == 0 {
+            Vec::new()
+        } else {
+            vec![self.output[..info.output_size].to_vec()]
+        })
+    }
+}
+
+pub struct AacDecoderInner {
+    decoder: Decoder,
+    /// Scratch space for one decoded frame.
+    pcm: Vec<i16>,
+}
+
+impl AacDecoderInner {
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn new(_config: AudioConfig) -> Result<Self, CodecError> {
+        // The decoder reads the configuration from each ADTS header
+        Ok(Self {
+            decoder: Decoder::new(DecoderTransport::Adts),
+            pcm: vec![0; MAX_DECODED_SAMPLES],
+        })
+    }
+
+    pub fn decode_frame(
+        &mut self,
+        frame: &[u8],
+        _header_len: usize,
+        out: &mut Vec<f32>,
+    ) -> Result<(), CodecError> {
+        let filled = self
+            .decoder
+            .fill(frame)
+            .map_err(|e| CodecError::DecodingFailed(format!("{e:?}")))?;
+        if filled < frame.len() {
+            return Err(CodecError::DecodingFailed(
+                "decoder input buffer full".into(),
+            ));
+        }
+
+        loop {
+            match self.decoder.decode_frame(&mut self.pcm) {
+                Ok(()) => {
+                    let len = self.decoder.decoded_frame_size().min(self.pcm.len());
+                    out.extend(
+                        self.pcm[..len]
+                            .iter()
+                            .map(|&sample| f32::from(sample) / f32::from(i16::MAX)),
+                    );
+                }
+                Err(DecoderError::NOT_ENOUGH_BITS) => break,
+                Err(e) => return Err(CodecError::DecodingFailed(format!("{e:?}"))),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+//! Image encoding and decoding with the pure-Rust `image` crate.
+
+use crate::CodecError;
+use crate::image::ImageFormat;
+use ::image::codecs::jpeg::JpegEncoder;
+use ::image::codecs::png::PngEncoder;
+use ::image::{DynamicImage, ExtendedColorType, ImageEncoder as _, RgbaImage};
+
+pub fn encode_image(
+    rgba: &[u8],
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+    quality: u8,
+) -> Result<Vec<u8>, CodecError> {
+    let mut out = Vec::new();
+    match format {
+        ImageFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let image = RgbaImage::from_raw(width, height, rgba.to_vec())
+                .ok_or_else(|| CodecError::EncodingFailed("frame data too short".into()))?;
+            let rgb = DynamicImage::ImageRgba8(image).into_rgb8();
+            JpegEncoder::new_with_quality(&mut out, quality)
+                .encode_image(&rgb)
+                .map_err(|e| CodecError::EncodingFailed(e.to_string()))?;
+        }
+        ImageFormat::Png => PngEncoder::new(&mut out)
+            .write_image(rgba, width, height, ExtendedColorType::Rgba8)
+            .map_err(|e| CodecError::EncodingFailed(e.to_string()))?,
+        ImageFormat::Heic => {
+            return Err(CodecError::Unsupported(
+                "HEIC encoding is only available on Apple platforms".into(),
+            ));
+        }
+    }
+    Ok(out)
+}
+
+pub fn decode_image(data: &[u8], format: ImageFormat) -> Result<(Vec<u8>, u32, u32), CodecError> {
+    let format = match format {
+        ImageFormat::Jpeg => ::image::ImageFormat::Jpeg,
+        ImageFormat::Png => ::image::ImageFormat::Png,
+        ImageFormat::Heic => {
+            return Err(CodecError::Unsupported(
+                "HEIC decoding is not available on this platform".into(),
+            ));
+        }
+    };
+    let image = ::image::load_from_memory_with_format(data, format)
+        .map_err(|e| CodecError::DecodingFailed(e.to_string()))?
+        .into_rgba8();
+    let (width, height) = image.dimensions();
+    Ok((image.into_raw(), width, height))
+}
@@ -0,0 +1,28 @@
+//! Hardware codec discovery on Linux.
+//!
+//! V4L2 memory-to-memory devices cover the codec blocks of ARM boards, and
+//! VA-API (with the `vaapi` feature) the GPUs of desktops and laptops.
+
+use crate::CodecInfo;
+
+/// List the hardware encoders and decoders of every source.
+pub fn codec_infos() -> (Vec<CodecInfo>, Vec<CodecInfo>) {
+    let infos = super::v4l2::infos();
+    #[cfg(feature = "vaapi")]
+    let infos = {
+        let mut infos = infos;
+        for info in super::vaapi::infos() {
+            if !infos.contains(&info) {
+                infos.push(info);
+            }
+        }
+        infos
+    };
+
+    let (encoders, decoders): (Vec<_>, Vec<_>) =
+        infos.into_iter().partition(|(is_encoder, _)| *is_encoder);
+    (
+        encoders.into_iter().map(|(_, info)| info).collect(),
+        decoders.into_iter().map(|(_, info)| info).collect(),
+    )
+}
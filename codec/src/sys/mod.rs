@@ -2,14 +2,40 @@ cfg_if::cfg_if! {
     if #[cfg(target_vendor = "apple")] {
         mod apple;
         pub use apple::*;
+        mod apple_image;
+        pub(crate) use apple_image::{decode_image, encode_image};
+        mod apple_aac;
+        pub(crate) use apple_aac::{AacDecoderInner, AacEncoderInner};
     } else if #[cfg(target_os = "android")] {
         mod android;
         pub use android::*;
+        mod android_image;
+        pub use android_image::{decode_image, encode_image};
+        mod android_aac;
+        pub use android_aac::{AacDecoderInner, AacEncoderInner};
     } else if #[cfg(target_os = "windows")] {
         mod windows;
         pub use windows::*;
+        mod desktop_image;
+        pub use desktop_image::{decode_image, encode_image};
+        mod desktop_aac;
+        pub use desktop_aac::{AacDecoderInner, AacEncoderInner};
     } else {
+        #[cfg(target_os = "linux")]
+        mod linux;
+        #[cfg(target_os = "linux")]
+        mod v4l2;
+        #[cfg(all(target_os = "linux", feature = "vaapi"))]
+        mod vaapi;
+        #[cfg(target_os = "linux")]
+        pub use linux::codec_infos;
+        #[cfg(not(target_os = "linux"))]
         mod stub;
-        pub use stub::*;
+        #[cfg(not(target_os = "linux"))]
+        pub use stub::codec_infos;
+        mod desktop_image;
+        pub use desktop_image::{decode_image, encode_image};
+        mod desktop_aac;
+        pub use desktop_aac::{AacDecoderInner, AacEncoderInner};
     }
 }
@@ -1,30 +1,7 @@
-//! Stub implementation for unsupported platforms (e.g. Linux for now).
-use crate::{CodecError, CodecType, Frame, VideoDecoder, VideoEncoder};
+//! Stub implementation for platforms without a codec API.
+use crate::CodecInfo;
 
-pub struct StubEncoder;
-
-impl StubEncoder {
-    pub fn new(_codec: CodecType) -> Result<Self, CodecError> {
-        Err(CodecError::NotSupported)
-    }
-}
-
-impl VideoEncoder for StubEncoder {
-    fn encode(&mut self, _frame: &Frame) -> Result<Vec<u8>, CodecError> {
-        Err(CodecError::NotSupported)
-    }
-}
-
-pub struct StubDecoder;
-
-impl StubDecoder {
-    pub fn new(_codec: CodecType) -> Result<Self, CodecError> {
-        Err(CodecError::NotSupported)
-    }
-}
-
-impl VideoDecoder for StubDecoder {
-    fn decode(&mut self, _data: &[u8]) -> Result<Vec<Frame>, CodecError> {
-        Err(CodecError::NotSupported)
-    }
+/// No encoders or decoders are available on this platform.
+pub const fn codec_infos() -> (Vec<CodecInfo>, Vec<CodecInfo>) {
+    (Vec::new(), Vec::new())
 }
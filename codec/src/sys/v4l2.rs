@@ -0,0 +1,269 @@
+//! Codec discovery through V4L2 memory-to-memory devices.
+//!
+//! Hardware codecs on Linux (mostly on ARM boards) are exposed as `/dev/video*`
+//! nodes with an output queue that takes frames in and a capture queue that
+//! hands them back. Whichever queue carries a compressed format tells
+//! encoders and decoders apart.
+
+use crate::{CodecInfo, CodecType, PixelFormat, Resolution};
+use std::fs::{self, File, OpenOptions};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+
+// ioctl numbers from <linux/videodev2.h>
+const VIDIOC_QUERYCAP: u32 = 0x8068_5600;
+const VIDIOC_ENUM_FMT: u32 = 0xC040_5602;
+const VIDIOC_QUERYCTRL: u32 = 0xC044_5624;
+const VIDIOC_ENUM_FRAMESIZES: u32 = 0xC02C_564A;
+
+const V4L2_CAP_VIDEO_M2M_MPLANE: u32 = 0x0000_4000;
+const V4L2_CAP_VIDEO_M2M: u32 = 0x0000_8000;
+const V4L2_CAP_DEVICE_CAPS: u32 = 0x8000_0000;
+
+const V4L2_BUF_TYPE_VIDEO_CAPTURE: u32 = 1;
+const V4L2_BUF_TYPE_VIDEO_OUTPUT: u32 = 2;
+const V4L2_BUF_TYPE_VIDEO_CAPTURE_MPLANE: u32 = 9;
+const V4L2_BUF_TYPE_VIDEO_OUTPUT_MPLANE: u32 = 10;
+
+const V4L2_FRMSIZE_TYPE_DISCRETE: u32 = 1;
+
+const V4L2_CID_MPEG_VIDEO_BITRATE: u32 = 0x0099_09CF;
+const V4L2_CTRL_FLAG_DISABLED: u32 = 0x0001;
+
+// The structs below mirror the kernel's layout, so not every field is read
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct Capability {
+    driver: [u8; 16],
+    card: [u8; 32],
+    bus_info: [u8; 32],
+    version: u32,
+    capabilities: u32,
+    device_caps: u32,
+    reserved: [u32; 3],
+}
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct FmtDesc {
+    index: u32,
+    buf_type: u32,
+    flags: u32,
+    description: [u8; 32],
+    pixelformat: u32,
+    mbus_code: u32,
+    reserved: [u32; 3],
+}
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct FrmSizeEnum {
+    index: u32,
+    pixel_format: u32,
+    size_type: u32,
+    /// `discrete` (width, height) or `stepwise` (min/max/step for width,
+    /// then height).
+    size: [u32; 6],
+    reserved: [u32; 2],
+}
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct QueryCtrl {
+    id: u32,
+    ctrl_type: u32,
+    name: [u8; 32],
+    minimum: i32,
+    maximum: i32,
+    step: i32,
+    default_value: i32,
+    flags: u32,
+    reserved: [u32; 2],
+}
+
+/// The codec a compressed V4L2 format carries. Stateless formats are
+/// included; they still mean the hardware can handle the codec.
+const fn codec_type(pixelformat: u32) -> Option<CodecType> {
+    match &pixelformat.to_le_bytes() {
+        b"H264" | b"S264" => Some(CodecType::H264),
+        b"HEVC" | b"S265" => Some(CodecType::H265),
+        b"VP80" | b"VP8F" => Some(CodecType::Vp8),
+        b"VP90" | b"VP9F" => Some(CodecType::Vp9),
+        b"AV01" | b"AV1F" => Some(CodecType::Av1),
+        _ => None,
+    }
+}
+
+const fn pixel_format(pixelformat: u32) -> Option<PixelFormat> {
+    match &pixelformat.to_le_bytes() {
+        b"NV12" | b"NM12" => Some(PixelFormat::Nv12),
+        b"YU12" | b"YM12" => Some(PixelFormat::I420),
+        // ABGR32 and XBGR32 are B, G, R, A in memory; RGBA32 and RGBX32 are R, G, B, A
+        b"AR24" | b"XR24" => Some(PixelFormat::Bgra),
+        b"AB24" | b"XB24" => Some(PixelFormat::Rgba),
+        _ => None,
+    }
+}
+
+/// Issue an ioctl that fills `arg`, returning whether it succeeded.
+fn ioctl<T>(file: &File, request: u32, arg: &mut T) -> bool {
+    // The request type is `c_ulong` on glibc and `c_int` on musl
+    #[allow(clippy::cast_possible_wrap, clippy::cast_lossless)]
+    let request = request as libc::Ioctl;
+    unsafe { libc::ioctl(file.as_raw_fd(), request, std::ptr::from_mut(arg)) == 0 }
+}
+
+/// The pixel formats a queue of the device supports.
+fn formats(file: &File, buf_type: u32) -> Vec<u32> {
+    (0..)
+        .map_while(|index| {
+            let mut desc = FmtDesc {
+                index,
+                buf_type,
+                ..FmtDesc::default()
+            };
+            ioctl(file, VIDIOC_ENUM_FMT, &mut desc).then_some(desc.pixelformat)
+        })
+        .collect()
+}
+
+/// The largest frame size the device accepts for a compressed format.
+fn max_resolution(file: &File, pixel_format: u32) -> Option<Resolution> {
+    let mut largest: Option<Resolution> = None;
+    for index in 0.. {
+        let mut size = FrmSizeEnum {
+            index,
+            pixel_format,
+            ..FrmSizeEnum::default()
+        };
+        if !ioctl(file, VIDIOC_ENUM_FRAMESIZES, &mut size) {
+            break;
+        }
+        let resolution = if size.size_type == V4L2_FRMSIZE_TYPE_DISCRETE {
+            Resolution {
+                width: size.size[0],
+                height: size.size[1],
+            }
+        } else {
+            // Continuous and stepwise ranges come as a single entry
+            return Some(Resolution {
+                width: size.size[1],
+                height: size.size[4],
+            });
+        };
+        if largest.is_none_or(|current| {
+            u64::from(current.width) * u64::from(current.height)
+                < u64::from(resolution.width) * u64::from(resolution.height)
+        }) {
+            largest = Some(resolution);
+        }
+    }
+    largest
+}
+
+/// The highest bitrate an encoder accepts, from its bitrate control.
+fn max_bitrate(file: &File) -> Option<u32> {
+    let mut ctrl = QueryCtrl {
+        id: V4L2_CID_MPEG_VIDEO_BITRATE,
+        ..QueryCtrl::default()
+    };
+    if !ioctl(file, VIDIOC_QUERYCTRL, &mut ctrl) || ctrl.flags & V4L2_CTRL_FLAG_DISABLED != 0 {
+        return None;
+    }
+    u32::try_from(ctrl.maximum).ok()
+}
+
+/// Describe one memory-to-memory device, or `None` for other video nodes.
+fn device_infos(file: &File) -> Option<Vec<(bool, CodecInfo)>> {
+    let mut cap = Capability::default();
+    if !ioctl(file, VIDIOC_QUERYCAP, &mut cap) {
+        return None;
+    }
+    let caps = if cap.capabilities & V4L2_CAP_DEVICE_CAPS == 0 {
+        cap.capabilities
+    } else {
+        cap.device_caps
+    };
+    let (output, capture) = if caps & V4L2_CAP_VIDEO_M2M_MPLANE != 0 {
+        (
+            V4L2_BUF_TYPE_VIDEO_OUTPUT_MPLANE,
+            V4L2_BUF_TYPE_VIDEO_CAPTURE_MPLANE,
+        )
+    } else if caps & V4L2_CAP_VIDEO_M2M != 0 {
+        (V4L2_BUF_TYPE_VIDEO_OUTPUT, V4L2_BUF_TYPE_VIDEO_CAPTURE)
+    } else {
+        return None;
+    };
+
+    let output = formats(file, output);
+    let capture = formats(file, capture);
+    // Decoders take compressed data in, encoders give it out
+    let is_encoder = capture.iter().any(|&format| codec_type(format).is_some());
+    let (compressed, raw) = if is_encoder {
+        (capture, output)
+    } else {
+        (output, capture)
+    };
+
+    let mut pixel_formats = Vec::new();
+    for format in raw.into_iter().filter_map(pixel_format) {
+        if !pixel_formats.contains(&format) {
+            pixel_formats.push(format);
+        }
+    }
+    let max_bitrate_bps = if is_encoder { max_bitrate(file) } else { None };
+
+    Some(
+        compressed
+            .into_iter()
+            .filter_map(|format| {
+                let codec = codec_type(format)?;
+                Some((
+                    is_encoder,
+                    CodecInfo {
+                        codec,
+                        is_hardware: true,
+                        max_resolution: max_resolution(file, format),
+                        max_bitrate_bps,
+                        supported_pixel_formats: pixel_formats.clone(),
+                    },
+                ))
+            })
+            .collect(),
+    )
+}
+
+/// Describe every memory-to-memory codec device, tagged with whether it
+/// encodes.
+pub fn infos() -> Vec<(bool, CodecInfo)> {
+    let Ok(entries) = fs::read_dir("/dev") else {
+        return Vec::new();
+    };
+    let mut infos: Vec<(bool, CodecInfo)> = Vec::new();
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with("video") {
+            continue;
+        }
+        // Opening doesn't claim the device, but shouldn't block on a busy one
+        let Ok(file) = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(entry.path())
+        else {
+            continue;
+        };
+        for info in device_infos(&file).unwrap_or_default() {
+            // Drivers may expose the same codec on several nodes
+            if !infos.contains(&info) {
+                infos.push(info);
+            }
+        }
+    }
+    infos
+}
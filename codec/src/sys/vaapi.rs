@@ -0,0 +1,130 @@
+//! Codec discovery through VA-API.
+//!
+//! Each DRM render node with a VA-API driver lists the profiles it handles,
+//! and for each profile its entrypoints: `VLD` decodes, the `EncSlice` ones
+//! encode.
+
+use crate::{CodecInfo, CodecType, PixelFormat, Resolution};
+use cros_libva::{
+    Display, DrmDeviceIterator, VA_ATTRIB_NOT_SUPPORTED, VA_RT_FORMAT_YUV420, VAConfigAttrib,
+    VAConfigAttribType, VAEntrypoint, VAProfile,
+};
+
+/// The codec of a VA-API profile.
+const fn codec_type(profile: VAProfile::Type) -> Option<CodecType> {
+    match profile {
+        VAProfile::VAProfileH264ConstrainedBaseline
+        | VAProfile::VAProfileH264Main
+        | VAProfile::VAProfileH264High => Some(CodecType::H264),
+        VAProfile::VAProfileHEVCMain | VAProfile::VAProfileHEVCMain10 => Some(CodecType::H265),
+        VAProfile::VAProfileVP8Version0_3 => Some(CodecType::Vp8),
+        VAProfile::VAProfileVP9Profile0 | VAProfile::VAProfileVP9Profile2 => Some(CodecType::Vp9),
+        VAProfile::VAProfileAV1Profile0 | VAProfile::VAProfileAV1Profile1 => Some(CodecType::Av1),
+        _ => None,
+    }
+}
+
+/// Whether an entrypoint encodes, or `None` if it neither encodes nor decodes.
+const fn is_encoder(entrypoint: VAEntrypoint::Type) -> Option<bool> {
+    match entrypoint {
+        VAEntrypoint::VAEntrypointVLD => Some(false),
+        VAEntrypoint::VAEntrypointEncSlice | VAEntrypoint::VAEntrypointEncSliceLP => Some(true),
+        _ => None,
+    }
+}
+
+/// Describe one profile and entrypoint from their config attributes.
+fn info(
+    display: &Display,
+    codec: CodecType,
+    profile: VAProfile::Type,
+    entrypoint: VAEntrypoint::Type,
+) -> CodecInfo {
+    let mut attributes = [
+        VAConfigAttribType::VAConfigAttribRTFormat,
+        VAConfigAttribType::VAConfigAttribMaxPictureWidth,
+        VAConfigAttribType::VAConfigAttribMaxPictureHeight,
+    ]
+    .map(|type_| VAConfigAttrib { type_, value: 0 });
+    let [rt_format, width, height] =
+        match display.get_config_attributes(profile, entrypoint, &mut attributes) {
+            Ok(()) => attributes.map(|attribute| {
+                (attribute.value != VA_ATTRIB_NOT_SUPPORTED).then_some(attribute.value)
+            }),
+            Err(_) => [None; 3],
+        };
+
+    CodecInfo {
+        codec,
+        is_hardware: true,
+        max_resolution: width
+            .zip(height)
+            .map(|(width, height)| Resolution { width, height }),
+        // VA-API has no attribute for it
+        max_bitrate_bps: None,
+        // 4:2:0 surfaces are NV12 in memory
+        supported_pixel_formats: rt_format
+            .filter(|formats| formats & VA_RT_FORMAT_YUV420 != 0)
+            .map(|_| vec![PixelFormat::Nv12])
+            .unwrap_or_default(),
+    }
+}
+
+/// Describe the codecs of one display, tagged with whether they encode.
+fn display_infos(display: &Display) -> Vec<(bool, CodecInfo)> {
+    let mut infos: Vec<(bool, CodecInfo)> = Vec::new();
+    for profile in display.query_config_profiles().unwrap_or_default() {
+        let Some(codec) = codec_type(profile) else {
+            continue;
+        };
+        for entrypoint in display
+            .query_config_entrypoints(profile)
+            .unwrap_or_default()
+        {
+            let Some(is_encoder) = is_encoder(entrypoint) else {
+                continue;
+            };
+            let info = info(display, codec, profile, entrypoint);
+            // Several profiles share a codec: keep the largest size and every format
+            match infos
+                .iter_mut()
+                .find(|(encodes, known)| *encodes == is_encoder && known.codec == codec)
+            {
+                Some((_, known)) => {
+                    if info.max_resolution.is_some_and(|new| {
+                        known.max_resolution.is_none_or(|current| {
+                            u64::from(current.width) * u64::from(current.height)
+                                < u64::from(new.width) * u64::from(new.height)
+                        })
+                    }) {
+                        known.max_resolution = info.max_resolution;
+                    }
+                    for format in info.supported_pixel_formats {
+                        if !known.supported_pixel_formats.contains(&format) {
+                            known.supported_pixel_formats.push(format);
+                        }
+                    }
+                }
+                None => infos.push((is_encoder, info)),
+            }
+        }
+    }
+    infos
+}
+
+/// Describe the codecs of every render node with a VA-API driver, tagged
+/// with whether they encode.
+pub fn infos() -> Vec<(bool, CodecInfo)> {
+    let mut infos: Vec<(bool, CodecInfo)> = Vec::new();
+    // Nodes without a driver fail to open and are skipped
+    for display in
+        DrmDeviceIterator::default().filter_map(|node| Display::open_drm_display(node).ok())
+    {
+        for info in display_infos(&display) {
+            if !infos.contains(&info) {
+                infos.push(info);
+            }
+        }
+    }
+    infos
+}
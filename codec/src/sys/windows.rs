@@ -1,6 +1,142 @@
 //! Windows Media Foundation implementation.
 
-use crate::{CodecError, CodecType, Frame, VideoDecoder, VideoEncoder};
+use crate::{CodecError, CodecInfo, CodecType, Frame, PixelFormat, VideoDecoder, VideoEncoder};
+use std::ptr;
+use windows::Win32::Media::MediaFoundation::{
+    IMFActivate, MFMediaType_Video, MFT_CATEGORY_VIDEO_DECODER, MFT_CATEGORY_VIDEO_ENCODER,
+    MFT_ENUM_FLAG, MFT_ENUM_FLAG_HARDWARE, MFT_ENUM_FLAG_SORTANDFILTER, MFT_ENUM_FLAG_SYNCMFT,
+    MFT_INPUT_TYPES_Attributes, MFT_OUTPUT_TYPES_Attributes, MFT_REGISTER_TYPE_INFO, MFTEnumEx,
+    MFVideoFormat_ABGR32, MFVideoFormat_ARGB32, MFVideoFormat_AV1, MFVideoFormat_H264,
+    MFVideoFormat_HEVC, MFVideoFormat_I420, MFVideoFormat_IYUV, MFVideoFormat_NV12,
+    MFVideoFormat_VP80, MFVideoFormat_VP90,
+};
+use windows::Win32::System::Com::CoTaskMemFree;
+use windows::core::GUID;
+
+const fn video_subtype(codec: CodecType) -> GUID {
+    match codec {
+        CodecType::H264 => MFVideoFormat_H264,
+        CodecType::H265 => MFVideoFormat_HEVC,
+        CodecType::Vp8 => MFVideoFormat_VP80,
+        CodecType::Vp9 => MFVideoFormat_VP90,
+        CodecType::Av1 => MFVideoFormat_AV1,
+    }
+}
+
+/// Enumerate the transforms Media Foundation registers for the format.
+fn enum_transforms(
+    category: GUID,
+    subtype: GUID,
+    encoder: bool,
+    flags: MFT_ENUM_FLAG,
+) -> Vec<IMFActivate> {
+    let info = MFT_REGISTER_TYPE_INFO {
+        guidMajorType: MFMediaType_Video,
+        guidSubtype: subtype,
+    };
+    let info_ptr = ptr::from_ref(&info);
+    // Encoders produce the format, decoders consume it
+    let (input, output) = if encoder {
+        (None, Some(info_ptr))
+    } else {
+        (Some(info_ptr), None)
+    };
+
+    let mut activates: *mut Option<IMFActivate> = ptr::null_mut();
+    let mut count = 0u32;
+    unsafe {
+        if MFTEnumEx(
+            category,
+            flags,
+            input,
+            output,
+            &raw mut activates,
+            &raw mut count,
+        )
+        .is_err()
+        {
+            return Vec::new();
+        }
+
+        let transforms = (0..count as usize)
+            .filter_map(|i| (*activates.add(i)).take())
+            .collect();
+        CoTaskMemFree(Some(activates.cast_const().cast()));
+        transforms
+    }
+}
+
+/// Map the uncompressed types a transform registered to the pixel formats this crate knows.
+fn pixel_formats(activate: &IMFActivate, key: &GUID) -> Vec<PixelFormat> {
+    let mut blob: *mut u8 = ptr::null_mut();
+    let mut size = 0u32;
+    if unsafe { activate.GetAllocatedBlob(key, &raw mut blob, &raw mut size) }.is_err() {
+        return Vec::new();
+    }
+
+    let types = unsafe {
+        std::slice::from_raw_parts(
+            blob.cast::<MFT_REGISTER_TYPE_INFO>().cast_const(),
+            size as usize / size_of::<MFT_REGISTER_TYPE_INFO>(),
+        )
+    };
+    let mut formats = Vec::new();
+    for info in types {
+        let format = match info.guidSubtype {
+            subtype if subtype == MFVideoFormat_NV12 => PixelFormat::Nv12,
+            subtype if subtype == MFVideoFormat_I420 || subtype == MFVideoFormat_IYUV => {
+                PixelFormat::I420
+            }
+            // ARGB32 is stored as BGRA, ABGR32 as RGBA
+            subtype if subtype == MFVideoFormat_ARGB32 => PixelFormat::Bgra,
+            subtype if subtype == MFVideoFormat_ABGR32 => PixelFormat::Rgba,
+            _ => continue,
+        };
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    unsafe { CoTaskMemFree(Some(blob.cast_const().cast())) };
+    formats
+}
+
+/// List the transforms Media Foundation registers in `category`.
+fn transform_infos(category: GUID, encoder: bool) -> Vec<CodecInfo> {
+    // Encoders take uncompressed input, decoders produce uncompressed output
+    let formats_key = if encoder {
+        MFT_INPUT_TYPES_Attributes
+    } else {
+        MFT_OUTPUT_TYPES_Attributes
+    };
+    let kinds = [
+        (true, MFT_ENUM_FLAG_HARDWARE | MFT_ENUM_FLAG_SORTANDFILTER),
+        (false, MFT_ENUM_FLAG_SYNCMFT | MFT_ENUM_FLAG_SORTANDFILTER),
+    ];
+
+    let mut infos = Vec::new();
+    for codec in CodecType::ALL {
+        for (is_hardware, flags) in kinds {
+            for activate in enum_transforms(category, video_subtype(codec), encoder, flags) {
+                infos.push(CodecInfo {
+                    codec,
+                    is_hardware,
+                    max_resolution: None,
+                    max_bitrate_bps: None,
+                    supported_pixel_formats: pixel_formats(&activate, &formats_key),
+                });
+            }
+        }
+    }
+    infos
+}
+
+/// List the encoder and decoder transforms Media Foundation registers.
+pub fn codec_infos() -> (Vec<CodecInfo>, Vec<CodecInfo>) {
+    (
+        transform_infos(MFT_CATEGORY_VIDEO_ENCODER, true),
+        transform_infos(MFT_CATEGORY_VIDEO_DECODER, false),
+    )
+}
 
 pub struct WindowsEncoder;
 
@@ -0,0 +1,118 @@
+//! VP9 software encoding (libvpx), for Apple platforms where `VideoToolbox`
+//! has no VP9 encoder.
+
+use crate::{CodecError, Frame, PixelFormat, VideoEncoder, yuv};
+use std::fmt;
+use vpx_encode::{Config, Encoder, VideoCodecId};
+
+/// VP9 software encoder using libvpx.
+pub struct Vp9Encoder {
+    /// Taken by [`flush`](VideoEncoder::flush), which ends the stream.
+    encoder: Option<Encoder>,
+    width: u32,
+    height: u32,
+    /// Presentation timestamp of the next frame, in frames.
+    pts: i64,
+}
+
+unsafe impl Send for Vp9Encoder {}
+unsafe impl Sync for Vp9Encoder {}
+
+impl fmt::Debug for Vp9Encoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vp9Encoder")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Vp9Encoder {
+    /// Create a new VP9 encoder for frames of `width` by `height` at `fps`
+    /// frames per second, averaging `bitrate_bps` bits per second.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::InitializationFailed` if libvpx rejects the
+    /// configuration, e.g. for odd dimensions.
+    pub fn new(width: u32, height: u32, fps: u32, bitrate_bps: u32) -> Result<Self, CodecError> {
+        let fps = i32::try_from(fps.max(1))
+            .map_err(|_| CodecError::InitializationFailed(format!("{fps} fps")))?;
+        let encoder = Encoder::new(Config {
+            width,
+            height,
+            timebase: [1, fps],
+            bitrate: bitrate_bps / 1000,
+            codec: VideoCodecId::VP9,
+        })
+        .map_err(|e| CodecError::InitializationFailed(e.to_string()))?;
+
+        Ok(Self {
+            encoder: Some(encoder),
+            width,
+            height,
+            pts: 0,
+        })
+    }
+}
+
+impl VideoEncoder for Vp9Encoder {
+    fn encode(&mut self, frame: &Frame) -> Result<Vec<u8>, CodecError> {
+        if frame.width != self.width || frame.height != self.height {
+            return Err(CodecError::EncodingFailed(format!(
+                "Frame size {}x{} doesn't match encoder {}x{}",
+                frame.width, frame.height, self.width, self.height
+            )));
+        }
+        let pixels = self.width as usize * self.height as usize;
+        let needed = match frame.format {
+            PixelFormat::Rgba | PixelFormat::Bgra => pixels * 4,
+            PixelFormat::Nv12 | PixelFormat::I420 => pixels * 3 / 2,
+        };
+        if frame.data.len() < needed {
+            return Err(CodecError::EncodingFailed(format!(
+                "{}x{} {:?} frame needs {needed} bytes, got {}",
+                frame.width,
+                frame.height,
+                frame.format,
+                frame.data.len()
+            )));
+        }
+        let encoder = self
+            .encoder
+            .as_mut()
+            .ok_or_else(|| CodecError::EncodingFailed("encoder already flushed".into()))?;
+
+        let planes: [Vec<u8>; 3] = yuv::i420_planes(frame).into();
+        let i420 = planes.concat();
+
+        let packets = encoder
+            .encode(self.pts, &i420)
+            .map_err(|e| CodecError::EncodingFailed(e.to_string()))?;
+        let mut output = Vec::new();
+        for packet in packets {
+            output.extend_from_slice(packet.data);
+        }
+        self.pts += 1;
+        Ok(output)
+    }
+
+    fn flush(&mut self) -> Result<Vec<Vec<u8>>, CodecError> {
+        let Some(encoder) = self.encoder.take() else {
+            return Ok(Vec::new());
+        };
+
+        // libvpx holds frames back for look-ahead until the end of the stream
+        let mut finish = encoder
+            .finish()
+            .map_err(|e| CodecError::EncodingFailed(e.to_string()))?;
+        let mut packets = Vec::new();
+        while let Some(packet) = finish
+            .next()
+            .map_err(|e| CodecError::EncodingFailed(e.to_string()))?
+        {
+            packets.push(packet.data.to_vec());
+        }
+        Ok(packets)
+    }
+}
@@ -0,0 +1,93 @@
+//! Conversion of frames to the I420 planes software encoders take.
+
+use crate::{Frame, PixelFormat};
+
+/// Split `frame` into I420 (YUV420 planar) Y, U and V planes.
+pub fn i420_planes(frame: &Frame) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let width = frame.width as usize;
+    let height = frame.height as usize;
+    match frame.format {
+        PixelFormat::Rgba | PixelFormat::Bgra => {
+            let rgba_data = if frame.format == PixelFormat::Bgra {
+                // Convert BGRA to RGBA
+                let mut rgba = frame.data.as_ref().clone();
+                for chunk in rgba.chunks_exact_mut(4) {
+                    chunk.swap(0, 2);
+                }
+                rgba
+            } else {
+                frame.data.as_ref().clone()
+            };
+            rgba_to_i420(&rgba_data, width, height)
+        }
+        PixelFormat::I420 => {
+            // Already I420 - split planes
+            let y_size = width * height;
+            let uv_size = (width / 2) * (height / 2);
+            let data = frame.data.as_ref();
+
+            (
+                data[0..y_size].to_vec(),
+                data[y_size..y_size + uv_size].to_vec(),
+                data[y_size + uv_size..].to_vec(),
+            )
+        }
+        PixelFormat::Nv12 => {
+            // NV12: Y plane followed by interleaved UV
+            let y_size = width * height;
+            let uv_size = (width / 2) * (height / 2);
+            let data = frame.data.as_ref();
+
+            let y_plane = data[0..y_size].to_vec();
+            let mut u_plane = vec![0u8; uv_size];
+            let mut v_plane = vec![0u8; uv_size];
+
+            // De-interleave UV
+            let uv_data = &data[y_size..];
+            for i in 0..uv_size {
+                u_plane[i] = uv_data[i * 2];
+                v_plane[i] = uv_data[i * 2 + 1];
+            }
+
+            (y_plane, u_plane, v_plane)
+        }
+    }
+}
+
+/// Convert RGBA to I420 (YUV420 planar).
+fn rgba_to_i420(rgba: &[u8], width: usize, height: usize) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let y_size = width * height;
+    let uv_size = (width / 2) * (height / 2);
+
+    let mut y_plane = vec![0u8; y_size];
+    let mut u_plane = vec![0u8; uv_size];
+    let mut v_plane = vec![0u8; uv_size];
+
+    for row_idx in 0..height {
+        for col_idx in 0..width {
+            let px_idx = (row_idx * width + col_idx) * 4;
+            let r_val = i32::from(rgba[px_idx]);
+            let g_val = i32::from(rgba[px_idx + 1]);
+            let b_val = i32::from(rgba[px_idx + 2]);
+
+            // BT.601 RGB to YUV conversion
+            let y_val = ((66 * r_val + 129 * g_val + 25 * b_val + 128) >> 8) + 16;
+            y_plane[row_idx * width + col_idx] = u8::try_from(y_val.clamp(0, 255)).unwrap_or(0);
+
+            // Subsample U and V (every 2x2 block)
+            if row_idx % 2 == 0 && col_idx % 2 == 0 {
+                let u_val = ((-38 * r_val - 74 * g_val + 112 * b_val + 128) >> 8) + 128;
+                let v_val = ((112 * r_val - 94 * g_val - 18 * b_val + 128) >> 8) + 128;
+
+                let uv_row = row_idx / 2;
+                let uv_col = col_idx / 2;
+                let uv_idx = uv_row * (width / 2) + uv_col;
+
+                u_plane[uv_idx] = u8::try_from(u_val.clamp(0, 255)).unwrap_or(0);
+                v_plane[uv_idx] = u8::try_from(v_val.clamp(0, 255)).unwrap_or(0);
+            }
+        }
+    }
+
+    (y_plane, u_plane, v_plane)
+}
@@ -0,0 +1,168 @@
+//! Crash-safe file replacement.
+//!
+//! Data goes to a temporary file next to the target, which is flushed to disk
+//! and then renamed over the target, so readers see either the old contents
+//! or the new ones, never a partial write.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+/// A file being written that replaces its target only when committed.
+///
+/// Created by [`crate::WaterFs::create_atomic`]. Dropping it without calling
+/// [`commit`](Self::commit) discards the data and leaves the target untouched.
+#[derive(Debug)]
+pub struct AtomicFile {
+    file: File,
+    temp: TempPath,
+    target: PathBuf,
+}
+
+impl AtomicFile {
+    pub(crate) fn create(target: &Path) -> io::Result<Self> {
+        let dir = parent(target);
+        let name = target
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+
+        loop {
+            let mut temp_name = std::ffi::OsString::from(".");
+            temp_name.push(name);
+            temp_name.push(format!(
+                ".{}.{}.tmp",
+                std::process::id(),
+                NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+            ));
+            let path = dir.join(temp_name);
+
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    return Ok(Self {
+                        file,
+                        temp: TempPath(Some(path)),
+                        target: target.to_path_buf(),
+                    });
+                }
+                // Left behind by a crashed process with a recycled pid
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Flush the data to disk and atomically replace the target with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if syncing or renaming fails. The temporary file
+    /// is removed and the target keeps its previous contents.
+    pub fn commit(self) -> io::Result<()> {
+        let Self {
+            mut file,
+            mut temp,
+            target,
+        } = self;
+        file.flush()?;
+        file.sync_data()?;
+        // Windows can't rename a file that is still open
+        drop(file);
+
+        let Some(path) = temp.0.as_deref() else {
+            return Ok(());
+        };
+        replace(path, &target)?;
+        temp.0 = None;
+        sync_dir(parent(&target));
+        Ok(())
+    }
+
+    /// Discard the data, deleting the temporary file.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the temporary file can't be removed.
+    pub fn cancel(self) -> io::Result<()> {
+        let Self { file, mut temp, .. } = self;
+        drop(file);
+        temp.0.take().map_or(Ok(()), std::fs::remove_file)
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Temporary file removed on drop unless it has been renamed.
+#[derive(Debug)]
+struct TempPath(Option<PathBuf>);
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if let Some(path) = self.0.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Write `data` to a new temporary file and commit it over `path`.
+pub(crate) fn write(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut file = AtomicFile::create(path)?;
+    file.write_all(data)?;
+    file.commit()
+}
+
+fn parent(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn replace(from: &Path, to: &Path) -> io::Result<()> {
+    std::fs::rename(from, to)
+}
+
+#[cfg(target_os = "windows")]
+fn replace(from: &Path, to: &Path) -> io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::Win32::Storage::FileSystem::{
+        MOVEFILE_REPLACE_EXISTING, MOVEFILE_WRITE_THROUGH, MoveFileExW,
+    };
+    use windows::core::PCWSTR;
+
+    let wide = |path: &Path| -> Vec<u16> { path.as_os_str().encode_wide().chain([0]).collect() };
+    let (from, to) = (wide(from), wide(to));
+    // SAFETY: both buffers are NUL-terminated and outlive the call
+    unsafe {
+        MoveFileExW(
+            PCWSTR(from.as_ptr()),
+            PCWSTR(to.as_ptr()),
+            MOVEFILE_REPLACE_EXISTING | MOVEFILE_WRITE_THROUGH,
+        )
+    }
+    .map_err(io::Error::from)
+}
+
+/// Persist the rename itself by syncing the directory entry.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    // Best effort: the data is already on disk, only the rename may be lost
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// `MOVEFILE_WRITE_THROUGH` already waits for the rename to reach the disk.
+#[cfg(not(unix))]
+const fn sync_dir(_dir: &Path) {}
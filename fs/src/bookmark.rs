@@ -0,0 +1,84 @@
+//! Persistent access to user-picked files.
+//!
+//! On iOS and in the macOS App Sandbox, a URL returned by a file picker only
+//! grants access for the current launch. A security-scoped bookmark can be
+//! stored and resolved later to get the file back, and access has to be
+//! claimed while it is in use. Other platforms have no such sandbox, so
+//! bookmarks are just the path.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Access to a security-scoped resource, released on drop.
+///
+/// Created by [`crate::WaterFs::start_accessing`].
+#[derive(Debug)]
+#[must_use = "access is released as soon as the guard is dropped"]
+pub struct SecurityScopeGuard {
+    path: PathBuf,
+}
+
+impl SecurityScopeGuard {
+    /// The path being accessed.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+impl Drop for SecurityScopeGuard {
+    fn drop(&mut self) {
+        if let Some(path) = self.path.to_str() {
+            crate::sys::stop_accessing(path);
+        }
+    }
+}
+
+fn path_str(path: &Path) -> io::Result<&str> {
+    path.to_str()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path is not valid UTF-8"))
+}
+
+pub(crate) fn create(path: &Path) -> io::Result<Vec<u8>> {
+    let path = path_str(path)?;
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    {
+        crate::sys::create_bookmark(path)
+    }
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    {
+        Ok(path.as_bytes().to_vec())
+    }
+}
+
+pub(crate) fn resolve(data: &[u8]) -> io::Result<(PathBuf, bool)> {
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    {
+        crate::sys::resolve_bookmark(data)
+    }
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    {
+        let path =
+            std::str::from_utf8(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok((PathBuf::from(path), false))
+    }
+}
+
+// Only Apple platforms can refuse access
+#[cfg_attr(
+    not(any(target_os = "ios", target_os = "macos")),
+    allow(clippy::unnecessary_wraps)
+)]
+pub(crate) fn start_accessing(path: &Path) -> io::Result<SecurityScopeGuard> {
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    if !crate::sys::start_accessing(path_str(path)?) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "path is not a security-scoped resource",
+        ));
+    }
+    Ok(SecurityScopeGuard {
+        path: path.to_path_buf(),
+    })
+}
@@ -3,11 +3,20 @@
 //! This crate provides a unified API for accessing common platform directories
 //! such as documents and cache folders across iOS, macOS, Android, Windows, and Linux.
 
+mod atomic;
+mod bookmark;
 /// Platform-specific implementations.
-#[cfg(any(target_os = "ios", target_os = "android"))]
+#[cfg(any(target_os = "ios", target_os = "macos", target_os = "android"))]
 mod sys;
+mod watch;
 
-use std::path::PathBuf;
+pub use atomic::AtomicFile;
+pub use bookmark::SecurityScopeGuard;
+pub use watch::{DEFAULT_DEBOUNCE, FsEvent};
+
+use futures::Stream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Cross-platform File System Utilities
 ///
@@ -61,4 +70,216 @@ impl WaterFs {
             None
         }
     }
+
+    /// Gets the directory for the application's own data files.
+    ///
+    /// This is `Application Support/<bundle id>` on macOS, `Library/Application
+    /// Support` on iOS, `getFilesDir()` on Android and `<data dir>/<app>` on
+    /// Windows and Linux, where `<app>` is the executable name. The directory
+    /// is created if it doesn't exist yet.
+    #[must_use]
+    pub fn app_support_dir() -> Option<PathBuf> {
+        #[cfg(any(target_os = "windows", target_os = "linux"))]
+        {
+            let exe = std::env::current_exe().ok()?;
+            let dir = dirs::data_dir()?.join(exe.file_stem()?);
+            std::fs::create_dir_all(&dir).ok()?;
+            Some(dir)
+        }
+        #[cfg(any(target_os = "ios", target_os = "macos", target_os = "android"))]
+        {
+            sys::app_support_dir()
+        }
+        #[cfg(not(any(
+            target_os = "macos",
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "ios",
+            target_os = "android"
+        )))]
+        {
+            None
+        }
+    }
+
+    /// Gets the same directory as [`WaterFs::app_support_dir`].
+    ///
+    /// Use it for persistent app state that shouldn't sit next to the
+    /// user's documents.
+    #[must_use]
+    pub fn data_dir() -> Option<PathBuf> {
+        Self::app_support_dir()
+    }
+
+    /// Gets a directory for scratch files.
+    ///
+    /// This is `NSTemporaryDirectory()` on iOS and macOS, a `tmp` directory
+    /// inside the cache directory on Android and [`std::env::temp_dir`] on
+    /// Windows and Linux. The system may delete its contents whenever the
+    /// app isn't running.
+    #[must_use]
+    pub fn temp_dir() -> Option<PathBuf> {
+        #[cfg(any(target_os = "windows", target_os = "linux"))]
+        {
+            Some(std::env::temp_dir())
+        }
+        #[cfg(any(target_os = "ios", target_os = "macos", target_os = "android"))]
+        {
+            sys::temp_dir()
+        }
+        #[cfg(not(any(
+            target_os = "macos",
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "ios",
+            target_os = "android"
+        )))]
+        {
+            None
+        }
+    }
+
+    /// Gets the container shared with extensions and widgets in the App Group
+    /// `group_id`.
+    ///
+    /// Returns `None` if the app isn't entitled to the group, and always on
+    /// platforms other than iOS and macOS.
+    #[must_use]
+    pub fn shared_container_dir(group_id: &str) -> Option<PathBuf> {
+        #[cfg(any(target_os = "ios", target_os = "macos"))]
+        {
+            sys::shared_container_dir(group_id)
+        }
+        #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+        {
+            let _ = group_id;
+            None
+        }
+    }
+
+    /// Gets the number of bytes free for new files on the volume containing
+    /// `path`.
+    ///
+    /// On iOS and macOS this is the capacity available for "important"
+    /// resources (`volumeAvailableCapacityForImportantUsageKey`): it includes
+    /// purgeable space the system would free on demand, so it is the value
+    /// to check before a user-requested download. Raw free space is smaller
+    /// and would reject downloads that fit. Elsewhere it is the space free
+    /// to the current user (`statvfs`, `GetDiskFreeSpaceExW`).
+    ///
+    /// Returns `None` if `path` doesn't exist or its volume can't be queried.
+    #[must_use]
+    pub fn available_space(path: &Path) -> Option<u64> {
+        #[cfg(any(target_os = "ios", target_os = "macos"))]
+        {
+            sys::available_space(path)
+        }
+        #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+        {
+            waterkit_system::disk_info(path)
+                .ok()
+                .map(|info| info.available_bytes)
+        }
+    }
+
+    /// Replaces the contents of `path` with `data` atomically.
+    ///
+    /// The data is written to a temporary file in the same directory, synced
+    /// to disk and renamed over `path`, so a crash leaves either the old or
+    /// the new contents in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if any step fails; `path` is left unchanged.
+    pub fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+        atomic::write(path, data)
+    }
+
+    /// Starts an atomic replacement of `path` that can be written in pieces.
+    ///
+    /// Nothing is visible at `path` until [`AtomicFile::commit`] is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the temporary file can't be created.
+    pub fn create_atomic(path: &Path) -> std::io::Result<AtomicFile> {
+        AtomicFile::create(path)
+    }
+
+    /// Serializes a bookmark that gives access to `path` after a restart.
+    ///
+    /// On iOS and macOS this is a security-scoped URL bookmark, which keeps
+    /// files picked from the Files app or an open panel reachable, including
+    /// in sandboxed Mac apps. Elsewhere it is just the encoded path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`std::io::ErrorKind::InvalidInput`] if `path` is not valid
+    /// UTF-8, or the platform error if the bookmark can't be created.
+    pub fn create_bookmark(path: &Path) -> std::io::Result<Vec<u8>> {
+        bookmark::create(path)
+    }
+
+    /// Resolves data from [`WaterFs::create_bookmark`] back to a path.
+    ///
+    /// The flag is `true` if the bookmark is stale, in which case a new one
+    /// should be created and stored in its place.
+    ///
+    /// # Errors
+    ///
+    /// Returns the platform error if the bookmark can't be resolved, or
+    /// [`std::io::ErrorKind::InvalidData`] if the data is not a bookmark.
+    pub fn resolve_bookmark(data: &[u8]) -> std::io::Result<(PathBuf, bool)> {
+        bookmark::resolve(data)
+    }
+
+    /// Starts accessing a security-scoped `path` until the guard is dropped.
+    ///
+    /// Required on iOS and in sandboxed Mac apps before reading a path from
+    /// [`WaterFs::resolve_bookmark`]; access is released when the guard is
+    /// dropped. Elsewhere the guard does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`std::io::ErrorKind::PermissionDenied`] on iOS and sandboxed
+    /// macOS if the system refuses access.
+    pub fn start_accessing(path: &Path) -> std::io::Result<SecurityScopeGuard> {
+        bookmark::start_accessing(path)
+    }
+
+    /// Watches `path` and everything beneath it for changes.
+    ///
+    /// Duplicate events within [`DEFAULT_DEBOUNCE`] are merged. Watching stops
+    /// when the stream is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`std::io::ErrorKind::NotFound`] if `path` does not exist, or the
+    /// platform error if the watch cannot be started.
+    pub fn watch(path: &Path) -> std::io::Result<impl Stream<Item = FsEvent> + use<>> {
+        watch::watch(path, DEFAULT_DEBOUNCE)
+    }
+
+    /// Like [`WaterFs::watch`], with a custom debounce window.
+    ///
+    /// A zero window delivers events as they arrive, so a rename may show up
+    /// as a deletion followed by a creation.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`WaterFs::watch`].
+    pub fn watch_with_debounce(
+        path: &Path,
+        debounce: Duration,
+    ) -> std::io::Result<impl Stream<Item = FsEvent> + use<>> {
+        watch::watch(path, debounce)
+    }
+}
+
+/// Gets the number of bytes available in the [`WaterFs::cache_dir`] volume.
+///
+/// Returns `None` if there is no cache directory or its volume cannot be queried.
+#[must_use]
+pub fn cache_dir_available_bytes() -> Option<u64> {
+    WaterFs::available_space(&WaterFs::cache_dir()?)
 }
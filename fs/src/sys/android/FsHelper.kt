@@ -15,5 +15,19 @@ class FsHelper {
         fun getCacheDir(context: Context): String? {
             return context.cacheDir.absolutePath
         }
+
+        @JvmStatic
+        fun getAppSupportDir(context: Context): String? {
+            return context.filesDir.absolutePath
+        }
+
+        @JvmStatic
+        fun getTempDir(context: Context): String? {
+            val dir = File(context.cacheDir, "tmp")
+            if (!dir.isDirectory && !dir.mkdirs()) {
+                return null
+            }
+            return dir.absolutePath
+        }
     }
 }
@@ -109,6 +109,24 @@ pub fn cache_dir_with_context(env: &mut JNIEnv, context: &JObject) -> Option<Pat
         .map(PathBuf::from)
 }
 
+pub fn app_support_dir_with_context(env: &mut JNIEnv, context: &JObject) -> Option<PathBuf> {
+    call_helper_method(env, context, "getAppSupportDir")
+        .unwrap_or_else(|e| {
+            eprintln!("Error getting app support dir: {}", e);
+            None
+        })
+        .map(PathBuf::from)
+}
+
+pub fn temp_dir_with_context(env: &mut JNIEnv, context: &JObject) -> Option<PathBuf> {
+    call_helper_method(env, context, "getTempDir")
+        .unwrap_or_else(|e| {
+            eprintln!("Error getting temp dir: {}", e);
+            None
+        })
+        .map(PathBuf::from)
+}
+
 pub fn documents_dir() -> Option<PathBuf> {
     eprintln!("Android: documents_dir requires Context.");
     None
@@ -118,3 +136,13 @@ pub fn cache_dir() -> Option<PathBuf> {
     eprintln!("Android: cache_dir requires Context.");
     None
 }
+
+pub fn app_support_dir() -> Option<PathBuf> {
+    eprintln!("Android: app_support_dir requires Context.");
+    None
+}
+
+pub fn temp_dir() -> Option<PathBuf> {
+    eprintln!("Android: temp_dir requires Context.");
+    None
+}
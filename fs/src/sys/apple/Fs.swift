@@ -11,3 +11,95 @@ public func documents_dir() -> String? {
 public func cache_dir() -> String? {
     return FileManager.default.urls(for: .cachesDirectory, in: .userDomainMask).first?.path
 }
+
+public func app_support_dir() -> String? {
+    guard let url = try? FileManager.default.url(for: .applicationSupportDirectory, in: .userDomainMask, appropriateFor: nil, create: true) else {
+        return nil
+    }
+    #if os(macOS)
+    // Unlike the iOS sandbox, this directory is shared by every app
+    let appURL = url.appendingPathComponent(Bundle.main.bundleIdentifier ?? ProcessInfo.processInfo.processName, isDirectory: true)
+    try? FileManager.default.createDirectory(at: appURL, withIntermediateDirectories: true)
+    return appURL.path
+    #else
+    return url.path
+    #endif
+}
+
+public func temp_dir() -> String? {
+    return NSTemporaryDirectory()
+}
+
+public func shared_container_dir(group_id: RustStr) -> String? {
+    return FileManager.default.containerURL(forSecurityApplicationGroupIdentifier: group_id.toString())?.path
+}
+
+public func fs_available_space(path: RustStr) -> UInt64? {
+    let url = URL(fileURLWithPath: path.toString())
+    guard let values = try? url.resourceValues(forKeys: [.volumeAvailableCapacityForImportantUsageKey]),
+          let capacity = values.volumeAvailableCapacityForImportantUsage else {
+        return nil
+    }
+    return UInt64(max(capacity, 0))
+}
+
+// MARK: - Security-scoped bookmarks
+
+// The access rights travel with the resolved URL rather than its path, so
+// resolved URLs are kept to start and stop access by path later.
+private var resolvedURLs: [String: URL] = [:]
+private let resolvedURLsLock = NSLock()
+
+#if os(macOS)
+// Sandboxed Mac apps only regain access through security-scoped bookmarks
+private let createOptions: URL.BookmarkCreationOptions = .withSecurityScope
+private let resolveOptions: URL.BookmarkResolutionOptions = .withSecurityScope
+private let isSandboxed = ProcessInfo.processInfo.environment["APP_SANDBOX_CONTAINER_ID"] != nil
+#else
+private let createOptions: URL.BookmarkCreationOptions = .minimalBookmark
+private let resolveOptions: URL.BookmarkResolutionOptions = []
+private let isSandboxed = true
+#endif
+
+private func scopedURL(_ path: String) -> URL {
+    resolvedURLsLock.lock()
+    defer { resolvedURLsLock.unlock() }
+    return resolvedURLs[path] ?? URL(fileURLWithPath: path)
+}
+
+public func fs_create_bookmark(path: RustStr) -> BookmarkData {
+    let url = scopedURL(path.toString())
+    do {
+        let bookmark = try url.bookmarkData(options: createOptions, includingResourceValuesForKeys: nil, relativeTo: nil)
+        let data = RustVec<UInt8>()
+        for byte in bookmark {
+            data.push(value: byte)
+        }
+        return BookmarkData(data: data, error: "".intoRustString())
+    } catch {
+        return BookmarkData(data: RustVec(), error: error.localizedDescription.intoRustString())
+    }
+}
+
+public func fs_resolve_bookmark(data: RustVec<UInt8>) -> ResolvedBookmark {
+    let bookmark = Data(bytes: data.as_ptr(), count: Int(data.len()))
+    var isStale = false
+    do {
+        let url = try URL(resolvingBookmarkData: bookmark, options: resolveOptions, relativeTo: nil, bookmarkDataIsStale: &isStale)
+        resolvedURLsLock.lock()
+        resolvedURLs[url.path] = url
+        resolvedURLsLock.unlock()
+        return ResolvedBookmark(path: url.path.intoRustString(), is_stale: isStale, error: "".intoRustString())
+    } catch {
+        return ResolvedBookmark(path: "".intoRustString(), is_stale: false, error: error.localizedDescription.intoRustString())
+    }
+}
+
+public func fs_start_accessing(path: RustStr) -> Bool {
+    // Unsandboxed apps can reach every path already
+    return scopedURL(path.toString()).startAccessingSecurityScopedResource() || !isSandboxed
+}
+
+public func fs_stop_accessing(path: RustStr) {
+    scopedURL(path.toString()).stopAccessingSecurityScopedResource()
+}
@@ -1,23 +1,104 @@
 //! Apple platform (iOS/macOS) file system implementation using swift-bridge.
 
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
 
 #[swift_bridge::bridge]
 mod ffi {
+    #[swift_bridge(swift_repr = "struct")]
+    struct BookmarkData {
+        data: Vec<u8>,
+        // Empty on success, otherwise the `URL` error description.
+        error: String,
+    }
+
+    #[swift_bridge(swift_repr = "struct")]
+    struct ResolvedBookmark {
+        path: String,
+        is_stale: bool,
+        // Empty on success, otherwise the `URL` error description.
+        error: String,
+    }
+
     extern "Swift" {
         fn documents_dir() -> Option<String>;
         fn cache_dir() -> Option<String>;
+        fn app_support_dir() -> Option<String>;
+        fn temp_dir() -> Option<String>;
+        fn shared_container_dir(group_id: &str) -> Option<String>;
+        fn fs_available_space(path: &str) -> Option<u64>;
+
+        // Security-scoped bookmarks
+        fn fs_create_bookmark(path: &str) -> BookmarkData;
+        fn fs_resolve_bookmark(data: Vec<u8>) -> ResolvedBookmark;
+        fn fs_start_accessing(path: &str) -> bool;
+        fn fs_stop_accessing(path: &str);
     }
 }
 
 /// Gets the application's documents directory on Apple platforms.
+#[cfg(target_os = "ios")]
 #[must_use]
 pub fn documents_dir() -> Option<PathBuf> {
     ffi::documents_dir().map(PathBuf::from)
 }
 
 /// Gets the application's cache directory on Apple platforms.
+#[cfg(target_os = "ios")]
 #[must_use]
 pub fn cache_dir() -> Option<PathBuf> {
     ffi::cache_dir().map(PathBuf::from)
 }
+
+/// Gets the application support directory, creating it if needed.
+#[must_use]
+pub fn app_support_dir() -> Option<PathBuf> {
+    ffi::app_support_dir().map(PathBuf::from)
+}
+
+/// Gets the temporary directory.
+#[must_use]
+pub fn temp_dir() -> Option<PathBuf> {
+    ffi::temp_dir().map(PathBuf::from)
+}
+
+/// Gets the container shared by apps and extensions in an App Group.
+#[must_use]
+pub fn shared_container_dir(group_id: &str) -> Option<PathBuf> {
+    ffi::shared_container_dir(group_id).map(PathBuf::from)
+}
+
+/// Gets the capacity available for important resources on the volume
+/// containing `path`.
+#[must_use]
+pub fn available_space(path: &Path) -> Option<u64> {
+    ffi::fs_available_space(path.to_str()?)
+}
+
+/// Serializes a bookmark for `path` that survives app restarts.
+pub fn create_bookmark(path: &str) -> io::Result<Vec<u8>> {
+    let bookmark = ffi::fs_create_bookmark(path);
+    if !bookmark.error.is_empty() {
+        return Err(io::Error::other(bookmark.error));
+    }
+    Ok(bookmark.data)
+}
+
+/// Resolves bookmark data to a path and whether the bookmark is stale.
+pub fn resolve_bookmark(data: &[u8]) -> io::Result<(PathBuf, bool)> {
+    let resolved = ffi::fs_resolve_bookmark(data.to_vec());
+    if !resolved.error.is_empty() {
+        return Err(io::Error::other(resolved.error));
+    }
+    Ok((PathBuf::from(resolved.path), resolved.is_stale))
+}
+
+/// Claims access to a security-scoped resource.
+pub fn start_accessing(path: &str) -> bool {
+    ffi::fs_start_accessing(path)
+}
+
+/// Releases access claimed by [`start_accessing`].
+pub fn stop_accessing(path: &str) {
+    ffi::fs_stop_accessing(path);
+}
@@ -0,0 +1,240 @@
+//! File system change notifications.
+//!
+//! Backed by the `notify` crate, which uses FSEvents on macOS, kqueue on iOS,
+//! inotify on Linux and Android (the mechanism behind `FileObserver`), and
+//! `ReadDirectoryChangesW` on Windows.
+
+use futures::Stream;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Debounce window used by [`crate::WaterFs::watch`].
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// A change to a watched file or directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsEvent {
+    /// A file or directory was created.
+    Created {
+        /// Path of the new entry.
+        path: PathBuf,
+    },
+    /// The contents or metadata of an entry changed.
+    Modified {
+        /// Path of the changed entry.
+        path: PathBuf,
+    },
+    /// A file or directory was removed, or moved out of the watched tree.
+    Deleted {
+        /// Path of the removed entry.
+        path: PathBuf,
+    },
+    /// An entry was renamed within the watched tree.
+    Renamed {
+        /// Previous path of the entry.
+        from: PathBuf,
+        /// New path of the entry.
+        to: PathBuf,
+    },
+}
+
+/// Start watching `path` recursively, coalescing duplicates within `debounce`.
+pub(crate) fn watch(
+    path: &Path,
+    debounce: Duration,
+) -> io::Result<impl Stream<Item = FsEvent> + use<>> {
+    // Fail fast with NotFound rather than a backend-specific error
+    std::fs::metadata(path)?;
+
+    let (raw_sender, raw_receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+        if let Ok(event) = result {
+            let _ = raw_sender.send(event);
+        }
+    })
+    .map_err(io_error)?;
+    watcher
+        .watch(path, RecursiveMode::Recursive)
+        .map_err(io_error)?;
+
+    let (sender, receiver) = async_channel::unbounded();
+    thread::Builder::new()
+        .name("waterkit-fs-watch".into())
+        .spawn(move || Debouncer::new(debounce).run(&raw_receiver, &sender))?;
+
+    // The watcher lives as long as the stream; dropping it closes the raw
+    // channel, which ends the debounce thread.
+    Ok(futures::stream::unfold(
+        (receiver, watcher),
+        |(receiver, watcher)| async move {
+            let event = receiver.recv().await.ok()?;
+            Some((event, (receiver, watcher)))
+        },
+    ))
+}
+
+fn io_error(error: notify::Error) -> io::Error {
+    match error.kind {
+        notify::ErrorKind::Io(error) => error,
+        notify::ErrorKind::PathNotFound => io::ErrorKind::NotFound.into(),
+        kind => io::Error::other(notify::Error::new(kind)),
+    }
+}
+
+/// Turns raw backend events into [`FsEvent`]s.
+///
+/// Events are held for the debounce window so duplicates can be merged and
+/// the two halves of a rename can be paired up.
+struct Debouncer {
+    window: Duration,
+    pending: Vec<(Instant, FsEvent)>,
+    /// Source half of a rename waiting for its destination.
+    moved_from: Option<(Instant, PathBuf)>,
+    /// Last rename paired from separate halves, to skip a combined duplicate.
+    last_rename: Option<(PathBuf, PathBuf)>,
+}
+
+impl Debouncer {
+    const fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Vec::new(),
+            moved_from: None,
+            last_rename: None,
+        }
+    }
+
+    fn run(mut self, raw: &mpsc::Receiver<Event>, sender: &async_channel::Sender<FsEvent>) {
+        loop {
+            let received = match self.deadline() {
+                Some(deadline) => {
+                    raw.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+                None => raw.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok(event) => self.handle(event),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+            if !self.flush(sender) {
+                return;
+            }
+        }
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        let oldest = self.pending.first().map(|(at, _)| *at);
+        let moved = self.moved_from.as_ref().map(|(at, _)| *at);
+        [oldest, moved]
+            .into_iter()
+            .flatten()
+            .min()
+            .map(|at| at + self.window)
+    }
+
+    fn handle(&mut self, event: Event) {
+        match event.kind {
+            EventKind::Create(_) => {
+                for path in event.paths {
+                    self.push(FsEvent::Created { path });
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(mode)) => self.handle_rename(mode, event.paths),
+            EventKind::Modify(_) => {
+                for path in event.paths {
+                    self.push(FsEvent::Modified { path });
+                }
+            }
+            EventKind::Remove(_) => {
+                for path in event.paths {
+                    self.push(FsEvent::Deleted { path });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_rename(&mut self, mode: RenameMode, paths: Vec<PathBuf>) {
+        match mode {
+            RenameMode::Both => {
+                let Ok([from, to]) = <[PathBuf; 2]>::try_from(paths) else {
+                    return;
+                };
+                // inotify reports both halves and then the combined event
+                if self.last_rename.take() != Some((from.clone(), to.clone())) {
+                    self.push(FsEvent::Renamed { from, to });
+                }
+            }
+            RenameMode::From => paths.into_iter().for_each(|path| self.moved_out(path)),
+            RenameMode::To => paths.into_iter().for_each(|path| self.moved_in(path)),
+            // FSEvents doesn't say which half an event is
+            _ => {
+                for path in paths {
+                    if path.exists() {
+                        self.moved_in(path);
+                    } else {
+                        self.moved_out(path);
+                    }
+                }
+            }
+        }
+    }
+
+    fn moved_out(&mut self, path: PathBuf) {
+        if let Some((_, previous)) = self.moved_from.replace((Instant::now(), path)) {
+            self.push(FsEvent::Deleted { path: previous });
+        }
+    }
+
+    fn moved_in(&mut self, path: PathBuf) {
+        if let Some((_, from)) = self.moved_from.take() {
+            self.last_rename = Some((from.clone(), path.clone()));
+            self.push(FsEvent::Renamed { from, to: path });
+        } else {
+            self.push(FsEvent::Created { path });
+        }
+    }
+
+    fn push(&mut self, event: FsEvent) {
+        let redundant = self.pending.iter().any(|(_, pending)| {
+            *pending == event
+                // Writing a new file is reported as part of its creation
+                || matches!(
+                    (pending, &event),
+                    (FsEvent::Created { path }, FsEvent::Modified { path: modified })
+                        if path == modified
+                )
+        });
+        if !redundant {
+            self.pending.push((Instant::now(), event));
+        }
+    }
+
+    /// Send events older than the window. Returns `false` once the stream is gone.
+    fn flush(&mut self, sender: &async_channel::Sender<FsEvent>) -> bool {
+        let now = Instant::now();
+        if let Some((at, _)) = &self.moved_from
+            && now.duration_since(*at) >= self.window
+            && let Some((at, path)) = self.moved_from.take()
+        {
+            // The destination is outside the watched tree
+            self.pending.push((at, FsEvent::Deleted { path }));
+            self.pending.sort_by_key(|(at, _)| *at);
+        }
+
+        let ready = self
+            .pending
+            .iter()
+            .take_while(|(at, _)| now.duration_since(*at) >= self.window)
+            .count();
+        self.pending
+            .drain(..ready)
+            .all(|(_, event)| sender.try_send(event).is_ok())
+    }
+}
@@ -5,9 +5,17 @@
 
 #![warn(missing_docs)]
 
+mod pattern;
 // Internal platform-specific implementations.
 mod sys;
 
+pub use pattern::{HapticEvent, HapticPattern};
+
+use std::time::Duration;
+
+#[cfg(target_os = "android")]
+pub use sys::android::{feedback_with_context, play_pattern_with_context, vibrate_with_context};
+
 /// Types of haptic feedback.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HapticFeedback {
@@ -51,3 +59,51 @@ pub enum HapticError {
 pub async fn feedback(style: HapticFeedback) -> Result<(), HapticError> {
     sys::feedback(style).await
 }
+
+/// Play a custom haptic pattern.
+///
+/// Returns once the pattern has started. iOS plays it with Core Haptics.
+/// Other platforms approximate it: Android as a vibration waveform, macOS as
+/// trackpad taps (continuous events become a series of taps), and Windows
+/// with the vibration device's click and buzz waveforms. Vibrators that can
+/// only switch on and off play just the strongest events. An empty pattern
+/// does nothing.
+///
+/// # Errors
+/// Returns [`HapticError::NotSupported`] if the device cannot play haptics,
+/// or another error if the pattern fails to start.
+pub async fn play_pattern(pattern: &HapticPattern) -> Result<(), HapticError> {
+    if pattern.is_empty() {
+        return Ok(());
+    }
+    sys::play_pattern(pattern).await
+}
+
+/// Play an Apple Haptic and Audio Pattern (AHAP), given either as JSON or
+/// as the path of an `.ahap` file.
+///
+/// Returns once the pattern has started. AHAP plays through Core Haptics,
+/// so only iOS supports it; use [`play_pattern`] elsewhere. The engine plays
+/// haptics only, so the pattern's audio events aren't heard.
+///
+/// # Errors
+/// Returns [`HapticError::NotSupported`] on platforms other than iOS and on
+/// devices without Core Haptics, or another error if the pattern can't be
+/// read, parsed or started.
+pub async fn play_ahap(path_or_json: &str) -> Result<(), HapticError> {
+    sys::play_ahap(path_or_json).await
+}
+
+/// Vibrate continuously for `duration`, e.g. for an alarm or timer.
+///
+/// Returns once the vibration has started. `intensity` is clamped to
+/// 0.0..=1.0. iOS holds a Core Haptics continuous event and Android plays a
+/// one-shot vibration. Trackpads can't hold a vibration, so macOS taps
+/// repeatedly instead. A zero `duration` does nothing.
+///
+/// # Errors
+/// Returns [`HapticError::NotSupported`] if the device cannot vibrate, or
+/// another error if the vibration fails to start.
+pub async fn vibrate(duration: Duration, intensity: f32) -> Result<(), HapticError> {
+    play_pattern(&HapticPattern::vibration(duration, intensity)).await
+}
@@ -0,0 +1,157 @@
+//! Custom haptic patterns, modelled on Core Haptics' AHAP events.
+
+use std::time::Duration;
+
+/// One event of a [`HapticPattern`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HapticEvent {
+    /// A brief tap, like a click.
+    Impulse {
+        /// Offset from the start of the pattern.
+        time: Duration,
+        /// Strength, from 0.0 to 1.0.
+        intensity: f32,
+        /// How crisp the tap feels, from 0.0 (dull) to 1.0 (sharp).
+        sharpness: f32,
+    },
+    /// A vibration held for a while.
+    Continuous {
+        /// Offset from the start of the pattern.
+        start: Duration,
+        /// How long the vibration lasts.
+        duration: Duration,
+        /// Strength, from 0.0 to 1.0.
+        intensity: f32,
+        /// How crisp the vibration feels, from 0.0 (dull) to 1.0 (sharp).
+        sharpness: f32,
+    },
+}
+
+impl HapticEvent {
+    /// Offset of the event from the start of the pattern.
+    #[must_use]
+    pub const fn start(&self) -> Duration {
+        match *self {
+            Self::Impulse { time, .. } => time,
+            Self::Continuous { start, .. } => start,
+        }
+    }
+
+    /// Strength of the event, from 0.0 to 1.0.
+    #[must_use]
+    pub const fn intensity(&self) -> f32 {
+        match *self {
+            Self::Impulse { intensity, .. } | Self::Continuous { intensity, .. } => intensity,
+        }
+    }
+
+    /// Sharpness of the event, from 0.0 to 1.0.
+    #[must_use]
+    pub const fn sharpness(&self) -> f32 {
+        match *self {
+            Self::Impulse { sharpness, .. } | Self::Continuous { sharpness, .. } => sharpness,
+        }
+    }
+}
+
+/// A timeline of haptic events, played with [`play_pattern`](crate::play_pattern).
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use waterkit_haptic::HapticPattern;
+///
+/// // A heartbeat: two taps, then a short rumble
+/// let pattern = HapticPattern::new()
+///     .add_impulse(Duration::ZERO, 1.0, 0.6)
+///     .add_impulse(Duration::from_millis(150), 0.7, 0.6)
+///     .add_continuous(Duration::from_millis(400), Duration::from_millis(300), 0.4, 0.2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HapticPattern {
+    events: Vec<HapticEvent>,
+}
+
+impl HapticPattern {
+    /// Create an empty pattern.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    /// Add a tap at `time_offset` from the start of the pattern.
+    ///
+    /// `intensity` and `sharpness` are clamped to 0.0..=1.0.
+    #[must_use]
+    pub fn add_impulse(mut self, time_offset: Duration, intensity: f32, sharpness: f32) -> Self {
+        self.events.push(HapticEvent::Impulse {
+            time: time_offset,
+            intensity: unit(intensity),
+            sharpness: unit(sharpness),
+        });
+        self
+    }
+
+    /// Add a vibration lasting `duration`, starting at `start` from the start
+    /// of the pattern.
+    ///
+    /// `intensity` and `sharpness` are clamped to 0.0..=1.0.
+    #[must_use]
+    pub fn add_continuous(
+        mut self,
+        start: Duration,
+        duration: Duration,
+        intensity: f32,
+        sharpness: f32,
+    ) -> Self {
+        self.events.push(HapticEvent::Continuous {
+            start,
+            duration,
+            intensity: unit(intensity),
+            sharpness: unit(sharpness),
+        });
+        self
+    }
+
+    /// A single vibration lasting `duration`, as played by
+    /// [`vibrate`](crate::vibrate).
+    ///
+    /// Split into events of at most 30 seconds, the longest continuous event
+    /// Core Haptics plays.
+    pub(crate) fn vibration(duration: Duration, intensity: f32) -> Self {
+        const MAX_EVENT: Duration = Duration::from_secs(30);
+        // Between a dull rumble and a crisp buzz
+        const SHARPNESS: f32 = 0.5;
+
+        let mut pattern = Self::new();
+        let mut start = Duration::ZERO;
+        while start < duration {
+            let length = duration.saturating_sub(start).min(MAX_EVENT);
+            pattern = pattern.add_continuous(start, length, intensity, SHARPNESS);
+            start += length;
+        }
+        pattern
+    }
+
+    /// The events of the pattern, in the order they were added.
+    #[must_use]
+    pub fn events(&self) -> &[HapticEvent] {
+        &self.events
+    }
+
+    /// Whether the pattern has no events.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Clamp to 0.0..=1.0, mapping NaN to 0.0.
+const fn unit(value: f32) -> f32 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
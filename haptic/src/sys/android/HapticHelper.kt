@@ -56,5 +56,57 @@ class HapticHelper {
                 vibrator.vibrate(20)
             }
         }
+
+        /**
+         * Play segments of [timings] milliseconds at [amplitudes] (0-255).
+         * Returns false if the device can't vibrate.
+         */
+        @JvmStatic
+        fun playWaveform(context: Context, timings: LongArray, amplitudes: IntArray): Boolean {
+            val vibrator = context.getSystemService(Context.VIBRATOR_SERVICE) as? Vibrator
+            if (vibrator == null || !vibrator.hasVibrator()) {
+                return false
+            }
+            if (timings.isEmpty()) {
+                return true
+            }
+
+            // Without amplitude control every segment would play at full
+            // strength, so keep only the strongest to preserve the rhythm
+            val amplitudeControl = Build.VERSION.SDK_INT >= Build.VERSION_CODES.O &&
+                vibrator.hasAmplitudeControl()
+            val levels = if (amplitudeControl) {
+                amplitudes
+            } else {
+                val threshold = (amplitudes.maxOrNull() ?: 0) / 2
+                IntArray(amplitudes.size) { if (amplitudes[it] > 0 && amplitudes[it] >= threshold) 255 else 0 }
+            }
+
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+                val effect = if (timings.size == 1 && levels[0] > 0) {
+                    VibrationEffect.createOneShot(timings[0], levels[0])
+                } else {
+                    VibrationEffect.createWaveform(timings, levels, -1)
+                }
+                vibrator.vibrate(effect)
+            } else {
+                // Legacy patterns alternate off and on, starting with off
+                val pattern = ArrayList<Long>()
+                var on = false
+                for (i in timings.indices) {
+                    val segmentOn = levels[i] > 0
+                    if (segmentOn == on && pattern.isNotEmpty()) {
+                        pattern[pattern.size - 1] = pattern.last() + timings[i]
+                    } else {
+                        if (segmentOn && pattern.isEmpty()) pattern.add(0L)
+                        pattern.add(timings[i])
+                        on = segmentOn
+                    }
+                }
+                @Suppress("DEPRECATION")
+                vibrator.vibrate(pattern.toLongArray(), -1)
+            }
+            return true
+        }
     }
 }
@@ -1,9 +1,10 @@
 //! Android haptic implementation using JNI.
 
-use crate::{HapticError, HapticFeedback};
+use crate::{HapticError, HapticEvent, HapticFeedback, HapticPattern};
 use jni::JNIEnv;
-use jni::objects::{GlobalRef, JObject, JValue};
+use jni::objects::{GlobalRef, JClass, JObject, JValue};
 use std::sync::OnceLock;
+use std::time::Duration;
 
 /// Embedded DEX bytecode containing HapticHelper class.
 static DEX_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/classes.dex"));
@@ -89,12 +90,11 @@ pub fn init_with_context(env: &mut JNIEnv, context: &JObject) -> Result<(), Hapt
     Ok(())
 }
 
-/// Trigger haptic feedback using the Context.
-pub fn feedback_with_context(
-    env: &mut JNIEnv,
+/// Load `HapticHelper` from the embedded DEX.
+fn helper_class<'local>(
+    env: &mut JNIEnv<'local>,
     context: &JObject,
-    style: HapticFeedback,
-) -> Result<(), HapticError> {
+) -> Result<JClass<'local>, HapticError> {
     init_with_context(env, context)?;
 
     let class_loader = CLASS_LOADER
@@ -116,6 +116,17 @@ pub fn feedback_with_context(
         .l()
         .map_err(|e| HapticError::Unknown(format!("loadClass result: {e}")))?;
 
+    Ok(helper_class.into())
+}
+
+/// Trigger haptic feedback using the Context.
+pub fn feedback_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    style: HapticFeedback,
+) -> Result<(), HapticError> {
+    let helper_jclass = helper_class(env, context)?;
+
     let style_id = match style {
         HapticFeedback::Light => STYLE_LIGHT,
         HapticFeedback::Medium => STYLE_MEDIUM,
@@ -128,7 +139,6 @@ pub fn feedback_with_context(
         HapticFeedback::Error => STYLE_ERROR,
     };
 
-    let helper_jclass: jni::objects::JClass = helper_class.into();
     env.call_static_method(
         helper_jclass,
         "feedback",
@@ -140,9 +150,142 @@ pub fn feedback_with_context(
     Ok(())
 }
 
+/// Play a haptic pattern as a vibration waveform using the Context.
+///
+/// Overlapping events play at the strongest intensity among them. Devices
+/// without amplitude control vibrate at full strength, and only for events
+/// at least half as strong as the strongest one.
+pub fn play_pattern_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    pattern: &HapticPattern,
+) -> Result<(), HapticError> {
+    let helper_jclass = helper_class(env, context)?;
+    let (timings, amplitudes) = waveform(pattern.events());
+
+    let jtimings = env
+        .new_long_array(len_i32(timings.len()))
+        .map_err(|e| HapticError::Unknown(format!("new_long_array: {e}")))?;
+    env.set_long_array_region(&jtimings, 0, &timings)
+        .map_err(|e| HapticError::Unknown(format!("set_long_array_region: {e}")))?;
+    let jamplitudes = env
+        .new_int_array(len_i32(amplitudes.len()))
+        .map_err(|e| HapticError::Unknown(format!("new_int_array: {e}")))?;
+    env.set_int_array_region(&jamplitudes, 0, &amplitudes)
+        .map_err(|e| HapticError::Unknown(format!("set_int_array_region: {e}")))?;
+
+    let played = env
+        .call_static_method(
+            helper_jclass,
+            "playWaveform",
+            "(Landroid/content/Context;[J[I)Z",
+            &[
+                JValue::Object(context),
+                JValue::Object(&jtimings),
+                JValue::Object(&jamplitudes),
+            ],
+        )
+        .map_err(|e| HapticError::Unknown(format!("playWaveform call failed: {e}")))?
+        .z()
+        .map_err(|e| HapticError::Unknown(format!("playWaveform result: {e}")))?;
+
+    if played {
+        Ok(())
+    } else {
+        Err(HapticError::NotSupported)
+    }
+}
+
+/// Vibrate continuously for `duration` using the Context.
+///
+/// `intensity` is clamped to 0.0..=1.0. Devices without amplitude control
+/// vibrate at full strength.
+pub fn vibrate_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    duration: Duration,
+    intensity: f32,
+) -> Result<(), HapticError> {
+    play_pattern_with_context(env, context, &HapticPattern::vibration(duration, intensity))
+}
+
+fn len_i32(len: usize) -> i32 {
+    i32::try_from(len).unwrap_or(i32::MAX)
+}
+
+/// Length of an impulse, from 30 ms when dull down to 10 ms when sharp.
+fn impulse_length(sharpness: f32) -> Duration {
+    Duration::from_secs_f32(0.02f32.mul_add(-sharpness, 0.03))
+}
+
+/// Flatten events into the segment lengths (ms) and amplitudes (0-255)
+/// `VibrationEffect.createWaveform` takes.
+fn waveform(events: &[HapticEvent]) -> (Vec<i64>, Vec<i32>) {
+    let span = |event: &HapticEvent| match *event {
+        HapticEvent::Impulse {
+            time, sharpness, ..
+        } => (time, time + impulse_length(sharpness)),
+        HapticEvent::Continuous {
+            start, duration, ..
+        } => (start, start + duration),
+    };
+
+    let mut edges: Vec<Duration> = events
+        .iter()
+        .flat_map(|event| <[Duration; 2]>::from(span(event)))
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+
+    let mut timings: Vec<i64> = Vec::new();
+    let mut amplitudes: Vec<i32> = Vec::new();
+    let mut previous = Duration::ZERO;
+    for &edge in &edges {
+        let length = edge.saturating_sub(previous);
+        if length.is_zero() {
+            continue;
+        }
+        let intensity = events
+            .iter()
+            .filter(|event| {
+                let (start, end) = span(event);
+                start <= previous && previous < end
+            })
+            .map(HapticEvent::intensity)
+            .fold(0.0, f32::max);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // 0.0..=255.0
+        let amplitude = i32::from((intensity * 255.0).round() as u8);
+        let millis = i64::try_from(length.as_millis()).unwrap_or(i64::MAX);
+
+        // Merge with the previous segment when the amplitude doesn't change
+        if amplitudes.last() == Some(&amplitude) {
+            if let Some(last) = timings.last_mut() {
+                *last += millis;
+            }
+        } else {
+            timings.push(millis);
+            amplitudes.push(amplitude);
+        }
+        previous = edge;
+    }
+    (timings, amplitudes)
+}
+
 // Async wrapper for the public API (stub)
-pub(crate) async fn feedback(_style: HapticFeedback) -> Result<(), HapticError> {
+pub async fn feedback(_style: HapticFeedback) -> Result<(), HapticError> {
     Err(HapticError::Unknown(
         "Android: use feedback_with_context() with Context".into(),
     ))
 }
+
+// Async wrapper for the public API (stub)
+pub async fn play_pattern(_pattern: &HapticPattern) -> Result<(), HapticError> {
+    Err(HapticError::Unknown(
+        "Android: use play_pattern_with_context() with Context".into(),
+    ))
+}
+
+// AHAP is Apple-only
+pub async fn play_ahap(_path_or_json: &str) -> Result<(), HapticError> {
+    Err(HapticError::NotSupported)
+}
@@ -2,6 +2,7 @@ import Foundation
 
 #if os(iOS)
 import UIKit
+import CoreHaptics
 #elseif os(macOS)
 import AppKit
 #endif
@@ -72,3 +73,122 @@ func trigger_haptic(style: HapticFeedbackType) {
     manager.perform(pattern, performanceTime: .default)
     #endif
 }
+
+#if os(iOS)
+/// Engine shared by all patterns, created on first use.
+private var engine: AnyObject?
+private let engineLock = NSLock()
+
+@available(iOS 13.0, *)
+private func sharedEngine() throws -> CHHapticEngine {
+    engineLock.lock()
+    defer { engineLock.unlock() }
+    if let engine = engine as? CHHapticEngine {
+        return engine
+    }
+    let created = try CHHapticEngine()
+    created.playsHapticsOnly = true
+    // The system stops the engine when the app goes to the background
+    created.resetHandler = { [weak created] in
+        try? created?.start()
+    }
+    engine = created
+    return created
+}
+#endif
+
+func play_haptic_pattern(events: PatternEventsRef) -> PatternResultFFI {
+    #if os(iOS)
+    guard #available(iOS 13.0, *), CHHapticEngine.capabilitiesForHardware().supportsHaptics else {
+        return PatternResultFFI(supported: false, error: "".intoRustString())
+    }
+    do {
+        var hapticEvents: [CHHapticEvent] = []
+        for index in 0..<events.count() {
+            let parameters = [
+                CHHapticEventParameter(parameterID: .hapticIntensity, value: events.intensity(index)),
+                CHHapticEventParameter(parameterID: .hapticSharpness, value: events.sharpness(index)),
+            ]
+            if events.is_continuous(index) {
+                hapticEvents.append(CHHapticEvent(
+                    eventType: .hapticContinuous,
+                    parameters: parameters,
+                    relativeTime: events.start_secs(index),
+                    duration: events.duration_secs(index)
+                ))
+            } else {
+                hapticEvents.append(CHHapticEvent(
+                    eventType: .hapticTransient,
+                    parameters: parameters,
+                    relativeTime: events.start_secs(index)
+                ))
+            }
+        }
+        let engine = try sharedEngine()
+        try engine.start()
+        let player = try engine.makePlayer(with: CHHapticPattern(events: hapticEvents, parameters: []))
+        try player.start(atTime: CHHapticTimeImmediate)
+        return PatternResultFFI(supported: true, error: "".intoRustString())
+    } catch {
+        return PatternResultFFI(supported: true, error: error.localizedDescription.intoRustString())
+    }
+    #elseif os(macOS)
+    // Trackpads only play fixed taps, so continuous events become a run of them
+    let tapInterval = 0.05
+    var taps: [(time: Double, sharpness: Float)] = []
+    for index in 0..<events.count() {
+        let start = events.start_secs(index)
+        if events.is_continuous(index) {
+            var time = start
+            while time < start + events.duration_secs(index) {
+                taps.append((time, events.sharpness(index)))
+                time += tapInterval
+            }
+        } else {
+            taps.append((start, events.sharpness(index)))
+        }
+    }
+    let manager = NSHapticFeedbackManager.defaultPerformer
+    for tap in taps {
+        DispatchQueue.main.asyncAfter(deadline: .now() + tap.time) {
+            manager.perform(tap.sharpness > 0.5 ? .alignment : .generic, performanceTime: .now)
+        }
+    }
+    return PatternResultFFI(supported: true, error: "".intoRustString())
+    #endif
+}
+
+func play_haptic_ahap(source: RustStr, is_file: Bool) -> PatternResultFFI {
+    #if os(iOS)
+    guard #available(iOS 13.0, *), CHHapticEngine.capabilitiesForHardware().supportsHaptics else {
+        return PatternResultFFI(supported: false, error: "".intoRustString())
+    }
+    do {
+        let engine = try sharedEngine()
+        try engine.start()
+        if is_file {
+            // Resolves custom audio resources relative to the file
+            try engine.playPattern(from: URL(fileURLWithPath: source.toString()))
+        } else {
+            let object = try JSONSerialization.jsonObject(with: Data(source.toString().utf8))
+            guard let json = object as? [String: Any] else {
+                return PatternResultFFI(
+                    supported: true,
+                    error: "AHAP must be a JSON object".intoRustString()
+                )
+            }
+            let dictionary = Dictionary(uniqueKeysWithValues: json.map {
+                (CHHapticPattern.Key(rawValue: $0.key), $0.value)
+            })
+            let player = try engine.makePlayer(with: CHHapticPattern(dictionary: dictionary))
+            try player.start(atTime: CHHapticTimeImmediate)
+        }
+        return PatternResultFFI(supported: true, error: "".intoRustString())
+    } catch {
+        return PatternResultFFI(supported: true, error: error.localizedDescription.intoRustString())
+    }
+    #elseif os(macOS)
+    // Trackpads have no Core Haptics engine
+    return PatternResultFFI(supported: false, error: "".intoRustString())
+    #endif
+}
@@ -1,6 +1,6 @@
 //! Apple platform (iOS/macOS) haptic implementation using swift-bridge.
 
-use crate::{HapticError, HapticFeedback};
+use crate::{HapticError, HapticEvent, HapticFeedback, HapticPattern};
 
 #[swift_bridge::bridge]
 mod ffi {
@@ -17,8 +17,60 @@ mod ffi {
         Error,
     }
 
+    #[swift_bridge(swift_repr = "struct")]
+    struct PatternResultFFI {
+        supported: bool,
+        error: String,
+    }
+
+    extern "Rust" {
+        type PatternEvents;
+        fn count(self: &PatternEvents) -> usize;
+        fn is_continuous(self: &PatternEvents, index: usize) -> bool;
+        fn start_secs(self: &PatternEvents, index: usize) -> f64;
+        fn duration_secs(self: &PatternEvents, index: usize) -> f64;
+        fn intensity(self: &PatternEvents, index: usize) -> f32;
+        fn sharpness(self: &PatternEvents, index: usize) -> f32;
+    }
+
     extern "Swift" {
         fn trigger_haptic(style: SwiftHapticFeedback);
+        fn play_haptic_pattern(events: &PatternEvents) -> PatternResultFFI;
+        fn play_haptic_ahap(source: &str, is_file: bool) -> PatternResultFFI;
+    }
+}
+
+/// Events of a pattern, read by Swift to build its player.
+pub struct PatternEvents(Vec<HapticEvent>);
+
+impl PatternEvents {
+    fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn is_continuous(&self, index: usize) -> bool {
+        matches!(self.0.get(index), Some(HapticEvent::Continuous { .. }))
+    }
+
+    fn start_secs(&self, index: usize) -> f64 {
+        self.0
+            .get(index)
+            .map_or(0.0, |event| event.start().as_secs_f64())
+    }
+
+    fn duration_secs(&self, index: usize) -> f64 {
+        match self.0.get(index) {
+            Some(HapticEvent::Continuous { duration, .. }) => duration.as_secs_f64(),
+            _ => 0.0,
+        }
+    }
+
+    fn intensity(&self, index: usize) -> f32 {
+        self.0.get(index).map_or(0.0, HapticEvent::intensity)
+    }
+
+    fn sharpness(&self, index: usize) -> f32 {
+        self.0.get(index).map_or(0.0, HapticEvent::sharpness)
     }
 }
 
@@ -38,3 +90,24 @@ pub async fn feedback(style: HapticFeedback) -> Result<(), HapticError> {
     ffi::trigger_haptic(swift_style);
     Ok(())
 }
+
+fn pattern_result(result: ffi::PatternResultFFI) -> Result<(), HapticError> {
+    if !result.supported {
+        Err(HapticError::NotSupported)
+    } else if result.error.is_empty() {
+        Ok(())
+    } else {
+        Err(HapticError::Unknown(result.error))
+    }
+}
+
+pub async fn play_pattern(pattern: &HapticPattern) -> Result<(), HapticError> {
+    pattern_result(ffi::play_haptic_pattern(&PatternEvents(
+        pattern.events().to_vec(),
+    )))
+}
+
+pub async fn play_ahap(path_or_json: &str) -> Result<(), HapticError> {
+    let is_file = !path_or_json.trim_start().starts_with('{');
+    pattern_result(ffi::play_haptic_ahap(path_or_json, is_file))
+}
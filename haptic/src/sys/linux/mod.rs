@@ -1,8 +1,16 @@
 //! Linux haptic implementation.
 
-use crate::{HapticError, HapticFeedback};
+use crate::{HapticError, HapticFeedback, HapticPattern};
 
-pub(crate) async fn feedback(_style: HapticFeedback) -> Result<(), HapticError> {
+pub async fn feedback(_style: HapticFeedback) -> Result<(), HapticError> {
     // TODO: Implement via UPower or other mechanism
     Err(HapticError::NotSupported)
 }
+
+pub async fn play_pattern(_pattern: &HapticPattern) -> Result<(), HapticError> {
+    Err(HapticError::NotSupported)
+}
+
+pub async fn play_ahap(_path_or_json: &str) -> Result<(), HapticError> {
+    Err(HapticError::NotSupported)
+}
@@ -13,19 +13,18 @@ mod windows;
 #[cfg(target_os = "linux")]
 mod linux;
 
-// Re-export platform implementations
 // Re-export platform implementations
 #[cfg(any(target_os = "ios", target_os = "macos"))]
-pub use apple::feedback;
+pub use apple::{feedback, play_ahap, play_pattern};
 
 #[cfg(target_os = "android")]
-pub use android::feedback;
+pub use android::{feedback, play_ahap, play_pattern};
 
 #[cfg(target_os = "windows")]
-pub use windows::feedback;
+pub use windows::{feedback, play_ahap, play_pattern};
 
 #[cfg(target_os = "linux")]
-pub use linux::feedback;
+pub use linux::{feedback, play_ahap, play_pattern};
 
 // Fallback for unsupported platforms
 #[cfg(not(any(
@@ -35,6 +34,30 @@ pub use linux::feedback;
     target_os = "windows",
     target_os = "linux"
 )))]
-pub(crate) async fn feedback(_style: crate::HapticFeedback) -> Result<(), crate::HapticError> {
+pub async fn feedback(_style: crate::HapticFeedback) -> Result<(), crate::HapticError> {
+    Err(crate::HapticError::NotSupported)
+}
+
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "linux"
+)))]
+pub async fn play_pattern(
+    _pattern: &crate::HapticPattern,
+) -> Result<(), crate::HapticError> {
+    Err(crate::HapticError::NotSupported)
+}
+
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "linux"
+)))]
+pub async fn play_ahap(_path_or_json: &str) -> Result<(), crate::HapticError> {
     Err(crate::HapticError::NotSupported)
 }
@@ -1,11 +1,15 @@
 //! Windows haptic implementation.
 
-use crate::{HapticError, HapticFeedback};
+use crate::{HapticError, HapticEvent, HapticFeedback, HapticPattern};
+use std::time::Instant;
 use windows::Devices::Haptics::{
-    KnownSimpleHapticsControllerWaveforms, VibrationAccessStatus, VibrationDevice,
+    KnownSimpleHapticsControllerWaveforms, SimpleHapticsController,
+    SimpleHapticsControllerFeedback, VibrationAccessStatus, VibrationDevice,
 };
+use windows::Foundation::TimeSpan;
 
-pub(crate) async fn feedback(style: HapticFeedback) -> Result<(), HapticError> {
+/// Get the controller of the default vibration device, if access is allowed.
+async fn controller() -> Result<SimpleHapticsController, HapticError> {
     // Check access
     let access = VibrationDevice::RequestAccessAsync()
         .map_err(|e| HapticError::Unknown(e.to_string()))?
@@ -27,10 +31,35 @@ pub(crate) async fn feedback(style: HapticFeedback) -> Result<(), HapticError> {
         None => return Err(HapticError::NotSupported),
     };
 
-    let controller = device
+    device
         .SimpleHapticsController()
+        .map_err(|e| HapticError::Unknown(e.to_string()))
+}
+
+/// Find the controller's feedback for a known waveform.
+fn find_feedback(
+    controller: &SimpleHapticsController,
+    waveform_id: u16,
+) -> Result<Option<SimpleHapticsControllerFeedback>, HapticError> {
+    let supported_feedbacks = controller
+        .SupportedFeedback()
         .map_err(|e| HapticError::Unknown(e.to_string()))?;
 
+    for feedback in supported_feedbacks {
+        let waveform = feedback
+            .Waveform()
+            .map_err(|e| HapticError::Unknown(e.to_string()))?;
+
+        if waveform == waveform_id {
+            return Ok(Some(feedback));
+        }
+    }
+    Ok(None)
+}
+
+pub async fn feedback(style: HapticFeedback) -> Result<(), HapticError> {
+    let controller = controller().await?;
+
     // Find supported feedback matching our style
     let waveform_id = match style {
         HapticFeedback::Light => KnownSimpleHapticsControllerWaveforms::Click()?,
@@ -44,23 +73,62 @@ pub(crate) async fn feedback(style: HapticFeedback) -> Result<(), HapticError> {
         HapticFeedback::Error => KnownSimpleHapticsControllerWaveforms::BuzzContinuous()?,
     };
 
-    let supported_feedbacks = controller
-        .SupportedFeedback()
+    if let Some(feedback) = find_feedback(&controller, waveform_id)? {
+        controller
+            .SendHapticFeedback(&feedback)
+            .map_err(|e| HapticError::Unknown(e.to_string()))?;
+    }
+
+    // Fallback or ignore if exact waveform not supported
+    Ok(())
+}
+
+pub async fn play_pattern(pattern: &HapticPattern) -> Result<(), HapticError> {
+    let controller = controller().await?;
+
+    // Impulses play as clicks, continuous events as a buzz held for their duration
+    let click_id = KnownSimpleHapticsControllerWaveforms::Click()
+        .map_err(|e| HapticError::Unknown(e.to_string()))?;
+    let buzz_id = KnownSimpleHapticsControllerWaveforms::BuzzContinuous()
         .map_err(|e| HapticError::Unknown(e.to_string()))?;
+    let click = find_feedback(&controller, click_id)?;
+    let buzz = find_feedback(&controller, buzz_id)?;
+    if click.is_none() && buzz.is_none() {
+        return Err(HapticError::NotSupported);
+    }
 
-    for feedback in supported_feedbacks {
-        let waveform = feedback
-            .Waveform()
-            .map_err(|e| HapticError::Unknown(e.to_string()))?;
+    let mut events = pattern.events().to_vec();
+    events.sort_by_key(HapticEvent::start);
 
-        if waveform == waveform_id {
-            controller
-                .SendHapticFeedback(&feedback)
-                .map_err(|e| HapticError::Unknown(e.to_string()))?;
-            return Ok(());
+    // The controller plays one event at a time, so time them from a thread
+    std::thread::spawn(move || {
+        let began = Instant::now();
+        for event in events {
+            if let Some(wait) = event.start().checked_sub(began.elapsed()) {
+                std::thread::sleep(wait);
+            }
+            let intensity = f64::from(event.intensity());
+            let _ = match (event, &click, &buzz) {
+                (HapticEvent::Impulse { .. }, Some(click), _) => {
+                    controller.SendHapticFeedbackWithIntensity(click, intensity)
+                }
+                (HapticEvent::Continuous { duration, .. }, _, Some(buzz)) => {
+                    // TimeSpan counts 100 ns ticks
+                    let ticks = i64::try_from(duration.as_nanos() / 100).unwrap_or(i64::MAX);
+                    controller.SendHapticFeedbackForDuration(
+                        buzz,
+                        intensity,
+                        TimeSpan { Duration: ticks },
+                    )
+                }
+                _ => Ok(()),
+            };
         }
-    }
+    });
 
-    // Fallback or ignore if exact waveform not supported
     Ok(())
 }
+
+pub async fn play_ahap(_path_or_json: &str) -> Result<(), HapticError> {
+    Err(HapticError::NotSupported)
+}
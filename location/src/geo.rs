@@ -0,0 +1,132 @@
+//! Great-circle distance and bearing between locations.
+//!
+//! The Earth is treated as a sphere with its mean radius, which is accurate
+//! to about 0.5%. Altitude is ignored.
+
+use crate::Location;
+
+/// Mean radius of the Earth in meters (IUGG).
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+impl Location {
+    /// Great-circle distance to `other` in meters, using the haversine
+    /// formula.
+    ///
+    /// ```
+    /// # use waterkit_location::Location;
+    /// # let at = |latitude, longitude| Location {
+    /// #     latitude,
+    /// #     longitude,
+    /// #     altitude: None,
+    /// #     horizontal_accuracy: None,
+    /// #     vertical_accuracy: None,
+    /// #     speed_mps: None,
+    /// #     course_degrees: None,
+    /// #     timestamp: 0,
+    /// # };
+    /// let london = at(51.5074, -0.1278);
+    /// let paris = at(48.8566, 2.3522);
+    /// let km = london.distance_to(&paris) / 1000.0;
+    /// assert!((km - 343.6).abs() < 0.5);
+    /// ```
+    #[must_use]
+    pub fn distance_to(&self, other: &Self) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let half_dlat = (lat2 - lat1) / 2.0;
+        let half_dlon = (other.longitude - self.longitude).to_radians() / 2.0;
+        let a = (lat1.cos() * lat2.cos()).mul_add(half_dlon.sin().powi(2), half_dlat.sin().powi(2));
+        2.0 * EARTH_RADIUS_M * a.sqrt().min(1.0).asin()
+    }
+
+    /// Initial bearing of the great circle to `other`, in degrees clockwise
+    /// from true north (0 to 360).
+    ///
+    /// The bearing changes along the way unless travelling along a meridian
+    /// or the equator.
+    ///
+    /// ```
+    /// # use waterkit_location::Location;
+    /// # let at = |latitude, longitude| Location {
+    /// #     latitude,
+    /// #     longitude,
+    /// #     altitude: None,
+    /// #     horizontal_accuracy: None,
+    /// #     vertical_accuracy: None,
+    /// #     speed_mps: None,
+    /// #     course_degrees: None,
+    /// #     timestamp: 0,
+    /// # };
+    /// let london = at(51.5074, -0.1278);
+    /// let paris = at(48.8566, 2.3522);
+    /// assert!((london.bearing_to(&paris) - 148.1).abs() < 0.1);
+    /// assert!((paris.bearing_to(&london) - 330.0).abs() < 0.1);
+    /// ```
+    #[must_use]
+    pub fn bearing_to(&self, other: &Self) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let dlon = (other.longitude - self.longitude).to_radians();
+        let y = dlon.sin() * lat2.cos();
+        let x = lat1
+            .cos()
+            .mul_add(lat2.sin(), -(lat1.sin() * lat2.cos() * dlon.cos()));
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+
+    /// The location reached by travelling `distance_m` meters along a great
+    /// circle, starting at `bearing_deg` degrees clockwise from true north.
+    ///
+    /// Only the coordinates and timestamp are set; the other fields are
+    /// `None`.
+    ///
+    /// ```
+    /// # use waterkit_location::Location;
+    /// # let at = |latitude, longitude| Location {
+    /// #     latitude,
+    /// #     longitude,
+    /// #     altitude: None,
+    /// #     horizontal_accuracy: None,
+    /// #     vertical_accuracy: None,
+    /// #     speed_mps: None,
+    /// #     course_degrees: None,
+    /// #     timestamp: 0,
+    /// # };
+    /// // 124.8 km from 53°19′14″N 1°43′47″W at 96°01′18″ ends at
+    /// // 53°11′18″N 0°08′00″E
+    /// let start = at(53.320_556, -1.729_722);
+    /// let end = start.destination(96.021_667, 124_800.0);
+    /// assert!((end.latitude - 53.188_333).abs() < 0.001);
+    /// assert!((end.longitude - 0.133_333).abs() < 0.001);
+    ///
+    /// // Crossing the antimeridian wraps the longitude
+    /// let east = at(0.0, 179.5).destination(90.0, 111_195.0);
+    /// assert!((east.longitude + 179.5).abs() < 0.001);
+    /// ```
+    #[must_use]
+    pub fn destination(&self, bearing_deg: f64, distance_m: f64) -> Self {
+        let lat1 = self.latitude.to_radians();
+        let lon1 = self.longitude.to_radians();
+        let bearing = bearing_deg.to_radians();
+        let angle = distance_m / EARTH_RADIUS_M;
+
+        let lat2 = lat1
+            .sin()
+            .mul_add(angle.cos(), lat1.cos() * angle.sin() * bearing.cos())
+            .clamp(-1.0, 1.0)
+            .asin();
+        let lon2 = lon1
+            + (bearing.sin() * angle.sin() * lat1.cos())
+                .atan2(lat1.sin().mul_add(-lat2.sin(), angle.cos()));
+
+        Self {
+            latitude: lat2.to_degrees(),
+            // Wrap into -180..180
+            longitude: (lon2.to_degrees() + 540.0).rem_euclid(360.0) - 180.0,
+            altitude: None,
+            horizontal_accuracy: None,
+            vertical_accuracy: None,
+            speed_mps: None,
+            course_degrees: None,
+            timestamp: self.timestamp,
+        }
+    }
+}
@@ -5,10 +5,19 @@
 
 #![warn(missing_docs)]
 
+/// Distance and bearing between locations.
+mod geo;
 /// Platform-specific implementations.
 mod sys;
 
-pub use waterkit_permission::{Permission, PermissionStatus};
+use futures::{Stream, StreamExt};
+pub use waterkit_permission::{LocationAccuracy, Permission, PermissionStatus};
+
+#[cfg(target_os = "android")]
+pub use sys::android::{
+    add_geofence_with_context, monitor_region_with_context, reverse_geocode_with_context,
+    watch_heading_with_context, watch_location_with_context, watch_with_context,
+};
 
 /// A geographic location with coordinates and metadata.
 #[derive(Debug, Clone, PartialEq)]
@@ -23,10 +32,128 @@ pub struct Location {
     pub horizontal_accuracy: Option<f64>,
     /// Vertical accuracy in meters, if available.
     pub vertical_accuracy: Option<f64>,
+    /// Ground speed in meters per second, if available.
+    pub speed_mps: Option<f64>,
+    /// Direction of travel in degrees clockwise from true north (0 to 360), if available.
+    pub course_degrees: Option<f64>,
+    /// Timestamp as Unix epoch milliseconds.
+    pub timestamp: u64,
+}
+
+/// The compass heading of the device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    /// Heading in degrees clockwise from magnetic north (0 to 360).
+    pub magnetic_heading: f64,
+    /// Heading in degrees clockwise from true north (0 to 360), or negative
+    /// while the declination is unknown for lack of a location.
+    pub true_heading: f64,
+    /// Maximum deviation of the magnetic heading in degrees, or negative if
+    /// unknown.
+    pub accuracy: f64,
     /// Timestamp as Unix epoch milliseconds.
     pub timestamp: u64,
 }
 
+/// A human-readable address for a location.
+///
+/// Fields the geocoder doesn't know are `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Placemark {
+    /// Name of the place, such as a building or point of interest.
+    pub name: Option<String>,
+    /// Street address, including the house number if known.
+    pub street: Option<String>,
+    /// City or locality.
+    pub city: Option<String>,
+    /// State, province or other administrative region.
+    pub region: Option<String>,
+    /// Postal code.
+    pub postal_code: Option<String>,
+    /// Country name.
+    pub country: Option<String>,
+    /// ISO 3166-1 alpha-2 country code.
+    pub country_code: Option<String>,
+}
+
+/// Whether a region boundary was crossed inwards or outwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionEventKind {
+    /// The device entered the region.
+    Enter,
+    /// The device left the region.
+    Exit,
+}
+
+/// A boundary crossing of a region monitored with [`LocationManager::monitor_region`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RegionEvent {
+    /// Identifier the region was registered with.
+    pub id: String,
+    /// Direction of the crossing.
+    pub kind: RegionEventKind,
+}
+
+/// Whether a geofence was entered or exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GeofenceTransition {
+    /// The device entered the geofence.
+    Entered,
+    /// The device left the geofence.
+    Exited,
+}
+
+/// A boundary crossing of a geofence added with [`LocationManager::add_geofence`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeofenceEvent {
+    /// Identifier the geofence was added with.
+    pub fence_id: String,
+    /// Direction of the crossing.
+    pub event: GeofenceTransition,
+    /// Location the crossing was detected at: the triggering fix on Android,
+    /// the location of the region callback on Apple platforms.
+    pub location: Location,
+}
+
+/// Desired accuracy of location updates.
+///
+/// Lower accuracy lets the platform use cheaper sources (Wi-Fi, cell towers)
+/// and saves battery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Accuracy {
+    /// Like [`Accuracy::Best`], and on Apple platforms also fused with
+    /// motion sensors for turn-by-turn navigation. Uses the most power.
+    Navigation,
+    /// The best accuracy the device can provide, typically GPS.
+    Best,
+    /// Within about 10 meters.
+    High,
+    /// Within about 100 meters.
+    #[default]
+    Balanced,
+    /// Within about 1 kilometer.
+    Low,
+    /// Only the fixes the system already has, so updates cost no extra power
+    /// but may be rare. Android reports the fixes other apps request, and
+    /// Apple platforms report significant changes of about 500 meters. Like
+    /// [`Accuracy::Low`] elsewhere.
+    Passive,
+}
+
+impl Accuracy {
+    /// Target accuracy in meters, or `None` for [`Accuracy::Best`],
+    /// [`Accuracy::Navigation`] and [`Accuracy::Passive`].
+    #[must_use]
+    pub const fn meters(self) -> Option<f64> {
+        match self {
+            Self::Navigation | Self::Best | Self::Passive => None,
+            Self::High => Some(10.0),
+            Self::Balanced => Some(100.0),
+            Self::Low => Some(1000.0),
+        }
+    }
+}
+
 /// Errors that can occur when accessing location.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum LocationError {
@@ -75,6 +202,189 @@ impl LocationManager {
         sys::get_location().await
     }
 
+    /// Watch the device location continuously.
+    ///
+    /// The stream yields a location for each platform update, and an error
+    /// when updates fail after starting, such as when the user turns location
+    /// services off or revokes the permission. It keeps going after errors,
+    /// since the platform may recover. On Android, use
+    /// `watch_location_with_context`.
+    ///
+    /// This will request location permission if not already granted.
+    /// Updates stop when the returned stream is dropped.
+    ///
+    /// # Errors
+    /// Returns a `LocationError` if permission is denied, location services are
+    /// disabled, or updates cannot be started.
+    pub async fn watch_location(
+        accuracy: Accuracy,
+    ) -> Result<impl Stream<Item = Result<Location, LocationError>>, LocationError> {
+        let status = waterkit_permission::request(Permission::Location)
+            .await
+            .map_err(|e| LocationError::Unknown(e.to_string()))?;
+
+        if status != PermissionStatus::Granted {
+            return Err(LocationError::PermissionDenied);
+        }
+
+        sys::watch_location(accuracy).await
+    }
+
+    /// Watch the device location continuously, skipping errors.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`watch_location`](Self::watch_location).
+    #[deprecated(note = "use `watch_location`")]
+    pub async fn watch(accuracy: Accuracy) -> Result<impl Stream<Item = Location>, LocationError> {
+        Ok(Self::watch_location(accuracy)
+            .await?
+            .filter_map(|update| futures::future::ready(update.ok())))
+    }
+
+    /// Watch the compass heading of the device.
+    ///
+    /// This uses `CLLocationManager` heading updates on iOS, and the rotation
+    /// vector sensor on Android, where the true heading applies the
+    /// declination at the last known location. The stream only yields a
+    /// heading once it moved by at least `filter_degrees` from the previous
+    /// one; pass 0 for every update. On Android, use
+    /// `watch_heading_with_context`.
+    ///
+    /// The true heading needs location permission, but magnetic headings
+    /// arrive without it. Updates stop when the returned stream is dropped.
+    ///
+    /// # Errors
+    /// Returns [`LocationError::NotAvailable`] if the device has no compass,
+    /// which includes every Mac and all desktop platforms.
+    // Only mobile platforms have a compass, so elsewhere it could be const
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn watch_heading(
+        filter_degrees: f64,
+    ) -> Result<impl Stream<Item = Result<Heading, LocationError>>, LocationError> {
+        sys::watch_heading(filter_degrees)
+    }
+
+    /// Check whether precise positioning is available.
+    ///
+    /// Users can grant approximate location only (iOS 14+, macOS 11+,
+    /// Android 12+). Locations are then accurate to a few kilometers,
+    /// whatever [`Accuracy`] is asked for. Returns `None` if location
+    /// permission isn't granted. On Android, use
+    /// `waterkit_permission::location_accuracy_with_activity`.
+    pub async fn location_accuracy() -> Option<LocationAccuracy> {
+        waterkit_permission::location_accuracy().await
+    }
+
+    /// Monitor a circular region for entry and exit.
+    ///
+    /// This uses `CLCircularRegion` monitoring on Apple platforms, which keeps
+    /// firing while the app is in the background. Android uses a
+    /// `GeofencingClient` geofence, which needs Google Play services location in
+    /// the app; events arrive in the background while the app process is alive.
+    /// Monitoring an `id` that is already monitored replaces that region.
+    /// Monitoring stops when the stream is dropped or [`stop_monitoring`](Self::stop_monitoring)
+    /// is called.
+    ///
+    /// The platform limits how many regions can be monitored at once:
+    /// 20 per app on iOS and macOS, 100 on Android.
+    ///
+    /// # Errors
+    /// Returns a `LocationError` if permission is denied, location services are
+    /// disabled, or region monitoring is not available on this device.
+    pub async fn monitor_region(
+        center: Location,
+        radius_m: f64,
+        id: String,
+    ) -> Result<impl Stream<Item = RegionEvent>, LocationError> {
+        let status = waterkit_permission::request(Permission::Location)
+            .await
+            .map_err(|e| LocationError::Unknown(e.to_string()))?;
+
+        if status != PermissionStatus::Granted {
+            return Err(LocationError::PermissionDenied);
+        }
+
+        sys::monitor_region(&center, radius_m, &id).await
+    }
+
+    /// Stop monitoring the region registered with `id`.
+    ///
+    /// The stream returned by [`monitor_region`](Self::monitor_region) ends.
+    /// Does nothing if the region is not monitored.
+    // Only mobile platforms monitor regions, so elsewhere it could be const
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn stop_monitoring(id: &str) {
+        sys::stop_monitoring(id);
+    }
+
+    /// Start monitoring a circular region as a geofence.
+    ///
+    /// Crossings are delivered to [`geofence_events`](Self::geofence_events)
+    /// until [`remove_geofence`](Self::remove_geofence) is called. Like
+    /// [`monitor_region`](Self::monitor_region), this uses `CLCircularRegion`
+    /// monitoring on Apple platforms and `GeofencingClient` on Android, shares
+    /// their limit on simultaneous regions, and replaces any region already
+    /// monitored under `id`. On Android, use `add_geofence_with_context`.
+    ///
+    /// This will request location permission if not already granted.
+    ///
+    /// # Errors
+    /// Returns a `LocationError` if permission is denied, location services are
+    /// disabled, or region monitoring is not available on this device.
+    pub async fn add_geofence(
+        id: &str,
+        center: Location,
+        radius_m: f64,
+    ) -> Result<(), LocationError> {
+        let status = waterkit_permission::request(Permission::Location)
+            .await
+            .map_err(|e| LocationError::Unknown(e.to_string()))?;
+
+        if status != PermissionStatus::Granted {
+            return Err(LocationError::PermissionDenied);
+        }
+
+        sys::add_geofence(id, &center, radius_m).await
+    }
+
+    /// Stop monitoring the geofence added with `id`.
+    ///
+    /// Does nothing if no geofence is registered under `id`.
+    ///
+    /// # Errors
+    /// Returns `LocationError::NotAvailable` on platforms without region monitoring.
+    // Only mobile platforms monitor regions, so elsewhere it could be const
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn remove_geofence(id: &str) -> Result<(), LocationError> {
+        sys::remove_geofence(id)
+    }
+
+    /// Receive the crossings of every geofence added with
+    /// [`add_geofence`](Self::add_geofence).
+    ///
+    /// Each stream gets the events reported after it was created. On platforms
+    /// without region monitoring the stream is empty.
+    pub fn geofence_events() -> impl Stream<Item = GeofenceEvent> {
+        sys::geofence_events()
+    }
+
+    /// Look up addresses for a location.
+    ///
+    /// This uses `CLGeocoder` on Apple platforms and `Geocoder` on Android.
+    /// Windows and Linux have no system geocoder and query the public
+    /// `OpenStreetMap` Nominatim service instead, which allows about one
+    /// request per second and returns at most one address. All of them are
+    /// online services, so network access is required.
+    /// Results are ordered from most to least relevant and may be empty.
+    ///
+    /// # Errors
+    /// Returns [`LocationError::NotAvailable`] if the platform has no geocoder
+    /// (some Android builds ship without one) or the service cannot be
+    /// reached.
+    pub async fn reverse_geocode(location: &Location) -> Result<Vec<Placemark>, LocationError> {
+        sys::reverse_geocode(location).await
+    }
+
     /// Get the current location without checking permissions.
     ///
     /// Use this if you've already verified permission status.
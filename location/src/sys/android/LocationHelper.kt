@@ -1,8 +1,28 @@
 package waterkit.location
 
+import android.app.PendingIntent
+import android.content.BroadcastReceiver
 import android.content.Context
+import android.content.Intent
+import android.content.IntentFilter
+import android.hardware.GeomagneticField
+import android.hardware.Sensor
+import android.hardware.SensorEvent
+import android.hardware.SensorEventListener
+import android.hardware.SensorManager
+import android.location.Geocoder
 import android.location.Location
+import android.location.LocationListener
 import android.location.LocationManager
+import android.os.Build
+import android.os.Bundle
+import android.os.Handler
+import android.os.HandlerThread
+import android.os.SystemClock
+import java.io.IOException
+import java.lang.reflect.InvocationTargetException
+import java.util.concurrent.ExecutionException
+import java.util.concurrent.TimeUnit
 
 /**
  * Helper class for accessing location on Android.
@@ -12,7 +32,8 @@ object LocationHelper {
     
     /**
      * Get the last known location from any available provider.
-     * Returns array: [success, latitude, longitude, altitude, accuracy, timestamp]
+     * Returns array: [success, latitude, longitude, altitude, accuracy, timestamp,
+     * speed, bearing, verticalAccuracy], with NaN for values the fix doesn't have.
      * On failure: [0.0]
      */
     @JvmStatic
@@ -24,14 +45,471 @@ object LocationHelper {
             ?: tryGetLocation(manager, LocationManager.NETWORK_PROVIDER)
             ?: return doubleArrayOf(0.0)
 
+        return toArray(location)
+    }
+
+    private fun toArray(location: Location): DoubleArray {
         return doubleArrayOf(
             1.0, // success
             location.latitude,
             location.longitude,
-            location.altitude,
-            location.accuracy.toDouble(),
-            location.time.toDouble()
+            if (location.hasAltitude()) location.altitude else Double.NaN,
+            if (location.hasAccuracy()) location.accuracy.toDouble() else Double.NaN,
+            location.time.toDouble(),
+            if (location.hasSpeed()) location.speed.toDouble() else Double.NaN,
+            if (location.hasBearing()) location.bearing.toDouble() else Double.NaN,
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O && location.hasVerticalAccuracy()) {
+                location.verticalAccuracyMeters.toDouble()
+            } else {
+                Double.NaN
+            }
+        )
+    }
+
+    private const val WATCH_STARTED = 0
+    private const val WATCH_PERMISSION_DENIED = 1
+    private const val WATCH_SERVICE_DISABLED = 2
+
+    private class Watch(
+        val manager: LocationManager,
+        val listener: LocationListener,
+        val thread: HandlerThread
+    )
+
+    private val watches = mutableMapOf<Long, Watch>()
+
+    /** Receives updates in the [getLastKnownLocation] array layout. */
+    @JvmStatic
+    external fun onLocation(watchId: Long, location: DoubleArray)
+
+    /** Reports a watch failing after it started, with a [startWatch] status code. */
+    @JvmStatic
+    external fun onWatchError(watchId: Long, status: Int)
+
+    /**
+     * Start continuous updates, delivered to [onLocation] with the given watch id.
+     * A negative accuracy requests the best available provider, and [passive]
+     * only receives the fixes other apps request.
+     * Returns 0 on success, 1 if permission is missing, 2 if no provider is enabled.
+     */
+    @JvmStatic
+    @Suppress("MissingPermission")
+    fun startWatch(context: Context, watchId: Long, accuracyMeters: Double, passive: Boolean): Int {
+        val manager = context.getSystemService(Context.LOCATION_SERVICE) as? LocationManager
+            ?: return WATCH_SERVICE_DISABLED
+
+        val provider = if (passive) {
+            LocationManager.PASSIVE_PROVIDER
+        } else {
+            selectProvider(manager, accuracyMeters) ?: return WATCH_SERVICE_DISABLED
+        }
+
+        val listener = object : LocationListener {
+            override fun onLocationChanged(location: Location) {
+                onLocation(watchId, toArray(location))
+            }
+
+            // Updates resume by themselves when the provider is turned back on
+            override fun onProviderDisabled(disabledProvider: String) {
+                onWatchError(watchId, WATCH_SERVICE_DISABLED)
+            }
+
+            // Abstract before Android 11, so they need implementing for older devices
+            override fun onProviderEnabled(enabledProvider: String) {}
+
+            @Deprecated("Deprecated in Java")
+            override fun onStatusChanged(statusProvider: String?, status: Int, extras: Bundle?) {}
+        }
+
+        // Deliver updates on a dedicated looper; the calling thread may not have one
+        val thread = HandlerThread("waterkit-location-$watchId").apply { start() }
+        return try {
+            manager.requestLocationUpdates(provider, 1000L, 0f, listener, thread.looper)
+            synchronized(watches) {
+                watches[watchId] = Watch(manager, listener, thread)
+            }
+            WATCH_STARTED
+        } catch (e: SecurityException) {
+            thread.quitSafely()
+            WATCH_PERMISSION_DENIED
+        } catch (e: IllegalArgumentException) {
+            thread.quitSafely()
+            WATCH_SERVICE_DISABLED
+        }
+    }
+
+    private class HeadingWatch(
+        val manager: SensorManager,
+        val listener: SensorEventListener,
+        val thread: HandlerThread
+    )
+
+    private val headingWatches = mutableMapOf<Long, HeadingWatch>()
+
+    /** Receives headings as [magneticHeading, trueHeading, accuracy, timestamp]. */
+    @JvmStatic
+    external fun onHeading(watchId: Long, heading: DoubleArray)
+
+    /**
+     * Start heading updates from the rotation vector sensor, delivered to
+     * [onHeading] once the heading moved by at least [filterDegrees].
+     * The true heading and accuracy are -1 when unknown.
+     * Returns false if the device has no rotation vector sensor.
+     */
+    @JvmStatic
+    fun startHeading(context: Context, watchId: Long, filterDegrees: Double): Boolean {
+        val manager = context.getSystemService(Context.SENSOR_SERVICE) as? SensorManager
+            ?: return false
+        val sensor = manager.getDefaultSensor(Sensor.TYPE_ROTATION_VECTOR) ?: return false
+
+        // The declination barely changes over the distances a watch covers
+        val locationManager = context.getSystemService(Context.LOCATION_SERVICE) as? LocationManager
+        val declination = locationManager?.let {
+            tryGetLocation(it, LocationManager.GPS_PROVIDER)
+                ?: tryGetLocation(it, LocationManager.NETWORK_PROVIDER)
+        }?.let {
+            GeomagneticField(
+                it.latitude.toFloat(),
+                it.longitude.toFloat(),
+                it.altitude.toFloat(),
+                System.currentTimeMillis()
+            ).declination.toDouble()
+        }
+
+        val listener = object : SensorEventListener {
+            private val rotation = FloatArray(9)
+            private val orientation = FloatArray(3)
+            private var last = Double.NaN
+
+            override fun onSensorChanged(event: SensorEvent) {
+                SensorManager.getRotationMatrixFromVector(rotation, event.values)
+                SensorManager.getOrientation(rotation, orientation)
+                val magnetic = (Math.toDegrees(orientation[0].toDouble()) + 360.0) % 360.0
+
+                // Always false for the first heading, since last is NaN
+                val change = Math.abs(magnetic - last) % 360.0
+                if (Math.min(change, 360.0 - change) < filterDegrees) {
+                    return
+                }
+                last = magnetic
+
+                // The fifth value is the accuracy in radians, -1 if unknown
+                val accuracy = event.values.getOrNull(4)
+                    ?.takeIf { it >= 0f }
+                    ?.let { Math.toDegrees(it.toDouble()) }
+                    ?: -1.0
+                // Event timestamps count from boot
+                val timestamp = System.currentTimeMillis() -
+                    (SystemClock.elapsedRealtimeNanos() - event.timestamp) / 1_000_000
+                onHeading(watchId, doubleArrayOf(
+                    magnetic,
+                    declination?.let { (magnetic + it + 360.0) % 360.0 } ?: -1.0,
+                    accuracy,
+                    timestamp.toDouble()
+                ))
+            }
+
+            override fun onAccuracyChanged(sensor: Sensor, accuracy: Int) {}
+        }
+
+        val thread = HandlerThread("waterkit-heading-$watchId").apply { start() }
+        if (!manager.registerListener(listener, sensor, SensorManager.SENSOR_DELAY_UI, Handler(thread.looper))) {
+            thread.quitSafely()
+            return false
+        }
+        synchronized(headingWatches) {
+            headingWatches[watchId] = HeadingWatch(manager, listener, thread)
+        }
+        return true
+    }
+
+    /** Stop the updates started by [startWatch] or [startHeading]. */
+    @JvmStatic
+    fun stopWatch(watchId: Long) {
+        synchronized(watches) { watches.remove(watchId) }?.let {
+            it.manager.removeUpdates(it.listener)
+            it.thread.quitSafely()
+        }
+        synchronized(headingWatches) { headingWatches.remove(watchId) }?.let {
+            it.manager.unregisterListener(it.listener)
+            it.thread.quitSafely()
+        }
+    }
+
+    private fun selectProvider(manager: LocationManager, accuracyMeters: Double): String? {
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.S &&
+            manager.isProviderEnabled(LocationManager.FUSED_PROVIDER)
+        ) {
+            return LocationManager.FUSED_PROVIDER
+        }
+
+        val preferGps = accuracyMeters < 0 || accuracyMeters <= 10.0
+        val candidates = if (preferGps) {
+            listOf(LocationManager.GPS_PROVIDER, LocationManager.NETWORK_PROVIDER)
+        } else {
+            listOf(LocationManager.NETWORK_PROVIDER, LocationManager.GPS_PROVIDER)
+        }
+        return candidates.firstOrNull { manager.isProviderEnabled(it) }
+    }
+
+    /** Status of [startRegion] when Google Play services location is missing or fails. */
+    private const val REGION_UNAVAILABLE = 3
+
+    /** `Geofence.GEOFENCE_TRANSITION_ENTER`. */
+    private const val GEOFENCE_TRANSITION_ENTER = 1
+
+    /** `Geofence.GEOFENCE_TRANSITION_EXIT`. */
+    private const val GEOFENCE_TRANSITION_EXIT = 2
+
+    /** `GeofenceStatusCodes.GEOFENCE_NOT_AVAILABLE`, e.g. with location turned off. */
+    private const val GEOFENCE_NOT_AVAILABLE = 1000
+
+    /** `GeofenceStatusCodes.GEOFENCE_INSUFFICIENT_LOCATION_PERMISSION`. */
+    private const val GEOFENCE_INSUFFICIENT_LOCATION_PERMISSION = 1004
+
+    /** How long [startRegion] waits for Play services to add a geofence. */
+    private const val ADD_GEOFENCE_TIMEOUT_SECONDS = 30L
+
+    /**
+     * The `GeofencingClient` API of Google Play services, looked up at runtime so that
+     * apps without `play-services-location` can still use the rest of this helper.
+     */
+    private class Geofencing(val context: Context) {
+        val geofenceClass: Class<*> = Class.forName("com.google.android.gms.location.Geofence")
+        private val clientClass = Class.forName("com.google.android.gms.location.GeofencingClient")
+        private val requestClass = Class.forName("com.google.android.gms.location.GeofencingRequest")
+        private val eventClass = Class.forName("com.google.android.gms.location.GeofencingEvent")
+        private val client = Class.forName("com.google.android.gms.location.LocationServices")
+            .getMethod("getGeofencingClient", Context::class.java)
+            .invoke(null, context)
+
+        fun add(id: String, latitude: Double, longitude: Double, radiusMeters: Float, intent: PendingIntent) {
+            val builderClass = Class.forName("com.google.android.gms.location.Geofence\$Builder")
+            val builder = builderClass.getConstructor().newInstance()
+            builderClass.getMethod("setRequestId", String::class.java).invoke(builder, id)
+            builderClass.getMethod(
+                "setCircularRegion",
+                Double::class.javaPrimitiveType,
+                Double::class.javaPrimitiveType,
+                Float::class.javaPrimitiveType
+            ).invoke(builder, latitude, longitude, radiusMeters)
+            builderClass.getMethod("setExpirationDuration", Long::class.javaPrimitiveType)
+                .invoke(builder, -1L) // Geofence.NEVER_EXPIRE
+            builderClass.getMethod("setTransitionTypes", Int::class.javaPrimitiveType)
+                .invoke(builder, GEOFENCE_TRANSITION_ENTER or GEOFENCE_TRANSITION_EXIT)
+            val geofence = builderClass.getMethod("build").invoke(builder)
+
+            val requestBuilderClass = Class.forName("com.google.android.gms.location.GeofencingRequest\$Builder")
+            val requestBuilder = requestBuilderClass.getConstructor().newInstance()
+            requestBuilderClass.getMethod("addGeofence", geofenceClass).invoke(requestBuilder, geofence)
+            val request = requestBuilderClass.getMethod("build").invoke(requestBuilder)
+
+            val task = clientClass.getMethod("addGeofences", requestClass, PendingIntent::class.java)
+                .invoke(client, request, intent)
+            await(task)
+        }
+
+        fun remove(id: String) {
+            clientClass.getMethod("removeGeofences", List::class.java).invoke(client, listOf(id))
+        }
+
+        /** Report the crossings of a `GeofencingEvent` broadcast to [onRegionEvent]. */
+        fun dispatch(intent: Intent) {
+            val event = eventClass.getMethod("fromIntent", Intent::class.java).invoke(null, intent) ?: return
+            if (eventClass.getMethod("hasError").invoke(event) as Boolean) {
+                return
+            }
+            val entered = when (eventClass.getMethod("getGeofenceTransition").invoke(event) as Int) {
+                GEOFENCE_TRANSITION_ENTER -> true
+                GEOFENCE_TRANSITION_EXIT -> false
+                else -> return
+            }
+            val triggering = eventClass.getMethod("getTriggeringLocation").invoke(event) as Location?
+            val location = triggering?.let(::toArray) ?: getLastKnownLocation(context)
+            val fences = eventClass.getMethod("getTriggeringGeofences").invoke(event) as List<*>? ?: return
+            val requestId = geofenceClass.getMethod("getRequestId")
+            for (fence in fences) {
+                onRegionEvent(requestId.invoke(fence) as String, entered, location)
+            }
+        }
+
+        /** Wait for a `Task`, throwing the `ApiException` it failed with. */
+        private fun await(task: Any?) {
+            val taskClass = Class.forName("com.google.android.gms.tasks.Task")
+            try {
+                Class.forName("com.google.android.gms.tasks.Tasks")
+                    .getMethod("await", taskClass, Long::class.javaPrimitiveType, TimeUnit::class.java)
+                    .invoke(null, task, ADD_GEOFENCE_TIMEOUT_SECONDS, TimeUnit.SECONDS)
+            } catch (e: InvocationTargetException) {
+                val cause = e.targetException
+                throw if (cause is ExecutionException) cause.cause ?: cause else cause
+            }
+        }
+    }
+
+    /** Geofences added by [startRegion] share one receiver and one pending intent. */
+    private class Regions(
+        val geofencing: Geofencing,
+        val intent: PendingIntent,
+        val receiver: BroadcastReceiver,
+        val ids: MutableSet<String> = mutableSetOf()
+    )
+
+    private var regions: Regions? = null
+
+    /**
+     * Receives region boundary crossings started by [startRegion], with the location
+     * that triggered them in the [getLastKnownLocation] array layout.
+     */
+    @JvmStatic
+    external fun onRegionEvent(id: String, entered: Boolean, location: DoubleArray)
+
+    /**
+     * Monitor a circular region with a `GeofencingClient` geofence, reporting crossings
+     * to [onRegionEvent]. The geofence broadcasts a pending intent to a receiver
+     * registered on the application context, so crossings are reported while the
+     * process is alive, also in the background.
+     * A region with the same id replaces the previous one.
+     * Returns 0 on success, 1 if permission is missing, 2 if location is unavailable,
+     * 3 if Google Play services location is missing or fails.
+     */
+    @JvmStatic
+    @Suppress("MissingPermission")
+    fun startRegion(context: Context, id: String, latitude: Double, longitude: Double, radiusMeters: Double): Int {
+        val appContext = context.applicationContext
+        // Claim the id first, so that stopping the other regions meanwhile keeps the receiver
+        val regions = try {
+            synchronized(this) {
+                val current = regions ?: registerRegions(appContext).also { regions = it }
+                current.ids.add(id)
+                current
+            }
+        } catch (e: ReflectiveOperationException) {
+            return REGION_UNAVAILABLE
+        }
+
+        return try {
+            // Adding a geofence with the id of another replaces it
+            regions.geofencing.add(id, latitude, longitude, radiusMeters.toFloat(), regions.intent)
+            WATCH_STARTED
+        } catch (e: Exception) {
+            stopRegion(id)
+            val cause = if (e is InvocationTargetException) e.targetException else e
+            when {
+                cause is SecurityException -> WATCH_PERMISSION_DENIED
+                statusCode(cause) == GEOFENCE_INSUFFICIENT_LOCATION_PERMISSION -> WATCH_PERMISSION_DENIED
+                statusCode(cause) == GEOFENCE_NOT_AVAILABLE -> WATCH_SERVICE_DISABLED
+                else -> REGION_UNAVAILABLE
+            }
+        }
+    }
+
+    /** Stop the geofence started by [startRegion]. */
+    @JvmStatic
+    fun stopRegion(id: String) {
+        var last = false
+        val removed = synchronized(this) {
+            val current = regions ?: return
+            if (!current.ids.remove(id)) {
+                return
+            }
+            last = current.ids.isEmpty()
+            if (last) {
+                regions = null
+            }
+            current
+        }
+        try {
+            removed.geofencing.remove(id)
+        } catch (e: ReflectiveOperationException) {
+            // Play services went away; so did its geofences
+        }
+        if (last) {
+            removed.intent.cancel()
+            removed.geofencing.context.unregisterReceiver(removed.receiver)
+        }
+    }
+
+    private fun registerRegions(appContext: Context): Regions {
+        val geofencing = Geofencing(appContext)
+        val action = "${appContext.packageName}.waterkit.location.GEOFENCE"
+        val receiver = object : BroadcastReceiver() {
+            override fun onReceive(context: Context, intent: Intent) {
+                try {
+                    geofencing.dispatch(intent)
+                } catch (e: ReflectiveOperationException) {
+                    // Not an event this Play services version understands
+                }
+            }
+        }
+        val filter = IntentFilter(action)
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+            appContext.registerReceiver(receiver, filter, Context.RECEIVER_NOT_EXPORTED)
+        } else {
+            appContext.registerReceiver(receiver, filter)
+        }
+
+        // Play services adds the GeofencingEvent extras, so the intent must be mutable
+        val flags = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.S) {
+            PendingIntent.FLAG_UPDATE_CURRENT or PendingIntent.FLAG_MUTABLE
+        } else {
+            PendingIntent.FLAG_UPDATE_CURRENT
+        }
+        val intent = PendingIntent.getBroadcast(
+            appContext,
+            0,
+            Intent(action).setPackage(appContext.packageName),
+            flags
         )
+        return Regions(geofencing, intent, receiver)
+    }
+
+    /** The `ApiException` status code of a failed geofence request, if it is one. */
+    private fun statusCode(e: Throwable): Int? = try {
+        e.javaClass.getMethod("getStatusCode").invoke(e) as? Int
+    } catch (e: ReflectiveOperationException) {
+        null
+    }
+
+    /** Number of fields per address returned by [reverseGeocode]. */
+    private const val PLACEMARK_FIELDS = 7
+
+    /**
+     * Look up addresses for a coordinate.
+     * Returns a flat array with [PLACEMARK_FIELDS] entries per address: name, street,
+     * city, region, postal code, country, country code (null when unknown).
+     * Returns null if no geocoder is present or the service can't be reached.
+     */
+    @JvmStatic
+    fun reverseGeocode(context: Context, latitude: Double, longitude: Double): Array<String?>? {
+        if (!Geocoder.isPresent()) {
+            return null
+        }
+
+        val addresses = try {
+            @Suppress("DEPRECATION")
+            Geocoder(context).getFromLocation(latitude, longitude, 5)
+        } catch (e: IOException) {
+            return null
+        } ?: emptyList()
+
+        val result = arrayOfNulls<String>(addresses.size * PLACEMARK_FIELDS)
+        addresses.forEachIndexed { i, address ->
+            val street = listOfNotNull(address.subThoroughfare, address.thoroughfare)
+                .joinToString(" ")
+                .ifEmpty { null }
+            val fields = arrayOf(
+                address.featureName,
+                street,
+                address.locality,
+                address.adminArea,
+                address.postalCode,
+                address.countryName,
+                address.countryCode
+            )
+            fields.copyInto(result, i * PLACEMARK_FIELDS)
+        }
+        return result
     }
 
     @Suppress("MissingPermission")
@@ -1,9 +1,13 @@
 //! Android location implementation using JNI.
 
-use crate::{Location, LocationError};
-use jni::JNIEnv;
-use jni::objects::{GlobalRef, JObject, JValue};
-use std::sync::OnceLock;
+use super::regions::{self, RegionGuard};
+use crate::{Accuracy, Heading, Location, LocationError, Placemark, RegionEvent, RegionEventKind};
+use jni::objects::{GlobalRef, JClass, JDoubleArray, JObject, JObjectArray, JString, JValue};
+use jni::sys::{jboolean, jint, jlong};
+use jni::{JNIEnv, JavaVM};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
 
 /// Embedded DEX bytecode containing LocationHelper class.
 /// Generated at build time by kotlinc + D8.
@@ -94,16 +98,44 @@ pub fn init(env: &mut JNIEnv, context: &JObject) -> Result<(), LocationError> {
         .map_err(|e| LocationError::Unknown(format!("new_global_ref: {e}")))?;
 
     let _ = CLASS_LOADER.set(global_ref);
+
+    // The helper is loaded from our own DexClassLoader, so the runtime can't
+    // resolve its native methods by symbol name; register them explicitly.
+    register_natives(env)?;
+
     Ok(())
 }
 
-/// Get location using the Context.
-pub fn get_location_with_context(
-    env: &mut JNIEnv,
-    context: &JObject,
-) -> Result<Location, LocationError> {
-    init(env, context)?;
+fn register_natives(env: &mut JNIEnv) -> Result<(), LocationError> {
+    let class = get_helper_class(env)?;
+    let native_methods = [
+        jni::NativeMethod {
+            name: "onLocation".into(),
+            sig: "(J[D)V".into(),
+            fn_ptr: Java_waterkit_location_LocationHelper_onLocation as *mut _,
+        },
+        jni::NativeMethod {
+            name: "onHeading".into(),
+            sig: "(J[D)V".into(),
+            fn_ptr: Java_waterkit_location_LocationHelper_onHeading as *mut _,
+        },
+        jni::NativeMethod {
+            name: "onWatchError".into(),
+            sig: "(JI)V".into(),
+            fn_ptr: Java_waterkit_location_LocationHelper_onWatchError as *mut _,
+        },
+        jni::NativeMethod {
+            name: "onRegionEvent".into(),
+            sig: "(Ljava/lang/String;Z[D)V".into(),
+            fn_ptr: Java_waterkit_location_LocationHelper_onRegionEvent as *mut _,
+        },
+    ];
+
+    env.register_native_methods(class, &native_methods)
+        .map_err(|e| LocationError::Unknown(format!("register_native_methods: {e}")))
+}
 
+fn get_helper_class<'a>(env: &mut JNIEnv<'a>) -> Result<JClass<'a>, LocationError> {
     let class_loader = CLASS_LOADER
         .get()
         .ok_or_else(|| LocationError::Unknown("Class loader not initialized".into()))?;
@@ -123,7 +155,17 @@ pub fn get_location_with_context(
         .l()
         .map_err(|e| LocationError::Unknown(format!("loadClass result: {e}")))?;
 
-    let helper_jclass: jni::objects::JClass = helper_class.into();
+    Ok(helper_class.into())
+}
+
+/// Get location using the Context.
+pub fn get_location_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<Location, LocationError> {
+    init(env, context)?;
+
+    let helper_jclass = get_helper_class(env)?;
     let result = env
         .call_static_method(
             helper_jclass,
@@ -135,10 +177,13 @@ pub fn get_location_with_context(
         .l()
         .map_err(|e| LocationError::Unknown(format!("getLastKnownLocation result: {e}")))?;
 
-    // Parse double array result
-    let result_array: jni::objects::JDoubleArray = result.into();
+    parse_location(env, &result.into())
+}
+
+/// Parse a location array as returned by `LocationHelper.getLastKnownLocation`.
+fn parse_location(env: &mut JNIEnv, array: &JDoubleArray) -> Result<Location, LocationError> {
     let len = env
-        .get_array_length(&result_array)
+        .get_array_length(array)
         .map_err(|e| LocationError::Unknown(format!("get_array_length: {e}")))?
         as usize;
 
@@ -148,7 +193,7 @@ pub fn get_location_with_context(
 
     // Copy array elements to a Rust buffer
     let mut buf = vec![0.0f64; len];
-    env.get_double_array_region(&result_array, 0, &mut buf)
+    env.get_double_array_region(array, 0, &mut buf)
         .map_err(|e| LocationError::Unknown(format!("get_double_array_region: {e}")))?;
 
     let success = buf[0];
@@ -160,21 +205,550 @@ pub fn get_location_with_context(
         return Err(LocationError::Unknown("Invalid result array".into()));
     }
 
+    // Missing values are NaN
+    let optional = |index: usize| buf.get(index).copied().filter(|v| !v.is_nan());
     Ok(Location {
         latitude: buf[1],
         longitude: buf[2],
-        altitude: Some(buf[3]),
-        horizontal_accuracy: Some(buf[4]),
-        vertical_accuracy: None,
+        altitude: optional(3),
+        horizontal_accuracy: optional(4),
+        vertical_accuracy: optional(8),
+        speed_mps: optional(6),
+        course_degrees: optional(7),
         timestamp: buf[5] as u64,
     })
 }
 
 // Async wrapper for the public API (requires runtime context)
-pub(crate) async fn get_location() -> Result<Location, LocationError> {
+pub async fn get_location() -> Result<Location, LocationError> {
     // Without JNI context, we can't get location
     // The application must call get_location_with_context directly
     Err(LocationError::Unknown(
         "Android: use get_location_with_context() with Context".into(),
     ))
 }
+
+/// Number of fields per address returned by `LocationHelper.reverseGeocode`.
+const PLACEMARK_FIELDS: usize = 7;
+
+/// Look up addresses for a location using the platform `Geocoder`.
+///
+/// This blocks while the geocoder queries its online service.
+///
+/// # Errors
+/// Returns `LocationError::NotAvailable` if the device has no geocoder
+/// or the service can't be reached.
+pub fn reverse_geocode_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    location: &Location,
+) -> Result<Vec<Placemark>, LocationError> {
+    init(env, context)?;
+
+    let helper_jclass = get_helper_class(env)?;
+    let result = env
+        .call_static_method(
+            helper_jclass,
+            "reverseGeocode",
+            "(Landroid/content/Context;DD)[Ljava/lang/String;",
+            &[
+                JValue::Object(context),
+                JValue::Double(location.latitude),
+                JValue::Double(location.longitude),
+            ],
+        )
+        .map_err(|e| LocationError::Unknown(format!("reverseGeocode: {e}")))?
+        .l()
+        .map_err(|e| LocationError::Unknown(format!("reverseGeocode result: {e}")))?;
+
+    if result.is_null() {
+        return Err(LocationError::NotAvailable);
+    }
+
+    let array = JObjectArray::from(result);
+    let len = env
+        .get_array_length(&array)
+        .map_err(|e| LocationError::Unknown(format!("get_array_length: {e}")))?;
+
+    let mut fields = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        let element = env
+            .get_object_array_element(&array, i)
+            .map_err(|e| LocationError::Unknown(format!("get_object_array_element: {e}")))?;
+        let value = if element.is_null() {
+            None
+        } else {
+            env.get_string(&JString::from(element))
+                .ok()
+                .map(String::from)
+        };
+        fields.push(value);
+    }
+
+    Ok(fields
+        .chunks_exact(PLACEMARK_FIELDS)
+        .map(|address| Placemark {
+            name: address[0].clone(),
+            street: address[1].clone(),
+            city: address[2].clone(),
+            region: address[3].clone(),
+            postal_code: address[4].clone(),
+            country: address[5].clone(),
+            country_code: address[6].clone(),
+        })
+        .collect())
+}
+
+// Async wrapper for the public API (requires runtime context)
+pub async fn reverse_geocode(_location: &Location) -> Result<Vec<Placemark>, LocationError> {
+    // The application must call reverse_geocode_with_context directly
+    Err(LocationError::Unknown(
+        "Android: use reverse_geocode_with_context() with Context".into(),
+    ))
+}
+
+/// Senders for active watches, keyed by watch id.
+static WATCHERS: Mutex<Option<HashMap<u64, WatchSender>>> = Mutex::new(None);
+static NEXT_WATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The sender of a location or a heading watch.
+enum WatchSender {
+    Location(async_channel::Sender<Result<Location, LocationError>>),
+    Heading(async_channel::Sender<Result<Heading, LocationError>>),
+}
+
+/// Run `send` with the sender of the watch registered under `watch_id`, if
+/// it's still alive.
+fn with_watch(watch_id: jlong, send: impl FnOnce(&WatchSender)) {
+    if let Ok(watchers) = WATCHERS.lock()
+        && let Some(sender) = watchers.as_ref().and_then(|w| w.get(&(watch_id as u64)))
+    {
+        send(sender);
+    }
+}
+
+/// Register a watch, returning the guard that removes it again.
+fn insert_watch(sender: WatchSender, vm: JavaVM) -> Result<WatchGuard, LocationError> {
+    let id = NEXT_WATCH_ID.fetch_add(1, Ordering::Relaxed);
+    WATCHERS
+        .lock()
+        .map_err(|e| LocationError::Unknown(e.to_string()))?
+        .get_or_insert_with(HashMap::new)
+        .insert(id, sender);
+    Ok(WatchGuard { id, vm })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_location_LocationHelper_onLocation(
+    mut env: JNIEnv,
+    _class: JClass,
+    watch_id: jlong,
+    location: JDoubleArray,
+) {
+    let Ok(location) = parse_location(&mut env, &location) else {
+        return;
+    };
+    with_watch(watch_id, |sender| {
+        if let WatchSender::Location(sender) = sender {
+            let _ = sender.try_send(Ok(location));
+        }
+    });
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_location_LocationHelper_onHeading(
+    env: JNIEnv,
+    _class: JClass,
+    watch_id: jlong,
+    heading: JDoubleArray,
+) {
+    // [magneticHeading, trueHeading, accuracy, timestamp]
+    let mut buf = [0.0f64; 4];
+    if env.get_double_array_region(&heading, 0, &mut buf).is_err() {
+        return;
+    }
+    with_watch(watch_id, |sender| {
+        if let WatchSender::Heading(sender) = sender {
+            let _ = sender.try_send(Ok(Heading {
+                magnetic_heading: buf[0],
+                true_heading: buf[1],
+                accuracy: buf[2],
+                timestamp: buf[3] as u64,
+            }));
+        }
+    });
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_location_LocationHelper_onWatchError(
+    _env: JNIEnv,
+    _class: JClass,
+    watch_id: jlong,
+    status: jint,
+) {
+    let error = watch_error(status);
+    with_watch(watch_id, |sender| match sender {
+        WatchSender::Location(sender) => {
+            let _ = sender.try_send(Err(error));
+        }
+        WatchSender::Heading(sender) => {
+            let _ = sender.try_send(Err(error));
+        }
+    });
+}
+
+/// The error of a `startWatch` status code other than 0 (started).
+const fn watch_error(status: jint) -> LocationError {
+    match status {
+        1 => LocationError::PermissionDenied,
+        2 => LocationError::ServiceDisabled,
+        _ => LocationError::NotAvailable,
+    }
+}
+
+/// Removes the `LocationManager` or `SensorManager` listener of a watch when
+/// dropped.
+struct WatchGuard {
+    id: u64,
+    vm: JavaVM,
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        if let Ok(mut watchers) = WATCHERS.lock()
+            && let Some(watchers) = watchers.as_mut()
+        {
+            watchers.remove(&self.id);
+        }
+
+        if let Ok(mut env) = self.vm.attach_current_thread()
+            && let Ok(class) = get_helper_class(&mut env)
+        {
+            let _ = env.call_static_method(
+                class,
+                "stopWatch",
+                "(J)V",
+                &[JValue::Long(self.id as jlong)],
+            );
+        }
+    }
+}
+
+/// Watch the device location using the Context.
+///
+/// Updates come from the platform `LocationManager`, preferring the fused
+/// provider on Android 12+, or from the passive provider for
+/// [`Accuracy::Passive`]. The stream yields
+/// [`LocationError::ServiceDisabled`] when the user turns the provider off.
+/// Updates stop when the returned stream is dropped.
+///
+/// # Errors
+/// Returns a `LocationError` if no suitable provider is enabled or the
+/// location permission has not been granted.
+pub fn watch_location_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    accuracy: Accuracy,
+) -> Result<impl futures::Stream<Item = Result<Location, LocationError>>, LocationError> {
+    init(env, context)?;
+
+    let vm = env
+        .get_java_vm()
+        .map_err(|e| LocationError::Unknown(format!("get_java_vm: {e}")))?;
+
+    let (sender, receiver) = async_channel::unbounded();
+    // Removes the sender again if starting fails
+    let guard = insert_watch(WatchSender::Location(sender), vm)?;
+
+    let helper_jclass = get_helper_class(env)?;
+    let status = env
+        .call_static_method(
+            helper_jclass,
+            "startWatch",
+            "(Landroid/content/Context;JDZ)I",
+            &[
+                JValue::Object(context),
+                JValue::Long(guard.id as jlong),
+                JValue::Double(accuracy.meters().unwrap_or(-1.0)),
+                JValue::Bool(jboolean::from(accuracy == Accuracy::Passive)),
+            ],
+        )
+        .map_err(|e| LocationError::Unknown(format!("startWatch: {e}")))?
+        .i()
+        .map_err(|e| LocationError::Unknown(format!("startWatch result: {e}")))?;
+
+    match status {
+        0 => Ok(super::guarded_stream(receiver, guard)),
+        status => Err(watch_error(status)),
+    }
+}
+
+/// Watch the device location using the Context, skipping errors.
+///
+/// # Errors
+/// Returns the same errors as [`watch_location_with_context`].
+#[deprecated(note = "use `watch_location_with_context`")]
+pub fn watch_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    accuracy: Accuracy,
+) -> Result<impl futures::Stream<Item = Location>, LocationError> {
+    use futures::StreamExt;
+
+    Ok(watch_location_with_context(env, context, accuracy)?
+        .filter_map(|update| futures::future::ready(update.ok())))
+}
+
+// Async wrapper for the public API (requires runtime context)
+pub async fn watch_location(
+    _accuracy: Accuracy,
+) -> Result<futures::stream::Empty<Result<Location, LocationError>>, LocationError> {
+    // The application must call watch_location_with_context directly
+    Err(LocationError::Unknown(
+        "Android: use watch_location_with_context() with Context".into(),
+    ))
+}
+
+/// Watch the compass heading using the Context.
+///
+/// Headings come from the rotation vector sensor. The true heading applies
+/// the declination at the last known location, and is -1 if there is none.
+/// Updates stop when the returned stream is dropped.
+///
+/// # Errors
+/// Returns `LocationError::NotAvailable` if the device has no rotation
+/// vector sensor.
+pub fn watch_heading_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    filter_degrees: f64,
+) -> Result<impl futures::Stream<Item = Result<Heading, LocationError>>, LocationError> {
+    init(env, context)?;
+
+    let vm = env
+        .get_java_vm()
+        .map_err(|e| LocationError::Unknown(format!("get_java_vm: {e}")))?;
+
+    let (sender, receiver) = async_channel::unbounded();
+    // Removes the sender again if starting fails
+    let guard = insert_watch(WatchSender::Heading(sender), vm)?;
+
+    let helper_jclass = get_helper_class(env)?;
+    let started = env
+        .call_static_method(
+            helper_jclass,
+            "startHeading",
+            "(Landroid/content/Context;JD)Z",
+            &[
+                JValue::Object(context),
+                JValue::Long(guard.id as jlong),
+                JValue::Double(filter_degrees),
+            ],
+        )
+        .map_err(|e| LocationError::Unknown(format!("startHeading: {e}")))?
+        .z()
+        .map_err(|e| LocationError::Unknown(format!("startHeading result: {e}")))?;
+
+    if started {
+        Ok(super::guarded_stream(receiver, guard))
+    } else {
+        Err(LocationError::NotAvailable)
+    }
+}
+
+// Sync wrapper for the public API (requires runtime context)
+pub fn watch_heading(
+    _filter_degrees: f64,
+) -> Result<futures::stream::Empty<Result<Heading, LocationError>>, LocationError> {
+    // The application must call watch_heading_with_context directly
+    Err(LocationError::Unknown(
+        "Android: use watch_heading_with_context() with Context".into(),
+    ))
+}
+
+/// VM used to stop region monitoring outside of a JNI call.
+static VM: OnceLock<JavaVM> = OnceLock::new();
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_location_LocationHelper_onRegionEvent(
+    mut env: JNIEnv,
+    _class: JClass,
+    id: JString,
+    entered: jboolean,
+    location: JDoubleArray,
+) {
+    let Ok(id) = env.get_string(&id).map(String::from) else {
+        return;
+    };
+    let kind = if entered != 0 {
+        RegionEventKind::Enter
+    } else {
+        RegionEventKind::Exit
+    };
+    let location = parse_location(&mut env, &location).ok();
+    regions::dispatch(&id, kind, location);
+}
+
+/// Remove the geofence of region `id`.
+fn stop_region(id: &str) {
+    let Some(vm) = VM.get() else {
+        return;
+    };
+    if let Ok(mut env) = vm.attach_current_thread()
+        && let Ok(class) = get_helper_class(&mut env)
+        && let Ok(id) = env.new_string(id)
+    {
+        let _ = env.call_static_method(
+            class,
+            "stopRegion",
+            "(Ljava/lang/String;)V",
+            &[JValue::Object(&id)],
+        );
+    }
+}
+
+/// Add a geofence for region `id`, replacing any geofence with that id.
+fn start_region(
+    env: &mut JNIEnv,
+    context: &JObject,
+    center: &Location,
+    radius_m: f64,
+    id: &str,
+) -> Result<(), LocationError> {
+    init(env, context)?;
+
+    if VM.get().is_none() {
+        let vm = env
+            .get_java_vm()
+            .map_err(|e| LocationError::Unknown(format!("get_java_vm: {e}")))?;
+        let _ = VM.set(vm);
+    }
+
+    let id_jstr = env
+        .new_string(id)
+        .map_err(|e| LocationError::Unknown(format!("new_string: {e}")))?;
+    let helper_jclass = get_helper_class(env)?;
+    let status = env
+        .call_static_method(
+            helper_jclass,
+            "startRegion",
+            "(Landroid/content/Context;Ljava/lang/String;DDD)I",
+            &[
+                JValue::Object(context),
+                JValue::Object(&id_jstr),
+                JValue::Double(center.latitude),
+                JValue::Double(center.longitude),
+                JValue::Double(radius_m),
+            ],
+        )
+        .map_err(|e| LocationError::Unknown(format!("startRegion: {e}")))?
+        .i()
+        .map_err(|e| LocationError::Unknown(format!("startRegion result: {e}")))?;
+
+    match status {
+        0 => Ok(()),
+        1 => Err(LocationError::PermissionDenied),
+        2 => Err(LocationError::ServiceDisabled),
+        // Google Play services location is missing or failed
+        _ => Err(LocationError::NotAvailable),
+    }
+}
+
+/// Monitor a circular region using the Context.
+///
+/// This adds a geofence with the `GeofencingClient` of Google Play services,
+/// which the app must include (`com.google.android.gms:play-services-location`).
+/// Its pending intent reaches a receiver registered on the application
+/// context, so events arrive while the app is in the background, as long as
+/// its process lives; the in-memory helper DEX can't declare a receiver in the
+/// manifest that would wake a killed app. In the background, Android needs
+/// `ACCESS_BACKGROUND_LOCATION` (Android 10+) and delivers events every few
+/// minutes. Monitoring stops when the returned stream is dropped.
+///
+/// # Errors
+/// Returns a `LocationError` if the location permission has not been granted,
+/// location is unavailable, or Google Play services location is missing
+/// ([`LocationError::NotAvailable`]).
+pub fn monitor_region_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    center: &Location,
+    radius_m: f64,
+    id: &str,
+) -> Result<impl futures::Stream<Item = RegionEvent>, LocationError> {
+    let (token, receiver) = regions::insert(id);
+    // Removes the region again if starting fails
+    let guard = RegionGuard {
+        id: id.to_string(),
+        token,
+        stop: stop_region,
+    };
+
+    start_region(env, context, center, radius_m, id)?;
+    Ok(super::guarded_stream(receiver, guard))
+}
+
+// Async wrapper for the public API (requires runtime context)
+pub async fn monitor_region(
+    _center: &Location,
+    _radius_m: f64,
+    _id: &str,
+) -> Result<futures::stream::Empty<RegionEvent>, LocationError> {
+    // The application must call monitor_region_with_context directly
+    Err(LocationError::Unknown(
+        "Android: use monitor_region_with_context() with Context".into(),
+    ))
+}
+
+/// Stop monitoring a region started with [`monitor_region_with_context`].
+pub fn stop_monitoring(id: &str) {
+    if regions::remove(id, None) {
+        stop_region(id);
+    }
+}
+
+/// Monitor a circular region as a geofence using the Context.
+///
+/// Events go to [`LocationManager::geofence_events`](crate::LocationManager::geofence_events).
+/// Like [`monitor_region_with_context`], this uses a `GeofencingClient`
+/// geofence, so events arrive while the app process is alive, also in the
+/// background.
+///
+/// # Errors
+/// Returns a `LocationError` if the location permission has not been granted,
+/// location is unavailable, or Google Play services location is missing
+/// ([`LocationError::NotAvailable`]).
+pub fn add_geofence_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    id: &str,
+    center: &Location,
+    radius_m: f64,
+) -> Result<(), LocationError> {
+    let token = regions::insert_geofence(id);
+    start_region(env, context, center, radius_m, id).inspect_err(|_| {
+        regions::remove(id, Some(token));
+    })
+}
+
+// Async wrapper for the public API (requires runtime context)
+pub async fn add_geofence(
+    _id: &str,
+    _center: &Location,
+    _radius_m: f64,
+) -> Result<(), LocationError> {
+    // The application must call add_geofence_with_context directly
+    Err(LocationError::Unknown(
+        "Android: use add_geofence_with_context() with Context".into(),
+    ))
+}
+
+/// Stop monitoring a geofence added with [`add_geofence_with_context`].
+// Platforms without region monitoring return an error here
+#[allow(clippy::unnecessary_wraps)]
+pub fn remove_geofence(id: &str) -> Result<(), LocationError> {
+    if regions::remove_geofence(id) {
+        stop_region(id);
+    }
+    Ok(())
+}
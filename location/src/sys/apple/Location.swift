@@ -19,7 +19,8 @@ class LocationDelegate: NSObject, CLLocationManagerDelegate {
     }
 }
 
-func get_current_location() -> LocationResult {
+/// Returns nil if location can be accessed, otherwise the failure.
+private func checkAvailability() -> LocationResult? {
     // Check authorization
     let status = CLLocationManager.authorizationStatus()
     switch status {
@@ -35,6 +36,27 @@ func get_current_location() -> LocationResult {
     guard CLLocationManager.locationServicesEnabled() else {
         return .ServiceDisabled
     }
+    return nil
+}
+
+private func locationData(_ location: CLLocation) -> LocationData {
+    let timestampMs = UInt64(location.timestamp.timeIntervalSince1970 * 1000)
+    return LocationData(
+        latitude: location.coordinate.latitude,
+        longitude: location.coordinate.longitude,
+        altitude: location.altitude,
+        horizontal_accuracy: location.horizontalAccuracy,
+        vertical_accuracy: location.verticalAccuracy,
+        speed: location.speed,
+        course: location.course,
+        timestamp_ms: timestampMs
+    )
+}
+
+func get_current_location() -> LocationResult {
+    if let failure = checkAvailability() {
+        return failure
+    }
     
     let manager = CLLocationManager()
     let delegate = LocationDelegate()
@@ -57,15 +79,329 @@ func get_current_location() -> LocationResult {
         return .NotAvailable
     }
     
-    let timestampMs = UInt64(location.timestamp.timeIntervalSince1970 * 1000)
-    let data = LocationData(
-        latitude: location.coordinate.latitude,
-        longitude: location.coordinate.longitude,
-        altitude: location.altitude,
-        horizontal_accuracy: location.horizontalAccuracy,
-        vertical_accuracy: location.verticalAccuracy,
-        timestamp_ms: timestampMs
-    )
-    
-    return .Success(data)
+    return .Success(locationData(location))
+}
+
+// MARK: - Continuous Updates
+
+/// Delivers every update of a `CLLocationManager` to Rust.
+/// The manager lives on a dedicated thread whose run loop is spun until stopped,
+/// so updates arrive even when the host has no main run loop (e.g. CLI tools).
+private class LocationWatcher: NSObject, CLLocationManagerDelegate {
+    let id: UInt64
+    private let lock = NSLock()
+    private var stopped = false
+
+    init(id: UInt64) {
+        self.id = id
+    }
+
+    var isStopped: Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        return stopped
+    }
+
+    func stop() {
+        lock.lock()
+        stopped = true
+        lock.unlock()
+    }
+
+    func locationManager(_ manager: CLLocationManager, didUpdateLocations locations: [CLLocation]) {
+        for location in locations {
+            location_watch_on_update(id, locationData(location))
+        }
+    }
+
+    func locationManager(_ manager: CLLocationManager, didUpdateHeading newHeading: CLHeading) {
+        location_watch_on_heading(id, HeadingData(
+            magnetic_heading: newHeading.magneticHeading,
+            true_heading: newHeading.trueHeading,
+            accuracy: newHeading.headingAccuracy,
+            timestamp_ms: UInt64(newHeading.timestamp.timeIntervalSince1970 * 1000)
+        ))
+    }
+
+    func locationManager(_ manager: CLLocationManager, didFailWithError error: Error) {
+        // Core Location keeps trying after failing to get a fix or a heading
+        if let error = error as? CLError, error.code == .locationUnknown || error.code == .headingFailure {
+            return
+        }
+        let denied = (error as? CLError)?.code == .denied
+        location_watch_on_error(id, denied, error.localizedDescription)
+    }
+}
+
+private var watchers: [UInt64: LocationWatcher] = [:]
+private let watchersLock = NSLock()
+
+func location_watch_start(
+    id: UInt64,
+    accuracy_meters: Double,
+    navigation: Bool,
+    significant_changes: Bool
+) -> WatchResult {
+    if let failure = checkAvailability() {
+        switch failure {
+        case .ServiceDisabled:
+            return .ServiceDisabled
+        default:
+            return .PermissionDenied
+        }
+    }
+    if significant_changes && !CLLocationManager.significantLocationChangeMonitoringAvailable() {
+        return .NotAvailable
+    }
+
+    let watcher = LocationWatcher(id: id)
+    watchersLock.lock()
+    watchers[id] = watcher
+    watchersLock.unlock()
+
+    let thread = Thread {
+        let manager = CLLocationManager()
+        manager.delegate = watcher
+        if significant_changes {
+            manager.startMonitoringSignificantLocationChanges()
+        } else {
+            if navigation {
+                manager.desiredAccuracy = kCLLocationAccuracyBestForNavigation
+            } else {
+                manager.desiredAccuracy = accuracy_meters < 0 ? kCLLocationAccuracyBest : accuracy_meters
+            }
+            manager.startUpdatingLocation()
+        }
+
+        while !watcher.isStopped {
+            RunLoop.current.run(until: Date().addingTimeInterval(0.1))
+        }
+        if significant_changes {
+            manager.stopMonitoringSignificantLocationChanges()
+        } else {
+            manager.stopUpdatingLocation()
+        }
+        manager.delegate = nil
+    }
+    thread.start()
+    return .Started
+}
+
+func location_heading_start(id: UInt64, filter_degrees: Double) -> WatchResult {
+    #if os(iOS)
+    guard CLLocationManager.headingAvailable() else {
+        return .NotAvailable
+    }
+
+    let watcher = LocationWatcher(id: id)
+    watchersLock.lock()
+    watchers[id] = watcher
+    watchersLock.unlock()
+
+    // The true heading is only valid while location updates run too
+    let locating = checkAvailability() == nil
+
+    let thread = Thread {
+        let manager = CLLocationManager()
+        manager.delegate = watcher
+        manager.headingFilter = filter_degrees > 0 ? filter_degrees : kCLHeadingFilterNone
+        manager.startUpdatingHeading()
+        if locating {
+            manager.desiredAccuracy = kCLLocationAccuracyKilometer
+            manager.startUpdatingLocation()
+        }
+
+        while !watcher.isStopped {
+            RunLoop.current.run(until: Date().addingTimeInterval(0.1))
+        }
+        manager.stopUpdatingHeading()
+        if locating {
+            manager.stopUpdatingLocation()
+        }
+        manager.delegate = nil
+    }
+    thread.start()
+    return .Started
+    #else
+    // Macs have no magnetometer
+    return .NotAvailable
+    #endif
+}
+
+func location_watch_stop(id: UInt64) {
+    watchersLock.lock()
+    let watcher = watchers.removeValue(forKey: id)
+    watchersLock.unlock()
+    watcher?.stop()
+}
+
+// MARK: - Reverse Geocoding
+
+// Geocoders are kept alive until their request completes
+private var geocoders: [UInt64: CLGeocoder] = [:]
+private let geocodersLock = NSLock()
+
+func location_reverse_geocode(id: UInt64, latitude: Double, longitude: Double) {
+    let geocoder = CLGeocoder()
+    geocodersLock.lock()
+    geocoders[id] = geocoder
+    geocodersLock.unlock()
+
+    let location = CLLocation(latitude: latitude, longitude: longitude)
+    geocoder.reverseGeocodeLocation(location) { placemarks, error in
+        geocodersLock.lock()
+        geocoders.removeValue(forKey: id)
+        geocodersLock.unlock()
+
+        if let error = error {
+            switch (error as? CLError)?.code {
+            case .geocodeFoundNoResult:
+                location_geocode_on_complete(id, 0, "")
+            case .network:
+                location_geocode_on_complete(id, 1, error.localizedDescription)
+            default:
+                location_geocode_on_complete(id, 2, error.localizedDescription)
+            }
+            return
+        }
+
+        for placemark in placemarks ?? [] {
+            let street = [placemark.subThoroughfare, placemark.thoroughfare]
+                .compactMap { $0 }
+                .joined(separator: " ")
+            location_geocode_on_placemark(
+                id,
+                placemark.name ?? "",
+                street,
+                placemark.locality ?? "",
+                placemark.administrativeArea ?? "",
+                placemark.postalCode ?? "",
+                placemark.country ?? "",
+                placemark.isoCountryCode ?? ""
+            )
+        }
+        location_geocode_on_complete(id, 0, "")
+    }
+}
+
+// MARK: - Region Monitoring
+
+/// Core Location allows at most this many monitored regions per app.
+private let maxMonitoredRegions = 20
+
+/// Forwards entry and exit events of one region to Rust, along with the
+/// manager's most recent location.
+/// Region events are delivered to every `CLLocationManager` of the app, so
+/// each monitor filters on its own identifier.
+private class RegionMonitor: NSObject, CLLocationManagerDelegate {
+    let identifier: String
+    private let lock = NSLock()
+    private var stopped = false
+    private var keepsRegion = false
+
+    init(identifier: String) {
+        self.identifier = identifier
+    }
+
+    var isStopped: Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        return stopped
+    }
+
+    /// Whether the region was handed over to a newer monitor with the same identifier.
+    var isReplaced: Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        return keepsRegion
+    }
+
+    func stop(replaced: Bool) {
+        lock.lock()
+        stopped = true
+        keepsRegion = replaced
+        lock.unlock()
+    }
+
+    // The manager's location is the fix the crossing was detected from
+    func locationManager(_ manager: CLLocationManager, didEnterRegion region: CLRegion) {
+        if region.identifier == identifier {
+            location_region_on_event(identifier, true, manager.location.map(locationData))
+        }
+    }
+
+    func locationManager(_ manager: CLLocationManager, didExitRegion region: CLRegion) {
+        if region.identifier == identifier {
+            location_region_on_event(identifier, false, manager.location.map(locationData))
+        }
+    }
+
+    func locationManager(_ manager: CLLocationManager, monitoringDidFailFor region: CLRegion?, withError error: Error) {
+        if region?.identifier == identifier {
+            print("waterkit-location: Region monitoring failed: \(error)")
+        }
+    }
+}
+
+private var regionMonitors: [String: RegionMonitor] = [:]
+private let regionMonitorsLock = NSLock()
+
+func location_region_start(id: RustStr, latitude: Double, longitude: Double, radius_m: Double) -> RegionResult {
+    if let failure = checkAvailability() {
+        switch failure {
+        case .ServiceDisabled:
+            return .ServiceDisabled
+        default:
+            return .PermissionDenied
+        }
+    }
+
+    guard CLLocationManager.isMonitoringAvailable(for: CLCircularRegion.self) else {
+        return .NotAvailable
+    }
+
+    let identifier = id.toString()
+    let monitored = CLLocationManager().monitoredRegions
+    if monitored.count >= maxMonitoredRegions && !monitored.contains(where: { $0.identifier == identifier }) {
+        return .LimitReached
+    }
+
+    let monitor = RegionMonitor(identifier: identifier)
+    regionMonitorsLock.lock()
+    let previous = regionMonitors.updateValue(monitor, forKey: identifier)
+    regionMonitorsLock.unlock()
+    previous?.stop(replaced: true)
+
+    let thread = Thread {
+        let manager = CLLocationManager()
+        manager.delegate = monitor
+        let radius = min(radius_m, manager.maximumRegionMonitoringDistance)
+        let region = CLCircularRegion(
+            center: CLLocationCoordinate2D(latitude: latitude, longitude: longitude),
+            radius: radius,
+            identifier: identifier
+        )
+        region.notifyOnEntry = true
+        region.notifyOnExit = true
+        manager.startMonitoring(for: region)
+
+        while !monitor.isStopped {
+            RunLoop.current.run(until: Date().addingTimeInterval(0.1))
+        }
+        // A replacement monitors the same identifier; leave its region alone
+        if !monitor.isReplaced {
+            manager.stopMonitoring(for: region)
+        }
+        manager.delegate = nil
+    }
+    thread.start()
+    return .Started
+}
+
+func location_region_stop(id: RustStr) {
+    let identifier = id.toString()
+    regionMonitorsLock.lock()
+    let monitor = regionMonitors.removeValue(forKey: identifier)
+    regionMonitorsLock.unlock()
+    monitor?.stop(replaced: false)
 }
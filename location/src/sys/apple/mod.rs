@@ -1,6 +1,10 @@
 //! Apple platform (iOS/macOS) location implementation using swift-bridge.
 
-use crate::{Location, LocationError};
+use super::regions::{self, RegionGuard};
+use crate::{Accuracy, Heading, Location, LocationError, Placemark, RegionEvent, RegionEventKind};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 #[swift_bridge::bridge]
 mod ffi {
@@ -12,6 +16,16 @@ mod ffi {
         altitude: f64,
         horizontal_accuracy: f64,
         vertical_accuracy: f64,
+        speed: f64,
+        course: f64,
+        timestamp_ms: u64,
+    }
+
+    #[swift_bridge(swift_repr = "struct")]
+    struct HeadingData {
+        magnetic_heading: f64,
+        true_heading: f64,
+        accuracy: f64,
         timestamp_ms: u64,
     }
 
@@ -24,8 +38,169 @@ mod ffi {
         NotAvailable,
     }
 
+    // Result of starting continuous updates
+    enum WatchResult {
+        Started,
+        PermissionDenied,
+        ServiceDisabled,
+        NotAvailable,
+    }
+
+    // Result of starting region monitoring
+    enum RegionResult {
+        Started,
+        PermissionDenied,
+        ServiceDisabled,
+        NotAvailable,
+        LimitReached,
+    }
+
     extern "Swift" {
         fn get_current_location() -> LocationResult;
+        fn location_watch_start(
+            id: u64,
+            accuracy_meters: f64,
+            navigation: bool,
+            significant_changes: bool,
+        ) -> WatchResult;
+        fn location_heading_start(id: u64, filter_degrees: f64) -> WatchResult;
+        fn location_watch_stop(id: u64);
+        fn location_reverse_geocode(id: u64, latitude: f64, longitude: f64);
+        fn location_region_start(
+            id: &str,
+            latitude: f64,
+            longitude: f64,
+            radius_m: f64,
+        ) -> RegionResult;
+        fn location_region_stop(id: &str);
+    }
+
+    extern "Rust" {
+        fn location_watch_on_update(id: u64, data: LocationData);
+        fn location_watch_on_heading(id: u64, data: HeadingData);
+        fn location_watch_on_error(id: u64, denied: bool, message: String);
+        fn location_geocode_on_placemark(
+            id: u64,
+            name: String,
+            street: String,
+            city: String,
+            region: String,
+            postal_code: String,
+            country: String,
+            country_code: String,
+        );
+        // status 0: Finished, 1: NotAvailable, 2: Failed
+        fn location_geocode_on_complete(id: u64, status: u8, error: String);
+        fn location_region_on_event(id: String, entered: bool, location: Option<LocationData>);
+    }
+}
+
+/// Senders for active watches, keyed by watch id.
+static WATCHERS: Mutex<Option<HashMap<u64, WatchSender>>> = Mutex::new(None);
+static NEXT_WATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The sender of a location or a heading watch.
+enum WatchSender {
+    Location(async_channel::Sender<Result<Location, LocationError>>),
+    Heading(async_channel::Sender<Result<Heading, LocationError>>),
+}
+
+/// Run `send` with the sender of the watch registered under `id`, if it's
+/// still alive.
+fn with_watch(id: u64, send: impl FnOnce(&WatchSender)) {
+    if let Ok(watchers) = WATCHERS.lock()
+        && let Some(sender) = watchers.as_ref().and_then(|w| w.get(&id))
+    {
+        send(sender);
+    }
+}
+
+/// Register a watch, returning the guard that removes it again.
+fn insert_watch(sender: WatchSender) -> Result<WatchGuard, LocationError> {
+    let id = NEXT_WATCH_ID.fetch_add(1, Ordering::Relaxed);
+    WATCHERS
+        .lock()
+        .map_err(|e| LocationError::Unknown(e.to_string()))?
+        .get_or_insert_with(HashMap::new)
+        .insert(id, sender);
+    Ok(WatchGuard(id))
+}
+
+fn location_watch_on_update(id: u64, data: ffi::LocationData) {
+    with_watch(id, |sender| {
+        // Heading watches run location updates too, for the true heading
+        if let WatchSender::Location(sender) = sender {
+            let _ = sender.try_send(Ok(to_location(&data)));
+        }
+    });
+}
+
+fn location_watch_on_heading(id: u64, data: ffi::HeadingData) {
+    with_watch(id, |sender| {
+        if let WatchSender::Heading(sender) = sender {
+            let _ = sender.try_send(Ok(Heading {
+                magnetic_heading: data.magnetic_heading,
+                true_heading: data.true_heading,
+                accuracy: data.accuracy,
+                timestamp: data.timestamp_ms,
+            }));
+        }
+    });
+}
+
+fn location_watch_on_error(id: u64, denied: bool, message: String) {
+    let error = if denied {
+        LocationError::PermissionDenied
+    } else {
+        LocationError::Unknown(message)
+    };
+    with_watch(id, |sender| match sender {
+        WatchSender::Location(sender) => {
+            let _ = sender.try_send(Err(error));
+        }
+        WatchSender::Heading(sender) => {
+            let _ = sender.try_send(Err(error));
+        }
+    });
+}
+
+/// Stops the `CLLocationManager` updates of a watch when dropped.
+struct WatchGuard(u64);
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        ffi::location_watch_stop(self.0);
+        if let Ok(mut watchers) = WATCHERS.lock()
+            && let Some(watchers) = watchers.as_mut()
+        {
+            watchers.remove(&self.0);
+        }
+    }
+}
+
+fn to_location(data: &ffi::LocationData) -> Location {
+    Location {
+        latitude: data.latitude,
+        longitude: data.longitude,
+        altitude: if data.altitude.is_nan() {
+            None
+        } else {
+            Some(data.altitude)
+        },
+        horizontal_accuracy: if data.horizontal_accuracy < 0.0 {
+            None
+        } else {
+            Some(data.horizontal_accuracy)
+        },
+        vertical_accuracy: if data.vertical_accuracy < 0.0 {
+            None
+        } else {
+            Some(data.vertical_accuracy)
+        },
+        // Core Location reports invalid speed and course as negative values
+        speed_mps: (data.speed >= 0.0).then_some(data.speed),
+        course_degrees: (data.course >= 0.0).then_some(data.course),
+        timestamp: data.timestamp_ms,
     }
 }
 
@@ -35,29 +210,211 @@ mod ffi {
 /// Returns a `LocationError` if the location cannot be retrieved.
 pub async fn get_location() -> Result<Location, LocationError> {
     match ffi::get_current_location() {
-        ffi::LocationResult::Success(data) => Ok(Location {
-            latitude: data.latitude,
-            longitude: data.longitude,
-            altitude: if data.altitude.is_nan() {
-                None
-            } else {
-                Some(data.altitude)
-            },
-            horizontal_accuracy: if data.horizontal_accuracy < 0.0 {
-                None
-            } else {
-                Some(data.horizontal_accuracy)
-            },
-            vertical_accuracy: if data.vertical_accuracy < 0.0 {
-                None
-            } else {
-                Some(data.vertical_accuracy)
-            },
-            timestamp: data.timestamp_ms,
-        }),
+        ffi::LocationResult::Success(data) => Ok(to_location(&data)),
         ffi::LocationResult::PermissionDenied => Err(LocationError::PermissionDenied),
         ffi::LocationResult::ServiceDisabled => Err(LocationError::ServiceDisabled),
         ffi::LocationResult::Timeout => Err(LocationError::Timeout),
         ffi::LocationResult::NotAvailable => Err(LocationError::NotAvailable),
     }
 }
+
+/// Start continuous location updates using `CLLocationManager.startUpdatingLocation`,
+/// or `startMonitoringSignificantLocationChanges` for [`Accuracy::Passive`].
+///
+/// # Errors
+/// Returns a `LocationError` if updates cannot be started.
+pub async fn watch_location(
+    accuracy: Accuracy,
+) -> Result<impl futures::Stream<Item = Result<Location, LocationError>>, LocationError> {
+    let (sender, receiver) = async_channel::unbounded();
+    // Removes the sender again if starting fails
+    let guard = insert_watch(WatchSender::Location(sender))?;
+
+    match ffi::location_watch_start(
+        guard.0,
+        accuracy.meters().unwrap_or(-1.0),
+        accuracy == Accuracy::Navigation,
+        accuracy == Accuracy::Passive,
+    ) {
+        ffi::WatchResult::Started => Ok(super::guarded_stream(receiver, guard)),
+        ffi::WatchResult::PermissionDenied => Err(LocationError::PermissionDenied),
+        ffi::WatchResult::ServiceDisabled => Err(LocationError::ServiceDisabled),
+        ffi::WatchResult::NotAvailable => Err(LocationError::NotAvailable),
+    }
+}
+
+/// Start heading updates using `CLLocationManager.startUpdatingHeading`.
+///
+/// # Errors
+/// Returns `LocationError::NotAvailable` if the device has no compass.
+pub fn watch_heading(
+    filter_degrees: f64,
+) -> Result<impl futures::Stream<Item = Result<Heading, LocationError>>, LocationError> {
+    let (sender, receiver) = async_channel::unbounded();
+    // Removes the sender again if starting fails
+    let guard = insert_watch(WatchSender::Heading(sender))?;
+
+    match ffi::location_heading_start(guard.0, filter_degrees) {
+        ffi::WatchResult::Started => Ok(super::guarded_stream(receiver, guard)),
+        ffi::WatchResult::PermissionDenied => Err(LocationError::PermissionDenied),
+        ffi::WatchResult::ServiceDisabled => Err(LocationError::ServiceDisabled),
+        ffi::WatchResult::NotAvailable => Err(LocationError::NotAvailable),
+    }
+}
+
+/// A pending `CLGeocoder` request.
+struct GeocodeRequest {
+    placemarks: Vec<Placemark>,
+    sender: async_channel::Sender<Result<Vec<Placemark>, LocationError>>,
+}
+
+/// Pending reverse geocoding requests, keyed by request id.
+static GEOCODES: Mutex<Option<HashMap<u64, GeocodeRequest>>> = Mutex::new(None);
+static NEXT_GEOCODE_ID: AtomicU64 = AtomicU64::new(1);
+
+#[allow(clippy::too_many_arguments, clippy::needless_pass_by_value)]
+fn location_geocode_on_placemark(
+    id: u64,
+    name: String,
+    street: String,
+    city: String,
+    region: String,
+    postal_code: String,
+    country: String,
+    country_code: String,
+) {
+    // Swift passes missing fields as empty strings
+    let field = |value: String| (!value.is_empty()).then_some(value);
+    if let Ok(mut geocodes) = GEOCODES.lock()
+        && let Some(request) = geocodes.as_mut().and_then(|g| g.get_mut(&id))
+    {
+        request.placemarks.push(Placemark {
+            name: field(name),
+            street: field(street),
+            city: field(city),
+            region: field(region),
+            postal_code: field(postal_code),
+            country: field(country),
+            country_code: field(country_code),
+        });
+    }
+}
+
+fn location_geocode_on_complete(id: u64, status: u8, error: String) {
+    let Some(request) = GEOCODES
+        .lock()
+        .ok()
+        .and_then(|mut g| g.as_mut().and_then(|g| g.remove(&id)))
+    else {
+        return;
+    };
+
+    let result = match status {
+        0 => Ok(request.placemarks),
+        1 => Err(LocationError::NotAvailable),
+        _ => Err(LocationError::Unknown(error)),
+    };
+    let _ = request.sender.try_send(result);
+}
+
+/// Look up addresses for a location using `CLGeocoder`.
+///
+/// The completion handler runs on the main thread, so the app's main run
+/// loop must be running.
+///
+/// # Errors
+/// Returns a `LocationError` if the geocoder can't be reached or fails.
+pub async fn reverse_geocode(location: &Location) -> Result<Vec<Placemark>, LocationError> {
+    let id = NEXT_GEOCODE_ID.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = async_channel::bounded(1);
+    GEOCODES
+        .lock()
+        .map_err(|e| LocationError::Unknown(e.to_string()))?
+        .get_or_insert_with(HashMap::new)
+        .insert(
+            id,
+            GeocodeRequest {
+                placemarks: Vec::new(),
+                sender,
+            },
+        );
+
+    ffi::location_reverse_geocode(id, location.latitude, location.longitude);
+
+    receiver
+        .recv()
+        .await
+        .map_err(|_| LocationError::Unknown("Geocoder dropped the request".into()))?
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn location_region_on_event(id: String, entered: bool, location: Option<ffi::LocationData>) {
+    let kind = if entered {
+        RegionEventKind::Enter
+    } else {
+        RegionEventKind::Exit
+    };
+    regions::dispatch(&id, kind, location.as_ref().map(to_location));
+}
+
+/// Start `CLCircularRegion` monitoring of region `id`.
+fn start_region(center: &Location, radius_m: f64, id: &str) -> Result<(), LocationError> {
+    match ffi::location_region_start(id, center.latitude, center.longitude, radius_m) {
+        ffi::RegionResult::Started => Ok(()),
+        ffi::RegionResult::PermissionDenied => Err(LocationError::PermissionDenied),
+        ffi::RegionResult::ServiceDisabled => Err(LocationError::ServiceDisabled),
+        ffi::RegionResult::NotAvailable => Err(LocationError::NotAvailable),
+        ffi::RegionResult::LimitReached => Err(LocationError::Unknown(
+            "too many regions are monitored already".into(),
+        )),
+    }
+}
+
+/// Monitor a circular region using `CLLocationManager.startMonitoring(for:)`.
+///
+/// # Errors
+/// Returns a `LocationError` if monitoring cannot be started.
+pub async fn monitor_region(
+    center: &Location,
+    radius_m: f64,
+    id: &str,
+) -> Result<impl futures::Stream<Item = RegionEvent>, LocationError> {
+    let (token, receiver) = regions::insert(id);
+    // Removes the region again if starting fails
+    let guard = RegionGuard {
+        id: id.to_string(),
+        token,
+        stop: ffi::location_region_stop,
+    };
+
+    start_region(center, radius_m, id)?;
+    Ok(super::guarded_stream(receiver, guard))
+}
+
+/// Stop monitoring a region started with [`monitor_region`].
+pub fn stop_monitoring(id: &str) {
+    if regions::remove(id, None) {
+        ffi::location_region_stop(id);
+    }
+}
+
+/// Monitor a circular region as a geofence using `CLLocationManager.startMonitoring(for:)`.
+///
+/// # Errors
+/// Returns a `LocationError` if monitoring cannot be started.
+pub async fn add_geofence(id: &str, center: &Location, radius_m: f64) -> Result<(), LocationError> {
+    let token = regions::insert_geofence(id);
+    start_region(center, radius_m, id).inspect_err(|_| {
+        regions::remove(id, Some(token));
+    })
+}
+
+/// Stop monitoring a geofence added with [`add_geofence`].
+// Platforms without region monitoring return an error here
+#[allow(clippy::unnecessary_wraps)]
+pub fn remove_geofence(id: &str) -> Result<(), LocationError> {
+    if regions::remove_geofence(id) {
+        ffi::location_region_stop(id);
+    }
+    Ok(())
+}
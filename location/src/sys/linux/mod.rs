@@ -1,59 +1,17 @@
-//! Linux location implementation using GeoClue2 D-Bus service.
+//! Linux location implementation using the `GeoClue2` D-Bus service.
 
-use crate::{Location, LocationError};
-
-pub(crate) async fn get_location() -> Result<Location, LocationError> {
-    use zbus::Connection;
+use crate::{Accuracy, Location, LocationError};
+use futures::StreamExt;
+use zbus::Connection;
+use zbus::zvariant::OwnedObjectPath;
 
+pub async fn get_location() -> Result<Location, LocationError> {
     // Connect to the system bus
     let connection = Connection::system()
         .await
         .map_err(|e| LocationError::Unknown(format!("D-Bus connection failed: {e}")))?;
 
-    // Call GeoClue2 Manager to get a client
-    let reply: (zbus::zvariant::OwnedObjectPath,) = connection
-        .call_method(
-            Some("org.freedesktop.GeoClue2"),
-            "/org/freedesktop/GeoClue2/Manager",
-            Some("org.freedesktop.GeoClue2.Manager"),
-            "GetClient",
-            &(),
-        )
-        .await
-        .map_err(|e| LocationError::Unknown(format!("GeoClue2 not available: {e}")))?
-        .body()
-        .deserialize()
-        .map_err(|e| LocationError::Unknown(format!("Failed to parse response: {e}")))?;
-
-    let client_path = reply.0;
-
-    // Set the desktop ID (required by GeoClue2)
-    connection
-        .call_method(
-            Some("org.freedesktop.GeoClue2"),
-            client_path.as_str(),
-            Some("org.freedesktop.DBus.Properties"),
-            "Set",
-            &(
-                "org.freedesktop.GeoClue2.Client",
-                "DesktopId",
-                zbus::zvariant::Value::from("waterkit"),
-            ),
-        )
-        .await
-        .map_err(|e| LocationError::Unknown(format!("Failed to set desktop ID: {e}")))?;
-
-    // Start the client
-    connection
-        .call_method(
-            Some("org.freedesktop.GeoClue2"),
-            client_path.as_str(),
-            Some("org.freedesktop.GeoClue2.Client"),
-            "Start",
-            &(),
-        )
-        .await
-        .map_err(|e| LocationError::Unknown(format!("Failed to start GeoClue client: {e}")))?;
+    let client_path = start_client(&connection, Accuracy::Best).await?;
 
     // Get the location object path
     let location_reply: zbus::zvariant::OwnedValue = connection
@@ -70,13 +28,34 @@ pub(crate) async fn get_location() -> Result<Location, LocationError> {
         .deserialize()
         .map_err(|e| LocationError::Unknown(format!("Failed to parse location path: {e}")))?;
 
-    let location_path: zbus::zvariant::OwnedObjectPath = location_reply
+    let location_path: OwnedObjectPath = location_reply
         .downcast_ref::<zbus::zvariant::ObjectPath>()
         .map(|p| p.to_owned().into())
-        .ok_or_else(|| LocationError::NotAvailable)?;
+        .map_err(|_| LocationError::NotAvailable)?;
+
+    let location = read_location(&connection, &location_path).await;
+
+    // Stop the client
+    let _ = connection
+        .call_method(
+            Some("org.freedesktop.GeoClue2"),
+            client_path.as_str(),
+            Some("org.freedesktop.GeoClue2.Client"),
+            "Stop",
+            &(),
+        )
+        .await;
 
+    location
+}
+
+/// Read the properties of a `GeoClue2` location object.
+async fn read_location(
+    connection: &Connection,
+    location_path: &OwnedObjectPath,
+) -> Result<Location, LocationError> {
     // Get latitude and longitude from the location object
-    let get_property = |prop: &str| async {
+    let get_property = |prop: &'static str| async move {
         let reply: zbus::zvariant::OwnedValue = connection
             .call_method(
                 Some("org.freedesktop.GeoClue2"),
@@ -88,7 +67,7 @@ pub(crate) async fn get_location() -> Result<Location, LocationError> {
             .await?
             .body()
             .deserialize()?;
-        Ok::<f64, zbus::Error>(reply.downcast_ref::<f64>().copied().unwrap_or(0.0))
+        Ok::<f64, zbus::Error>(reply.downcast_ref::<f64>()?)
     };
 
     let latitude = get_property("Latitude")
@@ -99,17 +78,9 @@ pub(crate) async fn get_location() -> Result<Location, LocationError> {
         .map_err(|e| LocationError::Unknown(format!("Failed to get longitude: {e}")))?;
     let altitude = get_property("Altitude").await.ok();
     let accuracy = get_property("Accuracy").await.ok();
-
-    // Stop the client
-    let _ = connection
-        .call_method(
-            Some("org.freedesktop.GeoClue2"),
-            client_path.as_str(),
-            Some("org.freedesktop.GeoClue2.Client"),
-            "Stop",
-            &(),
-        )
-        .await;
+    // GeoClue reports unknown speed and heading as -1
+    let speed = get_property("Speed").await.ok().filter(|v| *v >= 0.0);
+    let heading = get_property("Heading").await.ok().filter(|v| *v >= 0.0);
 
     Ok(Location {
         latitude,
@@ -117,9 +88,107 @@ pub(crate) async fn get_location() -> Result<Location, LocationError> {
         altitude,
         horizontal_accuracy: accuracy,
         vertical_accuracy: None,
+        speed_mps: speed,
+        course_degrees: heading,
         timestamp: std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_millis() as u64)
-            .unwrap_or(0),
+            .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX)),
     })
 }
+
+/// Start a `GeoClue2` client that tracks the location at the given accuracy.
+async fn start_client(
+    connection: &Connection,
+    accuracy: Accuracy,
+) -> Result<OwnedObjectPath, LocationError> {
+    let reply: (OwnedObjectPath,) = connection
+        .call_method(
+            Some("org.freedesktop.GeoClue2"),
+            "/org/freedesktop/GeoClue2/Manager",
+            Some("org.freedesktop.GeoClue2.Manager"),
+            "GetClient",
+            &(),
+        )
+        .await
+        .map_err(|e| LocationError::Unknown(format!("GeoClue2 not available: {e}")))?
+        .body()
+        .deserialize()
+        .map_err(|e| LocationError::Unknown(format!("Failed to parse response: {e}")))?;
+
+    let client_path = reply.0;
+
+    // GClueAccuracyLevel: 4 = city, 5 = neighborhood, 6 = street, 8 = exact
+    let level: u32 = match accuracy {
+        Accuracy::Navigation | Accuracy::Best => 8,
+        Accuracy::High => 6,
+        Accuracy::Balanced => 5,
+        // GeoClue has no passive level, so the cheapest one is closest
+        Accuracy::Low | Accuracy::Passive => 4,
+    };
+
+    for (property, value) in [
+        ("DesktopId", zbus::zvariant::Value::from("waterkit")),
+        ("RequestedAccuracyLevel", zbus::zvariant::Value::from(level)),
+    ] {
+        connection
+            .call_method(
+                Some("org.freedesktop.GeoClue2"),
+                client_path.as_str(),
+                Some("org.freedesktop.DBus.Properties"),
+                "Set",
+                &("org.freedesktop.GeoClue2.Client", property, value),
+            )
+            .await
+            .map_err(|e| LocationError::Unknown(format!("Failed to set {property}: {e}")))?;
+    }
+
+    connection
+        .call_method(
+            Some("org.freedesktop.GeoClue2"),
+            client_path.as_str(),
+            Some("org.freedesktop.GeoClue2.Client"),
+            "Start",
+            &(),
+        )
+        .await
+        .map_err(|e| LocationError::Unknown(format!("Failed to start GeoClue client: {e}")))?;
+
+    Ok(client_path)
+}
+
+pub async fn watch_location(
+    accuracy: Accuracy,
+) -> Result<impl futures::Stream<Item = Result<Location, LocationError>>, LocationError> {
+    // A dedicated connection: GeoClue drops the client once it disconnects,
+    // which happens when the returned stream (the last holder) is dropped.
+    let connection = Connection::system()
+        .await
+        .map_err(|e| LocationError::Unknown(format!("D-Bus connection failed: {e}")))?;
+
+    let client_path = start_client(&connection, accuracy).await?;
+
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.GeoClue2",
+        client_path,
+        "org.freedesktop.GeoClue2.Client",
+    )
+    .await
+    .map_err(|e| LocationError::Unknown(format!("Failed to create client proxy: {e}")))?;
+
+    let updates = proxy
+        .receive_signal("LocationUpdated")
+        .await
+        .map_err(|e| LocationError::Unknown(format!("Failed to subscribe to updates: {e}")))?;
+
+    Ok(updates.then(move |message| {
+        let connection = connection.clone();
+        async move {
+            let (_old, new): (OwnedObjectPath, OwnedObjectPath) =
+                message.body().deserialize().map_err(|e| {
+                    LocationError::Unknown(format!("Failed to parse LocationUpdated: {e}"))
+                })?;
+            read_location(&connection, &new).await
+        }
+    }))
+}
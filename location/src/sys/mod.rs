@@ -13,19 +13,129 @@ mod windows;
 #[cfg(target_os = "linux")]
 mod linux;
 
-// Re-export platform implementations
+#[cfg(any(target_os = "ios", target_os = "macos", target_os = "android"))]
+mod regions;
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+mod nominatim;
+
 // Re-export platform implementations
 #[cfg(any(target_os = "ios", target_os = "macos"))]
-pub use apple::get_location;
+pub use apple::{
+    add_geofence, get_location, monitor_region, remove_geofence, reverse_geocode, stop_monitoring,
+    watch_heading, watch_location,
+};
 
 #[cfg(target_os = "android")]
-pub use android::get_location;
+pub use android::{
+    add_geofence, get_location, monitor_region, remove_geofence, reverse_geocode, stop_monitoring,
+    watch_heading, watch_location,
+};
 
 #[cfg(target_os = "windows")]
-pub use windows::get_location;
+pub use windows::{get_location, watch_location};
 
 #[cfg(target_os = "linux")]
-pub use linux::get_location;
+pub use linux::{get_location, watch_location};
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub use nominatim::reverse_geocode;
+
+/// Turn a channel of updates into a stream that keeps `guard` alive.
+///
+/// Platform watchers stop their updates when the guard is dropped, which
+/// happens when the stream is dropped.
+#[cfg(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows"
+))]
+pub fn guarded_stream<T, G>(
+    receiver: async_channel::Receiver<T>,
+    guard: G,
+) -> impl futures::Stream<Item = T> {
+    futures::stream::unfold((receiver, guard), |(receiver, guard)| async move {
+        let item = receiver.recv().await.ok()?;
+        Some((item, (receiver, guard)))
+    })
+}
+
+// Region monitoring is only available on mobile platforms
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+#[allow(clippy::unused_async)]
+pub async fn monitor_region(
+    _center: &crate::Location,
+    _radius_m: f64,
+    _id: &str,
+) -> Result<futures::stream::Empty<crate::RegionEvent>, crate::LocationError> {
+    Err(crate::LocationError::NotAvailable)
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+pub const fn stop_monitoring(_id: &str) {}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+#[allow(clippy::unused_async)]
+pub async fn add_geofence(
+    _id: &str,
+    _center: &crate::Location,
+    _radius_m: f64,
+) -> Result<(), crate::LocationError> {
+    Err(crate::LocationError::NotAvailable)
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+pub const fn remove_geofence(_id: &str) -> Result<(), crate::LocationError> {
+    Err(crate::LocationError::NotAvailable)
+}
+
+// Compass headings are only available on mobile platforms
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+pub const fn watch_heading(
+    _filter_degrees: f64,
+) -> Result<
+    futures::stream::Empty<Result<crate::Heading, crate::LocationError>>,
+    crate::LocationError,
+> {
+    Err(crate::LocationError::NotAvailable)
+}
+
+/// Receive the events of every geofence.
+#[cfg(any(target_os = "ios", target_os = "macos", target_os = "android"))]
+pub fn geofence_events() -> impl futures::Stream<Item = crate::GeofenceEvent> {
+    regions::listen()
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+pub fn geofence_events() -> futures::stream::Empty<crate::GeofenceEvent> {
+    futures::stream::empty()
+}
+
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "linux"
+)))]
+pub async fn reverse_geocode(
+    _location: &crate::Location,
+) -> Result<Vec<crate::Placemark>, crate::LocationError> {
+    Err(crate::LocationError::NotAvailable)
+}
+
+// Fallback for unsupported platforms
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "linux"
+)))]
+pub async fn get_location() -> Result<crate::Location, crate::LocationError> {
+    Err(crate::LocationError::NotAvailable)
+}
 
 // Fallback for unsupported platforms
 #[cfg(not(any(
@@ -35,6 +145,11 @@ pub use linux::get_location;
     target_os = "windows",
     target_os = "linux"
 )))]
-pub(crate) async fn get_location() -> Result<crate::Location, crate::LocationError> {
+pub async fn watch_location(
+    _accuracy: crate::Accuracy,
+) -> Result<
+    futures::stream::Empty<Result<crate::Location, crate::LocationError>>,
+    crate::LocationError,
+> {
     Err(crate::LocationError::NotAvailable)
 }
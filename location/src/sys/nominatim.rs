@@ -0,0 +1,112 @@
+//! Reverse geocoding through the `OpenStreetMap` Nominatim service, for
+//! desktop platforms without a system geocoder.
+//!
+//! The usage policy of the public instance asks for an identifying
+//! `User-Agent` and at most one request per second; see
+//! <https://operations.osmfoundation.org/policies/nominatim/>.
+
+use crate::{Location, LocationError, Placemark};
+use serde_json::Value;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use zenwave::Client;
+use zenwave::header::USER_AGENT;
+
+const ENDPOINT: &str = "https://nominatim.openstreetmap.org/reverse";
+
+/// Identifies this library to Nominatim, as its usage policy requires.
+const AGENT: &str = concat!(
+    "waterkit-location/",
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")"
+);
+
+/// The shortest time between two requests the usage policy allows.
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When the next request may be sent.
+static NEXT_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Reserve the next request slot, keeping requests from the whole process
+/// at least [`MIN_INTERVAL`] apart, and return how long to wait for it.
+fn reserve_slot() -> Duration {
+    let now = Instant::now();
+    let mut next = NEXT_REQUEST.lock().unwrap_or_else(PoisonError::into_inner);
+    let slot = next.map_or(now, |next| next.max(now));
+    *next = Some(slot + MIN_INTERVAL);
+    drop(next);
+    slot - now
+}
+
+/// Look up the address of `location`.
+///
+/// Nominatim returns at most one address, so the result has zero or one
+/// placemarks. Concurrent lookups are queued to keep to the rate limit.
+///
+/// # Errors
+/// Returns `LocationError::NotAvailable` if Nominatim can't be reached, and
+/// `LocationError::Unknown` if it responds with an error status, such as
+/// 429 or 403 when it throttles or blocks this client.
+#[allow(clippy::future_not_send)]
+pub async fn reverse_geocode(location: &Location) -> Result<Vec<Placemark>, LocationError> {
+    let url = format!(
+        "{ENDPOINT}?format=jsonv2&addressdetails=1&lat={}&lon={}",
+        location.latitude, location.longitude
+    );
+    futures_timer::Delay::new(reserve_slot()).await;
+    let mut client = zenwave::client();
+    let response = client
+        .get(url.as_str())
+        .header(USER_AGENT, AGENT)
+        .await
+        .map_err(|_| LocationError::NotAvailable)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(LocationError::Unknown(format!(
+            "Nominatim responded with {status}"
+        )));
+    }
+    let bytes = response
+        .into_body()
+        .into_bytes()
+        .await
+        .map_err(|_| LocationError::NotAvailable)?;
+    let json: Value =
+        serde_json::from_slice(&bytes).map_err(|e| LocationError::Unknown(e.to_string()))?;
+
+    // Places in the sea or outside any country come back as an error
+    if json.get("error").is_some() {
+        return Ok(Vec::new());
+    }
+    Ok(vec![placemark(&json)])
+}
+
+fn placemark(json: &Value) -> Placemark {
+    let address = &json["address"];
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| address[*key].as_str())
+            .map(str::to_string)
+    };
+
+    let street = [field(&["house_number"]), field(&["road", "pedestrian"])]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Placemark {
+        name: json["name"]
+            .as_str()
+            .filter(|name| !name.is_empty())
+            .map(str::to_string),
+        street: Some(street).filter(|street| !street.is_empty()),
+        city: field(&["city", "town", "village", "hamlet"]),
+        region: field(&["state", "province", "region"]),
+        postal_code: field(&["postcode"]),
+        country: field(&["country"]),
+        country_code: field(&["country_code"]).map(|code| code.to_uppercase()),
+    }
+}
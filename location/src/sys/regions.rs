@@ -0,0 +1,145 @@
+//! Bookkeeping for monitored regions shared by the platform backends.
+//!
+//! Each region id maps to where its events go: the stream returned by
+//! `monitor_region`, or the listeners of `geofence_events` for geofences.
+//! A token tells apart successive monitors started with the same id, so
+//! dropping a stale stream doesn't stop the region that replaced it.
+
+use crate::{GeofenceEvent, GeofenceTransition, Location, RegionEvent, RegionEventKind};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Where the events of a region are delivered.
+enum Sink {
+    /// The stream of a region started with `monitor_region`.
+    Stream(async_channel::Sender<RegionEvent>),
+    /// Every receiver returned by [`listen`].
+    Geofence,
+}
+
+static REGIONS: Mutex<Option<HashMap<String, (u64, Sink)>>> = Mutex::new(None);
+static GEOFENCE_LISTENERS: Mutex<Vec<async_channel::Sender<GeofenceEvent>>> =
+    Mutex::new(Vec::new());
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+/// Register a region under `id`, replacing any previous monitor with that id.
+fn register(id: &str, sink: Sink) -> u64 {
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut regions) = REGIONS.lock() {
+        regions
+            .get_or_insert_with(HashMap::new)
+            .insert(id.to_string(), (token, sink));
+    }
+    token
+}
+
+/// Register a region with its own stream.
+pub fn insert(id: &str) -> (u64, async_channel::Receiver<RegionEvent>) {
+    let (sender, receiver) = async_channel::unbounded();
+    (register(id, Sink::Stream(sender)), receiver)
+}
+
+/// Register a geofence, whose events go to the geofence listeners.
+pub fn insert_geofence(id: &str) -> u64 {
+    register(id, Sink::Geofence)
+}
+
+/// Receive the events of every geofence from now on.
+pub fn listen() -> async_channel::Receiver<GeofenceEvent> {
+    let (sender, receiver) = async_channel::unbounded();
+    if let Ok(mut listeners) = GEOFENCE_LISTENERS.lock() {
+        listeners.push(sender);
+    }
+    receiver
+}
+
+/// Deliver an event of region `id`, with the location it was detected at if
+/// the platform reported one.
+pub fn dispatch(id: &str, kind: RegionEventKind, location: Option<Location>) {
+    let Ok(regions) = REGIONS.lock() else {
+        return;
+    };
+    match regions.as_ref().and_then(|r| r.get(id)) {
+        Some((_, Sink::Stream(sender))) => {
+            let _ = sender.try_send(RegionEvent {
+                id: id.to_string(),
+                kind,
+            });
+        }
+        Some((_, Sink::Geofence)) => {
+            // Crossings are detected from a fix, which the platforms report
+            let Some(location) = location else {
+                return;
+            };
+            let event = GeofenceEvent {
+                fence_id: id.to_string(),
+                event: match kind {
+                    RegionEventKind::Enter => GeofenceTransition::Entered,
+                    RegionEventKind::Exit => GeofenceTransition::Exited,
+                },
+                location,
+            };
+            if let Ok(mut listeners) = GEOFENCE_LISTENERS.lock() {
+                // Dropped receivers close their channel
+                listeners.retain(|sender| sender.try_send(event.clone()).is_ok());
+            }
+        }
+        None => {}
+    }
+}
+
+/// Remove region `id`, ending its stream.
+///
+/// With a token, only removes the region if it is still that monitor.
+/// Returns whether a region was removed.
+pub fn remove(id: &str, token: Option<u64>) -> bool {
+    let Ok(mut regions) = REGIONS.lock() else {
+        return false;
+    };
+    let Some(regions) = regions.as_mut() else {
+        return false;
+    };
+    match regions.get(id) {
+        Some((current, _)) if token.is_none_or(|token| token == *current) => {
+            regions.remove(id);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Remove geofence `id`, leaving a region streamed under that id alone.
+///
+/// Returns whether a geofence was removed.
+pub fn remove_geofence(id: &str) -> bool {
+    let Ok(mut regions) = REGIONS.lock() else {
+        return false;
+    };
+    let Some(regions) = regions.as_mut() else {
+        return false;
+    };
+    match regions.get(id) {
+        Some((_, Sink::Geofence)) => {
+            regions.remove(id);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Stops monitoring a region when its stream is dropped.
+pub struct RegionGuard {
+    pub id: String,
+    pub token: u64,
+    /// Platform function that stops monitoring the region.
+    pub stop: fn(&str),
+}
+
+impl Drop for RegionGuard {
+    fn drop(&mut self) {
+        if remove(&self.id, Some(self.token)) {
+            (self.stop)(&self.id);
+        }
+    }
+}
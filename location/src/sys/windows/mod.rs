@@ -1,22 +1,13 @@
 //! Windows location implementation using WinRT Geolocator.
 
-use crate::{Location, LocationError};
+use crate::{Accuracy, Location, LocationError};
+use windows::Devices::Geolocation::{
+    GeolocationAccessStatus, Geolocator, Geoposition, PositionAccuracy, PositionStatus,
+};
+use windows::Foundation::TypedEventHandler;
 
-pub(crate) async fn get_location() -> Result<Location, LocationError> {
-    use windows::Devices::Geolocation::{GeolocationAccessStatus, Geolocator};
-
-    // Request access (this also serves as permission check on Windows)
-    let access = Geolocator::RequestAccessAsync()
-        .map_err(|e| LocationError::Unknown(e.message().to_string()))?
-        .get()
-        .map_err(|e| LocationError::Unknown(e.message().to_string()))?;
-
-    match access {
-        GeolocationAccessStatus::Denied => return Err(LocationError::PermissionDenied),
-        GeolocationAccessStatus::Unspecified => return Err(LocationError::NotAvailable),
-        GeolocationAccessStatus::Allowed => {}
-        _ => return Err(LocationError::NotAvailable),
-    }
+pub async fn get_location() -> Result<Location, LocationError> {
+    request_access()?;
 
     let geolocator =
         Geolocator::new().map_err(|e| LocationError::Unknown(e.message().to_string()))?;
@@ -27,6 +18,24 @@ pub(crate) async fn get_location() -> Result<Location, LocationError> {
         .get()
         .map_err(|e| LocationError::Unknown(e.message().to_string()))?;
 
+    to_location(&position)
+}
+
+/// Request access; this also serves as permission check on Windows.
+fn request_access() -> Result<(), LocationError> {
+    let access = Geolocator::RequestAccessAsync()
+        .map_err(|e| LocationError::Unknown(e.message().to_string()))?
+        .get()
+        .map_err(|e| LocationError::Unknown(e.message().to_string()))?;
+
+    match access {
+        GeolocationAccessStatus::Denied => Err(LocationError::PermissionDenied),
+        GeolocationAccessStatus::Allowed => Ok(()),
+        _ => Err(LocationError::NotAvailable),
+    }
+}
+
+fn to_location(position: &Geoposition) -> Result<Location, LocationError> {
     let coord = position
         .Coordinate()
         .map_err(|e| LocationError::Unknown(e.message().to_string()))?;
@@ -46,6 +55,13 @@ pub(crate) async fn get_location() -> Result<Location, LocationError> {
         .unwrap_or(0) as u64;
 
     let accuracy = coord.Accuracy().ok().map(|a| a.GetDouble().unwrap_or(0.0));
+    // Null when the source can't measure them; heading is NaN while stationary
+    let speed = coord.Speed().ok().and_then(|s| s.Value().ok());
+    let heading = coord
+        .Heading()
+        .ok()
+        .and_then(|h| h.Value().ok())
+        .filter(|h| !h.is_nan());
 
     Ok(Location {
         latitude: pos.Latitude,
@@ -53,6 +69,79 @@ pub(crate) async fn get_location() -> Result<Location, LocationError> {
         altitude: Some(pos.Altitude),
         horizontal_accuracy: accuracy,
         vertical_accuracy: None,
+        speed_mps: speed,
+        course_degrees: heading,
         timestamp,
     })
 }
+
+/// Unsubscribes from `PositionChanged` and `StatusChanged` when dropped.
+struct WatchGuard {
+    geolocator: Geolocator,
+    position_token: i64,
+    status_token: i64,
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        let _ = self.geolocator.RemovePositionChanged(self.position_token);
+        let _ = self.geolocator.RemoveStatusChanged(self.status_token);
+    }
+}
+
+pub async fn watch_location(
+    accuracy: Accuracy,
+) -> Result<impl futures::Stream<Item = Result<Location, LocationError>>, LocationError> {
+    request_access()?;
+
+    let geolocator =
+        Geolocator::new().map_err(|e| LocationError::Unknown(e.message().to_string()))?;
+
+    let desired = match accuracy {
+        Accuracy::Navigation | Accuracy::Best | Accuracy::High => PositionAccuracy::High,
+        Accuracy::Balanced | Accuracy::Low | Accuracy::Passive => PositionAccuracy::Default,
+    };
+    geolocator
+        .SetDesiredAccuracy(desired)
+        .map_err(|e| LocationError::Unknown(e.message().to_string()))?;
+    geolocator
+        .SetReportInterval(1000)
+        .map_err(|e| LocationError::Unknown(e.message().to_string()))?;
+
+    let (sender, receiver) = async_channel::unbounded();
+    let position_sender = sender.clone();
+    let position_token = geolocator
+        .PositionChanged(&TypedEventHandler::new(move |_, args| {
+            if let Ok(args) = args.ok()
+                && let Ok(position) = args.Position()
+            {
+                let _ = position_sender.try_send(to_location(&position));
+            }
+            Ok(())
+        }))
+        .map_err(|e| LocationError::Unknown(e.message().to_string()))?;
+    let status_token = geolocator
+        .StatusChanged(&TypedEventHandler::new(move |_, args| {
+            // Disabled also means the user turned off location for the app
+            let error = match args.ok().and_then(|args| args.Status()) {
+                Ok(PositionStatus::Disabled) => LocationError::PermissionDenied,
+                Ok(PositionStatus::NotAvailable) => LocationError::NotAvailable,
+                _ => return Ok(()),
+            };
+            let _ = sender.try_send(Err(error));
+            Ok(())
+        }))
+        .map_err(|e| {
+            let _ = geolocator.RemovePositionChanged(position_token);
+            LocationError::Unknown(e.message().to_string())
+        })?;
+
+    Ok(super::guarded_stream(
+        receiver,
+        WatchGuard {
+            geolocator,
+            position_token,
+            status_token,
+        },
+    ))
+}
@@ -0,0 +1,54 @@
+//! Notification channels, which group notifications on Android 8+.
+
+/// How strongly notifications in a channel interrupt the user.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NotificationImportance {
+    /// Shown only in the shade, collapsed, without an icon in the status bar.
+    Min,
+    /// Shown in the shade and status bar without a sound.
+    Low,
+    /// Shown everywhere with the default sound.
+    #[default]
+    Default,
+    /// Like [`Default`](Self::Default), and also pops up on screen.
+    High,
+}
+
+/// A category of notifications the user can configure in the system
+/// settings, such as "Messages" or "Downloads".
+///
+/// Android 8+ posts every notification to a channel. Once created, the
+/// user owns its importance: creating it again only updates the name.
+/// Other platforms have no channels and ignore them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationChannel {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) importance: NotificationImportance,
+}
+
+impl NotificationChannel {
+    /// Create a channel with the ID notifications refer to it by, and the
+    /// name shown to the user.
+    #[must_use]
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            importance: NotificationImportance::Default,
+        }
+    }
+
+    /// Set the importance of the channel's notifications.
+    #[must_use]
+    pub const fn importance(mut self, importance: NotificationImportance) -> Self {
+        self.importance = importance;
+        self
+    }
+
+    /// The ID to pass to [`Notification::channel`](crate::Notification::channel).
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
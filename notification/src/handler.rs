@@ -0,0 +1,185 @@
+//! Callbacks for shown notifications.
+//!
+//! Every notification passed to [`crate::Notification::show`] is registered
+//! here under a unique key. Platform backends report what happens to it with
+//! [`dispatch`], and the matching handlers run on a dedicated thread so they
+//! never block a platform callback thread.
+
+use crate::{NotificationDeliveryHandle, NotificationDeliveryStatus, NotificationError};
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// Called with the ID of the tapped action.
+///
+/// Handlers are wrapped in a `Mutex` so they only need to be `Send`.
+pub type ActionHandler = Arc<Mutex<Box<dyn Fn(String) + Send>>>;
+
+/// Called when the notification itself is tapped.
+pub type TapHandler = Arc<Mutex<Box<dyn Fn() + Send>>>;
+
+/// A button shown on a notification.
+#[derive(Debug, Clone)]
+pub struct Action {
+    pub id: String,
+    pub title: String,
+}
+
+/// Something that happened to a shown notification.
+#[derive(Debug)]
+pub enum Event {
+    /// The platform finished posting the notification.
+    Delivered(NotificationDeliveryStatus),
+    /// The notification could not be posted.
+    Failed(NotificationError),
+    /// The user tapped the notification.
+    Tapped,
+    /// The user tapped an action button.
+    Action(String),
+    /// The notification was dismissed without being tapped.
+    Closed,
+}
+
+impl Event {
+    /// Decode an event reported by Swift or Kotlin.
+    ///
+    /// `detail` is the action ID for actions and the message for failures.
+    #[cfg(any(target_os = "ios", target_os = "macos", target_os = "android"))]
+    pub fn from_code(code: u8, detail: String) -> Option<Self> {
+        Some(match code {
+            0 => Self::Delivered(NotificationDeliveryStatus::Delivered),
+            1 => Self::Delivered(NotificationDeliveryStatus::Replaced),
+            2 => Self::Delivered(NotificationDeliveryStatus::Dismissed),
+            3 => Self::Failed(NotificationError::PermissionDenied),
+            4 => Self::Failed(NotificationError::Platform(detail)),
+            5 => Self::Tapped,
+            6 => Self::Action(detail),
+            7 => Self::Closed,
+            _ => return None,
+        })
+    }
+}
+
+/// Callbacks waiting on one notification.
+struct Handlers {
+    delivery: Option<async_channel::Sender<Result<NotificationDeliveryStatus, NotificationError>>>,
+    on_tap: Option<TapHandler>,
+    on_action: Option<ActionHandler>,
+}
+
+/// Handlers of shown notifications, keyed by notification.
+///
+/// An entry lives until its delivery is reported and, if it has tap or action
+/// handlers, until the notification is tapped or closed.
+static HANDLERS: Mutex<Option<HashMap<String, Handlers>>> = Mutex::new(None);
+
+/// Events waiting for the dispatch thread.
+static EVENTS: OnceLock<mpsc::Sender<(String, Event)>> = OnceLock::new();
+
+/// Register a notification about to be shown, returning its key.
+pub fn register(
+    on_tap: Option<TapHandler>,
+    on_action: Option<ActionHandler>,
+) -> (String, NotificationDeliveryHandle) {
+    let key = uuid::Uuid::new_v4().to_string();
+    let (sender, receiver) = async_channel::bounded(1);
+    HANDLERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_with(HashMap::new)
+        .insert(
+            key.clone(),
+            Handlers {
+                delivery: Some(sender),
+                on_tap,
+                on_action,
+            },
+        );
+    let id = key.clone().into();
+    (key, NotificationDeliveryHandle { id, receiver })
+}
+
+/// Drop the handlers of a cancelled notification.
+///
+/// A delivery still awaited fails, since the notification will never be shown.
+pub fn forget(key: &str) {
+    if let Some(handlers) = HANDLERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+    {
+        handlers.remove(key);
+    }
+}
+
+/// Drop the handlers of every notification.
+pub fn forget_all() {
+    *HANDLERS.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Report `event` for the notification registered under `key`.
+///
+/// Unknown keys are ignored, so backends can pass an empty key for
+/// notifications nobody is listening to.
+pub fn dispatch(key: &str, event: Event) {
+    if key.is_empty() {
+        return;
+    }
+    let events = EVENTS.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<(String, Event)>();
+        let _ = std::thread::Builder::new()
+            .name("waterkit-notification".into())
+            .spawn(move || {
+                for (key, event) in receiver {
+                    handle(&key, event);
+                }
+            });
+        sender
+    });
+    let _ = events.send((key.to_owned(), event));
+}
+
+fn handle(key: &str, event: Event) {
+    let mut guard = HANDLERS.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(handlers) = guard.as_mut() else {
+        return;
+    };
+    let Some(entry) = handlers.get_mut(key) else {
+        return;
+    };
+
+    let delivered = match &event {
+        Event::Delivered(status) => Ok(*status),
+        Event::Failed(error) => Err(error.clone()),
+        // Interacting with a notification means it was shown
+        Event::Tapped | Event::Action(_) | Event::Closed => {
+            Ok(NotificationDeliveryStatus::Delivered)
+        }
+    };
+    if let Some(delivery) = entry.delivery.take() {
+        let _ = delivery.try_send(delivered);
+    }
+
+    let done = match event {
+        Event::Delivered(_) => entry.on_tap.is_none() && entry.on_action.is_none(),
+        Event::Failed(_) | Event::Tapped | Event::Action(_) | Event::Closed => true,
+    };
+    let Some(entry) = done.then(|| handlers.remove(key)).flatten() else {
+        return;
+    };
+    // Run outside the lock so a handler can show another notification
+    drop(guard);
+    match event {
+        Event::Tapped => {
+            if let Some(on_tap) = entry.on_tap {
+                (on_tap.lock().unwrap_or_else(PoisonError::into_inner))();
+            }
+        }
+        Event::Action(action_id) => {
+            if let Some(on_action) = entry.on_action {
+                (on_action.lock().unwrap_or_else(PoisonError::into_inner))(action_id);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,122 @@
+//! Images attached to notifications.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// An image shown in a notification, such as a message thumbnail or album
+/// art.
+///
+/// PNG, JPEG and GIF images are supported everywhere.
+#[derive(Clone, PartialEq, Eq)]
+pub enum NotificationImage {
+    /// An image file. It is copied or decoded when the notification is
+    /// shown, so it can be removed afterwards.
+    Path(PathBuf),
+    /// Encoded image data.
+    Bytes(Vec<u8>),
+}
+
+impl fmt::Debug for NotificationImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            Self::Bytes(bytes) => write!(f, "Bytes({} bytes)", bytes.len()),
+        }
+    }
+}
+
+impl From<PathBuf> for NotificationImage {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&Path> for NotificationImage {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_owned())
+    }
+}
+
+impl From<&str> for NotificationImage {
+    fn from(path: &str) -> Self {
+        Self::Path(path.into())
+    }
+}
+
+impl From<String> for NotificationImage {
+    fn from(path: String) -> Self {
+        Self::Path(path.into())
+    }
+}
+
+impl From<Vec<u8>> for NotificationImage {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+impl From<&[u8]> for NotificationImage {
+    fn from(bytes: &[u8]) -> Self {
+        Self::Bytes(bytes.to_vec())
+    }
+}
+
+/// The image as a file the platform can read.
+///
+/// Image data is written to a temporary file, which is removed on drop, so
+/// backends must copy or decode the file before returning.
+pub struct ImageFile {
+    path: PathBuf,
+    temporary: bool,
+}
+
+impl ImageFile {
+    /// Prepare `image`, or log why it can't be and return `None`.
+    pub fn new(image: &NotificationImage) -> Option<Self> {
+        match image {
+            NotificationImage::Path(path) => Some(Self {
+                path: path.clone(),
+                temporary: false,
+            }),
+            NotificationImage::Bytes(bytes) => {
+                // Apple infers the image type from the file extension
+                let name = format!("waterkit-notification-{}", uuid::Uuid::new_v4());
+                let path = std::env::temp_dir()
+                    .join(name)
+                    .with_extension(extension(bytes));
+                match std::fs::write(&path, bytes) {
+                    Ok(()) => Some(Self {
+                        path,
+                        temporary: true,
+                    }),
+                    Err(e) => {
+                        log::warn!("Showing notification without its image: {e}");
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ImageFile {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// The file extension of encoded image data, judged by its signature.
+fn extension(bytes: &[u8]) -> &'static str {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => "png",
+        [0xFF, 0xD8, 0xFF, ..] => "jpg",
+        [b'G', b'I', b'F', b'8', ..] => "gif",
+        _ => "",
+    }
+}
@@ -3,13 +3,223 @@
 //! This crate provides a unified API for sending local notifications
 //! across iOS, macOS, Android, Windows, and Linux platforms.
 
+mod channel;
+mod handler;
+mod image;
 mod sys;
 
+use handler::{Action, ActionHandler, TapHandler};
+use image::ImageFile;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub use channel::{NotificationChannel, NotificationImportance};
+pub use image::NotificationImage;
+
+/// Errors that can occur when showing, scheduling or cancelling notifications.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum NotificationError {
+    /// Notifications are not supported on this platform.
+    #[error("notifications not supported")]
+    NotSupported,
+    /// The user has not allowed the app to post notifications.
+    #[error("notification permission denied")]
+    PermissionDenied,
+    /// An error occurred in the platform backend.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// What became of a shown notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationDeliveryStatus {
+    /// The notification was posted and is being shown.
+    Delivered,
+    /// The notification was posted in place of an earlier one with the same
+    /// platform ID (Android only).
+    Replaced,
+    /// The notification was accepted but the user has turned off its alerts,
+    /// so it is not being shown.
+    Dismissed,
+}
+
+/// Identifies a shown or scheduled notification so it can be cancelled.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NotificationId(String);
+
+impl NotificationId {
+    /// The ID as a string, e.g. for storing it.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for NotificationId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl fmt::Display for NotificationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Tracks whether a notification passed to [`Notification::show`] was shown.
+#[derive(Debug)]
+pub struct NotificationDeliveryHandle {
+    id: NotificationId,
+    receiver: async_channel::Receiver<Result<NotificationDeliveryStatus, NotificationError>>,
+}
+
+impl NotificationDeliveryHandle {
+    /// The ID of the notification, for [`Notification::cancel`].
+    #[must_use]
+    pub const fn id(&self) -> &NotificationId {
+        &self.id
+    }
+
+    /// Wait until the platform has posted the notification.
+    ///
+    /// # Errors
+    /// Returns [`NotificationError::PermissionDenied`] if the user has not
+    /// allowed notifications, or another error if the notification could not
+    /// be posted.
+    pub async fn await_delivery(self) -> Result<NotificationDeliveryStatus, NotificationError> {
+        self.receiver.recv().await.map_err(|_| {
+            NotificationError::Platform("notification was dropped before delivery".into())
+        })?
+    }
+}
+
+/// Show `count` as the badge on the app icon.
+///
+/// A count of 0 removes the badge, the same as [`clear_badge`]. On macOS
+/// this sets the Dock tile label. Android needs a context, so use
+/// `set_badge_with_context` there.
+///
+/// # Errors
+/// Returns [`NotificationError::NotSupported`] on Linux and Windows, and
+/// [`NotificationError::PermissionDenied`] if the user has not allowed badges.
+// Linux and Windows always fail here, so on those targets it could be const
+#[allow(clippy::missing_const_for_fn)]
+pub fn set_badge(count: u32) -> Result<(), NotificationError> {
+    sys::set_badge(count)
+}
+
+/// Remove the badge from the app icon.
+///
+/// # Errors
+/// Returns the same errors as [`set_badge`].
+pub fn clear_badge() -> Result<(), NotificationError> {
+    set_badge(0)
+}
+
+/// Show `count` as the badge on the app icon using an Android context.
+///
+/// Android has no badge API, so the count is carried by a silent
+/// notification that launchers with numeric badges read.
+///
+/// # Errors
+/// Returns an error if the badge cannot be updated.
+#[cfg(target_os = "android")]
+pub fn set_badge_with_context(
+    env: &mut jni::JNIEnv,
+    context: &jni::objects::JObject,
+    count: u32,
+) -> Result<(), NotificationError> {
+    sys::android::set_badge_with_context(env, context, count).map_err(NotificationError::Platform)
+}
+
+/// Remove the badge from the app icon using an Android context.
+///
+/// # Errors
+/// Returns an error if the badge cannot be updated.
+#[cfg(target_os = "android")]
+pub fn clear_badge_with_context(
+    env: &mut jni::JNIEnv,
+    context: &jni::objects::JObject,
+) -> Result<(), NotificationError> {
+    set_badge_with_context(env, context, 0)
+}
+
+/// Create `channel`, or update its name if it exists.
+///
+/// Channels only exist on Android, where this needs a context: use
+/// `create_channel_with_context` there. On every other platform this does
+/// nothing and returns `Ok(())`, so shared setup code can call it
+/// unconditionally.
+///
+/// # Errors
+/// Returns [`NotificationError::Platform`] on Android.
+// Only Android can fail or do any work, so elsewhere it could be const
+#[allow(clippy::missing_const_for_fn)]
+pub fn create_channel(channel: &NotificationChannel) -> Result<(), NotificationError> {
+    sys::create_channel(channel)
+}
+
+/// Create `channel` using an Android context, or update its name if it
+/// exists.
+///
+/// # Errors
+/// Returns an error if the channel cannot be created.
+#[cfg(target_os = "android")]
+pub fn create_channel_with_context(
+    env: &mut jni::JNIEnv,
+    context: &jni::objects::JObject,
+    channel: &NotificationChannel,
+) -> Result<(), NotificationError> {
+    sys::android::create_channel_with_context(env, context, channel)
+        .map_err(NotificationError::Platform)
+}
+
+/// Handle an intent broadcast by a notification and received by the app's
+/// own Android broadcast receiver, returning `false` if it didn't come from
+/// a notification.
+///
+/// Action buttons are dismissed, and handlers registered in this process
+/// are called. Handlers don't survive the process, so after a restart the
+/// app reads the `waterkit.notification.ACTION_ID` extra itself.
+///
+/// # Errors
+/// Returns an error if the intent cannot be passed to the helper class.
+#[cfg(target_os = "android")]
+pub fn handle_event_with_context(
+    env: &mut jni::JNIEnv,
+    context: &jni::objects::JObject,
+    intent: &jni::objects::JObject,
+) -> Result<bool, NotificationError> {
+    sys::android::handle_event_with_context(env, context, intent)
+        .map_err(NotificationError::Platform)
+}
+
 /// A builder for local notifications.
-#[derive(Debug, Clone, Default)]
+#[derive(Clone, Default)]
 pub struct Notification {
     title: String,
     body: String,
+    image: Option<NotificationImage>,
+    channel: Option<String>,
+    actions: Vec<Action>,
+    on_tap: Option<TapHandler>,
+    on_action: Option<ActionHandler>,
+}
+
+impl fmt::Debug for Notification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notification")
+            .field("title", &self.title)
+            .field("body", &self.body)
+            .field("image", &self.image)
+            .field("channel", &self.channel)
+            .field("actions", &self.actions)
+            .field("on_tap", &self.on_tap.is_some())
+            .field("on_action", &self.on_action.is_some())
+            .finish()
+    }
 }
 
 impl Notification {
@@ -19,6 +229,11 @@ impl Notification {
         Self {
             title: String::new(),
             body: String::new(),
+            image: None,
+            channel: None,
+            actions: Vec::new(),
+            on_tap: None,
+            on_action: None,
         }
     }
 
@@ -36,16 +251,165 @@ impl Notification {
         self
     }
 
+    /// Attach an image, given as a file path or encoded bytes.
+    ///
+    /// iOS and macOS show it as an attachment thumbnail, and Android as a
+    /// large icon that expands to a big picture. Other platforms show the
+    /// notification without it. If the image can't be read or decoded, a
+    /// warning is logged and the notification is shown as text only.
+    ///
+    /// On macOS, notifications with an image are posted through
+    /// `UNUserNotificationCenter`, which requires the app to be bundled.
+    #[must_use]
+    pub fn image(mut self, image: impl Into<NotificationImage>) -> Self {
+        self.image = Some(image.into());
+        self
+    }
+
+    /// Post the notification to the channel with ID `id` (Android only).
+    ///
+    /// A channel that hasn't been created with `create_channel_with_context`
+    /// is created with the ID as its name and default importance. Without a
+    /// channel, notifications go to a shared default one.
+    #[must_use]
+    pub fn channel(mut self, id: impl Into<String>) -> Self {
+        self.channel = Some(id.into());
+        self
+    }
+
+    /// Add a button to the notification.
+    ///
+    /// `id` is passed to the [`on_action`](Self::on_action) handler when the
+    /// button is tapped. Platforms limit how many buttons are shown: Android
+    /// shows at most three and Windows at most five.
+    #[must_use]
+    pub fn action(mut self, id: impl Into<String>, title: impl Into<String>) -> Self {
+        self.actions.push(Action {
+            id: id.into(),
+            title: title.into(),
+        });
+        self
+    }
+
+    /// Add a button to the notification.
+    #[deprecated(note = "use `action`")]
+    #[must_use]
+    pub fn add_action(self, id: impl Into<String>, title: impl Into<String>) -> Self {
+        self.action(id, title)
+    }
+
+    /// Set the handler called with the action ID when a button is tapped.
+    ///
+    /// The handler runs at most once. Tapping the notification itself, or
+    /// dismissing it, does not call it.
+    ///
+    /// Like [`on_tap`](Self::on_tap), it runs on a thread owned by this crate
+    /// and only while the app is running.
+    ///
+    /// On Android, buttons broadcast to a receiver this crate registers at
+    /// runtime, which is gone once the system kills the app. To handle a tap
+    /// after that, the app has to declare its own broadcast receiver for the
+    /// `<package>.waterkit.notification.EVENT` action in its manifest. That
+    /// receiver gets the button ID in the `waterkit.notification.ACTION_ID`
+    /// extra, and should pass the intent to [`handle_event_with_context`] so
+    /// the notification is dismissed.
+    #[must_use]
+    pub fn on_action(mut self, handler: impl Fn(String) + Send + 'static) -> Self {
+        self.on_action = Some(Arc::new(Mutex::new(Box::new(handler))));
+        self
+    }
+
+    /// Set the handler called when the notification itself is tapped.
+    ///
+    /// The handler runs at most once, on a thread owned by this crate.
+    /// Taps are only reported while the app is running: on iOS and macOS the
+    /// app must not have set its own `UNUserNotificationCenter` delegate, and
+    /// on Linux the notification server must support actions.
+    #[must_use]
+    pub fn on_tap(mut self, handler: impl Fn() + Send + 'static) -> Self {
+        self.on_tap = Some(Arc::new(Mutex::new(Box::new(handler))));
+        self
+    }
+
     /// Show the notification.
-    pub fn show(self) {
-        #[cfg(any(
-            target_os = "linux",
-            target_os = "windows",
-            target_os = "macos",
-            target_os = "android",
-            target_os = "ios"
-        ))]
-        sys::show_notification(&self.title, &self.body);
+    ///
+    /// The returned handle reports whether it was shown and carries the
+    /// [`NotificationId`] to cancel it with; it can be dropped if neither
+    /// matters. On macOS, notifications with actions, a tap handler or an
+    /// image are posted through `UNUserNotificationCenter`, which requires
+    /// the app to be bundled.
+    pub fn show(self) -> NotificationDeliveryHandle {
+        let interactive = self.is_interactive();
+        let image = self.image.as_ref().and_then(ImageFile::new);
+        let (key, handle) = handler::register(self.on_tap, self.on_action);
+        sys::show_notification(
+            &key,
+            &self.title,
+            &self.body,
+            image.as_ref().map(ImageFile::path),
+            &self.actions,
+            interactive,
+        );
+        handle
+    }
+
+    /// Whether the notification reports taps or has buttons.
+    fn is_interactive(&self) -> bool {
+        !self.actions.is_empty() || self.on_tap.is_some() || self.on_action.is_some()
+    }
+
+    /// Deliver the notification after `delay`.
+    ///
+    /// Returns an ID that can be passed to [`Notification::cancel`].
+    /// A zero delay delivers the notification immediately.
+    ///
+    /// On Linux and Windows the notification is held in-process, so it is
+    /// lost if the app exits before it fires.
+    ///
+    /// # Errors
+    /// Returns an error if the user denied notification permission or the
+    /// notification cannot be scheduled.
+    pub fn schedule(self, delay: Duration) -> Result<NotificationId, NotificationError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let image = self.image.as_ref().and_then(ImageFile::new);
+        sys::schedule_notification(
+            &id,
+            &self.title,
+            &self.body,
+            image.as_ref().map(ImageFile::path),
+            delay,
+        )?;
+        Ok(NotificationId(id))
+    }
+
+    /// Cancel a notification.
+    ///
+    /// A scheduled notification that hasn't fired yet is dropped, and a
+    /// delivered one is removed from the screen and the notification center,
+    /// so it can be replaced by showing an updated one. Its tap and action
+    /// handlers won't run. An unknown ID does nothing.
+    ///
+    /// On macOS, notifications shown without actions or a tap handler go
+    /// through `NSUserNotificationCenter` and stay until the user dismisses
+    /// them. Android needs a context, so use `cancel_with_context` there.
+    ///
+    /// # Errors
+    /// Returns an error if the platform backend fails.
+    pub fn cancel(id: &NotificationId) -> Result<(), NotificationError> {
+        handler::forget(id.as_str());
+        sys::cancel_notification(id.as_str())
+    }
+
+    /// Cancel every notification this app has shown or scheduled.
+    ///
+    /// The same caveats as [`Notification::cancel`] apply. Android needs a
+    /// context, so use `cancel_all_with_context` there.
+    ///
+    /// # Errors
+    /// Returns an error if the platform backend fails.
+    pub fn cancel_all() -> Result<(), NotificationError> {
+        handler::forget_all();
+        sys::cancel_all_notifications()
     }
 
     /// Show the notification with an Android context.
@@ -57,7 +421,91 @@ impl Notification {
         self,
         env: &mut jni::JNIEnv,
         context: &jni::objects::JObject,
-    ) -> Result<(), String> {
-        sys::android::show_notification_with_context(env, context, &self.title, &self.body)
+    ) -> Result<NotificationDeliveryHandle, String> {
+        let interactive = self.is_interactive();
+        let image = self.image.as_ref().and_then(ImageFile::new);
+        let (key, handle) = handler::register(self.on_tap, self.on_action);
+        if let Err(e) = sys::android::show_notification_with_context(
+            env,
+            context,
+            &key,
+            &self.title,
+            &self.body,
+            image.as_ref().map(ImageFile::path),
+            self.channel.as_deref(),
+            &self.actions,
+            interactive,
+        ) {
+            handler::dispatch(
+                &key,
+                handler::Event::Failed(NotificationError::Platform(e.clone())),
+            );
+            return Err(e);
+        }
+        Ok(handle)
+    }
+
+    /// Deliver the notification after `delay` using an Android context.
+    ///
+    /// The delay is tracked by `AlarmManager` within the app process, so the
+    /// notification is lost if the process is killed before it fires.
+    ///
+    /// # Errors
+    /// Returns an error if the notification cannot be scheduled.
+    #[cfg(target_os = "android")]
+    pub fn schedule_with_context(
+        self,
+        env: &mut jni::JNIEnv,
+        context: &jni::objects::JObject,
+        delay: Duration,
+    ) -> Result<NotificationId, NotificationError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let image = self.image.as_ref().and_then(ImageFile::new);
+        sys::android::schedule_notification_with_context(
+            env,
+            context,
+            &id,
+            &self.title,
+            &self.body,
+            image.as_ref().map(ImageFile::path),
+            self.channel.as_deref(),
+            delay,
+        )
+        .map_err(NotificationError::Platform)?;
+        Ok(NotificationId(id))
+    }
+
+    /// Cancel a notification using an Android context.
+    ///
+    /// Behaves like [`Notification::cancel`].
+    ///
+    /// # Errors
+    /// Returns an error if the notification cannot be cancelled.
+    #[cfg(target_os = "android")]
+    pub fn cancel_with_context(
+        env: &mut jni::JNIEnv,
+        context: &jni::objects::JObject,
+        id: &NotificationId,
+    ) -> Result<(), NotificationError> {
+        handler::forget(id.as_str());
+        sys::android::cancel_notification_with_context(env, context, id.as_str())
+            .map_err(NotificationError::Platform)
+    }
+
+    /// Cancel every notification this app has shown or scheduled using an
+    /// Android context.
+    ///
+    /// The badge set with `set_badge_with_context` is left in place.
+    ///
+    /// # Errors
+    /// Returns an error if the notifications cannot be cancelled.
+    #[cfg(target_os = "android")]
+    pub fn cancel_all_with_context(
+        env: &mut jni::JNIEnv,
+        context: &jni::objects::JObject,
+    ) -> Result<(), NotificationError> {
+        handler::forget_all();
+        sys::android::cancel_all_notifications_with_context(env, context)
+            .map_err(NotificationError::Platform)
     }
 }
@@ -1,26 +1,332 @@
 package waterkit.notification
 
+import android.app.AlarmManager
 import android.app.NotificationChannel
 import android.app.NotificationManager
+import android.app.PendingIntent
+import android.content.BroadcastReceiver
 import android.content.Context
+import android.content.Intent
+import android.content.IntentFilter
 import android.content.Context.NOTIFICATION_SERVICE
 import android.os.Build
+import android.os.Handler
+import android.os.SystemClock
 import android.app.Notification
+import android.graphics.Bitmap
+import android.graphics.BitmapFactory
+import android.graphics.drawable.Icon
+import android.util.Log
+import java.util.concurrent.atomic.AtomicInteger
 
 class NotificationHelper {
     companion object {
+        /** Cancel actions for notifications that have not fired yet, keyed by ID. */
+        private val pending = HashMap<String, () -> Unit>()
+
+        private const val CHANNEL_ID = "water_notification_channel"
+        private const val BADGE_CHANNEL_ID = "water_badge_channel"
+        private const val BADGE_TAG = "waterkit.notification.BADGE"
+        private const val LOG_TAG = "waterkit-notification"
+
+        private const val EXTRA_KEY = "waterkit.notification.KEY"
+        private const val EXTRA_EVENT = "waterkit.notification.EVENT"
+        private const val EXTRA_ACTION = "waterkit.notification.ACTION_ID"
+        private const val EXTRA_NOTIFICATION_ID = "waterkit.notification.NOTIFICATION_ID"
+
+        // Event codes understood by onNotificationEvent
+        private const val EVENT_DELIVERED = 0
+        private const val EVENT_REPLACED = 1
+        private const val EVENT_DISMISSED = 2
+        private const val EVENT_DENIED = 3
+        private const val EVENT_TAPPED = 5
+        private const val EVENT_ACTION = 6
+        private const val EVENT_CLOSED = 7
+
+        /** Receives taps and dismissals, registered on first use. */
+        private var eventReceiver: BroadcastReceiver? = null
+        private val requestCodes = AtomicInteger()
+
+        /** Reports what happened to the notification shown with handler [key]. */
+        @JvmStatic
+        external fun onNotificationEvent(key: String, event: Int, detail: String)
+
+        /**
+         * Show a notification and report its delivery to [onNotificationEvent].
+         * If [interactive], taps, action buttons and dismissal are reported too.
+         * The image at [imagePath], if any, is decoded before returning.
+         * Without a [channelId] the notification goes to the default channel.
+         */
+        @JvmStatic
+        fun showNotification(
+            context: Context,
+            key: String,
+            title: String,
+            body: String,
+            imagePath: String?,
+            channelId: String?,
+            actionIds: Array<String>,
+            actionTitles: Array<String>,
+            interactive: Boolean
+        ) {
+            val appContext = context.applicationContext
+            val manager = appContext.getSystemService(NOTIFICATION_SERVICE) as NotificationManager
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.N && !manager.areNotificationsEnabled()) {
+                onNotificationEvent(key, EVENT_DENIED, "")
+                return
+            }
+
+            // Derived from the key so cancelNotification can find it again
+            val notificationId = key.hashCode()
+            val channel = ensureChannel(manager, channelId)
+            val builder = builder(appContext, channel, title, body, loadImage(imagePath))
+            if (interactive) {
+                val event = registerEventReceiver(appContext)
+                fun broadcast(code: Int, actionId: String = ""): PendingIntent {
+                    val intent = Intent(event)
+                        .setPackage(appContext.packageName)
+                        .putExtra(EXTRA_KEY, key)
+                        .putExtra(EXTRA_EVENT, code)
+                        .putExtra(EXTRA_ACTION, actionId)
+                        .putExtra(EXTRA_NOTIFICATION_ID, notificationId)
+                    val flags = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.M) {
+                        PendingIntent.FLAG_UPDATE_CURRENT or PendingIntent.FLAG_IMMUTABLE
+                    } else {
+                        PendingIntent.FLAG_UPDATE_CURRENT
+                    }
+                    return PendingIntent.getBroadcast(appContext, requestCodes.getAndIncrement(), intent, flags)
+                }
+
+                builder.setContentIntent(broadcast(EVENT_TAPPED))
+                builder.setDeleteIntent(broadcast(EVENT_CLOSED))
+                for ((actionId, actionTitle) in actionIds.zip(actionTitles)) {
+                    val pendingIntent = broadcast(EVENT_ACTION, actionId)
+                    val action = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.M) {
+                        Notification.Action.Builder(null as Icon?, actionTitle, pendingIntent).build()
+                    } else {
+                        @Suppress("DEPRECATION")
+                        Notification.Action.Builder(0, actionTitle, pendingIntent).build()
+                    }
+                    builder.addAction(action)
+                }
+            }
+
+            val replaced = Build.VERSION.SDK_INT >= Build.VERSION_CODES.M &&
+                manager.activeNotifications.any { it.id == notificationId }
+            manager.notify(notificationId, builder.build())
+
+            val muted = Build.VERSION.SDK_INT >= Build.VERSION_CODES.O &&
+                manager.getNotificationChannel(channel)?.importance == NotificationManager.IMPORTANCE_NONE
+            val status = when {
+                muted -> EVENT_DISMISSED
+                replaced -> EVENT_REPLACED
+                else -> EVENT_DELIVERED
+            }
+            onNotificationEvent(key, status, "")
+        }
+
+        /**
+         * Handle a tap, action or dismissal broadcast by a notification, returning
+         * false if [intent] didn't come from one. Called by the receiver registered
+         * here, and by the app's own receiver once this process has been restarted.
+         */
+        @JvmStatic
+        fun handleEvent(context: Context, intent: Intent): Boolean {
+            val key = intent.getStringExtra(EXTRA_KEY) ?: return false
+            val code = intent.getIntExtra(EXTRA_EVENT, EVENT_CLOSED)
+            // Action buttons don't dismiss the notification by themselves
+            if (code == EVENT_ACTION) {
+                val manager = context.getSystemService(NOTIFICATION_SERVICE) as NotificationManager
+                manager.cancel(intent.getIntExtra(EXTRA_NOTIFICATION_ID, 0))
+            }
+
+            onNotificationEvent(key, code, intent.getStringExtra(EXTRA_ACTION) ?: "")
+            return true
+        }
+
+        /** Register the receiver for notification events once, returning its intent action. */
+        private fun registerEventReceiver(context: Context): String {
+            val event = "${context.packageName}.waterkit.notification.EVENT"
+            synchronized(this) {
+                if (eventReceiver != null) return event
+
+                val receiver = object : BroadcastReceiver() {
+                    override fun onReceive(context: Context, intent: Intent) {
+                        handleEvent(context, intent)
+                    }
+                }
+                val filter = IntentFilter(event)
+                if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+                    context.registerReceiver(receiver, filter, Context.RECEIVER_NOT_EXPORTED)
+                } else {
+                    context.registerReceiver(receiver, filter)
+                }
+                eventReceiver = receiver
+            }
+            return event
+        }
+
+        /**
+         * Post the notification after [delayMs]. The alarm is delivered to this
+         * process, so the notification is lost if the process dies first.
+         */
+        @JvmStatic
+        fun scheduleNotification(
+            context: Context,
+            id: String,
+            title: String,
+            body: String,
+            imagePath: String?,
+            channelId: String?,
+            delayMs: Long
+        ) {
+            val appContext = context.applicationContext
+            val handler = Handler(appContext.mainLooper)
+            // Decoded now, since the file may be gone when the alarm fires
+            val image = loadImage(imagePath)
+            val fire = Runnable {
+                val due = synchronized(pending) { pending.remove(id) != null }
+                if (due) {
+                    postNotification(appContext, id.hashCode(), channelId, title, body, image)
+                }
+            }
+
+            // Registered before arming so a short delay can't fire first
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.N) {
+                val alarms = appContext.getSystemService(Context.ALARM_SERVICE) as AlarmManager
+                val listener = AlarmManager.OnAlarmListener { fire.run() }
+                synchronized(pending) { pending[id] = { alarms.cancel(listener) } }
+                alarms.set(
+                    AlarmManager.ELAPSED_REALTIME_WAKEUP,
+                    SystemClock.elapsedRealtime() + delayMs,
+                    "waterkit:notification",
+                    listener,
+                    handler
+                )
+            } else {
+                synchronized(pending) { pending[id] = { handler.removeCallbacks(fire) } }
+                handler.postDelayed(fire, delayMs)
+            }
+        }
+
+        /** Cancel the notification [id] if it is pending, and remove it if it was posted. */
         @JvmStatic
-        fun showNotification(context: Context, title: String, body: String) {
+        fun cancelNotification(context: Context, id: String) {
+            val cancel = synchronized(pending) { pending.remove(id) }
+            cancel?.invoke()
             val manager = context.getSystemService(NOTIFICATION_SERVICE) as NotificationManager
-            val channelId = "water_notification_channel"
+            manager.cancel(id.hashCode())
+        }
+
+        /** Cancel every pending notification and remove the posted ones, except the badge. */
+        @JvmStatic
+        fun cancelAllNotifications(context: Context) {
+            val cancels = synchronized(pending) {
+                val cancels = pending.values.toList()
+                pending.clear()
+                cancels
+            }
+            cancels.forEach { it() }
+
+            val manager = context.getSystemService(NOTIFICATION_SERVICE) as NotificationManager
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.M) {
+                for (notification in manager.activeNotifications) {
+                    if (notification.tag != BADGE_TAG) {
+                        manager.cancel(notification.tag, notification.id)
+                    }
+                }
+            } else {
+                manager.cancelAll()
+            }
+        }
+
+        /**
+         * Create the channel [id], or update its [name] if it exists. Android
+         * leaves the importance of an existing channel to the user.
+         */
+        @JvmStatic
+        fun createChannel(context: Context, id: String, name: String, importance: Int) {
+            if (Build.VERSION.SDK_INT < Build.VERSION_CODES.O) return
+            val manager = context.getSystemService(NOTIFICATION_SERVICE) as NotificationManager
+            manager.createNotificationChannel(NotificationChannel(id, name, importance))
+        }
+
+        /**
+         * Show [count] on the launcher icon, or clear it for 0.
+         *
+         * Android has no badge API of its own: launchers count active
+         * notifications, so the count rides on a silent notification.
+         */
+        @JvmStatic
+        fun setBadge(context: Context, count: Int) {
+            val manager = context.getSystemService(NOTIFICATION_SERVICE) as NotificationManager
+            if (count == 0) {
+                manager.cancel(BADGE_TAG, 0)
+                return
+            }
 
-            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
-                val channel = NotificationChannel(channelId, "Notifications", NotificationManager.IMPORTANCE_DEFAULT)
+            val builder = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+                val channel = NotificationChannel(BADGE_CHANNEL_ID, "Badge", NotificationManager.IMPORTANCE_MIN)
+                channel.setShowBadge(true)
                 manager.createNotificationChannel(channel)
+                Notification.Builder(context, BADGE_CHANNEL_ID)
+                    .setBadgeIconType(Notification.BADGE_ICON_SMALL)
+            } else {
+                Notification.Builder(context)
+            }
+            val notification = builder
+                .setSmallIcon(android.R.drawable.ic_dialog_info)
+                .setNumber(count)
+                .setOnlyAlertOnce(true)
+                .build()
+            manager.notify(BADGE_TAG, 0, notification)
+        }
+
+        private fun postNotification(
+            context: Context,
+            notificationId: Int,
+            channelId: String?,
+            title: String,
+            body: String,
+            image: Bitmap?
+        ) {
+            val manager = context.getSystemService(NOTIFICATION_SERVICE) as NotificationManager
+            val channel = ensureChannel(manager, channelId)
+            manager.notify(notificationId, builder(context, channel, title, body, image).build())
+        }
+
+        /**
+         * Create [channelId] with default importance if it doesn't exist yet,
+         * or the default channel for null. Returns the ID of the channel.
+         */
+        private fun ensureChannel(manager: NotificationManager, channelId: String?): String {
+            val id = channelId ?: CHANNEL_ID
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O && manager.getNotificationChannel(id) == null) {
+                val name = channelId ?: "Notifications"
+                manager.createNotificationChannel(NotificationChannel(id, name, NotificationManager.IMPORTANCE_DEFAULT))
+            }
+            return id
+        }
+
+        /** Decode the image at [path], or return null to show the notification as text only. */
+        private fun loadImage(path: String?): Bitmap? {
+            if (path == null) return null
+            val image = BitmapFactory.decodeFile(path)
+            if (image == null) {
+                Log.w(LOG_TAG, "Showing notification without its image: can't decode $path")
             }
+            return image
+        }
 
+        private fun builder(
+            context: Context,
+            channel: String,
+            title: String,
+            body: String,
+            image: Bitmap?
+        ): Notification.Builder {
             val builder = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
-                Notification.Builder(context, channelId)
+                Notification.Builder(context, channel)
             } else {
                 Notification.Builder(context)
             }
@@ -29,8 +335,12 @@ class NotificationHelper {
                 .setContentText(body)
                 .setSmallIcon(android.R.drawable.ic_dialog_info)
                 .setAutoCancel(true)
-
-            manager.notify(System.currentTimeMillis().toInt(), builder.build())
+            if (image != null) {
+                // A thumbnail while collapsed, and the full picture once expanded
+                builder.setLargeIcon(image)
+                    .setStyle(Notification.BigPictureStyle().bigPicture(image).bigLargeIcon(null as Bitmap?))
+            }
+            return builder
         }
     }
 }
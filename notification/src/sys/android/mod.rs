@@ -1,8 +1,13 @@
 //! Android notification implementation using JNI.
 
+use crate::handler::{self, Action, Event};
+use crate::{NotificationChannel, NotificationError, NotificationImportance};
 use jni::JNIEnv;
-use jni::objects::{GlobalRef, JObject, JValue};
+use jni::objects::{GlobalRef, JClass, JObject, JObjectArray, JString, JValue};
+use jni::sys::jint;
+use std::path::Path;
 use std::sync::OnceLock;
+use std::time::Duration;
 
 /// Embedded DEX bytecode containing NotificationHelper class.
 static DEX_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/classes.dex"));
@@ -73,15 +78,47 @@ pub fn init_with_context(env: &mut JNIEnv, context: &JObject) -> Result<(), Stri
         .map_err(|e| format!("new_global_ref: {e}"))?;
 
     let _ = CLASS_LOADER.set(global_ref);
-    Ok(())
+
+    // The helper is loaded from our own DexClassLoader, so the runtime can't
+    // resolve its native methods by symbol name; register them explicitly.
+    register_natives(env, context)
 }
 
-pub fn show_notification_with_context(
-    env: &mut JNIEnv,
+fn register_natives(env: &mut JNIEnv, context: &JObject) -> Result<(), String> {
+    let class = helper_class(env, context)?;
+    let native_methods = [jni::NativeMethod {
+        name: "onNotificationEvent".into(),
+        sig: "(Ljava/lang/String;ILjava/lang/String;)V".into(),
+        fn_ptr: Java_waterkit_notification_NotificationHelper_onNotificationEvent as *mut _,
+    }];
+
+    env.register_native_methods(class, &native_methods)
+        .map_err(|e| format!("register_native_methods: {e}"))
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_notification_NotificationHelper_onNotificationEvent(
+    mut env: JNIEnv,
+    _class: JClass,
+    key: JString,
+    code: jint,
+    detail: JString,
+) {
+    let key: String = env.get_string(&key).map(Into::into).unwrap_or_default();
+    let detail: String = env.get_string(&detail).map(Into::into).unwrap_or_default();
+    if let Some(event) = u8::try_from(code)
+        .ok()
+        .and_then(|code| Event::from_code(code, detail))
+    {
+        handler::dispatch(&key, event);
+    }
+}
+
+/// Load `NotificationHelper` from the embedded DEX.
+fn helper_class<'local>(
+    env: &mut JNIEnv<'local>,
     context: &JObject,
-    title: &str,
-    body: &str,
-) -> Result<(), String> {
+) -> Result<JClass<'local>, String> {
     init_with_context(env, context)?;
 
     let class_loader = CLASS_LOADER.get().ok_or("Class loader not initialized")?;
@@ -101,23 +138,108 @@ pub fn show_notification_with_context(
         .l()
         .map_err(|e| format!("loadClass result: {e}"))?;
 
-    let helper_jclass: jni::objects::JClass = helper_class.into();
+    Ok(helper_class.into())
+}
+
+/// Build a `String[]` from `values`.
+fn string_array<'local, 'a>(
+    env: &mut JNIEnv<'local>,
+    values: impl ExactSizeIterator<Item = &'a str>,
+) -> Result<JObjectArray<'local>, String> {
+    let len = i32::try_from(values.len()).map_err(|e| format!("array length: {e}"))?;
+    let array = env
+        .new_object_array(len, "java/lang/String", JObject::null())
+        .map_err(|e| format!("new_object_array: {e}"))?;
+    for (index, value) in (0..len).zip(values) {
+        let value = env
+            .new_string(value)
+            .map_err(|e| format!("new_string: {e}"))?;
+        env.set_object_array_element(&array, index, value)
+            .map_err(|e| format!("set_object_array_element: {e}"))?;
+    }
+    Ok(array)
+}
+
+/// The image path as a `String`, or null without an image.
+fn image_path<'local>(
+    env: &mut JNIEnv<'local>,
+    image: Option<&Path>,
+) -> Result<JObject<'local>, String> {
+    match image {
+        Some(path) => env
+            .new_string(path.to_string_lossy())
+            .map(JObject::from)
+            .map_err(|e| format!("new_string: {e}")),
+        None => Ok(JObject::null()),
+    }
+}
+
+/// The channel ID as a `String`, or null for the default channel.
+fn channel_id<'local>(
+    env: &mut JNIEnv<'local>,
+    channel: Option<&str>,
+) -> Result<JObject<'local>, String> {
+    match channel {
+        Some(id) => env
+            .new_string(id)
+            .map(JObject::from)
+            .map_err(|e| format!("new_string: {e}")),
+        None => Ok(JObject::null()),
+    }
+}
+
+/// The `NotificationManager.IMPORTANCE_*` value of `importance`.
+const fn importance_code(importance: NotificationImportance) -> i32 {
+    match importance {
+        NotificationImportance::Min => 1,
+        NotificationImportance::Low => 2,
+        NotificationImportance::Default => 3,
+        NotificationImportance::High => 4,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn show_notification_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    key: &str,
+    title: &str,
+    body: &str,
+    image: Option<&Path>,
+    channel: Option<&str>,
+    actions: &[Action],
+    interactive: bool,
+) -> Result<(), String> {
+    let helper_jclass = helper_class(env, context)?;
 
+    let jkey = env
+        .new_string(key)
+        .map_err(|e| format!("new_string: {e}"))?;
     let jtitle = env
         .new_string(title)
         .map_err(|e| format!("new_string: {e}"))?;
     let jbody = env
         .new_string(body)
         .map_err(|e| format!("new_string: {e}"))?;
+    let jimage = image_path(env, image)?;
+    let jchannel = channel_id(env, channel)?;
+    let jids = string_array(env, actions.iter().map(|a| a.id.as_str()))?;
+    let jtitles = string_array(env, actions.iter().map(|a| a.title.as_str()))?;
 
     env.call_static_method(
         helper_jclass,
         "showNotification",
-        "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;)V",
+        "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;[Ljava/lang/String;[Ljava/lang/String;Z)V",
         &[
             JValue::Object(context),
+            JValue::Object(&jkey),
             JValue::Object(&jtitle),
             JValue::Object(&jbody),
+            JValue::Object(&jimage),
+            JValue::Object(&jchannel),
+            JValue::Object(&jids),
+            JValue::Object(&jtitles),
+            JValue::Bool(interactive.into()),
         ],
     )
     .map_err(|e| format!("showNotification call failed: {e}"))?;
@@ -125,7 +247,204 @@ pub fn show_notification_with_context(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn schedule_notification_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    id: &str,
+    title: &str,
+    body: &str,
+    image: Option<&Path>,
+    channel: Option<&str>,
+    delay: Duration,
+) -> Result<(), String> {
+    let helper_jclass = helper_class(env, context)?;
+
+    let jid = env.new_string(id).map_err(|e| format!("new_string: {e}"))?;
+    let jtitle = env
+        .new_string(title)
+        .map_err(|e| format!("new_string: {e}"))?;
+    let jbody = env
+        .new_string(body)
+        .map_err(|e| format!("new_string: {e}"))?;
+    let jimage = image_path(env, image)?;
+    let jchannel = channel_id(env, channel)?;
+    let delay_ms = i64::try_from(delay.as_millis()).unwrap_or(i64::MAX);
+
+    env.call_static_method(
+        helper_jclass,
+        "scheduleNotification",
+        "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;J)V",
+        &[
+            JValue::Object(context),
+            JValue::Object(&jid),
+            JValue::Object(&jtitle),
+            JValue::Object(&jbody),
+            JValue::Object(&jimage),
+            JValue::Object(&jchannel),
+            JValue::Long(delay_ms),
+        ],
+    )
+    .map_err(|e| format!("scheduleNotification call failed: {e}"))?;
+
+    Ok(())
+}
+
+pub fn cancel_notification_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    id: &str,
+) -> Result<(), String> {
+    let helper_jclass = helper_class(env, context)?;
+
+    let jid = env.new_string(id).map_err(|e| format!("new_string: {e}"))?;
+
+    env.call_static_method(
+        helper_jclass,
+        "cancelNotification",
+        "(Landroid/content/Context;Ljava/lang/String;)V",
+        &[JValue::Object(context), JValue::Object(&jid)],
+    )
+    .map_err(|e| format!("cancelNotification call failed: {e}"))?;
+
+    Ok(())
+}
+
+pub fn cancel_all_notifications_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<(), String> {
+    let helper_jclass = helper_class(env, context)?;
+
+    env.call_static_method(
+        helper_jclass,
+        "cancelAllNotifications",
+        "(Landroid/content/Context;)V",
+        &[JValue::Object(context)],
+    )
+    .map_err(|e| format!("cancelAllNotifications call failed: {e}"))?;
+
+    Ok(())
+}
+
+pub fn create_channel_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    channel: &NotificationChannel,
+) -> Result<(), String> {
+    let helper_jclass = helper_class(env, context)?;
+
+    let jid = env
+        .new_string(&channel.id)
+        .map_err(|e| format!("new_string: {e}"))?;
+    let jname = env
+        .new_string(&channel.name)
+        .map_err(|e| format!("new_string: {e}"))?;
+
+    env.call_static_method(
+        helper_jclass,
+        "createChannel",
+        "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;I)V",
+        &[
+            JValue::Object(context),
+            JValue::Object(&jid),
+            JValue::Object(&jname),
+            JValue::Int(importance_code(channel.importance)),
+        ],
+    )
+    .map_err(|e| format!("createChannel call failed: {e}"))?;
+
+    Ok(())
+}
+
+pub fn handle_event_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    intent: &JObject,
+) -> Result<bool, String> {
+    let helper_jclass = helper_class(env, context)?;
+
+    env.call_static_method(
+        helper_jclass,
+        "handleEvent",
+        "(Landroid/content/Context;Landroid/content/Intent;)Z",
+        &[JValue::Object(context), JValue::Object(intent)],
+    )
+    .map_err(|e| format!("handleEvent call failed: {e}"))?
+    .z()
+    .map_err(|e| format!("handleEvent result: {e}"))
+}
+
+pub fn set_badge_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    count: u32,
+) -> Result<(), String> {
+    let helper_jclass = helper_class(env, context)?;
+
+    env.call_static_method(
+        helper_jclass,
+        "setBadge",
+        "(Landroid/content/Context;I)V",
+        &[
+            JValue::Object(context),
+            JValue::Int(i32::try_from(count).unwrap_or(i32::MAX)),
+        ],
+    )
+    .map_err(|e| format!("setBadge call failed: {e}"))?;
+
+    Ok(())
+}
+
 // Stub for the default trait method trying to find context or fail
-pub fn show_notification(_title: &str, _body: &str) {
-    eprintln!("Android notification requires generic show_with_context call.");
+pub fn show_notification(
+    key: &str,
+    _title: &str,
+    _body: &str,
+    _image: Option<&Path>,
+    _actions: &[Action],
+    _interactive: bool,
+) {
+    handler::dispatch(
+        key,
+        Event::Failed(NotificationError::Platform(
+            "Android: use show_with_context()".into(),
+        )),
+    );
+}
+
+pub fn schedule_notification(
+    _id: &str,
+    _title: &str,
+    _body: &str,
+    _image: Option<&Path>,
+    _delay: Duration,
+) -> Result<(), NotificationError> {
+    Err(NotificationError::Platform(
+        "Android: use schedule_with_context()".into(),
+    ))
+}
+
+pub fn cancel_notification(_id: &str) -> Result<(), NotificationError> {
+    Err(NotificationError::Platform(
+        "Android: use cancel_with_context()".into(),
+    ))
+}
+
+pub fn cancel_all_notifications() -> Result<(), NotificationError> {
+    Err(NotificationError::Platform(
+        "Android: use cancel_all_with_context()".into(),
+    ))
+}
+
+pub fn create_channel(_channel: &NotificationChannel) -> Result<(), NotificationError> {
+    Err(NotificationError::Platform(
+        "Android: use create_channel_with_context()".into(),
+    ))
+}
+
+pub fn set_badge(_count: u32) -> Result<(), NotificationError> {
+    Err(NotificationError::Platform(
+        "Android: use set_badge_with_context()".into(),
+    ))
 }
@@ -1,22 +1,244 @@
 import UserNotifications
 import Foundation
+#if os(iOS)
+import UIKit
+#elseif os(macOS)
+import AppKit
+#endif
+
+// Event codes understood by `notification_event`
+private let eventDelivered: UInt8 = 0
+private let eventDismissed: UInt8 = 2
+private let eventDenied: UInt8 = 3
+private let eventFailed: UInt8 = 4
+private let eventTapped: UInt8 = 5
+private let eventAction: UInt8 = 6
+private let eventClosed: UInt8 = 7
+
+/// Key under which the Rust handler key is stored, in the request's userInfo.
+private let handlerKeyInfo = "waterkit_handler_key"
+
+/// Categories registered so far, since the center only accepts the full set.
+private var categories: [String: UNNotificationCategory] = [:]
+private let categoriesLock = NSLock()
+
+/// Forwards responses to Rust. Held strongly because the center's delegate
+/// reference is weak.
+private let responseDelegate = NotificationResponseDelegate()
+
+final class NotificationResponseDelegate: NSObject, UNUserNotificationCenterDelegate {
+    func userNotificationCenter(
+        _ center: UNUserNotificationCenter,
+        didReceive response: UNNotificationResponse,
+        withCompletionHandler completionHandler: @escaping () -> Void
+    ) {
+        if let key = response.notification.request.content.userInfo[handlerKeyInfo] as? String {
+            switch response.actionIdentifier {
+            case UNNotificationDefaultActionIdentifier:
+                notification_event(key, eventTapped, "")
+            case UNNotificationDismissActionIdentifier:
+                notification_event(key, eventClosed, "")
+            case let action:
+                notification_event(key, eventAction, action)
+            }
+        }
+        completionHandler()
+    }
+
+    // Without this, notifications are silently dropped while the app is frontmost
+    func userNotificationCenter(
+        _ center: UNUserNotificationCenter,
+        willPresent notification: UNNotification,
+        withCompletionHandler completionHandler: @escaping (UNNotificationPresentationOptions) -> Void
+    ) {
+        if #available(iOS 14.0, macOS 11.0, *) {
+            completionHandler([.banner, .list, .sound])
+        } else {
+            completionHandler([.alert, .sound])
+        }
+    }
+}
+
+private func registerCategory(_ actions: ActionListRef, center: UNUserNotificationCenter) -> String {
+    var notificationActions: [UNNotificationAction] = []
+    var parts: [String] = []
+    for index in 0..<actions.count() {
+        let id = actions.id(index).toString()
+        let title = actions.title(index).toString()
+        notificationActions.append(UNNotificationAction(identifier: id, title: title, options: [.foreground]))
+        parts.append(id)
+        parts.append(title)
+    }
+    // Notifications with the same buttons share a category
+    let identifier = "waterkit." + parts.joined(separator: "\u{1F}")
+
+    categoriesLock.lock()
+    defer { categoriesLock.unlock() }
+    if categories[identifier] == nil {
+        categories[identifier] = UNNotificationCategory(
+            identifier: identifier,
+            actions: notificationActions,
+            intentIdentifiers: [],
+            // Report dismissal so Rust can release the handlers
+            options: [.customDismissAction]
+        )
+        center.setNotificationCategories(Set(categories.values))
+    }
+    // Leave a delegate installed by the app in place
+    if center.delegate == nil {
+        center.delegate = responseDelegate
+    }
+    return identifier
+}
+
+/// Attach a copy of the image at `path`, since attaching moves the file into
+/// the notification store. Returns nil, after logging why, if it can't be
+/// attached.
+private func imageAttachment(_ path: String) -> UNNotificationAttachment? {
+    guard !path.isEmpty else {
+        return nil
+    }
+    let source = URL(fileURLWithPath: path)
+    let copy = FileManager.default.temporaryDirectory
+        .appendingPathComponent(UUID().uuidString)
+        .appendingPathExtension(source.pathExtension)
+    do {
+        try FileManager.default.copyItem(at: source, to: copy)
+        return try UNNotificationAttachment(identifier: "image", url: copy, options: nil)
+    } catch {
+        try? FileManager.default.removeItem(at: copy)
+        print("waterkit-notification: Showing notification without its image: \(error)")
+        return nil
+    }
+}
+
+public func show_notification(key: RustStr, title: RustStr, body: RustStr, image_path: RustStr, actions: ActionListRef, interactive: Bool) {
+    let keyStr = key.toString()
+    let content = UNMutableNotificationContent()
+    content.title = title.toString()
+    content.body = body.toString()
+    content.sound = UNNotificationSound.default
+    if let attachment = imageAttachment(image_path.toString()) {
+        content.attachments = [attachment]
+    }
+
+    let center = UNUserNotificationCenter.current()
+    if interactive {
+        content.categoryIdentifier = registerCategory(actions, center: center)
+        content.userInfo = [handlerKeyInfo: keyStr]
+    }
+
+    center.requestAuthorization(options: [.alert, .sound]) { granted, error in
+        guard granted else {
+            if let error = error {
+                notification_event(keyStr, eventFailed, error.localizedDescription)
+            } else {
+                notification_event(keyStr, eventDenied, "")
+            }
+            return
+        }
+        // The handler key doubles as the ID Rust cancels the notification with
+        let request = UNNotificationRequest(identifier: keyStr, content: content, trigger: nil)
+        center.add(request) { error in
+            if let error = error {
+                notification_event(keyStr, eventFailed, error.localizedDescription)
+                return
+            }
+            center.getNotificationSettings { settings in
+                // Accepted, but neither alerted nor kept in the notification center
+                let hidden = settings.alertSetting != .enabled && settings.notificationCenterSetting != .enabled
+                notification_event(keyStr, hidden ? eventDismissed : eventDelivered, "")
+            }
+        }
+    }
+}
+
+public func schedule_notification(id: RustStr, title: RustStr, body: RustStr, image_path: RustStr, delay_secs: Double) -> RequestResultFFI {
+    let content = UNMutableNotificationContent()
+    content.title = title.toString()
+    content.body = body.toString()
+    content.sound = UNNotificationSound.default
+    if let attachment = imageAttachment(image_path.toString()) {
+        content.attachments = [attachment]
+    }
+
+    // Time interval triggers reject zero, so deliver right away instead
+    let trigger = delay_secs > 0
+        ? UNTimeIntervalNotificationTrigger(timeInterval: delay_secs, repeats: false)
+        : nil
+    let request = UNNotificationRequest(identifier: id.toString(), content: content, trigger: trigger)
 
-public func show_notification(title: RustStr, body: RustStr) {
-    let titleStr = title.toString()
-    let bodyStr = body.toString()
-    
     let center = UNUserNotificationCenter.current()
+    let semaphore = DispatchSemaphore(value: 0)
+    var denied = false
+    var message = ""
     center.requestAuthorization(options: [.alert, .sound]) { granted, error in
-        // Ideally we handle error or rejection, but for local notification fire-and-forget:
-        if granted {
-            let content = UNMutableNotificationContent()
-            content.title = titleStr
-            content.body = bodyStr
-            content.sound = UNNotificationSound.default
+        guard granted else {
+            if let error = error {
+                message = error.localizedDescription
+            } else {
+                denied = true
+            }
+            semaphore.signal()
+            return
+        }
+        center.add(request) { error in
+            message = error?.localizedDescription ?? ""
+            semaphore.signal()
+        }
+    }
+    semaphore.wait()
+    return RequestResultFFI(denied: denied, error: message.intoRustString())
+}
+
+public func cancel_notification(id: RustStr) {
+    let center = UNUserNotificationCenter.current()
+    let identifiers = [id.toString()]
+    center.removePendingNotificationRequests(withIdentifiers: identifiers)
+    center.removeDeliveredNotifications(withIdentifiers: identifiers)
+}
 
-            // Helper to run on main thread if needed? add() is thread safe.
-            let request = UNNotificationRequest(identifier: UUID().uuidString, content: content, trigger: nil) // nil trigger = immediate
-            center.add(request)
+public func cancel_all_notifications() {
+    let center = UNUserNotificationCenter.current()
+    center.removeAllPendingNotificationRequests()
+    center.removeAllDeliveredNotifications()
+}
+
+public func set_badge(count: UInt32) -> RequestResultFFI {
+#if os(macOS)
+    // The Dock badge needs no permission
+    DispatchQueue.main.async {
+        NSApplication.shared.dockTile.badgeLabel = count == 0 ? nil : String(count)
+    }
+    return RequestResultFFI(denied: false, error: "".intoRustString())
+#else
+    let center = UNUserNotificationCenter.current()
+    let semaphore = DispatchSemaphore(value: 0)
+    var denied = false
+    var message = ""
+    center.requestAuthorization(options: [.badge]) { granted, error in
+        guard granted else {
+            if let error = error {
+                message = error.localizedDescription
+            } else {
+                denied = true
+            }
+            semaphore.signal()
+            return
+        }
+        if #available(iOS 16.0, *) {
+            center.setBadgeCount(Int(count)) { error in
+                message = error?.localizedDescription ?? ""
+                semaphore.signal()
+            }
+        } else {
+            DispatchQueue.main.async {
+                UIApplication.shared.applicationIconBadgeNumber = Int(count)
+            }
+            semaphore.signal()
         }
     }
+    semaphore.wait()
+    return RequestResultFFI(denied: denied, error: message.intoRustString())
+#endif
 }
@@ -1,10 +1,142 @@
+use crate::NotificationError;
+use crate::handler::{self, Action, Event};
+use std::path::Path;
+use std::time::Duration;
+
 #[swift_bridge::bridge]
 mod ffi {
+    #[swift_bridge(swift_repr = "struct")]
+    struct RequestResultFFI {
+        denied: bool,
+        error: String,
+    }
+
+    extern "Rust" {
+        type ActionList;
+        fn count(self: &ActionList) -> usize;
+        fn id(self: &ActionList, index: usize) -> String;
+        fn title(self: &ActionList, index: usize) -> String;
+
+        // See `Event::from_code` for the codes
+        fn notification_event(key: String, code: u8, detail: String);
+    }
+
     extern "Swift" {
-        fn show_notification(title: &str, body: &str);
+        // Set `interactive` to report taps and dismissal. An empty
+        // `image_path` means no image; the file is copied before returning.
+        fn show_notification(
+            key: &str,
+            title: &str,
+            body: &str,
+            image_path: &str,
+            actions: &ActionList,
+            interactive: bool,
+        );
+        fn schedule_notification(
+            id: &str,
+            title: &str,
+            body: &str,
+            image_path: &str,
+            delay_secs: f64,
+        ) -> RequestResultFFI;
+        // Removes pending and delivered notifications
+        fn cancel_notification(id: &str);
+        fn cancel_all_notifications();
+        fn set_badge(count: u32) -> RequestResultFFI;
+    }
+}
+
+/// Actions of a notification, read by Swift to build its category.
+pub struct ActionList(Vec<Action>);
+
+impl ActionList {
+    fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn id(&self, index: usize) -> String {
+        self.0.get(index).map(|a| a.id.clone()).unwrap_or_default()
+    }
+
+    fn title(&self, index: usize) -> String {
+        self.0
+            .get(index)
+            .map(|a| a.title.clone())
+            .unwrap_or_default()
+    }
+}
+
+fn notification_event(key: String, code: u8, detail: String) {
+    if let Some(event) = Event::from_code(code, detail) {
+        handler::dispatch(&key, event);
     }
 }
 
-pub fn show_notification(title: &str, body: &str) {
-    ffi::show_notification(title, body);
+/// The image path as passed to Swift, which takes an empty one for none.
+fn image_path(image: Option<&Path>) -> String {
+    image
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+pub fn show_notification(
+    key: &str,
+    title: &str,
+    body: &str,
+    image: Option<&Path>,
+    actions: &[Action],
+    interactive: bool,
+) {
+    ffi::show_notification(
+        key,
+        title,
+        body,
+        &image_path(image),
+        &ActionList(actions.to_vec()),
+        interactive,
+    );
+}
+
+fn into_result(result: ffi::RequestResultFFI) -> Result<(), NotificationError> {
+    if result.denied {
+        Err(NotificationError::PermissionDenied)
+    } else if result.error.is_empty() {
+        Ok(())
+    } else {
+        Err(NotificationError::Platform(result.error))
+    }
+}
+
+pub fn schedule_notification(
+    id: &str,
+    title: &str,
+    body: &str,
+    image: Option<&Path>,
+    delay: Duration,
+) -> Result<(), NotificationError> {
+    into_result(ffi::schedule_notification(
+        id,
+        title,
+        body,
+        &image_path(image),
+        delay.as_secs_f64(),
+    ))
+}
+
+// Fallible to match the other backends
+#[allow(clippy::unnecessary_wraps)]
+pub fn cancel_notification(id: &str) -> Result<(), NotificationError> {
+    ffi::cancel_notification(id);
+    Ok(())
+}
+
+// Fallible to match the other backends
+#[allow(clippy::unnecessary_wraps)]
+pub fn cancel_all_notifications() -> Result<(), NotificationError> {
+    ffi::cancel_all_notifications();
+    Ok(())
+}
+
+pub fn set_badge(count: u32) -> Result<(), NotificationError> {
+    into_result(ffi::set_badge(count))
 }
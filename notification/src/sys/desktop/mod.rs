@@ -1,5 +1,257 @@
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use crate::NotificationDeliveryStatus;
+use crate::NotificationError;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use crate::handler::{self, Event};
+#[cfg(any(target_os = "linux", target_os = "macos"))]
 use notify_rust::Notification as NrNotification;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use std::sync::{Mutex, PoisonError};
 
-pub fn show_notification(title: &str, body: &str) {
-    let _ = NrNotification::new().summary(title).body(body).show();
+#[cfg(target_os = "windows")]
+mod toast;
+
+/// Report the outcome of posting the notification registered under `key`.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn report(key: &str, result: Result<(), String>) {
+    let event = match result {
+        Ok(()) => Event::Delivered(NotificationDeliveryStatus::Delivered),
+        Err(e) => Event::Failed(NotificationError::Platform(e)),
+    };
+    handler::dispatch(key, event);
+}
+
+#[cfg(target_os = "macos")]
+pub fn show_notification(key: &str, title: &str, body: &str) {
+    let result = NrNotification::new().summary(title).body(body).show();
+    report(key, result.map(drop).map_err(|e| e.to_string()));
+}
+
+// notify-rust can't report clicks, show buttons or remove toasts on Windows
+#[cfg(target_os = "windows")]
+pub use toast::show_notification;
+
+// Images aren't supported, since the server may read the file after it
+// has been removed
+#[cfg(target_os = "linux")]
+pub fn show_notification(
+    key: &str,
+    title: &str,
+    body: &str,
+    _image: Option<&Path>,
+    actions: &[handler::Action],
+    interactive: bool,
+) {
+    let mut notification = NrNotification::new();
+    notification.summary(title).body(body);
+    if interactive {
+        // Makes the server report clicks on the body as "default"
+        notification.action("default", "");
+        for action in actions {
+            notification.action(&action.id, &action.title);
+        }
+    }
+    let handle = match notification.show() {
+        Ok(handle) => handle,
+        Err(e) => return report(key, Err(e.to_string())),
+    };
+    delivered()
+        .get_or_insert_with(HashMap::new)
+        .insert(key.to_owned(), handle.id());
+    report(key, Ok(()));
+    if !interactive {
+        return;
+    }
+
+    // The server reports the chosen action over D-Bus until the notification closes
+    let key = key.to_owned();
+    let _ = std::thread::Builder::new()
+        .name("waterkit-notification-action".into())
+        .spawn(move || {
+            handle.wait_for_action(|action| {
+                let event = match action {
+                    "__closed" => {
+                        if let Some(map) = delivered().as_mut() {
+                            map.remove(&key);
+                        }
+                        Event::Closed
+                    }
+                    "default" => Event::Tapped,
+                    action => Event::Action(action.to_owned()),
+                };
+                handler::dispatch(&key, event);
+            });
+        });
+}
+
+/// Server IDs of posted notifications, keyed by notification ID.
+///
+/// Entries of notifications without actions stay after the user closes
+/// them, since only interactive ones are told about it.
+#[cfg(target_os = "linux")]
+static DELIVERED: Mutex<Option<HashMap<String, u32>>> = Mutex::new(None);
+
+#[cfg(target_os = "linux")]
+fn delivered() -> std::sync::MutexGuard<'static, Option<HashMap<String, u32>>> {
+    DELIVERED.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Ask the notification server to close the notifications with `server_ids`.
+#[cfg(target_os = "linux")]
+fn close(server_ids: impl IntoIterator<Item = u32>) -> Result<(), NotificationError> {
+    let mut server_ids = server_ids.into_iter().peekable();
+    if server_ids.peek().is_none() {
+        return Ok(());
+    }
+    let connection = zbus::blocking::Connection::session()
+        .map_err(|e| NotificationError::Platform(e.to_string()))?;
+    for server_id in server_ids {
+        // Fails if the user already closed it, which is fine
+        let _ = connection.call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "CloseNotification",
+            &(server_id,),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn cancel_notification(id: &str) -> Result<(), NotificationError> {
+    scheduled::cancel(id);
+    let server_id = delivered().as_mut().and_then(|map| map.remove(id));
+    close(server_id)
+}
+
+#[cfg(target_os = "linux")]
+pub fn cancel_all_notifications() -> Result<(), NotificationError> {
+    scheduled::cancel_all();
+    let server_ids = delivered().take().unwrap_or_default();
+    close(server_ids.into_values())
+}
+
+#[cfg(target_os = "windows")]
+pub fn cancel_notification(id: &str) -> Result<(), NotificationError> {
+    scheduled::cancel(id);
+    toast::remove(id)
+}
+
+#[cfg(target_os = "windows")]
+pub fn cancel_all_notifications() -> Result<(), NotificationError> {
+    scheduled::cancel_all();
+    toast::remove_all()
+}
+
+/// Neither Linux desktops nor unpackaged Windows apps have a badge API.
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+pub const fn set_badge(_count: u32) -> Result<(), NotificationError> {
+    Err(NotificationError::NotSupported)
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+pub use scheduled::schedule_notification;
+
+/// Delayed delivery for backends without a scheduling API.
+///
+/// Each pending notification waits on its own thread and can be woken early
+/// to cancel it.
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+mod scheduled {
+    use crate::NotificationError;
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::sync::{Arc, Condvar, Mutex, PoisonError};
+    use std::thread;
+    use std::time::Duration;
+
+    /// Cancelled flag and the condvar its waiting thread sleeps on.
+    type Pending = Arc<(Mutex<bool>, Condvar)>;
+
+    static PENDING: Mutex<Option<HashMap<String, Pending>>> = Mutex::new(None);
+
+    fn pending() -> std::sync::MutexGuard<'static, Option<HashMap<String, Pending>>> {
+        PENDING.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn schedule_notification(
+        id: &str,
+        title: &str,
+        body: &str,
+        _image: Option<&Path>,
+        delay: Duration,
+    ) -> Result<(), NotificationError> {
+        // Shown under its ID so it can still be removed once delivered
+        if delay.is_zero() {
+            super::show_notification(id, title, body, None, &[], false);
+            return Ok(());
+        }
+
+        let state: Pending = Arc::new((Mutex::new(false), Condvar::new()));
+        pending()
+            .get_or_insert_with(HashMap::new)
+            .insert(id.to_owned(), Arc::clone(&state));
+
+        let (id, title, body) = (id.to_owned(), title.to_owned(), body.to_owned());
+        let spawned = thread::Builder::new()
+            .name("waterkit-notification".into())
+            .spawn({
+                let id = id.clone();
+                move || {
+                    let (cancelled, wake) = &*state;
+                    let cancelled = wake
+                        .wait_timeout_while(
+                            cancelled.lock().unwrap_or_else(PoisonError::into_inner),
+                            delay,
+                            |cancelled| !*cancelled,
+                        )
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                    if !*cancelled {
+                        super::show_notification(&id, &title, &body, None, &[], false);
+                    }
+                    drop(cancelled);
+                    if let Some(map) = pending().as_mut() {
+                        map.remove(&id);
+                    }
+                }
+            });
+
+        if let Err(e) = spawned {
+            if let Some(map) = pending().as_mut() {
+                map.remove(&id);
+            }
+            return Err(NotificationError::Platform(e.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Wake the thread of a pending notification so it exits without
+    /// showing it.
+    fn wake(state: &Pending) {
+        let (cancelled, wake) = &**state;
+        *cancelled.lock().unwrap_or_else(PoisonError::into_inner) = true;
+        wake.notify_one();
+    }
+
+    /// Drop the notification `id` if it hasn't fired yet.
+    pub fn cancel(id: &str) {
+        let state = pending().as_mut().and_then(|map| map.remove(id));
+        if let Some(state) = state {
+            wake(&state);
+        }
+    }
+
+    /// Drop every notification that hasn't fired yet.
+    pub fn cancel_all() {
+        let states = pending().take().unwrap_or_default();
+        for state in states.values() {
+            wake(state);
+        }
+    }
 }
@@ -0,0 +1,173 @@
+//! Toasts with action buttons, click reporting and removal, which
+//! `notify-rust` doesn't offer on Windows.
+
+use crate::handler::{self, Action, Event};
+use crate::{NotificationDeliveryStatus, NotificationError};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::Foundation::TypedEventHandler;
+use windows::UI::Notifications::{
+    ToastActivatedEventArgs, ToastDismissalReason, ToastNotification, ToastNotificationManager,
+};
+use windows::core::{HSTRING, IInspectable, Interface};
+
+/// App ID toasts are shown under, the same default `notify-rust` uses so
+/// unpackaged apps don't need to register one.
+const APP_ID: &str =
+    "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+
+/// Group of every toast shown by this crate, so they can be removed without
+/// touching other toasts under the shared app ID.
+const GROUP: &str = "waterkit";
+
+/// Toasts that can still be activated, kept alive so their handlers can run.
+static SHOWN: Mutex<Option<HashMap<String, ToastNotification>>> = Mutex::new(None);
+
+pub fn show_notification(
+    key: &str,
+    title: &str,
+    body: &str,
+    _image: Option<&Path>,
+    actions: &[Action],
+    interactive: bool,
+) {
+    let event = match show(key, title, body, actions, interactive) {
+        Ok(()) => Event::Delivered(NotificationDeliveryStatus::Delivered),
+        Err(e) => Event::Failed(NotificationError::Platform(e.message().to_string())),
+    };
+    handler::dispatch(key, event);
+}
+
+fn show(
+    key: &str,
+    title: &str,
+    body: &str,
+    actions: &[Action],
+    interactive: bool,
+) -> windows::core::Result<()> {
+    let xml = XmlDocument::new()?;
+    xml.LoadXml(&HSTRING::from(toast_xml(title, body, actions)))?;
+    let toast = ToastNotification::CreateToastNotification(&xml)?;
+    // Tagged with the key so `remove` can find it in the notification center
+    toast.SetTag(&HSTRING::from(key))?;
+    toast.SetGroup(&HSTRING::from(GROUP))?;
+    let notifier = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))?;
+    if !interactive {
+        return notifier.Show(&toast);
+    }
+
+    let activated_key = key.to_owned();
+    toast.Activated(&TypedEventHandler::<ToastNotification, IInspectable>::new(
+        move |_, args| {
+            forget(&activated_key);
+            // A click on the toast body has empty arguments
+            let arguments = args
+                .ok()
+                .and_then(|args| args.cast::<ToastActivatedEventArgs>())
+                .and_then(|args| args.Arguments())
+                .unwrap_or_default();
+            let event = if arguments.is_empty() {
+                Event::Tapped
+            } else {
+                Event::Action(arguments.to_string())
+            };
+            handler::dispatch(&activated_key, event);
+            Ok(())
+        },
+    ))?;
+    let dismissed_key = key.to_owned();
+    toast.Dismissed(&TypedEventHandler::new(move |_, args| {
+        // Timed out toasts move to the notification center and can still be activated
+        let timed_out = args
+            .ok()
+            .and_then(|args| args.Reason())
+            .is_ok_and(|reason| reason == ToastDismissalReason::TimedOut);
+        if !timed_out {
+            forget(&dismissed_key);
+            handler::dispatch(&dismissed_key, Event::Closed);
+        }
+        Ok(())
+    }))?;
+
+    SHOWN
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_with(HashMap::new)
+        .insert(key.to_owned(), toast.clone());
+    let shown = notifier.Show(&toast);
+    if shown.is_err() {
+        forget(key);
+    }
+    shown
+}
+
+fn forget(key: &str) {
+    if let Some(shown) = SHOWN
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+    {
+        shown.remove(key);
+    }
+}
+
+/// Remove the toast shown under `key` from the screen and the notification
+/// center.
+pub fn remove(key: &str) -> Result<(), NotificationError> {
+    forget(key);
+    ToastNotificationManager::History()
+        .and_then(|history| {
+            history.RemoveGroupedTagWithId(
+                &HSTRING::from(key),
+                &HSTRING::from(GROUP),
+                &HSTRING::from(APP_ID),
+            )
+        })
+        .map_err(|e| NotificationError::Platform(e.message().to_string()))
+}
+
+/// Remove every toast shown by this crate.
+pub fn remove_all() -> Result<(), NotificationError> {
+    *SHOWN.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    ToastNotificationManager::History()
+        .and_then(|history| {
+            history.RemoveGroupWithId(&HSTRING::from(GROUP), &HSTRING::from(APP_ID))
+        })
+        .map_err(|e| NotificationError::Platform(e.message().to_string()))
+}
+
+fn toast_xml(title: &str, body: &str, actions: &[Action]) -> String {
+    let mut xml = format!(
+        "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual><actions>",
+        escape(title),
+        escape(body)
+    );
+    for action in actions {
+        let _ = write!(
+            xml,
+            "<action content=\"{}\" arguments=\"{}\" activationType=\"foreground\"/>",
+            escape(&action.title),
+            escape(&action.id)
+        );
+    }
+    xml.push_str("</actions></toast>");
+    xml
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,14 +1,108 @@
 #[cfg(target_os = "android")]
 pub mod android;
 #[cfg(target_os = "android")]
-pub use android::show_notification;
+pub use android::{
+    cancel_all_notifications, cancel_notification, create_channel, schedule_notification,
+    set_badge, show_notification,
+};
 
 #[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
 pub mod desktop;
-#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
-pub use desktop::show_notification;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+pub use desktop::{
+    cancel_all_notifications, cancel_notification, schedule_notification, set_badge,
+    show_notification,
+};
 
-#[cfg(target_os = "ios")]
+#[cfg(any(target_os = "ios", target_os = "macos"))]
 pub mod apple;
 #[cfg(target_os = "ios")]
 pub use apple::show_notification;
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub use apple::{cancel_all_notifications, cancel_notification, schedule_notification, set_badge};
+
+/// Show through `notify-rust` unless the notification needs callbacks or an
+/// image, which only `UNUserNotificationCenter` provides.
+#[cfg(target_os = "macos")]
+pub fn show_notification(
+    key: &str,
+    title: &str,
+    body: &str,
+    image: Option<&std::path::Path>,
+    actions: &[crate::handler::Action],
+    interactive: bool,
+) {
+    if interactive || image.is_some() {
+        apple::show_notification(key, title, body, image, actions, interactive);
+    } else {
+        desktop::show_notification(key, title, body);
+    }
+}
+
+/// Does nothing: only Android has channels, so elsewhere there is nothing to
+/// create. Returns a `Result` to match the Android signature.
+#[cfg(not(target_os = "android"))]
+#[allow(clippy::unnecessary_wraps)]
+pub const fn create_channel(
+    _channel: &crate::NotificationChannel,
+) -> Result<(), crate::NotificationError> {
+    Ok(())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "ios"
+)))]
+mod unsupported {
+    use crate::NotificationError;
+    use crate::handler::{self, Action, Event};
+    use std::path::Path;
+    use std::time::Duration;
+
+    pub fn show_notification(
+        key: &str,
+        _title: &str,
+        _body: &str,
+        _image: Option<&Path>,
+        _actions: &[Action],
+        _interactive: bool,
+    ) {
+        handler::dispatch(key, Event::Failed(NotificationError::NotSupported));
+    }
+
+    pub fn schedule_notification(
+        _id: &str,
+        _title: &str,
+        _body: &str,
+        _image: Option<&Path>,
+        _delay: Duration,
+    ) -> Result<(), NotificationError> {
+        Err(NotificationError::NotSupported)
+    }
+
+    pub fn cancel_notification(_id: &str) -> Result<(), NotificationError> {
+        Err(NotificationError::NotSupported)
+    }
+
+    pub const fn cancel_all_notifications() -> Result<(), NotificationError> {
+        Err(NotificationError::NotSupported)
+    }
+
+    pub const fn set_badge(_count: u32) -> Result<(), NotificationError> {
+        Err(NotificationError::NotSupported)
+    }
+}
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "ios"
+)))]
+pub use unsupported::{
+    cancel_all_notifications, cancel_notification, schedule_notification, set_badge,
+    show_notification,
+};
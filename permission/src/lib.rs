@@ -8,6 +8,15 @@
 /// Platform-specific implementations.
 mod sys;
 
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+#[cfg(target_os = "android")]
+pub use sys::android::{
+    PERMISSION_REQUEST_CODE, check_with_activity, init_with_activity,
+    location_accuracy_with_activity, open_app_settings_with_activity, request_many_with_activity,
+};
+
 /// Types of permissions that can be requested.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -24,6 +33,10 @@ pub enum Permission {
     Contacts,
     /// Access to calendar.
     Calendar,
+    /// Posting notifications.
+    Notifications,
+    /// Connecting to Bluetooth devices.
+    Bluetooth,
 }
 
 /// The current status of a permission.
@@ -39,6 +52,17 @@ pub enum PermissionStatus {
     NotDetermined,
 }
 
+/// How precisely a granted location permission lets the app locate the
+/// device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LocationAccuracy {
+    /// Precise location.
+    Full,
+    /// Approximate location only, accurate to a few kilometers. iOS 14+,
+    /// macOS 11+ and Android 12+ let the user choose this.
+    Reduced,
+}
+
 /// Errors that can occur when requesting permissions.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum PermissionError {
@@ -67,3 +91,54 @@ pub async fn check(permission: Permission) -> PermissionStatus {
 pub async fn request(permission: Permission) -> Result<PermissionStatus, PermissionError> {
     sys::request(permission).await
 }
+
+/// Open the system settings where the user can grant permissions they
+/// denied before.
+///
+/// Once a permission is [`PermissionStatus::Denied`], the system no longer
+/// prompts for it, so this is the only way back. It opens the app's page in
+/// Settings on iOS, the Privacy & Security pane on macOS, and the privacy
+/// page on Windows. On Android, use [`open_app_settings_with_activity`].
+///
+/// # Errors
+/// Returns a `PermissionError` if the settings could not be opened, or
+/// `NotSupported` on Linux, which has no such page.
+// Linux always fails here, so on that target it could be const
+#[allow(clippy::missing_const_for_fn)]
+pub fn open_app_settings() -> Result<(), PermissionError> {
+    sys::open_app_settings()
+}
+
+/// Check how precisely the app may locate the device.
+///
+/// [`Permission::Location`] is [`PermissionStatus::Granted`] whether the
+/// user allowed precise or only approximate location; this tells them
+/// apart. Returns `None` if location isn't granted. On Android, use
+/// [`location_accuracy_with_activity`]. Windows and Linux have no
+/// approximate grant, so location is always full there.
+pub async fn location_accuracy() -> Option<LocationAccuracy> {
+    if check(Permission::Location).await != PermissionStatus::Granted {
+        return None;
+    }
+    Some(sys::location_accuracy())
+}
+
+/// Request several permissions, returning the status of each.
+///
+/// The permissions are requested one after another, since iOS and macOS
+/// have no API to request several at once. On Android, use
+/// [`request_many_with_activity`] to show a single system dialog instead.
+///
+/// # Errors
+/// Returns the first `PermissionError` a request fails with; see [`request`].
+pub async fn request_many(
+    permissions: &[Permission],
+) -> Result<HashMap<Permission, PermissionStatus>, PermissionError> {
+    let mut statuses = HashMap::with_capacity(permissions.len());
+    for &permission in permissions {
+        if let Entry::Vacant(entry) = statuses.entry(permission) {
+            entry.insert(sys::request(permission).await?);
+        }
+    }
+    Ok(statuses)
+}
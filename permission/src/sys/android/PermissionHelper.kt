@@ -2,7 +2,11 @@ package waterkit.permission
 
 import android.Manifest
 import android.app.Activity
+import android.content.Intent
 import android.content.pm.PackageManager
+import android.net.Uri
+import android.os.Build
+import android.provider.Settings
 
 /**
  * Helper class for checking and requesting permissions on Android.
@@ -17,6 +21,8 @@ object PermissionHelper {
     const val PERMISSION_PHOTOS = 3
     const val PERMISSION_CONTACTS = 4
     const val PERMISSION_CALENDAR = 5
+    const val PERMISSION_NOTIFICATIONS = 6
+    const val PERMISSION_BLUETOOTH = 7
 
     // Status constants (must match Rust enum)
     const val STATUS_NOT_DETERMINED = 0
@@ -24,18 +30,25 @@ object PermissionHelper {
     const val STATUS_DENIED = 2
     const val STATUS_GRANTED = 3
 
+    // Location accuracy constants (must match Rust)
+    const val ACCURACY_NONE = 0
+    const val ACCURACY_REDUCED = 1
+    const val ACCURACY_FULL = 2
+
     /**
-     * Check if a permission is granted.
+     * Check if a permission is granted. Location counts as granted with
+     * approximate access only.
      */
     @JvmStatic
     fun checkPermission(activity: Activity, permissionType: Int): Int {
-        val permission = getPermissionString(permissionType) ?: return STATUS_NOT_DETERMINED
+        if (isInstallTime(permissionType)) return STATUS_GRANTED
+        val permissions = getPermissionStrings(permissionType)
+        if (permissions.isEmpty()) return STATUS_NOT_DETERMINED
 
-        return if (activity.checkSelfPermission(permission) == PackageManager.PERMISSION_GRANTED) {
-            STATUS_GRANTED
-        } else {
-            STATUS_DENIED
+        val granted = permissions.any {
+            activity.checkSelfPermission(it) == PackageManager.PERMISSION_GRANTED
         }
+        return if (granted) STATUS_GRANTED else STATUS_DENIED
     }
 
     /**
@@ -43,17 +56,89 @@ object PermissionHelper {
      */
     @JvmStatic
     fun requestPermission(activity: Activity, permissionType: Int, requestCode: Int) {
-        val permission = getPermissionString(permissionType) ?: return
-        activity.requestPermissions(arrayOf(permission), requestCode)
-    }
-
-    private fun getPermissionString(permissionType: Int): String? = when (permissionType) {
-        PERMISSION_LOCATION -> Manifest.permission.ACCESS_FINE_LOCATION
-        PERMISSION_CAMERA -> Manifest.permission.CAMERA
-        PERMISSION_MICROPHONE -> Manifest.permission.RECORD_AUDIO
-        PERMISSION_PHOTOS -> Manifest.permission.READ_MEDIA_IMAGES
-        PERMISSION_CONTACTS -> Manifest.permission.READ_CONTACTS
-        PERMISSION_CALENDAR -> Manifest.permission.READ_CALENDAR
-        else -> null
+        val permissions = getPermissionStrings(permissionType)
+        if (permissions.isNotEmpty()) {
+            activity.requestPermissions(permissions.toTypedArray(), requestCode)
+        }
+    }
+
+    /**
+     * How precisely the app may locate the device: precise, approximate
+     * only (Android 12+ lets the user choose), or not at all.
+     */
+    @JvmStatic
+    fun getLocationAccuracy(activity: Activity): Int = when {
+        activity.checkSelfPermission(Manifest.permission.ACCESS_FINE_LOCATION) ==
+            PackageManager.PERMISSION_GRANTED -> ACCURACY_FULL
+        activity.checkSelfPermission(Manifest.permission.ACCESS_COARSE_LOCATION) ==
+            PackageManager.PERMISSION_GRANTED -> ACCURACY_REDUCED
+        else -> ACCURACY_NONE
+    }
+
+    /**
+     * Request several permissions with a single system dialog. Permissions
+     * that are already granted are left out.
+     */
+    @JvmStatic
+    fun requestPermissions(activity: Activity, permissionTypes: IntArray, requestCode: Int) {
+        val permissions = permissionTypes
+            .flatMap { getPermissionStrings(it) }
+            .distinct()
+            .filter { activity.checkSelfPermission(it) != PackageManager.PERMISSION_GRANTED }
+        if (permissions.isNotEmpty()) {
+            activity.requestPermissions(permissions.toTypedArray(), requestCode)
+        }
+    }
+
+    /**
+     * Open the app's details page in Settings, where the user can grant
+     * permissions they denied before.
+     */
+    @JvmStatic
+    fun openAppSettings(activity: Activity) {
+        val intent = Intent(
+            Settings.ACTION_APPLICATION_DETAILS_SETTINGS,
+            Uri.fromParts("package", activity.packageName, null)
+        )
+        activity.startActivity(intent)
+    }
+
+    /**
+     * Whether the permission is granted at install time on this Android
+     * version: notifications before 13, Bluetooth before 12.
+     */
+    private fun isInstallTime(permissionType: Int): Boolean = when (permissionType) {
+        PERMISSION_NOTIFICATIONS -> Build.VERSION.SDK_INT < Build.VERSION_CODES.TIRAMISU
+        PERMISSION_BLUETOOTH -> Build.VERSION.SDK_INT < Build.VERSION_CODES.S
+        else -> false
+    }
+
+    /**
+     * The manifest permissions behind a permission type. Location asks for
+     * both precise and approximate access, which Android 12+ requires to
+     * offer the user the choice.
+     */
+    private fun getPermissionStrings(permissionType: Int): List<String> = when (permissionType) {
+        PERMISSION_LOCATION -> listOfNotNull(
+            getPermissionString(permissionType),
+            Manifest.permission.ACCESS_COARSE_LOCATION
+        )
+        else -> listOfNotNull(getPermissionString(permissionType))
+    }
+
+    private fun getPermissionString(permissionType: Int): String? {
+        // Install-time permissions have nothing to check or request
+        if (isInstallTime(permissionType)) return null
+        return when (permissionType) {
+            PERMISSION_LOCATION -> Manifest.permission.ACCESS_FINE_LOCATION
+            PERMISSION_CAMERA -> Manifest.permission.CAMERA
+            PERMISSION_MICROPHONE -> Manifest.permission.RECORD_AUDIO
+            PERMISSION_PHOTOS -> Manifest.permission.READ_MEDIA_IMAGES
+            PERMISSION_CONTACTS -> Manifest.permission.READ_CONTACTS
+            PERMISSION_CALENDAR -> Manifest.permission.READ_CALENDAR
+            PERMISSION_NOTIFICATIONS -> Manifest.permission.POST_NOTIFICATIONS
+            PERMISSION_BLUETOOTH -> Manifest.permission.BLUETOOTH_CONNECT
+            else -> null
+        }
     }
 }
@@ -1,9 +1,10 @@
 //! Android permission implementation using JNI.
 
-use crate::{Permission, PermissionError, PermissionStatus};
+use crate::{LocationAccuracy, Permission, PermissionError, PermissionStatus};
 use jni::JNIEnv;
-use jni::objects::{GlobalRef, JObject, JValue};
+use jni::objects::{GlobalRef, JClass, JObject, JValue};
 use jni::sys::jint;
+use std::collections::HashMap;
 use std::sync::OnceLock;
 
 /// Embedded DEX bytecode containing PermissionHelper class.
@@ -20,6 +21,12 @@ const PERMISSION_MICROPHONE: jint = 2;
 const PERMISSION_PHOTOS: jint = 3;
 const PERMISSION_CONTACTS: jint = 4;
 const PERMISSION_CALENDAR: jint = 5;
+const PERMISSION_NOTIFICATIONS: jint = 6;
+const PERMISSION_BLUETOOTH: jint = 7;
+
+/// Request code passed to `Activity.requestPermissions`, so the activity
+/// can recognize the result in `onRequestPermissionsResult`.
+pub const PERMISSION_REQUEST_CODE: jint = 0x5754;
 
 /// Status constants (must match Kotlin).
 const STATUS_NOT_DETERMINED: jint = 0;
@@ -27,6 +34,10 @@ const STATUS_RESTRICTED: jint = 1;
 const STATUS_DENIED: jint = 2;
 const STATUS_GRANTED: jint = 3;
 
+/// Location accuracy constants (must match Kotlin).
+const ACCURACY_REDUCED: jint = 1;
+const ACCURACY_FULL: jint = 2;
+
 fn permission_to_jint(permission: Permission) -> jint {
     match permission {
         Permission::Location => PERMISSION_LOCATION,
@@ -35,6 +46,8 @@ fn permission_to_jint(permission: Permission) -> jint {
         Permission::Photos => PERMISSION_PHOTOS,
         Permission::Contacts => PERMISSION_CONTACTS,
         Permission::Calendar => PERMISSION_CALENDAR,
+        Permission::Notifications => PERMISSION_NOTIFICATIONS,
+        Permission::Bluetooth => PERMISSION_BLUETOOTH,
     }
 }
 
@@ -118,14 +131,8 @@ pub fn init_with_activity(env: &mut JNIEnv, activity: &JObject) -> Result<(), Pe
     Ok(())
 }
 
-/// Check permission using the Activity context.
-pub fn check_with_activity(
-    env: &mut JNIEnv,
-    activity: &JObject,
-    permission: Permission,
-) -> Result<PermissionStatus, PermissionError> {
-    init_with_activity(env, activity)?;
-
+/// Load the `PermissionHelper` class from the embedded DEX.
+fn helper_class<'a>(env: &mut JNIEnv<'a>) -> Result<JClass<'a>, PermissionError> {
     let class_loader = CLASS_LOADER
         .get()
         .ok_or_else(|| PermissionError::Unknown("Class loader not initialized".into()))?;
@@ -145,7 +152,18 @@ pub fn check_with_activity(
         .l()
         .map_err(|e| PermissionError::Unknown(format!("loadClass result: {e}")))?;
 
-    let helper_jclass: jni::objects::JClass = helper_class.into();
+    Ok(helper_class.into())
+}
+
+/// Check permission using the Activity context.
+pub fn check_with_activity(
+    env: &mut JNIEnv,
+    activity: &JObject,
+    permission: Permission,
+) -> Result<PermissionStatus, PermissionError> {
+    init_with_activity(env, activity)?;
+    let helper_jclass = helper_class(env)?;
+
     let result = env
         .call_static_method(
             helper_jclass,
@@ -163,15 +181,108 @@ pub fn check_with_activity(
     Ok(status_from_jint(result))
 }
 
+/// Check how precisely the app may locate the device using the Activity
+/// context. Returns `None` if neither precise nor approximate location is
+/// granted.
+pub fn location_accuracy_with_activity(
+    env: &mut JNIEnv,
+    activity: &JObject,
+) -> Result<Option<LocationAccuracy>, PermissionError> {
+    init_with_activity(env, activity)?;
+    let helper_jclass = helper_class(env)?;
+
+    let result = env
+        .call_static_method(
+            helper_jclass,
+            "getLocationAccuracy",
+            "(Landroid/app/Activity;)I",
+            &[JValue::Object(activity)],
+        )
+        .map_err(|e| PermissionError::Unknown(format!("getLocationAccuracy: {e}")))?
+        .i()
+        .map_err(|e| PermissionError::Unknown(format!("getLocationAccuracy result: {e}")))?;
+
+    Ok(match result {
+        ACCURACY_FULL => Some(LocationAccuracy::Full),
+        ACCURACY_REDUCED => Some(LocationAccuracy::Reduced),
+        _ => None,
+    })
+}
+
+/// Request several permissions with a single `requestPermissions` call,
+/// using [`PERMISSION_REQUEST_CODE`].
+///
+/// Android delivers the user's answer to the activity's
+/// `onRequestPermissionsResult`, so the returned map holds the statuses at
+/// the time of the call: permissions being requested read as `Denied` until
+/// then. Check them again once the result arrives.
+pub fn request_many_with_activity(
+    env: &mut JNIEnv,
+    activity: &JObject,
+    permissions: &[Permission],
+) -> Result<HashMap<Permission, PermissionStatus>, PermissionError> {
+    init_with_activity(env, activity)?;
+    let helper_jclass = helper_class(env)?;
+
+    let types: Vec<jint> = permissions
+        .iter()
+        .copied()
+        .map(permission_to_jint)
+        .collect();
+    let len = jint::try_from(types.len())
+        .map_err(|_| PermissionError::Unknown("too many permissions".into()))?;
+    let jtypes = env
+        .new_int_array(len)
+        .map_err(|e| PermissionError::Unknown(format!("new_int_array: {e}")))?;
+    env.set_int_array_region(&jtypes, 0, &types)
+        .map_err(|e| PermissionError::Unknown(format!("set_int_array_region: {e}")))?;
+
+    env.call_static_method(
+        helper_jclass,
+        "requestPermissions",
+        "(Landroid/app/Activity;[II)V",
+        &[
+            JValue::Object(activity),
+            JValue::Object(&jtypes),
+            JValue::Int(PERMISSION_REQUEST_CODE),
+        ],
+    )
+    .map_err(|e| PermissionError::Unknown(format!("requestPermissions: {e}")))?;
+
+    permissions
+        .iter()
+        .map(|&permission| Ok((permission, check_with_activity(env, activity, permission)?)))
+        .collect()
+}
+
+/// Open the app's details page in Settings, using the Activity context.
+pub fn open_app_settings_with_activity(
+    env: &mut JNIEnv,
+    activity: &JObject,
+) -> Result<(), PermissionError> {
+    init_with_activity(env, activity)?;
+    let helper_jclass = helper_class(env)?;
+
+    env.call_static_method(
+        helper_jclass,
+        "openAppSettings",
+        "(Landroid/app/Activity;)V",
+        &[JValue::Object(activity)],
+    )
+    .map_err(|e| PermissionError::Unknown(format!("openAppSettings: {e}")))?;
+
+    Ok(())
+}
+
 // Async wrappers for the public API (require runtime context)
-pub(crate) async fn check(permission: Permission) -> PermissionStatus {
+pub async fn check(permission: Permission) -> PermissionStatus {
     // Without JNI context, we can't check permissions
     // The application must call check_with_activity directly
     let _ = permission;
     PermissionStatus::NotDetermined
 }
 
-pub(crate) async fn request(permission: Permission) -> Result<PermissionStatus, PermissionError> {
+pub async fn request(permission: Permission) -> Result<PermissionStatus, PermissionError> {
     // Without JNI context, we can't request permissions
     // The application must use the Android Activity API directly
     let _ = permission;
@@ -179,3 +290,16 @@ pub(crate) async fn request(permission: Permission) -> Result<PermissionStatus,
         "Android: use check_with_activity() with Activity context".into(),
     ))
 }
+
+pub const fn location_accuracy() -> LocationAccuracy {
+    // Unreachable in practice: without JNI context, location is never
+    // reported as granted
+    LocationAccuracy::Full
+}
+
+pub fn open_app_settings() -> Result<(), PermissionError> {
+    // Starting an activity needs the Activity context
+    Err(PermissionError::Unknown(
+        "Android: use open_app_settings_with_activity() with Activity context".into(),
+    ))
+}
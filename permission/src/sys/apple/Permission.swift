@@ -4,6 +4,13 @@ import AVFoundation
 import Photos
 import Contacts
 import EventKit
+import UserNotifications
+import CoreBluetooth
+#if os(iOS)
+import UIKit
+#elseif os(macOS)
+import AppKit
+#endif
 
 // Swift implementations of the functions declared in extern "Swift" block.
 // swift-bridge generates the FFI glue - we just implement the functions.
@@ -22,6 +29,10 @@ func check_permission(_ permission: PermissionType) -> PermissionResult {
         return checkContactsPermission()
     case .Calendar:
         return checkCalendarPermission()
+    case .Notifications:
+        return checkNotificationsPermission()
+    case .Bluetooth:
+        return checkBluetoothPermission()
     }
 }
 
@@ -39,9 +50,29 @@ func request_permission(_ permission: PermissionType) -> PermissionResult {
         return requestContactsPermission()
     case .Calendar:
         return requestCalendarPermission()
+    case .Notifications:
+        return requestNotificationsPermission()
+    case .Bluetooth:
+        return requestBluetoothPermission()
     }
 }
 
+/// Open the app's page in Settings on iOS, or the Privacy & Security pane
+/// of System Settings on macOS, which has no per-app page.
+func open_app_settings() -> Bool {
+    #if os(iOS)
+    guard let url = URL(string: UIApplication.openSettingsURLString) else { return false }
+    DispatchQueue.main.async {
+        UIApplication.shared.open(url)
+    }
+    return true
+    #elseif os(macOS)
+    guard let url = URL(string: "x-apple.systempreferences:com.apple.preference.security?Privacy")
+    else { return false }
+    return NSWorkspace.shared.open(url)
+    #endif
+}
+
 // MARK: - Request Implementations
 
 private func requestLocationPermission() -> PermissionResult {
@@ -118,6 +149,41 @@ private func requestCalendarPermission() -> PermissionResult {
     return result
 }
 
+private func requestNotificationsPermission() -> PermissionResult {
+    let semaphore = DispatchSemaphore(value: 0)
+    var result: PermissionResult = .NotDetermined
+    UNUserNotificationCenter.current().requestAuthorization(options: [.alert, .sound, .badge]) { granted, _ in
+        result = granted ? .Granted : .Denied
+        semaphore.signal()
+    }
+    semaphore.wait()
+    return result
+}
+
+/// Reports the authorization once the manager has settled its state, which
+/// happens after the user answers the prompt.
+private final class BluetoothAuthorizationDelegate: NSObject, CBCentralManagerDelegate {
+    let semaphore = DispatchSemaphore(value: 0)
+
+    func centralManagerDidUpdateState(_ central: CBCentralManager) {
+        semaphore.signal()
+    }
+}
+
+private func requestBluetoothPermission() -> PermissionResult {
+    let current = checkBluetoothPermission()
+    guard current == .NotDetermined else { return current }
+
+    // Creating a central manager is what shows the prompt; its delegate is
+    // called on a background queue so waiting here can't block it
+    let delegate = BluetoothAuthorizationDelegate()
+    let queue = DispatchQueue(label: "waterkit.permission.bluetooth")
+    let manager = CBCentralManager(delegate: delegate, queue: queue)
+    delegate.semaphore.wait()
+    withExtendedLifetime(manager) {}
+    return checkBluetoothPermission()
+}
+
 // MARK: - Location
 
 private func checkLocationPermission() -> PermissionResult {
@@ -136,6 +202,21 @@ private func checkLocationPermission() -> PermissionResult {
     }
 }
 
+/// Whether location was granted as precise or approximate (iOS 14+,
+/// macOS 11+). Only meaningful once location is granted.
+func check_location_accuracy() -> LocationAccuracyResult {
+    // Earlier versions always grant precise location
+    guard #available(iOS 14.0, macOS 11.0, *) else { return .Full }
+    switch CLLocationManager().accuracyAuthorization {
+    case .fullAccuracy:
+        return .Full
+    case .reducedAccuracy:
+        return .Reduced
+    @unknown default:
+        return .Full
+    }
+}
+
 // MARK: - Camera
 
 private func checkCameraPermission() -> PermissionResult {
@@ -225,3 +306,46 @@ private func checkCalendarPermission() -> PermissionResult {
         return .NotDetermined
     }
 }
+
+// MARK: - Notifications
+
+private func checkNotificationsPermission() -> PermissionResult {
+    let semaphore = DispatchSemaphore(value: 0)
+    var result: PermissionResult = .NotDetermined
+    UNUserNotificationCenter.current().getNotificationSettings { settings in
+        switch settings.authorizationStatus {
+        case .notDetermined:
+            result = .NotDetermined
+        case .denied:
+            result = .Denied
+        case .authorized, .provisional:
+            result = .Granted
+        #if os(iOS)
+        case .ephemeral:
+            result = .Granted
+        #endif
+        @unknown default:
+            result = .NotDetermined
+        }
+        semaphore.signal()
+    }
+    semaphore.wait()
+    return result
+}
+
+// MARK: - Bluetooth
+
+private func checkBluetoothPermission() -> PermissionResult {
+    switch CBManager.authorization {
+    case .notDetermined:
+        return .NotDetermined
+    case .restricted:
+        return .Restricted
+    case .denied:
+        return .Denied
+    case .allowedAlways:
+        return .Granted
+    @unknown default:
+        return .NotDetermined
+    }
+}
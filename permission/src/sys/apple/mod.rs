@@ -1,6 +1,6 @@
 //! Apple platform (iOS/macOS) permission implementation using swift-bridge.
 
-use crate::{Permission, PermissionError, PermissionStatus};
+use crate::{LocationAccuracy, Permission, PermissionError, PermissionStatus};
 
 #[swift_bridge::bridge]
 mod ffi {
@@ -12,6 +12,8 @@ mod ffi {
         Photos,
         Contacts,
         Calendar,
+        Notifications,
+        Bluetooth,
     }
 
     enum PermissionResult {
@@ -21,9 +23,16 @@ mod ffi {
         Granted,
     }
 
+    enum LocationAccuracyResult {
+        Full,
+        Reduced,
+    }
+
     extern "Swift" {
         fn check_permission(permission: PermissionType) -> PermissionResult;
         fn request_permission(permission: PermissionType) -> PermissionResult;
+        fn open_app_settings() -> bool;
+        fn check_location_accuracy() -> LocationAccuracyResult;
     }
 }
 
@@ -35,6 +44,8 @@ const fn permission_to_ffi(permission: Permission) -> ffi::PermissionType {
         Permission::Photos => ffi::PermissionType::Photos,
         Permission::Contacts => ffi::PermissionType::Contacts,
         Permission::Calendar => ffi::PermissionType::Calendar,
+        Permission::Notifications => ffi::PermissionType::Notifications,
+        Permission::Bluetooth => ffi::PermissionType::Bluetooth,
     }
 }
 
@@ -61,3 +72,24 @@ pub async fn request(permission: Permission) -> Result<PermissionStatus, Permiss
     let result = ffi::request_permission(permission_to_ffi(permission));
     Ok(status_from_ffi(result))
 }
+
+/// Check the location accuracy the user granted, from
+/// `CLLocationManager.accuracyAuthorization`.
+pub fn location_accuracy() -> LocationAccuracy {
+    match ffi::check_location_accuracy() {
+        ffi::LocationAccuracyResult::Full => LocationAccuracy::Full,
+        ffi::LocationAccuracyResult::Reduced => LocationAccuracy::Reduced,
+    }
+}
+
+/// Open the app's settings on Apple platforms.
+///
+/// # Errors
+/// Returns `PermissionError::Unknown` if the settings could not be opened.
+pub fn open_app_settings() -> Result<(), PermissionError> {
+    if ffi::open_app_settings() {
+        Ok(())
+    } else {
+        Err(PermissionError::Unknown("failed to open settings".into()))
+    }
+}
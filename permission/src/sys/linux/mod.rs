@@ -5,18 +5,28 @@
 //! - Desktop portal systems (Flatpak/Snap sandboxing)
 //! - User groups (e.g., 'video' group for camera access)
 //!
-//! For GeoClue (location), the application just needs to connect to the D-Bus service.
+//! For `GeoClue` (location), the application just needs to connect to the D-Bus service.
 
-use crate::{Permission, PermissionError, PermissionStatus};
+use crate::{LocationAccuracy, Permission, PermissionError, PermissionStatus};
 
-pub(crate) async fn check(_permission: Permission) -> PermissionStatus {
+pub async fn check(_permission: Permission) -> PermissionStatus {
     // Linux permissions are generally handled at the OS/container level
     // Applications typically have access unless sandboxed
     PermissionStatus::Granted
 }
 
-pub(crate) async fn request(_permission: Permission) -> Result<PermissionStatus, PermissionError> {
+pub async fn request(_permission: Permission) -> Result<PermissionStatus, PermissionError> {
     // No runtime permission prompts on traditional Linux
     // Sandboxed apps (Flatpak/Snap) use portals which handle this differently
     Ok(PermissionStatus::Granted)
 }
+
+pub const fn open_app_settings() -> Result<(), PermissionError> {
+    // Desktop environments have no common per-app settings page
+    Err(PermissionError::NotSupported)
+}
+
+pub const fn location_accuracy() -> LocationAccuracy {
+    // GeoClue's accuracy levels are set per app by the desktop, not granted
+    LocationAccuracy::Full
+}
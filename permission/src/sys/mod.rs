@@ -16,16 +16,16 @@ mod linux;
 // Re-export platform implementations
 // Re-export platform implementations
 #[cfg(any(target_os = "ios", target_os = "macos"))]
-pub use apple::{check, request};
+pub use apple::{check, location_accuracy, open_app_settings, request};
 
 #[cfg(target_os = "android")]
-pub use android::{check, request};
+pub use android::{check, location_accuracy, open_app_settings, request};
 
 #[cfg(target_os = "windows")]
-pub use windows::{check, request};
+pub use windows::{check, location_accuracy, open_app_settings, request};
 
 #[cfg(target_os = "linux")]
-pub use linux::{check, request};
+pub use linux::{check, location_accuracy, open_app_settings, request};
 
 // Fallback for unsupported platforms (compile-time stub)
 #[cfg(not(any(
@@ -35,7 +35,7 @@ pub use linux::{check, request};
     target_os = "windows",
     target_os = "linux"
 )))]
-pub(crate) async fn check(_permission: crate::Permission) -> crate::PermissionStatus {
+pub async fn check(_permission: crate::Permission) -> crate::PermissionStatus {
     crate::PermissionStatus::NotDetermined
 }
 
@@ -46,8 +46,30 @@ pub(crate) async fn check(_permission: crate::Permission) -> crate::PermissionSt
     target_os = "windows",
     target_os = "linux"
 )))]
-pub(crate) async fn request(
+pub async fn request(
     _permission: crate::Permission,
 ) -> Result<crate::PermissionStatus, crate::PermissionError> {
     Err(crate::PermissionError::NotSupported)
 }
+
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "linux"
+)))]
+pub fn open_app_settings() -> Result<(), crate::PermissionError> {
+    Err(crate::PermissionError::NotSupported)
+}
+
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "linux"
+)))]
+pub const fn location_accuracy() -> crate::LocationAccuracy {
+    crate::LocationAccuracy::Full
+}
@@ -1,15 +1,15 @@
 //! Windows permission implementation using WinRT.
 
-use crate::{Permission, PermissionError, PermissionStatus};
+use crate::{LocationAccuracy, Permission, PermissionError, PermissionStatus};
 
-pub(crate) async fn check(permission: Permission) -> PermissionStatus {
+pub async fn check(permission: Permission) -> PermissionStatus {
     match permission {
         Permission::Location => check_location().await,
         _ => PermissionStatus::Granted, // Most permissions are implicit on Windows
     }
 }
 
-pub(crate) async fn request(permission: Permission) -> Result<PermissionStatus, PermissionError> {
+pub async fn request(permission: Permission) -> Result<PermissionStatus, PermissionError> {
     match permission {
         Permission::Location => request_location().await,
         _ => Ok(PermissionStatus::Granted),
@@ -37,3 +37,27 @@ async fn request_location() -> Result<PermissionStatus, PermissionError> {
     // On Windows, RequestAccessAsync both checks and requests if needed
     Ok(check_location().await)
 }
+
+pub const fn location_accuracy() -> LocationAccuracy {
+    // The location privacy switch has no approximate setting
+    LocationAccuracy::Full
+}
+
+pub fn open_app_settings() -> Result<(), PermissionError> {
+    use windows::Foundation::Uri;
+    use windows::System::Launcher;
+    use windows::core::HSTRING;
+
+    // Desktop apps have no settings page of their own; the privacy page
+    // lists the per-capability switches
+    let uri = Uri::CreateUri(&HSTRING::from("ms-settings:privacy"))
+        .map_err(|e| PermissionError::Unknown(e.to_string()))?;
+    let launched = Launcher::LaunchUriAsync(&uri)
+        .and_then(|op| op.get())
+        .map_err(|e| PermissionError::Unknown(e.to_string()))?;
+    if launched {
+        Ok(())
+    } else {
+        Err(PermissionError::Unknown("failed to open settings".into()))
+    }
+}
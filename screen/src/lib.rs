@@ -9,6 +9,7 @@
 //!
 //! - **Screen Listing**: Enumerate available monitors and their properties.
 //! - **Screen Capture**: Capture screenshots as PNG-encoded bytes.
+//! - **Window Capture**: Enumerate top-level windows and capture a single one.
 //! - **Brightness Control**: Get and set screen brightness levels.
 //! - **System Picker**: (macOS 14.0+) High-privacy screen/window selection via `ScreenCaptureKit`.
 //!
@@ -26,7 +27,8 @@
 //!
 //! ### macOS
 //! Brightness control for macOS is currently a stub due to downstream dependency limitations.
-//! Screen capture via `capture_screen` requires the "Screen Recording" permission.
+//! Screen capture via `capture_screen` requires the "Screen Recording" permission,
+//! as do `windows` and `capture_window`.
 //! `pick_and_capture` uses the system-provided picker and does not require broad permissions.
 
 mod platform;
@@ -39,13 +41,20 @@ pub enum Error {
     Platform(String),
 
     /// The requested feature is not supported on the current platform.
-    #[error("Unsupported platform or feature")]
-    Unsupported,
+    ///
+    /// The message names the feature, and why it's unavailable where that
+    /// isn't obvious from the platform.
+    #[error("Unsupported: {0}")]
+    Unsupported(&'static str),
 
     /// The specified monitor index was not found.
     #[error("Monitor not found")]
     MonitorNotFound,
 
+    /// The specified window ID was not found.
+    #[error("Window not found")]
+    WindowNotFound,
+
     /// An I/O error occurred during image processing.
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -68,6 +77,28 @@ pub struct ScreenInfo {
     pub is_primary: bool,
 }
 
+/// Information about a top-level window.
+#[derive(Debug, Clone)]
+pub struct WindowInfo {
+    /// A platform-specific identifier for the window, valid while it is open.
+    pub id: u32,
+    /// The window title. May be empty.
+    pub title: String,
+    /// The name of the application that owns the window.
+    pub app_name: String,
+    /// Horizontal position of the left edge, in desktop coordinates.
+    pub x: i32,
+    /// Vertical position of the top edge, in desktop coordinates.
+    pub y: i32,
+    /// Width in pixels (points on macOS).
+    pub width: u32,
+    /// Height in pixels (points on macOS).
+    pub height: u32,
+    /// Whether the window is minimized. On macOS this means it is not on
+    /// screen, which includes windows on other Spaces.
+    pub is_minimized: bool,
+}
+
 /// Capture the screen content as a PNG.
 ///
 /// # Arguments
@@ -112,6 +143,67 @@ pub fn capture_screen_raw(display_index: usize) -> Result<RawCapture, Error> {
     platform::capture_screen_raw(display_index)
 }
 
+/// List the top-level windows, front to back.
+///
+/// # Errors
+///
+/// Returns [`Error::Unsupported`] on iOS and Android, or in a Wayland session
+/// on Linux, or [`Error::Platform`] if the windows can't be listed, such as
+/// without the "Screen Recording" permission on macOS.
+pub fn windows() -> Result<Vec<WindowInfo>, Error> {
+    platform::windows()
+}
+
+/// Capture a single window as a PNG.
+///
+/// * `window_id` - The [`WindowInfo::id`] of the window to capture.
+///
+/// # Errors
+///
+/// Returns [`Error::WindowNotFound`] if no window has the ID,
+/// [`Error::Unsupported`] wherever [`windows`] is, or [`Error::Platform`] if
+/// the window is minimized or has no area, or the capture fails.
+pub fn capture_window(window_id: u32) -> Result<Vec<u8>, Error> {
+    check_capturable(window_id)?;
+    platform::capture_window(window_id)
+}
+
+/// Capture a single window as raw RGBA bytes (no PNG encoding).
+///
+/// * `window_id` - The [`WindowInfo::id`] of the window to capture.
+///
+/// # Errors
+///
+/// Returns [`Error::WindowNotFound`] if no window has the ID,
+/// [`Error::Unsupported`] wherever [`windows`] is, or [`Error::Platform`] if
+/// the window is minimized or has no area, or the capture fails.
+pub fn capture_window_raw(window_id: u32) -> Result<RawCapture, Error> {
+    check_capturable(window_id)?;
+    platform::capture_window_raw(window_id)
+}
+
+/// Fail if the window doesn't exist or has nothing to capture, which
+/// platforms would otherwise report as an empty or stale image.
+fn check_capturable(window_id: u32) -> Result<(), Error> {
+    let window = windows()?
+        .into_iter()
+        .find(|window| window.id == window_id)
+        .ok_or(Error::WindowNotFound)?;
+    if window.is_minimized {
+        return Err(Error::Platform(format!(
+            "window {window_id} ({:?}) is minimized",
+            window.title
+        )));
+    }
+    if window.width == 0 || window.height == 0 {
+        return Err(Error::Platform(format!(
+            "window {window_id} ({:?}) has zero size",
+            window.title
+        )));
+    }
+    Ok(())
+}
+
 /// Re-export `ScreenCapturer` for high-performance repeated captures.
 #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
 pub use platform::desktop::ScreenCapturer;
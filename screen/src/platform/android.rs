@@ -51,7 +51,7 @@ pub fn capture_screen(display_index: usize) -> Result<Vec<u8>, Error> {
     // TODO: Implement using MediaProjection or View snapshotting.
     // This is complex and requires Activity/Permission.
     // For now returning Unsupported.
-    Err(Error::Unsupported)
+    Err(Error::Unsupported("screen capture on Android"))
 }
 
 pub fn screens() -> Result<Vec<ScreenInfo>, Error> {
@@ -146,5 +146,18 @@ pub async fn set_brightness(val: f32) -> Result<(), Error> {
 }
 
 pub async fn pick_and_capture() -> Result<Vec<u8>, Error> {
-    Err(Error::Unsupported)
+    Err(Error::Unsupported("the capture picker on Android"))
+}
+
+// Apps can't see the windows of other apps
+pub fn windows() -> Result<Vec<crate::WindowInfo>, Error> {
+    Err(Error::Unsupported("window capture on Android"))
+}
+
+pub fn capture_window(_window_id: u32) -> Result<Vec<u8>, Error> {
+    Err(Error::Unsupported("window capture on Android"))
+}
+
+pub fn capture_window_raw(_window_id: u32) -> Result<crate::RawCapture, Error> {
+    Err(Error::Unsupported("window capture on Android"))
 }
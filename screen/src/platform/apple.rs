@@ -5,6 +5,19 @@ mod ffi {
     extern "Rust" {
         // Rust types exposed to Swift
         fn on_picker_result(data: Vec<u8>);
+
+        type WindowList;
+        fn push(
+            self: &mut WindowList,
+            id: u32,
+            title: String,
+            app_name: String,
+            x: i32,
+            y: i32,
+            width: u32,
+            height: u32,
+            is_on_screen: bool,
+        );
     }
 
     extern "Swift" {
@@ -31,6 +44,43 @@ mod ffi {
 
         // Control raw frame copying (disable for zero-copy pipelines)
         fn set_raw_frame_capture_enabled(enabled: bool);
+
+        // Window enumeration and capture (macOS 12.3+). Listing returns
+        // false if ScreenCaptureKit can't read the shareable content
+        fn list_windows(windows: &mut WindowList) -> bool;
+        // Width and height (4 bytes LE each) followed by RGBA pixels, or
+        // empty on failure
+        fn capture_window_rgba(window_id: u32) -> Vec<u8>;
+    }
+}
+
+/// Windows reported by Swift.
+#[derive(Default)]
+pub struct WindowList(Vec<crate::WindowInfo>);
+
+impl WindowList {
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        id: u32,
+        title: String,
+        app_name: String,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        is_on_screen: bool,
+    ) {
+        self.0.push(crate::WindowInfo {
+            id,
+            title,
+            app_name,
+            x,
+            y,
+            width,
+            height,
+            is_minimized: !is_on_screen,
+        });
     }
 }
 
@@ -96,7 +146,7 @@ pub fn screens() -> Result<Vec<ScreenInfo>, Error> {
 #[cfg(target_os = "ios")]
 pub async fn pick_and_capture() -> Result<Vec<u8>, Error> {
     // Can implement for iOS later if needed
-    Err(Error::Unsupported)
+    Err(Error::Unsupported("the capture picker on iOS"))
 }
 
 #[cfg(target_os = "macos")]
@@ -120,6 +170,56 @@ pub async fn pick_and_capture() -> Result<Vec<u8>, Error> {
     }
 }
 
+#[cfg(target_os = "ios")]
+pub fn windows() -> Result<Vec<crate::WindowInfo>, Error> {
+    Err(Error::Unsupported("window capture on iOS"))
+}
+
+#[cfg(target_os = "ios")]
+pub fn capture_window(_window_id: u32) -> Result<Vec<u8>, Error> {
+    Err(Error::Unsupported("window capture on iOS"))
+}
+
+#[cfg(target_os = "ios")]
+pub fn capture_window_raw(_window_id: u32) -> Result<crate::RawCapture, Error> {
+    Err(Error::Unsupported("window capture on iOS"))
+}
+
+#[cfg(target_os = "macos")]
+pub fn windows() -> Result<Vec<crate::WindowInfo>, Error> {
+    let mut windows = WindowList::default();
+    if ffi::list_windows(&mut windows) {
+        Ok(windows.0)
+    } else {
+        Err(Error::Platform(
+            "Failed to list windows (is Screen Recording permitted?)".into(),
+        ))
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn capture_window_raw(window_id: u32) -> Result<crate::RawCapture, Error> {
+    let data = ffi::capture_window_rgba(window_id);
+    if data.len() < 8 {
+        return Err(Error::Platform(format!(
+            "Failed to capture window {window_id}"
+        )));
+    }
+
+    let width = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    let height = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    Ok(crate::RawCapture {
+        data: data[8..].to_vec(),
+        width,
+        height,
+    })
+}
+
+#[cfg(target_os = "macos")]
+pub fn capture_window(window_id: u32) -> Result<Vec<u8>, Error> {
+    super::desktop::encode_png(capture_window_raw(window_id)?)
+}
+
 /// High-speed ScreenCaptureKit-based screen capturer (macOS 12.3+).
 ///
 /// Uses `SCStream` for 60fps+ capable frame capture.
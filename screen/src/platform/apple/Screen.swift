@@ -61,3 +61,12 @@ public func capture_main_screen() -> RustVec<UInt8> {
 public func show_picker_and_capture() {
     // Stub for iOS
 }
+
+// iOS apps can't see other windows
+public func list_windows(windows: WindowListRefMut) -> Bool {
+    return false
+}
+
+public func capture_window_rgba(window_id: UInt32) -> RustVec<UInt8> {
+    return RustVec()
+}
@@ -377,3 +377,115 @@ public func get_iosurface_sequence() -> UInt32 {
     defer { frameLock.unlock() }
     return ioSurfaceSequence
 }
+
+// MARK: - Window Enumeration and Capture
+
+/// Fetch the windows and displays that can be captured, waiting for
+/// ScreenCaptureKit's callback.
+@available(macOS 12.3, *)
+fileprivate func shareableContent() -> SCShareableContent? {
+    var result: SCShareableContent? = nil
+    let sem = DispatchSemaphore(value: 0)
+
+    SCShareableContent.getExcludingDesktopWindows(true, onScreenWindowsOnly: false) { content, error in
+        if let error = error {
+            print("Failed to get shareable content: \(error)")
+        }
+        result = content
+        sem.signal()
+    }
+
+    sem.wait()
+    return result
+}
+
+/// List the application windows into `windows`, front to back.
+public func list_windows(windows: WindowListRefMut) -> Bool {
+    guard #available(macOS 12.3, *) else {
+        print("Window listing requires macOS 12.3+")
+        return false
+    }
+    guard let content = shareableContent() else { return false }
+
+    // Layer 0 holds application windows; the others are menus, the Dock and overlays
+    for window in content.windows where window.windowLayer == 0 {
+        let frame = window.frame
+        windows.push(
+            window.windowID,
+            window.title ?? "",
+            window.owningApplication?.applicationName ?? "",
+            Int32(frame.origin.x),
+            Int32(frame.origin.y),
+            UInt32(max(frame.width, 0)),
+            UInt32(max(frame.height, 0)),
+            window.isOnScreen
+        )
+    }
+    return true
+}
+
+/// Capture a single window at its backing resolution, with the frame layout
+/// `capture_window_raw` expects: width and height LE, then RGBA pixels.
+public func capture_window_rgba(window_id: UInt32) -> RustVec<UInt8> {
+    guard #available(macOS 14.0, *) else {
+        // Before macOS 14, ScreenCaptureKit can only stream
+        print("Window capture requires macOS 14.0+")
+        return RustVec()
+    }
+    guard let content = shareableContent(),
+          let window = content.windows.first(where: { $0.windowID == window_id }) else {
+        return RustVec()
+    }
+
+    let filter = SCContentFilter(desktopIndependentWindow: window)
+    let config = SCStreamConfiguration()
+    config.width = Int(filter.contentRect.width * CGFloat(filter.pointPixelScale))
+    config.height = Int(filter.contentRect.height * CGFloat(filter.pointPixelScale))
+    config.showsCursor = false
+
+    var captured: CGImage? = nil
+    let sem = DispatchSemaphore(value: 0)
+    SCScreenshotManager.captureImage(contentFilter: filter, configuration: config) { image, error in
+        if let error = error {
+            print("Window capture failed: \(error)")
+        }
+        captured = image
+        sem.signal()
+    }
+    sem.wait()
+
+    guard let image = captured else { return RustVec() }
+    return rgbaFrame(image)
+}
+
+/// Draw `image` into an RGBA buffer behind an 8-byte size header.
+fileprivate func rgbaFrame(_ image: CGImage) -> RustVec<UInt8> {
+    let width = image.width
+    let height = image.height
+    var rawData = [UInt8](repeating: 0, count: width * height * 4)
+
+    let colorSpace = CGColorSpaceCreateDeviceRGB()
+    let bitmapInfo = CGImageAlphaInfo.premultipliedLast.rawValue
+    guard let context = CGContext(data: &rawData,
+                                  width: width,
+                                  height: height,
+                                  bitsPerComponent: 8,
+                                  bytesPerRow: width * 4,
+                                  space: colorSpace,
+                                  bitmapInfo: bitmapInfo) else {
+        return RustVec()
+    }
+    context.draw(image, in: CGRect(x: 0, y: 0, width: width, height: height))
+
+    let vec = RustVec<UInt8>()
+    for value in [UInt32(width), UInt32(height)] {
+        vec.push(value: UInt8(value & 0xFF))
+        vec.push(value: UInt8((value >> 8) & 0xFF))
+        vec.push(value: UInt8((value >> 16) & 0xFF))
+        vec.push(value: UInt8((value >> 24) & 0xFF))
+    }
+    for byte in rawData {
+        vec.push(value: byte)
+    }
+    return vec
+}
@@ -1,4 +1,4 @@
-use crate::{Error, ScreenInfo};
+use crate::{Error, RawCapture, ScreenInfo};
 use std::io::Cursor;
 // use brightness::Brightness; // Removed due to build failure
 
@@ -123,5 +123,88 @@ pub async fn set_brightness(_val: f32) -> Result<(), Error> {
 #[cfg(not(target_os = "macos"))]
 #[allow(clippy::unused_async)]
 pub async fn pick_and_capture() -> Result<Vec<u8>, Error> {
-    Err(Error::Unsupported)
+    Err(Error::Unsupported("the capture picker outside macOS"))
+}
+
+/// Encode a raw RGBA capture as a PNG.
+pub fn encode_png(capture: RawCapture) -> Result<Vec<u8>, Error> {
+    let image =
+        screenshots::image::RgbaImage::from_raw(capture.width, capture.height, capture.data)
+            .ok_or_else(|| Error::Platform("Capture size doesn't match its pixels".into()))?;
+
+    let mut buffer = Vec::new();
+    image
+        .write_to(
+            &mut Cursor::new(&mut buffer),
+            screenshots::image::ImageFormat::Png,
+        )
+        .map_err(|e| Error::Platform(e.to_string()))?;
+    Ok(buffer)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn window_info(window: &xcap::Window) -> xcap::XCapResult<crate::WindowInfo> {
+    Ok(crate::WindowInfo {
+        id: window.id()?,
+        title: window.title()?,
+        app_name: window.app_name()?,
+        x: window.x()?,
+        y: window.y()?,
+        width: window.width()?,
+        height: window.height()?,
+        is_minimized: window.is_minimized()?,
+    })
+}
+
+// xcap lists windows through X11 on Linux. A Wayland compositor doesn't let
+// apps see other windows at all, and under XWayland xcap would only find the
+// X11 clients, so refuse the session outright.
+#[cfg(not(target_os = "macos"))]
+fn all_windows() -> Result<Vec<xcap::Window>, Error> {
+    #[cfg(target_os = "linux")]
+    if std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|session| session == "wayland")
+    {
+        return Err(Error::Unsupported(
+            "window listing and capture under Wayland, which doesn't expose other \
+             windows to apps; run in an X11 session instead",
+        ));
+    }
+    xcap::Window::all().map_err(|e| Error::Platform(e.to_string()))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn windows() -> Result<Vec<crate::WindowInfo>, Error> {
+    let windows = all_windows()?;
+    // Windows can close while they are listed; leave those out
+    Ok(windows
+        .iter()
+        .filter_map(|window| window_info(window).ok())
+        .collect())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn capture_window_raw(window_id: u32) -> Result<RawCapture, Error> {
+    let windows = all_windows()?;
+    let window = windows
+        .into_iter()
+        .find(|window| window.id().is_ok_and(|id| id == window_id))
+        .ok_or(Error::WindowNotFound)?;
+
+    let image = window
+        .capture_image()
+        .map_err(|e| Error::Platform(e.to_string()))?;
+    let width = image.width();
+    let height = image.height();
+
+    Ok(RawCapture {
+        data: image.into_raw(),
+        width,
+        height,
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn capture_window(window_id: u32) -> Result<Vec<u8>, Error> {
+    encode_png(capture_window_raw(window_id)?)
 }
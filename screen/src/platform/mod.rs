@@ -46,28 +46,37 @@ pub use android::*;
     target_os = "android"
 )))]
 mod dummy {
-    use crate::{Error, RawCapture, ScreenInfo};
+    use crate::{Error, RawCapture, ScreenInfo, WindowInfo};
 
     pub fn capture_screen(_idx: usize) -> Result<Vec<u8>, Error> {
-        Err(Error::Unsupported)
+        Err(Error::Unsupported("screen capture on this platform"))
     }
     pub fn capture_screen_raw(_idx: usize) -> Result<RawCapture, Error> {
-        Err(Error::Unsupported)
+        Err(Error::Unsupported("screen capture on this platform"))
     }
     #[allow(clippy::unused_async)]
     pub async fn pick_and_capture() -> Result<Vec<u8>, Error> {
-        Err(Error::Unsupported)
+        Err(Error::Unsupported("screen capture on this platform"))
     }
     #[allow(clippy::unused_async)]
     pub async fn get_brightness() -> Result<f32, Error> {
-        Err(Error::Unsupported)
+        Err(Error::Unsupported("screen capture on this platform"))
     }
     #[allow(clippy::unused_async)]
     pub async fn set_brightness(_val: f32) -> Result<(), Error> {
-        Err(Error::Unsupported)
+        Err(Error::Unsupported("screen capture on this platform"))
     }
     pub fn screens() -> Result<Vec<ScreenInfo>, Error> {
-        Err(Error::Unsupported)
+        Err(Error::Unsupported("screen capture on this platform"))
+    }
+    pub fn windows() -> Result<Vec<WindowInfo>, Error> {
+        Err(Error::Unsupported("screen capture on this platform"))
+    }
+    pub fn capture_window(_window_id: u32) -> Result<Vec<u8>, Error> {
+        Err(Error::Unsupported("screen capture on this platform"))
+    }
+    pub fn capture_window_raw(_window_id: u32) -> Result<RawCapture, Error> {
+        Err(Error::Unsupported("screen capture on this platform"))
     }
 }
 #[cfg(not(any(
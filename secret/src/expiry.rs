@@ -0,0 +1,34 @@
+//! Expiry timestamps for secrets saved with a TTL.
+//!
+//! The expiry of a secret is stored as a separate entry with the same account
+//! under `{service}:expiry`, holding the unix time in milliseconds. This works
+//! with every backend and keeps [`SecretManager::list_accounts`](crate::SecretManager::list_accounts)
+//! for `service` unaffected. The Android context APIs keep expiries in a
+//! separate SharedPreferences file instead.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Service of the entries holding the expiry of secrets stored under `service`.
+pub fn service(service: &str) -> String {
+    format!("{service}:expiry")
+}
+
+/// Serialize an expiry time for storage.
+pub fn encode(expires_at: SystemTime) -> String {
+    let millis = expires_at
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    millis.to_string()
+}
+
+/// Parse an expiry time written by [`encode`].
+pub fn decode(value: &str) -> Option<SystemTime> {
+    let millis = value.parse().ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_millis(millis))
+}
+
+/// Whether a secret with this expiry should no longer be returned.
+pub fn is_expired(expires_at: Option<SystemTime>) -> bool {
+    expires_at.is_some_and(|expires_at| expires_at <= SystemTime::now())
+}
@@ -5,9 +5,17 @@
 
 #![warn(missing_docs)]
 
+/// Expiry timestamps for secrets saved with a TTL.
+mod expiry;
 /// Platform-specific implementations.
 mod sys;
 
+use std::time::{Duration, SystemTime};
+
+/// Android APIs that take a JNI environment and Context.
+#[cfg(target_os = "android")]
+pub use sys::android;
+
 /// Errors that can occur when accessing secrets.
 #[derive(Debug, thiserror::Error)]
 pub enum SecretError {
@@ -25,6 +33,31 @@ pub enum SecretError {
     InvalidInput(String),
 }
 
+/// How the user must authenticate to read a protected secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessPolicy {
+    /// Any enrolled biometric, including ones enrolled after the secret was saved.
+    BiometricAny,
+    /// Only the biometrics enrolled when the secret was saved.
+    ///
+    /// Enrolling a new fingerprint or face makes the secret unreadable.
+    BiometricCurrentSet,
+    /// The device passcode, PIN or pattern.
+    DevicePasscode,
+}
+
+impl AccessPolicy {
+    /// Code passed to the native helpers.
+    #[cfg(any(target_os = "ios", target_os = "macos", target_os = "android"))]
+    pub(crate) const fn code(self) -> u8 {
+        match self {
+            Self::BiometricAny => 0,
+            Self::BiometricCurrentSet => 1,
+            Self::DevicePasscode => 2,
+        }
+    }
+}
+
 /// A manager for secure secret storage.
 #[derive(Debug)]
 pub struct SecretManager;
@@ -32,6 +65,10 @@ pub struct SecretManager;
 impl SecretManager {
     /// Save a secret.
     ///
+    /// This is an upsert: an existing secret for the same service and account
+    /// is replaced, never duplicated, so a later [`get`](Self::get) returns the
+    /// latest value.
+    ///
     /// # Errors
     /// Returns a `SecretError` if:
     /// - The service name is empty.
@@ -40,7 +77,42 @@ impl SecretManager {
         if service.is_empty() {
             return Err(SecretError::InvalidInput("service cannot be empty".into()));
         }
-        sys::set(service, account, password).await
+        sys::set(service, account, password).await?;
+        // The new value doesn't inherit the expiry of the one it replaces
+        sys::delete(&expiry::service(service), account).await
+    }
+
+    /// Save a secret that expires after `ttl`.
+    ///
+    /// Once the TTL has elapsed, [`get`](Self::get) returns
+    /// [`SecretError::NotFound`] and the secret is deleted. The expiry is
+    /// stored as a separate entry next to the secret; saving the secret
+    /// again with [`set`](Self::set) removes it.
+    ///
+    /// # Errors
+    /// Returns a `SecretError` if:
+    /// - The service name is empty.
+    /// - The TTL is too large to represent.
+    /// - The underlying system storage fails.
+    pub async fn set_with_expiry(
+        service: &str,
+        account: &str,
+        password: &str,
+        ttl: Duration,
+    ) -> Result<(), SecretError> {
+        if service.is_empty() {
+            return Err(SecretError::InvalidInput("service cannot be empty".into()));
+        }
+        let expires_at = SystemTime::now()
+            .checked_add(ttl)
+            .ok_or_else(|| SecretError::InvalidInput("ttl is too large".into()))?;
+        sys::set(service, account, password).await?;
+        sys::set(
+            &expiry::service(service),
+            account,
+            &expiry::encode(expires_at),
+        )
+        .await
     }
 
     /// Retrieve a secret.
@@ -48,13 +120,178 @@ impl SecretManager {
     /// # Errors
     /// Returns a `SecretError` if:
     /// - The service name is empty.
-    /// - The secret is not found.
+    /// - The secret is not found or has expired.
     /// - The underlying system storage fails.
     pub async fn get(service: &str, account: &str) -> Result<String, SecretError> {
+        Self::get_with_metadata(service, account)
+            .await
+            .map(|(password, _)| password)
+    }
+
+    /// Retrieve a secret and its expiry.
+    ///
+    /// The expiry is `None` for secrets saved without a TTL.
+    ///
+    /// # Errors
+    /// Returns a `SecretError` if:
+    /// - The service name is empty.
+    /// - The secret is not found or has expired.
+    /// - The underlying system storage fails.
+    pub async fn get_with_metadata(
+        service: &str,
+        account: &str,
+    ) -> Result<(String, Option<SystemTime>), SecretError> {
+        if service.is_empty() {
+            return Err(SecretError::InvalidInput("service cannot be empty".into()));
+        }
+        let password = sys::get(service, account).await?;
+        let expires_at = Self::expiry(service, account).await?;
+        if expiry::is_expired(expires_at) {
+            let _ = Self::delete(service, account).await;
+            return Err(SecretError::NotFound);
+        }
+        Ok((password, expires_at))
+    }
+
+    /// Read the expiry stored for a secret, if any.
+    async fn expiry(service: &str, account: &str) -> Result<Option<SystemTime>, SecretError> {
+        match sys::get(&expiry::service(service), account).await {
+            Ok(value) => Ok(expiry::decode(&value)),
+            Err(SecretError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Check whether a secret is stored for `service` and `account`.
+    ///
+    /// The secret itself isn't read, so this never prompts for authentication,
+    /// including for secrets saved with [`set_protected`](Self::set_protected).
+    ///
+    /// # Errors
+    /// Returns a `SecretError` if:
+    /// - The service name is empty.
+    /// - The underlying system storage fails.
+    pub async fn exists(service: &str, account: &str) -> Result<bool, SecretError> {
+        if service.is_empty() {
+            return Err(SecretError::InvalidInput("service cannot be empty".into()));
+        }
+        if !sys::exists(service, account).await? {
+            return Ok(false);
+        }
+        Ok(!expiry::is_expired(Self::expiry(service, account).await?))
+    }
+
+    /// Save a secret that can only be read after the user authenticates.
+    ///
+    /// `policy` chooses how. The user is prompted with `reason` before the
+    /// secret is stored. On iOS and macOS the keychain item is created with
+    /// `SecAccessControlCreateWithFlags`. On Android it is encrypted with a
+    /// Keystore key created with `setUserAuthenticationRequired(true)`.
+    /// Read it back with [`get_protected`](Self::get_protected).
+    ///
+    /// # Errors
+    /// Returns a `SecretError` if:
+    /// - The service name is empty.
+    /// - Authentication fails (`PermissionDenied`).
+    /// - The platform doesn't support the policy.
+    /// - The underlying system storage fails.
+    pub async fn set_protected(
+        service: &str,
+        account: &str,
+        password: &str,
+        policy: AccessPolicy,
+        reason: &str,
+    ) -> Result<(), SecretError> {
+        if service.is_empty() {
+            return Err(SecretError::InvalidInput("service cannot be empty".into()));
+        }
+        sys::set_protected(service, account, password, policy, reason).await?;
+        sys::delete(&expiry::service(service), account).await
+    }
+
+    /// Retrieve a secret saved with [`set_protected`](Self::set_protected).
+    ///
+    /// The user is prompted with `reason` and must satisfy the policy the
+    /// secret was saved with before it is decrypted.
+    ///
+    /// # Errors
+    /// Returns a `SecretError` if:
+    /// - The service name is empty.
+    /// - The secret is not found.
+    /// - Authentication fails (`PermissionDenied`).
+    /// - The underlying system storage fails.
+    pub async fn get_protected(
+        service: &str,
+        account: &str,
+        reason: &str,
+    ) -> Result<String, SecretError> {
+        if service.is_empty() {
+            return Err(SecretError::InvalidInput("service cannot be empty".into()));
+        }
+        sys::get_protected(service, account, reason).await
+    }
+
+    /// Save a secret that can only be read after biometric authentication.
+    ///
+    /// Shorthand for [`set_protected`](Self::set_protected) with
+    /// [`AccessPolicy::BiometricCurrentSet`], so enrolling a new biometric
+    /// makes the secret unreadable. Read it back with
+    /// [`get_with_biometric`](Self::get_with_biometric).
+    ///
+    /// # Errors
+    /// Returns a `SecretError` if:
+    /// - The service name is empty.
+    /// - Biometric authentication fails (`PermissionDenied`).
+    /// - The platform doesn't support biometric protection.
+    /// - The underlying system storage fails.
+    pub async fn set_with_biometric(
+        service: &str,
+        account: &str,
+        password: &str,
+        reason: &str,
+    ) -> Result<(), SecretError> {
+        Self::set_protected(
+            service,
+            account,
+            password,
+            AccessPolicy::BiometricCurrentSet,
+            reason,
+        )
+        .await
+    }
+
+    /// Retrieve a secret saved with [`set_with_biometric`](Self::set_with_biometric).
+    ///
+    /// The user is prompted with `reason` and the secret is only decrypted
+    /// after biometric authentication succeeds.
+    ///
+    /// # Errors
+    /// Returns a `SecretError` if:
+    /// - The service name is empty.
+    /// - The secret is not found.
+    /// - Biometric authentication fails (`PermissionDenied`).
+    /// - The underlying system storage fails.
+    pub async fn get_with_biometric(
+        service: &str,
+        account: &str,
+        reason: &str,
+    ) -> Result<String, SecretError> {
+        Self::get_protected(service, account, reason).await
+    }
+
+    /// List the accounts that have a secret stored under `service`.
+    ///
+    /// Returns an empty list when no accounts are stored.
+    ///
+    /// # Errors
+    /// Returns a `SecretError` if:
+    /// - The service name is empty.
+    /// - The underlying system storage fails.
+    pub async fn list_accounts(service: &str) -> Result<Vec<String>, SecretError> {
         if service.is_empty() {
             return Err(SecretError::InvalidInput("service cannot be empty".into()));
         }
-        sys::get(service, account).await
+        sys::list_accounts(service).await
     }
 
     /// Delete a secret.
@@ -67,6 +304,7 @@ impl SecretManager {
         if service.is_empty() {
             return Err(SecretError::InvalidInput("service cannot be empty".into()));
         }
-        sys::delete(service, account).await
+        sys::delete(service, account).await?;
+        sys::delete(&expiry::service(service), account).await
     }
 }
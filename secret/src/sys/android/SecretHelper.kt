@@ -0,0 +1,233 @@
+package waterkit.secret
+
+import android.content.Context
+import android.content.SharedPreferences
+import android.hardware.biometrics.BiometricManager
+import android.hardware.biometrics.BiometricPrompt
+import android.os.Build
+import android.os.CancellationSignal
+import android.os.Handler
+import android.os.Looper
+import android.security.keystore.KeyGenParameterSpec
+import android.security.keystore.KeyPermanentlyInvalidatedException
+import android.security.keystore.KeyProperties
+import android.util.Base64
+import java.security.GeneralSecurityException
+import java.security.KeyStore
+import java.util.concurrent.Executor
+import javax.crypto.Cipher
+import javax.crypto.KeyGenerator
+import javax.crypto.SecretKey
+import javax.crypto.spec.GCMParameterSpec
+
+/**
+ * Secrets encrypted with an Android Keystore key that requires biometric or
+ * device credential authentication for every use. The ciphertext lives in SharedPreferences.
+ * Compiled to DEX and embedded in the Rust library.
+ */
+object SecretHelper {
+    private const val PREFS = "waterkit_biometric_secrets"
+    // Written by the Rust side for secrets without biometric protection
+    private const val PLAIN_PREFS = "waterkit_secrets"
+    private const val KEYSTORE = "AndroidKeyStore"
+    private const val TRANSFORMATION = "AES/GCM/NoPadding"
+    private const val TAG_BITS = 128
+
+    // Result codes passed to onResult
+    private const val RESULT_OK = 0
+    private const val RESULT_NOT_FOUND = 1
+    private const val RESULT_PERMISSION_DENIED = 2
+    private const val RESULT_SYSTEM = 3
+
+    // AccessPolicy codes passed by Rust; 0 is BiometricAny
+    private const val POLICY_BIOMETRIC_CURRENT_SET = 1
+    private const val POLICY_DEVICE_PASSCODE = 2
+
+    /** Receives the outcome of a request; [value] is the secret or an error message. */
+    @JvmStatic
+    external fun onResult(requestId: Long, code: Int, value: String?)
+
+    /**
+     * Encrypt [value] under a new key bound to [policy] after the user authenticates.
+     */
+    @JvmStatic
+    fun setProtected(context: Context, key: String, value: String, policy: Int, reason: String, requestId: Long) {
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.P) {
+            onResult(requestId, RESULT_SYSTEM, "Protected secrets require Android 9 (API 28)")
+            return
+        }
+        if (policy == POLICY_DEVICE_PASSCODE && Build.VERSION.SDK_INT < Build.VERSION_CODES.R) {
+            onResult(requestId, RESULT_SYSTEM, "Passcode-protected secrets require Android 11 (API 30)")
+            return
+        }
+
+        val cipher = try {
+            val secretKey = createKey(alias(key), policy)
+            Cipher.getInstance(TRANSFORMATION).apply { init(Cipher.ENCRYPT_MODE, secretKey) }
+        } catch (e: GeneralSecurityException) {
+            // Also raised when no biometric is enrolled
+            onResult(requestId, RESULT_SYSTEM, e.message ?: "Failed to create key")
+            return
+        }
+
+        authenticate(context, reason, policy, cipher, requestId) { authenticated ->
+            val encrypted = authenticated.doFinal(value.toByteArray(Charsets.UTF_8))
+            val stored = encode(authenticated.iv) + ":" + encode(encrypted) + ":" + policy
+            prefs(context).edit().putString(key, stored).apply()
+            onResult(requestId, RESULT_OK, null)
+        }
+    }
+
+    /**
+     * Decrypt the secret stored by [setProtected] after the user authenticates.
+     */
+    @JvmStatic
+    fun getProtected(context: Context, key: String, reason: String, requestId: Long) {
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.P) {
+            onResult(requestId, RESULT_SYSTEM, "Protected secrets require Android 9 (API 28)")
+            return
+        }
+
+        val parts = prefs(context).getString(key, null)?.split(":")
+        val keyStore = KeyStore.getInstance(KEYSTORE).apply { load(null) }
+        val secretKey = keyStore.getKey(alias(key), null) as? SecretKey
+        if (parts == null || parts.size !in 2..3 || secretKey == null) {
+            onResult(requestId, RESULT_NOT_FOUND, "Secret not found")
+            return
+        }
+        // Secrets stored before policies were recorded are biometric-only
+        val policy = parts.getOrNull(2)?.toIntOrNull() ?: POLICY_BIOMETRIC_CURRENT_SET
+
+        val cipher = try {
+            Cipher.getInstance(TRANSFORMATION).apply {
+                init(Cipher.DECRYPT_MODE, secretKey, GCMParameterSpec(TAG_BITS, decode(parts[0])))
+            }
+        } catch (e: KeyPermanentlyInvalidatedException) {
+            // Biometric enrollment changed since the secret was stored
+            onResult(requestId, RESULT_PERMISSION_DENIED, e.message ?: "Key invalidated")
+            return
+        } catch (e: GeneralSecurityException) {
+            onResult(requestId, RESULT_SYSTEM, e.message ?: "Failed to init cipher")
+            return
+        }
+
+        authenticate(context, reason, policy, cipher, requestId) { authenticated ->
+            val decrypted = authenticated.doFinal(decode(parts[1]))
+            onResult(requestId, RESULT_OK, String(decrypted, Charsets.UTF_8))
+        }
+    }
+
+    /** Remove a secret stored by [setProtected] and its key. */
+    @JvmStatic
+    fun deleteBiometric(context: Context, key: String) {
+        prefs(context).edit().remove(key).apply()
+        try {
+            KeyStore.getInstance(KEYSTORE).apply { load(null) }.deleteEntry(alias(key))
+        } catch (e: GeneralSecurityException) {
+            // Nothing to delete
+        }
+    }
+
+    /**
+     * Accounts stored under [service], with or without biometric protection.
+     * Keys in both preference files are `service:account`.
+     */
+    @JvmStatic
+    fun listAccounts(context: Context, service: String): Array<String> {
+        val prefix = "$service:"
+        return listOf(context.getSharedPreferences(PLAIN_PREFS, Context.MODE_PRIVATE), prefs(context))
+            .flatMap { it.all.keys }
+            .filter { it.startsWith(prefix) }
+            .map { it.removePrefix(prefix) }
+            .distinct()
+            .sorted()
+            .toTypedArray()
+    }
+
+    private fun authenticate(
+        context: Context,
+        reason: String,
+        policy: Int,
+        cipher: Cipher,
+        requestId: Long,
+        onAuthenticated: (Cipher) -> Unit
+    ) {
+        val mainHandler = Handler(Looper.getMainLooper())
+        mainHandler.post {
+            try {
+                val executor = Executor { command -> mainHandler.post(command) }
+
+                val builder = BiometricPrompt.Builder(context)
+                    .setTitle("Authentication Required")
+                    .setDescription(reason)
+                if (policy == POLICY_DEVICE_PASSCODE) {
+                    // The system provides its own cancel action for credential prompts
+                    builder.setAllowedAuthenticators(BiometricManager.Authenticators.DEVICE_CREDENTIAL)
+                } else {
+                    if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.R) {
+                        builder.setAllowedAuthenticators(BiometricManager.Authenticators.BIOMETRIC_STRONG)
+                    }
+                    builder.setNegativeButton("Cancel", executor) { _, _ ->
+                        onResult(requestId, RESULT_PERMISSION_DENIED, "Cancelled by user")
+                    }
+                }
+                val prompt = builder.build()
+
+                prompt.authenticate(
+                    BiometricPrompt.CryptoObject(cipher),
+                    CancellationSignal(),
+                    executor,
+                    object : BiometricPrompt.AuthenticationCallback() {
+                        override fun onAuthenticationSucceeded(result: BiometricPrompt.AuthenticationResult) {
+                            val authenticated = result.cryptoObject?.cipher ?: cipher
+                            try {
+                                onAuthenticated(authenticated)
+                            } catch (e: GeneralSecurityException) {
+                                onResult(requestId, RESULT_SYSTEM, e.message ?: "Cipher failed")
+                            }
+                        }
+
+                        override fun onAuthenticationError(errorCode: Int, errString: CharSequence) {
+                            onResult(requestId, RESULT_PERMISSION_DENIED, errString.toString())
+                        }
+                    }
+                )
+            } catch (e: Exception) {
+                onResult(requestId, RESULT_SYSTEM, e.message ?: "Unknown error")
+            }
+        }
+    }
+
+    private fun createKey(alias: String, policy: Int): SecretKey {
+        val builder = KeyGenParameterSpec.Builder(
+            alias,
+            KeyProperties.PURPOSE_ENCRYPT or KeyProperties.PURPOSE_DECRYPT
+        )
+            .setBlockModes(KeyProperties.BLOCK_MODE_GCM)
+            .setEncryptionPaddings(KeyProperties.ENCRYPTION_PADDING_NONE)
+            .setUserAuthenticationRequired(true)
+            .setInvalidatedByBiometricEnrollment(policy == POLICY_BIOMETRIC_CURRENT_SET)
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.R) {
+            val authenticators = if (policy == POLICY_DEVICE_PASSCODE) {
+                KeyProperties.AUTH_DEVICE_CREDENTIAL
+            } else {
+                KeyProperties.AUTH_BIOMETRIC_STRONG
+            }
+            builder.setUserAuthenticationParameters(0, authenticators)
+        }
+
+        return KeyGenerator.getInstance(KeyProperties.KEY_ALGORITHM_AES, KEYSTORE).run {
+            init(builder.build())
+            generateKey()
+        }
+    }
+
+    private fun alias(key: String) = "waterkit_secret:$key"
+
+    private fun prefs(context: Context): SharedPreferences =
+        context.getSharedPreferences(PREFS, Context.MODE_PRIVATE)
+
+    private fun encode(bytes: ByteArray) = Base64.encodeToString(bytes, Base64.NO_WRAP)
+
+    private fun decode(text: String) = Base64.decode(text, Base64.NO_WRAP)
+}
@@ -1,6 +1,12 @@
-use crate::SecretError;
+use crate::{AccessPolicy, SecretError, expiry};
 use jni::JNIEnv;
-use jni::objects::{JObject, JString, JValue};
+use jni::objects::{GlobalRef, JClass, JObject, JObjectArray, JString, JValue};
+use jni::sys::{jint, jlong};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
 
 /// Helper to attach thread and get JNIEnv, but since our API is async and typically
 /// waterkit passes context explicitly or assumes a thread-local JNI env is not available,
@@ -44,19 +50,94 @@ pub async fn delete(_service: &str, _account: &str) -> Result<(), SecretError> {
     ))
 }
 
+/// Check whether a secret exists (stub, use `exists_with_context`).
+pub async fn exists(_service: &str, _account: &str) -> Result<bool, SecretError> {
+    Err(SecretError::System(
+        "On Android, use `waterkit_secret::android::exists_with_context`".into(),
+    ))
+}
+
+/// List stored accounts (stub, use `list_accounts_with_context`).
+pub async fn list_accounts(_service: &str) -> Result<Vec<String>, SecretError> {
+    Err(SecretError::System(
+        "On Android, use `waterkit_secret::android::list_accounts_with_context`".into(),
+    ))
+}
+
+/// SharedPreferences file holding secrets without biometric protection.
+const SECRETS: &str = "waterkit_secrets";
+/// SharedPreferences file holding the expiry of secrets saved with a TTL.
+const EXPIRY: &str = "waterkit_secret_expiry";
+
 /// Android-specific API
+///
+/// Overwrites any secret already stored for `service` and `account`,
+/// including its expiry.
 pub fn set_with_context(
     env: &mut JNIEnv,
     context: &JObject,
     service: &str,
     account: &str,
     password: &str,
+) -> Result<(), SecretError> {
+    write(env, context, SECRETS, service, account, password)?;
+    remove(env, context, EXPIRY, service, account)
+}
+
+/// Save a secret that expires after `ttl` using Android Context.
+///
+/// Once the TTL has elapsed, [`get_with_context`] returns
+/// `SecretError::NotFound` and the secret is deleted.
+pub fn set_with_expiry_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    service: &str,
+    account: &str,
+    password: &str,
+    ttl: Duration,
+) -> Result<(), SecretError> {
+    let expires_at = SystemTime::now()
+        .checked_add(ttl)
+        .ok_or_else(|| SecretError::InvalidInput("ttl is too large".into()))?;
+    write(env, context, SECRETS, service, account, password)?;
+    write(
+        env,
+        context,
+        EXPIRY,
+        service,
+        account,
+        &expiry::encode(expires_at),
+    )
+}
+
+/// Read the expiry stored for a secret, if any.
+fn read_expiry(
+    env: &mut JNIEnv,
+    context: &JObject,
+    service: &str,
+    account: &str,
+) -> Result<Option<SystemTime>, SecretError> {
+    match read(env, context, EXPIRY, service, account) {
+        Ok(value) => Ok(expiry::decode(&value)),
+        Err(SecretError::NotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Store `password` under `service:account` in the SharedPreferences file `prefs_name`.
+fn write(
+    env: &mut JNIEnv,
+    context: &JObject,
+    prefs_name: &str,
+    service: &str,
+    account: &str,
+    password: &str,
 ) -> Result<(), SecretError> {
     let ctx = context;
 
-    // context.getSharedPreferences("waterkit_secrets", Context.MODE_PRIVATE)
+    // context.getSharedPreferences(prefs_name, Context.MODE_PRIVATE)
     let prefs_name = env
-        .new_string("waterkit_secrets")
+        .new_string(prefs_name)
         .map_err(|e| SecretError::System(e.to_string()))?;
 
     let prefs = env
@@ -114,9 +195,39 @@ pub fn get_with_context(
     context: &JObject,
     service: &str,
     account: &str,
+) -> Result<String, SecretError> {
+    get_with_metadata_with_context(env, context, service, account).map(|(password, _)| password)
+}
+
+/// Retrieve a secret and its expiry using Android Context.
+///
+/// The expiry is `None` for secrets saved without a TTL. Expired secrets
+/// are deleted and reported as `SecretError::NotFound`.
+pub fn get_with_metadata_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    service: &str,
+    account: &str,
+) -> Result<(String, Option<SystemTime>), SecretError> {
+    let password = read(env, context, SECRETS, service, account)?;
+    let expires_at = read_expiry(env, context, service, account)?;
+    if expiry::is_expired(expires_at) {
+        let _ = delete_with_context(env, context, service, account);
+        return Err(SecretError::NotFound);
+    }
+    Ok((password, expires_at))
+}
+
+/// Read the value stored under `service:account` in the SharedPreferences file `prefs_name`.
+fn read(
+    env: &mut JNIEnv,
+    context: &JObject,
+    prefs_name: &str,
+    service: &str,
+    account: &str,
 ) -> Result<String, SecretError> {
     let prefs_name = env
-        .new_string("waterkit_secrets")
+        .new_string(prefs_name)
         .map_err(|e| SecretError::System(e.to_string()))?;
 
     let prefs = env
@@ -166,9 +277,37 @@ pub fn delete_with_context(
     context: &JObject,
     service: &str,
     account: &str,
+) -> Result<(), SecretError> {
+    remove(env, context, SECRETS, service, account)?;
+    remove(env, context, EXPIRY, service, account)?;
+
+    // Also remove a secret stored with biometric protection
+    init(env, context)?;
+    let key = env
+        .new_string(format!("{service}:{account}"))
+        .map_err(|e| SecretError::System(e.to_string()))?;
+    let class = get_helper_class(env)?;
+    env.call_static_method(
+        class,
+        "deleteBiometric",
+        "(Landroid/content/Context;Ljava/lang/String;)V",
+        &[JValue::Object(context), JValue::Object(&key)],
+    )
+    .map_err(|e| SecretError::System(format!("deleteBiometric: {e}")))?;
+
+    Ok(())
+}
+
+/// Remove `service:account` from the SharedPreferences file `prefs_name`.
+fn remove(
+    env: &mut JNIEnv,
+    context: &JObject,
+    prefs_name: &str,
+    service: &str,
+    account: &str,
 ) -> Result<(), SecretError> {
     let prefs_name = env
-        .new_string("waterkit_secrets")
+        .new_string(prefs_name)
         .map_err(|e| SecretError::System(e.to_string()))?;
 
     let prefs = env
@@ -213,3 +352,412 @@ pub fn delete_with_context(
 
     Ok(())
 }
+
+/// List the accounts stored under `service` using Android Context.
+///
+/// Includes secrets saved with biometric protection; listing them doesn't
+/// prompt for authentication. Returns an empty list when none are stored.
+pub fn list_accounts_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    service: &str,
+) -> Result<Vec<String>, SecretError> {
+    init(env, context)?;
+    let service = env
+        .new_string(service)
+        .map_err(|e| SecretError::System(e.to_string()))?;
+    let class = get_helper_class(env)?;
+    let array: JObjectArray = env
+        .call_static_method(
+            class,
+            "listAccounts",
+            "(Landroid/content/Context;Ljava/lang/String;)[Ljava/lang/String;",
+            &[JValue::Object(context), JValue::Object(&service)],
+        )
+        .map_err(|e| SecretError::System(format!("listAccounts: {e}")))?
+        .l()
+        .map_err(|e| SecretError::System(format!("listAccounts result: {e}")))?
+        .into();
+
+    let len = env
+        .get_array_length(&array)
+        .map_err(|e| SecretError::System(e.to_string()))?;
+    let mut accounts = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        let account: JString = env
+            .get_object_array_element(&array, i)
+            .map_err(|e| SecretError::System(e.to_string()))?
+            .into();
+        accounts.push(
+            env.get_string(&account)
+                .map_err(|e| SecretError::System(e.to_string()))?
+                .into(),
+        );
+    }
+    Ok(accounts)
+}
+
+/// Check whether a secret is stored for `service` and `account` using Android Context.
+///
+/// The secret isn't decrypted, so this never prompts for biometrics.
+pub fn exists_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    service: &str,
+    account: &str,
+) -> Result<bool, SecretError> {
+    if !list_accounts_with_context(env, context, service)?
+        .iter()
+        .any(|a| a == account)
+    {
+        return Ok(false);
+    }
+    let expires_at = read_expiry(env, context, service, account)?;
+    Ok(!expiry::is_expired(expires_at))
+}
+
+/// Embedded DEX bytecode containing the `SecretHelper` class.
+/// Generated at build time by kotlinc + D8.
+static DEX_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/classes.dex"));
+
+/// Cached class loader for the embedded DEX.
+static CLASS_LOADER: OnceLock<GlobalRef> = OnceLock::new();
+
+/// Initialize the DEX class loader. Must be called with a valid Context.
+fn init(env: &mut JNIEnv, context: &JObject) -> Result<(), SecretError> {
+    if CLASS_LOADER.get().is_some() {
+        return Ok(());
+    }
+
+    // Write DEX to cache directory
+    let cache_dir = env
+        .call_method(context, "getCacheDir", "()Ljava/io/File;", &[])
+        .map_err(|e| SecretError::System(format!("getCacheDir failed: {e}")))?
+        .l()
+        .map_err(|e| SecretError::System(format!("getCacheDir result: {e}")))?;
+
+    let cache_path = env
+        .call_method(&cache_dir, "getAbsolutePath", "()Ljava/lang/String;", &[])
+        .map_err(|e| SecretError::System(format!("getAbsolutePath failed: {e}")))?
+        .l()
+        .map_err(|e| SecretError::System(format!("getAbsolutePath result: {e}")))?;
+
+    let dex_path = format!(
+        "{}/waterkit_secret.dex",
+        env.get_string((&cache_path).into())
+            .map_err(|e| SecretError::System(format!("get_string failed: {e}")))?
+            .to_str()
+            .map_err(|e| SecretError::System(format!("to_str failed: {e}")))?
+    );
+
+    // Remove if exists to handle previous read-only setting
+    let _ = std::fs::remove_file(&dex_path);
+
+    std::fs::write(&dex_path, DEX_BYTES)
+        .map_err(|e| SecretError::System(format!("write DEX failed: {e}")))?;
+
+    // Make DEX read-only as required by modern Android security
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(&dex_path)
+            .map_err(|e| SecretError::System(format!("metadata DEX failed: {e}")))?
+            .permissions();
+        perms.set_mode(0o444); // Read-only
+        std::fs::set_permissions(&dex_path, perms)
+            .map_err(|e| SecretError::System(format!("set_permissions DEX failed: {e}")))?;
+    }
+
+    let dex_path_jstring = env
+        .new_string(&dex_path)
+        .map_err(|e| SecretError::System(format!("new_string failed: {e}")))?;
+
+    let parent_loader = env
+        .call_method(context, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+        .map_err(|e| SecretError::System(format!("getClassLoader failed: {e}")))?
+        .l()
+        .map_err(|e| SecretError::System(format!("getClassLoader result: {e}")))?;
+
+    let dex_class_loader_class = env
+        .find_class("dalvik/system/DexClassLoader")
+        .map_err(|e| SecretError::System(format!("find DexClassLoader: {e}")))?;
+
+    let class_loader = env
+        .new_object(
+            dex_class_loader_class,
+            "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/ClassLoader;)V",
+            &[
+                JValue::Object(&dex_path_jstring),
+                JValue::Object(&cache_path),
+                JValue::Object(&JObject::null()),
+                JValue::Object(&parent_loader),
+            ],
+        )
+        .map_err(|e| SecretError::System(format!("new DexClassLoader: {e}")))?;
+
+    let global_ref = env
+        .new_global_ref(class_loader)
+        .map_err(|e| SecretError::System(format!("new_global_ref: {e}")))?;
+
+    let _ = CLASS_LOADER.set(global_ref);
+
+    // The helper is loaded from our own DexClassLoader, so the runtime can't
+    // resolve its native methods by symbol name; register them explicitly.
+    register_natives(env)?;
+
+    Ok(())
+}
+
+fn register_natives(env: &mut JNIEnv) -> Result<(), SecretError> {
+    let class = get_helper_class(env)?;
+    let native_methods = [jni::NativeMethod {
+        name: "onResult".into(),
+        sig: "(JILjava/lang/String;)V".into(),
+        fn_ptr: Java_waterkit_secret_SecretHelper_onResult as *mut _,
+    }];
+
+    env.register_native_methods(class, &native_methods)
+        .map_err(|e| SecretError::System(format!("register_native_methods: {e}")))
+}
+
+fn get_helper_class<'a>(env: &mut JNIEnv<'a>) -> Result<JClass<'a>, SecretError> {
+    let class_loader = CLASS_LOADER
+        .get()
+        .ok_or_else(|| SecretError::System("Class loader not initialized".into()))?;
+
+    let helper_class_name = env
+        .new_string("waterkit.secret.SecretHelper")
+        .map_err(|e| SecretError::System(format!("new_string: {e}")))?;
+
+    let helper_class = env
+        .call_method(
+            class_loader.as_obj(),
+            "loadClass",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[JValue::Object(&helper_class_name)],
+        )
+        .map_err(|e| SecretError::System(format!("loadClass: {e}")))?
+        .l()
+        .map_err(|e| SecretError::System(format!("loadClass result: {e}")))?;
+
+    Ok(helper_class.into())
+}
+
+type ResultReceiver = async_channel::Receiver<Result<String, SecretError>>;
+
+/// Pending biometric requests, keyed by request id.
+static REQUESTS: Mutex<Option<HashMap<u64, async_channel::Sender<Result<String, SecretError>>>>> =
+    Mutex::new(None);
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_waterkit_secret_SecretHelper_onResult(
+    mut env: JNIEnv,
+    _class: JClass,
+    request_id: jlong,
+    code: jint,
+    value: JString,
+) {
+    // The prompt may report more than once (e.g. cancel button and error)
+    let Some(sender) = REQUESTS
+        .lock()
+        .ok()
+        .and_then(|mut r| r.as_mut().and_then(|r| r.remove(&(request_id as u64))))
+    else {
+        return;
+    };
+
+    let value: String = if value.is_null() {
+        String::new()
+    } else {
+        env.get_string(&value).map(Into::into).unwrap_or_default()
+    };
+    let result = match code {
+        0 => Ok(value),
+        1 => Err(SecretError::NotFound),
+        2 => Err(SecretError::PermissionDenied),
+        _ => Err(SecretError::System(value)),
+    };
+    let _ = sender.try_send(result);
+}
+
+/// Register a request and start it with `call`, which receives the helper
+/// class and request id.
+fn start_request(
+    env: &mut JNIEnv,
+    context: &JObject,
+    call: impl FnOnce(&mut JNIEnv, JClass, jlong) -> jni::errors::Result<()>,
+) -> Result<ResultReceiver, SecretError> {
+    init(env, context)?;
+
+    let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = async_channel::bounded(1);
+    REQUESTS
+        .lock()
+        .map_err(|e| SecretError::System(e.to_string()))?
+        .get_or_insert_with(HashMap::new)
+        .insert(id, sender);
+
+    let class = get_helper_class(env)?;
+    if let Err(e) = call(env, class, id as jlong) {
+        if let Ok(mut requests) = REQUESTS.lock()
+            && let Some(requests) = requests.as_mut()
+        {
+            requests.remove(&id);
+        }
+        return Err(SecretError::System(e.to_string()));
+    }
+    Ok(receiver)
+}
+
+/// Wait for the result of a request started with [`start_request`].
+async fn finish_request(
+    started: Result<ResultReceiver, SecretError>,
+) -> Result<String, SecretError> {
+    started?
+        .recv()
+        .await
+        .map_err(|_| SecretError::System("Biometric request dropped".into()))?
+}
+
+/// Save a secret encrypted with a Keystore key that requires user authentication.
+///
+/// The key is created with `setUserAuthenticationRequired(true)` and only
+/// unlocks with the authenticators allowed by `policy`; with
+/// [`AccessPolicy::BiometricCurrentSet`] it is invalidated when a new
+/// biometric is enrolled. The user is prompted once to authorize the
+/// encryption. The returned future doesn't borrow `env`, so it can be
+/// awaited after the JNI call returns.
+///
+/// # Errors
+/// Returns `SecretError::PermissionDenied` if authentication fails or is
+/// cancelled, or `SecretError::System` if no biometric or passcode is
+/// enrolled, the Android version doesn't support `policy`, or the Keystore
+/// operation fails.
+pub fn set_protected_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    service: &str,
+    account: &str,
+    password: &str,
+    policy: AccessPolicy,
+    reason: &str,
+) -> impl Future<Output = Result<(), SecretError>> + use<> {
+    let started = start_request(env, context, |env, class, id| {
+        let key = env.new_string(format!("{service}:{account}"))?;
+        let value = env.new_string(password)?;
+        let reason = env.new_string(reason)?;
+        env.call_static_method(
+            class,
+            "setProtected",
+            "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;ILjava/lang/String;J)V",
+            &[
+                JValue::Object(context),
+                JValue::Object(&key),
+                JValue::Object(&value),
+                JValue::Int(jint::from(policy.code())),
+                JValue::Object(&reason),
+                JValue::Long(id),
+            ],
+        )
+        .map(drop)
+    });
+    async move { finish_request(started).await.map(drop) }
+}
+
+/// Retrieve a secret saved with [`set_protected_with_context`], prompting the user.
+///
+/// # Errors
+/// Returns `SecretError::NotFound` if the secret doesn't exist,
+/// `SecretError::PermissionDenied` if authentication fails or biometric
+/// enrollment changed since it was saved, or `SecretError::System` if the
+/// Keystore operation fails.
+pub fn get_protected_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    service: &str,
+    account: &str,
+    reason: &str,
+) -> impl Future<Output = Result<String, SecretError>> + use<> {
+    let started = start_request(env, context, |env, class, id| {
+        let key = env.new_string(format!("{service}:{account}"))?;
+        let reason = env.new_string(reason)?;
+        env.call_static_method(
+            class,
+            "getProtected",
+            "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;J)V",
+            &[
+                JValue::Object(context),
+                JValue::Object(&key),
+                JValue::Object(&reason),
+                JValue::Long(id),
+            ],
+        )
+        .map(drop)
+    });
+    finish_request(started)
+}
+
+/// Save a secret that requires biometric authentication to read.
+///
+/// Same as [`set_protected_with_context`] with
+/// [`AccessPolicy::BiometricCurrentSet`].
+///
+/// # Errors
+/// See [`set_protected_with_context`].
+pub fn set_with_biometric_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    service: &str,
+    account: &str,
+    password: &str,
+    reason: &str,
+) -> impl Future<Output = Result<(), SecretError>> + use<> {
+    set_protected_with_context(
+        env,
+        context,
+        service,
+        account,
+        password,
+        AccessPolicy::BiometricCurrentSet,
+        reason,
+    )
+}
+
+/// Retrieve a secret saved with [`set_with_biometric_with_context`], prompting for biometrics.
+///
+/// # Errors
+/// See [`get_protected_with_context`].
+pub fn get_with_biometric_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    service: &str,
+    account: &str,
+    reason: &str,
+) -> impl Future<Output = Result<String, SecretError>> + use<> {
+    get_protected_with_context(env, context, service, account, reason)
+}
+
+/// Save a protected secret (stub, use `set_protected_with_context`).
+pub async fn set_protected(
+    _service: &str,
+    _account: &str,
+    _password: &str,
+    _policy: AccessPolicy,
+    _reason: &str,
+) -> Result<(), SecretError> {
+    Err(SecretError::System(
+        "On Android, use `waterkit_secret::android::set_protected_with_context`".into(),
+    ))
+}
+
+/// Retrieve a protected secret (stub, use `get_protected_with_context`).
+pub async fn get_protected(
+    _service: &str,
+    _account: &str,
+    _reason: &str,
+) -> Result<String, SecretError> {
+    Err(SecretError::System(
+        "On Android, use `waterkit_secret::android::get_protected_with_context`".into(),
+    ))
+}
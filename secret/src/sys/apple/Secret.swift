@@ -0,0 +1,160 @@
+import Foundation
+import LocalAuthentication
+import Security
+
+// Error codes passed to SecretCallback.on_error
+private let errorNotFound: UInt8 = 1
+private let errorPermissionDenied: UInt8 = 2
+private let errorSystem: UInt8 = 3
+
+private func baseQuery(service: String, account: String) -> [String: Any] {
+    var query: [String: Any] = [
+        kSecClass as String: kSecClassGenericPassword,
+        kSecAttrService as String: service,
+        kSecAttrAccount as String: account,
+    ]
+    #if os(macOS)
+    // Access control is only supported by the data protection keychain on macOS
+    query[kSecUseDataProtectionKeychain as String] = true
+    #endif
+    return query
+}
+
+private func errorMessage(_ status: OSStatus) -> String {
+    SecCopyErrorMessageString(status, nil) as String? ?? "Keychain error \(status)"
+}
+
+/// Add the accounts of generic passwords stored under `service` to `accounts`.
+private func copyAccounts(service: String, dataProtection: Bool, into accounts: AccountListRefMut) -> OSStatus {
+    var query: [String: Any] = [
+        kSecClass as String: kSecClassGenericPassword,
+        kSecAttrService as String: service,
+        kSecMatchLimit as String: kSecMatchLimitAll,
+        kSecReturnAttributes as String: true,
+    ]
+    #if os(macOS)
+    query[kSecUseDataProtectionKeychain as String] = dataProtection
+    #endif
+
+    var result: AnyObject?
+    let status = SecItemCopyMatching(query as CFDictionary, &result)
+    guard status == errSecSuccess else { return status }
+
+    for item in result as? [[String: Any]] ?? [] {
+        if let account = item[kSecAttrAccount as String] as? String {
+            accounts.push(account)
+        }
+    }
+    return errSecSuccess
+}
+
+public func secret_list_accounts(service: RustStr, accounts: AccountListRefMut) -> Int32 {
+    let service = service.toString()
+    #if os(macOS)
+    // Plain secrets live in the file-based keychain, biometric ones in the data protection keychain
+    let status = copyAccounts(service: service, dataProtection: false, into: accounts)
+    guard status == errSecSuccess || status == errSecItemNotFound else { return status }
+    let protectedStatus = copyAccounts(service: service, dataProtection: true, into: accounts)
+    // Apps without a keychain access group can't use the data protection keychain
+    if protectedStatus == errSecMissingEntitlement {
+        return status
+    }
+    return protectedStatus == errSecItemNotFound ? status : protectedStatus
+    #else
+    return copyAccounts(service: service, dataProtection: true, into: accounts)
+    #endif
+}
+
+// AccessPolicy codes passed by Rust
+private let policyBiometricAny: UInt8 = 0
+private let policyDevicePasscode: UInt8 = 2
+
+public func secret_set_protected(service: RustStr, account: RustStr, password: RustStr, policy: UInt8, reason: RustStr, callback: SecretCallback) {
+    let service = service.toString()
+    let account = account.toString()
+    let password = password.toString()
+    let context = LAContext()
+
+    let flags: SecAccessControlCreateFlags
+    let authPolicy: LAPolicy
+    switch policy {
+    case policyBiometricAny:
+        flags = .biometryAny
+        authPolicy = .deviceOwnerAuthenticationWithBiometrics
+    case policyDevicePasscode:
+        flags = .devicePasscode
+        authPolicy = .deviceOwnerAuthentication
+    default:
+        flags = .biometryCurrentSet
+        authPolicy = .deviceOwnerAuthenticationWithBiometrics
+    }
+
+    context.evaluatePolicy(authPolicy, localizedReason: reason.toString()) { success, error in
+        guard success else {
+            callback.on_error(errorPermissionDenied, error?.localizedDescription ?? "Authentication failed")
+            return
+        }
+
+        var accessError: Unmanaged<CFError>?
+        guard let access = SecAccessControlCreateWithFlags(
+            nil,
+            kSecAttrAccessibleWhenPasscodeSetThisDeviceOnly,
+            flags,
+            &accessError
+        ) else {
+            let message = accessError?.takeRetainedValue().localizedDescription ?? "Failed to create access control"
+            callback.on_error(errorSystem, message)
+            return
+        }
+
+        // Replace any existing item, which may have a different access policy
+        let base = baseQuery(service: service, account: account)
+        SecItemDelete(base as CFDictionary)
+
+        var query = base
+        query[kSecAttrAccessControl as String] = access
+        query[kSecValueData as String] = Data(password.utf8)
+        query[kSecUseAuthenticationContext as String] = context
+
+        let status = SecItemAdd(query as CFDictionary, nil)
+        if status == errSecSuccess {
+            callback.on_success("")
+        } else {
+            callback.on_error(errorSystem, errorMessage(status))
+        }
+    }
+}
+
+public func secret_get_protected(service: RustStr, account: RustStr, reason: RustStr, callback: SecretCallback) {
+    let service = service.toString()
+    let account = account.toString()
+    let reason = reason.toString()
+
+    // SecItemCopyMatching blocks while the prompt is shown
+    DispatchQueue.global(qos: .userInitiated).async {
+        let context = LAContext()
+        context.localizedReason = reason
+
+        var query = baseQuery(service: service, account: account)
+        query[kSecReturnData as String] = true
+        query[kSecMatchLimit as String] = kSecMatchLimitOne
+        query[kSecUseAuthenticationContext as String] = context
+
+        var result: AnyObject?
+        let status = SecItemCopyMatching(query as CFDictionary, &result)
+        switch status {
+        case errSecSuccess:
+            guard let data = result as? Data, let value = String(data: data, encoding: .utf8) else {
+                callback.on_error(errorSystem, "Stored secret is not valid UTF-8")
+                return
+            }
+            callback.on_success(value)
+        case errSecItemNotFound:
+            callback.on_error(errorNotFound, errorMessage(status))
+        case errSecUserCanceled, errSecAuthFailed:
+            callback.on_error(errorPermissionDenied, errorMessage(status))
+        default:
+            callback.on_error(errorSystem, errorMessage(status))
+        }
+    }
+}
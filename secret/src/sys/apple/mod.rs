@@ -1,10 +1,96 @@
 //! Apple platform (iOS/macOS) secure storage implementation.
 
-use crate::SecretError;
+use crate::{AccessPolicy, SecretError};
 use keyring::Entry;
 
+#[swift_bridge::bridge]
+mod ffi {
+    extern "Rust" {
+        type SecretCallback;
+        fn on_success(self, value: String);
+        // code 1: NotFound, 2: PermissionDenied, 3: System
+        fn on_error(self, code: u8, message: String);
+
+        type AccountList;
+        fn push(self: &mut AccountList, account: String);
+    }
+
+    extern "Swift" {
+        // policy: 0 BiometricAny, 1 BiometricCurrentSet, 2 DevicePasscode
+        fn secret_set_protected(
+            service: &str,
+            account: &str,
+            password: &str,
+            policy: u8,
+            reason: &str,
+            callback: SecretCallback,
+        );
+        fn secret_get_protected(
+            service: &str,
+            account: &str,
+            reason: &str,
+            callback: SecretCallback,
+        );
+        // Returns an OSStatus; errSecItemNotFound means no accounts
+        fn secret_list_accounts(service: &str, accounts: &mut AccountList) -> i32;
+    }
+}
+
+/// Receives the result of a biometric keychain operation from Swift.
+pub struct SecretCallback {
+    sender: async_channel::Sender<Result<String, SecretError>>,
+}
+
+impl SecretCallback {
+    fn on_success(self, value: String) {
+        let _ = self.sender.try_send(Ok(value));
+    }
+
+    fn on_error(self, code: u8, message: String) {
+        let error = match code {
+            1 => SecretError::NotFound,
+            2 => SecretError::PermissionDenied,
+            _ => SecretError::System(message),
+        };
+        let _ = self.sender.try_send(Err(error));
+    }
+}
+
+/// Collects account names reported by Swift.
+#[derive(Default)]
+pub struct AccountList(Vec<String>);
+
+impl AccountList {
+    fn push(&mut self, account: String) {
+        if !self.0.contains(&account) {
+            self.0.push(account);
+        }
+    }
+}
+
+/// Create a callback and the receiver of its result.
+fn callback() -> (
+    SecretCallback,
+    async_channel::Receiver<Result<String, SecretError>>,
+) {
+    let (sender, receiver) = async_channel::bounded(1);
+    (SecretCallback { sender }, receiver)
+}
+
+async fn receive(
+    receiver: async_channel::Receiver<Result<String, SecretError>>,
+) -> Result<String, SecretError> {
+    receiver
+        .recv()
+        .await
+        .map_err(|_| SecretError::System("Keychain callback dropped".into()))?
+}
+
 /// Save a secret to the Apple Keychain.
 ///
+/// Updates the existing item (`SecItemUpdate`) when adding it reports
+/// `errSecDuplicateItem`, so repeated calls replace the value.
+///
 /// # Errors
 /// Returns a `SecretError::System` if the keychain operation fails.
 #[allow(clippy::unused_async)]
@@ -46,3 +132,62 @@ pub async fn delete(service: &str, account: &str) -> Result<(), SecretError> {
         Err(e) => Err(SecretError::System(e.to_string())),
     }
 }
+
+/// List the accounts stored under `service` in the Apple Keychain.
+///
+/// Includes secrets saved with [`set_protected`]; listing them doesn't
+/// prompt for authentication.
+///
+/// # Errors
+/// Returns a `SecretError::System` if the keychain query fails.
+#[allow(clippy::unused_async)]
+pub async fn list_accounts(service: &str) -> Result<Vec<String>, SecretError> {
+    /// `errSecItemNotFound`
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    let mut accounts = AccountList::default();
+    match ffi::secret_list_accounts(service, &mut accounts) {
+        0 | ITEM_NOT_FOUND => {
+            accounts.0.sort();
+            Ok(accounts.0)
+        }
+        status => Err(SecretError::System(format!("Keychain error {status}"))),
+    }
+}
+
+/// Save a secret to the Apple Keychain, readable only after the user authenticates.
+///
+/// The user authenticates first; the item is then stored with the access
+/// control flag matching `policy` (`kSecAccessControlBiometryAny`,
+/// `kSecAccessControlBiometryCurrentSet` or `kSecAccessControlDevicePasscode`).
+///
+/// # Errors
+/// Returns `SecretError::PermissionDenied` if authentication fails,
+/// or `SecretError::System` if the keychain operation fails.
+pub async fn set_protected(
+    service: &str,
+    account: &str,
+    password: &str,
+    policy: AccessPolicy,
+    reason: &str,
+) -> Result<(), SecretError> {
+    let (callback, receiver) = callback();
+    ffi::secret_set_protected(service, account, password, policy.code(), reason, callback);
+    receive(receiver).await.map(drop)
+}
+
+/// Retrieve a secret saved with [`set_protected`], prompting the user.
+///
+/// # Errors
+/// Returns `SecretError::NotFound` if the secret doesn't exist,
+/// `SecretError::PermissionDenied` if authentication fails,
+/// or `SecretError::System` if the keychain operation fails.
+pub async fn get_protected(
+    service: &str,
+    account: &str,
+    reason: &str,
+) -> Result<String, SecretError> {
+    let (callback, receiver) = callback();
+    ffi::secret_get_protected(service, account, reason, callback);
+    receive(receiver).await
+}
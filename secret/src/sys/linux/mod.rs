@@ -1,6 +1,10 @@
 use crate::SecretError;
 use keyring::Entry;
+use std::collections::HashMap;
+use zbus::Connection;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
 
+#[allow(clippy::unused_async)]
 pub async fn set(service: &str, account: &str, password: &str) -> Result<(), SecretError> {
     let entry = Entry::new(service, account).map_err(|e| SecretError::System(e.to_string()))?;
 
@@ -9,6 +13,7 @@ pub async fn set(service: &str, account: &str, password: &str) -> Result<(), Sec
         .map_err(|e| SecretError::System(e.to_string()))
 }
 
+#[allow(clippy::unused_async)]
 pub async fn get(service: &str, account: &str) -> Result<String, SecretError> {
     let entry = Entry::new(service, account).map_err(|e| SecretError::System(e.to_string()))?;
 
@@ -19,12 +24,62 @@ pub async fn get(service: &str, account: &str) -> Result<String, SecretError> {
     }
 }
 
+#[allow(clippy::unused_async)]
 pub async fn delete(service: &str, account: &str) -> Result<(), SecretError> {
     let entry = Entry::new(service, account).map_err(|e| SecretError::System(e.to_string()))?;
 
     match entry.delete_credential() {
-        Ok(_) => Ok(()),
-        Err(keyring::Error::NoEntry) => Ok(()),
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
         Err(e) => Err(SecretError::System(e.to_string())),
     }
 }
+
+/// List accounts by searching the Secret Service for items with keyring's `service` attribute.
+pub async fn list_accounts(service: &str) -> Result<Vec<String>, SecretError> {
+    let connection = Connection::session()
+        .await
+        .map_err(|e| SecretError::System(format!("D-Bus connection failed: {e}")))?;
+
+    let (unlocked, locked): (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) = connection
+        .call_method(
+            Some("org.freedesktop.secrets"),
+            "/org/freedesktop/secrets",
+            Some("org.freedesktop.Secret.Service"),
+            "SearchItems",
+            &(HashMap::from([("service", service)]),),
+        )
+        .await
+        .map_err(|e| SecretError::System(format!("SearchItems failed: {e}")))?
+        .body()
+        .deserialize()
+        .map_err(|e| SecretError::System(format!("Failed to parse search results: {e}")))?;
+
+    // Attributes are readable without unlocking the item
+    let mut accounts = Vec::new();
+    for path in unlocked.iter().chain(&locked) {
+        let reply: OwnedValue = connection
+            .call_method(
+                Some("org.freedesktop.secrets"),
+                path.as_str(),
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.freedesktop.Secret.Item", "Attributes"),
+            )
+            .await
+            .map_err(|e| SecretError::System(format!("Failed to get attributes: {e}")))?
+            .body()
+            .deserialize()
+            .map_err(|e| SecretError::System(format!("Failed to parse attributes: {e}")))?;
+
+        let attributes = HashMap::<String, String>::try_from(reply)
+            .map_err(|e| SecretError::System(format!("Failed to parse attributes: {e}")))?;
+        if let Some(account) = attributes.get("username")
+            && !accounts.contains(account)
+        {
+            accounts.push(account.clone());
+        }
+    }
+
+    accounts.sort();
+    Ok(accounts)
+}
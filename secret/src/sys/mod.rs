@@ -28,6 +28,17 @@ pub mod linux;
 #[cfg(target_os = "linux")]
 pub use linux::*;
 
+#[cfg(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux"
+))]
+/// Check whether a secret exists by listing the service's accounts, without reading the secret.
+pub async fn exists(service: &str, account: &str) -> Result<bool, crate::SecretError> {
+    Ok(list_accounts(service).await?.iter().any(|a| a == account))
+}
+
 #[cfg(not(any(
     target_os = "ios",
     target_os = "macos",
@@ -67,3 +78,55 @@ pub async fn get(_service: &str, _account: &str) -> Result<String, crate::Secret
 pub async fn delete(_service: &str, _account: &str) -> Result<(), crate::SecretError> {
     Err(crate::SecretError::System("Unsupported platform".into()))
 }
+
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "linux"
+)))]
+/// Check whether a secret exists (fallback).
+pub async fn exists(_service: &str, _account: &str) -> Result<bool, crate::SecretError> {
+    Err(crate::SecretError::System("Unsupported platform".into()))
+}
+
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "linux"
+)))]
+/// List stored accounts (fallback).
+pub async fn list_accounts(_service: &str) -> Result<Vec<String>, crate::SecretError> {
+    Err(crate::SecretError::System("Unsupported platform".into()))
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+/// Save a protected secret (unsupported on this platform).
+#[allow(clippy::unused_async)]
+pub async fn set_protected(
+    _service: &str,
+    _account: &str,
+    _password: &str,
+    _policy: crate::AccessPolicy,
+    _reason: &str,
+) -> Result<(), crate::SecretError> {
+    Err(crate::SecretError::System(
+        "Protected secrets are not supported on this platform".into(),
+    ))
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+/// Retrieve a protected secret (unsupported on this platform).
+#[allow(clippy::unused_async)]
+pub async fn get_protected(
+    _service: &str,
+    _account: &str,
+    _reason: &str,
+) -> Result<String, crate::SecretError> {
+    Err(crate::SecretError::System(
+        "Protected secrets are not supported on this platform".into(),
+    ))
+}
@@ -1,5 +1,10 @@
 use crate::SecretError;
 use keyring::Entry;
+use windows::Win32::Foundation::ERROR_NOT_FOUND;
+use windows::Win32::Security::Credentials::{
+    CRED_TYPE_GENERIC, CREDENTIALW, CredEnumerateW, CredFree,
+};
+use windows::core::PCWSTR;
 
 pub async fn set(service: &str, account: &str, password: &str) -> Result<(), SecretError> {
     let entry = Entry::new(service, account).map_err(|e| SecretError::System(e.to_string()))?;
@@ -28,3 +33,49 @@ pub async fn delete(service: &str, account: &str) -> Result<(), SecretError> {
         Err(e) => Err(SecretError::System(e.to_string())),
     }
 }
+
+/// List accounts by enumerating generic credentials.
+///
+/// keyring names credentials `{account}.{service}`, which the trailing
+/// wildcard filter of `CredEnumerateW` can't match, so all generic
+/// credentials are enumerated and filtered here.
+#[allow(clippy::unused_async)]
+pub async fn list_accounts(service: &str) -> Result<Vec<String>, SecretError> {
+    let mut count = 0;
+    let mut credentials: *mut *mut CREDENTIALW = std::ptr::null_mut();
+
+    // SAFETY: count and credentials are valid out-pointers; the buffer is freed below.
+    match unsafe { CredEnumerateW(PCWSTR::null(), None, &mut count, &mut credentials) } {
+        Ok(()) => {}
+        Err(e) if e.code() == ERROR_NOT_FOUND.to_hresult() => return Ok(Vec::new()),
+        Err(e) => return Err(SecretError::System(e.message())),
+    }
+
+    let mut accounts = Vec::new();
+    // SAFETY: CredEnumerateW returned `count` valid credential pointers.
+    for &credential in unsafe { std::slice::from_raw_parts(credentials, count as usize) } {
+        let credential = unsafe { &*credential };
+        if credential.Type != CRED_TYPE_GENERIC || credential.UserName.is_null() {
+            continue;
+        }
+        // SAFETY: both names are NUL-terminated strings owned by the buffer.
+        let names = unsafe {
+            (
+                credential.TargetName.to_string(),
+                credential.UserName.to_string(),
+            )
+        };
+        let (Ok(target), Ok(account)) = names else {
+            continue;
+        };
+        if target == format!("{account}.{service}") && !accounts.contains(&account) {
+            accounts.push(account);
+        }
+    }
+
+    // SAFETY: credentials was allocated by CredEnumerateW.
+    unsafe { CredFree(credentials.cast()) };
+
+    accounts.sort();
+    Ok(accounts)
+}
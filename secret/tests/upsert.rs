@@ -0,0 +1,31 @@
+//! `SecretManager::set` replaces an existing secret instead of duplicating it.
+//!
+//! Runs against the real store: the login keychain on macOS, and the Secret
+//! Service on Linux, where it's skipped without a D-Bus session bus.
+
+#![cfg(any(target_os = "macos", target_os = "linux"))]
+
+use waterkit_secret::SecretManager;
+
+const SERVICE: &str = "com.waterkit.secret.tests.upsert";
+const ACCOUNT: &str = "upsert";
+
+#[tokio::test]
+async fn set_twice_returns_latest_value() {
+    if cfg!(target_os = "linux") && std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none() {
+        eprintln!("skipped: no D-Bus session bus for the Secret Service");
+        return;
+    }
+
+    SecretManager::set(SERVICE, ACCOUNT, "first").await.unwrap();
+    SecretManager::set(SERVICE, ACCOUNT, "second")
+        .await
+        .unwrap();
+
+    let value = SecretManager::get(SERVICE, ACCOUNT).await;
+    let accounts = SecretManager::list_accounts(SERVICE).await;
+    SecretManager::delete(SERVICE, ACCOUNT).await.unwrap();
+
+    assert_eq!(value.unwrap(), "second");
+    assert_eq!(accounts.unwrap(), [ACCOUNT]);
+}
@@ -0,0 +1,116 @@
+//! Altitude from barometric pressure.
+//!
+//! Absolute altitudes use the international barometric formula, which
+//! assumes the standard atmosphere, so weather moves them by up to a few
+//! hundred metres unless the reference pressure is the current sea-level
+//! pressure. Changes in altitude are much more reliable, about 10cm per
+//! 0.012 hPa, which is enough to tell floors apart.
+
+use crate::{ScalarData, SensorError, SensorOptions, SensorStream, sys};
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+
+/// Standard sea-level pressure in hPa.
+pub const STANDARD_PRESSURE_HPA: f64 = 1013.25;
+
+/// Altitude in metres at which the pressure is `pressure_hpa`, above the
+/// level where it is `reference_hpa`.
+#[must_use]
+pub fn pressure_altitude(pressure_hpa: f64, reference_hpa: f64) -> f64 {
+    44_330.0 * (1.0 - (pressure_hpa / reference_hpa).powf(1.0 / 5.255))
+}
+
+/// Read the altitude above the level where the pressure is `reference_hpa`.
+pub async fn read(reference_hpa: Option<f64>) -> Result<ScalarData, SensorError> {
+    let reference = reference_hpa.unwrap_or(STANDARD_PRESSURE_HPA);
+    let data = sys::barometer_read().await?;
+    Ok(ScalarData {
+        value: pressure_altitude(data.value, reference),
+        timestamp: data.timestamp,
+    })
+}
+
+/// Altitudes in metres, whatever their origin; only differences count.
+fn raw_watch(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    if sys::relative_altitude_available() {
+        return sys::relative_altitude_watch(options);
+    }
+    Ok(Box::pin(sys::barometer_watch(options)?.map(|data| {
+        ScalarData {
+            value: pressure_altitude(data.value, STANDARD_PRESSURE_HPA),
+            timestamp: data.timestamp,
+        }
+    })))
+}
+
+/// Watch the altitude relative to the first reading.
+pub fn watch(options: SensorOptions) -> Result<AltitudeWatch, SensorError> {
+    let zero = Arc::new(Mutex::new(Zero::default()));
+    let shared = Arc::clone(&zero);
+    let stream = raw_watch(options)?.map(move |data| {
+        let origin = {
+            let mut zero = shared.lock().unwrap_or_else(PoisonError::into_inner);
+            zero.last = Some(data.value);
+            *zero.origin.get_or_insert(data.value)
+        };
+        ScalarData {
+            value: data.value - origin,
+            timestamp: data.timestamp,
+        }
+    });
+    Ok(AltitudeWatch {
+        stream: Box::pin(stream),
+        zero,
+    })
+}
+
+/// Where a watch measures altitude from.
+#[derive(Debug, Default)]
+struct Zero {
+    /// Raw altitude that reads as zero, set by the first reading.
+    origin: Option<f64>,
+    /// Raw altitude of the last reading.
+    last: Option<f64>,
+}
+
+/// Stream of altitude changes in metres, from
+/// [`Barometer::watch_altitude`](crate::Barometer::watch_altitude).
+///
+/// Readings start at zero and go up as the device climbs. Call
+/// [`zero`](Self::zero) to measure from the current altitude instead, such
+/// as on arriving at a floor.
+pub struct AltitudeWatch {
+    stream: SensorStream<ScalarData>,
+    zero: Arc<Mutex<Zero>>,
+}
+
+impl AltitudeWatch {
+    /// Measure later readings from the altitude of the last one.
+    ///
+    /// Before the first reading this does nothing, since it reads as zero
+    /// anyway.
+    pub fn zero(&self) {
+        let mut zero = self.zero.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(last) = zero.last {
+            zero.origin = Some(last);
+        }
+    }
+}
+
+impl std::fmt::Debug for AltitudeWatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AltitudeWatch")
+            .field("zero", &self.zero)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Stream for AltitudeWatch {
+    type Item = ScalarData;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
@@ -0,0 +1,48 @@
+//! Windows of full-rate samples, for gesture recognition.
+//!
+//! A batch is a watch at the fastest rate whose report latency is the
+//! window, so on Android the sensor hub fills its FIFO while the
+//! application processor sleeps and hands the samples over at once.
+//! Elsewhere the watch simply runs at full rate for the window.
+
+use crate::{SamplingRate, SensorData, SensorError, SensorOptions, SensorStream};
+use futures::{StreamExt, future};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Extra time to wait for the samples of a window to be delivered, on top
+/// of the window itself and the interval at which watches drain them.
+const DELIVERY_GRACE: Duration = Duration::from_millis(100);
+
+/// Current time as Unix epoch milliseconds, the epoch of sample timestamps.
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Collect the samples that `watch` delivers with timestamps in the next
+/// `window`, in timestamp order.
+pub async fn read(
+    watch: fn(SensorOptions) -> Result<SensorStream<SensorData>, SensorError>,
+    window: Duration,
+) -> Result<Vec<SensorData>, SensorError> {
+    let samples = watch(SensorOptions {
+        rate: SamplingRate::Fastest,
+        max_report_latency: Some(window),
+    })?;
+
+    let start = now_ms();
+    let end = start.saturating_add(u64::try_from(window.as_millis()).unwrap_or(u64::MAX));
+    // A batched watch drains once per window, so the last samples can take
+    // up to two windows to arrive
+    let deadline = futures_timer::Delay::new(window.saturating_mul(2) + DELIVERY_GRACE);
+
+    let mut batch: Vec<SensorData> = samples
+        .take_until(deadline)
+        .take_while(|data| future::ready(data.timestamp <= end))
+        .filter(|data| future::ready(data.timestamp >= start))
+        .collect()
+        .await;
+    batch.sort_by_key(|data| data.timestamp);
+    Ok(batch)
+}
@@ -0,0 +1,66 @@
+//! Magnetometer calibration status.
+//!
+//! Every magnetometer and compass reading records its accuracy, so the
+//! status reflects the latest reading of any stream or one-shot read.
+
+use crate::{Compass, Magnetometer, SensorAccuracy, SensorError, SensorOptions, SensorStream};
+use futures::{StreamExt, future};
+use std::sync::{Mutex, PoisonError};
+
+/// Accuracy of the latest reading that reported one.
+static ACCURACY: Mutex<SensorAccuracy> = Mutex::new(SensorAccuracy::Unknown);
+
+/// Whether the magnetometer needs calibration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CalibrationStatus {
+    /// The platform doesn't report accuracy, or nothing was read yet.
+    Unknown,
+    /// Accuracy is below medium; ask the user to wave the device in a
+    /// figure-eight.
+    NeedsCalibration,
+    /// Accuracy is medium or better.
+    Calibrated,
+}
+
+impl From<SensorAccuracy> for CalibrationStatus {
+    fn from(accuracy: SensorAccuracy) -> Self {
+        match accuracy {
+            SensorAccuracy::Unknown => Self::Unknown,
+            SensorAccuracy::Unreliable | SensorAccuracy::Low => Self::NeedsCalibration,
+            SensorAccuracy::Medium | SensorAccuracy::High => Self::Calibrated,
+        }
+    }
+}
+
+/// Record the accuracy of a reading. Readings without one don't erase what
+/// other sources reported.
+pub fn record(accuracy: SensorAccuracy) {
+    if accuracy != SensorAccuracy::Unknown {
+        *ACCURACY.lock().unwrap_or_else(PoisonError::into_inner) = accuracy;
+    }
+}
+
+/// The status according to the latest recorded accuracy.
+pub fn status() -> CalibrationStatus {
+    (*ACCURACY.lock().unwrap_or_else(PoisonError::into_inner)).into()
+}
+
+/// Stream the status whenever it changes.
+///
+/// Follows the compass where there is one, since `CoreMotion`'s raw
+/// magnetometer doesn't report accuracy but `CoreLocation`'s heading does.
+pub fn watch() -> Result<SensorStream<CalibrationStatus>, SensorError> {
+    let options = SensorOptions::default();
+    let statuses: SensorStream<CalibrationStatus> = if Compass::is_available() {
+        Box::pin(Compass::watch_with(options)?.map(|data| data.accuracy.into()))
+    } else {
+        Box::pin(Magnetometer::watch_with(options)?.map(|data| data.accuracy.into()))
+    };
+
+    let mut last = None;
+    Ok(Box::pin(statuses.filter(move |status| {
+        let changed = last != Some(*status);
+        last = Some(*status);
+        future::ready(changed)
+    })))
+}
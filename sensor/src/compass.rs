@@ -0,0 +1,168 @@
+//! Compass heading with declination correction.
+
+use crate::{SensorAccuracy, SensorError, SensorOptions, SensorStream, calibration, sys};
+use futures::{StreamExt, future};
+use std::sync::Mutex;
+
+/// A compass heading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadingData {
+    /// Heading of the device's top edge in degrees clockwise from magnetic
+    /// north, in `0.0..360.0`.
+    pub magnetic_heading: f64,
+    /// Heading in degrees clockwise from true (geographic) north, in
+    /// `0.0..360.0`.
+    ///
+    /// `None` until the device's location is known, since the magnetic
+    /// declination depends on it.
+    pub true_heading: Option<f64>,
+    /// How far the heading can be trusted.
+    pub accuracy: SensorAccuracy,
+    /// Timestamp as Unix epoch milliseconds.
+    pub timestamp: u64,
+}
+
+/// Where the user is, for computing the magnetic declination.
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+}
+
+static POSITION: Mutex<Option<Position>> = Mutex::new(None);
+
+fn position() -> Option<Position> {
+    *POSITION
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn set_position(position: Option<Position>) {
+    *POSITION
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = position;
+}
+
+/// Fill in the true heading from the stored position if the platform
+/// didn't provide one.
+fn correct(mut data: HeadingData) -> HeadingData {
+    if data.true_heading.is_none()
+        && let Some(p) = position()
+        && let Some(declination) = sys::declination(p.latitude, p.longitude, p.altitude)
+    {
+        data.true_heading = Some((data.magnetic_heading + declination).rem_euclid(360.0));
+    }
+    data
+}
+
+/// Smallest angle between two headings in degrees, in `0.0..=180.0`.
+fn heading_change(from: f64, to: f64) -> f64 {
+    let delta = (to - from).rem_euclid(360.0);
+    delta.min(360.0 - delta)
+}
+
+/// Tilt-compensated compass.
+///
+/// On iOS the heading comes from `CLLocationManager`, which also reports the
+/// true heading while location services are authorized. On Android it is the
+/// azimuth of the rotation vector, as `SensorManager.getOrientation` computes
+/// it, and the true heading is derived from the position passed to
+/// [`set_position`](Self::set_position). Desktop platforms have no compass.
+#[derive(Debug)]
+pub struct Compass;
+
+impl Compass {
+    /// Check if the compass is available.
+    #[must_use]
+    // Linux has no such sensor, so there it could be const
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn is_available() -> bool {
+        sys::compass_available()
+    }
+
+    /// Read the current heading.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the compass is not available.
+    pub async fn read() -> Result<HeadingData, SensorError> {
+        sys::compass_read()
+            .await
+            .map(correct)
+            .inspect(|data| calibration::record(data.accuracy))
+    }
+
+    /// Watch for heading updates at a specified interval.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the compass is not available.
+    pub fn watch(interval_ms: u32) -> Result<SensorStream<HeadingData>, SensorError> {
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for heading updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the compass is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<HeadingData>, SensorError> {
+        let stream = sys::compass_watch(options)?.map(correct);
+        Ok(Box::pin(
+            stream.inspect(|data| calibration::record(data.accuracy)),
+        ))
+    }
+
+    /// Watch for headings that differ from the last one yielded by at least
+    /// `filter_degrees`, like `CLLocationManager.headingFilter`.
+    ///
+    /// The first heading is always yielded. Changes are measured on the true
+    /// heading when known, otherwise on the magnetic heading.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the compass is not available.
+    pub fn watch_filtered(
+        options: SensorOptions,
+        filter_degrees: f64,
+    ) -> Result<SensorStream<HeadingData>, SensorError> {
+        let mut last = None;
+        Ok(Box::pin(Self::watch_with(options)?.filter(move |data| {
+            let heading = data.true_heading.unwrap_or(data.magnetic_heading);
+            let changed = last.is_none_or(|last| heading_change(last, heading) >= filter_degrees);
+            if changed {
+                last = Some(heading);
+            }
+            future::ready(changed)
+        })))
+    }
+
+    /// Correct headings for the magnetic declination at the given
+    /// coordinates, with the altitude in meters above sea level.
+    ///
+    /// On Android, headings only carry a
+    /// [`true_heading`](HeadingData::true_heading) once a position is set.
+    /// iOS locates the device itself and ignores it. The declination changes
+    /// slowly with distance, so an occasional update is enough.
+    pub fn set_position(latitude: f64, longitude: f64, altitude: f64) {
+        set_position(Some(Position {
+            latitude,
+            longitude,
+            altitude,
+        }));
+    }
+
+    /// Correct headings for the magnetic declination at `location`.
+    ///
+    /// See [`set_position`](Self::set_position).
+    #[cfg(feature = "location")]
+    pub fn set_location(location: &waterkit_location::Location) {
+        Self::set_position(
+            location.latitude,
+            location.longitude,
+            location.altitude.unwrap_or(0.0),
+        );
+    }
+
+    /// Forget the position passed to [`set_position`](Self::set_position).
+    pub fn clear_position() {
+        set_position(None);
+    }
+}
@@ -0,0 +1,144 @@
+//! Software gravity estimate for platforms without a gravity sensor.
+//!
+//! A complementary filter tracks gravity in the device's frame: the
+//! gyroscope rotates the previous estimate, which is then pulled towards the
+//! accelerometer to cancel gyroscope drift. Without a gyroscope the filter
+//! degrades to a plain low-pass filter over the accelerometer.
+
+use crate::{SensorData, SensorError, SensorOptions, SensorStream, sys};
+use futures::stream;
+use std::time::Duration;
+
+/// Time constant of the filter in seconds. Motion faster than this is
+/// treated as linear acceleration, slower motion as a change in gravity.
+const TIME_CONSTANT: f64 = 0.5;
+
+/// Accelerometer samples taken for a one-shot estimate.
+const READ_SAMPLES: u32 = 10;
+
+/// Interval between the samples of a one-shot estimate.
+const READ_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Complementary filter estimating gravity.
+#[derive(Debug, Default)]
+struct GravityFilter {
+    gravity: Option<SensorData>,
+}
+
+impl GravityFilter {
+    /// Feed an accelerometer sample, and the gyroscope when available, in
+    /// radians per second. Returns the new gravity estimate.
+    fn update(&mut self, accel: &SensorData, gyro: Option<&SensorData>) -> SensorData {
+        let Some(prev) = &self.gravity else {
+            // Assume the device starts at rest
+            self.gravity = Some(accel.clone());
+            return accel.clone();
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let dt = accel.timestamp.saturating_sub(prev.timestamp) as f64 / 1000.0;
+        let alpha = TIME_CONSTANT / (TIME_CONSTANT + dt);
+
+        // Gravity is fixed in the world, so it turns against the device's
+        // rotation: dg/dt = g × ω
+        let (mut x, mut y, mut z) = (prev.x, prev.y, prev.z);
+        if let Some(w) = gyro {
+            (x, y, z) = (
+                dt.mul_add(y.mul_add(w.z, -z * w.y), x),
+                dt.mul_add(z.mul_add(w.x, -x * w.z), y),
+                dt.mul_add(x.mul_add(w.y, -y * w.x), z),
+            );
+        }
+
+        let gravity = SensorData {
+            x: alpha.mul_add(x - accel.x, accel.x),
+            y: alpha.mul_add(y - accel.y, accel.y),
+            z: alpha.mul_add(z - accel.z, accel.z),
+            accuracy: accel.accuracy,
+            timestamp: accel.timestamp,
+        };
+        self.gravity = Some(gravity.clone());
+        gravity
+    }
+
+    /// Read the raw sensors and update the estimate.
+    ///
+    /// Returns the accelerometer sample along with the new estimate.
+    async fn sample(&mut self) -> Result<(SensorData, SensorData), SensorError> {
+        let accel = sys::accelerometer_read().await?;
+        let gyro = if sys::gyroscope_available() {
+            sys::gyroscope_read().await.ok()
+        } else {
+            None
+        };
+        let gravity = self.update(&accel, gyro.as_ref());
+        Ok((accel, gravity))
+    }
+
+    /// Sample the sensors for a short while to settle the estimate.
+    async fn settle(&mut self) -> Result<(SensorData, SensorData), SensorError> {
+        let mut sample = self.sample().await?;
+        for _ in 1..READ_SAMPLES {
+            futures_timer::Delay::new(READ_INTERVAL).await;
+            sample = self.sample().await?;
+        }
+        Ok(sample)
+    }
+}
+
+fn linear(accel: &SensorData, gravity: &SensorData) -> SensorData {
+    SensorData {
+        x: accel.x - gravity.x,
+        y: accel.y - gravity.y,
+        z: accel.z - gravity.z,
+        accuracy: accel.accuracy,
+        timestamp: accel.timestamp,
+    }
+}
+
+/// Whether gravity can be estimated on this device.
+pub fn available() -> bool {
+    sys::accelerometer_available()
+}
+
+pub async fn gravity_read() -> Result<SensorData, SensorError> {
+    let (_, gravity) = GravityFilter::default().settle().await?;
+    Ok(gravity)
+}
+
+pub fn gravity_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+    if !available() {
+        return Err(SensorError::NotAvailable);
+    }
+    let interval = options.poll_interval();
+    Ok(Box::pin(stream::unfold(
+        GravityFilter::default(),
+        move |mut filter| async move {
+            futures_timer::Delay::new(interval).await;
+            let (_, gravity) = filter.sample().await.ok()?;
+            Some((gravity, filter))
+        },
+    )))
+}
+
+pub async fn linear_acceleration_read() -> Result<SensorData, SensorError> {
+    let (accel, gravity) = GravityFilter::default().settle().await?;
+    Ok(linear(&accel, &gravity))
+}
+
+pub fn linear_acceleration_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<SensorData>, SensorError> {
+    if !available() {
+        return Err(SensorError::NotAvailable);
+    }
+    let interval = options.poll_interval();
+    Ok(Box::pin(stream::unfold(
+        GravityFilter::default(),
+        move |mut filter| async move {
+            futures_timer::Delay::new(interval).await;
+            let (accel, gravity) = filter.sample().await.ok()?;
+            Some((linear(&accel, &gravity), filter))
+        },
+    )))
+}
@@ -1,7 +1,9 @@
 //! Cross-platform sensor access.
 //!
 //! This crate provides access to device sensors (accelerometer, gyroscope,
-//! magnetometer, barometer) across iOS, macOS, Android, Windows, and Linux.
+//! magnetometer, barometer, proximity, orientation, gravity, linear
+//! acceleration, compass, ambient temperature, humidity, significant motion)
+//! across iOS, macOS, Android, Windows, and Linux.
 //!
 //! # Usage
 //!
@@ -25,11 +27,32 @@
 
 #![warn(missing_docs)]
 
+/// Altitude from barometric pressure.
+mod altitude;
+/// Full-rate sample windows.
+mod batch;
+/// Magnetometer calibration status.
+mod calibration;
+/// Compass heading.
+mod compass;
+/// Software gravity estimate.
+mod fusion;
+/// Significant motion and stationary detection.
+mod motion;
+/// Sampling options for watches.
+mod options;
 /// Platform-specific implementations.
 mod sys;
 
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use std::pin::Pin;
+use std::time::Duration;
+
+pub use altitude::{AltitudeWatch, STANDARD_PRESSURE_HPA};
+pub use calibration::CalibrationStatus;
+pub use compass::{Compass, HeadingData};
+pub use motion::{MotionState, SignificantMotion};
+pub use options::{SamplingRate, SensorOptions};
 
 /// 3-axis sensor data (accelerometer, gyroscope, magnetometer).
 #[derive(Debug, Clone, PartialEq)]
@@ -40,11 +63,42 @@ pub struct SensorData {
     pub y: f64,
     /// Z-axis value.
     pub z: f64,
+    /// How far the reading can be trusted.
+    pub accuracy: SensorAccuracy,
     /// Timestamp as Unix epoch milliseconds.
     pub timestamp: u64,
 }
 
-/// Single-value sensor data (barometer).
+/// Accuracy of a [`SensorData`] reading, as reported by the platform.
+///
+/// Mostly relevant to the magnetometer, whose accuracy drops near metal or
+/// after the device was exposed to a strong field. Waving the device in a
+/// figure-eight recalibrates it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SensorAccuracy {
+    /// The platform doesn't report accuracy.
+    #[default]
+    Unknown,
+    /// The reading can't be trusted, and the sensor needs calibration.
+    Unreliable,
+    /// Low accuracy; calibration is recommended.
+    Low,
+    /// Medium accuracy.
+    Medium,
+    /// Maximum accuracy.
+    High,
+}
+
+impl SensorAccuracy {
+    /// Whether the user should be asked to calibrate the sensor.
+    #[must_use]
+    pub const fn needs_calibration(self) -> bool {
+        matches!(self, Self::Unreliable | Self::Low)
+    }
+}
+
+/// Single-value sensor data (barometer, ambient light, proximity, ambient
+/// temperature, humidity).
 #[derive(Debug, Clone, PartialEq)]
 pub struct ScalarData {
     /// Sensor value.
@@ -53,6 +107,63 @@ pub struct ScalarData {
     pub timestamp: u64,
 }
 
+/// What the yaw of an [`OrientationData`] is measured from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceFrame {
+    /// Magnetic north; add the local declination to get a true heading.
+    MagneticNorth,
+    /// True (geographic) north.
+    TrueNorth,
+    /// A heading fixed when updates started, without a magnetometer.
+    Arbitrary,
+}
+
+/// Fused device orientation (attitude).
+///
+/// The quaternion rotates the device's coordinate system into the world's
+/// East-North-Up system, where north is given by `reference`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrientationData {
+    /// X component of the quaternion.
+    pub x: f64,
+    /// Y component of the quaternion.
+    pub y: f64,
+    /// Z component of the quaternion.
+    pub z: f64,
+    /// Scalar component of the quaternion.
+    pub w: f64,
+    /// What north means for this reading.
+    pub reference: ReferenceFrame,
+    /// Timestamp as Unix epoch milliseconds.
+    pub timestamp: u64,
+}
+
+impl OrientationData {
+    /// Heading of the device's top edge in radians, clockwise from north,
+    /// in `-π..=π`.
+    #[must_use]
+    pub fn yaw(&self) -> f64 {
+        let Self { x, y, z, w, .. } = *self;
+        (2.0 * x)
+            .mul_add(y, -2.0 * w * z)
+            .atan2(2.0f64.mul_add(-x.mul_add(x, z * z), 1.0))
+    }
+
+    /// Rotation about the device's x axis in radians, in `-π/2..=π/2`.
+    #[must_use]
+    pub fn pitch(&self) -> f64 {
+        let Self { x, y, z, w, .. } = *self;
+        (-2.0 * y.mul_add(z, w * x)).clamp(-1.0, 1.0).asin()
+    }
+
+    /// Rotation about the device's y axis in radians, in `-π..=π`.
+    #[must_use]
+    pub fn roll(&self) -> f64 {
+        let Self { x, y, z, w, .. } = *self;
+        (-2.0 * x.mul_add(z, -w * y)).atan2(2.0f64.mul_add(-x.mul_add(x, y * y), 1.0))
+    }
+}
+
 /// Errors that can occur when accessing sensors.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum SensorError {
@@ -71,6 +182,8 @@ pub enum SensorError {
 }
 
 /// A boxed Stream of sensor data.
+///
+/// The sensor keeps running while the stream is alive; drop it to stop.
 pub type SensorStream<T> = Pin<Box<dyn Stream<Item = T> + Send>>;
 
 /// Accelerometer sensor (measures linear acceleration in g).
@@ -97,7 +210,27 @@ impl Accelerometer {
     /// # Errors
     /// Returns a [`SensorError`] if the sensor is not available.
     pub fn watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
-        sys::accelerometer_watch(interval_ms)
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+        sys::accelerometer_watch(options)
+    }
+
+    /// Collect every sample delivered over the next `window`, at the
+    /// fastest rate, in timestamp order.
+    ///
+    /// On Android the samples are batched in the sensor hub's FIFO so the
+    /// CPU can sleep. Timestamps share the epoch of [`read`](Self::read).
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub async fn read_batch(window: Duration) -> Result<Vec<SensorData>, SensorError> {
+        batch::read(Self::watch_with, window).await
     }
 }
 
@@ -108,6 +241,8 @@ pub struct Gyroscope;
 impl Gyroscope {
     /// Check if the gyroscope is available.
     #[must_use]
+    // Linux has no such sensor, so there it could be const
+    #[allow(clippy::missing_const_for_fn)]
     pub fn is_available() -> bool {
         sys::gyroscope_available()
     }
@@ -125,11 +260,35 @@ impl Gyroscope {
     /// # Errors
     /// Returns a [`SensorError`] if the sensor is not available.
     pub fn watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
-        sys::gyroscope_watch(interval_ms)
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+        sys::gyroscope_watch(options)
+    }
+
+    /// Collect every sample delivered over the next `window`, at the
+    /// fastest rate, in timestamp order.
+    ///
+    /// On Android the samples are batched in the sensor hub's FIFO so the
+    /// CPU can sleep. Timestamps share the epoch of [`read`](Self::read).
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub async fn read_batch(window: Duration) -> Result<Vec<SensorData>, SensorError> {
+        batch::read(Self::watch_with, window).await
     }
 }
 
 /// Magnetometer sensor.
+///
+/// Readings report their [`SensorAccuracy`] on Android and Windows; watch
+/// for [`SensorAccuracy::needs_calibration`] to prompt the user to
+/// calibrate the compass.
 #[derive(Debug)]
 pub struct Magnetometer;
 
@@ -145,7 +304,9 @@ impl Magnetometer {
     /// # Errors
     /// Returns a [`SensorError`] if the sensor is not available.
     pub async fn read() -> Result<SensorData, SensorError> {
-        sys::magnetometer_read().await
+        sys::magnetometer_read()
+            .await
+            .inspect(|data| calibration::record(data.accuracy))
     }
 
     /// Watch for sensor data updates at a specified interval.
@@ -153,7 +314,52 @@ impl Magnetometer {
     /// # Errors
     /// Returns a [`SensorError`] if the sensor is not available.
     pub fn watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
-        sys::magnetometer_watch(interval_ms)
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+        let stream = sys::magnetometer_watch(options)?;
+        Ok(Box::pin(
+            stream.inspect(|data| calibration::record(data.accuracy)),
+        ))
+    }
+
+    /// Whether the magnetometer needs calibration, according to the latest
+    /// magnetometer or compass reading.
+    ///
+    /// Returns [`CalibrationStatus::Unknown`] until something was read, and
+    /// where the platform doesn't report accuracy.
+    #[must_use]
+    pub fn calibration_status() -> CalibrationStatus {
+        calibration::status()
+    }
+
+    /// Watch the calibration status, yielding the first status and then
+    /// each change.
+    ///
+    /// Yields [`CalibrationStatus::NeedsCalibration`] when the accuracy
+    /// drops below medium, and [`CalibrationStatus::Calibrated`] once it
+    /// recovers. The stream keeps the compass, or the magnetometer where
+    /// there is no compass, running at the normal rate.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn needs_calibration_stream() -> Result<SensorStream<CalibrationStatus>, SensorError> {
+        calibration::watch()
+    }
+
+    /// Allow iOS to show its heading calibration screen while the compass
+    /// is running and its accuracy is poor. Off by default.
+    ///
+    /// Other platforms have no calibration screen and ignore this.
+    // Only iOS does anything here, so elsewhere it could be const
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn set_allow_calibration_ui(allow: bool) {
+        sys::set_allow_calibration_ui(allow);
     }
 }
 
@@ -164,6 +370,8 @@ pub struct Barometer;
 impl Barometer {
     /// Check if the barometer is available.
     #[must_use]
+    // Linux has no such sensor, so there it could be const
+    #[allow(clippy::missing_const_for_fn)]
     pub fn is_available() -> bool {
         sys::barometer_available()
     }
@@ -181,13 +389,51 @@ impl Barometer {
     /// # Errors
     /// Returns a [`SensorError`] if the sensor is not available.
     pub fn watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
-        sys::barometer_watch(interval_ms)
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+        sys::barometer_watch(options)
+    }
+
+    /// Read the altitude in metres from the barometric formula.
+    ///
+    /// The altitude is measured from the level where the pressure is
+    /// `reference_pressure_hpa`, by default [`STANDARD_PRESSURE_HPA`]. Pass
+    /// the current sea-level pressure from a weather service to get a true
+    /// altitude; otherwise it is off by up to a few hundred metres.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub async fn read_altitude(
+        reference_pressure_hpa: Option<f64>,
+    ) -> Result<ScalarData, SensorError> {
+        altitude::read(reference_pressure_hpa).await
+    }
+
+    /// Watch the change in altitude in metres since the first reading.
+    ///
+    /// On iOS the altitude comes from `CMAltimeter`'s relative altitude,
+    /// which fuses the barometer with the accelerometer. Elsewhere it is
+    /// computed from the pressure. Call [`AltitudeWatch::zero`] to track
+    /// changes from the current floor.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_altitude(options: SensorOptions) -> Result<AltitudeWatch, SensorError> {
+        altitude::watch(options)
     }
 }
 
 /// Ambient light sensor.
 ///
-/// Available on macOS (`MacBooks`) and some mobile devices.
+/// Available on macOS (`MacBooks`), some mobile devices, Windows devices with
+/// a light sensor, and Linux laptops through iio-sensor-proxy. Values are in
+/// lux, except from Linux drivers that only report vendor units.
 #[derive(Debug)]
 pub struct AmbientLight;
 
@@ -211,6 +457,305 @@ impl AmbientLight {
     /// # Errors
     /// Returns a [`SensorError`] if the sensor is not available.
     pub fn watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
-        sys::ambient_light_watch(interval_ms)
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+        sys::ambient_light_watch(options)
+    }
+}
+
+/// Ambient temperature sensor, in degrees Celsius.
+///
+/// Found on a few Android devices, and on Linux boards with an IIO or hwmon
+/// environmental sensor (one that also measures humidity or pressure).
+#[derive(Debug)]
+pub struct AmbientTemperature;
+
+impl AmbientTemperature {
+    /// Check if the ambient temperature sensor is available.
+    #[must_use]
+    pub fn is_available() -> bool {
+        sys::ambient_temperature_available()
+    }
+
+    /// Read the current sensor data.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub async fn read() -> Result<ScalarData, SensorError> {
+        sys::ambient_temperature_read().await
+    }
+
+    /// Watch for sensor data updates at a specified interval.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+        sys::ambient_temperature_watch(options)
+    }
+}
+
+/// Relative humidity sensor, in percent.
+///
+/// Found on a few Android devices, and on Linux boards with an IIO or hwmon
+/// humidity sensor.
+#[derive(Debug)]
+pub struct Humidity;
+
+impl Humidity {
+    /// Check if the humidity sensor is available.
+    #[must_use]
+    pub fn is_available() -> bool {
+        sys::humidity_available()
+    }
+
+    /// Read the current sensor data.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub async fn read() -> Result<ScalarData, SensorError> {
+        sys::humidity_read().await
+    }
+
+    /// Watch for sensor data updates at a specified interval.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+        sys::humidity_watch(options)
+    }
+}
+
+/// Proximity sensor.
+///
+/// Reports the distance to the nearest object in centimeters. Many sensors
+/// only tell near from far: they report `0.0` when something is close and
+/// their maximum range otherwise. iOS is always binary and reports `0.0` or
+/// [`Proximity::FAR_CM`].
+///
+/// On iOS, monitoring the proximity sensor turns the screen off while
+/// something is close to it. Monitoring stays enabled only while a
+/// [`watch`](Self::watch) stream is alive.
+#[derive(Debug)]
+pub struct Proximity;
+
+impl Proximity {
+    /// The distance reported on iOS when nothing is near the sensor.
+    pub const FAR_CM: f64 = 5.0;
+
+    /// Check if the proximity sensor is available.
+    #[must_use]
+    // Linux has no such sensor, so there it could be const
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn is_available() -> bool {
+        sys::proximity_available()
+    }
+
+    /// Read the current sensor data.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub async fn read() -> Result<ScalarData, SensorError> {
+        sys::proximity_read().await
+    }
+
+    /// Watch for sensor data updates at a specified interval.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+        sys::proximity_watch(options)
+    }
+}
+
+/// Fused orientation sensor.
+///
+/// Combines the accelerometer, gyroscope and (when present) magnetometer
+/// into the device's attitude, which is steadier than deriving it from the
+/// raw sensors.
+#[derive(Debug)]
+pub struct Orientation;
+
+impl Orientation {
+    /// Check if the orientation sensor is available.
+    #[must_use]
+    // Linux has no such sensor, so there it could be const
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn is_available() -> bool {
+        sys::orientation_available()
+    }
+
+    /// Read the current sensor data.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub async fn read() -> Result<OrientationData, SensorError> {
+        sys::orientation_read().await
+    }
+
+    /// Watch for sensor data updates at a specified interval.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch(interval_ms: u32) -> Result<SensorStream<OrientationData>, SensorError> {
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(
+        options: SensorOptions,
+    ) -> Result<SensorStream<OrientationData>, SensorError> {
+        sys::orientation_watch(options)
+    }
+}
+
+/// Gravity sensor.
+///
+/// Reports the part of the accelerometer reading caused by gravity, in the
+/// same units and axes as [`Accelerometer`]. Where the platform has no
+/// gravity sensor it is estimated from the accelerometer and gyroscope; see
+/// [`is_estimated`](Self::is_estimated).
+#[derive(Debug)]
+pub struct Gravity;
+
+impl Gravity {
+    /// Check if gravity can be measured or estimated.
+    #[must_use]
+    pub fn is_available() -> bool {
+        sys::gravity_available() || fusion::available()
+    }
+
+    /// Check if readings are estimated in software.
+    ///
+    /// Estimates lag behind fast rotations and, without a gyroscope, mistake
+    /// sustained acceleration for a tilt.
+    #[must_use]
+    pub fn is_estimated() -> bool {
+        !sys::gravity_available() && fusion::available()
+    }
+
+    /// Read the current sensor data.
+    ///
+    /// An estimated reading samples the accelerometer for about 200ms.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub async fn read() -> Result<SensorData, SensorError> {
+        if sys::gravity_available() {
+            sys::gravity_read().await
+        } else {
+            fusion::gravity_read().await
+        }
+    }
+
+    /// Watch for sensor data updates at a specified interval.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+        if sys::gravity_available() {
+            sys::gravity_watch(options)
+        } else {
+            fusion::gravity_watch(options)
+        }
+    }
+}
+
+/// Linear acceleration sensor.
+///
+/// Reports the accelerometer reading with gravity removed, so a device at
+/// rest reads zero on every axis. Where the platform has no such sensor it
+/// is estimated like [`Gravity`]; see [`is_estimated`](Self::is_estimated).
+#[derive(Debug)]
+pub struct LinearAcceleration;
+
+impl LinearAcceleration {
+    /// Check if linear acceleration can be measured or estimated.
+    #[must_use]
+    pub fn is_available() -> bool {
+        sys::linear_acceleration_available() || fusion::available()
+    }
+
+    /// Check if readings are estimated in software.
+    ///
+    /// Estimates lag behind fast rotations and, without a gyroscope, fade
+    /// towards zero during sustained acceleration.
+    #[must_use]
+    pub fn is_estimated() -> bool {
+        !sys::linear_acceleration_available() && fusion::available()
+    }
+
+    /// Read the current sensor data.
+    ///
+    /// An estimated reading samples the accelerometer for about 200ms.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub async fn read() -> Result<SensorData, SensorError> {
+        if sys::linear_acceleration_available() {
+            sys::linear_acceleration_read().await
+        } else {
+            fusion::linear_acceleration_read().await
+        }
+    }
+
+    /// Watch for sensor data updates at a specified interval.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+        Self::watch_with(SensorOptions::every(interval_ms))
+    }
+
+    /// Watch for sensor data updates with the given sampling options.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if the sensor is not available.
+    pub fn watch_with(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+        if sys::linear_acceleration_available() {
+            sys::linear_acceleration_watch(options)
+        } else {
+            fusion::linear_acceleration_watch(options)
+        }
     }
 }
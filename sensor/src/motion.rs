@@ -0,0 +1,159 @@
+//! Significant motion and stationary detection.
+//!
+//! Where the platform has no detector, motion is estimated from the
+//! accelerometer: the device counts as moving while the magnitude of its
+//! acceleration varies by more than a threshold over a short window.
+
+use crate::{Accelerometer, SensorError, SensorStream, sys};
+use futures::{StreamExt, stream};
+use std::collections::VecDeque;
+
+/// Interval between the accelerometer samples of an estimate.
+const SAMPLE_INTERVAL_MS: u32 = 50;
+
+/// Samples in the window of an estimate, covering two seconds.
+const WINDOW: usize = 40;
+
+/// Standard deviation of the acceleration magnitude in g above which a
+/// stationary device starts moving.
+const MOVING_THRESHOLD: f64 = 0.05;
+
+/// Standard deviation below which a moving device comes to rest. Lower
+/// than [`MOVING_THRESHOLD`] so the state doesn't flap near the threshold.
+const STATIONARY_THRESHOLD: f64 = 0.02;
+
+/// Whether the device is moving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MotionState {
+    /// The device is at rest, such as lying on a table.
+    Stationary,
+    /// The device is carried, driven or otherwise moved.
+    Moving,
+}
+
+impl MotionState {
+    /// Check if the motion state can be detected or estimated.
+    #[must_use]
+    pub fn is_available() -> bool {
+        sys::motion_state_available() || Accelerometer::is_available()
+    }
+
+    /// Check if states are estimated from the accelerometer.
+    #[must_use]
+    pub fn is_estimated() -> bool {
+        !sys::motion_state_available() && Accelerometer::is_available()
+    }
+
+    /// Watch the motion state.
+    ///
+    /// Yields the state once it is known, then each change. On iOS it comes
+    /// from `CMMotionActivityManager`, which counts walking, running,
+    /// cycling and driving as moving and needs `NSMotionUsageDescription`.
+    /// Elsewhere it is estimated, which keeps the accelerometer running.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if motion can't be detected, or motion
+    /// activity access was denied.
+    pub fn watch() -> Result<SensorStream<Self>, SensorError> {
+        if sys::motion_state_available() {
+            sys::motion_state_watch()
+        } else {
+            estimate()
+        }
+    }
+}
+
+/// One-shot detector of motion that likely takes the device somewhere
+/// else, such as walking, cycling or driving.
+///
+/// Android has a low-power trigger sensor for it that keeps working while
+/// the device sleeps. Elsewhere it waits for [`MotionState::Moving`].
+#[derive(Debug)]
+pub struct SignificantMotion;
+
+impl SignificantMotion {
+    /// Check if significant motion can be detected.
+    #[must_use]
+    pub fn is_available() -> bool {
+        sys::significant_motion_available() || MotionState::is_available()
+    }
+
+    /// Wait until significant motion is detected.
+    ///
+    /// Like Android's trigger sensor this fires once; call it again to wait
+    /// for the next motion.
+    ///
+    /// # Errors
+    /// Returns a [`SensorError`] if motion can't be detected.
+    pub async fn wait() -> Result<(), SensorError> {
+        if sys::significant_motion_available() {
+            return sys::significant_motion_wait().await;
+        }
+        let mut states = MotionState::watch()?;
+        while let Some(state) = states.next().await {
+            if state == MotionState::Moving {
+                return Ok(());
+            }
+        }
+        Err(SensorError::Unknown("motion updates stopped".into()))
+    }
+}
+
+/// Estimate the motion state from the accelerometer.
+fn estimate() -> Result<SensorStream<MotionState>, SensorError> {
+    let samples = Accelerometer::watch(SAMPLE_INTERVAL_MS)?;
+    Ok(Box::pin(stream::unfold(
+        (samples, MotionDetector::default()),
+        |(mut samples, mut detector)| async move {
+            loop {
+                let data = samples.next().await?;
+                let magnitude = data.x.hypot(data.y).hypot(data.z);
+                if let Some(state) = detector.update(magnitude) {
+                    return Some((state, (samples, detector)));
+                }
+            }
+        },
+    )))
+}
+
+/// Classifies a window of acceleration magnitudes as moving or not.
+#[derive(Debug, Default)]
+struct MotionDetector {
+    window: VecDeque<f64>,
+    state: Option<MotionState>,
+}
+
+impl MotionDetector {
+    /// Feed the magnitude of an accelerometer sample. Returns the state
+    /// when it changes.
+    fn update(&mut self, magnitude: f64) -> Option<MotionState> {
+        if self.window.len() == WINDOW {
+            self.window.pop_front();
+        }
+        self.window.push_back(magnitude);
+        if self.window.len() < WINDOW {
+            return None;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let n = WINDOW as f64;
+        let mean = self.window.iter().sum::<f64>() / n;
+        let variance = self.window.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / n;
+        let threshold = if self.state == Some(MotionState::Moving) {
+            STATIONARY_THRESHOLD
+        } else {
+            MOVING_THRESHOLD
+        };
+        let state = if variance.sqrt() > threshold {
+            MotionState::Moving
+        } else {
+            MotionState::Stationary
+        };
+
+        if self.state == Some(state) {
+            return None;
+        }
+        self.state = Some(state);
+        Some(state)
+    }
+}
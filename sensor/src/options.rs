@@ -0,0 +1,74 @@
+//! Sampling options for sensor watches.
+
+use std::time::Duration;
+
+/// Interval between polls for [`SamplingRate::Fastest`] on platforms
+/// without a push API, the 100Hz most motion sensors top out at.
+const FASTEST_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How often a watched sensor delivers readings.
+///
+/// The named rates mirror Android's `SENSOR_DELAY_*` constants. They are
+/// hints: the platform may deliver readings faster or slower.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SamplingRate {
+    /// As fast as the sensor can report.
+    Fastest,
+    /// Suitable for games, about 50Hz.
+    Game,
+    /// Suitable for updating the user interface, about 15Hz.
+    Ui,
+    /// Suitable for orientation changes, about 5Hz.
+    #[default]
+    Normal,
+    /// A specific interval between readings.
+    Custom(Duration),
+}
+
+impl SamplingRate {
+    /// The interval between readings this rate asks for.
+    ///
+    /// [`Fastest`](Self::Fastest) has no interval and returns zero.
+    #[must_use]
+    pub const fn period(self) -> Duration {
+        match self {
+            Self::Fastest => Duration::ZERO,
+            Self::Game => Duration::from_millis(20),
+            Self::Ui => Duration::from_micros(66_667),
+            Self::Normal => Duration::from_millis(200),
+            Self::Custom(period) => period,
+        }
+    }
+}
+
+/// Options for [`watch_with`](crate::Accelerometer::watch_with).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SensorOptions {
+    /// How often the sensor should deliver readings.
+    pub rate: SamplingRate,
+    /// How long readings may be held back and delivered in a batch.
+    ///
+    /// On Android this enables the sensor hub's hardware FIFO, which lets
+    /// the application processor sleep between batches. Every reading is
+    /// still delivered, with its original timestamp. `None` delivers
+    /// readings as they arrive. Other platforms ignore it.
+    pub max_report_latency: Option<Duration>,
+}
+
+impl SensorOptions {
+    /// Options for a watch polled every `interval_ms` milliseconds.
+    pub(crate) fn every(interval_ms: u32) -> Self {
+        Self {
+            rate: SamplingRate::Custom(Duration::from_millis(u64::from(interval_ms))),
+            max_report_latency: None,
+        }
+    }
+
+    /// How long platforms that poll wait between readings.
+    pub(crate) const fn poll_interval(self) -> Duration {
+        match self.rate {
+            SamplingRate::Fastest => FASTEST_POLL_INTERVAL,
+            rate => rate.period(),
+        }
+    }
+}
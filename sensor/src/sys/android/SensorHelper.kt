@@ -1,12 +1,18 @@
 package waterkit.sensor
 
 import android.content.Context
+import android.hardware.GeomagneticField
 import android.hardware.Sensor
 import android.hardware.SensorEvent
 import android.hardware.SensorEventListener
 import android.hardware.SensorManager
+import android.hardware.TriggerEvent
+import android.hardware.TriggerEventListener
 import android.os.Handler
+import android.os.HandlerThread
 import android.os.Looper
+import android.os.SystemClock
+import java.util.concurrent.atomic.AtomicInteger
 
 /**
  * Helper class for accessing sensors on Android.
@@ -19,6 +25,37 @@ object SensorHelper {
     const val TYPE_GYROSCOPE = 4
     const val TYPE_MAGNETOMETER = 2
     const val TYPE_PRESSURE = 6
+    const val TYPE_PROXIMITY = 8
+    const val TYPE_GRAVITY = 9
+    const val TYPE_LINEAR_ACCELERATION = 10
+    const val TYPE_ROTATION_VECTOR = 11
+    const val TYPE_RELATIVE_HUMIDITY = 12
+    const val TYPE_AMBIENT_TEMPERATURE = 13
+    const val TYPE_SIGNIFICANT_MOTION = 17
+
+    /** Length of each record returned by [drainWatch]. */
+    private const val RECORD_LEN = 6
+
+    /** Records buffered per watch; the oldest are dropped if Rust falls behind. */
+    private const val WATCH_CAPACITY = 512
+
+    /** Active watches, keyed by the ID returned from [startWatch]. */
+    private val watches = HashMap<Int, Watch>()
+    private val watchIds = AtomicInteger(1)
+
+    /** Delivers watch events off the main thread, started on first use. */
+    private val watchThread: HandlerThread by lazy {
+        HandlerThread("waterkit-sensor").apply { start() }
+    }
+
+    /**
+     * Convert a [SensorEvent.timestamp], in nanoseconds since boot, to Unix
+     * epoch milliseconds like the timestamps on other platforms.
+     */
+    private fun epochMillis(timestampNs: Long): Double {
+        val ageNs = SystemClock.elapsedRealtimeNanos() - timestampNs
+        return System.currentTimeMillis() - ageNs / 1_000_000.0
+    }
 
     /**
      * Check if a sensor type is available.
@@ -31,8 +68,9 @@ object SensorHelper {
     }
 
     /**
-     * Read a 3-axis sensor (accelerometer, gyroscope, magnetometer).
-     * Returns array: [success, x, y, z, timestamp]
+     * Read a 3-axis sensor (accelerometer, gyroscope, magnetometer, gravity,
+     * linear acceleration).
+     * Returns array: [success, x, y, z, timestamp, accuracy]
      * On failure: [0.0]
      */
     @JvmStatic
@@ -54,7 +92,8 @@ object SensorHelper {
                         event.values[0].toDouble(),
                         event.values[1].toDouble(),
                         event.values[2].toDouble(),
-                        event.timestamp.toDouble() / 1_000_000.0 // ns to ms
+                        epochMillis(event.timestamp),
+                        event.accuracy.toDouble() // SENSOR_STATUS_*
                     )
                 }
                 synchronized(lock) {
@@ -103,7 +142,7 @@ object SensorHelper {
                     result = doubleArrayOf(
                         1.0, // success
                         event.values[0].toDouble(), // pressure in hPa
-                        event.timestamp.toDouble() / 1_000_000.0 // ns to ms
+                        epochMillis(event.timestamp)
                     )
                 }
                 synchronized(lock) {
@@ -131,6 +170,56 @@ object SensorHelper {
         return result ?: doubleArrayOf(0.0)
     }
 
+    /**
+     * Read the first value of a single-value sensor, such as ambient
+     * temperature (°C) or relative humidity (%).
+     * Returns array: [success, value, timestamp]
+     * On failure: [0.0]
+     */
+    @JvmStatic
+    fun readScalar(context: Context, sensorType: Int): DoubleArray {
+        val manager = context.getSystemService(Context.SENSOR_SERVICE) as? SensorManager
+            ?: return doubleArrayOf(0.0)
+
+        val sensor = manager.getDefaultSensor(sensorType)
+            ?: return doubleArrayOf(0.0)
+
+        var result: DoubleArray? = null
+        val lock = Object()
+
+        val listener = object : SensorEventListener {
+            override fun onSensorChanged(event: SensorEvent) {
+                if (event.values.isNotEmpty()) {
+                    result = doubleArrayOf(
+                        1.0, // success
+                        event.values[0].toDouble(),
+                        epochMillis(event.timestamp)
+                    )
+                }
+                synchronized(lock) {
+                    lock.notify()
+                }
+            }
+
+            override fun onAccuracyChanged(sensor: Sensor, accuracy: Int) {}
+        }
+
+        val handler = Handler(Looper.getMainLooper())
+        manager.registerListener(listener, sensor, SensorManager.SENSOR_DELAY_NORMAL, handler)
+
+        synchronized(lock) {
+            try {
+                lock.wait(1000)
+            } catch (e: InterruptedException) {
+                // Ignored
+            }
+        }
+
+        manager.unregisterListener(listener)
+
+        return result ?: doubleArrayOf(0.0)
+    }
+
     /**
      * Read ambient light sensor.
      * Returns array: [success, lux, timestamp]
@@ -153,7 +242,111 @@ object SensorHelper {
                     result = doubleArrayOf(
                         1.0, // success
                         event.values[0].toDouble(), // light in lux
-                        event.timestamp.toDouble() / 1_000_000.0 // ns to ms
+                        epochMillis(event.timestamp)
+                    )
+                }
+                synchronized(lock) {
+                    lock.notify()
+                }
+            }
+
+            override fun onAccuracyChanged(sensor: Sensor, accuracy: Int) {}
+        }
+
+        val handler = Handler(Looper.getMainLooper())
+        manager.registerListener(listener, sensor, SensorManager.SENSOR_DELAY_GAME, handler)
+
+        synchronized(lock) {
+            try {
+                lock.wait(1000)
+            } catch (e: InterruptedException) {
+                // Ignored
+            }
+        }
+
+        manager.unregisterListener(listener)
+
+        return result ?: doubleArrayOf(0.0)
+    }
+
+    /**
+     * Read proximity sensor.
+     * Returns array: [success, distance_cm, timestamp]
+     * On failure: [0.0]
+     */
+    @JvmStatic
+    fun readProximity(context: Context): DoubleArray {
+        val manager = context.getSystemService(Context.SENSOR_SERVICE) as? SensorManager
+            ?: return doubleArrayOf(0.0)
+
+        val sensor = manager.getDefaultSensor(Sensor.TYPE_PROXIMITY)
+            ?: return doubleArrayOf(0.0)
+
+        var result: DoubleArray? = null
+        val lock = Object()
+
+        val listener = object : SensorEventListener {
+            override fun onSensorChanged(event: SensorEvent) {
+                if (event.values.isNotEmpty()) {
+                    result = doubleArrayOf(
+                        1.0, // success
+                        event.values[0].toDouble(), // distance in cm
+                        epochMillis(event.timestamp)
+                    )
+                }
+                synchronized(lock) {
+                    lock.notify()
+                }
+            }
+
+            override fun onAccuracyChanged(sensor: Sensor, accuracy: Int) {}
+        }
+
+        val handler = Handler(Looper.getMainLooper())
+        manager.registerListener(listener, sensor, SensorManager.SENSOR_DELAY_GAME, handler)
+
+        synchronized(lock) {
+            try {
+                lock.wait(1000)
+            } catch (e: InterruptedException) {
+                // Ignored
+            }
+        }
+
+        manager.unregisterListener(listener)
+
+        return result ?: doubleArrayOf(0.0)
+    }
+
+    /**
+     * Read rotation vector sensor as a quaternion.
+     * Returns array: [success, x, y, z, w, timestamp, accuracy]
+     * On failure: [0.0]
+     */
+    @JvmStatic
+    fun readRotationVector(context: Context): DoubleArray {
+        val manager = context.getSystemService(Context.SENSOR_SERVICE) as? SensorManager
+            ?: return doubleArrayOf(0.0)
+
+        val sensor = manager.getDefaultSensor(Sensor.TYPE_ROTATION_VECTOR)
+            ?: return doubleArrayOf(0.0)
+
+        var result: DoubleArray? = null
+        val lock = Object()
+
+        val listener = object : SensorEventListener {
+            override fun onSensorChanged(event: SensorEvent) {
+                if (event.values.size >= 3) {
+                    val q = FloatArray(4) // w, x, y, z
+                    SensorManager.getQuaternionFromVector(q, event.values)
+                    result = doubleArrayOf(
+                        1.0, // success
+                        q[1].toDouble(),
+                        q[2].toDouble(),
+                        q[3].toDouble(),
+                        q[0].toDouble(),
+                        epochMillis(event.timestamp),
+                        event.accuracy.toDouble()
                     )
                 }
                 synchronized(lock) {
@@ -179,4 +372,180 @@ object SensorHelper {
 
         return result ?: doubleArrayOf(0.0)
     }
+
+    /**
+     * Magnetic declination in degrees at the given position today, positive
+     * when magnetic north is east of true north.
+     */
+    @JvmStatic
+    fun declination(latitude: Double, longitude: Double, altitude: Double): Double {
+        val field = GeomagneticField(
+            latitude.toFloat(),
+            longitude.toFloat(),
+            altitude.toFloat(),
+            System.currentTimeMillis()
+        )
+        return field.declination.toDouble()
+    }
+
+    /**
+     * Register a persistent listener for [sensorType], delivering events
+     * about every [periodUs] microseconds into a buffer drained by
+     * [drainWatch]. Returns the watch ID, or 0 if the sensor is missing.
+     *
+     * [periodUs] may also be one of the `SENSOR_DELAY_*` constants. A
+     * positive [maxLatencyUs] lets the sensor hub batch events in its FIFO
+     * for up to that long; sensors without a FIFO ignore it.
+     */
+    @JvmStatic
+    fun startWatch(context: Context, sensorType: Int, periodUs: Int, maxLatencyUs: Int): Int {
+        val manager = context.getSystemService(Context.SENSOR_SERVICE) as? SensorManager
+            ?: return 0
+
+        val sensor = manager.getDefaultSensor(sensorType)
+            ?: return 0
+
+        val id = watchIds.getAndIncrement()
+        val watch = Watch(manager)
+        synchronized(watches) { watches[id] = watch }
+        manager.registerListener(
+            watch,
+            sensor,
+            periodUs,
+            maxLatencyUs,
+            Handler(watchThread.looper)
+        )
+        return id
+    }
+
+    /**
+     * Take the events buffered for watch [id], flattened into records of
+     * [v0, v1, v2, v3, timestamp, accuracy]. 3-axis and scalar sensors fill
+     * the leading values; the rotation vector is a quaternion [x, y, z, w].
+     */
+    @JvmStatic
+    fun drainWatch(id: Int): DoubleArray {
+        val watch = synchronized(watches) { watches[id] }
+            ?: return DoubleArray(0)
+        return watch.drain()
+    }
+
+    /** Unregister the listener of watch [id], letting the sensor power down. */
+    @JvmStatic
+    fun stopWatch(id: Int) {
+        val watch = synchronized(watches) { watches.remove(id) }
+            ?: return
+        watch.manager.unregisterListener(watch)
+    }
+
+    /** Pending trigger requests, keyed by the ID returned from [requestTrigger]. */
+    private val triggers = HashMap<Int, Trigger>()
+
+    /**
+     * Request a one-shot trigger sensor such as [TYPE_SIGNIFICANT_MOTION].
+     * Returns the request ID, or 0 if the sensor is missing.
+     */
+    @JvmStatic
+    fun requestTrigger(context: Context, sensorType: Int): Int {
+        val manager = context.getSystemService(Context.SENSOR_SERVICE) as? SensorManager
+            ?: return 0
+
+        val sensor = manager.getDefaultSensor(sensorType)
+            ?: return 0
+
+        val id = watchIds.getAndIncrement()
+        val trigger = Trigger(manager, sensor)
+        synchronized(triggers) { triggers[id] = trigger }
+        if (!manager.requestTriggerSensor(trigger, sensor)) {
+            synchronized(triggers) { triggers.remove(id) }
+            return 0
+        }
+        return id
+    }
+
+    /** Whether trigger request [id] has fired. */
+    @JvmStatic
+    fun isTriggered(id: Int): Boolean {
+        val trigger = synchronized(triggers) { triggers[id] }
+            ?: return false
+        return trigger.fired
+    }
+
+    /** Cancel trigger request [id] if it hasn't fired yet. */
+    @JvmStatic
+    fun cancelTrigger(id: Int) {
+        val trigger = synchronized(triggers) { triggers.remove(id) }
+            ?: return
+        trigger.manager.cancelTriggerSensor(trigger, trigger.sensor)
+    }
+
+    /** A one-shot trigger request; the sensor disables itself once fired. */
+    private class Trigger(val manager: SensorManager, val sensor: Sensor) : TriggerEventListener() {
+        @Volatile
+        var fired = false
+
+        override fun onTrigger(event: TriggerEvent) {
+            fired = true
+        }
+    }
+
+    /** A persistent listener and the records it has buffered for Rust. */
+    private class Watch(val manager: SensorManager) : SensorEventListener {
+        private val records = ArrayDeque<DoubleArray>()
+        private var last: DoubleArray? = null
+
+        override fun onSensorChanged(event: SensorEvent) {
+            push(record(event))
+        }
+
+        override fun onAccuracyChanged(sensor: Sensor, accuracy: Int) {
+            // Repeat the last event with the new accuracy, so a drop shows up
+            // without waiting for the next event
+            val last = synchronized(records) { last } ?: return
+            push(last.copyOf().also { it[RECORD_LEN - 1] = accuracy.toDouble() })
+        }
+
+        private fun push(record: DoubleArray) {
+            synchronized(records) {
+                if (records.size == WATCH_CAPACITY) {
+                    records.removeFirst()
+                }
+                records.addLast(record)
+                last = record
+            }
+        }
+
+        fun drain(): DoubleArray {
+            synchronized(records) {
+                val out = DoubleArray(records.size * RECORD_LEN)
+                records.forEachIndexed { i, record -> record.copyInto(out, i * RECORD_LEN) }
+                records.clear()
+                return out
+            }
+        }
+
+        private fun record(event: SensorEvent): DoubleArray {
+            val values = DoubleArray(4)
+            if (event.sensor.type == TYPE_ROTATION_VECTOR) {
+                val q = FloatArray(4) // w, x, y, z
+                SensorManager.getQuaternionFromVector(q, event.values)
+                values[0] = q[1].toDouble()
+                values[1] = q[2].toDouble()
+                values[2] = q[3].toDouble()
+                values[3] = q[0].toDouble()
+            } else {
+                for (i in 0 until minOf(3, event.values.size)) {
+                    values[i] = event.values[i].toDouble()
+                }
+            }
+            return doubleArrayOf(
+                values[0],
+                values[1],
+                values[2],
+                values[3],
+                epochMillis(event.timestamp),
+                event.accuracy.toDouble() // SENSOR_STATUS_*
+            )
+        }
+    }
 }
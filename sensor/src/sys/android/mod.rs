@@ -1,10 +1,15 @@
 //! Android sensor implementation using JNI.
 
-use crate::{ScalarData, SensorData, SensorError, SensorStream};
+use crate::{
+    HeadingData, MotionState, OrientationData, ReferenceFrame, SamplingRate, ScalarData,
+    SensorAccuracy, SensorData, SensorError, SensorOptions, SensorStream,
+};
 use futures::stream;
 use jni::objects::{GlobalRef, JObject, JValue};
 use jni::{JNIEnv, JavaVM};
+use std::collections::VecDeque;
 use std::sync::OnceLock;
+use std::time::Duration;
 
 /// Embedded DEX bytecode containing SensorHelper class.
 static DEX_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/classes.dex"));
@@ -68,7 +73,7 @@ fn init_with_context(env: &mut JNIEnv, context: &JObject) -> Result<(), SensorEr
             .to_str()
             .map_err(|e| SensorError::Unknown(format!("to_str failed: {e}")))?
     );
-    
+
     // Remove if exists to handle previous read-only setting
     let _ = std::fs::remove_file(&dex_path);
 
@@ -171,6 +176,18 @@ fn get_env_and_context() -> Result<(jni::AttachGuard<'static>, JObject<'static>)
     Ok((env, local_ref))
 }
 
+/// Map a `SensorManager.SENSOR_STATUS_*` value to [`SensorAccuracy`].
+fn convert_accuracy(status: f64) -> SensorAccuracy {
+    match status as i32 {
+        0 => SensorAccuracy::Unreliable,
+        1 => SensorAccuracy::Low,
+        2 => SensorAccuracy::Medium,
+        3 => SensorAccuracy::High,
+        // SENSOR_STATUS_NO_CONTACT and anything newer
+        _ => SensorAccuracy::Unknown,
+    }
+}
+
 fn parse_sensor_result(env: &mut JNIEnv, result: JObject) -> Result<SensorData, SensorError> {
     let arr: jni::objects::JDoubleArray = result.into();
     let len =
@@ -189,7 +206,7 @@ fn parse_sensor_result(env: &mut JNIEnv, result: JObject) -> Result<SensorData,
         return Err(SensorError::NotAvailable);
     }
 
-    if len < 5 {
+    if len < 6 {
         return Err(SensorError::Unknown("Invalid result array".into()));
     }
 
@@ -197,6 +214,7 @@ fn parse_sensor_result(env: &mut JNIEnv, result: JObject) -> Result<SensorData,
         x: buf[1],
         y: buf[2],
         z: buf[3],
+        accuracy: convert_accuracy(buf[5]),
         timestamp: buf[4] as u64,
     })
 }
@@ -229,6 +247,89 @@ fn parse_scalar_result(env: &mut JNIEnv, result: JObject) -> Result<ScalarData,
     })
 }
 
+fn parse_orientation_result(
+    env: &mut JNIEnv,
+    result: JObject,
+) -> Result<OrientationData, SensorError> {
+    let arr: jni::objects::JDoubleArray = result.into();
+    let len =
+        env.get_array_length(&arr)
+            .map_err(|e| SensorError::Unknown(format!("get_array_length: {e}")))? as usize;
+
+    if len < 1 {
+        return Err(SensorError::NotAvailable);
+    }
+
+    let mut buf = vec![0.0f64; len];
+    env.get_double_array_region(&arr, 0, &mut buf)
+        .map_err(|e| SensorError::Unknown(format!("get_double_array_region: {e}")))?;
+
+    if buf[0] < 0.5 {
+        return Err(SensorError::NotAvailable);
+    }
+
+    if len < 6 {
+        return Err(SensorError::Unknown("Invalid result array".into()));
+    }
+
+    Ok(OrientationData {
+        x: buf[1],
+        y: buf[2],
+        z: buf[3],
+        w: buf[4],
+        // The rotation vector is referenced to geomagnetic north
+        reference: ReferenceFrame::MagneticNorth,
+        timestamp: buf[5] as u64,
+    })
+}
+
+/// Heading of the device's top edge from a rotation vector, which is
+/// referenced to magnetic north.
+///
+/// This is the azimuth `SensorManager.getOrientation` derives from the
+/// rotation matrix, in degrees.
+fn heading(orientation: &OrientationData, accuracy: SensorAccuracy) -> HeadingData {
+    HeadingData {
+        magnetic_heading: orientation.yaw().to_degrees().rem_euclid(360.0),
+        true_heading: None,
+        accuracy,
+        timestamp: orientation.timestamp,
+    }
+}
+
+fn parse_heading_result(env: &mut JNIEnv, result: JObject) -> Result<HeadingData, SensorError> {
+    let arr: jni::objects::JDoubleArray = result.into();
+    let len =
+        env.get_array_length(&arr)
+            .map_err(|e| SensorError::Unknown(format!("get_array_length: {e}")))? as usize;
+
+    if len < 1 {
+        return Err(SensorError::NotAvailable);
+    }
+
+    let mut buf = vec![0.0f64; len];
+    env.get_double_array_region(&arr, 0, &mut buf)
+        .map_err(|e| SensorError::Unknown(format!("get_double_array_region: {e}")))?;
+
+    if buf[0] < 0.5 {
+        return Err(SensorError::NotAvailable);
+    }
+
+    if len < 7 {
+        return Err(SensorError::Unknown("Invalid result array".into()));
+    }
+
+    let orientation = OrientationData {
+        x: buf[1],
+        y: buf[2],
+        z: buf[3],
+        w: buf[4],
+        reference: ReferenceFrame::MagneticNorth,
+        timestamp: buf[5] as u64,
+    };
+    Ok(heading(&orientation, convert_accuracy(buf[6])))
+}
+
 // Check sensor availability with manual context (helper)
 pub fn is_sensor_available_with_context(
     env: &mut JNIEnv,
@@ -326,6 +427,262 @@ pub fn read_light_with_context(
     parse_scalar_result(env, result)
 }
 
+// Read proximity with manual context (helper)
+pub fn read_proximity_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<ScalarData, SensorError> {
+    init_with_context(env, context)?;
+    let helper = load_helper_class(env)?;
+
+    let result = env
+        .call_static_method(
+            helper,
+            "readProximity",
+            "(Landroid/content/Context;)[D",
+            &[JValue::Object(context)],
+        )
+        .map_err(|e| SensorError::Unknown(format!("readProximity: {e}")))?
+        .l()
+        .map_err(|e| SensorError::Unknown(format!("readProximity result: {e}")))?;
+
+    parse_scalar_result(env, result)
+}
+
+// Read a single-value sensor with manual context (helper)
+pub fn read_scalar_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+    sensor_type: i32,
+) -> Result<ScalarData, SensorError> {
+    init_with_context(env, context)?;
+    let helper = load_helper_class(env)?;
+
+    let result = env
+        .call_static_method(
+            helper,
+            "readScalar",
+            "(Landroid/content/Context;I)[D",
+            &[JValue::Object(context), JValue::Int(sensor_type)],
+        )
+        .map_err(|e| SensorError::Unknown(format!("readScalar: {e}")))?
+        .l()
+        .map_err(|e| SensorError::Unknown(format!("readScalar result: {e}")))?;
+
+    parse_scalar_result(env, result)
+}
+
+// Read rotation vector with manual context (helper)
+pub fn read_orientation_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<OrientationData, SensorError> {
+    init_with_context(env, context)?;
+    let helper = load_helper_class(env)?;
+
+    let result = env
+        .call_static_method(
+            helper,
+            "readRotationVector",
+            "(Landroid/content/Context;)[D",
+            &[JValue::Object(context)],
+        )
+        .map_err(|e| SensorError::Unknown(format!("readRotationVector: {e}")))?
+        .l()
+        .map_err(|e| SensorError::Unknown(format!("readRotationVector result: {e}")))?;
+
+    parse_orientation_result(env, result)
+}
+
+// Read the compass heading with manual context (helper)
+pub fn read_heading_with_context(
+    env: &mut JNIEnv,
+    context: &JObject,
+) -> Result<HeadingData, SensorError> {
+    init_with_context(env, context)?;
+    let helper = load_helper_class(env)?;
+
+    let result = env
+        .call_static_method(
+            helper,
+            "readRotationVector",
+            "(Landroid/content/Context;)[D",
+            &[JValue::Object(context)],
+        )
+        .map_err(|e| SensorError::Unknown(format!("readRotationVector: {e}")))?
+        .l()
+        .map_err(|e| SensorError::Unknown(format!("readRotationVector result: {e}")))?;
+
+    parse_heading_result(env, result)
+}
+
+/// Fields in each record drained from a watch, see `SensorHelper.drainWatch`.
+const RECORD_LEN: usize = 6;
+
+/// A persistent `SensorEventListener` registered by the helper.
+///
+/// Dropping it unregisters the listener so the sensor can power down.
+#[derive(Debug)]
+struct Watch {
+    id: i32,
+}
+
+impl Watch {
+    fn start(sensor_type: i32, options: SensorOptions) -> Result<Self, SensorError> {
+        let (mut env, context) = get_env_and_context()?;
+        let helper = load_helper_class(&mut env)?;
+        let period_us = sampling_period_us(options.rate);
+        let latency_us = options.max_report_latency.map_or(0, micros);
+
+        let id = env
+            .call_static_method(
+                helper,
+                "startWatch",
+                "(Landroid/content/Context;III)I",
+                &[
+                    JValue::Object(&context),
+                    JValue::Int(sensor_type),
+                    JValue::Int(period_us),
+                    JValue::Int(latency_us),
+                ],
+            )
+            .map_err(|e| SensorError::Unknown(format!("startWatch: {e}")))?
+            .i()
+            .map_err(|e| SensorError::Unknown(format!("startWatch result: {e}")))?;
+
+        if id == 0 {
+            return Err(SensorError::NotAvailable);
+        }
+        Ok(Self { id })
+    }
+
+    /// Take the records buffered since the last drain.
+    fn drain(&self) -> Result<Vec<[f64; RECORD_LEN]>, SensorError> {
+        let (mut env, _) = get_env_and_context()?;
+        let helper = load_helper_class(&mut env)?;
+
+        let result = env
+            .call_static_method(helper, "drainWatch", "(I)[D", &[JValue::Int(self.id)])
+            .map_err(|e| SensorError::Unknown(format!("drainWatch: {e}")))?
+            .l()
+            .map_err(|e| SensorError::Unknown(format!("drainWatch result: {e}")))?;
+
+        let arr: jni::objects::JDoubleArray = result.into();
+        let len = env
+            .get_array_length(&arr)
+            .map_err(|e| SensorError::Unknown(format!("get_array_length: {e}")))?
+            as usize;
+
+        let mut buf = vec![0.0f64; len];
+        env.get_double_array_region(&arr, 0, &mut buf)
+            .map_err(|e| SensorError::Unknown(format!("get_double_array_region: {e}")))?;
+
+        Ok(buf
+            .chunks_exact(RECORD_LEN)
+            .map(|record| {
+                let mut out = [0.0; RECORD_LEN];
+                out.copy_from_slice(record);
+                out
+            })
+            .collect())
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let stop = || -> Result<(), SensorError> {
+            let (mut env, _) = get_env_and_context()?;
+            let helper = load_helper_class(&mut env)?;
+            env.call_static_method(helper, "stopWatch", "(I)V", &[JValue::Int(self.id)])
+                .map_err(|e| SensorError::Unknown(format!("stopWatch: {e}")))?;
+            Ok(())
+        };
+        if let Err(e) = stop() {
+            log::error!("Failed to stop sensor watch: {e}");
+        }
+    }
+}
+
+/// A duration in whole microseconds, saturating at `i32::MAX`.
+fn micros(duration: Duration) -> i32 {
+    i32::try_from(duration.as_micros()).unwrap_or(i32::MAX)
+}
+
+/// The `samplingPeriodUs` argument of `SensorManager.registerListener`.
+///
+/// Values up to 3 are read as the `SENSOR_DELAY_*` constants, so named
+/// rates pass those and custom periods are kept above them.
+fn sampling_period_us(rate: SamplingRate) -> i32 {
+    match rate {
+        SamplingRate::Fastest => 0,
+        SamplingRate::Game => 1,
+        SamplingRate::Ui => 2,
+        SamplingRate::Normal => 3,
+        SamplingRate::Custom(period) => micros(period).max(4),
+    }
+}
+
+/// Stream the events of `sensor_type` from a persistent listener.
+///
+/// The listener buffers every event the sensor delivers; the stream drains
+/// the buffer once per interval, or once per batch when batching, and
+/// yields the events in order.
+fn watch<T: Send + 'static>(
+    sensor_type: i32,
+    options: SensorOptions,
+    convert: fn(&[f64; RECORD_LEN]) -> T,
+) -> Result<SensorStream<T>, SensorError> {
+    let watch = Watch::start(sensor_type, options)?;
+    let poll = options.poll_interval();
+    let interval = options
+        .max_report_latency
+        .map_or(poll, |latency| latency.max(poll));
+    Ok(Box::pin(stream::unfold(
+        (watch, VecDeque::new()),
+        move |(watch, mut pending)| async move {
+            loop {
+                if let Some(record) = pending.pop_front() {
+                    return Some((convert(&record), (watch, pending)));
+                }
+                futures_timer::Delay::new(interval).await;
+                pending.extend(watch.drain().ok()?);
+            }
+        },
+    )))
+}
+
+fn sensor_record(record: &[f64; RECORD_LEN]) -> SensorData {
+    SensorData {
+        x: record[0],
+        y: record[1],
+        z: record[2],
+        accuracy: convert_accuracy(record[5]),
+        timestamp: record[4] as u64,
+    }
+}
+
+fn scalar_record(record: &[f64; RECORD_LEN]) -> ScalarData {
+    ScalarData {
+        value: record[0],
+        timestamp: record[4] as u64,
+    }
+}
+
+fn orientation_record(record: &[f64; RECORD_LEN]) -> OrientationData {
+    OrientationData {
+        x: record[0],
+        y: record[1],
+        z: record[2],
+        w: record[3],
+        reference: ReferenceFrame::MagneticNorth,
+        timestamp: record[4] as u64,
+    }
+}
+
+fn heading_record(record: &[f64; RECORD_LEN]) -> HeadingData {
+    heading(&orientation_record(record), convert_accuracy(record[5]))
+}
+
 // --- Parameter-less API Implementation using Global Context ---
 
 pub fn accelerometer_available() -> bool {
@@ -341,18 +698,10 @@ pub async fn accelerometer_read() -> Result<SensorData, SensorError> {
     read_sensor_with_context(&mut env, &context, 1)
 }
 
-pub fn accelerometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
-    if !accelerometer_available() {
-        return Err(SensorError::NotAvailable);
-    }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
-    Ok(Box::pin(stream::unfold((), move |()| async move {
-        futures_timer::Delay::new(interval).await;
-        match accelerometer_read().await {
-            Ok(data) => Some((data, ())),
-            _ => None,
-        }
-    })))
+pub fn accelerometer_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<SensorData>, SensorError> {
+    watch(1, options, sensor_record)
 }
 
 pub fn gyroscope_available() -> bool {
@@ -368,18 +717,8 @@ pub async fn gyroscope_read() -> Result<SensorData, SensorError> {
     read_sensor_with_context(&mut env, &context, 4)
 }
 
-pub fn gyroscope_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
-    if !gyroscope_available() {
-        return Err(SensorError::NotAvailable);
-    }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
-    Ok(Box::pin(stream::unfold((), move |()| async move {
-        futures_timer::Delay::new(interval).await;
-        match gyroscope_read().await {
-            Ok(data) => Some((data, ())),
-            _ => None,
-        }
-    })))
+pub fn gyroscope_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+    watch(4, options, sensor_record)
 }
 
 pub fn magnetometer_available() -> bool {
@@ -395,18 +734,8 @@ pub async fn magnetometer_read() -> Result<SensorData, SensorError> {
     read_sensor_with_context(&mut env, &context, 2)
 }
 
-pub fn magnetometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
-    if !magnetometer_available() {
-        return Err(SensorError::NotAvailable);
-    }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
-    Ok(Box::pin(stream::unfold((), move |()| async move {
-        futures_timer::Delay::new(interval).await;
-        match magnetometer_read().await {
-            Ok(data) => Some((data, ())),
-            _ => None,
-        }
-    })))
+pub fn magnetometer_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+    watch(2, options, sensor_record)
 }
 
 pub fn barometer_available() -> bool {
@@ -422,18 +751,18 @@ pub async fn barometer_read() -> Result<ScalarData, SensorError> {
     read_pressure_with_context(&mut env, &context)
 }
 
-pub fn barometer_watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
-    if !barometer_available() {
-        return Err(SensorError::NotAvailable);
-    }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
-    Ok(Box::pin(stream::unfold((), move |()| async move {
-        futures_timer::Delay::new(interval).await;
-        match barometer_read().await {
-            Ok(data) => Some((data, ())),
-            _ => None,
-        }
-    })))
+pub fn barometer_watch(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    watch(6, options, scalar_record)
+}
+
+pub fn relative_altitude_available() -> bool {
+    false
+}
+
+pub fn relative_altitude_watch(
+    _options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
 }
 
 pub fn ambient_light_available() -> bool {
@@ -449,16 +778,236 @@ pub async fn ambient_light_read() -> Result<ScalarData, SensorError> {
     read_light_with_context(&mut env, &context)
 }
 
-pub fn ambient_light_watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
-    if !ambient_light_available() {
-        return Err(SensorError::NotAvailable);
+pub fn ambient_light_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    watch(5, options, scalar_record)
+}
+
+pub fn proximity_available() -> bool {
+    if let Ok((mut env, context)) = get_env_and_context() {
+        is_sensor_available_with_context(&mut env, &context, 8).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+pub async fn proximity_read() -> Result<ScalarData, SensorError> {
+    let (mut env, context) = get_env_and_context()?;
+    read_proximity_with_context(&mut env, &context)
+}
+
+pub fn proximity_watch(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    watch(8, options, scalar_record)
+}
+
+pub fn orientation_available() -> bool {
+    if let Ok((mut env, context)) = get_env_and_context() {
+        is_sensor_available_with_context(&mut env, &context, 11).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+pub async fn orientation_read() -> Result<OrientationData, SensorError> {
+    let (mut env, context) = get_env_and_context()?;
+    read_orientation_with_context(&mut env, &context)
+}
+
+pub fn orientation_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<OrientationData>, SensorError> {
+    watch(11, options, orientation_record)
+}
+
+pub fn gravity_available() -> bool {
+    if let Ok((mut env, context)) = get_env_and_context() {
+        is_sensor_available_with_context(&mut env, &context, 9).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+pub async fn gravity_read() -> Result<SensorData, SensorError> {
+    let (mut env, context) = get_env_and_context()?;
+    read_sensor_with_context(&mut env, &context, 9)
+}
+
+pub fn gravity_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+    watch(9, options, sensor_record)
+}
+
+pub fn linear_acceleration_available() -> bool {
+    if let Ok((mut env, context)) = get_env_and_context() {
+        is_sensor_available_with_context(&mut env, &context, 10).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+pub async fn linear_acceleration_read() -> Result<SensorData, SensorError> {
+    let (mut env, context) = get_env_and_context()?;
+    read_sensor_with_context(&mut env, &context, 10)
+}
+
+pub fn linear_acceleration_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<SensorData>, SensorError> {
+    watch(10, options, sensor_record)
+}
+
+pub fn ambient_temperature_available() -> bool {
+    if let Ok((mut env, context)) = get_env_and_context() {
+        is_sensor_available_with_context(&mut env, &context, 13).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+pub async fn ambient_temperature_read() -> Result<ScalarData, SensorError> {
+    let (mut env, context) = get_env_and_context()?;
+    read_scalar_with_context(&mut env, &context, 13)
+}
+
+pub fn ambient_temperature_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    watch(13, options, scalar_record)
+}
+
+pub fn humidity_available() -> bool {
+    if let Ok((mut env, context)) = get_env_and_context() {
+        is_sensor_available_with_context(&mut env, &context, 12).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+pub async fn humidity_read() -> Result<ScalarData, SensorError> {
+    let (mut env, context) = get_env_and_context()?;
+    read_scalar_with_context(&mut env, &context, 12)
+}
+
+pub fn humidity_watch(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    watch(12, options, scalar_record)
+}
+
+pub fn compass_available() -> bool {
+    orientation_available()
+}
+
+pub async fn compass_read() -> Result<HeadingData, SensorError> {
+    let (mut env, context) = get_env_and_context()?;
+    read_heading_with_context(&mut env, &context)
+}
+
+pub fn compass_watch(options: SensorOptions) -> Result<SensorStream<HeadingData>, SensorError> {
+    watch(11, options, heading_record)
+}
+
+pub const fn set_allow_calibration_ui(_allow: bool) {}
+
+/// Magnetic declination in degrees from `GeomagneticField`.
+pub fn declination(latitude: f64, longitude: f64, altitude: f64) -> Option<f64> {
+    let (mut env, _) = get_env_and_context().ok()?;
+    let helper = load_helper_class(&mut env).ok()?;
+    env.call_static_method(
+        helper,
+        "declination",
+        "(DDD)D",
+        &[
+            JValue::Double(latitude),
+            JValue::Double(longitude),
+            JValue::Double(altitude),
+        ],
+    )
+    .ok()?
+    .d()
+    .ok()
+}
+
+/// How often a pending trigger request is checked.
+const TRIGGER_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A one-shot trigger sensor request made by the helper.
+///
+/// Dropping it cancels the request if it hasn't fired.
+#[derive(Debug)]
+struct Trigger {
+    id: i32,
+}
+
+impl Trigger {
+    fn request(sensor_type: i32) -> Result<Self, SensorError> {
+        let (mut env, context) = get_env_and_context()?;
+        let helper = load_helper_class(&mut env)?;
+
+        let id = env
+            .call_static_method(
+                helper,
+                "requestTrigger",
+                "(Landroid/content/Context;I)I",
+                &[JValue::Object(&context), JValue::Int(sensor_type)],
+            )
+            .map_err(|e| SensorError::Unknown(format!("requestTrigger: {e}")))?
+            .i()
+            .map_err(|e| SensorError::Unknown(format!("requestTrigger result: {e}")))?;
+
+        if id == 0 {
+            return Err(SensorError::NotAvailable);
+        }
+        Ok(Self { id })
+    }
+
+    fn fired(&self) -> Result<bool, SensorError> {
+        let (mut env, _) = get_env_and_context()?;
+        let helper = load_helper_class(&mut env)?;
+        env.call_static_method(helper, "isTriggered", "(I)Z", &[JValue::Int(self.id)])
+            .map_err(|e| SensorError::Unknown(format!("isTriggered: {e}")))?
+            .z()
+            .map_err(|e| SensorError::Unknown(format!("isTriggered result: {e}")))
     }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
-    Ok(Box::pin(stream::unfold((), move |()| async move {
-        futures_timer::Delay::new(interval).await;
-        match ambient_light_read().await {
-            Ok(data) => Some((data, ())),
-            _ => None,
+}
+
+impl Drop for Trigger {
+    fn drop(&mut self) {
+        let cancel = || -> Result<(), SensorError> {
+            let (mut env, _) = get_env_and_context()?;
+            let helper = load_helper_class(&mut env)?;
+            env.call_static_method(helper, "cancelTrigger", "(I)V", &[JValue::Int(self.id)])
+                .map_err(|e| SensorError::Unknown(format!("cancelTrigger: {e}")))?;
+            Ok(())
+        };
+        if let Err(e) = cancel() {
+            log::error!("Failed to cancel trigger sensor: {e}");
         }
-    })))
+    }
+}
+
+// Significant motion
+pub fn significant_motion_available() -> bool {
+    if let Ok((mut env, context)) = get_env_and_context() {
+        is_sensor_available_with_context(&mut env, &context, 17).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+pub async fn significant_motion_wait() -> Result<(), SensorError> {
+    let trigger = Trigger::request(17)?;
+    loop {
+        futures_timer::Delay::new(TRIGGER_POLL_INTERVAL).await;
+        if trigger.fired()? {
+            return Ok(());
+        }
+    }
+}
+
+// Motion state (estimated from the accelerometer)
+pub fn motion_state_available() -> bool {
+    false
+}
+
+pub fn motion_state_watch() -> Result<SensorStream<MotionState>, SensorError> {
+    Err(SensorError::NotAvailable)
 }
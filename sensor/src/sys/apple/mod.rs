@@ -1,7 +1,11 @@
 //! Apple platform (iOS/macOS) sensor implementation using swift-bridge.
 
-use crate::{ScalarData, SensorData, SensorError, SensorStream};
+use crate::{
+    HeadingData, MotionState, OrientationData, ReferenceFrame, ScalarData, SensorAccuracy,
+    SensorData, SensorError, SensorOptions, SensorStream,
+};
 use futures::stream;
+use std::collections::VecDeque;
 
 #[swift_bridge::bridge]
 mod ffi {
@@ -19,6 +23,27 @@ mod ffi {
         timestamp_ms: u64,
     }
 
+    #[swift_bridge(swift_repr = "struct")]
+    struct OrientationReading {
+        x: f64,
+        y: f64,
+        z: f64,
+        w: f64,
+        // 0 = arbitrary, 1 = magnetic north, 2 = true north
+        reference: u8,
+        timestamp_ms: u64,
+    }
+
+    #[swift_bridge(swift_repr = "struct")]
+    struct HeadingReading {
+        magnetic_heading: f64,
+        // Negative when the true heading is unknown
+        true_heading: f64,
+        // Maximum error in degrees; negative when invalid
+        accuracy: f64,
+        timestamp_ms: u64,
+    }
+
     enum SensorResult {
         Success(SensorReading),
         NotAvailable,
@@ -33,6 +58,20 @@ mod ffi {
         Timeout,
     }
 
+    enum OrientationResult {
+        Success(OrientationReading),
+        NotAvailable,
+        PermissionDenied,
+        Timeout,
+    }
+
+    enum HeadingResult {
+        Success(HeadingReading),
+        NotAvailable,
+        PermissionDenied,
+        Timeout,
+    }
+
     extern "Swift" {
         fn is_accelerometer_available() -> bool;
         fn read_accelerometer() -> SensorResult;
@@ -48,6 +87,35 @@ mod ffi {
 
         fn is_ambient_light_available() -> bool;
         fn read_ambient_light() -> ScalarResult;
+
+        fn is_proximity_available() -> bool;
+        fn read_proximity() -> ScalarResult;
+        fn start_proximity_monitoring() -> bool;
+        fn stop_proximity_monitoring();
+
+        fn is_orientation_available() -> bool;
+        fn read_orientation() -> OrientationResult;
+
+        fn is_gravity_available() -> bool;
+        fn read_gravity() -> SensorResult;
+
+        fn is_linear_acceleration_available() -> bool;
+        fn read_linear_acceleration() -> SensorResult;
+
+        fn is_compass_available() -> bool;
+        fn read_heading() -> HeadingResult;
+        fn start_heading_updates() -> bool;
+        fn stop_heading_updates();
+        fn set_heading_calibration_display(allow: bool);
+
+        fn start_motion_watch(kind: u8, interval: f64) -> u32;
+        fn drain_motion_watch(id: u32) -> Vec<f64>;
+        fn stop_motion_watch(id: u32);
+
+        fn is_motion_activity_available() -> bool;
+        fn start_activity_watch() -> i64;
+        fn drain_activity_watch(id: u32) -> Vec<u8>;
+        fn stop_activity_watch(id: u32);
     }
 }
 
@@ -56,6 +124,7 @@ const fn convert_reading(reading: &ffi::SensorReading) -> SensorData {
         x: reading.x,
         y: reading.y,
         z: reading.z,
+        accuracy: SensorAccuracy::Unknown,
         timestamp: reading.timestamp_ms,
     }
 }
@@ -67,6 +136,53 @@ const fn convert_scalar(reading: &ffi::ScalarReading) -> ScalarData {
     }
 }
 
+/// Map the reference frame code of an orientation reading.
+const fn reference_frame(code: u8) -> ReferenceFrame {
+    match code {
+        1 => ReferenceFrame::MagneticNorth,
+        2 => ReferenceFrame::TrueNorth,
+        _ => ReferenceFrame::Arbitrary,
+    }
+}
+
+const fn convert_orientation(reading: &ffi::OrientationReading) -> OrientationData {
+    OrientationData {
+        x: reading.x,
+        y: reading.y,
+        z: reading.z,
+        w: reading.w,
+        reference: reference_frame(reading.reference),
+        timestamp: reading.timestamp_ms,
+    }
+}
+
+/// Map `CLHeading.headingAccuracy`, the maximum error in degrees, to
+/// [`SensorAccuracy`].
+const fn heading_accuracy(degrees: f64) -> SensorAccuracy {
+    if degrees < 0.0 {
+        SensorAccuracy::Unreliable
+    } else if degrees <= 10.0 {
+        SensorAccuracy::High
+    } else if degrees <= 25.0 {
+        SensorAccuracy::Medium
+    } else {
+        SensorAccuracy::Low
+    }
+}
+
+const fn convert_heading(reading: &ffi::HeadingReading) -> HeadingData {
+    HeadingData {
+        magnetic_heading: reading.magnetic_heading,
+        true_heading: if reading.true_heading >= 0.0 {
+            Some(reading.true_heading)
+        } else {
+            None
+        },
+        accuracy: heading_accuracy(reading.accuracy),
+        timestamp: reading.timestamp_ms,
+    }
+}
+
 const fn convert_result(result: ffi::SensorResult) -> Result<SensorData, SensorError> {
     match result {
         ffi::SensorResult::Success(r) => Ok(convert_reading(&r)),
@@ -85,6 +201,124 @@ const fn convert_scalar_result(result: ffi::ScalarResult) -> Result<ScalarData,
     }
 }
 
+/// Sensors a [`MotionWatch`] can stream, as numbered by `start_motion_watch`.
+#[derive(Debug, Clone, Copy)]
+enum Motion {
+    Accelerometer = 0,
+    Gyroscope = 1,
+    Magnetometer = 2,
+    Barometer = 3,
+    Orientation = 4,
+    Gravity = 5,
+    LinearAcceleration = 6,
+}
+
+/// Fields in each record drained from a watch, see `MotionWatch.push`.
+const RECORD_LEN: usize = 6;
+
+/// `CoreMotion` updates delivered to an `OperationQueue` and buffered on the
+/// Swift side.
+///
+/// Dropping it stops the updates so the sensor can power down.
+#[derive(Debug)]
+struct MotionWatch {
+    id: u32,
+}
+
+impl MotionWatch {
+    fn start(motion: Motion, options: SensorOptions) -> Result<Self, SensorError> {
+        let id = ffi::start_motion_watch(motion as u8, options.poll_interval().as_secs_f64());
+        if id == 0 {
+            return Err(SensorError::NotAvailable);
+        }
+        Ok(Self { id })
+    }
+
+    /// Take the records buffered since the last drain.
+    fn drain(&self) -> Vec<[f64; RECORD_LEN]> {
+        ffi::drain_motion_watch(self.id)
+            .chunks_exact(RECORD_LEN)
+            .map(|record| {
+                let mut out = [0.0; RECORD_LEN];
+                out.copy_from_slice(record);
+                out
+            })
+            .collect()
+    }
+}
+
+impl Drop for MotionWatch {
+    fn drop(&mut self) {
+        ffi::stop_motion_watch(self.id);
+    }
+}
+
+/// Stream every `CoreMotion` update of `motion`, draining the buffer once
+/// per interval.
+fn motion_watch<T: Send + 'static>(
+    motion: Motion,
+    options: SensorOptions,
+    convert: fn(&[f64; RECORD_LEN]) -> T,
+) -> Result<SensorStream<T>, SensorError> {
+    let watch = MotionWatch::start(motion, options)?;
+    let interval = options.poll_interval();
+    Ok(Box::pin(stream::unfold(
+        (watch, VecDeque::new()),
+        move |(watch, mut pending)| async move {
+            loop {
+                if let Some(record) = pending.pop_front() {
+                    return Some((convert(&record), (watch, pending)));
+                }
+                futures_timer::Delay::new(interval).await;
+                pending.extend(watch.drain());
+            }
+        },
+    )))
+}
+
+/// The timestamp of a record, in Unix epoch milliseconds.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+const fn record_timestamp(record: &[f64; RECORD_LEN]) -> u64 {
+    record[4] as u64
+}
+
+const fn sensor_record(record: &[f64; RECORD_LEN]) -> SensorData {
+    SensorData {
+        x: record[0],
+        y: record[1],
+        z: record[2],
+        accuracy: SensorAccuracy::Unknown,
+        timestamp: record_timestamp(record),
+    }
+}
+
+const fn scalar_record(record: &[f64; RECORD_LEN]) -> ScalarData {
+    ScalarData {
+        value: record[0],
+        timestamp: record_timestamp(record),
+    }
+}
+
+/// The relative altitude that barometer records carry next to the pressure.
+const fn altitude_record(record: &[f64; RECORD_LEN]) -> ScalarData {
+    ScalarData {
+        value: record[1],
+        timestamp: record_timestamp(record),
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+const fn orientation_record(record: &[f64; RECORD_LEN]) -> OrientationData {
+    OrientationData {
+        x: record[0],
+        y: record[1],
+        z: record[2],
+        w: record[3],
+        reference: reference_frame(record[5] as u8),
+        timestamp: record_timestamp(record),
+    }
+}
+
 // Accelerometer
 pub fn accelerometer_available() -> bool {
     ffi::is_accelerometer_available()
@@ -95,18 +329,10 @@ pub async fn accelerometer_read() -> Result<SensorData, SensorError> {
     convert_result(ffi::read_accelerometer())
 }
 
-pub fn accelerometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
-    if !accelerometer_available() {
-        return Err(SensorError::NotAvailable);
-    }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
-    Ok(Box::pin(stream::unfold((), move |()| async move {
-        futures_timer::Delay::new(interval).await;
-        match ffi::read_accelerometer() {
-            ffi::SensorResult::Success(r) => Some((convert_reading(&r), ())),
-            _ => None,
-        }
-    })))
+pub fn accelerometer_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<SensorData>, SensorError> {
+    motion_watch(Motion::Accelerometer, options, sensor_record)
 }
 
 // Gyroscope
@@ -119,18 +345,8 @@ pub async fn gyroscope_read() -> Result<SensorData, SensorError> {
     convert_result(ffi::read_gyroscope())
 }
 
-pub fn gyroscope_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
-    if !gyroscope_available() {
-        return Err(SensorError::NotAvailable);
-    }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
-    Ok(Box::pin(stream::unfold((), move |()| async move {
-        futures_timer::Delay::new(interval).await;
-        match ffi::read_gyroscope() {
-            ffi::SensorResult::Success(r) => Some((convert_reading(&r), ())),
-            _ => None,
-        }
-    })))
+pub fn gyroscope_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+    motion_watch(Motion::Gyroscope, options, sensor_record)
 }
 
 // Magnetometer
@@ -143,18 +359,8 @@ pub async fn magnetometer_read() -> Result<SensorData, SensorError> {
     convert_result(ffi::read_magnetometer())
 }
 
-pub fn magnetometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
-    if !magnetometer_available() {
-        return Err(SensorError::NotAvailable);
-    }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
-    Ok(Box::pin(stream::unfold((), move |()| async move {
-        futures_timer::Delay::new(interval).await;
-        match ffi::read_magnetometer() {
-            ffi::SensorResult::Success(r) => Some((convert_reading(&r), ())),
-            _ => None,
-        }
-    })))
+pub fn magnetometer_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+    motion_watch(Motion::Magnetometer, options, sensor_record)
 }
 
 // Barometer
@@ -167,18 +373,18 @@ pub async fn barometer_read() -> Result<ScalarData, SensorError> {
     convert_scalar_result(ffi::read_barometer())
 }
 
-pub fn barometer_watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
-    if !barometer_available() {
-        return Err(SensorError::NotAvailable);
-    }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
-    Ok(Box::pin(stream::unfold((), move |()| async move {
-        futures_timer::Delay::new(interval).await;
-        match ffi::read_barometer() {
-            ffi::ScalarResult::Success(r) => Some((convert_scalar(&r), ())),
-            _ => None,
-        }
-    })))
+pub fn barometer_watch(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    motion_watch(Motion::Barometer, options, scalar_record)
+}
+
+pub fn relative_altitude_available() -> bool {
+    ffi::is_barometer_available()
+}
+
+pub fn relative_altitude_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    motion_watch(Motion::Barometer, options, altitude_record)
 }
 
 // Ambient Light
@@ -191,11 +397,13 @@ pub async fn ambient_light_read() -> Result<ScalarData, SensorError> {
     convert_scalar_result(ffi::read_ambient_light())
 }
 
-pub fn ambient_light_watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
+pub fn ambient_light_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
     if !ambient_light_available() {
         return Err(SensorError::NotAvailable);
     }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
+    let interval = options.poll_interval();
     Ok(Box::pin(stream::unfold((), move |()| async move {
         futures_timer::Delay::new(interval).await;
         match ffi::read_ambient_light() {
@@ -204,3 +412,247 @@ pub fn ambient_light_watch(interval_ms: u32) -> Result<SensorStream<ScalarData>,
         }
     })))
 }
+
+// Proximity
+pub fn proximity_available() -> bool {
+    ffi::is_proximity_available()
+}
+
+#[allow(clippy::unused_async)]
+pub async fn proximity_read() -> Result<ScalarData, SensorError> {
+    convert_scalar_result(ffi::read_proximity())
+}
+
+/// Keeps proximity monitoring enabled while a watch stream is alive.
+struct ProximityMonitor;
+
+impl ProximityMonitor {
+    fn start() -> Result<Self, SensorError> {
+        if ffi::start_proximity_monitoring() {
+            Ok(Self)
+        } else {
+            Err(SensorError::NotAvailable)
+        }
+    }
+}
+
+impl Drop for ProximityMonitor {
+    fn drop(&mut self) {
+        ffi::stop_proximity_monitoring();
+    }
+}
+
+pub fn proximity_watch(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    let monitor = ProximityMonitor::start()?;
+    let interval = options.poll_interval();
+    Ok(Box::pin(stream::unfold(
+        monitor,
+        move |monitor| async move {
+            futures_timer::Delay::new(interval).await;
+            match ffi::read_proximity() {
+                ffi::ScalarResult::Success(r) => Some((convert_scalar(&r), monitor)),
+                _ => None,
+            }
+        },
+    )))
+}
+
+// Orientation
+pub fn orientation_available() -> bool {
+    ffi::is_orientation_available()
+}
+
+#[allow(clippy::unused_async)]
+pub async fn orientation_read() -> Result<OrientationData, SensorError> {
+    match ffi::read_orientation() {
+        ffi::OrientationResult::Success(r) => Ok(convert_orientation(&r)),
+        ffi::OrientationResult::NotAvailable => Err(SensorError::NotAvailable),
+        ffi::OrientationResult::PermissionDenied => Err(SensorError::PermissionDenied),
+        ffi::OrientationResult::Timeout => Err(SensorError::Timeout),
+    }
+}
+
+pub fn orientation_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<OrientationData>, SensorError> {
+    motion_watch(Motion::Orientation, options, orientation_record)
+}
+
+// Gravity
+pub fn gravity_available() -> bool {
+    ffi::is_gravity_available()
+}
+
+#[allow(clippy::unused_async)]
+pub async fn gravity_read() -> Result<SensorData, SensorError> {
+    convert_result(ffi::read_gravity())
+}
+
+pub fn gravity_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+    motion_watch(Motion::Gravity, options, sensor_record)
+}
+
+// Linear Acceleration
+pub fn linear_acceleration_available() -> bool {
+    ffi::is_linear_acceleration_available()
+}
+
+#[allow(clippy::unused_async)]
+pub async fn linear_acceleration_read() -> Result<SensorData, SensorError> {
+    convert_result(ffi::read_linear_acceleration())
+}
+
+pub fn linear_acceleration_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<SensorData>, SensorError> {
+    motion_watch(Motion::LinearAcceleration, options, sensor_record)
+}
+
+// Ambient temperature (Apple devices have no such sensor)
+pub fn ambient_temperature_available() -> bool {
+    false
+}
+
+#[allow(clippy::unused_async)]
+pub async fn ambient_temperature_read() -> Result<ScalarData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn ambient_temperature_watch(
+    _options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Humidity (Apple devices have no such sensor)
+pub fn humidity_available() -> bool {
+    false
+}
+
+#[allow(clippy::unused_async)]
+pub async fn humidity_read() -> Result<ScalarData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn humidity_watch(_options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Compass
+pub fn compass_available() -> bool {
+    ffi::is_compass_available()
+}
+
+#[allow(clippy::unused_async)]
+pub async fn compass_read() -> Result<HeadingData, SensorError> {
+    match ffi::read_heading() {
+        ffi::HeadingResult::Success(r) => Ok(convert_heading(&r)),
+        ffi::HeadingResult::NotAvailable => Err(SensorError::NotAvailable),
+        ffi::HeadingResult::PermissionDenied => Err(SensorError::PermissionDenied),
+        ffi::HeadingResult::Timeout => Err(SensorError::Timeout),
+    }
+}
+
+pub fn set_allow_calibration_ui(allow: bool) {
+    ffi::set_heading_calibration_display(allow);
+}
+
+/// Keeps heading updates running while a watch stream is alive.
+struct HeadingMonitor;
+
+impl HeadingMonitor {
+    fn start() -> Result<Self, SensorError> {
+        if ffi::start_heading_updates() {
+            Ok(Self)
+        } else {
+            Err(SensorError::NotAvailable)
+        }
+    }
+}
+
+impl Drop for HeadingMonitor {
+    fn drop(&mut self) {
+        ffi::stop_heading_updates();
+    }
+}
+
+pub fn compass_watch(options: SensorOptions) -> Result<SensorStream<HeadingData>, SensorError> {
+    let monitor = HeadingMonitor::start()?;
+    let interval = options.poll_interval();
+    Ok(Box::pin(stream::unfold(
+        monitor,
+        move |monitor| async move {
+            futures_timer::Delay::new(interval).await;
+            match ffi::read_heading() {
+                ffi::HeadingResult::Success(r) => Some((convert_heading(&r), monitor)),
+                _ => None,
+            }
+        },
+    )))
+}
+
+/// `CLLocationManager` reports the true heading itself.
+pub const fn declination(_latitude: f64, _longitude: f64, _altitude: f64) -> Option<f64> {
+    None
+}
+
+// Significant motion (waits for `CMMotionActivityManager` to report motion)
+pub fn significant_motion_available() -> bool {
+    false
+}
+
+#[allow(clippy::unused_async)]
+pub async fn significant_motion_wait() -> Result<(), SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Motion state
+pub fn motion_state_available() -> bool {
+    ffi::is_motion_activity_available()
+}
+
+/// Keeps motion activity updates running while a watch stream is alive.
+#[derive(Debug)]
+struct ActivityWatch {
+    id: u32,
+}
+
+impl Drop for ActivityWatch {
+    fn drop(&mut self) {
+        ffi::stop_activity_watch(self.id);
+    }
+}
+
+/// Activity updates arrive every few seconds at most.
+const ACTIVITY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+pub fn motion_state_watch() -> Result<SensorStream<MotionState>, SensorError> {
+    let watch = match ffi::start_activity_watch() {
+        0 => return Err(SensorError::NotAvailable),
+        id => ActivityWatch {
+            id: u32::try_from(id).map_err(|_| SensorError::PermissionDenied)?,
+        },
+    };
+    Ok(Box::pin(stream::unfold(
+        (watch, VecDeque::new(), None),
+        |(watch, mut pending, last)| async move {
+            loop {
+                if let Some(state) = pending.pop_front() {
+                    // Activity updates repeat the state; only yield changes
+                    if last != Some(state) {
+                        return Some((state, (watch, pending, Some(state))));
+                    }
+                    continue;
+                }
+                futures_timer::Delay::new(ACTIVITY_POLL_INTERVAL).await;
+                pending.extend(ffi::drain_activity_watch(watch.id).into_iter().map(|code| {
+                    if code == 0 {
+                        MotionState::Stationary
+                    } else {
+                        MotionState::Moving
+                    }
+                }));
+            }
+        },
+    )))
+}
@@ -3,7 +3,9 @@ import Foundation
 #if os(macOS)
 import IOKit
 #else
+import CoreLocation
 import CoreMotion
+import UIKit
 #endif
 
 // MARK: - Sensor Reading Helpers
@@ -155,6 +157,364 @@ func read_barometer() -> ScalarResult {
     return result
 }
 
+func is_orientation_available() -> Bool {
+    return motionManager.isDeviceMotionAvailable
+}
+
+/// The best attitude reference frame, and its code for `OrientationReading`.
+private func attitudeReferenceFrame() -> (CMAttitudeReferenceFrame, UInt8) {
+    let frames = CMMotionManager.availableAttitudeReferenceFrames()
+    // True north needs the device's location
+    let status = CLLocationManager().authorizationStatus
+    let located = status == .authorizedWhenInUse || status == .authorizedAlways
+    if located && frames.contains(.xTrueNorthZVertical) {
+        return (.xTrueNorthZVertical, 2)
+    }
+    if frames.contains(.xMagneticNorthZVertical) {
+        return (.xMagneticNorthZVertical, 1)
+    }
+    return (.xArbitraryZVertical, 0)
+}
+
+/// CoreMotion's world frame has x pointing north; turn it 90° about z so
+/// x points east, matching Android's East-North-Up frame.
+private func eastNorthUp(_ q: CMQuaternion) -> CMQuaternion {
+    let c = 0.5.squareRoot()
+    return CMQuaternion(
+        x: c * q.x - c * q.y,
+        y: c * q.y + c * q.x,
+        z: c * q.z + c * q.w,
+        w: c * q.w - c * q.z
+    )
+}
+
+func read_orientation() -> OrientationResult {
+    guard motionManager.isDeviceMotionAvailable else {
+        return .NotAvailable
+    }
+
+    let (frame, reference) = attitudeReferenceFrame()
+    motionManager.deviceMotionUpdateInterval = 0.01
+    motionManager.startDeviceMotionUpdates(using: frame)
+
+    var attempts = 0
+    while motionManager.deviceMotion == nil && attempts < 100 {
+        Thread.sleep(forTimeInterval: 0.01)
+        attempts += 1
+    }
+
+    guard let motion = motionManager.deviceMotion else {
+        motionManager.stopDeviceMotionUpdates()
+        return .Timeout
+    }
+
+    let q = eastNorthUp(motion.attitude.quaternion)
+    let reading = OrientationReading(
+        x: q.x,
+        y: q.y,
+        z: q.z,
+        w: q.w,
+        reference: reference,
+        timestamp_ms: currentTimestampMs()
+    )
+
+    motionManager.stopDeviceMotionUpdates()
+    return .Success(reading)
+}
+
+/// Read one device motion sample with the default reference frame.
+private func readDeviceMotion() -> CMDeviceMotion? {
+    motionManager.deviceMotionUpdateInterval = 0.01
+    motionManager.startDeviceMotionUpdates()
+    defer { motionManager.stopDeviceMotionUpdates() }
+
+    var attempts = 0
+    while motionManager.deviceMotion == nil && attempts < 100 {
+        Thread.sleep(forTimeInterval: 0.01)
+        attempts += 1
+    }
+    return motionManager.deviceMotion
+}
+
+func is_gravity_available() -> Bool {
+    return motionManager.isDeviceMotionAvailable
+}
+
+func read_gravity() -> SensorResult {
+    guard motionManager.isDeviceMotionAvailable else {
+        return .NotAvailable
+    }
+    guard let motion = readDeviceMotion() else {
+        return .Timeout
+    }
+    return .Success(SensorReading(
+        x: motion.gravity.x,
+        y: motion.gravity.y,
+        z: motion.gravity.z,
+        timestamp_ms: currentTimestampMs()
+    ))
+}
+
+func is_linear_acceleration_available() -> Bool {
+    return motionManager.isDeviceMotionAvailable
+}
+
+func read_linear_acceleration() -> SensorResult {
+    guard motionManager.isDeviceMotionAvailable else {
+        return .NotAvailable
+    }
+    guard let motion = readDeviceMotion() else {
+        return .Timeout
+    }
+    return .Success(SensorReading(
+        x: motion.userAcceleration.x,
+        y: motion.userAcceleration.y,
+        z: motion.userAcceleration.z,
+        timestamp_ms: currentTimestampMs()
+    ))
+}
+
+// MARK: - Motion Watches
+
+/// Wall-clock time in ms of a CoreMotion timestamp, which counts seconds
+/// since boot.
+private func eventTimestampMs(_ uptime: TimeInterval) -> UInt64 {
+    let age = ProcessInfo.processInfo.systemUptime - uptime
+    return UInt64((Date().timeIntervalSince1970 - age) * 1000)
+}
+
+/// A watch started by `start_motion_watch`, buffering every update
+/// CoreMotion delivers until `drain_motion_watch` takes them.
+///
+/// Each watch owns its managers, so one-shot reads and other watches can
+/// start and stop updates without affecting it.
+private final class MotionWatch {
+    let manager = CMMotionManager()
+    let altimeter = CMAltimeter()
+    let queue: OperationQueue = {
+        let queue = OperationQueue()
+        queue.name = "waterkit-sensor"
+        queue.maxConcurrentOperationCount = 1
+        return queue
+    }()
+
+    private let lock = NSLock()
+    private var records: [Double] = []
+
+    /// Append a record of [v0, v1, v2, v3, timestamp_ms, reference].
+    func push(
+        _ v0: Double, _ v1: Double, _ v2: Double, _ v3: Double,
+        uptime: TimeInterval, reference: UInt8 = 0
+    ) {
+        let record = [v0, v1, v2, v3, Double(eventTimestampMs(uptime)), Double(reference)]
+        lock.lock()
+        records.append(contentsOf: record)
+        lock.unlock()
+    }
+
+    func drain() -> [Double] {
+        lock.lock()
+        defer { lock.unlock() }
+        let drained = records
+        records.removeAll()
+        return drained
+    }
+
+    func stop() {
+        manager.stopAccelerometerUpdates()
+        manager.stopGyroUpdates()
+        manager.stopMagnetometerUpdates()
+        manager.stopDeviceMotionUpdates()
+        altimeter.stopRelativeAltitudeUpdates()
+    }
+}
+
+private var motionWatches: [UInt32: MotionWatch] = [:]
+private var nextMotionWatchId: UInt32 = 1
+private let motionWatchesLock = NSLock()
+
+/// Start delivering updates of `kind` about every `interval` seconds.
+/// Returns the watch ID, or 0 if the sensor is missing.
+///
+/// Kinds: 0 = accelerometer, 1 = gyroscope, 2 = magnetometer,
+/// 3 = barometer, 4 = orientation, 5 = gravity, 6 = linear acceleration.
+func start_motion_watch(kind: UInt8, interval: Double) -> UInt32 {
+    let watch = MotionWatch()
+    let manager = watch.manager
+
+    switch kind {
+    case 0:
+        guard manager.isAccelerometerAvailable else { return 0 }
+        manager.accelerometerUpdateInterval = interval
+        manager.startAccelerometerUpdates(to: watch.queue) { [weak watch] data, _ in
+            guard let watch, let data else { return }
+            let a = data.acceleration
+            watch.push(a.x, a.y, a.z, 0, uptime: data.timestamp)
+        }
+    case 1:
+        guard manager.isGyroAvailable else { return 0 }
+        manager.gyroUpdateInterval = interval
+        manager.startGyroUpdates(to: watch.queue) { [weak watch] data, _ in
+            guard let watch, let data else { return }
+            let r = data.rotationRate
+            watch.push(r.x, r.y, r.z, 0, uptime: data.timestamp)
+        }
+    case 2:
+        guard manager.isMagnetometerAvailable else { return 0 }
+        manager.magnetometerUpdateInterval = interval
+        manager.startMagnetometerUpdates(to: watch.queue) { [weak watch] data, _ in
+            guard let watch, let data else { return }
+            let m = data.magneticField
+            watch.push(m.x, m.y, m.z, 0, uptime: data.timestamp)
+        }
+    case 3:
+        // The altimeter reports at its own pace, about once a second
+        guard CMAltimeter.isRelativeAltitudeAvailable() else { return 0 }
+        watch.altimeter.startRelativeAltitudeUpdates(to: watch.queue) { [weak watch] data, _ in
+            guard let watch, let data else { return }
+            // kPa to hPa, then metres since updates started
+            watch.push(
+                data.pressure.doubleValue * 10.0, data.relativeAltitude.doubleValue, 0, 0,
+                uptime: data.timestamp
+            )
+        }
+    case 4:
+        guard manager.isDeviceMotionAvailable else { return 0 }
+        let (frame, reference) = attitudeReferenceFrame()
+        manager.deviceMotionUpdateInterval = interval
+        manager.startDeviceMotionUpdates(using: frame, to: watch.queue) { [weak watch] motion, _ in
+            guard let watch, let motion else { return }
+            let q = eastNorthUp(motion.attitude.quaternion)
+            watch.push(q.x, q.y, q.z, q.w, uptime: motion.timestamp, reference: reference)
+        }
+    case 5, 6:
+        guard manager.isDeviceMotionAvailable else { return 0 }
+        let gravity = kind == 5
+        manager.deviceMotionUpdateInterval = interval
+        manager.startDeviceMotionUpdates(to: watch.queue) { [weak watch] motion, _ in
+            guard let watch, let motion else { return }
+            let v = gravity ? motion.gravity : motion.userAcceleration
+            watch.push(v.x, v.y, v.z, 0, uptime: motion.timestamp)
+        }
+    default:
+        return 0
+    }
+
+    motionWatchesLock.lock()
+    defer { motionWatchesLock.unlock() }
+    let id = nextMotionWatchId
+    nextMotionWatchId += 1
+    motionWatches[id] = watch
+    return id
+}
+
+/// Take the records buffered for watch `id`, flattened.
+func drain_motion_watch(id: UInt32) -> RustVec<Double> {
+    motionWatchesLock.lock()
+    let watch = motionWatches[id]
+    motionWatchesLock.unlock()
+
+    let values = RustVec<Double>()
+    for value in watch?.drain() ?? [] {
+        values.push(value: value)
+    }
+    return values
+}
+
+/// Stop watch `id`, letting the sensor power down.
+func stop_motion_watch(id: UInt32) {
+    motionWatchesLock.lock()
+    let watch = motionWatches.removeValue(forKey: id)
+    motionWatchesLock.unlock()
+    watch?.stop()
+}
+
+// MARK: - Motion Activity
+
+func is_motion_activity_available() -> Bool {
+    return CMMotionActivityManager.isActivityAvailable()
+}
+
+/// A watch started by `start_activity_watch`, buffering motion states
+/// (0 = stationary, 1 = moving) until `drain_activity_watch` takes them.
+private final class ActivityWatch {
+    let manager = CMMotionActivityManager()
+    let queue = OperationQueue()
+
+    private let lock = NSLock()
+    private var states: [UInt8] = []
+
+    func push(_ state: UInt8) {
+        lock.lock()
+        states.append(state)
+        lock.unlock()
+    }
+
+    func drain() -> [UInt8] {
+        lock.lock()
+        defer { lock.unlock() }
+        let drained = states
+        states.removeAll()
+        return drained
+    }
+}
+
+private var activityWatches: [UInt32: ActivityWatch] = [:]
+private var nextActivityWatchId: UInt32 = 1
+private let activityWatchesLock = NSLock()
+
+/// Start motion activity updates. Returns the watch ID, 0 if activity
+/// detection is missing, or -1 if the user denied motion access.
+func start_activity_watch() -> Int64 {
+    guard CMMotionActivityManager.isActivityAvailable() else {
+        return 0
+    }
+    switch CMMotionActivityManager.authorizationStatus() {
+    case .denied, .restricted:
+        return -1
+    default:
+        break
+    }
+
+    let watch = ActivityWatch()
+    watch.manager.startActivityUpdates(to: watch.queue) { [weak watch] activity in
+        guard let watch, let activity else { return }
+        if activity.stationary {
+            watch.push(0)
+        } else if activity.walking || activity.running || activity.cycling || activity.automotive {
+            watch.push(1)
+        }
+    }
+
+    activityWatchesLock.lock()
+    defer { activityWatchesLock.unlock() }
+    let id = nextActivityWatchId
+    nextActivityWatchId += 1
+    activityWatches[id] = watch
+    return Int64(id)
+}
+
+/// Take the states buffered for watch `id`.
+func drain_activity_watch(id: UInt32) -> RustVec<UInt8> {
+    activityWatchesLock.lock()
+    let watch = activityWatches[id]
+    activityWatchesLock.unlock()
+
+    let states = RustVec<UInt8>()
+    for state in watch?.drain() ?? [] {
+        states.push(value: state)
+    }
+    return states
+}
+
+/// Stop watch `id`.
+func stop_activity_watch(id: UInt32) {
+    activityWatchesLock.lock()
+    let watch = activityWatches.removeValue(forKey: id)
+    activityWatchesLock.unlock()
+    watch?.manager.stopActivityUpdates()
+}
+
 // Ambient light is not exposed via public API on iOS
 func is_ambient_light_available() -> Bool {
     return false
@@ -164,6 +524,177 @@ func read_ambient_light() -> ScalarResult {
     return .NotAvailable
 }
 
+// Proximity monitoring is reference counted so that overlapping reads and
+// watch streams share it; it also turns the screen off, so it is only left
+// enabled while someone is listening.
+
+/// Distance reported when nothing is near; mirrors `Proximity::FAR_CM`.
+private let proximityFarCm = 5.0
+
+private var proximityUsers = 0
+private var proximityWasEnabled = false
+
+private func onMain<T>(_ body: () -> T) -> T {
+    if Thread.isMainThread {
+        return body()
+    }
+    return DispatchQueue.main.sync(execute: body)
+}
+
+func is_proximity_available() -> Bool {
+    return onMain {
+        let device = UIDevice.current
+        if device.isProximityMonitoringEnabled {
+            return true
+        }
+        // Enabling fails silently on devices without the sensor
+        device.isProximityMonitoringEnabled = true
+        let available = device.isProximityMonitoringEnabled
+        device.isProximityMonitoringEnabled = false
+        return available
+    }
+}
+
+func start_proximity_monitoring() -> Bool {
+    return onMain {
+        let device = UIDevice.current
+        if proximityUsers == 0 {
+            proximityWasEnabled = device.isProximityMonitoringEnabled
+            device.isProximityMonitoringEnabled = true
+            guard device.isProximityMonitoringEnabled else {
+                return false
+            }
+        }
+        proximityUsers += 1
+        return true
+    }
+}
+
+func stop_proximity_monitoring() {
+    onMain {
+        guard proximityUsers > 0 else {
+            return
+        }
+        proximityUsers -= 1
+        // Leave monitoring on if the app had enabled it itself
+        if proximityUsers == 0 && !proximityWasEnabled {
+            UIDevice.current.isProximityMonitoringEnabled = false
+        }
+    }
+}
+
+func read_proximity() -> ScalarResult {
+    let wasMonitoring = onMain {
+        proximityUsers > 0 || UIDevice.current.isProximityMonitoringEnabled
+    }
+    guard start_proximity_monitoring() else {
+        return .NotAvailable
+    }
+    defer { stop_proximity_monitoring() }
+
+    // Give a freshly enabled sensor time to report its state
+    if !wasMonitoring {
+        Thread.sleep(forTimeInterval: 0.1)
+    }
+
+    let near = onMain { UIDevice.current.proximityState }
+    let reading = ScalarReading(
+        value: near ? 0.0 : proximityFarCm,
+        timestamp_ms: currentTimestampMs()
+    )
+    return .Success(reading)
+}
+
+// Heading updates are reference counted like proximity monitoring, so a
+// watch stream keeps them running between reads.
+
+/// Decides whether iOS may show its heading calibration screen.
+private final class HeadingDelegate: NSObject, CLLocationManagerDelegate {
+    var allowCalibrationDisplay = false
+
+    func locationManagerShouldDisplayHeadingCalibration(_ manager: CLLocationManager) -> Bool {
+        return allowCalibrationDisplay
+    }
+}
+
+private let headingDelegate = HeadingDelegate()
+
+// Created on the main thread, whose run loop delivers its updates
+private let headingManager: CLLocationManager = onMain {
+    let manager = CLLocationManager()
+    manager.delegate = headingDelegate
+    return manager
+}
+
+private var headingUsers = 0
+
+func is_compass_available() -> Bool {
+    return CLLocationManager.headingAvailable()
+}
+
+func start_heading_updates() -> Bool {
+    guard CLLocationManager.headingAvailable() else {
+        return false
+    }
+    onMain {
+        if headingUsers == 0 {
+            headingManager.headingFilter = kCLHeadingFilterNone
+            headingManager.startUpdatingHeading()
+        }
+        headingUsers += 1
+    }
+    return true
+}
+
+func stop_heading_updates() {
+    onMain {
+        guard headingUsers > 0 else {
+            return
+        }
+        headingUsers -= 1
+        if headingUsers == 0 {
+            headingManager.stopUpdatingHeading()
+        }
+    }
+}
+
+func set_heading_calibration_display(allow: Bool) {
+    onMain {
+        headingDelegate.allowCalibrationDisplay = allow
+        if !allow {
+            headingManager.dismissHeadingCalibrationDisplay()
+        }
+    }
+}
+
+func read_heading() -> HeadingResult {
+    let wasRunning = onMain { headingUsers > 0 }
+    guard start_heading_updates() else {
+        return .NotAvailable
+    }
+    defer { stop_heading_updates() }
+
+    // The manager keeps the last heading after stopping; skip it
+    let since = wasRunning ? Date.distantPast : Date()
+    var heading = onMain { headingManager.heading }
+    var attempts = 0
+    while (heading == nil || heading!.timestamp < since) && attempts < 100 {
+        Thread.sleep(forTimeInterval: 0.01)
+        attempts += 1
+        heading = onMain { headingManager.heading }
+    }
+
+    guard let heading, heading.timestamp >= since else {
+        return .Timeout
+    }
+    return .Success(HeadingReading(
+        magnetic_heading: heading.magneticHeading,
+        true_heading: heading.trueHeading,
+        accuracy: heading.headingAccuracy,
+        timestamp_ms: UInt64(heading.timestamp.timeIntervalSince1970 * 1000)
+    ))
+}
+
 #endif
 
 // MARK: - macOS Implementation
@@ -182,6 +713,47 @@ func read_magnetometer() -> SensorResult { return .NotAvailable }
 func is_barometer_available() -> Bool { return false }
 func read_barometer() -> ScalarResult { return .NotAvailable }
 
+func is_proximity_available() -> Bool { return false }
+func read_proximity() -> ScalarResult { return .NotAvailable }
+
+func is_orientation_available() -> Bool { return false }
+/// CoreMotion's world frame has x pointing north; turn it 90° about z so
+/// x points east, matching Android's East-North-Up frame.
+private func eastNorthUp(_ q: CMQuaternion) -> CMQuaternion {
+    let c = 0.5.squareRoot()
+    return CMQuaternion(
+        x: c * q.x - c * q.y,
+        y: c * q.y + c * q.x,
+        z: c * q.z + c * q.w,
+        w: c * q.w - c * q.z
+    )
+}
+
+func read_orientation() -> OrientationResult { return .NotAvailable }
+
+func is_gravity_available() -> Bool { return false }
+func read_gravity() -> SensorResult { return .NotAvailable }
+
+func is_linear_acceleration_available() -> Bool { return false }
+func read_linear_acceleration() -> SensorResult { return .NotAvailable }
+func start_proximity_monitoring() -> Bool { return false }
+func stop_proximity_monitoring() {}
+
+func is_compass_available() -> Bool { return false }
+func read_heading() -> HeadingResult { return .NotAvailable }
+func start_heading_updates() -> Bool { return false }
+func stop_heading_updates() {}
+func set_heading_calibration_display(allow: Bool) {}
+
+func start_motion_watch(kind: UInt8, interval: Double) -> UInt32 { return 0 }
+func drain_motion_watch(id: UInt32) -> RustVec<Double> { return RustVec() }
+func stop_motion_watch(id: UInt32) {}
+
+func is_motion_activity_available() -> Bool { return false }
+func start_activity_watch() -> Int64 { return 0 }
+func drain_activity_watch(id: UInt32) -> RustVec<UInt8> { return RustVec() }
+func stop_activity_watch(id: UInt32) {}
+
 // Ambient Light Support for macOS (IOKit)
 
 func is_ambient_light_available() -> Bool {
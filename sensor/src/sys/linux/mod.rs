@@ -1,18 +1,29 @@
 //! Linux sensor implementation using iio-sensor-proxy D-Bus service.
 //!
 //! Most Linux desktops don't have motion sensors, but some laptops
-//! (like ThinkPads, Surface devices) have accelerometers accessible
-//! via the iio-sensor-proxy service.
+//! (like `ThinkPads`, Surface devices) have accelerometers accessible
+//! via the iio-sensor-proxy service. Temperature and humidity sensors,
+//! common on single-board computers, are read from IIO and hwmon in sysfs.
 
-use crate::{ScalarData, SensorData, SensorError, SensorStream};
-use futures::stream;
+use crate::{
+    HeadingData, MotionState, OrientationData, ScalarData, SensorAccuracy, SensorData, SensorError,
+    SensorOptions, SensorStream,
+};
+use futures::{StreamExt, future, stream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use zbus::blocking::Connection;
+use zbus::names::InterfaceName;
+use zbus::zvariant::OwnedValue;
 
 const IIO_PROXY_BUS: &str = "net.hadess.SensorProxy";
 const IIO_PROXY_PATH: &str = "/net/hadess/SensorProxy";
 const IIO_PROXY_IFACE: &str = "net.hadess.SensorProxy";
 
-fn get_proxy_property<T: for<'a> serde::Deserialize<'a>>(
+const IIO_DEVICES: &str = "/sys/bus/iio/devices";
+const HWMON_DEVICES: &str = "/sys/class/hwmon";
+
+fn get_proxy_property<T: TryFrom<OwnedValue, Error = zbus::zvariant::Error>>(
     conn: &Connection,
     property: &str,
 ) -> Result<T, SensorError> {
@@ -25,20 +36,19 @@ fn get_proxy_property<T: for<'a> serde::Deserialize<'a>>(
         .map_err(|e| SensorError::Unknown(e.to_string()))?;
 
     let value = proxy
-        .get(IIO_PROXY_IFACE, property)
+        .get(
+            InterfaceName::from_static_str_unchecked(IIO_PROXY_IFACE),
+            property,
+        )
         .map_err(|e| SensorError::Unknown(e.to_string()))?;
 
-    value
-        .downcast_ref::<T>()
-        .cloned()
-        .ok_or_else(|| SensorError::Unknown("Invalid property type".into()))
+    T::try_from(value).map_err(|_| SensorError::Unknown("Invalid property type".into()))
 }
 
 fn timestamp_now() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
 }
 
 // Accelerometer (via iio-sensor-proxy)
@@ -51,6 +61,7 @@ pub fn accelerometer_available() -> bool {
         .unwrap_or(false)
 }
 
+#[allow(clippy::unused_async)]
 pub async fn accelerometer_read() -> Result<SensorData, SensorError> {
     let conn = Connection::system().map_err(|e| SensorError::Unknown(e.to_string()))?;
 
@@ -66,10 +77,10 @@ pub async fn accelerometer_read() -> Result<SensorData, SensorError> {
 
     // Map orientation to approximate accelerometer values
     let (x, y, z) = match orientation.as_str() {
-        "normal" => (0.0, 0.0, -1.0),
         "bottom-up" => (0.0, 0.0, 1.0),
         "left-up" => (-1.0, 0.0, 0.0),
         "right-up" => (1.0, 0.0, 0.0),
+        // "normal" or "undefined"
         _ => (0.0, 0.0, -1.0),
     };
 
@@ -77,15 +88,18 @@ pub async fn accelerometer_read() -> Result<SensorData, SensorError> {
         x,
         y,
         z,
+        accuracy: SensorAccuracy::Unknown,
         timestamp: timestamp_now(),
     })
 }
 
-pub fn accelerometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+pub fn accelerometer_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<SensorData>, SensorError> {
     if !accelerometer_available() {
         return Err(SensorError::NotAvailable);
     }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
+    let interval = options.poll_interval();
     Ok(Box::pin(stream::unfold((), move |()| async move {
         futures_timer::Delay::new(interval).await;
         accelerometer_read().await.ok().map(|data| (data, ()))
@@ -93,15 +107,16 @@ pub fn accelerometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>,
 }
 
 // Gyroscope (not typically available on Linux laptops)
-pub fn gyroscope_available() -> bool {
+pub const fn gyroscope_available() -> bool {
     false
 }
 
+#[allow(clippy::unused_async)]
 pub async fn gyroscope_read() -> Result<SensorData, SensorError> {
     Err(SensorError::NotAvailable)
 }
 
-pub fn gyroscope_watch(_interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+pub fn gyroscope_watch(_options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
     Err(SensorError::NotAvailable)
 }
 
@@ -115,6 +130,7 @@ pub fn magnetometer_available() -> bool {
         .unwrap_or(false)
 }
 
+#[allow(clippy::unused_async)]
 pub async fn magnetometer_read() -> Result<SensorData, SensorError> {
     let conn = Connection::system().map_err(|e| SensorError::Unknown(e.to_string()))?;
 
@@ -132,15 +148,16 @@ pub async fn magnetometer_read() -> Result<SensorData, SensorError> {
         x: rad.sin(),
         y: rad.cos(),
         z: 0.0,
+        accuracy: SensorAccuracy::Unknown,
         timestamp: timestamp_now(),
     })
 }
 
-pub fn magnetometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+pub fn magnetometer_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
     if !magnetometer_available() {
         return Err(SensorError::NotAvailable);
     }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
+    let interval = options.poll_interval();
     Ok(Box::pin(stream::unfold((), move |()| async move {
         futures_timer::Delay::new(interval).await;
         magnetometer_read().await.ok().map(|data| (data, ()))
@@ -148,14 +165,320 @@ pub fn magnetometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>,
 }
 
 // Barometer (not typically available on Linux laptops)
-pub fn barometer_available() -> bool {
+pub const fn barometer_available() -> bool {
     false
 }
 
+#[allow(clippy::unused_async)]
 pub async fn barometer_read() -> Result<ScalarData, SensorError> {
     Err(SensorError::NotAvailable)
 }
 
-pub fn barometer_watch(_interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
+pub fn barometer_watch(_options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Relative altitude (no fused altimeter)
+pub const fn relative_altitude_available() -> bool {
+    false
+}
+
+pub fn relative_altitude_watch(
+    _options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Ambient light (via iio-sensor-proxy)
+pub fn ambient_light_available() -> bool {
+    Connection::system()
+        .and_then(|conn| {
+            get_proxy_property::<bool>(&conn, "HasAmbientLight")
+                .map_err(|_| zbus::Error::Failure("not available".into()))
+        })
+        .unwrap_or(false)
+}
+
+/// Claim the light sensor: iio-sensor-proxy only updates `LightLevel` for
+/// claimed sensors, and drops the claim when the connection closes.
+fn claim_light() -> Result<Connection, SensorError> {
+    let conn = Connection::system().map_err(|e| SensorError::Unknown(e.to_string()))?;
+
+    let has = get_proxy_property::<bool>(&conn, "HasAmbientLight")?;
+    if !has {
+        return Err(SensorError::NotAvailable);
+    }
+
+    conn.call_method(
+        Some(IIO_PROXY_BUS),
+        IIO_PROXY_PATH,
+        Some(IIO_PROXY_IFACE),
+        "ClaimLight",
+        &(),
+    )
+    .map_err(|e| SensorError::Unknown(e.to_string()))?;
+    Ok(conn)
+}
+
+/// Read the level of a claimed light sensor. Drivers without a lux scale
+/// report `vendor` units instead, which are passed through as is.
+fn light_level(conn: &Connection) -> Result<ScalarData, SensorError> {
+    Ok(ScalarData {
+        value: get_proxy_property(conn, "LightLevel")?,
+        timestamp: timestamp_now(),
+    })
+}
+
+pub async fn ambient_light_read() -> Result<ScalarData, SensorError> {
+    let conn = claim_light()?;
+
+    let proxy = zbus::fdo::PropertiesProxy::builder(conn.inner())
+        .destination(IIO_PROXY_BUS)
+        .map_err(|e| SensorError::Unknown(e.to_string()))?
+        .path(IIO_PROXY_PATH)
+        .map_err(|e| SensorError::Unknown(e.to_string()))?
+        .build()
+        .await
+        .map_err(|e| SensorError::Unknown(e.to_string()))?;
+    let mut changes = proxy
+        .receive_properties_changed()
+        .await
+        .map_err(|e| SensorError::Unknown(e.to_string()))?;
+
+    // The first reading after the claim arrives as a change, unless it
+    // equals the stale level; stop waiting after a second in that case
+    let first_reading = async {
+        while let Some(change) = changes.next().await {
+            let has_level = change
+                .args()
+                .is_ok_and(|args| args.changed_properties().contains_key("LightLevel"));
+            if has_level {
+                break;
+            }
+        }
+    };
+    future::select(
+        Box::pin(first_reading),
+        futures_timer::Delay::new(Duration::from_secs(1)),
+    )
+    .await;
+
+    light_level(&conn)
+}
+
+pub fn ambient_light_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    let conn = claim_light()?;
+    let interval = options.poll_interval();
+    // The stream owns the connection, so the claim lasts until it is dropped
+    Ok(Box::pin(stream::unfold(conn, move |conn| async move {
+        futures_timer::Delay::new(interval).await;
+        light_level(&conn).ok().map(|data| (data, conn))
+    })))
+}
+
+// Proximity (not available on desktop)
+pub const fn proximity_available() -> bool {
+    false
+}
+
+#[allow(clippy::unused_async)]
+pub async fn proximity_read() -> Result<ScalarData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn proximity_watch(_options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Orientation (not available on desktop)
+pub const fn orientation_available() -> bool {
+    false
+}
+
+#[allow(clippy::unused_async)]
+pub async fn orientation_read() -> Result<OrientationData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn orientation_watch(
+    _options: SensorOptions,
+) -> Result<SensorStream<OrientationData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Gravity (no virtual sensor; estimated from the accelerometer instead)
+pub const fn gravity_available() -> bool {
+    false
+}
+
+#[allow(clippy::unused_async)]
+pub async fn gravity_read() -> Result<SensorData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn gravity_watch(_options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Linear acceleration (no virtual sensor; estimated from the accelerometer instead)
+pub const fn linear_acceleration_available() -> bool {
+    false
+}
+
+#[allow(clippy::unused_async)]
+pub async fn linear_acceleration_read() -> Result<SensorData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn linear_acceleration_watch(
+    _options: SensorOptions,
+) -> Result<SensorStream<SensorData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Compass
+pub const fn compass_available() -> bool {
+    false
+}
+
+#[allow(clippy::unused_async)]
+pub async fn compass_read() -> Result<HeadingData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn compass_watch(_options: SensorOptions) -> Result<SensorStream<HeadingData>, SensorError> {
     Err(SensorError::NotAvailable)
 }
+
+pub const fn set_allow_calibration_ui(_allow: bool) {}
+
+pub const fn declination(_latitude: f64, _longitude: f64, _altitude: f64) -> Option<f64> {
+    None
+}
+
+// Significant motion and motion state (estimated from the accelerometer)
+pub const fn significant_motion_available() -> bool {
+    false
+}
+
+#[allow(clippy::unused_async)]
+pub async fn significant_motion_wait() -> Result<(), SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub const fn motion_state_available() -> bool {
+    false
+}
+
+pub fn motion_state_watch() -> Result<SensorStream<MotionState>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Ambient temperature and humidity (IIO or hwmon sensors in sysfs)
+
+/// Read a number from a sysfs attribute.
+fn read_attribute(dir: &Path, name: &str) -> Option<f64> {
+    std::fs::read_to_string(dir.join(name))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Whether a sysfs sensor measures the environment: it has a humidity or
+/// pressure channel. This leaves out CPU, disk and chip die temperatures.
+fn is_environmental(dir: &Path) -> bool {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .any(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("in_humidityrelative")
+                || name.starts_with("in_pressure")
+                || name.starts_with("humidity")
+        })
+}
+
+fn sensor_dirs() -> impl Iterator<Item = PathBuf> {
+    [IIO_DEVICES, HWMON_DEVICES]
+        .into_iter()
+        .filter_map(|root| std::fs::read_dir(root).ok())
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+}
+
+/// Read the IIO channel `iio_channel` or hwmon attribute `hwmon_attribute`
+/// of the first environmental sensor that has it.
+fn read_environment(iio_channel: &str, hwmon_attribute: &str) -> Option<f64> {
+    sensor_dirs()
+        .filter(|dir| is_environmental(dir))
+        .find_map(|dir| {
+            // Both report milli-units, IIO either processed or raw
+            if let Some(value) = read_attribute(&dir, &format!("in_{iio_channel}_input")) {
+                return Some(value / 1000.0);
+            }
+            if let Some(raw) = read_attribute(&dir, &format!("in_{iio_channel}_raw")) {
+                let offset = read_attribute(&dir, &format!("in_{iio_channel}_offset"));
+                let scale = read_attribute(&dir, &format!("in_{iio_channel}_scale"));
+                return Some((raw + offset.unwrap_or(0.0)) * scale.unwrap_or(1.0) / 1000.0);
+            }
+            read_attribute(&dir, hwmon_attribute).map(|value| value / 1000.0)
+        })
+}
+
+fn environment_read(iio_channel: &str, hwmon_attribute: &str) -> Result<ScalarData, SensorError> {
+    read_environment(iio_channel, hwmon_attribute)
+        .map(|value| ScalarData {
+            value,
+            timestamp: timestamp_now(),
+        })
+        .ok_or(SensorError::NotAvailable)
+}
+
+fn environment_watch(
+    iio_channel: &'static str,
+    hwmon_attribute: &'static str,
+    options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    environment_read(iio_channel, hwmon_attribute)?;
+    let interval = options.poll_interval();
+    Ok(Box::pin(stream::unfold((), move |()| async move {
+        futures_timer::Delay::new(interval).await;
+        environment_read(iio_channel, hwmon_attribute)
+            .ok()
+            .map(|data| (data, ()))
+    })))
+}
+
+pub fn ambient_temperature_available() -> bool {
+    read_environment("temp", "temp1_input").is_some()
+}
+
+#[allow(clippy::unused_async)]
+pub async fn ambient_temperature_read() -> Result<ScalarData, SensorError> {
+    environment_read("temp", "temp1_input")
+}
+
+pub fn ambient_temperature_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    environment_watch("temp", "temp1_input", options)
+}
+
+pub fn humidity_available() -> bool {
+    read_environment("humidityrelative", "humidity1_input").is_some()
+}
+
+#[allow(clippy::unused_async)]
+pub async fn humidity_read() -> Result<ScalarData, SensorError> {
+    environment_read("humidityrelative", "humidity1_input")
+}
+
+pub fn humidity_watch(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    environment_watch("humidityrelative", "humidity1_input", options)
+}
@@ -35,7 +35,10 @@ pub use linux::*;
     target_os = "linux"
 )))]
 mod fallback {
-    use crate::{ScalarData, SensorData, SensorError, SensorStream};
+    use crate::{
+        HeadingData, MotionState, OrientationData, ScalarData, SensorData, SensorError,
+        SensorOptions, SensorStream,
+    };
 
     pub fn accelerometer_available() -> bool {
         false
@@ -43,7 +46,9 @@ mod fallback {
     pub async fn accelerometer_read() -> Result<SensorData, SensorError> {
         Err(SensorError::NotAvailable)
     }
-    pub fn accelerometer_watch(_interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+    pub fn accelerometer_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<SensorData>, SensorError> {
         Err(SensorError::NotAvailable)
     }
 
@@ -53,7 +58,9 @@ mod fallback {
     pub async fn gyroscope_read() -> Result<SensorData, SensorError> {
         Err(SensorError::NotAvailable)
     }
-    pub fn gyroscope_watch(_interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+    pub fn gyroscope_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<SensorData>, SensorError> {
         Err(SensorError::NotAvailable)
     }
 
@@ -63,7 +70,9 @@ mod fallback {
     pub async fn magnetometer_read() -> Result<SensorData, SensorError> {
         Err(SensorError::NotAvailable)
     }
-    pub fn magnetometer_watch(_interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+    pub fn magnetometer_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<SensorData>, SensorError> {
         Err(SensorError::NotAvailable)
     }
 
@@ -73,7 +82,18 @@ mod fallback {
     pub async fn barometer_read() -> Result<ScalarData, SensorError> {
         Err(SensorError::NotAvailable)
     }
-    pub fn barometer_watch(_interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
+    pub fn barometer_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<ScalarData>, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+
+    pub fn relative_altitude_available() -> bool {
+        false
+    }
+    pub fn relative_altitude_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<ScalarData>, SensorError> {
         Err(SensorError::NotAvailable)
     }
 
@@ -83,7 +103,108 @@ mod fallback {
     pub async fn ambient_light_read() -> Result<ScalarData, SensorError> {
         Err(SensorError::NotAvailable)
     }
-    pub fn ambient_light_watch(_interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
+    pub fn ambient_light_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<ScalarData>, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+
+    pub fn proximity_available() -> bool {
+        false
+    }
+    pub async fn proximity_read() -> Result<ScalarData, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+    pub fn proximity_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<ScalarData>, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+
+    pub fn orientation_available() -> bool {
+        false
+    }
+    pub async fn orientation_read() -> Result<OrientationData, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+    pub fn orientation_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<OrientationData>, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+
+    pub fn gravity_available() -> bool {
+        false
+    }
+    pub async fn gravity_read() -> Result<SensorData, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+    pub fn gravity_watch(_options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+
+    pub fn linear_acceleration_available() -> bool {
+        false
+    }
+    pub async fn linear_acceleration_read() -> Result<SensorData, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+    pub fn linear_acceleration_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<SensorData>, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+
+    pub fn ambient_temperature_available() -> bool {
+        false
+    }
+    pub async fn ambient_temperature_read() -> Result<ScalarData, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+    pub fn ambient_temperature_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<ScalarData>, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+
+    pub fn humidity_available() -> bool {
+        false
+    }
+    pub async fn humidity_read() -> Result<ScalarData, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+    pub fn humidity_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<ScalarData>, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+
+    pub fn compass_available() -> bool {
+        false
+    }
+    pub async fn compass_read() -> Result<HeadingData, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+    pub fn compass_watch(
+        _options: SensorOptions,
+    ) -> Result<SensorStream<HeadingData>, SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+    pub const fn set_allow_calibration_ui(_allow: bool) {}
+    pub const fn declination(_latitude: f64, _longitude: f64, _altitude: f64) -> Option<f64> {
+        None
+    }
+
+    pub fn significant_motion_available() -> bool {
+        false
+    }
+    pub async fn significant_motion_wait() -> Result<(), SensorError> {
+        Err(SensorError::NotAvailable)
+    }
+    pub fn motion_state_available() -> bool {
+        false
+    }
+    pub fn motion_state_watch() -> Result<SensorStream<MotionState>, SensorError> {
         Err(SensorError::NotAvailable)
     }
 }
@@ -1,10 +1,14 @@
 //! Windows sensor implementation using WinRT.
 
-use crate::{ScalarData, SensorData, SensorError, SensorStream};
+use crate::{
+    HeadingData, MotionState, OrientationData, ScalarData, SensorAccuracy, SensorData, SensorError,
+    SensorOptions, SensorStream,
+};
 use futures::stream;
 use windows::Devices::Sensors::{
-    Accelerometer as WinAccelerometer, Barometer as WinBarometer, Gyrometer as WinGyrometer,
-    Magnetometer as WinMagnetometer,
+    Accelerometer as WinAccelerometer, AccelerometerReadingType, Barometer as WinBarometer,
+    Gyrometer as WinGyrometer, LightSensor as WinLightSensor, Magnetometer as WinMagnetometer,
+    MagnetometerAccuracy,
 };
 
 fn timestamp_now() -> u64 {
@@ -30,15 +34,18 @@ pub async fn accelerometer_read() -> Result<SensorData, SensorError> {
         x: reading.AccelerationX().unwrap_or(0.0),
         y: reading.AccelerationY().unwrap_or(0.0),
         z: reading.AccelerationZ().unwrap_or(0.0),
+        accuracy: SensorAccuracy::Unknown,
         timestamp: timestamp_now(),
     })
 }
 
-pub fn accelerometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+pub fn accelerometer_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<SensorData>, SensorError> {
     if !accelerometer_available() {
         return Err(SensorError::NotAvailable);
     }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
+    let interval = options.poll_interval();
     Ok(Box::pin(stream::unfold((), move |()| async move {
         futures_timer::Delay::new(interval).await;
         accelerometer_read().await.ok().map(|data| (data, ()))
@@ -61,15 +68,16 @@ pub async fn gyroscope_read() -> Result<SensorData, SensorError> {
         x: reading.AngularVelocityX().unwrap_or(0.0),
         y: reading.AngularVelocityY().unwrap_or(0.0),
         z: reading.AngularVelocityZ().unwrap_or(0.0),
+        accuracy: SensorAccuracy::Unknown,
         timestamp: timestamp_now(),
     })
 }
 
-pub fn gyroscope_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+pub fn gyroscope_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
     if !gyroscope_available() {
         return Err(SensorError::NotAvailable);
     }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
+    let interval = options.poll_interval();
     Ok(Box::pin(stream::unfold((), move |()| async move {
         futures_timer::Delay::new(interval).await;
         gyroscope_read().await.ok().map(|data| (data, ()))
@@ -92,15 +100,21 @@ pub async fn magnetometer_read() -> Result<SensorData, SensorError> {
         x: reading.MagneticFieldX().unwrap_or(0.0),
         y: reading.MagneticFieldY().unwrap_or(0.0),
         z: reading.MagneticFieldZ().unwrap_or(0.0),
+        accuracy: match reading.DirectionalAccuracy() {
+            Ok(MagnetometerAccuracy::Unreliable) => SensorAccuracy::Unreliable,
+            Ok(MagnetometerAccuracy::Approximate) => SensorAccuracy::Medium,
+            Ok(MagnetometerAccuracy::High) => SensorAccuracy::High,
+            _ => SensorAccuracy::Unknown,
+        },
         timestamp: timestamp_now(),
     })
 }
 
-pub fn magnetometer_watch(interval_ms: u32) -> Result<SensorStream<SensorData>, SensorError> {
+pub fn magnetometer_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
     if !magnetometer_available() {
         return Err(SensorError::NotAvailable);
     }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
+    let interval = options.poll_interval();
     Ok(Box::pin(stream::unfold((), move |()| async move {
         futures_timer::Delay::new(interval).await;
         magnetometer_read().await.ok().map(|data| (data, ()))
@@ -125,13 +139,211 @@ pub async fn barometer_read() -> Result<ScalarData, SensorError> {
     })
 }
 
-pub fn barometer_watch(interval_ms: u32) -> Result<SensorStream<ScalarData>, SensorError> {
+pub fn barometer_watch(options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
     if !barometer_available() {
         return Err(SensorError::NotAvailable);
     }
-    let interval = std::time::Duration::from_millis(u64::from(interval_ms));
+    let interval = options.poll_interval();
     Ok(Box::pin(stream::unfold((), move |()| async move {
         futures_timer::Delay::new(interval).await;
         barometer_read().await.ok().map(|data| (data, ()))
     })))
 }
+
+// Relative altitude (no fused altimeter)
+pub fn relative_altitude_available() -> bool {
+    false
+}
+
+pub fn relative_altitude_watch(
+    _options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Ambient light
+pub fn ambient_light_available() -> bool {
+    WinLightSensor::GetDefault().is_ok()
+}
+
+pub async fn ambient_light_read() -> Result<ScalarData, SensorError> {
+    let sensor = WinLightSensor::GetDefault().map_err(|_| SensorError::NotAvailable)?;
+
+    let reading = sensor
+        .GetCurrentReading()
+        .map_err(|e| SensorError::Unknown(e.to_string()))?;
+
+    Ok(ScalarData {
+        value: f64::from(
+            reading
+                .IlluminanceInLux()
+                .map_err(|e| SensorError::Unknown(e.to_string()))?,
+        ),
+        timestamp: timestamp_now(),
+    })
+}
+
+pub fn ambient_light_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    if !ambient_light_available() {
+        return Err(SensorError::NotAvailable);
+    }
+    let interval = options.poll_interval();
+    Ok(Box::pin(stream::unfold((), move |()| async move {
+        futures_timer::Delay::new(interval).await;
+        ambient_light_read().await.ok().map(|data| (data, ()))
+    })))
+}
+
+// Proximity (not available on desktop)
+pub fn proximity_available() -> bool {
+    false
+}
+
+pub async fn proximity_read() -> Result<ScalarData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn proximity_watch(_options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Orientation (not available on desktop)
+pub fn orientation_available() -> bool {
+    false
+}
+
+pub async fn orientation_read() -> Result<OrientationData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn orientation_watch(
+    _options: SensorOptions,
+) -> Result<SensorStream<OrientationData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Gravity and linear acceleration (accelerometer reading types, Windows 10 1703+)
+fn accelerometer_of_type_read(kind: AccelerometerReadingType) -> Result<SensorData, SensorError> {
+    let sensor = WinAccelerometer::GetDefaultWithAccelerometerReadingType(kind)
+        .map_err(|_| SensorError::NotAvailable)?;
+
+    let reading = sensor
+        .GetCurrentReading()
+        .map_err(|e| SensorError::Unknown(e.to_string()))?;
+
+    Ok(SensorData {
+        x: reading.AccelerationX().unwrap_or(0.0),
+        y: reading.AccelerationY().unwrap_or(0.0),
+        z: reading.AccelerationZ().unwrap_or(0.0),
+        accuracy: SensorAccuracy::Unknown,
+        timestamp: timestamp_now(),
+    })
+}
+
+pub fn gravity_available() -> bool {
+    WinAccelerometer::GetDefaultWithAccelerometerReadingType(AccelerometerReadingType::Gravity)
+        .is_ok()
+}
+
+pub async fn gravity_read() -> Result<SensorData, SensorError> {
+    accelerometer_of_type_read(AccelerometerReadingType::Gravity)
+}
+
+pub fn gravity_watch(options: SensorOptions) -> Result<SensorStream<SensorData>, SensorError> {
+    if !gravity_available() {
+        return Err(SensorError::NotAvailable);
+    }
+    let interval = options.poll_interval();
+    Ok(Box::pin(stream::unfold((), move |()| async move {
+        futures_timer::Delay::new(interval).await;
+        gravity_read().await.ok().map(|data| (data, ()))
+    })))
+}
+
+pub fn linear_acceleration_available() -> bool {
+    WinAccelerometer::GetDefaultWithAccelerometerReadingType(AccelerometerReadingType::Linear)
+        .is_ok()
+}
+
+pub async fn linear_acceleration_read() -> Result<SensorData, SensorError> {
+    accelerometer_of_type_read(AccelerometerReadingType::Linear)
+}
+
+pub fn linear_acceleration_watch(
+    options: SensorOptions,
+) -> Result<SensorStream<SensorData>, SensorError> {
+    if !linear_acceleration_available() {
+        return Err(SensorError::NotAvailable);
+    }
+    let interval = options.poll_interval();
+    Ok(Box::pin(stream::unfold((), move |()| async move {
+        futures_timer::Delay::new(interval).await;
+        linear_acceleration_read().await.ok().map(|data| (data, ()))
+    })))
+}
+
+// Ambient temperature (no WinRT sensor)
+pub fn ambient_temperature_available() -> bool {
+    false
+}
+
+pub async fn ambient_temperature_read() -> Result<ScalarData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn ambient_temperature_watch(
+    _options: SensorOptions,
+) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Humidity (no WinRT sensor)
+pub fn humidity_available() -> bool {
+    false
+}
+
+pub async fn humidity_read() -> Result<ScalarData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn humidity_watch(_options: SensorOptions) -> Result<SensorStream<ScalarData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+// Compass
+pub fn compass_available() -> bool {
+    false
+}
+
+pub async fn compass_read() -> Result<HeadingData, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn compass_watch(_options: SensorOptions) -> Result<SensorStream<HeadingData>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub const fn set_allow_calibration_ui(_allow: bool) {}
+
+pub const fn declination(_latitude: f64, _longitude: f64, _altitude: f64) -> Option<f64> {
+    None
+}
+
+// Significant motion and motion state (estimated from the accelerometer)
+pub fn significant_motion_available() -> bool {
+    false
+}
+
+pub async fn significant_motion_wait() -> Result<(), SensorError> {
+    Err(SensorError::NotAvailable)
+}
+
+pub fn motion_state_available() -> bool {
+    false
+}
+
+pub fn motion_state_watch() -> Result<SensorStream<MotionState>, SensorError> {
+    Err(SensorError::NotAvailable)
+}
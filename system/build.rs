@@ -15,7 +15,7 @@ fn main() {
         if target.contains("ios") {
             config = config.framework("UIKit");
         } else {
-            config = config.framework("AppKit");
+            config = config.framework("AppKit").framework("IOKit");
         }
 
         waterkit_build::compile_swift("src/sys/apple/mod.rs", &config);
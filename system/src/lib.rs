@@ -3,8 +3,13 @@
 //! This crate provides a unified API for retrieving system information
 //! such as connectivity, thermal state, and system load across different platforms.
 
+#[cfg(feature = "model-names")]
+mod model_names;
 mod sys;
 
+use futures::Stream;
+use std::path::Path;
+
 /// Type of network connection.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ConnectionType {
@@ -25,7 +30,7 @@ pub enum ConnectionType {
 }
 
 /// Information about network connectivity.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ConnectivityInfo {
     /// The type of the current connection.
     pub connection_type: ConnectionType,
@@ -59,20 +64,177 @@ pub struct SystemLoad {
     pub memory_total: u64,
 }
 
+/// Storage capacity of the volume containing a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskInfo {
+    /// Total size of the volume in bytes.
+    pub total_bytes: u64,
+    /// Bytes available to the current user.
+    pub available_bytes: u64,
+    /// Bytes in use on the volume.
+    pub used_bytes: u64,
+}
+
+/// Battery charge and power state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryInfo {
+    /// Charge level from 0.0 to 1.0, or -1.0 if the device has no battery
+    /// or the level is unknown.
+    pub level: f32,
+    /// Whether the battery is currently charging.
+    pub is_charging: bool,
+    /// Whether Low Power Mode (Battery Saver on Android and Windows) is on.
+    pub is_low_power_mode: bool,
+}
+
+impl BatteryInfo {
+    /// Whether a battery level is available.
+    #[must_use]
+    pub fn has_battery(&self) -> bool {
+        self.level >= 0.0
+    }
+}
+
+/// Operating system name and version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsInfo {
+    /// Name of the operating system, e.g. "iOS", "macOS" or "Android".
+    pub name: String,
+    /// User-facing version, e.g. "17.2.1" or "14".
+    pub version: String,
+    /// Build identifier, if the platform reports one.
+    ///
+    /// The build number on Apple platforms, `Build.ID` on Android, the OS
+    /// build on Windows and the kernel release on Linux.
+    pub build: Option<String>,
+}
+
+/// Hardware model and user-visible name of the device.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DeviceInfo {
+    /// Model identifier, e.g. "iPhone15,2", "MacBookPro18,3" or "Pixel 8".
+    ///
+    /// Empty if the platform doesn't report one.
+    pub model: String,
+    /// Device manufacturer, e.g. "Apple" or "Google".
+    pub manufacturer: String,
+    /// Name the user gave the device, or the host name on desktops.
+    ///
+    /// Since iOS 16 this is the generic "iPhone" or "iPad" unless the app has
+    /// the user-assigned device name entitlement.
+    pub name: String,
+}
+
+#[cfg(feature = "model-names")]
+impl DeviceInfo {
+    /// Marketing name for Apple model identifiers, e.g. "iPhone 14 Pro" for
+    /// "iPhone15,2".
+    ///
+    /// Returns `None` for other manufacturers and identifiers missing from
+    /// the built-in table.
+    #[must_use]
+    pub fn marketing_name(&self) -> Option<&'static str> {
+        if self.manufacturer != "Apple" {
+            return None;
+        }
+        model_names::marketing_name(&self.model)
+    }
+}
+
 /// Get the current network connectivity information.
 #[must_use]
 pub fn get_connectivity_info() -> ConnectivityInfo {
     sys::get_connectivity_info()
 }
 
+/// Watch network connectivity for changes.
+///
+/// The stream yields the current status immediately, then a new value
+/// whenever the connection type or reachability changes. The platform
+/// monitor is stopped when the stream is dropped.
+pub fn watch_connectivity() -> impl Stream<Item = ConnectivityInfo> {
+    let (sender, receiver) = async_channel::unbounded();
+    // Start monitoring before taking the snapshot so no change is missed
+    let watcher = sys::ConnectivityWatcher::start(sender);
+    changes(sys::get_connectivity_info(), receiver, watcher)
+}
+
 /// Get the current thermal state of the device.
 #[must_use]
 pub fn get_thermal_state() -> ThermalState {
     sys::get_thermal_state()
 }
 
+/// Watch the device thermal state for changes.
+///
+/// The stream yields the current state immediately, then a new value on
+/// every transition. The platform observer is stopped when the stream is
+/// dropped.
+pub fn watch_thermal_state() -> impl Stream<Item = ThermalState> {
+    let (sender, receiver) = async_channel::unbounded();
+    // Start observing before taking the snapshot so no change is missed
+    let watcher = sys::ThermalWatcher::start(sender);
+    changes(sys::get_thermal_state(), receiver, watcher)
+}
+
+/// Get the battery level and charging state.
+///
+/// Desktops without a battery report a `level` of -1.0.
+#[must_use]
+pub fn get_battery_info() -> BatteryInfo {
+    sys::get_battery_info()
+}
+
+/// Get the name and version of the running operating system.
+#[must_use]
+pub fn os_info() -> OsInfo {
+    sys::os_info()
+}
+
+/// Get the model, manufacturer and name of the device.
+#[must_use]
+pub fn device_info() -> DeviceInfo {
+    sys::device_info()
+}
+
 /// Get the current system load information.
 #[must_use]
 pub fn get_system_load() -> SystemLoad {
     sys::get_system_load()
 }
+
+/// Get storage capacity of the volume containing `path`.
+///
+/// # Errors
+/// Returns an error if the path does not exist or the volume cannot be queried.
+pub fn disk_info(path: &Path) -> Result<DiskInfo, std::io::Error> {
+    sys::disk_info(path)
+}
+
+/// Yield `initial`, then every received value that differs from the last one.
+///
+/// `watcher` is kept alive as long as the stream.
+fn changes<T, W>(
+    initial: T,
+    receiver: async_channel::Receiver<T>,
+    watcher: W,
+) -> impl Stream<Item = T>
+where
+    T: Clone + PartialEq,
+{
+    futures::stream::unfold(
+        (receiver, watcher, Some(initial), None::<T>),
+        |(receiver, watcher, mut pending, mut last)| async move {
+            loop {
+                let value = match pending.take() {
+                    Some(value) => value,
+                    None => receiver.recv().await.ok()?,
+                };
+                if last.as_ref() != Some(&value) {
+                    last = Some(value.clone());
+                    return Some((value, (receiver, watcher, pending, last)));
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,70 @@
+//! Marketing names for Apple model identifiers.
+//!
+//! Covers devices from roughly the last five years; older or newer
+//! identifiers are not in the table.
+
+/// Look up the marketing name for a `hw.machine` / `hw.model` identifier.
+pub(crate) fn marketing_name(identifier: &str) -> Option<&'static str> {
+    let name = match identifier {
+        // iPhone
+        "iPhone12,1" => "iPhone 11",
+        "iPhone12,3" => "iPhone 11 Pro",
+        "iPhone12,5" => "iPhone 11 Pro Max",
+        "iPhone12,8" => "iPhone SE (2nd generation)",
+        "iPhone13,1" => "iPhone 12 mini",
+        "iPhone13,2" => "iPhone 12",
+        "iPhone13,3" => "iPhone 12 Pro",
+        "iPhone13,4" => "iPhone 12 Pro Max",
+        "iPhone14,4" => "iPhone 13 mini",
+        "iPhone14,5" => "iPhone 13",
+        "iPhone14,2" => "iPhone 13 Pro",
+        "iPhone14,3" => "iPhone 13 Pro Max",
+        "iPhone14,6" => "iPhone SE (3rd generation)",
+        "iPhone14,7" => "iPhone 14",
+        "iPhone14,8" => "iPhone 14 Plus",
+        "iPhone15,2" => "iPhone 14 Pro",
+        "iPhone15,3" => "iPhone 14 Pro Max",
+        "iPhone15,4" => "iPhone 15",
+        "iPhone15,5" => "iPhone 15 Plus",
+        "iPhone16,1" => "iPhone 15 Pro",
+        "iPhone16,2" => "iPhone 15 Pro Max",
+        "iPhone17,3" => "iPhone 16",
+        "iPhone17,4" => "iPhone 16 Plus",
+        "iPhone17,1" => "iPhone 16 Pro",
+        "iPhone17,2" => "iPhone 16 Pro Max",
+        "iPhone17,5" => "iPhone 16e",
+
+        // iPad
+        "iPad13,18" | "iPad13,19" => "iPad (10th generation)",
+        "iPad13,16" | "iPad13,17" => "iPad Air (5th generation)",
+        "iPad14,1" | "iPad14,2" => "iPad mini (6th generation)",
+        "iPad14,3" | "iPad14,4" => "iPad Pro 11-inch (4th generation)",
+        "iPad14,5" | "iPad14,6" => "iPad Pro 12.9-inch (6th generation)",
+        "iPad14,8" | "iPad14,9" => "iPad Air 11-inch (M2)",
+        "iPad14,10" | "iPad14,11" => "iPad Air 13-inch (M2)",
+        "iPad16,1" | "iPad16,2" => "iPad mini (A17 Pro)",
+        "iPad16,3" | "iPad16,4" => "iPad Pro 11-inch (M4)",
+        "iPad16,5" | "iPad16,6" => "iPad Pro 13-inch (M4)",
+
+        // Mac
+        "MacBookAir10,1" => "MacBook Air (M1, 2020)",
+        "MacBookPro17,1" => "MacBook Pro (13-inch, M1, 2020)",
+        "Macmini9,1" => "Mac mini (M1, 2020)",
+        "iMac21,1" | "iMac21,2" => "iMac (24-inch, M1, 2021)",
+        "MacBookPro18,1" | "MacBookPro18,2" => "MacBook Pro (16-inch, 2021)",
+        "MacBookPro18,3" | "MacBookPro18,4" => "MacBook Pro (14-inch, 2021)",
+        "Mac13,1" => "Mac Studio (M1 Max, 2022)",
+        "Mac13,2" => "Mac Studio (M1 Ultra, 2022)",
+        "Mac14,2" => "MacBook Air (M2, 2022)",
+        "Mac14,7" => "MacBook Pro (13-inch, M2, 2022)",
+        "Mac14,15" => "MacBook Air (15-inch, M2, 2023)",
+        "Mac14,3" => "Mac mini (M2, 2023)",
+        "Mac14,12" => "Mac mini (M2 Pro, 2023)",
+        "Mac14,13" => "Mac Studio (M2 Max, 2023)",
+        "Mac14,14" => "Mac Studio (M2 Ultra, 2023)",
+        "Mac15,12" => "MacBook Air (13-inch, M3, 2024)",
+        "Mac15,13" => "MacBook Air (15-inch, M3, 2024)",
+        _ => return None,
+    };
+    Some(name)
+}
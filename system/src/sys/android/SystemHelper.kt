@@ -1,12 +1,19 @@
 package com.waterkit.system
 
 import android.content.Context
+import android.content.Intent
+import android.content.IntentFilter
 import android.net.ConnectivityManager
+import android.os.BatteryManager
+import android.net.Network
 import android.net.NetworkCapabilities
 import android.os.Build
+import android.os.StatFs
 import android.os.PowerManager
+import android.provider.Settings
 import android.app.ActivityManager
 import java.io.RandomAccessFile
+import java.util.concurrent.Executor
 
 object SystemHelper {
     // Previous CPU stats for delta calculation
@@ -16,7 +23,10 @@ object SystemHelper {
         val cm = context.getSystemService(Context.CONNECTIVITY_SERVICE) as? ConnectivityManager
         val network = cm?.activeNetwork ?: return 0 // None
         val caps = cm.getNetworkCapabilities(network) ?: return 0
+        return connectionType(caps)
+    }
 
+    private fun connectionType(caps: NetworkCapabilities): Int {
         if (caps.hasTransport(NetworkCapabilities.TRANSPORT_WIFI)) return 1 // Wifi
         if (caps.hasTransport(NetworkCapabilities.TRANSPORT_CELLULAR)) return 2 // Cellular
         if (caps.hasTransport(NetworkCapabilities.TRANSPORT_ETHERNET)) return 3 // Ethernet
@@ -25,6 +35,39 @@ object SystemHelper {
         return 6 // Other
     }
 
+    private val connectivityCallbacks = mutableMapOf<Long, ConnectivityManager.NetworkCallback>()
+
+    @JvmStatic
+    external fun onConnectivityChanged(watcherId: Long, connectionType: Int)
+
+    /** Report default network changes to [onConnectivityChanged] until stopped. */
+    @JvmStatic
+    fun startConnectivityWatch(context: Context, watcherId: Long) {
+        val cm = context.getSystemService(Context.CONNECTIVITY_SERVICE) as? ConnectivityManager ?: return
+        val callback = object : ConnectivityManager.NetworkCallback() {
+            override fun onCapabilitiesChanged(network: Network, caps: NetworkCapabilities) {
+                onConnectivityChanged(watcherId, connectionType(caps))
+            }
+
+            override fun onLost(network: Network) {
+                onConnectivityChanged(watcherId, 0) // None
+            }
+        }
+        synchronized(connectivityCallbacks) {
+            connectivityCallbacks[watcherId] = callback
+        }
+        cm.registerDefaultNetworkCallback(callback)
+    }
+
+    @JvmStatic
+    fun stopConnectivityWatch(context: Context, watcherId: Long) {
+        val callback = synchronized(connectivityCallbacks) {
+            connectivityCallbacks.remove(watcherId)
+        } ?: return
+        val cm = context.getSystemService(Context.CONNECTIVITY_SERVICE) as? ConnectivityManager ?: return
+        cm.unregisterNetworkCallback(callback)
+    }
+
     fun getThermalState(context: Context): Int {
         if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.Q) {
             val pm = context.getSystemService(Context.POWER_SERVICE) as? PowerManager
@@ -33,6 +76,41 @@ object SystemHelper {
         return -1 // Unknown
     }
 
+    private val thermalListeners = mutableMapOf<Long, PowerManager.OnThermalStatusChangedListener>()
+
+    @JvmStatic
+    external fun onThermalStatusChanged(watcherId: Long, status: Int)
+
+    /**
+     * Report thermal status changes to [onThermalStatusChanged] until stopped.
+     * Returns false below API 29, where the caller has to poll instead.
+     */
+    @JvmStatic
+    fun startThermalWatch(context: Context, watcherId: Long): Boolean {
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.Q) return false
+        val pm = context.getSystemService(Context.POWER_SERVICE) as? PowerManager ?: return false
+
+        val listener = PowerManager.OnThermalStatusChangedListener { status ->
+            onThermalStatusChanged(watcherId, status)
+        }
+        synchronized(thermalListeners) {
+            thermalListeners[watcherId] = listener
+        }
+        // Deliver on the calling binder thread; the app may not have a looper
+        pm.addThermalStatusListener(Executor { it.run() }, listener)
+        return true
+    }
+
+    @JvmStatic
+    fun stopThermalWatch(context: Context, watcherId: Long) {
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.Q) return
+        val listener = synchronized(thermalListeners) {
+            thermalListeners.remove(watcherId)
+        } ?: return
+        val pm = context.getSystemService(Context.POWER_SERVICE) as? PowerManager ?: return
+        pm.removeThermalStatusListener(listener)
+    }
+
     data class LoadInfo(val cpu: Float, val memUsed: Long, val memTotal: Long)
 
     fun getSystemLoad(context: Context): LoadInfo {
@@ -45,6 +123,45 @@ object SystemHelper {
         return LoadInfo(cpuUsage, memInfo.totalMem - memInfo.availMem, memInfo.totalMem)
     }
 
+    data class BatteryInfo(val level: Float, val isCharging: Boolean, val isLowPowerMode: Boolean)
+
+    fun getBatteryInfo(context: Context): BatteryInfo {
+        // The battery broadcast is sticky, so this returns the last status without a receiver
+        val status = context.registerReceiver(null, IntentFilter(Intent.ACTION_BATTERY_CHANGED))
+        val level = status?.getIntExtra(BatteryManager.EXTRA_LEVEL, -1) ?: -1
+        val scale = status?.getIntExtra(BatteryManager.EXTRA_SCALE, -1) ?: -1
+        val state = status?.getIntExtra(BatteryManager.EXTRA_STATUS, -1) ?: -1
+        val present = status?.getBooleanExtra(BatteryManager.EXTRA_PRESENT, true) ?: false
+
+        val pm = context.getSystemService(Context.POWER_SERVICE) as? PowerManager
+        return BatteryInfo(
+            if (present && level >= 0 && scale > 0) level.toFloat() / scale else -1.0f,
+            state == BatteryManager.BATTERY_STATUS_CHARGING,
+            pm?.isPowerSaveMode ?: false
+        )
+    }
+
+    data class DeviceInfo(val model: String, val manufacturer: String, val name: String)
+
+    @JvmStatic
+    fun getDeviceInfo(context: Context): DeviceInfo {
+        // The name set under Settings > About phone, when the vendor exposes it
+        val name = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.N_MR1) {
+            Settings.Global.getString(context.contentResolver, Settings.Global.DEVICE_NAME)
+        } else {
+            null
+        }
+        return DeviceInfo(Build.MODEL, Build.MANUFACTURER, name ?: Build.MODEL)
+    }
+
+    data class DiskInfo(val totalBytes: Long, val availableBytes: Long, val freeBytes: Long)
+
+    /** Throws IllegalArgumentException if the path cannot be queried. */
+    fun getDiskInfo(path: String): DiskInfo {
+        val stat = StatFs(path)
+        return DiskInfo(stat.totalBytes, stat.availableBytes, stat.freeBytes)
+    }
+
     private fun getCpuUsage(): Float {
         try {
             val reader = RandomAccessFile("/proc/stat", "r")
@@ -1,7 +1,14 @@
-use crate::{ConnectionType, ConnectivityInfo, SystemLoad, ThermalState};
-use jni::objects::{GlobalRef, JObject, JValue};
+use crate::{
+    BatteryInfo, ConnectionType, ConnectivityInfo, DeviceInfo, DiskInfo, OsInfo, SystemLoad,
+    ThermalState,
+};
+use jni::objects::{GlobalRef, JClass, JObject, JValue};
+use jni::sys::{jint, jlong};
 use jni::{JNIEnv, JavaVM};
-use std::sync::OnceLock;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
 
 static JAVA_VM: OnceLock<JavaVM> = OnceLock::new();
 static CONTEXT: OnceLock<GlobalRef> = OnceLock::new();
@@ -16,6 +23,27 @@ pub fn init(env: &mut JNIEnv, context: JObject) {
         .new_global_ref(context)
         .expect("Failed to create global ref");
     let _ = CONTEXT.set(global_ctx);
+
+    // Connectivity and thermal callbacks call back into Rust; without them
+    // the watch streams only yield the initial snapshot.
+    let _ = register_natives(env);
+}
+
+fn register_natives(env: &mut JNIEnv) -> jni::errors::Result<()> {
+    let class = env.find_class("com/waterkit/system/SystemHelper")?;
+    let native_methods = [
+        jni::NativeMethod {
+            name: "onConnectivityChanged".into(),
+            sig: "(JI)V".into(),
+            fn_ptr: Java_com_waterkit_system_SystemHelper_onConnectivityChanged as *mut _,
+        },
+        jni::NativeMethod {
+            name: "onThermalStatusChanged".into(),
+            sig: "(JI)V".into(),
+            fn_ptr: Java_com_waterkit_system_SystemHelper_onThermalStatusChanged as *mut _,
+        },
+    ];
+    env.register_native_methods(class, &native_methods)
 }
 
 fn with_jni<T, F>(f: F) -> Option<T>
@@ -45,7 +73,12 @@ pub fn get_connectivity_info() -> ConnectivityInfo {
         Some(result)
     });
 
-    let connection_type = match result.unwrap_or(0) {
+    to_connectivity_info(result.unwrap_or(0))
+}
+
+/// Map a `SystemHelper` connection code to `ConnectivityInfo`.
+const fn to_connectivity_info(code: i32) -> ConnectivityInfo {
+    let connection_type = match code {
         1 => ConnectionType::Wifi,
         2 => ConnectionType::Cellular,
         3 => ConnectionType::Ethernet,
@@ -57,7 +90,71 @@ pub fn get_connectivity_info() -> ConnectivityInfo {
 
     ConnectivityInfo {
         connection_type,
-        is_connected: !matches!(result.unwrap_or(0), 0),
+        is_connected: code != 0,
+    }
+}
+
+/// Senders for active connectivity watchers, keyed by watcher id.
+static CONNECTIVITY_SENDERS: Mutex<Option<HashMap<u64, async_channel::Sender<ConnectivityInfo>>>> =
+    Mutex::new(None);
+static NEXT_WATCHER_ID: AtomicU64 = AtomicU64::new(1);
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_waterkit_system_SystemHelper_onConnectivityChanged(
+    _env: JNIEnv,
+    _class: JClass,
+    watcher_id: jlong,
+    code: jint,
+) {
+    if let Ok(senders) = CONNECTIVITY_SENDERS.lock()
+        && let Some(sender) = senders.as_ref().and_then(|s| s.get(&(watcher_id as u64)))
+    {
+        let _ = sender.try_send(to_connectivity_info(code));
+    }
+}
+
+/// A registered `ConnectivityManager.NetworkCallback`, unregistered on drop.
+pub struct ConnectivityWatcher {
+    id: u64,
+}
+
+impl ConnectivityWatcher {
+    pub fn start(sender: async_channel::Sender<ConnectivityInfo>) -> Self {
+        let id = NEXT_WATCHER_ID.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut senders) = CONNECTIVITY_SENDERS.lock() {
+            senders.get_or_insert_with(HashMap::new).insert(id, sender);
+        }
+        let _ = with_jni(|env, ctx| {
+            let class = env.find_class("com/waterkit/system/SystemHelper").ok()?;
+            env.call_static_method(
+                class,
+                "startConnectivityWatch",
+                "(Landroid/content/Context;J)V",
+                &[JValue::Object(ctx), JValue::Long(id as jlong)],
+            )
+            .ok()
+        });
+        Self { id }
+    }
+}
+
+impl Drop for ConnectivityWatcher {
+    fn drop(&mut self) {
+        let _ = with_jni(|env, ctx| {
+            let class = env.find_class("com/waterkit/system/SystemHelper").ok()?;
+            env.call_static_method(
+                class,
+                "stopConnectivityWatch",
+                "(Landroid/content/Context;J)V",
+                &[JValue::Object(ctx), JValue::Long(self.id as jlong)],
+            )
+            .ok()
+        });
+        if let Ok(mut senders) = CONNECTIVITY_SENDERS.lock()
+            && let Some(senders) = senders.as_mut()
+        {
+            senders.remove(&self.id);
+        }
     }
 }
 
@@ -77,8 +174,12 @@ pub fn get_thermal_state() -> ThermalState {
         Some(result)
     });
 
+    to_thermal_state(result.unwrap_or(-1))
+}
+
+const fn to_thermal_state(status: i32) -> ThermalState {
     // Android thermal statuses map: 0=None, 1=Light, 2=Moderate, 3=Severe, 4=Critical, 5=Emergency, 6=Shutdown
-    match result.unwrap_or(-1) {
+    match status {
         0 => ThermalState::Nominal,
         1 => ThermalState::Fair,
         2 => ThermalState::Fair,
@@ -88,6 +189,95 @@ pub fn get_thermal_state() -> ThermalState {
     }
 }
 
+/// Senders for active thermal listeners, keyed by watcher id.
+static THERMAL_SENDERS: Mutex<Option<HashMap<u64, async_channel::Sender<ThermalState>>>> =
+    Mutex::new(None);
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_waterkit_system_SystemHelper_onThermalStatusChanged(
+    _env: JNIEnv,
+    _class: JClass,
+    watcher_id: jlong,
+    status: jint,
+) {
+    if let Ok(senders) = THERMAL_SENDERS.lock()
+        && let Some(sender) = senders.as_ref().and_then(|s| s.get(&(watcher_id as u64)))
+    {
+        let _ = sender.try_send(to_thermal_state(status));
+    }
+}
+
+/// A `PowerManager` thermal status listener (API 29+), or a poller on older
+/// versions. Stopped on drop.
+pub enum ThermalWatcher {
+    Listener(u64),
+    Polling(super::Poller),
+}
+
+impl ThermalWatcher {
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+    pub fn start(sender: async_channel::Sender<ThermalState>) -> Self {
+        let id = NEXT_WATCHER_ID.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut senders) = THERMAL_SENDERS.lock() {
+            senders
+                .get_or_insert_with(HashMap::new)
+                .insert(id, sender.clone());
+        }
+        let registered = with_jni(|env, ctx| {
+            let class = env.find_class("com/waterkit/system/SystemHelper").ok()?;
+            env.call_static_method(
+                class,
+                "startThermalWatch",
+                "(Landroid/content/Context;J)Z",
+                &[JValue::Object(ctx), JValue::Long(id as jlong)],
+            )
+            .ok()?
+            .z()
+            .ok()
+        })
+        .unwrap_or(false);
+
+        if registered {
+            Self::Listener(id)
+        } else {
+            if let Ok(mut senders) = THERMAL_SENDERS.lock()
+                && let Some(senders) = senders.as_mut()
+            {
+                senders.remove(&id);
+            }
+            Self::Polling(super::Poller::start(
+                Self::POLL_INTERVAL,
+                sender,
+                get_thermal_state,
+            ))
+        }
+    }
+}
+
+impl Drop for ThermalWatcher {
+    fn drop(&mut self) {
+        let Self::Listener(id) = *self else {
+            return;
+        };
+        let _ = with_jni(|env, ctx| {
+            let class = env.find_class("com/waterkit/system/SystemHelper").ok()?;
+            env.call_static_method(
+                class,
+                "stopThermalWatch",
+                "(Landroid/content/Context;J)V",
+                &[JValue::Object(ctx), JValue::Long(id as jlong)],
+            )
+            .ok()
+        });
+        if let Ok(mut senders) = THERMAL_SENDERS.lock()
+            && let Some(senders) = senders.as_mut()
+        {
+            senders.remove(&id);
+        }
+    }
+}
+
 pub fn get_system_load() -> SystemLoad {
     let result = with_jni(|env, ctx| {
         let class = env.find_class("com/waterkit/system/SystemHelper").ok()?;
@@ -123,6 +313,138 @@ pub fn get_system_load() -> SystemLoad {
     }
 }
 
+pub fn get_battery_info() -> BatteryInfo {
+    let result = with_jni(|env, ctx| {
+        let class = env.find_class("com/waterkit/system/SystemHelper").ok()?;
+        let info = env
+            .call_static_method(
+                class,
+                "getBatteryInfo",
+                "(Landroid/content/Context;)Lcom/waterkit/system/SystemHelper$BatteryInfo;",
+                &[JValue::Object(ctx)],
+            )
+            .ok()?
+            .l()
+            .ok()?;
+
+        let level = env.get_field(&info, "level", "F").ok()?.f().ok()?;
+        let is_charging = env.get_field(&info, "isCharging", "Z").ok()?.z().ok()?;
+        let is_low_power_mode = env.get_field(&info, "isLowPowerMode", "Z").ok()?.z().ok()?;
+
+        Some(BatteryInfo {
+            level,
+            is_charging,
+            is_low_power_mode,
+        })
+    });
+
+    result.unwrap_or(BatteryInfo {
+        level: -1.0,
+        is_charging: false,
+        is_low_power_mode: false,
+    })
+}
+
+/// Read `Build.VERSION.RELEASE` and `Build.ID`, which need no Context.
+pub fn os_info() -> OsInfo {
+    let read = |env: &mut JNIEnv, class: &str, field: &str| -> Option<String> {
+        let value = env
+            .get_static_field(class, field, "Ljava/lang/String;")
+            .ok()?
+            .l()
+            .ok()?;
+        if value.is_null() {
+            return None;
+        }
+        env.get_string((&value).into()).ok().map(Into::into)
+    };
+
+    let (version, build) = JAVA_VM
+        .get()
+        .and_then(|vm| vm.attach_current_thread().ok())
+        .map(|mut env| {
+            (
+                read(&mut env, "android/os/Build$VERSION", "RELEASE"),
+                read(&mut env, "android/os/Build", "ID"),
+            )
+        })
+        .unwrap_or_default();
+
+    OsInfo {
+        name: "Android".to_owned(),
+        version: version.unwrap_or_default(),
+        build,
+    }
+}
+
+pub fn device_info() -> DeviceInfo {
+    let result = with_jni(|env, ctx| {
+        let class = env.find_class("com/waterkit/system/SystemHelper").ok()?;
+        let info = env
+            .call_static_method(
+                class,
+                "getDeviceInfo",
+                "(Landroid/content/Context;)Lcom/waterkit/system/SystemHelper$DeviceInfo;",
+                &[JValue::Object(ctx)],
+            )
+            .ok()?
+            .l()
+            .ok()?;
+
+        let mut read = |field: &str| -> Option<String> {
+            let value = env
+                .get_field(&info, field, "Ljava/lang/String;")
+                .ok()?
+                .l()
+                .ok()?;
+            env.get_string((&value).into()).ok().map(Into::into)
+        };
+        Some(DeviceInfo {
+            model: read("model")?,
+            manufacturer: read("manufacturer")?,
+            name: read("name")?,
+        })
+    });
+
+    result.unwrap_or_default()
+}
+
+pub fn disk_info(path: &Path) -> Result<DiskInfo, std::io::Error> {
+    let vm = JAVA_VM
+        .get()
+        .ok_or_else(|| std::io::Error::other("waterkit-system is not initialized"))?;
+    let mut env = vm.attach_current_thread().map_err(std::io::Error::other)?;
+
+    let result = (|| -> jni::errors::Result<(i64, i64, i64)> {
+        let class = env.find_class("com/waterkit/system/SystemHelper")?;
+        let path = env.new_string(path.to_string_lossy())?;
+        let info = env
+            .call_static_method(
+                class,
+                "getDiskInfo",
+                "(Ljava/lang/String;)Lcom/waterkit/system/SystemHelper$DiskInfo;",
+                &[JValue::Object(&path)],
+            )?
+            .l()?;
+        let total = env.get_field(&info, "totalBytes", "J")?.j()?;
+        let available = env.get_field(&info, "availableBytes", "J")?.j()?;
+        let free = env.get_field(&info, "freeBytes", "J")?.j()?;
+        Ok((total, available, free))
+    })();
+
+    // StatFs throws for missing paths; clear it so the thread can keep using JNI
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+
+    let (total, available, free) = result.map_err(std::io::Error::other)?;
+    Ok(DiskInfo {
+        total_bytes: total as u64,
+        available_bytes: available as u64,
+        used_bytes: (total - free) as u64,
+    })
+}
+
 // JNI export for initialization from Java/Kotlin
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_com_waterkit_system_SystemBridge_nativeInit<'local>(
@@ -1,5 +1,10 @@
 import Foundation
 import Network
+#if os(iOS)
+import UIKit
+#else
+import IOKit.ps
+#endif
 
 public func get_apple_connectivity() -> RustConnectivityInfo {
     let monitor = NWPathMonitor()
@@ -18,7 +23,10 @@ public func get_apple_connectivity() -> RustConnectivityInfo {
     guard let p = path else {
         return RustConnectivityInfo(connection_type: .None, is_connected: false)
     }
+    return connectivityInfo(p)
+}
 
+private func connectivityInfo(_ p: NWPath) -> RustConnectivityInfo {
     if p.status != .satisfied {
         return RustConnectivityInfo(connection_type: .None, is_connected: false)
     }
@@ -35,8 +43,36 @@ public func get_apple_connectivity() -> RustConnectivityInfo {
     return RustConnectivityInfo(connection_type: type, is_connected: true)
 }
 
+// MARK: - Connectivity Monitoring
+
+private var connectivityMonitors: [UInt64: NWPathMonitor] = [:]
+private let connectivityMonitorsLock = NSLock()
+
+public func start_apple_connectivity_monitor(id: UInt64) {
+    let monitor = NWPathMonitor()
+    monitor.pathUpdateHandler = { p in
+        on_apple_connectivity_changed(id, connectivityInfo(p))
+    }
+
+    connectivityMonitorsLock.lock()
+    connectivityMonitors[id] = monitor
+    connectivityMonitorsLock.unlock()
+
+    monitor.start(queue: DispatchQueue(label: "waterkit.system.connectivity.\(id)"))
+}
+
+public func stop_apple_connectivity_monitor(id: UInt64) {
+    connectivityMonitorsLock.lock()
+    let monitor = connectivityMonitors.removeValue(forKey: id)
+    connectivityMonitorsLock.unlock()
+    monitor?.cancel()
+}
+
 public func get_apple_thermal_state() -> ThermalState {
-    let state = ProcessInfo.processInfo.thermalState
+    return rustThermalState(ProcessInfo.processInfo.thermalState)
+}
+
+private func rustThermalState(_ state: ProcessInfo.ThermalState) -> ThermalState {
     var rustState: ThermalState = .Nominal
     switch state {
     case .nominal: rustState = .Nominal
@@ -48,6 +84,35 @@ public func get_apple_thermal_state() -> ThermalState {
     return rustState
 }
 
+// MARK: - Thermal State Observation
+
+private var thermalObservers: [UInt64: NSObjectProtocol] = [:]
+private let thermalObserversLock = NSLock()
+
+public func start_apple_thermal_observer(id: UInt64) {
+    // Posted on an arbitrary thread; no run loop needed
+    let observer = NotificationCenter.default.addObserver(
+        forName: ProcessInfo.thermalStateDidChangeNotification,
+        object: nil,
+        queue: nil
+    ) { _ in
+        on_apple_thermal_state_changed(id, rustThermalState(ProcessInfo.processInfo.thermalState))
+    }
+
+    thermalObserversLock.lock()
+    thermalObservers[id] = observer
+    thermalObserversLock.unlock()
+}
+
+public func stop_apple_thermal_observer(id: UInt64) {
+    thermalObserversLock.lock()
+    let observer = thermalObservers.removeValue(forKey: id)
+    thermalObserversLock.unlock()
+    if let observer = observer {
+        NotificationCenter.default.removeObserver(observer)
+    }
+}
+
 public func get_apple_system_load() -> RustSystemLoad {
     let cpuUsage = getHostCPUUsage()
     let memTotal = ProcessInfo.processInfo.physicalMemory
@@ -56,6 +121,123 @@ public func get_apple_system_load() -> RustSystemLoad {
     return RustSystemLoad(cpu_usage: cpuUsage, memory_used: memUsed, memory_total: memTotal)
 }
 
+// MARK: - Battery
+
+public func get_apple_battery_info() -> RustBatteryInfo {
+    let lowPower: Bool
+    if #available(macOS 12.0, *) {
+        lowPower = ProcessInfo.processInfo.isLowPowerModeEnabled
+    } else {
+        lowPower = false
+    }
+
+    #if os(iOS)
+    let device = UIDevice.current
+    // Level and state read as unknown until monitoring is enabled
+    device.isBatteryMonitoringEnabled = true
+    return RustBatteryInfo(
+        level: device.batteryLevel,
+        is_charging: device.batteryState == .charging,
+        is_low_power_mode: lowPower
+    )
+    #else
+    return macBatteryInfo(lowPower: lowPower)
+    #endif
+}
+
+#if os(macOS)
+/// Read the internal battery from the IOKit power sources.
+private func macBatteryInfo(lowPower: Bool) -> RustBatteryInfo {
+    let noBattery = RustBatteryInfo(level: -1.0, is_charging: false, is_low_power_mode: lowPower)
+    guard let info = IOPSCopyPowerSourcesInfo()?.takeRetainedValue(),
+          let sources = IOPSCopyPowerSourcesList(info)?.takeRetainedValue() as? [CFTypeRef] else {
+        return noBattery
+    }
+
+    for source in sources {
+        guard let description = IOPSGetPowerSourceDescription(info, source)?.takeUnretainedValue() as? [String: Any],
+              description[kIOPSTypeKey] as? String == kIOPSInternalBatteryType,
+              let current = description[kIOPSCurrentCapacityKey] as? Int,
+              let max = description[kIOPSMaxCapacityKey] as? Int, max > 0 else {
+            continue
+        }
+        return RustBatteryInfo(
+            level: Float(current) / Float(max),
+            is_charging: description[kIOPSIsChargingKey] as? Bool ?? false,
+            is_low_power_mode: lowPower
+        )
+    }
+    return noBattery
+}
+#endif
+
+// MARK: - OS Version
+
+public func get_apple_os_info() -> RustOsInfo {
+    let version = ProcessInfo.processInfo.operatingSystemVersion
+    var versionString = "\(version.majorVersion).\(version.minorVersion)"
+    if version.patchVersion > 0 {
+        versionString += ".\(version.patchVersion)"
+    }
+
+    #if os(iOS)
+    let name = UIDevice.current.systemName
+    #else
+    let name = "macOS"
+    #endif
+
+    return RustOsInfo(
+        name: name.intoRustString(),
+        version: versionString.intoRustString(),
+        // e.g. "23C71"
+        build: sysctlString("kern.osversion").intoRustString()
+    )
+}
+
+// MARK: - Device Model
+
+public func get_apple_device_info() -> RustDeviceInfo {
+    #if os(iOS)
+    // The simulator reports the host architecture instead
+    let model = ProcessInfo.processInfo.environment["SIMULATOR_MODEL_IDENTIFIER"] ?? sysctlString("hw.machine")
+    // Generic ("iPhone") since iOS 16 without the user-assigned-device-name entitlement
+    let name = UIDevice.current.name
+    #else
+    // hw.machine is only the architecture on macOS
+    let model = sysctlString("hw.model")
+    let name = Host.current().localizedName ?? ProcessInfo.processInfo.hostName
+    #endif
+
+    return RustDeviceInfo(
+        model: model.intoRustString(),
+        name: name.intoRustString()
+    )
+}
+
+/// Read a string sysctl, or an empty string if it doesn't exist.
+private func sysctlString(_ key: String) -> String {
+    var size = 0
+    guard sysctlbyname(key, nil, &size, nil, 0) == 0, size > 0 else {
+        return ""
+    }
+    var buffer = [CChar](repeating: 0, count: size)
+    guard sysctlbyname(key, &buffer, &size, nil, 0) == 0 else {
+        return ""
+    }
+    return String(cString: buffer)
+}
+
+public func get_apple_disk_info(path: RustString) -> RustDiskInfo {
+    do {
+        let attributes = try FileManager.default.attributesOfFileSystem(forPath: path.toString())
+        let total = (attributes[.systemSize] as? NSNumber)?.uint64Value ?? 0
+        let free = (attributes[.systemFreeSize] as? NSNumber)?.uint64Value ?? 0
+        return RustDiskInfo(total_bytes: total, free_bytes: free, error: "".intoRustString())
+    } catch {
+        return RustDiskInfo(total_bytes: 0, free_bytes: 0, error: error.localizedDescription.intoRustString())
+    }
+}
+
 // MARK: - CPU Usage via host_processor_info
 
 private var previousCPUInfo: host_cpu_load_info?
@@ -1,4 +1,11 @@
-use crate::{ConnectionType, ConnectivityInfo, SystemLoad, ThermalState};
+use crate::{
+    BatteryInfo, ConnectionType, ConnectivityInfo, DeviceInfo, DiskInfo, OsInfo, SystemLoad,
+    ThermalState,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 #[swift_bridge::bridge]
 mod ffi {
@@ -35,17 +42,62 @@ mod ffi {
         pub memory_total: u64,
     }
 
+    #[swift_bridge(swift_repr = "struct")]
+    pub struct RustDiskInfo {
+        pub total_bytes: u64,
+        pub free_bytes: u64,
+        // Empty on success, otherwise the `FileManager` error description.
+        pub error: String,
+    }
+
+    #[swift_bridge(swift_repr = "struct")]
+    pub struct RustOsInfo {
+        pub name: String,
+        pub version: String,
+        // Empty if the build number can't be read.
+        pub build: String,
+    }
+
+    #[swift_bridge(swift_repr = "struct")]
+    pub struct RustDeviceInfo {
+        pub model: String,
+        pub name: String,
+    }
+
+    #[swift_bridge(swift_repr = "struct")]
+    pub struct RustBatteryInfo {
+        pub level: f32,
+        pub is_charging: bool,
+        pub is_low_power_mode: bool,
+    }
+
     extern "Swift" {
         fn get_apple_connectivity() -> RustConnectivityInfo;
         fn get_apple_thermal_state() -> ThermalState;
         fn get_apple_system_load() -> RustSystemLoad;
+        fn get_apple_battery_info() -> RustBatteryInfo;
+        fn get_apple_os_info() -> RustOsInfo;
+        fn get_apple_device_info() -> RustDeviceInfo;
+        fn get_apple_disk_info(path: String) -> RustDiskInfo;
+        fn start_apple_connectivity_monitor(id: u64);
+        fn stop_apple_connectivity_monitor(id: u64);
+        fn start_apple_thermal_observer(id: u64);
+        fn stop_apple_thermal_observer(id: u64);
+    }
+
+    extern "Rust" {
+        fn on_apple_connectivity_changed(id: u64, info: RustConnectivityInfo);
+        fn on_apple_thermal_state_changed(id: u64, state: ThermalState);
     }
 }
 
 // ... existing helpers ...
 
 pub fn get_connectivity_info() -> ConnectivityInfo {
-    let info = ffi::get_apple_connectivity();
+    to_connectivity_info(&ffi::get_apple_connectivity())
+}
+
+fn to_connectivity_info(info: &ffi::RustConnectivityInfo) -> ConnectivityInfo {
     let ct = match info.connection_type {
         ffi::ConnectionType::Wifi => ConnectionType::Wifi,
         ffi::ConnectionType::Cellular => ConnectionType::Cellular,
@@ -61,8 +113,52 @@ pub fn get_connectivity_info() -> ConnectivityInfo {
     }
 }
 
+/// Senders for active connectivity monitors, keyed by monitor id.
+static CONNECTIVITY_SENDERS: Mutex<Option<HashMap<u64, async_channel::Sender<ConnectivityInfo>>>> =
+    Mutex::new(None);
+static NEXT_MONITOR_ID: AtomicU64 = AtomicU64::new(1);
+
+fn on_apple_connectivity_changed(id: u64, info: ffi::RustConnectivityInfo) {
+    if let Ok(senders) = CONNECTIVITY_SENDERS.lock()
+        && let Some(sender) = senders.as_ref().and_then(|s| s.get(&id))
+    {
+        let _ = sender.try_send(to_connectivity_info(&info));
+    }
+}
+
+/// An `NWPathMonitor` forwarding path updates, cancelled on drop.
+pub struct ConnectivityWatcher {
+    id: u64,
+}
+
+impl ConnectivityWatcher {
+    pub fn start(sender: async_channel::Sender<ConnectivityInfo>) -> Self {
+        let id = NEXT_MONITOR_ID.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut senders) = CONNECTIVITY_SENDERS.lock() {
+            senders.get_or_insert_with(HashMap::new).insert(id, sender);
+        }
+        ffi::start_apple_connectivity_monitor(id);
+        Self { id }
+    }
+}
+
+impl Drop for ConnectivityWatcher {
+    fn drop(&mut self) {
+        ffi::stop_apple_connectivity_monitor(self.id);
+        if let Ok(mut senders) = CONNECTIVITY_SENDERS.lock()
+            && let Some(senders) = senders.as_mut()
+        {
+            senders.remove(&self.id);
+        }
+    }
+}
+
 pub fn get_thermal_state() -> ThermalState {
-    match ffi::get_apple_thermal_state() {
+    to_thermal_state(&ffi::get_apple_thermal_state())
+}
+
+const fn to_thermal_state(state: &ffi::ThermalState) -> ThermalState {
+    match state {
         ffi::ThermalState::Nominal => ThermalState::Nominal,
         ffi::ThermalState::Fair => ThermalState::Fair,
         ffi::ThermalState::Serious => ThermalState::Serious,
@@ -71,6 +167,45 @@ pub fn get_thermal_state() -> ThermalState {
     }
 }
 
+/// Senders for active thermal observers, keyed by observer id.
+static THERMAL_SENDERS: Mutex<Option<HashMap<u64, async_channel::Sender<ThermalState>>>> =
+    Mutex::new(None);
+
+fn on_apple_thermal_state_changed(id: u64, state: ffi::ThermalState) {
+    if let Ok(senders) = THERMAL_SENDERS.lock()
+        && let Some(sender) = senders.as_ref().and_then(|s| s.get(&id))
+    {
+        let _ = sender.try_send(to_thermal_state(&state));
+    }
+}
+
+/// A `thermalStateDidChangeNotification` observer, removed on drop.
+pub struct ThermalWatcher {
+    id: u64,
+}
+
+impl ThermalWatcher {
+    pub fn start(sender: async_channel::Sender<ThermalState>) -> Self {
+        let id = NEXT_MONITOR_ID.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut senders) = THERMAL_SENDERS.lock() {
+            senders.get_or_insert_with(HashMap::new).insert(id, sender);
+        }
+        ffi::start_apple_thermal_observer(id);
+        Self { id }
+    }
+}
+
+impl Drop for ThermalWatcher {
+    fn drop(&mut self) {
+        ffi::stop_apple_thermal_observer(self.id);
+        if let Ok(mut senders) = THERMAL_SENDERS.lock()
+            && let Some(senders) = senders.as_mut()
+        {
+            senders.remove(&self.id);
+        }
+    }
+}
+
 pub fn get_system_load() -> SystemLoad {
     let load = ffi::get_apple_system_load();
     SystemLoad {
@@ -79,3 +214,42 @@ pub fn get_system_load() -> SystemLoad {
         memory_total: load.memory_total,
     }
 }
+
+pub fn get_battery_info() -> BatteryInfo {
+    let info = ffi::get_apple_battery_info();
+    BatteryInfo {
+        level: info.level,
+        is_charging: info.is_charging,
+        is_low_power_mode: info.is_low_power_mode,
+    }
+}
+
+pub fn os_info() -> OsInfo {
+    let info = ffi::get_apple_os_info();
+    OsInfo {
+        name: info.name,
+        version: info.version,
+        build: Some(info.build).filter(|build| !build.is_empty()),
+    }
+}
+
+pub fn device_info() -> DeviceInfo {
+    let info = ffi::get_apple_device_info();
+    DeviceInfo {
+        model: info.model,
+        manufacturer: "Apple".to_owned(),
+        name: info.name,
+    }
+}
+
+pub fn disk_info(path: &Path) -> Result<DiskInfo, std::io::Error> {
+    let info = ffi::get_apple_disk_info(path.to_string_lossy().into_owned());
+    if !info.error.is_empty() {
+        return Err(std::io::Error::other(info.error));
+    }
+    Ok(DiskInfo {
+        total_bytes: info.total_bytes,
+        available_bytes: info.free_bytes,
+        used_bytes: info.total_bytes - info.free_bytes,
+    })
+}
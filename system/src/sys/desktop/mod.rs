@@ -1,4 +1,8 @@
-use crate::{ConnectionType, ConnectivityInfo, SystemLoad, ThermalState};
+use crate::{
+    BatteryInfo, ConnectionType, ConnectivityInfo, DeviceInfo, DiskInfo, OsInfo, SystemLoad,
+    ThermalState,
+};
+use std::path::Path;
 use sysinfo::{CpuRefreshKind, MemoryRefreshKind, Networks, RefreshKind, System};
 
 pub fn get_connectivity_info() -> ConnectivityInfo {
@@ -47,8 +51,176 @@ pub fn get_connectivity_info() -> ConnectivityInfo {
     }
 
     ConnectivityInfo {
-        connection_type,
         is_connected: has_connection && connection_type != ConnectionType::None,
+        connection_type,
+    }
+}
+
+/// A netlink route socket watcher, stopped on drop.
+#[cfg(target_os = "linux")]
+pub struct ConnectivityWatcher {
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(target_os = "linux")]
+impl ConnectivityWatcher {
+    pub fn start(sender: async_channel::Sender<ConnectivityInfo>) -> Self {
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        // Without a netlink socket the stream only yields the initial snapshot
+        let thread = open_route_socket().ok().map(|socket| {
+            let stop = stop.clone();
+            std::thread::spawn(move || watch_route_socket(&socket, &sender, &stop))
+        });
+        Self { stop, thread }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for ConnectivityWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Open a netlink socket subscribed to link, address and route changes.
+#[cfg(target_os = "linux")]
+fn open_route_socket() -> Result<std::os::fd::OwnedFd, std::io::Error> {
+    use std::os::fd::FromRawFd;
+
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+            libc::NETLINK_ROUTE,
+        )
+    };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let socket = unsafe { std::os::fd::OwnedFd::from_raw_fd(fd) };
+
+    let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    addr.nl_groups = (libc::RTMGRP_LINK
+        | libc::RTMGRP_IPV4_IFADDR
+        | libc::RTMGRP_IPV6_IFADDR
+        | libc::RTMGRP_IPV4_ROUTE
+        | libc::RTMGRP_IPV6_ROUTE) as u32;
+
+    let result = unsafe {
+        libc::bind(
+            fd,
+            (&raw const addr).cast::<libc::sockaddr>(),
+            std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(socket)
+}
+
+#[cfg(target_os = "linux")]
+fn watch_route_socket(
+    socket: &std::os::fd::OwnedFd,
+    sender: &async_channel::Sender<ConnectivityInfo>,
+    stop: &std::sync::atomic::AtomicBool,
+) {
+    use std::os::fd::AsRawFd;
+
+    let mut buffer = [0u8; 8192];
+    while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+        let mut pollfd = libc::pollfd {
+            fd: socket.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // Wake up periodically to notice the stop flag
+        let ready = unsafe { libc::poll(&raw mut pollfd, 1, 250) };
+        if ready < 0 {
+            if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            break;
+        }
+        if ready == 0 {
+            continue;
+        }
+
+        // Drain the queued events; one snapshot covers the whole burst
+        while unsafe {
+            libc::recv(
+                socket.as_raw_fd(),
+                buffer.as_mut_ptr().cast(),
+                buffer.len(),
+                libc::MSG_DONTWAIT,
+            )
+        } > 0
+        {}
+
+        if sender.try_send(get_connectivity_info()).is_err() {
+            break;
+        }
+    }
+}
+
+/// A `NetworkStatusChanged` subscription, removed on drop.
+#[cfg(windows)]
+pub struct ConnectivityWatcher {
+    token: Option<i64>,
+}
+
+#[cfg(windows)]
+impl ConnectivityWatcher {
+    pub fn start(sender: async_channel::Sender<ConnectivityInfo>) -> Self {
+        use windows::Networking::Connectivity::{
+            NetworkInformation, NetworkStatusChangedEventHandler,
+        };
+
+        let token = NetworkInformation::NetworkStatusChanged(
+            &NetworkStatusChangedEventHandler::new(move |_| {
+                let _ = sender.try_send(get_connectivity_info());
+                Ok(())
+            }),
+        )
+        .ok();
+        Self { token }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ConnectivityWatcher {
+    fn drop(&mut self) {
+        use windows::Networking::Connectivity::NetworkInformation;
+
+        if let Some(token) = self.token.take() {
+            let _ = NetworkInformation::RemoveNetworkStatusChanged(token);
+        }
+    }
+}
+
+/// Polls component temperatures every [`INTERVAL`](Self::INTERVAL).
+///
+/// Nothing can be watched instead. On Linux, `thermal_zone*/temp` and hwmon
+/// `temp*_input` are sysfs attributes that the kernel computes on each read.
+/// Their drivers never call `sysfs_notify`, so neither inotify nor `poll()`
+/// reports a change. The WMI temperature sensors on Windows have no change
+/// event either.
+pub struct ThermalWatcher {
+    _poller: super::Poller,
+}
+
+impl ThermalWatcher {
+    const INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+    pub fn start(sender: async_channel::Sender<ThermalState>) -> Self {
+        Self {
+            _poller: super::Poller::start(Self::INTERVAL, sender, get_thermal_state),
+        }
     }
 }
 
@@ -57,13 +229,10 @@ pub fn get_thermal_state() -> ThermalState {
     let components = Components::new_with_refreshed_list();
 
     // Very simple heuristic: check max component temp
-    let mut max_temp = 0.0f32;
-    for component in &components {
-        let temp = component.temperature();
-        if temp > max_temp {
-            max_temp = temp;
-        }
-    }
+    let max_temp = components
+        .iter()
+        .filter_map(sysinfo::Component::temperature)
+        .fold(0.0f32, f32::max);
 
     if max_temp > 90.0 {
         ThermalState::Critical
@@ -78,7 +247,7 @@ pub fn get_thermal_state() -> ThermalState {
 
 pub fn get_system_load() -> SystemLoad {
     let mut system = System::new_with_specifics(
-        RefreshKind::new()
+        RefreshKind::nothing()
             .with_cpu(CpuRefreshKind::everything())
             .with_memory(MemoryRefreshKind::everything()),
     );
@@ -87,11 +256,11 @@ pub fn get_system_load() -> SystemLoad {
     // For a oneshot call, this might return 0.0 for CPU.
     // A proper implementation might need a background thread or stateful object.
     // For simplicity here, we'll just read what we can.
-    std::thread::sleep(System::MINIMUM_CPU_UPDATE_INTERVAL);
-    system.refresh_cpu();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_cpu_usage();
     system.refresh_memory();
 
-    let cpu_usage = system.global_cpu_info().cpu_usage();
+    let cpu_usage = system.global_cpu_usage();
     let memory_used = system.used_memory();
     let memory_total = system.total_memory();
 
@@ -101,3 +270,204 @@ pub fn get_system_load() -> SystemLoad {
         memory_total,
     }
 }
+
+/// Uses the distribution name and release on Linux.
+pub fn os_info() -> OsInfo {
+    let fallback = if cfg!(windows) { "Windows" } else { "Linux" };
+    OsInfo {
+        name: System::name().unwrap_or_else(|| fallback.to_owned()),
+        version: System::os_version().unwrap_or_default(),
+        build: System::kernel_version(),
+    }
+}
+
+/// Read the model and vendor the firmware reports through DMI.
+#[cfg(target_os = "linux")]
+pub fn device_info() -> DeviceInfo {
+    let read = |name: &str| {
+        std::fs::read_to_string(Path::new("/sys/class/dmi/id").join(name))
+            .map(|s| s.trim().to_owned())
+            .unwrap_or_default()
+    };
+    DeviceInfo {
+        model: read("product_name"),
+        manufacturer: read("sys_vendor"),
+        name: System::host_name().unwrap_or_default(),
+    }
+}
+
+/// Read the model and vendor the firmware reports through the registry.
+#[cfg(windows)]
+pub fn device_info() -> DeviceInfo {
+    use windows::core::w;
+
+    DeviceInfo {
+        model: bios_string(w!("SystemProductName")).unwrap_or_default(),
+        manufacturer: bios_string(w!("SystemManufacturer")).unwrap_or_default(),
+        name: System::host_name().unwrap_or_default(),
+    }
+}
+
+#[cfg(windows)]
+fn bios_string(value: windows::core::PCWSTR) -> Option<String> {
+    use windows::Win32::System::Registry::{HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ, RegGetValueW};
+    use windows::core::w;
+
+    let key = w!("HARDWARE\\DESCRIPTION\\System\\BIOS");
+    let mut size = 0u32;
+    unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            key,
+            value,
+            RRF_RT_REG_SZ,
+            None,
+            None,
+            Some(&raw mut size),
+        )
+    }
+    .ok()
+    .ok()?;
+
+    let mut buffer = vec![0u16; size as usize / 2];
+    unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            key,
+            value,
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&raw mut size),
+        )
+    }
+    .ok()
+    .ok()?;
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+/// Reported when there is no battery or its level can't be read.
+const NO_BATTERY: BatteryInfo = BatteryInfo {
+    level: -1.0,
+    is_charging: false,
+    is_low_power_mode: false,
+};
+
+/// Read the batteries listed under `/sys/class/power_supply`.
+///
+/// With several batteries the level is their average. Low power mode
+/// follows the ACPI platform profile.
+#[cfg(target_os = "linux")]
+pub fn get_battery_info() -> BatteryInfo {
+    let read = |path: &Path| std::fs::read_to_string(path).map(|s| s.trim().to_owned());
+
+    let mut levels = Vec::new();
+    let mut is_charging = false;
+    if let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") {
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            if read(&dir.join("type")).is_ok_and(|kind| kind == "Battery")
+                && let Some(capacity) = read(&dir.join("capacity"))
+                    .ok()
+                    .and_then(|c| c.parse::<f32>().ok())
+            {
+                levels.push((capacity / 100.0).clamp(0.0, 1.0));
+                is_charging |= read(&dir.join("status")).is_ok_and(|status| status == "Charging");
+            }
+        }
+    }
+
+    let is_low_power_mode = read(Path::new("/sys/firmware/acpi/platform_profile"))
+        .is_ok_and(|profile| profile == "low-power");
+    if levels.is_empty() {
+        return BatteryInfo {
+            is_low_power_mode,
+            ..NO_BATTERY
+        };
+    }
+    #[allow(clippy::cast_precision_loss)] // a handful of batteries
+    let level = levels.iter().sum::<f32>() / levels.len() as f32;
+    BatteryInfo {
+        level,
+        is_charging,
+        is_low_power_mode,
+    }
+}
+
+#[cfg(windows)]
+pub fn get_battery_info() -> BatteryInfo {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    /// `BatteryFlag` bits
+    const CHARGING: u8 = 8;
+    const NO_SYSTEM_BATTERY: u8 = 128;
+    const UNKNOWN_STATUS: u8 = 255;
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    if unsafe { GetSystemPowerStatus(&raw mut status) }.is_err() {
+        return NO_BATTERY;
+    }
+
+    // SystemStatusFlag is 1 while Battery Saver is on
+    let is_low_power_mode = status.SystemStatusFlag == 1;
+    if status.BatteryFlag == UNKNOWN_STATUS
+        || status.BatteryFlag & NO_SYSTEM_BATTERY != 0
+        || status.BatteryLifePercent > 100
+    {
+        return BatteryInfo {
+            is_low_power_mode,
+            ..NO_BATTERY
+        };
+    }
+    BatteryInfo {
+        level: f32::from(status.BatteryLifePercent) / 100.0,
+        is_charging: status.BatteryFlag & CHARGING != 0,
+        is_low_power_mode,
+    }
+}
+
+#[cfg(unix)]
+#[allow(clippy::useless_conversion)] // statvfs field widths vary by target
+pub fn disk_info(path: &Path) -> Result<DiskInfo, std::io::Error> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    if unsafe { libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let stat = unsafe { stat.assume_init() };
+
+    let block_size = u64::from(stat.f_frsize);
+    let total_bytes = u64::from(stat.f_blocks) * block_size;
+    let free_bytes = u64::from(stat.f_bfree) * block_size;
+    Ok(DiskInfo {
+        total_bytes,
+        available_bytes: u64::from(stat.f_bavail) * block_size,
+        used_bytes: total_bytes - free_bytes,
+    })
+}
+
+#[cfg(windows)]
+pub fn disk_info(path: &Path) -> Result<DiskInfo, std::io::Error> {
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+    use windows::core::HSTRING;
+
+    let mut available_bytes = 0u64;
+    let mut total_bytes = 0u64;
+    let mut free_bytes = 0u64;
+    unsafe {
+        GetDiskFreeSpaceExW(
+            &HSTRING::from(path.as_os_str()),
+            Some(&raw mut available_bytes),
+            Some(&raw mut total_bytes),
+            Some(&raw mut free_bytes),
+        )?;
+    }
+    Ok(DiskInfo {
+        total_bytes,
+        available_bytes,
+        used_bytes: total_bytes - free_bytes,
+    })
+}
@@ -12,3 +12,57 @@ pub use android::*;
 mod desktop;
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 pub use desktop::*;
+
+/// Reads a value on a background thread at a fixed interval until dropped.
+///
+/// Used where the platform offers no change notification.
+#[cfg(any(target_os = "android", target_os = "windows", target_os = "linux"))]
+pub struct Poller {
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(any(target_os = "android", target_os = "windows", target_os = "linux"))]
+impl Poller {
+    /// Granularity at which the thread notices it should stop.
+    const STOP_CHECK: std::time::Duration = std::time::Duration::from_millis(100);
+
+    pub fn start<T: Send + 'static>(
+        interval: std::time::Duration,
+        sender: async_channel::Sender<T>,
+        read: impl Fn() -> T + Send + 'static,
+    ) -> Self {
+        use std::sync::atomic::Ordering;
+
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let thread = {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    if sender.try_send(read()).is_err() {
+                        break;
+                    }
+                    let mut waited = std::time::Duration::ZERO;
+                    while waited < interval && !stop.load(Ordering::Relaxed) {
+                        std::thread::sleep(Self::STOP_CHECK);
+                        waited += Self::STOP_CHECK;
+                    }
+                }
+            })
+        };
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "windows", target_os = "linux"))]
+impl Drop for Poller {
+    fn drop(&mut self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -17,7 +17,19 @@ fn run_tests() {
         #[cfg(feature = "sensor")]
         {
             println!("Testing waterkit-sensor...");
-            // Add sensor test calls here if API is available for iOS
+            use waterkit_sensor::Accelerometer;
+            match Accelerometer::read().await {
+                Ok(data) => println!("Accelerometer: x={}, y={}, z={}", data.x, data.y, data.z),
+                Err(e) => println!("Accelerometer FAILED: {:?}", e),
+            }
+            match Accelerometer::watch(20) {
+                Ok(stream) => {
+                    use futures::StreamExt;
+                    let samples: Vec<_> = stream.take(10).collect().await;
+                    println!("Accelerometer: watch delivered {} samples", samples.len());
+                }
+                Err(e) => println!("Accelerometer watch FAILED: {:?}", e),
+            }
         }
 
         #[cfg(feature = "biometric")]
@@ -115,8 +127,8 @@ fn run_tests() {
         #[cfg(feature = "system")]
         {
             println!("Testing waterkit-system...");
-            // println!("System OS: {}", waterkit_system::os_name());
-            println!("System OS: Unknown (API not yet exposed)");
+            let os = waterkit_system::os_info();
+            println!("System OS: {} {}", os.name, os.version);
         }
 
         #[cfg(feature = "video")]
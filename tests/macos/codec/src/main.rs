@@ -8,7 +8,7 @@
 
 use std::sync::Arc;
 use std::time::Instant;
-use waterkit_codec::{CodecType, Frame, PixelFormat, VideoEncoder};
+use waterkit_codec::{CodecType, EncoderConfig, Frame, PixelFormat, VideoEncoder};
 
 fn create_test_frame(width: u32, height: u32) -> Frame {
     // Create a dummy RGBA frame for testing
@@ -109,7 +109,12 @@ fn main() {
         #[cfg(target_vendor = "apple")]
         {
             println!("\n--- Hardware H.264 (VideoToolbox) ---");
-            match waterkit_codec::sys::AppleEncoder::new(CodecType::H264) {
+            match waterkit_codec::sys::AppleEncoder::with_config(
+                CodecType::H264,
+                1920,
+                1080,
+                EncoderConfig::default(),
+            ) {
                 Ok(mut encoder) => {
                     results.push(benchmark_encoder(
                         "H.264 VT (1080p)",
@@ -200,10 +205,11 @@ fn main() {
     #[cfg(target_vendor = "apple")]
     {
         println!("\n--- Hardware H.264 (VideoToolbox) on 4K ---");
-        match waterkit_codec::sys::AppleEncoder::with_size(
+        match waterkit_codec::sys::AppleEncoder::with_config(
             CodecType::H264,
             screen_frame.width,
             screen_frame.height,
+            EncoderConfig::default(),
         ) {
             Ok(mut encoder) => {
                 results.push(benchmark_encoder(
@@ -217,10 +223,11 @@ fn main() {
         }
 
         println!("\n--- Hardware H.265 (VideoToolbox) on 4K ---");
-        match waterkit_codec::sys::AppleEncoder::with_size(
+        match waterkit_codec::sys::AppleEncoder::with_config(
             CodecType::H265,
             screen_frame.width,
             screen_frame.height,
+            EncoderConfig::default(),
         ) {
             Ok(mut encoder) => {
                 results.push(benchmark_encoder(
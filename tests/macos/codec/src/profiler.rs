@@ -4,7 +4,7 @@
 
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use waterkit_codec::{CodecType, Frame, PixelFormat, VideoEncoder};
+use waterkit_codec::{CodecType, EncoderConfig, Frame, PixelFormat, VideoEncoder};
 
 const ITERATIONS: usize = 100;
 
@@ -68,8 +68,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("=== Test 3: ScreenCapturer + H.265 Encode ===");
     {
         let capturer = waterkit_screen::ScreenCapturer::new(0)?;
-        let mut encoder =
-            waterkit_codec::sys::AppleEncoder::with_size(CodecType::H265, width, height)?;
+        let mut encoder = waterkit_codec::sys::AppleEncoder::with_config(
+            CodecType::H265,
+            width,
+            height,
+            EncoderConfig::default(),
+        )?;
 
         let start = Instant::now();
         let mut total_capture = Duration::ZERO;
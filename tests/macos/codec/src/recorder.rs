@@ -12,7 +12,7 @@ use std::sync::Arc;
 use std::sync::mpsc::{self, Receiver};
 use std::thread;
 use std::time::{Duration, Instant};
-use waterkit_codec::{CodecType, Frame, PixelFormat, VideoEncoder};
+use waterkit_codec::{CodecType, EncoderConfig, Frame, PixelFormat, VideoEncoder};
 
 const TARGET_FPS: f64 = 30.0;
 const FRAME_INTERVAL: Duration = Duration::from_nanos((1_000_000_000.0 / TARGET_FPS) as u64);
@@ -199,8 +199,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Create encoder
     println!("Creating H.265 encoder...");
-    let mut encoder = waterkit_codec::sys::AppleEncoder::with_size(CodecType::H265, width, height)
-        .map_err(|e| format!("Failed to create encoder: {:?}", e))?;
+    let mut encoder = waterkit_codec::sys::AppleEncoder::with_config(
+        CodecType::H265,
+        width,
+        height,
+        EncoderConfig::default(),
+    )
+    .map_err(|e| format!("Failed to create encoder: {:?}", e))?;
     println!("Encoder ready!");
 
     // Create output file
@@ -278,6 +283,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Wait for capture thread
     let _ = capture_handle.join();
 
+    // Drain frames still inside the encoder
+    for data in encoder.flush()? {
+        output_file.write_all(&data)?;
+        stats.total_bytes += data.len();
+        stats.successful_frames += 1;
+    }
+
     let total_elapsed = start_time.elapsed();
     println!();
 
@@ -0,0 +1,45 @@
+//! macOS test for the waterkit-notification Dock badge.
+//!
+//! Checks that `set_badge(0)` clears the badge the same way `clear_badge`
+//! does, by reading the Dock tile label back.
+//!
+//! Run with: cargo run -p waterkit-notification-test
+
+#[cfg(target_os = "macos")]
+fn main() {
+    use objc2::MainThreadMarker;
+
+    let mtm = MainThreadMarker::new().expect("must run on the main thread");
+
+    waterkit_notification::set_badge(3).expect("set_badge(3)");
+    assert_eq!(dock_badge(mtm).as_deref(), Some("3"));
+
+    waterkit_notification::set_badge(0).expect("set_badge(0)");
+    let after_zero = dock_badge(mtm);
+
+    waterkit_notification::set_badge(3).expect("set_badge(3)");
+    waterkit_notification::clear_badge().expect("clear_badge");
+    let after_clear = dock_badge(mtm);
+
+    assert_eq!(after_zero, None, "set_badge(0) left a badge");
+    assert_eq!(after_zero, after_clear);
+    println!("✓ set_badge(0) and clear_badge both remove the Dock badge");
+}
+
+/// The Dock tile label, once the badge update queued on the main thread ran.
+#[cfg(target_os = "macos")]
+fn dock_badge(mtm: objc2::MainThreadMarker) -> Option<String> {
+    use objc2_app_kit::NSApplication;
+    use objc2_foundation::{NSDate, NSRunLoop};
+
+    NSRunLoop::currentRunLoop().runUntilDate(&NSDate::dateWithTimeIntervalSinceNow(0.2));
+    NSApplication::sharedApplication(mtm)
+        .dockTile()
+        .badgeLabel()
+        .map(|label| label.to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn main() {
+    println!("The Dock badge only exists on macOS");
+}
@@ -11,8 +11,8 @@ use objc::runtime::Object;
 use objc::{msg_send, sel, sel_impl};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use waterkit_codec::CodecType;
 use waterkit_codec::sys::{AppleDecoder, AppleEncoder, IOSurfaceFrame};
+use waterkit_codec::{CodecType, EncoderConfig, VideoEncoder};
 use waterkit_screen::SCKCapturer;
 use waterkit_video::{VideoReader, VideoWriter};
 use winit::application::ApplicationHandler;
@@ -63,13 +63,14 @@ fn record_screen(output_path: &str, duration_secs: u64) {
     println!("Capture dimensions: {}x{}", width, height);
 
     // Initialize H.265 encoder
-    let mut encoder = match AppleEncoder::with_size(CodecType::H265, width, height) {
-        Ok(e) => e,
-        Err(e) => {
-            eprintln!("Failed to create encoder: {:?}", e);
-            return;
-        }
-    };
+    let mut encoder =
+        match AppleEncoder::with_config(CodecType::H265, width, height, EncoderConfig::default()) {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("Failed to create encoder: {:?}", e);
+                return;
+            }
+        };
 
     // Initialize video writer
     let mut writer = match VideoWriter::new(
@@ -133,7 +134,18 @@ fn record_screen(output_path: &str, duration_secs: u64) {
         }
     }
 
-    // Finish
+    // Drain frames still inside the encoder, then finish
+    match encoder.flush() {
+        Ok(packets) => {
+            for packet in packets {
+                if let Err(e) = writer.write_sample(&packet, false) {
+                    eprintln!("Failed to write sample: {:?}", e);
+                }
+            }
+        }
+        Err(e) => eprintln!("Flush error: {:?}", e),
+    }
+
     if let Err(e) = writer.finish() {
         eprintln!("Failed to finish video: {:?}", e);
     }
@@ -491,7 +503,7 @@ impl ApplicationHandler for VideoPlayer {
                     if should_decode && self.decoder.is_some() {
                         let decoder = self.decoder.as_mut().unwrap();
                         // Read sample
-                        if let Some((sample_data, pts, _key)) = self.reader.read_sample() {
+                        if let Some(Ok(sample)) = self.reader.next() {
                             self.last_frame_time = Some(Instant::now());
                             self.frame_count += 1;
                             if self.frame_count.is_multiple_of(30) {
@@ -499,8 +511,8 @@ impl ApplicationHandler for VideoPlayer {
                             }
 
                             // Decode - frames returned from previous callback (IOSurface zero-copy)
-                            let timescale = self.reader.timescale();
-                            match decoder.decode_surface(&sample_data, pts, timescale) {
+                            match decoder.decode_surface(&sample.data, sample.pts_ns, 1_000_000_000)
+                            {
                                 Ok(mut frames) => {
                                     if self.frame_count.is_multiple_of(30) {
                                         println!(
@@ -0,0 +1,74 @@
+//! AAC framing shared by the muxer and demuxer.
+//!
+//! MP4 stores bare AAC frames and describes the stream once, in the
+//! `AudioSpecificConfig` of the track. Encoders such as `waterkit-codec`'s
+//! `AacEncoder` produce ADTS frames instead, which carry the same description
+//! in a header on every frame.
+
+/// PCM frames (samples per channel) in one AAC frame.
+pub const FRAME_LEN: u32 = 1024;
+
+/// Sample rates by their MPEG-4 sampling frequency index.
+const SAMPLE_RATES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// MPEG-4 audio object type of AAC-LC.
+const AAC_LC: u8 = 2;
+
+/// Length of an ADTS header without CRC.
+const ADTS_HEADER_LEN: usize = 7;
+
+/// The MPEG-4 sampling frequency index of `sample_rate`.
+pub fn sample_rate_index(sample_rate: u32) -> Option<u8> {
+    SAMPLE_RATES
+        .iter()
+        .position(|&rate| rate == sample_rate)
+        .and_then(|index| u8::try_from(index).ok())
+}
+
+/// The two-byte `AudioSpecificConfig` of an AAC-LC stream.
+pub const fn audio_specific_config(rate_index: u8, channels: u8) -> [u8; 2] {
+    [
+        (AAC_LC << 3) | (rate_index >> 1),
+        ((rate_index & 1) << 7) | (channels << 3),
+    ]
+}
+
+/// The frame without its ADTS header, or unchanged if it has none.
+///
+/// Bare AAC frames start with a syntax element ID below 0xFF, so they can't
+/// be mistaken for a header.
+pub fn strip_adts(frame: &[u8]) -> &[u8] {
+    match frame {
+        [0xFF, second, ..] if second & 0xF6 == 0xF0 => {
+            // The CRC follows the header when protection is on
+            let header_len = if second & 1 == 0 {
+                ADTS_HEADER_LEN + 2
+            } else {
+                ADTS_HEADER_LEN
+            };
+            frame.get(header_len..).unwrap_or_default()
+        }
+        _ => frame,
+    }
+}
+
+/// Prefix a bare AAC-LC frame with its ADTS header.
+pub fn adts_frame(rate_index: u8, channels: u8, payload: &[u8]) -> Vec<u8> {
+    let len = ADTS_HEADER_LEN + payload.len();
+    let mut frame = Vec::with_capacity(len);
+    // MPEG-4 without CRC; the buffer fullness of 0x7FF marks VBR
+    #[allow(clippy::cast_possible_truncation)] // 13-bit frame length
+    frame.extend_from_slice(&[
+        0xFF,
+        0xF1,
+        ((AAC_LC - 1) << 6) | (rate_index << 2) | (channels >> 2),
+        ((channels & 3) << 6) | (len >> 11) as u8,
+        (len >> 3) as u8,
+        ((len & 7) << 5) as u8 | 0x1F,
+        0xFC,
+    ]);
+    frame.extend_from_slice(payload);
+    frame
+}
@@ -1,9 +1,10 @@
 //! Video demuxer and frame representation.
 
-use crate::VideoError;
+use crate::{AudioCodecType, CodecType, VideoError, aac, sys, webm};
 use mp4::WriteBox;
-use std::io::{Cursor, Read};
-use std::path::Path;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// A decoded video frame.
 #[derive(Clone)]
@@ -73,25 +74,116 @@ impl std::fmt::Debug for VideoFrame {
     }
 }
 
-/// Video reader for MP4/MOV files.
+/// An encoded video frame, as stored in the container.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncodedFrame {
+    /// Encoded data: length-prefixed NAL units for H.264/H.265, a VP8/VP9
+    /// frame, or an AV1 temporal unit.
+    pub data: Vec<u8>,
+    /// Presentation timestamp in nanoseconds. Frames are yielded in decoding
+    /// order, so with B-frames this doesn't always increase.
+    pub pts_ns: u64,
+    /// Time until the next frame in decoding order, in nanoseconds.
+    pub duration_ns: u64,
+    /// Whether the frame can be decoded without the ones before it.
+    pub is_keyframe: bool,
+    /// ID of the video track in the container.
+    pub track_id: u32,
+}
+
+impl std::fmt::Debug for EncodedFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncodedFrame")
+            .field("pts_ns", &self.pts_ns)
+            .field("duration_ns", &self.duration_ns)
+            .field("is_keyframe", &self.is_keyframe)
+            .field("track_id", &self.track_id)
+            .field("data_len", &self.data.len())
+            .finish()
+    }
+}
+
+/// Description of the audio track of a video file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioTrackInfo {
+    /// Audio codec.
+    pub codec: AudioCodecType,
+    /// Sample rate in Hz.
+    pub sample_rate: u32,
+    /// Number of channels.
+    pub channels: u8,
+    /// Number of encoded samples (frames) in the track.
+    pub sample_count: u32,
+}
+
+/// Image format of a [`VideoReader::thumbnail`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThumbnailFormat {
+    /// JPEG with a quality from 1 (smallest) to 100 (best).
+    Jpeg(u8),
+    /// PNG.
+    Png,
+}
+
+/// Encoded samples of a track as (data, pts, `is_keyframe`).
+type Samples = Vec<(Vec<u8>, u64, bool)>;
+
+/// An encoded video sample, with timestamps in timescale units.
+#[derive(Debug, Clone)]
+struct Sample {
+    data: Vec<u8>,
+    /// Presentation timestamp.
+    pts: u64,
+    /// Time until the next sample in decoding order.
+    duration: u64,
+    is_keyframe: bool,
+}
+
+/// Video reader for MP4/MOV and `WebM` files.
+///
+/// The reader is an iterator over the encoded frames of the video track:
+///
+/// ```ignore
+/// let mut reader = VideoReader::open("video.mp4")?;
+/// for frame in &mut reader {
+///     let frame = frame?;
+///     println!("{} bytes at {}ns", frame.data.len(), frame.pts_ns);
+/// }
+/// reader.reset(); // play it again
+/// ```
 #[derive(Debug)]
 pub struct VideoReader {
+    path: PathBuf,
+    codec: Option<CodecType>,
+    track_id: u32,
     width: u32,
     height: u32,
-    samples: Vec<(Vec<u8>, u64, bool)>, // (data, pts, is_keyframe)
+    samples: Vec<Sample>,
     codec_config: Option<Vec<u8>>,
     current_index: usize,
     timescale: u32,
+    audio: Option<AudioTrackInfo>,
+    audio_samples: Samples, // pts in nanoseconds
+    audio_index: usize,
 }
 
 impl VideoReader {
     /// Open a video file for reading.
     ///
+    /// The container is detected from the file's signature. `WebM` files are
+    /// read with their VP8, VP9 or AV1 video track and without audio.
+    ///
     /// # Errors
-    /// Returns [`VideoError::Io`] if the file cannot be opened.
+    /// Returns [`VideoError::Io`] if the file cannot be opened, and
+    /// [`VideoError::NotSupported`] for a `WebM` file with another codec.
     #[allow(clippy::cast_possible_truncation)]
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, VideoError> {
-        let file = std::fs::File::open(path.as_ref())?;
+        let mut file = std::fs::File::open(path.as_ref())?;
+        let mut signature = [0u8; 4];
+        if file.read_exact(&mut signature).is_ok() && signature == webm::SIGNATURE {
+            return Self::open_webm(path.as_ref());
+        }
+        file.rewind()?;
         let size = file.metadata()?.len();
         let reader = mp4::Mp4Reader::read_header(std::io::BufReader::new(file), size)
             .map_err(|e| VideoError::Container(e.to_string()))?;
@@ -102,6 +194,7 @@ impl VideoReader {
         let mut height = 0u32;
         let mut sample_count = 0u32;
         let mut codec_config: Option<Vec<u8>> = None;
+        let mut codec = None;
         let mut timescale = 0u32;
 
         for track in reader.tracks().values() {
@@ -120,6 +213,7 @@ impl VideoReader {
                 // Check for HEVC (hev1) - mp4 crate's HvcCBox is broken (discards all data)
                 // We must read raw hvcC bytes directly from the file
                 if stsd.hev1.is_some() {
+                    codec = Some(CodecType::H265);
                     // Read raw hvcC by scanning file for the atom
                     let mut file = std::fs::File::open(&path)?;
                     let mut buf = vec![0u8; file.metadata()?.len() as usize];
@@ -145,6 +239,7 @@ impl VideoReader {
                 }
                 // Check for AVC (avc1)
                 else if let Some(avc1) = &stsd.avc1 {
+                    codec = Some(CodecType::H264);
                     let avcc = &avc1.avcc;
                     let mut buf = Vec::new();
                     let mut cursor = Cursor::new(&mut buf);
@@ -165,20 +260,80 @@ impl VideoReader {
         let mut reader = reader;
         for i in 1..=sample_count {
             if let Ok(Some(sample)) = reader.read_sample(video_track_id, i) {
-                samples.push((sample.bytes.to_vec(), sample.start_time, sample.is_sync));
+                // The composition offset (ctts) leads from decoding to
+                // presentation order
+                samples.push(Sample {
+                    data: sample.bytes.to_vec(),
+                    pts: sample
+                        .start_time
+                        .saturating_add_signed(i64::from(sample.rendering_offset)),
+                    duration: u64::from(sample.duration),
+                    is_keyframe: sample.is_sync,
+                });
             }
         }
 
+        let (audio, audio_samples) = read_audio(&mut reader).unzip();
+
         Ok(Self {
+            path: path.as_ref().to_owned(),
+            codec,
+            track_id: video_track_id,
             width,
             height,
             samples,
             codec_config,
             current_index: 0,
             timescale,
+            audio,
+            audio_samples: audio_samples.unwrap_or_default(),
+            audio_index: 0,
+        })
+    }
+
+    /// Read a `WebM` file, which holds its samples in memory like MP4 does.
+    fn open_webm(path: &Path) -> Result<Self, VideoError> {
+        let video = webm::read(std::io::BufReader::new(std::fs::File::open(path)?))?;
+        let mut samples: Vec<Sample> = video
+            .samples
+            .into_iter()
+            .map(|(data, pts, is_keyframe)| Sample {
+                data,
+                pts,
+                duration: 0,
+                is_keyframe,
+            })
+            .collect();
+        // VP8, VP9 and AV1 frames are stored in presentation order, so each
+        // lasts until the next. The last lasts as long as the one before it.
+        for i in 1..samples.len() {
+            samples[i - 1].duration = samples[i].pts.saturating_sub(samples[i - 1].pts);
+        }
+        if let [.., previous, last] = samples.as_mut_slice() {
+            last.duration = previous.duration;
+        }
+        Ok(Self {
+            path: path.to_owned(),
+            codec: Some(video.codec),
+            track_id: video.track_number,
+            width: video.width,
+            height: video.height,
+            samples,
+            codec_config: video.codec_private,
+            current_index: 0,
+            timescale: video.timescale,
+            audio: None,
+            audio_samples: Vec::new(),
+            audio_index: 0,
         })
     }
 
+    /// Get the video codec, if it is one this crate knows.
+    #[must_use]
+    pub const fn codec(&self) -> Option<CodecType> {
+        self.codec
+    }
+
     /// Get timescale.
     #[must_use]
     pub const fn timescale(&self) -> u32 {
@@ -199,30 +354,157 @@ impl VideoReader {
     }
 
     /// Read the next video sample (encoded data).
-    /// Returns (data, `pts_ms`, `is_keyframe`) or None if at end.
+    /// Returns (data, pts in [`timescale`](Self::timescale) units,
+    /// `is_keyframe`) or None if at end.
+    #[deprecated(note = "iterate the reader, which yields `EncodedFrame`s")]
     pub fn read_sample(&mut self) -> Option<(Vec<u8>, u64, bool)> {
-        if self.current_index >= self.samples.len() {
-            return None;
-        }
-
-        let sample = self.samples[self.current_index].clone();
-        self.current_index += 1;
-        Some(sample)
+        let pts = self.samples.get(self.current_index)?.pts;
+        let frame = self.next()?.ok()?;
+        Some((frame.data, pts, frame.is_keyframe))
     }
 
     /// Iterate over samples from the current position.
+    #[deprecated(note = "iterate the reader, which yields `EncodedFrame`s")]
+    #[allow(deprecated)]
     pub fn samples(&mut self) -> impl Iterator<Item = (Vec<u8>, u64, bool)> + '_ {
         std::iter::from_fn(move || self.read_sample())
     }
 
-    /// Get codec configuration (avcC or hvcC raw data).
+    /// Get codec configuration (avcC or hvcC raw data, or the `WebM`
+    /// `CodecPrivate`).
     #[must_use]
     pub fn codec_config(&self) -> Option<&[u8]> {
         self.codec_config.as_deref()
     }
 
-    /// Reset to beginning.
+    /// Get the audio track, if the file has an AAC one.
+    #[must_use]
+    pub const fn audio_track(&self) -> Option<AudioTrackInfo> {
+        self.audio
+    }
+
+    /// Read the next audio sample.
+    /// Returns (data, `pts_ns`, `is_key`) or None if at end or without audio.
+    ///
+    /// AAC frames are returned with an ADTS header, ready for
+    /// `waterkit-codec`'s `AacDecoder`.
+    pub fn read_audio_sample(&mut self) -> Option<(Vec<u8>, u64, bool)> {
+        let sample = self.audio_samples.get(self.audio_index)?.clone();
+        self.audio_index += 1;
+        Some(sample)
+    }
+
+    /// Extract the frame at `time` as an encoded image, e.g. a poster frame.
+    ///
+    /// Only the frames from the preceding keyframe are decoded, not the whole
+    /// file, and the reading position is left unchanged. If no frame starts
+    /// exactly at `time`, the closest one is returned; times past the end
+    /// give the last frame. The image is rotated the way the video is shown.
+    ///
+    /// # Errors
+    /// Returns [`VideoError::NotSupported`] if the platform can't decode the
+    /// video; apart from Apple and Android, only AV1 is decoded. Returns
+    /// [`VideoError::Codec`] if the frame can't be decoded or encoded.
+    pub fn thumbnail(
+        &self,
+        time: Duration,
+        format: ThumbnailFormat,
+    ) -> Result<Vec<u8>, VideoError> {
+        let format = match format {
+            ThumbnailFormat::Jpeg(quality) => ThumbnailFormat::Jpeg(quality.clamp(1, 100)),
+            ThumbnailFormat::Png => ThumbnailFormat::Png,
+        };
+        // Platforms fail rather than pick the last frame for a time past it
+        let last = self
+            .samples
+            .iter()
+            .map(|sample| sample.pts)
+            .max()
+            .unwrap_or(0);
+        let end = Duration::from_secs(last) / self.timescale.max(1);
+        sys::thumbnail(&self.path, time.min(end), format)
+    }
+
+    /// Reset to beginning, so that iterating replays every frame.
     pub const fn reset(&mut self) {
         self.current_index = 0;
+        self.audio_index = 0;
+    }
+
+    /// Convert a timestamp from [`timescale`](Self::timescale) units.
+    #[allow(clippy::cast_possible_truncation)]
+    fn ticks_to_ns(&self, ticks: u64) -> u64 {
+        (u128::from(ticks) * 1_000_000_000 / u128::from(self.timescale.max(1))) as u64
+    }
+}
+
+/// Samples are read into memory when the file is opened, so iterating
+/// doesn't fail yet; the `Result` leaves room for streaming reads.
+impl Iterator for VideoReader {
+    type Item = Result<EncodedFrame, VideoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.samples.get(self.current_index)?.clone();
+        self.current_index += 1;
+        Some(Ok(EncodedFrame {
+            data: sample.data,
+            pts_ns: self.ticks_to_ns(sample.pts),
+            duration_ns: self.ticks_to_ns(sample.duration),
+            is_keyframe: sample.is_keyframe,
+            track_id: self.track_id,
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.samples.len().saturating_sub(self.current_index);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for VideoReader {}
+
+/// Find the AAC track and read its samples as ADTS frames.
+fn read_audio<R: Read + std::io::Seek>(
+    reader: &mut mp4::Mp4Reader<R>,
+) -> Option<(AudioTrackInfo, Samples)> {
+    let movie_timescale = u64::from(reader.timescale());
+    let track = reader.tracks().values().find(|track| {
+        track.track_type().ok() == Some(mp4::TrackType::Audio)
+            && track.trak.mdia.minf.stbl.stsd.mp4a.is_some()
+    })?;
+    let track_id = track.track_id();
+    let timescale = u64::from(track.timescale());
+    let rate_index = track.sample_freq_index().ok()?;
+    let channels = track.channel_config().ok()? as u8;
+    let info = AudioTrackInfo {
+        codec: AudioCodecType::Aac,
+        sample_rate: rate_index.freq(),
+        channels,
+        sample_count: track.sample_count(),
+    };
+
+    // Empty edits at the start of the edit list delay the whole track
+    let delay_ns: u64 = track
+        .trak
+        .edts
+        .iter()
+        .filter_map(|edts| edts.elst.as_ref())
+        .flat_map(|elst| &elst.entries)
+        .take_while(|entry| entry.media_time == u64::from(u32::MAX) || entry.media_time == u64::MAX)
+        .map(|entry| entry.segment_duration * 1_000_000_000 / movie_timescale.max(1))
+        .sum();
+
+    let rate_index = rate_index as u8;
+    let mut samples = Vec::new();
+    for i in 1..=info.sample_count {
+        if let Ok(Some(sample)) = reader.read_sample(track_id, i) {
+            let pts_ns = delay_ns + sample.start_time * 1_000_000_000 / timescale.max(1);
+            samples.push((
+                aac::adts_frame(rate_index, channels, &sample.bytes),
+                pts_ns,
+                sample.is_sync,
+            ));
+        }
     }
+    Some((info, samples))
 }
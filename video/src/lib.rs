@@ -1,22 +1,26 @@
 //! Cross-platform video muxing, demuxing, and playback.
 //!
 //! This crate provides:
-//! - **Muxing**: Write H.264/H.265 video to MP4/MOV containers
-//! - **Demuxing**: Read video samples from containers
+//! - **Muxing**: Write H.264/H.265 video and AAC audio to MP4/MOV containers,
+//!   and VP8/VP9/AV1 video to `WebM`
+//! - **Demuxing**: Read video and audio samples from containers
+//! - **Thumbnails**: Extract a single frame as JPEG or PNG
 //! - **Hardware Decode**: `VideoToolbox` (Apple), `MediaCodec` (Android)
 //! - **wgpu Integration**: Render decoded frames to GPU textures
 
 #![warn(missing_docs)]
 
+mod aac;
 mod demuxer;
 mod muxer;
+mod webm;
 
-// Platform-specific (hardware decode) - to be implemented
-// #[cfg(any(target_os = "macos", target_os = "ios"))]
-// mod sys;
+/// Platform-specific implementations (thumbnails; hardware decode is to be
+/// implemented).
+mod sys;
 
-pub use demuxer::{VideoFrame, VideoReader};
-pub use muxer::{CodecType, VideoFormat, VideoWriter};
+pub use demuxer::{AudioTrackInfo, EncodedFrame, ThumbnailFormat, VideoFrame, VideoReader};
+pub use muxer::{AudioCodecType, AudioTrackHandle, CodecType, VideoFormat, VideoWriter};
 
 /// Re-export wgpu for texture integration.
 pub use wgpu;
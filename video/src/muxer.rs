@@ -1,8 +1,9 @@
-use crate::VideoError;
+use crate::{VideoError, aac, webm};
 use byteorder::{BigEndian, WriteBytesExt};
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
 
 /// Video container format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -12,6 +13,18 @@ pub enum VideoFormat {
     Mp4,
     /// MOV container (Apple `QuickTime`).
     Mov,
+    /// `WebM` container, for VP8, VP9 and AV1 video.
+    Webm,
+}
+
+impl VideoFormat {
+    /// The container [`VideoWriter::new`] picks for `codec`.
+    const fn for_codec(codec: CodecType) -> Self {
+        match codec {
+            CodecType::H264 | CodecType::H265 => Self::Mov,
+            CodecType::Vp8 | CodecType::Vp9 | CodecType::Av1 => Self::Webm,
+        }
+    }
 }
 
 /// Video codec type.
@@ -22,9 +35,92 @@ pub enum CodecType {
     /// H.265/HEVC codec.
     #[default]
     H265,
+    /// VP8 codec (`WebM` only).
+    Vp8,
+    /// VP9 codec (`WebM` only).
+    Vp9,
+    /// AV1 codec (`WebM` only).
+    Av1,
+}
+
+/// Audio codec type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AudioCodecType {
+    /// AAC-LC.
+    #[default]
+    Aac,
+}
+
+/// Samples of an audio track, shared between the writer and its handles.
+#[derive(Debug)]
+struct AudioTrack {
+    sample_rate: u32,
+    channels: u8,
+    codec: AudioCodecType,
+    samples: Vec<(Vec<u8>, u64, bool)>, // (data, pts_ns, is_key)
+    finished: bool,
+}
+
+impl AudioTrack {
+    /// A timestamp in the track's timescale (the sample rate).
+    ///
+    /// Rounded to the nearest tick, so timestamps truncated to whole
+    /// nanoseconds still give even frame durations.
+    #[allow(clippy::cast_possible_truncation)]
+    fn ticks(&self, pts_ns: u64) -> u64 {
+        ((u128::from(pts_ns) * u128::from(self.sample_rate) + 500_000_000) / 1_000_000_000) as u64
+    }
+
+    /// Duration of every sample in ticks. The last one lasts one AAC frame.
+    fn durations(&self) -> Vec<u32> {
+        let ticks: Vec<u64> = self.samples.iter().map(|s| self.ticks(s.1)).collect();
+        let mut durations: Vec<u32> = ticks
+            .windows(2)
+            .map(|pair| u32::try_from(pair[1].saturating_sub(pair[0])).unwrap_or(u32::MAX))
+            .collect();
+        if !ticks.is_empty() {
+            durations.push(aac::FRAME_LEN);
+        }
+        durations
+    }
 }
 
-/// Video writer for creating MP4/MOV files.
+/// Writes samples to an audio track of a [`VideoWriter`].
+///
+/// Handles can be cloned and sent to another thread, e.g. the one capturing
+/// audio.
+#[derive(Debug, Clone)]
+pub struct AudioTrackHandle {
+    track: Arc<Mutex<AudioTrack>>,
+}
+
+impl AudioTrackHandle {
+    /// Write an audio sample (one encoded frame).
+    ///
+    /// `pts_ns` is the presentation time in nanoseconds on the same clock
+    /// as the video, whose first frame is at 0. AAC frames may be bare or
+    /// ADTS framed, as produced by `waterkit-codec`'s `AacEncoder`; the ADTS
+    /// header is dropped since MP4 describes the stream once per track.
+    ///
+    /// # Errors
+    /// Returns [`VideoError::Container`] if the writer has already finished.
+    pub fn write_sample(&self, data: &[u8], pts_ns: u64, is_key: bool) -> Result<(), VideoError> {
+        let mut track = self.track.lock().unwrap_or_else(PoisonError::into_inner);
+        if track.finished {
+            return Err(VideoError::Container(
+                "video writer already finished".into(),
+            ));
+        }
+        let data = match track.codec {
+            AudioCodecType::Aac => aac::strip_adts(data),
+        };
+        track.samples.push((data.to_vec(), pts_ns, is_key));
+        drop(track);
+        Ok(())
+    }
+}
+
+/// Video writer for creating MP4/MOV and `WebM` files.
 ///
 /// Note: This is a simplified writer. For production use, consider
 /// using the full mp4 crate API or `AVFoundation` on Apple platforms.
@@ -35,20 +131,25 @@ pub struct VideoWriter {
     height: u32,
     fps: u32,
     codec: CodecType,
+    format: VideoFormat,
     samples: Vec<(Vec<u8>, bool)>, // (data, is_keyframe)
     codec_config: Option<Vec<u8>>,
+    audio: Option<Arc<Mutex<AudioTrack>>>,
 }
 
 // Minimal manual MOV muxer to avoid mp4 crate limitations
 impl VideoWriter {
     /// Create a new video writer.
     ///
+    /// H.264 and H.265 are written to a MOV container, VP8, VP9 and AV1 to
+    /// `WebM`.
+    ///
     /// # Arguments
-    /// * `path` - Output file path (.mp4 or .mov)
+    /// * `path` - Output file path (.mp4, .mov or .webm)
     /// * `width` - Video width in pixels
     /// * `height` - Video height in pixels  
     /// * `fps` - Frames per second
-    /// * `codec` - Video codec (H264 or H265)
+    /// * `codec` - Video codec
     ///
     /// # Errors
     /// Returns [`VideoError::Io`] if the file cannot be created.
@@ -59,6 +160,34 @@ impl VideoWriter {
         fps: u32,
         codec: CodecType,
     ) -> Result<Self, VideoError> {
+        Self::with_format(
+            path,
+            width,
+            height,
+            fps,
+            codec,
+            VideoFormat::for_codec(codec),
+        )
+    }
+
+    /// Create a new video writer for the container `format`.
+    ///
+    /// # Errors
+    /// Returns [`VideoError::NotSupported`] if `format` can't hold `codec`:
+    /// `WebM` takes VP8, VP9 and AV1 only, MP4 and MOV H.264 and H.265 only.
+    /// Returns [`VideoError::Io`] if the file cannot be created.
+    pub fn with_format<P: AsRef<Path>>(
+        path: P,
+        width: u32,
+        height: u32,
+        fps: u32,
+        codec: CodecType,
+        format: VideoFormat,
+    ) -> Result<Self, VideoError> {
+        let webm_codec = matches!(codec, CodecType::Vp8 | CodecType::Vp9 | CodecType::Av1);
+        if (format == VideoFormat::Webm) != webm_codec {
+            return Err(VideoError::NotSupported(format!("{codec:?} in {format:?}")));
+        }
         let file = File::create(path)?;
         let writer_buf = BufWriter::new(file);
 
@@ -68,12 +197,63 @@ impl VideoWriter {
             height,
             fps,
             codec,
+            format,
             samples: Vec::new(),
             codec_config: None,
+            audio: None,
         })
     }
 
-    /// Set codec configuration (hvcC/avcC atom data).
+    /// Add an audio track.
+    ///
+    /// Samples are written through the returned handle and are stored in
+    /// the file when [`finish`](Self::finish) is called. One audio track is
+    /// supported.
+    ///
+    /// # Errors
+    /// Returns [`VideoError::NotSupported`] for `WebM` files or if the sample
+    /// rate or channel count can't be described by the codec, and
+    /// [`VideoError::Container`] if the writer already has an audio track.
+    pub fn add_audio_track(
+        &mut self,
+        sample_rate: u32,
+        channels: u8,
+        codec: AudioCodecType,
+    ) -> Result<AudioTrackHandle, VideoError> {
+        if self.format == VideoFormat::Webm {
+            return Err(VideoError::NotSupported(format!("{codec:?} in WebM")));
+        }
+        if self.audio.is_some() {
+            return Err(VideoError::Container("audio track already added".into()));
+        }
+        match codec {
+            AudioCodecType::Aac => {
+                if aac::sample_rate_index(sample_rate).is_none() {
+                    return Err(VideoError::NotSupported(format!(
+                        "AAC sample rate {sample_rate}"
+                    )));
+                }
+                if !(1..=6).contains(&channels) {
+                    return Err(VideoError::NotSupported(format!(
+                        "AAC with {channels} channels"
+                    )));
+                }
+            }
+        }
+
+        let track = Arc::new(Mutex::new(AudioTrack {
+            sample_rate,
+            channels,
+            codec,
+            samples: Vec::new(),
+            finished: false,
+        }));
+        self.audio = Some(Arc::clone(&track));
+        Ok(AudioTrackHandle { track })
+    }
+
+    /// Set codec configuration (hvcC/avcC atom data, or the `WebM`
+    /// `CodecPrivate`: the av1C record for AV1, not needed for VP8 and VP9).
     pub fn set_codec_config(&mut self, config: Vec<u8>) {
         self.codec_config = Some(config);
     }
@@ -89,14 +269,39 @@ impl VideoWriter {
 
     /// Finish writing and close the file.
     ///
+    /// Writes the samples of the video and audio tracks. Audio samples
+    /// written through an [`AudioTrackHandle`] afterwards are refused.
+    ///
     /// # Errors
     /// Returns [`VideoError::Io`] if writing to the file fails.
     #[allow(clippy::too_many_lines, clippy::cast_possible_truncation)]
     pub fn finish(self) -> Result<(), VideoError> {
+        if self.format == VideoFormat::Webm {
+            let track = webm::TrackConfig {
+                codec: self.codec,
+                width: self.width,
+                height: self.height,
+                fps: self.fps,
+                codec_private: self.codec_config.as_deref(),
+            };
+            let mut file = self.file;
+            return webm::write(&mut file, &track, &self.samples);
+        }
+
         if self.codec_config.is_none() {
             eprintln!("Warning: No codec config provided. File may be invalid.");
         }
 
+        // Later samples from the audio handles are refused
+        let audio = self.audio.map(|track| {
+            let mut track = track.lock().unwrap_or_else(PoisonError::into_inner);
+            track.finished = true;
+            AudioTrack {
+                samples: std::mem::take(&mut track.samples),
+                ..*track
+            }
+        });
+
         let mut w = self.file;
 
         // 1. Write ftyp
@@ -108,7 +313,14 @@ impl VideoWriter {
 
         // 2. Write mdat
         // Calculate mdat size
-        let mdat_data_size: u64 = self.samples.iter().map(|(d, _)| d.len() as u64).sum();
+        let mdat_data_size: u64 = self
+            .samples
+            .iter()
+            .map(|(d, _)| d.len() as u64)
+            .sum::<u64>()
+            + audio.as_ref().map_or(0, |audio| {
+                audio.samples.iter().map(|(d, ..)| d.len() as u64).sum()
+            });
         let mdat_box_size = 8 + mdat_data_size;
 
         // We use 64-bit size for safety if large, but standard uses 32-bit if < 4GB.
@@ -134,6 +346,24 @@ impl VideoWriter {
             }
         }
 
+        // Audio samples follow the video in mdat
+        let mut audio_sizes = Vec::new();
+        let mut audio_offsets = Vec::new();
+        for (data, ..) in audio.iter().flat_map(|audio| &audio.samples) {
+            w.write_all(data)?;
+            audio_sizes.push(data.len() as u32);
+            audio_offsets.push(current_offset as u32);
+            current_offset += data.len() as u64;
+        }
+
+        // In the movie timescale (fps), the longer track sets the duration
+        let audio_end = audio.as_ref().map_or(0, |audio| {
+            let end = audio.samples.first().map_or(0, |s| audio.ticks(s.1))
+                + audio.durations().iter().map(|&d| u64::from(d)).sum::<u64>();
+            end * u64::from(self.fps) / u64::from(audio.sample_rate)
+        });
+        let movie_duration = (self.samples.len() as u64).max(audio_end);
+
         // 3. Write moov
         // Note: write_box_header is moved to the top level
 
@@ -150,7 +380,7 @@ impl VideoWriter {
                 mw.write_u32::<BigEndian>(0)?; // Creation time
                 mw.write_u32::<BigEndian>(0)?; // Modification time
                 mw.write_u32::<BigEndian>(self.fps)?; // Timescale
-                mw.write_u32::<BigEndian>(self.samples.len() as u32)?; // Duration (assuming 1 unit per frame with timescale=fps)
+                mw.write_u32::<BigEndian>(movie_duration as u32)?; // Duration (1 unit per frame with timescale=fps)
                 mw.write_u32::<BigEndian>(0x0001_0000)?; // Rate (1.0)
                 mw.write_u16::<BigEndian>(0x0100)?; // Volume (1.0)
                 mw.write_all(&[0u8; 10])?; // Reserved
@@ -165,7 +395,7 @@ impl VideoWriter {
                 mw.write_u32::<BigEndian>(0)?;
                 mw.write_u32::<BigEndian>(0x4000_0000)?;
                 mw.write_all(&[0u8; 24])?; // Pre-defined
-                mw.write_u32::<BigEndian>(2)?; // Next track ID
+                mw.write_u32::<BigEndian>(if audio.is_some() { 3 } else { 2 })?; // Next track ID
 
                 write_box_header(w, b"mvhd", mvhd.len() as u64)?;
                 w.write_all(&mvhd)?;
@@ -430,6 +660,12 @@ impl VideoWriter {
                 write_box_header(w, b"trak", trak.len() as u64)?;
                 w.write_all(&trak)?;
             }
+
+            if let Some(audio) = &audio {
+                let trak = audio_trak(audio, &audio_sizes, &audio_offsets, self.fps)?;
+                write_box_header(w, b"trak", trak.len() as u64)?;
+                w.write_all(&trak)?;
+            }
         }
 
         write_box_header(&mut w, b"moov", moov.len() as u64)?;
@@ -452,6 +688,317 @@ impl VideoWriter {
     }
 }
 
+/// Build the `trak` box content of an audio track.
+///
+/// `sizes` and `offsets` locate its samples in `mdat`, one sample per chunk
+/// like the video track. `movie_timescale` is the timescale of `mvhd`.
+#[allow(clippy::too_many_lines, clippy::cast_possible_truncation)]
+fn audio_trak(
+    audio: &AudioTrack,
+    sizes: &[u32],
+    offsets: &[u32],
+    movie_timescale: u32,
+) -> Result<Vec<u8>, VideoError> {
+    let durations = audio.durations();
+    let media_duration: u64 = durations.iter().map(|&d| u64::from(d)).sum();
+    let to_movie = |ticks: u64| ticks * u64::from(movie_timescale) / u64::from(audio.sample_rate);
+    let start = audio.samples.first().map_or(0, |s| audio.ticks(s.1));
+
+    let mut trak = Vec::new();
+    let tw = &mut trak;
+
+    // tkhd
+    {
+        let mut tkhd = Vec::new();
+        let thw = &mut tkhd;
+        thw.write_u32::<BigEndian>(0x0000_0001)?; // Version/Flags (Enabled)
+        thw.write_u32::<BigEndian>(0)?; // Creation time
+        thw.write_u32::<BigEndian>(0)?; // Modification time
+        thw.write_u32::<BigEndian>(2)?; // Track ID
+        thw.write_u32::<BigEndian>(0)?; // Reserved
+        thw.write_u32::<BigEndian>(to_movie(start + media_duration) as u32)?; // Duration
+        thw.write_all(&[0u8; 8])?; // Reserved
+        thw.write_u16::<BigEndian>(0)?; // Layer
+        thw.write_u16::<BigEndian>(0)?; // Alt group
+        thw.write_u16::<BigEndian>(0x0100)?; // Volume (1.0)
+        thw.write_u16::<BigEndian>(0)?; // Reserved
+        // Matrix (unity)
+        thw.write_all(&[
+            0x00, 0x01, 0x00, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x01, 0x00, 0x00, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x40, 0x00, 0x00, 0x00,
+        ])?;
+        thw.write_u32::<BigEndian>(0)?; // Width
+        thw.write_u32::<BigEndian>(0)?; // Height
+
+        write_box_header(tw, b"tkhd", tkhd.len() as u64)?;
+        tw.write_all(&tkhd)?;
+    }
+
+    // edts: delay the track until its first sample is due
+    if start > 0 {
+        let mut elst = Vec::new();
+        let ew = &mut elst;
+        ew.write_u32::<BigEndian>(0)?; // Version/Flags
+        ew.write_u32::<BigEndian>(2)?; // Entry count
+        ew.write_u32::<BigEndian>(to_movie(start) as u32)?; // Segment duration
+        ew.write_i32::<BigEndian>(-1)?; // Media time (empty edit)
+        ew.write_u32::<BigEndian>(0x0001_0000)?; // Media rate (1.0)
+        ew.write_u32::<BigEndian>(to_movie(media_duration) as u32)?; // Segment duration
+        ew.write_i32::<BigEndian>(0)?; // Media time
+        ew.write_u32::<BigEndian>(0x0001_0000)?; // Media rate (1.0)
+
+        let mut edts = Vec::new();
+        write_box_header(&mut edts, b"elst", elst.len() as u64)?;
+        edts.write_all(&elst)?;
+        write_box_header(tw, b"edts", edts.len() as u64)?;
+        tw.write_all(&edts)?;
+    }
+
+    // mdia
+    {
+        let mut mdia = Vec::new();
+        let mw = &mut mdia;
+
+        // mdhd
+        {
+            let mut mdhd = Vec::new();
+            let mhw = &mut mdhd;
+            mhw.write_u32::<BigEndian>(0)?; // Version/Flags
+            mhw.write_u32::<BigEndian>(0)?; // Creation time
+            mhw.write_u32::<BigEndian>(0)?; // Modification time
+            mhw.write_u32::<BigEndian>(audio.sample_rate)?; // Timescale
+            mhw.write_u32::<BigEndian>(media_duration as u32)?; // Duration
+            mhw.write_u16::<BigEndian>(0)?; // Language (0)
+            mhw.write_u16::<BigEndian>(0)?; // Pre-defined
+
+            write_box_header(mw, b"mdhd", mdhd.len() as u64)?;
+            mw.write_all(&mdhd)?;
+        }
+
+        // hdlr
+        {
+            let mut hdlr = Vec::new();
+            let hw = &mut hdlr;
+            hw.write_u32::<BigEndian>(0)?; // Version/Flags
+            hw.write_u32::<BigEndian>(0)?; // Pre-defined
+            hw.write_all(b"soun")?; // Component sub-type
+            hw.write_all(&[0u8; 12])?; // Reserved
+            hw.write_all(b"SoundHandler\0")?; // Component name
+
+            write_box_header(mw, b"hdlr", hdlr.len() as u64)?;
+            mw.write_all(&hdlr)?;
+        }
+
+        // minf
+        {
+            let mut minf = Vec::new();
+            let miw = &mut minf;
+
+            // smhd
+            {
+                let mut smhd = Vec::new();
+                smhd.write_u32::<BigEndian>(0)?; // Version/Flags
+                smhd.write_u16::<BigEndian>(0)?; // Balance
+                smhd.write_u16::<BigEndian>(0)?; // Reserved
+
+                write_box_header(miw, b"smhd", smhd.len() as u64)?;
+                miw.write_all(&smhd)?;
+            }
+
+            // dinf
+            {
+                let mut dref = Vec::new();
+                dref.write_u32::<BigEndian>(0)?; // Version/Flags
+                dref.write_u32::<BigEndian>(1)?; // Entry count
+                write_box_header(&mut dref, b"url ", 4)?;
+                dref.write_u32::<BigEndian>(0x0000_0001)?; // Version/Flags (self-contained)
+
+                let mut dinf = Vec::new();
+                write_box_header(&mut dinf, b"dref", dref.len() as u64)?;
+                dinf.write_all(&dref)?;
+
+                write_box_header(miw, b"dinf", dinf.len() as u64)?;
+                miw.write_all(&dinf)?;
+            }
+
+            // stbl
+            {
+                let mut stbl = Vec::new();
+                let sw = &mut stbl;
+
+                // stsd
+                {
+                    let mut stsd = Vec::new();
+                    let ssw = &mut stsd;
+                    ssw.write_u32::<BigEndian>(0)?; // Version/Flags
+                    ssw.write_u32::<BigEndian>(1)?; // Entry count
+
+                    // AudioSampleEntry (mp4a)
+                    let mut entry = Vec::new();
+                    let ew = &mut entry;
+                    ew.write_all(&[0u8; 6])?; // Reserved
+                    ew.write_u16::<BigEndian>(1)?; // Data ref index
+                    ew.write_all(&[0u8; 8])?; // Version, revision, vendor
+                    ew.write_u16::<BigEndian>(u16::from(audio.channels))?; // Channel count
+                    ew.write_u16::<BigEndian>(16)?; // Sample size
+                    ew.write_u16::<BigEndian>(0)?; // Compression ID
+                    ew.write_u16::<BigEndian>(0)?; // Packet size
+                    // Sample rate (fixed point 16.16); rates above 65535 don't fit
+                    ew.write_u32::<BigEndian>(audio.sample_rate.min(0xFFFF) << 16)?;
+
+                    match audio.codec {
+                        AudioCodecType::Aac => {
+                            let esds = esds(audio)?;
+                            write_box_header(ew, b"esds", esds.len() as u64)?;
+                            ew.write_all(&esds)?;
+                        }
+                    }
+
+                    write_box_header(ssw, b"mp4a", entry.len() as u64)?;
+                    ssw.write_all(&entry)?;
+
+                    write_box_header(sw, b"stsd", stsd.len() as u64)?;
+                    sw.write_all(&stsd)?;
+                }
+
+                // stts (time to sample), run-length encoded
+                {
+                    let mut runs: Vec<(u32, u32)> = Vec::new(); // (count, delta)
+                    for &delta in &durations {
+                        match runs.last_mut() {
+                            Some((count, last)) if *last == delta => *count += 1,
+                            _ => runs.push((1, delta)),
+                        }
+                    }
+
+                    let mut stts = Vec::new();
+                    let stw = &mut stts;
+                    stw.write_u32::<BigEndian>(0)?; // Version/Flags
+                    stw.write_u32::<BigEndian>(runs.len() as u32)?; // Entry count
+                    for (count, delta) in runs {
+                        stw.write_u32::<BigEndian>(count)?; // Sample count
+                        stw.write_u32::<BigEndian>(delta)?; // Sample delta
+                    }
+
+                    write_box_header(sw, b"stts", stts.len() as u64)?;
+                    sw.write_all(&stts)?;
+                }
+
+                // stsc (sample to chunk): one sample per chunk
+                {
+                    let mut stsc = Vec::new();
+                    let scw = &mut stsc;
+                    scw.write_u32::<BigEndian>(0)?; // Version/Flags
+                    scw.write_u32::<BigEndian>(1)?; // Entry count
+                    scw.write_u32::<BigEndian>(1)?; // First chunk
+                    scw.write_u32::<BigEndian>(1)?; // Samples per chunk
+                    scw.write_u32::<BigEndian>(1)?; // Sample description index
+
+                    write_box_header(sw, b"stsc", stsc.len() as u64)?;
+                    sw.write_all(&stsc)?;
+                }
+
+                // stss (sync samples), left out when every sample is one
+                if audio.samples.iter().any(|(_, _, is_key)| !is_key) {
+                    let sync: Vec<u32> = (1..)
+                        .zip(&audio.samples)
+                        .filter(|(_, (_, _, is_key))| *is_key)
+                        .map(|(index, _)| index)
+                        .collect();
+
+                    let mut stss = Vec::new();
+                    let ssw = &mut stss;
+                    ssw.write_u32::<BigEndian>(0)?; // Version/Flags
+                    ssw.write_u32::<BigEndian>(sync.len() as u32)?; // Entry count
+                    for idx in sync {
+                        ssw.write_u32::<BigEndian>(idx)?;
+                    }
+
+                    write_box_header(sw, b"stss", stss.len() as u64)?;
+                    sw.write_all(&stss)?;
+                }
+
+                // stsz (sample sizes)
+                {
+                    let mut stsz = Vec::new();
+                    let szw = &mut stsz;
+                    szw.write_u32::<BigEndian>(0)?; // Version/Flags
+                    szw.write_u32::<BigEndian>(0)?; // Default sample size (0=variable)
+                    szw.write_u32::<BigEndian>(sizes.len() as u32)?; // Sample count
+                    for &size in sizes {
+                        szw.write_u32::<BigEndian>(size)?;
+                    }
+
+                    write_box_header(sw, b"stsz", stsz.len() as u64)?;
+                    sw.write_all(&stsz)?;
+                }
+
+                // stco (chunk offsets - 32 bit)
+                {
+                    let mut stco = Vec::new();
+                    let cow = &mut stco;
+                    cow.write_u32::<BigEndian>(0)?; // Version/Flags
+                    cow.write_u32::<BigEndian>(offsets.len() as u32)?; // Entry count
+                    for &offset in offsets {
+                        cow.write_u32::<BigEndian>(offset)?;
+                    }
+
+                    write_box_header(sw, b"stco", stco.len() as u64)?;
+                    sw.write_all(&stco)?;
+                }
+
+                write_box_header(miw, b"stbl", stbl.len() as u64)?;
+                miw.write_all(&stbl)?;
+            }
+
+            write_box_header(mw, b"minf", minf.len() as u64)?;
+            mw.write_all(&minf)?;
+        }
+
+        write_box_header(tw, b"mdia", mdia.len() as u64)?;
+        tw.write_all(&mdia)?;
+    }
+
+    Ok(trak)
+}
+
+/// Build the `esds` box content describing an AAC track.
+#[allow(clippy::cast_possible_truncation)]
+fn esds(audio: &AudioTrack) -> Result<Vec<u8>, VideoError> {
+    let rate_index = aac::sample_rate_index(audio.sample_rate).ok_or_else(|| {
+        VideoError::NotSupported(format!("AAC sample rate {}", audio.sample_rate))
+    })?;
+    let config = aac::audio_specific_config(rate_index, audio.channels);
+
+    // Descriptors are nested tag-length-value records; all of them are short
+    // enough for single-byte lengths
+    let mut decoder_specific = vec![0x05, config.len() as u8];
+    decoder_specific.extend_from_slice(&config);
+
+    let mut decoder_config = Vec::new();
+    decoder_config.write_u8(0x40)?; // Object type (MPEG-4 audio)
+    decoder_config.write_u8((0x05 << 2) | 1)?; // Stream type (audio), reserved bit
+    decoder_config.write_all(&[0u8; 3])?; // Buffer size
+    decoder_config.write_u32::<BigEndian>(0)?; // Max bitrate
+    decoder_config.write_u32::<BigEndian>(0)?; // Average bitrate
+    decoder_config.extend_from_slice(&decoder_specific);
+
+    let mut es = Vec::new();
+    es.write_u16::<BigEndian>(2)?; // ES ID (the track ID)
+    es.write_u8(0)?; // Flags
+    es.write_u8(0x04)?; // DecoderConfigDescriptor
+    es.write_u8(decoder_config.len() as u8)?;
+    es.extend_from_slice(&decoder_config);
+    es.extend_from_slice(&[0x06, 1, 0x02]); // SLConfigDescriptor (MP4)
+
+    let mut esds = Vec::new();
+    esds.write_u32::<BigEndian>(0)?; // Version/Flags
+    esds.write_u8(0x03)?; // ES_Descriptor
+    esds.write_u8(es.len() as u8)?;
+    esds.extend_from_slice(&es);
+    Ok(esds)
+}
+
 #[allow(clippy::cast_possible_truncation)]
 fn write_box_header<W: Write>(
     w: &mut W,
@@ -0,0 +1,110 @@
+//! Android implementation using `MediaMetadataRetriever` over JNI.
+
+use crate::{ThumbnailFormat, VideoError};
+use jni::JNIEnv;
+use jni::objects::{JByteArray, JObject, JValue};
+use std::path::Path;
+use std::time::Duration;
+
+/// `MediaMetadataRetriever.OPTION_CLOSEST`: decode up to the exact frame
+/// rather than returning the nearest keyframe.
+const OPTION_CLOSEST: i32 = 3;
+
+/// Run `f` with the JNI environment of the current thread.
+fn with_env<T>(f: impl FnOnce(&mut JNIEnv) -> jni::errors::Result<T>) -> Result<T, VideoError> {
+    let vm = unsafe { jni::JavaVM::from_raw(ndk_context::android_context().vm().cast()) }
+        .map_err(|e| VideoError::Codec(e.to_string()))?;
+    let mut env = vm
+        .attach_current_thread()
+        .map_err(|e| VideoError::Codec(e.to_string()))?;
+    let result = f(&mut env);
+    // Leave no pending exception behind for the next JNI call
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+    result.map_err(|e| VideoError::Codec(e.to_string()))
+}
+
+/// Grab the frame at `time_us` as a `Bitmap`, or null if there is none.
+fn frame_at<'local>(
+    env: &mut JNIEnv<'local>,
+    path: &str,
+    time_us: i64,
+) -> jni::errors::Result<JObject<'local>> {
+    let retriever = env.new_object("android/media/MediaMetadataRetriever", "()V", &[])?;
+    let path = env.new_string(path)?;
+    let bitmap = env
+        .call_method(
+            &retriever,
+            "setDataSource",
+            "(Ljava/lang/String;)V",
+            &[JValue::Object(&path)],
+        )
+        .and_then(|_| {
+            env.call_method(
+                &retriever,
+                "getFrameAtTime",
+                "(JI)Landroid/graphics/Bitmap;",
+                &[JValue::Long(time_us), JValue::Int(OPTION_CLOSEST)],
+            )
+        })
+        .and_then(|value| value.l());
+    // Released even if reading failed, to free the decoder; the exception is
+    // cleared first, since no JNI call can be made while one is pending
+    if bitmap.is_err() && env.exception_check()? {
+        env.exception_clear()?;
+    }
+    env.call_method(&retriever, "release", "()V", &[])?;
+    bitmap
+}
+
+pub fn thumbnail(
+    path: &Path,
+    time: Duration,
+    format: ThumbnailFormat,
+) -> Result<Vec<u8>, VideoError> {
+    let (name, quality) = match format {
+        ThumbnailFormat::Jpeg(quality) => ("JPEG", quality),
+        ThumbnailFormat::Png => ("PNG", 100),
+    };
+    let time_us = i64::try_from(time.as_micros()).unwrap_or(i64::MAX);
+
+    let encoded = with_env(|env| {
+        let bitmap = frame_at(env, &path.to_string_lossy(), time_us)?;
+        if bitmap.is_null() {
+            return Ok(None);
+        }
+
+        let compress_format = env
+            .get_static_field(
+                "android/graphics/Bitmap$CompressFormat",
+                name,
+                "Landroid/graphics/Bitmap$CompressFormat;",
+            )?
+            .l()?;
+        let stream = env.new_object("java/io/ByteArrayOutputStream", "()V", &[])?;
+        let compressed = env
+            .call_method(
+                &bitmap,
+                "compress",
+                "(Landroid/graphics/Bitmap$CompressFormat;ILjava/io/OutputStream;)Z",
+                &[
+                    JValue::Object(&compress_format),
+                    JValue::Int(i32::from(quality)),
+                    JValue::Object(&stream),
+                ],
+            )?
+            .z()?;
+        env.call_method(&bitmap, "recycle", "()V", &[])?;
+        if !compressed {
+            return Ok(None);
+        }
+
+        let bytes: JByteArray = env
+            .call_method(&stream, "toByteArray", "()[B", &[])?
+            .l()?
+            .into();
+        env.convert_byte_array(bytes).map(Some)
+    })?;
+    encoded.ok_or_else(|| VideoError::Codec("no frame could be extracted".into()))
+}
@@ -0,0 +1,41 @@
+import AVFoundation
+import Foundation
+import ImageIO
+
+private func failure(_ message: String) -> ThumbnailData {
+    return ThumbnailData(data: RustVec(), error: message.intoRustString())
+}
+
+public func video_thumbnail(path: RustStr, time_secs: Double, png: Bool, quality: UInt8) -> ThumbnailData {
+    let asset = AVURLAsset(url: URL(fileURLWithPath: path.toString()))
+    let generator = AVAssetImageGenerator(asset: asset)
+    // Upright, the way the video is shown
+    generator.appliesPreferredTrackTransform = true
+    // Decode up to the frame closest to the time rather than the keyframe before it
+    generator.requestedTimeToleranceBefore = .zero
+    generator.requestedTimeToleranceAfter = .zero
+
+    let image: CGImage
+    do {
+        image = try generator.copyCGImage(at: CMTime(seconds: time_secs, preferredTimescale: 600), actualTime: nil)
+    } catch {
+        return failure(error.localizedDescription)
+    }
+
+    let output = NSMutableData()
+    let type = (png ? "public.png" : "public.jpeg") as CFString
+    guard let destination = CGImageDestinationCreateWithData(output, type, 1, nil) else {
+        return failure("can't create image destination")
+    }
+    let options = [kCGImageDestinationLossyCompressionQuality: Double(quality) / 100.0] as CFDictionary
+    CGImageDestinationAddImage(destination, image, png ? nil : options)
+    guard CGImageDestinationFinalize(destination) else {
+        return failure("can't encode thumbnail")
+    }
+
+    let data = RustVec<UInt8>()
+    for byte in output as Data {
+        data.push(value: byte)
+    }
+    return ThumbnailData(data: data, error: "".intoRustString())
+}
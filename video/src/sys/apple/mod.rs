@@ -0,0 +1,37 @@
+//! Apple platform (iOS/macOS) implementation using swift-bridge.
+
+use crate::{ThumbnailFormat, VideoError};
+use std::path::Path;
+use std::time::Duration;
+
+#[swift_bridge::bridge]
+mod ffi {
+    #[swift_bridge(swift_repr = "struct")]
+    struct ThumbnailData {
+        data: Vec<u8>,
+        // Empty on success, otherwise what went wrong.
+        error: String,
+    }
+
+    extern "Swift" {
+        // `quality` (1-100) only applies to JPEG
+        fn video_thumbnail(path: &str, time_secs: f64, png: bool, quality: u8) -> ThumbnailData;
+    }
+}
+
+pub fn thumbnail(
+    path: &Path,
+    time: Duration,
+    format: ThumbnailFormat,
+) -> Result<Vec<u8>, VideoError> {
+    let (png, quality) = match format {
+        ThumbnailFormat::Jpeg(quality) => (false, quality),
+        ThumbnailFormat::Png => (true, 100),
+    };
+    let result = ffi::video_thumbnail(&path.to_string_lossy(), time.as_secs_f64(), png, quality);
+    if result.error.is_empty() {
+        Ok(result.data)
+    } else {
+        Err(VideoError::Codec(result.error))
+    }
+}
@@ -0,0 +1,63 @@
+//! Thumbnails on platforms without a thumbnail API, decoded with the
+//! software decoders of `waterkit-codec`.
+
+use crate::{CodecType, ThumbnailFormat, VideoError, VideoReader};
+use std::fmt::Display;
+use std::path::Path;
+use std::time::Duration;
+use waterkit_codec::av1::Av1Decoder;
+use waterkit_codec::{ImageEncode, ImageEncoder, VideoDecoder};
+
+/// Decode the frame closest to `time`, starting at the keyframe before it.
+///
+/// Like the platform APIs, this reads the file again, so the caller's
+/// reader keeps its position.
+pub fn thumbnail(
+    path: &Path,
+    time: Duration,
+    format: ThumbnailFormat,
+) -> Result<Vec<u8>, VideoError> {
+    let reader = VideoReader::open(path)?;
+    if reader.codec() != Some(CodecType::Av1) {
+        return Err(VideoError::NotSupported(
+            "thumbnails of other codecs than AV1, which waterkit-codec can't decode here".into(),
+        ));
+    }
+    let mut decoder = Av1Decoder::new().map_err(codec_error)?;
+
+    // AV1 frames are stored in presentation order, so they get closer to
+    // `time` up to the closest one, and only further away after it
+    let time_ns = u64::try_from(time.as_nanos()).unwrap_or(u64::MAX);
+    let mut closest = u64::MAX;
+    let mut group = Vec::new();
+    for frame in reader {
+        let frame = frame?;
+        let distance = frame.pts_ns.abs_diff(time_ns);
+        if distance > closest {
+            break;
+        }
+        closest = distance;
+        if frame.is_keyframe {
+            group.clear();
+        }
+        group.push(frame.data);
+    }
+
+    let mut picture = None;
+    for data in &group {
+        if let Some(decoded) = decoder.decode(data).map_err(codec_error)?.pop() {
+            picture = Some(decoded);
+        }
+    }
+    let picture = picture.ok_or_else(|| VideoError::Codec("No frame decoded".into()))?;
+
+    match format {
+        ThumbnailFormat::Jpeg(quality) => ImageEncoder::jpeg(quality).encode(&picture),
+        ThumbnailFormat::Png => ImageEncoder::png().encode(&picture),
+    }
+    .map_err(codec_error)
+}
+
+fn codec_error(error: impl Display) -> VideoError {
+    VideoError::Codec(error.to_string())
+}
@@ -0,0 +1,16 @@
+//! Platform-specific implementations.
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+mod apple;
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub use apple::thumbnail;
+
+#[cfg(target_os = "android")]
+mod android;
+#[cfg(target_os = "android")]
+pub use android::thumbnail;
+
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+mod desktop;
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "android")))]
+pub use desktop::thumbnail;
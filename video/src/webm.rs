@@ -0,0 +1,317 @@
+//! `WebM` container support for VP8, VP9 and AV1 video.
+//!
+//! `WebM` is a subset of Matroska, read and written with `webm-iterable`.
+//! Video frames are stored as blocks in clusters, with timestamps relative
+//! to their cluster.
+
+use crate::{CodecType, VideoError};
+use std::fmt::Display;
+use std::io::{Read, Seek, Write};
+use webm_iterable::matroska_spec::{Block, BlockLacing, Master, MatroskaSpec, SimpleBlock};
+use webm_iterable::{WebmIterator, WebmWriter, WriteOptions};
+
+/// The leading bytes of every EBML file.
+pub const SIGNATURE: [u8; 4] = [0x1A, 0x45, 0xDF, 0xA3];
+
+/// `TrackType` of video tracks.
+const TRACK_TYPE_VIDEO: u64 = 1;
+
+/// Nanoseconds per timestamp tick. Millisecond ticks are the `WebM` norm.
+const TICK_NS: u64 = 1_000_000;
+
+/// Longest cluster in ticks. Blocks store their timestamp relative to the
+/// cluster as a signed 16-bit value.
+const MAX_CLUSTER_TICKS: u64 = 5_000;
+
+/// The `WebM` `CodecID` of `codec`.
+pub const fn codec_id(codec: CodecType) -> Option<&'static str> {
+    match codec {
+        CodecType::Vp8 => Some("V_VP8"),
+        CodecType::Vp9 => Some("V_VP9"),
+        CodecType::Av1 => Some("V_AV1"),
+        CodecType::H264 | CodecType::H265 => None,
+    }
+}
+
+/// The video track of a `WebM` file being written.
+#[derive(Debug)]
+pub struct TrackConfig<'a> {
+    pub codec: CodecType,
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub codec_private: Option<&'a [u8]>,
+}
+
+/// Write a `WebM` file of one video track with one sample per frame
+/// interval. Clusters start at keyframes and are indexed by cues, so
+/// players can seek.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+pub fn write<W: Write + Seek>(
+    w: &mut W,
+    track: &TrackConfig,
+    samples: &[(Vec<u8>, bool)], // (data, is_keyframe)
+) -> Result<(), VideoError> {
+    let codec_id = codec_id(track.codec)
+        .ok_or_else(|| VideoError::NotSupported(format!("{:?} in WebM", track.codec)))?;
+    let fps = u64::from(track.fps.max(1));
+    let frame_ns = 1_000_000_000 / fps;
+    let tick = |frame: usize| frame as u64 * 1_000_000_000 / fps / TICK_NS;
+
+    let mut writer = WebmWriter::new(w);
+    writer
+        .write(&MatroskaSpec::Ebml(Master::Full(vec![
+            MatroskaSpec::EbmlVersion(1),
+            MatroskaSpec::EbmlReadVersion(1),
+            MatroskaSpec::EbmlMaxIdLength(4),
+            MatroskaSpec::EbmlMaxSizeLength(8),
+            MatroskaSpec::DocType("webm".into()),
+            MatroskaSpec::DocTypeVersion(4),
+            MatroskaSpec::DocTypeReadVersion(2),
+        ])))
+        .map_err(container)?;
+
+    // Children of a segment of unknown size reach the file as soon as they
+    // are complete, so the cues can give the position of each cluster
+    writer
+        .write_advanced(
+            &MatroskaSpec::Segment(Master::Start),
+            WriteOptions::is_unknown_sized_element(),
+        )
+        .map_err(container)?;
+    let segment_start = writer.get_mut().stream_position()? + segment_header_len()?;
+
+    let duration_ns = samples.len() as u64 * 1_000_000_000 / fps;
+    writer
+        .write(&MatroskaSpec::Info(Master::Full(vec![
+            MatroskaSpec::TimestampScale(TICK_NS),
+            MatroskaSpec::MuxingApp("waterkit-video".into()),
+            MatroskaSpec::WritingApp("waterkit-video".into()),
+            MatroskaSpec::Duration(duration_ns as f64 / TICK_NS as f64),
+        ])))
+        .map_err(container)?;
+
+    let mut entry = vec![
+        MatroskaSpec::TrackNumber(1),
+        MatroskaSpec::TrackUID(1),
+        MatroskaSpec::TrackType(TRACK_TYPE_VIDEO),
+        MatroskaSpec::CodecID(codec_id.into()),
+    ];
+    if let Some(private) = track.codec_private {
+        entry.push(MatroskaSpec::CodecPrivate(private.to_vec()));
+    }
+    entry.push(MatroskaSpec::DefaultDuration(frame_ns));
+    entry.push(MatroskaSpec::Video(Master::Full(vec![
+        MatroskaSpec::PixelWidth(u64::from(track.width)),
+        MatroskaSpec::PixelHeight(u64::from(track.height)),
+    ])));
+    writer
+        .write(&MatroskaSpec::Tracks(Master::Full(vec![
+            MatroskaSpec::TrackEntry(Master::Full(entry)),
+        ])))
+        .map_err(container)?;
+
+    let mut cues = Vec::new();
+    let mut start = 0;
+    while start < samples.len() {
+        let cluster_tick = tick(start);
+        let mut end = start + 1;
+        while end < samples.len() && !samples[end].1 && tick(end) - cluster_tick < MAX_CLUSTER_TICKS
+        {
+            end += 1;
+        }
+
+        if samples[start].1 {
+            let position = writer.get_mut().stream_position()? - segment_start;
+            cues.push(MatroskaSpec::CuePoint(Master::Full(vec![
+                MatroskaSpec::CueTime(cluster_tick),
+                MatroskaSpec::CueTrackPositions(Master::Full(vec![
+                    MatroskaSpec::CueTrack(1),
+                    MatroskaSpec::CueClusterPosition(position),
+                ])),
+            ])));
+        }
+
+        let mut cluster = vec![MatroskaSpec::Timestamp(cluster_tick)];
+        for (i, (data, is_keyframe)) in samples[start..end].iter().enumerate() {
+            let relative = (tick(start + i) - cluster_tick) as i16;
+            let block =
+                SimpleBlock::new_uncheked(data, 1, relative, false, None, false, *is_keyframe);
+            cluster.push(block.into());
+        }
+        writer
+            .write(&MatroskaSpec::Cluster(Master::Full(cluster)))
+            .map_err(container)?;
+        start = end;
+    }
+
+    if !cues.is_empty() {
+        writer
+            .write(&MatroskaSpec::Cues(Master::Full(cues)))
+            .map_err(container)?;
+    }
+    writer.into_inner().map_err(container)?.flush()?;
+    Ok(())
+}
+
+/// Length of the ID and size of a segment of unknown size.
+fn segment_header_len() -> Result<u64, VideoError> {
+    let mut writer = WebmWriter::new(Vec::new());
+    writer
+        .write_advanced(
+            &MatroskaSpec::Segment(Master::Start),
+            WriteOptions::is_unknown_sized_element(),
+        )
+        .map_err(container)?;
+    Ok(writer.into_inner().map_err(container)?.len() as u64)
+}
+
+/// The video track of a `WebM` file.
+#[derive(Debug)]
+pub struct Video {
+    pub codec: CodecType,
+    pub width: u32,
+    pub height: u32,
+    pub codec_private: Option<Vec<u8>>,
+    pub track_number: u32,
+    /// Timestamp ticks per second.
+    pub timescale: u32,
+    pub samples: Vec<(Vec<u8>, u64, bool)>, // (data, pts, is_keyframe)
+}
+
+/// Read the first video track of a `WebM` file.
+///
+/// # Errors
+/// Returns [`VideoError::NotSupported`] if the track isn't VP8, VP9 or AV1, and
+/// [`VideoError::Container`] if the file is malformed.
+#[allow(clippy::cast_possible_truncation)]
+pub fn read<R: Read>(source: R) -> Result<Video, VideoError> {
+    let tags = WebmIterator::new(
+        source,
+        &[
+            MatroskaSpec::TrackEntry(Master::Start),
+            MatroskaSpec::BlockGroup(Master::Start),
+        ],
+    );
+
+    let mut scale = TICK_NS;
+    let mut video = None;
+    let mut cluster_timestamp = 0;
+    // (track, pts, is_keyframe, data)
+    let mut blocks = Vec::new();
+    for tag in tags {
+        match tag.map_err(container)? {
+            MatroskaSpec::DocType(doc_type) if doc_type != "webm" && doc_type != "matroska" => {
+                return Err(VideoError::NotSupported(format!(
+                    "EBML document type {doc_type}"
+                )));
+            }
+            MatroskaSpec::TimestampScale(value) => scale = value.max(1),
+            MatroskaSpec::TrackEntry(entry) if video.is_none() => {
+                video = read_track(entry.get_children())?;
+            }
+            MatroskaSpec::Timestamp(value) => cluster_timestamp = value,
+            MatroskaSpec::SimpleBlock(data) => {
+                let block = SimpleBlock::try_from(data.as_slice()).map_err(container)?;
+                blocks.push((
+                    block.track,
+                    pts(cluster_timestamp, block.timestamp, block.lacing)?,
+                    block.keyframe,
+                    block.raw_frame_data().to_vec(),
+                ));
+            }
+            MatroskaSpec::BlockGroup(group) => {
+                let children = group.get_children();
+                // A block without references to others is a keyframe
+                let referenced = children
+                    .iter()
+                    .any(|child| matches!(child, MatroskaSpec::ReferenceBlock(_)));
+                for child in &children {
+                    if let MatroskaSpec::Block(data) = child {
+                        let block = Block::try_from(data.as_slice()).map_err(container)?;
+                        blocks.push((
+                            block.track,
+                            pts(cluster_timestamp, block.timestamp, block.lacing)?,
+                            !referenced,
+                            block.raw_frame_data().to_vec(),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut video = video.ok_or_else(|| VideoError::Container("No video track found".into()))?;
+    video.timescale = (1_000_000_000 / scale).max(1) as u32;
+    video.samples = blocks
+        .into_iter()
+        .filter(|block| block.0 == u64::from(video.track_number))
+        .map(|(_, pts, is_keyframe, data)| (data, pts, is_keyframe))
+        .collect();
+    Ok(video)
+}
+
+/// The description of a video track entry, or `None` for other tracks.
+#[allow(clippy::cast_possible_truncation)]
+fn read_track(entry: Vec<MatroskaSpec>) -> Result<Option<Video>, VideoError> {
+    let mut number = 0;
+    let mut track_type = 0;
+    let mut codec_id = String::new();
+    let mut codec_private = None;
+    let (mut width, mut height) = (0, 0);
+
+    for child in entry {
+        match child {
+            MatroskaSpec::TrackNumber(value) => number = value as u32,
+            MatroskaSpec::TrackType(value) => track_type = value,
+            MatroskaSpec::CodecID(value) => codec_id = value,
+            MatroskaSpec::CodecPrivate(value) => codec_private = Some(value),
+            MatroskaSpec::Video(video) => {
+                for child in video.get_children() {
+                    match child {
+                        MatroskaSpec::PixelWidth(value) => width = value as u32,
+                        MatroskaSpec::PixelHeight(value) => height = value as u32,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if track_type != TRACK_TYPE_VIDEO {
+        return Ok(None);
+    }
+    let codec = match codec_id.as_str() {
+        "V_VP8" => CodecType::Vp8,
+        "V_VP9" => CodecType::Vp9,
+        "V_AV1" => CodecType::Av1,
+        other => return Err(VideoError::NotSupported(format!("WebM codec {other}"))),
+    };
+    Ok(Some(Video {
+        codec,
+        width,
+        height,
+        codec_private,
+        track_number: number,
+        timescale: 0,
+        samples: Vec::new(),
+    }))
+}
+
+/// The absolute timestamp of a block, which must hold a single frame.
+fn pts(
+    cluster_timestamp: u64,
+    relative: i16,
+    lacing: Option<BlockLacing>,
+) -> Result<u64, VideoError> {
+    if lacing.is_some() {
+        return Err(VideoError::NotSupported("laced WebM blocks".into()));
+    }
+    Ok(cluster_timestamp.saturating_add_signed(i64::from(relative)))
+}
+
+fn container(error: impl Display) -> VideoError {
+    VideoError::Container(error.to_string())
+}